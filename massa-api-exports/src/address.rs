@@ -23,6 +23,8 @@ pub struct AddressInfo {
     pub final_roll_count: u64,
     /// final datastore keys
     pub final_datastore_keys: Vec<Vec<u8>>,
+    /// final stake delegation operator
+    pub final_delegation: Option<Address>,
 
     /// candidate balance
     pub candidate_balance: Amount,
@@ -30,6 +32,8 @@ pub struct AddressInfo {
     pub candidate_roll_count: u64,
     /// candidate datastore keys
     pub candidate_datastore_keys: Vec<Vec<u8>>,
+    /// candidate stake delegation operator
+    pub candidate_delegation: Option<Address>,
 
     /// deferred credits
     pub deferred_credits: Vec<SlotAmount>,
@@ -63,6 +67,18 @@ impl std::fmt::Display for AddressInfo {
             "\tRolls: final={}, candidate={}",
             self.final_roll_count, self.candidate_roll_count
         )?;
+        if self.final_delegation.is_some() || self.candidate_delegation.is_some() {
+            let display_operator = |operator: &Option<Address>| match operator {
+                Some(operator) => operator.to_string(),
+                None => "none".to_string(),
+            };
+            writeln!(
+                f,
+                "\tStake delegated to: final={}, candidate={}",
+                display_operator(&self.final_delegation),
+                display_operator(&self.candidate_delegation)
+            )?;
+        }
         write!(f, "\tLocked coins:")?;
         if self.deferred_credits.is_empty() {
             writeln!(f, "0")?;
@@ -1,6 +1,13 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use massa_models::{address::Address, block::Block, block_id::BlockId, slot::Slot};
+use massa_models::{
+    address::Address,
+    block::{Block, BlockFinality, BlockGraphStatus},
+    block_header::SecuredHeader,
+    block_id::BlockId,
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -50,6 +57,95 @@ impl std::fmt::Display for BlockInfo {
     }
 }
 
+/// Block and its status in the graph, returned by `v2_get_blocks`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockInfoV2 {
+    /// block id
+    pub id: BlockId,
+    /// status of the block in the graph, `NotFound` if the block is unknown
+    pub status: BlockGraphStatus,
+    /// the block, if it is still kept by the node
+    pub block: Option<Block>,
+}
+
+impl std::fmt::Display for BlockInfoV2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Block ID: {} ({:?})", self.id, self.status)?;
+        if let Some(block) = &self.block {
+            writeln!(f, "Block: {}", block)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finality of a block, returned by `get_blocks_finality`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockFinalityInfo {
+    /// block id
+    pub id: BlockId,
+    /// finality of the block, None if the block is unknown
+    pub finality: Option<BlockFinality>,
+}
+
+impl std::fmt::Display for BlockFinalityInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.finality {
+            Some(finality) => writeln!(
+                f,
+                "Block ID: {} ({:?}, {} confirmations, fitness margin {})",
+                self.id, finality.status, finality.confirmations, finality.fitness_margin
+            ),
+            None => writeln!(f, "Block ID: {} (not found)", self.id),
+        }
+    }
+}
+
+/// Options of the blocks returned by `get_block_by_slot` and `get_blocks_by_slot_range`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SlotBlockOptions {
+    /// return the blocks with the content of their operations instead of their headers only
+    #[serde(default)]
+    pub filled: bool,
+    /// only return final blocks
+    #[serde(default)]
+    pub final_only: bool,
+}
+
+/// Block of the blockclique, or final block, at a slot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlotBlock {
+    /// block id
+    pub id: BlockId,
+    /// true if final
+    pub is_final: bool,
+    /// signed header
+    pub header: SecuredHeader,
+    /// operations of the block with their content (if still known), only for filled blocks
+    pub operations: Option<Vec<(OperationId, Option<SecureShareOperation>)>>,
+}
+
+impl std::fmt::Display for SlotBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Block {} at slot {}{}",
+            self.id,
+            self.header.content.slot,
+            display_if_true(self.is_final, " (final)")
+        )?;
+        if let Some(operations) = &self.operations {
+            writeln!(f, "Operations:")?;
+            for (op_id, operation) in operations {
+                match operation {
+                    Some(operation) => writeln!(f, "\t- {}", operation)?,
+                    None => writeln!(f, "\t- {} (content unknown)", op_id)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A block resume (without the block itself)
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BlockSummary {
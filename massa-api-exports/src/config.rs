@@ -21,8 +21,6 @@ pub struct APIConfig {
     pub bind_api: SocketAddr,
     /// max argument count
     pub max_arguments: u64,
-    /// openrpc specification path
-    pub openrpc_spec_path: PathBuf,
     /// bootstrap whitelist path
     pub bootstrap_whitelist_path: PathBuf,
     /// bootstrap blacklist path
@@ -39,6 +37,12 @@ pub struct APIConfig {
     pub max_log_length: u32,
     /// host filtering.
     pub allow_hosts: Vec<String>,
+    /// origins allowed by CORS, any origin if empty.
+    pub cors_allowed_origins: Vec<String>,
+    /// request headers allowed by CORS.
+    pub cors_allowed_headers: Vec<String>,
+    /// bearer token required by the public API, none to disable authentication.
+    pub public_bearer_token: Option<String>,
     /// batch request limit. 0 means disabled.
     pub batch_request_limit: u32,
     /// the interval at which `Ping` frames are submitted.
@@ -69,4 +73,12 @@ pub struct APIConfig {
     pub periods_per_cycle: u64,
     /// keypair file
     pub keypair: KeyPair,
+    /// max gas tried by the gas estimations
+    pub gas_estimation_max_gas: u64,
+    /// max number of read-only executions run by a gas estimation
+    pub max_gas_estimation_executions: u32,
+    /// safety margin added to the estimated gas, in percent
+    pub gas_estimation_margin_percent: u64,
+    /// gas paid by the `ExecuteSC` operations for the compilation of their bytecode
+    pub sp_compilation_cost: u64,
 }
@@ -21,6 +21,44 @@ pub struct DatastoreEntryOutput {
     pub candidate_value: Option<Vec<u8>>,
 }
 
+/// Datastore keys listing input structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreKeysInput {
+    /// address owning the datastore
+    pub address: Address,
+    /// only the keys starting with this prefix are listed. Default empty
+    #[serde(default)]
+    pub prefix: Vec<u8>,
+    /// only the keys strictly after this one are listed, optional
+    pub start_after: Option<Vec<u8>>,
+    /// max number of keys to list, optional. Defaults to the server limit
+    pub limit: Option<u64>,
+    /// whether to list the final or the candidate keys. Default false
+    #[serde(default)]
+    pub is_final: bool,
+    /// whether to return the values along with the keys. Default false
+    #[serde(default)]
+    pub include_values: bool,
+}
+
+/// Datastore keys listing output structure
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreKeysOutput {
+    /// listed datastore entries, sorted by key
+    pub entries: Vec<DatastoreKeyEntry>,
+    /// key to pass as `start_after` to get the next page, if there may be one
+    pub next_key: Option<Vec<u8>>,
+}
+
+/// Datastore entry listed by key
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreKeyEntry {
+    /// datastore key
+    pub key: Vec<u8>,
+    /// datastore value, if requested
+    pub value: Option<Vec<u8>>,
+}
+
 impl std::fmt::Display for DatastoreEntryOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "final value: {:?}", self.final_value)?;
@@ -1,9 +1,13 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+pub use massa_execution_exports::{CallTrace, CoinTransfer, DatastoreAccess, OperationTrace};
 use massa_final_state::StateChanges;
-use massa_models::{address::Address, output_event::SCOutputEvent, slot::Slot};
+use massa_models::{address::Address, amount::Amount, output_event::SCOutputEvent, slot::Slot};
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
 
 /// The result of the read-only execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -63,9 +67,15 @@ pub struct ReadOnlyBytecodeExecution {
     pub address: Option<Address>,
     /// Operation datastore, optional
     pub operation_datastore: Option<Vec<u8>>,
+    /// fee debited from the caller before the execution, as for an operation. Default zero
+    #[serde(default)]
+    pub fee: Amount,
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// ledger entries to override before the execution, by address. Default none
+    #[serde(default)]
+    pub overrides: BTreeMap<Address, ReadOnlyStateOverride>,
 }
 
 /// read SC call request
@@ -81,7 +91,137 @@ pub struct ReadOnlyCall {
     pub parameter: Vec<u8>,
     /// caller's address, optional
     pub caller_address: Option<Address>,
+    /// coins transferred from the caller to the target address with the call. Default zero
+    #[serde(default)]
+    pub coins: Amount,
+    /// fee debited from the caller before the execution, as for an operation. Default zero
+    #[serde(default)]
+    pub fee: Amount,
     /// whether to start execution from final or active state. Default false
     #[serde(default)]
     pub is_final: bool,
+    /// ledger entries to override before the execution, by address. Default none
+    #[serde(default)]
+    pub overrides: BTreeMap<Address, ReadOnlyStateOverride>,
+}
+
+/// Smart contract operation whose gas is estimated, as it would be sent unsigned
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub enum GasEstimationTarget {
+    /// execution of bytecode
+    ExecuteSC {
+        /// byte code
+        bytecode: Vec<u8>,
+        /// operation datastore, optional
+        operation_datastore: Option<Vec<u8>>,
+    },
+    /// call of a smart contract function
+    CallSC {
+        /// target address
+        target_address: Address,
+        /// target function
+        target_function: String,
+        /// function parameter
+        parameter: Vec<u8>,
+        /// coins transferred to the target address with the call
+        coins: Amount,
+    },
+}
+
+/// Request of the minimal gas needed by a smart contract operation
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct GasEstimationRequest {
+    /// address of the operation sender
+    pub caller_address: Address,
+    /// operation to estimate
+    pub target: GasEstimationTarget,
+    /// whether to start the executions from final or active state. Default false
+    #[serde(default)]
+    pub is_final: bool,
+}
+
+/// Gas estimated for a smart contract operation
+#[derive(Debug, Deserialize, Clone, Copy, Serialize)]
+pub struct GasEstimate {
+    /// minimal max gas with which the operation succeeded
+    pub min_gas: u64,
+    /// max gas to set in the operation: the minimal gas with a safety margin,
+    /// as the state may change before the execution
+    pub max_gas: u64,
+    /// number of read-only executions run for the estimation
+    pub execution_count: u32,
+}
+
+impl Display for GasEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Minimal gas: {}", self.min_gas)?;
+        writeln!(f, "Recommended max gas: {}", self.max_gas)?;
+        writeln!(f, "Executions: {}", self.execution_count)
+    }
+}
+
+/// Override of the ledger entry of an address, applied to a speculative copy of the state
+/// before a read-only execution to simulate it on a hypothetical state.
+/// The entry is created if it does not exist.
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
+pub struct ReadOnlyStateOverride {
+    /// balance to set, optional
+    pub balance: Option<Amount>,
+    /// bytecode to set, optional
+    pub bytecode: Option<Vec<u8>>,
+    /// datastore entries to set, as (key, value) pairs. Default none
+    #[serde(default)]
+    pub datastore: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Query of the execution events kept by the node, paginated by slot
+#[derive(Debug, Default, Deserialize, Clone, Serialize)]
+pub struct SCOutputEventQuery {
+    /// first slot of the range, included, optional
+    pub start: Option<Slot>,
+    /// last slot of the range, excluded, optional
+    pub end: Option<Slot>,
+    /// emitter address, optional
+    pub emitter_address: Option<Address>,
+    /// original caller address, optional
+    pub original_caller_address: Option<Address>,
+    /// only the events whose data starts with this prefix are returned, optional
+    pub data_prefix: Option<String>,
+    /// final events if true, candidate events if false, both if none
+    pub is_final: Option<bool>,
+    /// `next_cursor` of the previous page, none to start from the beginning of the range
+    pub cursor: Option<String>,
+    /// max number of events to return, optional. Defaults to the server limit
+    pub limit: Option<u64>,
+}
+
+/// A page of execution events, sorted by slot and index in slot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SCOutputEventPage {
+    /// events of the page
+    pub events: Vec<SCOutputEvent>,
+    /// cursor to get the next page, none if there is no more event
+    pub next_cursor: Option<String>,
+}
+
+impl SCOutputEventPage {
+    /// Cursor to pass to `get_sc_output_events` to get the events after `event`
+    pub fn cursor(event: &SCOutputEvent) -> String {
+        format!(
+            "{}:{}:{}",
+            event.context.slot.period, event.context.slot.thread, event.context.index_in_slot
+        )
+    }
+}
+
+impl Display for SCOutputEventPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for event in self.events.iter() {
+            writeln!(f, "{}", event)?;
+        }
+        if let Some(cursor) = &self.next_cursor {
+            writeln!(f, "Next cursor: {}", cursor)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,327 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_consensus_exports::{block_graph_export::BlockGraphExport, block_status::DiscardReason};
+use massa_models::{address::Address, block_id::BlockId, prehash::PreHashSet, slot::Slot};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Format of an exported block graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, strum::EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum GraphExportFormat {
+    /// Graphviz DOT
+    Dot,
+    /// GraphML
+    GraphMl,
+}
+
+/// Status of an exported block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum GraphBlockStatus {
+    /// final
+    Final,
+    /// active in the blockclique
+    Blockclique,
+    /// active in alternative cliques only
+    Active,
+    /// stale
+    Stale,
+}
+
+/// Block of an exported block graph
+#[derive(Debug, Clone)]
+pub struct GraphBlock {
+    /// block id
+    pub id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// creator of the block
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// fitness of the block, unknown for stale blocks
+    pub fitness: Option<u64>,
+    /// status of the block
+    pub status: GraphBlockStatus,
+    /// indexes of the cliques the block belongs to
+    pub cliques: Vec<usize>,
+}
+
+/// Slot range of the block graph, exported to debug forks and visualize the health of the DAG
+#[derive(Debug, Clone)]
+pub struct BlockGraph {
+    /// blocks, sorted by slot
+    pub blocks: Vec<GraphBlock>,
+    /// fitness of each clique, and whether it is the blockclique
+    pub cliques: Vec<(u64, bool)>,
+}
+
+impl From<BlockGraphExport> for BlockGraph {
+    fn from(graph: BlockGraphExport) -> Self {
+        let cliques_of = |id: &BlockId| -> Vec<usize> {
+            graph
+                .max_cliques
+                .iter()
+                .enumerate()
+                .filter(|(_, clique)| clique.block_ids.contains(id))
+                .map(|(index, _)| index)
+                .collect()
+        };
+        let blockclique: PreHashSet<BlockId> = graph
+            .max_cliques
+            .iter()
+            .find(|clique| clique.is_blockclique)
+            .map(|clique| clique.block_ids.clone())
+            .unwrap_or_default();
+
+        let mut blocks: Vec<GraphBlock> = graph
+            .active_blocks
+            .iter()
+            .map(|(id, block)| GraphBlock {
+                id: *id,
+                slot: block.header.content.slot,
+                creator: block.header.content_creator_address,
+                parents: block.header.content.parents.clone(),
+                fitness: Some(block.header.get_fitness()),
+                status: if block.is_final {
+                    GraphBlockStatus::Final
+                } else if blockclique.contains(id) {
+                    GraphBlockStatus::Blockclique
+                } else {
+                    GraphBlockStatus::Active
+                },
+                cliques: cliques_of(id),
+            })
+            .collect();
+        blocks.extend(graph.discarded_blocks.iter().filter_map(
+            |(id, (reason, (slot, creator, parents)))| {
+                (*reason == DiscardReason::Stale).then(|| GraphBlock {
+                    id: *id,
+                    slot: *slot,
+                    creator: *creator,
+                    parents: parents.clone(),
+                    fitness: None,
+                    status: GraphBlockStatus::Stale,
+                    cliques: Vec::new(),
+                })
+            },
+        ));
+        blocks.sort_unstable_by_key(|block| (block.slot, block.id));
+
+        BlockGraph {
+            blocks,
+            cliques: graph
+                .max_cliques
+                .iter()
+                .map(|clique| (clique.fitness, clique.is_blockclique))
+                .collect(),
+        }
+    }
+}
+
+impl BlockGraph {
+    /// Renders the graph in the given format.
+    /// Only the parent links between exported blocks are rendered.
+    pub fn render(&self, format: GraphExportFormat) -> String {
+        match format {
+            GraphExportFormat::Dot => self.to_dot(),
+            GraphExportFormat::GraphMl => self.to_graphml(),
+        }
+    }
+
+    /// Graphviz DOT rendering, with the blocks colored by status
+    fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph blockgraph {{").unwrap();
+        writeln!(out, "  rankdir=LR;").unwrap();
+        writeln!(out, "  node [shape=box, style=filled];").unwrap();
+        writeln!(out, "  label=\"{}\";", self.cliques_label()).unwrap();
+        for block in &self.blocks {
+            let color = match block.status {
+                GraphBlockStatus::Final => "palegreen",
+                GraphBlockStatus::Blockclique => "lightblue",
+                GraphBlockStatus::Active => "white",
+                GraphBlockStatus::Stale => "lightgrey",
+            };
+            writeln!(
+                out,
+                "  \"{}\" [label=\"{}\\n{}\\n{}, fitness {}\\ncliques {:?}\", fillcolor={}];",
+                block.id,
+                block.slot,
+                block.id,
+                block.status,
+                display_fitness(block.fitness),
+                block.cliques,
+                color
+            )
+            .unwrap();
+        }
+        for (block, parent) in self.links() {
+            writeln!(out, "  \"{}\" -> \"{}\";", block, parent).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// GraphML rendering, with the block data as node attributes
+    fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+        writeln!(
+            out,
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+        )
+        .unwrap();
+        for (key, attr_type) in [
+            ("slot", "string"),
+            ("creator", "string"),
+            ("fitness", "long"),
+            ("status", "string"),
+            ("cliques", "string"),
+        ] {
+            writeln!(
+                out,
+                "  <key id=\"{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"{1}\"/>",
+                key, attr_type
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            "  <key id=\"clique_fitness\" for=\"graph\" attr.name=\"clique_fitness\" attr.type=\"string\"/>"
+        )
+        .unwrap();
+        writeln!(out, "  <graph id=\"blockgraph\" edgedefault=\"directed\">").unwrap();
+        writeln!(
+            out,
+            "    <data key=\"clique_fitness\">{}</data>",
+            self.cliques_label()
+        )
+        .unwrap();
+        for block in &self.blocks {
+            writeln!(out, "    <node id=\"{}\">", block.id).unwrap();
+            writeln!(out, "      <data key=\"slot\">{}</data>", block.slot).unwrap();
+            writeln!(out, "      <data key=\"creator\">{}</data>", block.creator).unwrap();
+            if let Some(fitness) = block.fitness {
+                writeln!(out, "      <data key=\"fitness\">{}</data>", fitness).unwrap();
+            }
+            writeln!(out, "      <data key=\"status\">{}</data>", block.status).unwrap();
+            writeln!(
+                out,
+                "      <data key=\"cliques\">{:?}</data>",
+                block.cliques
+            )
+            .unwrap();
+            writeln!(out, "    </node>").unwrap();
+        }
+        for (block, parent) in self.links() {
+            writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                block, parent
+            )
+            .unwrap();
+        }
+        writeln!(out, "  </graph>").unwrap();
+        writeln!(out, "</graphml>").unwrap();
+        out
+    }
+
+    /// Parent links between exported blocks, as `(block, parent)`
+    fn links(&self) -> Vec<(BlockId, BlockId)> {
+        let exported: PreHashSet<BlockId> = self.blocks.iter().map(|block| block.id).collect();
+        self.blocks
+            .iter()
+            .flat_map(|block| {
+                block
+                    .parents
+                    .iter()
+                    .filter(|parent| exported.contains(parent))
+                    .map(|parent| (block.id, *parent))
+            })
+            .collect()
+    }
+
+    /// Description of the cliques: index, fitness, and which one is the blockclique
+    fn cliques_label(&self) -> String {
+        self.cliques
+            .iter()
+            .enumerate()
+            .map(|(index, (fitness, is_blockclique))| {
+                format!(
+                    "clique {}: fitness {}{}",
+                    index,
+                    fitness,
+                    if *is_blockclique {
+                        " (blockclique)"
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn display_fitness(fitness: Option<u64>) -> String {
+    fitness.map_or_else(|| "unknown".to_string(), |fitness| fitness.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_signature::KeyPair;
+
+    #[test]
+    fn test_block_graph_render() {
+        let creator = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let parent = BlockId(Hash::compute_from(b"parent"));
+        let child = BlockId(Hash::compute_from(b"child"));
+        let outside = BlockId(Hash::compute_from(b"outside"));
+        let graph = BlockGraph {
+            blocks: vec![
+                GraphBlock {
+                    id: parent,
+                    slot: Slot::new(1, 0),
+                    creator,
+                    parents: vec![outside],
+                    fitness: Some(1),
+                    status: GraphBlockStatus::Final,
+                    cliques: vec![0],
+                },
+                GraphBlock {
+                    id: child,
+                    slot: Slot::new(2, 0),
+                    creator,
+                    parents: vec![parent],
+                    fitness: None,
+                    status: GraphBlockStatus::Stale,
+                    cliques: vec![],
+                },
+            ],
+            cliques: vec![(17, true)],
+        };
+
+        let dot = graph.render(GraphExportFormat::Dot);
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", child, parent)));
+        assert!(!dot.contains(&outside.to_string()));
+        assert!(dot.contains("clique 0: fitness 17 (blockclique)"));
+
+        let graphml = graph.render(GraphExportFormat::GraphMl);
+        assert!(graphml.contains(&format!(
+            "<edge source=\"{}\" target=\"{}\"/>",
+            child, parent
+        )));
+        assert!(graphml.contains("<data key=\"status\">stale</data>"));
+        assert_eq!(graphml.matches("<node ").count(), 2);
+
+        assert_eq!(
+            "GraphML".parse::<GraphExportFormat>().unwrap(),
+            GraphExportFormat::GraphMl
+        );
+    }
+}
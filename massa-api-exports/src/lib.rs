@@ -25,6 +25,8 @@ pub mod endorsement;
 pub mod error;
 /// execution
 pub mod execution;
+/// block graph export
+pub mod graph;
 /// ledger structures
 pub mod ledger;
 /// node related structure
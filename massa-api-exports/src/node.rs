@@ -1,7 +1,8 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_models::amount::Amount;
 use massa_models::node::NodeId;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{BootstrapStats, ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
@@ -39,6 +40,10 @@ pub struct NodeStatus {
     pub network_stats: NetworkStats,
     /// execution stats
     pub execution_stats: ExecutionStats,
+    /// headline chain statistics derived from the other stats
+    pub chain_stats: ChainStats,
+    /// last progress report of the bootstrap of the node, none if it did not bootstrap
+    pub bootstrap_stats: Option<BootstrapStats>,
     /// compact configuration
     pub config: CompactConfig,
 }
@@ -76,6 +81,12 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "{}", self.execution_stats)?;
 
+        writeln!(f, "{}", self.chain_stats)?;
+
+        if let Some(bootstrap_stats) = &self.bootstrap_stats {
+            writeln!(f, "{}", bootstrap_stats)?;
+        }
+
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
             writeln!(
@@ -89,3 +100,152 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// headline chain statistics
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChainStats {
+    /// final blocks produced per second over the consensus stats time window
+    pub block_production_rate: f64,
+    /// average number of executed operations per final block over the execution stats time window
+    pub average_operations_per_block: f64,
+    /// fees of the operations currently in the pool, none if the pool is empty
+    pub operation_fee_percentiles: Option<FeePercentiles>,
+    /// number of slots the execution cursor is behind the latest slot
+    pub execution_backlog: u64,
+    /// progress of the execution towards the latest slot since genesis, in percent.
+    /// Reaches 100 once the node has caught up after bootstrap.
+    pub bootstrap_progress: f64,
+}
+
+/// operation fees at some percentiles
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeePercentiles {
+    /// 10th percentile
+    pub p10: Amount,
+    /// median
+    pub p50: Amount,
+    /// 90th percentile
+    pub p90: Amount,
+}
+
+impl std::fmt::Display for ChainStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Chain stats:")?;
+        writeln!(
+            f,
+            "\tBlock production rate: {:.3} blocks/s",
+            self.block_production_rate
+        )?;
+        writeln!(
+            f,
+            "\tAverage operations per block: {:.2}",
+            self.average_operations_per_block
+        )?;
+        match &self.operation_fee_percentiles {
+            Some(fees) => writeln!(
+                f,
+                "\tPool operation fees: p10 {} / p50 {} / p90 {}",
+                fees.p10, fees.p50, fees.p90
+            )?,
+            None => writeln!(f, "\tPool operation fees: no operation in the pool")?,
+        }
+        writeln!(f, "\tExecution backlog: {} slots", self.execution_backlog)?;
+        writeln!(f, "\tBootstrap progress: {:.2}%", self.bootstrap_progress)?;
+        Ok(())
+    }
+}
+
+/// Peer or ip a ban applies to
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum BanTarget {
+    /// a node, whatever the ip it connects from
+    NodeId(NodeId),
+    /// all the nodes connecting from an ip
+    Ip(IpAddr),
+}
+
+impl std::fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BanTarget::NodeId(node_id) => write!(f, "node {}", node_id),
+            BanTarget::Ip(ip) => write!(f, "ip {}", ip),
+        }
+    }
+}
+
+/// Bans to set through the private API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BanInput {
+    /// nodes and ips to ban
+    pub targets: Vec<BanTarget>,
+    /// duration of the ban, forever if none
+    pub duration: Option<MassaTime>,
+    /// human readable cause of the ban, optional
+    pub reason: Option<String>,
+}
+
+/// Ban set through the private API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BanInfo {
+    /// what is banned
+    pub target: BanTarget,
+    /// human readable cause of the ban
+    pub reason: String,
+    /// when the ban was set
+    pub banned_at: MassaTime,
+    /// when the ban is lifted, never if none
+    pub expires_at: Option<MassaTime>,
+}
+
+impl std::fmt::Display for BanInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Banned {} since {}",
+            self.target,
+            self.banned_at.format_instant()
+        )?;
+        match self.expires_at {
+            Some(expires_at) => write!(f, " until {}", expires_at.format_instant())?,
+            None => write!(f, " forever")?,
+        }
+        writeln!(f, ": {}", self.reason)
+    }
+}
+
+/// gRPC API drain progress
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GrpcDrainStatus {
+    /// true if the gRPC API stopped accepting new requests and streams
+    pub draining: bool,
+    /// number of requests and streams still being served
+    pub active_requests: usize,
+    /// time elapsed since the drain was started, none if not draining
+    pub elapsed: Option<MassaTime>,
+    /// time left before the remaining streams are closed, none if not draining
+    pub remaining: Option<MassaTime>,
+    /// true if draining and either no request is left or the grace period is over
+    pub drained: bool,
+}
+
+impl std::fmt::Display for GrpcDrainStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.draining {
+            writeln!(f, "gRPC API is not draining")?;
+            return writeln!(f, "Active requests: {}", self.active_requests);
+        }
+        writeln!(
+            f,
+            "gRPC API is draining{}",
+            if self.drained { " (drained)" } else { "" }
+        )?;
+        writeln!(f, "Active requests: {}", self.active_requests)?;
+        if let Some(elapsed) = self.elapsed {
+            writeln!(f, "Elapsed: {} ms", elapsed.to_millis())?;
+        }
+        if let Some(remaining) = self.remaining {
+            writeln!(f, "Remaining grace period: {} ms", remaining.to_millis())?;
+        }
+        Ok(())
+    }
+}
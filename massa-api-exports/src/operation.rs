@@ -1,14 +1,19 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    address::Address,
+    amount::Amount,
     block_id::BlockId,
     operation::{OperationId, SecureShareOperation},
+    slot::Slot,
 };
+use massa_pool_exports::OperationPoolUsage;
+pub use massa_pool_exports::{ExpiryBucket, FeePercentile, PoolStatistics};
 
 use massa_signature::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
 
-use crate::{display_if_true, display_option_bool};
+use crate::{display_if_true, display_option_bool, node::FeePercentiles};
 
 /// operation input
 #[derive(Serialize, Deserialize, Debug)]
@@ -65,6 +70,203 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Final executed operation involving an address
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressOperationInfo {
+    /// operation id
+    pub id: OperationId,
+    /// slot of the final block that executed the operation
+    pub slot: Slot,
+    /// final block that executed the operation
+    pub block_id: BlockId,
+    /// true if the operation execution succeeded, false if failed
+    pub op_exec_status: bool,
+}
+
+impl AddressOperationInfo {
+    /// Cursor to pass to `get_operations_by_address` to get the operations before this one
+    pub fn cursor(&self) -> String {
+        format!("{}:{}:{}", self.slot.period, self.slot.thread, self.id)
+    }
+}
+
+impl std::fmt::Display for AddressOperationInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Operation {} executed at slot {} in block {} ({})",
+            self.id,
+            self.slot,
+            self.block_id,
+            if self.op_exec_status {
+                "success"
+            } else {
+                "failed"
+            }
+        )
+    }
+}
+
+/// A page of the final executed operations involving an address, most recent first
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressOperations {
+    /// operations of the page
+    pub operations: Vec<AddressOperationInfo>,
+    /// cursor to get the next page, none if there is no more operation
+    pub next_cursor: Option<String>,
+}
+
+/// Fee suggested for an operation to be included within a target number of slots
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeeEstimate {
+    /// suggested fee
+    pub fee: Amount,
+    /// number of slots the operation should be included within
+    pub target_inclusion_slots: u64,
+    /// fee needed to rank among the operations of the pool fitting in the target slots,
+    /// none if they all fit
+    pub pool_fee: Option<Amount>,
+    /// number of operations in the pool
+    pub pool_operation_count: usize,
+    /// median fee of the operations included in the blocks kept in the graph
+    pub recent_median_fee: Option<Amount>,
+    /// number of operations included in the blocks kept in the graph
+    pub recent_operation_count: usize,
+}
+
+impl std::fmt::Display for FeeEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Suggested fee for an inclusion within {} slots: {}",
+            self.target_inclusion_slots, self.fee
+        )?;
+        match self.pool_fee {
+            Some(fee) => writeln!(
+                f,
+                "\tPool: {} operations, fee needed to outbid the backlog: {}",
+                self.pool_operation_count, fee
+            )?,
+            None => writeln!(
+                f,
+                "\tPool: {} operations, no backlog",
+                self.pool_operation_count
+            )?,
+        }
+        match self.recent_median_fee {
+            Some(fee) => writeln!(
+                f,
+                "\tRecent blocks: {} operations, median fee: {}",
+                self.recent_operation_count, fee
+            ),
+            None => writeln!(f, "\tRecent blocks: no operation"),
+        }
+    }
+}
+
+/// Pool statistics and the pending operations of some addresses
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PoolStatus {
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// number of endorsements in the pool
+    pub endorsement_count: usize,
+    /// number of denunciations in the pool
+    pub denunciation_count: usize,
+    /// fees of the operations in the pool, none if the pool is empty
+    pub operation_fee_percentiles: Option<FeePercentiles>,
+    /// fee needed to rank among the operations of the pool fitting in the next slot,
+    /// none if they all fit
+    pub next_slot_inclusion_fee: Option<Amount>,
+    /// usage of the operation pool quotas, and eviction policy
+    pub quotas: OperationPoolUsage,
+    /// operations of the requested addresses waiting in the pool
+    pub pending_operations: Vec<PendingOperation>,
+}
+
+impl std::fmt::Display for PoolStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Pool status:")?;
+        writeln!(f, "\tOperations: {}", self.operation_count)?;
+        writeln!(f, "\tEndorsements: {}", self.endorsement_count)?;
+        writeln!(f, "\tDenunciations: {}", self.denunciation_count)?;
+        if let Some(fees) = &self.operation_fee_percentiles {
+            writeln!(
+                f,
+                "\tOperation fees: p10 {}, p50 {}, p90 {}",
+                fees.p10, fees.p50, fees.p90
+            )?;
+        }
+        if let Some(fee) = self.next_slot_inclusion_fee {
+            writeln!(f, "\tFee needed for the next slot: {}", fee)?;
+        }
+        writeln!(
+            f,
+            "\tQuotas: {}/{} operations, {}/{} bytes, {}/{} operations for the busiest sender, eviction {}",
+            self.quotas.operation_count,
+            self.quotas.max_operation_count,
+            self.quotas.byte_size,
+            self.quotas.max_byte_size,
+            self.quotas.max_sender_operation_count,
+            self.quotas.max_operations_per_sender,
+            self.quotas.eviction_policy
+        )?;
+        for operation in &self.pending_operations {
+            write!(f, "{}", operation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Operation waiting in the pool
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PendingOperation {
+    /// operation id
+    pub id: OperationId,
+    /// creator of the operation
+    pub creator: Address,
+    /// fee of the operation
+    pub fee: Amount,
+    /// thread in which the operation can be included
+    pub thread: u8,
+    /// last period at which the operation can be included
+    pub expire_period: u64,
+    /// why the operation may not be included yet
+    pub status: PendingOperationStatus,
+}
+
+impl std::fmt::Display for PendingOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Operation {} from {}: fee {}, thread {}, expires at period {} ({})",
+            self.id, self.creator, self.fee, self.thread, self.expire_period, self.status
+        )
+    }
+}
+
+/// Status of an operation waiting in the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingOperationStatus {
+    /// the operation is waiting to be included
+    Pending,
+    /// the operation expires in a few periods
+    ExpiringSoon,
+    /// the fee of the operation is too low to outbid the pool backlog before it expires
+    InsufficientFee,
+}
+
+impl std::fmt::Display for PendingOperationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingOperationStatus::Pending => write!(f, "pending"),
+            PendingOperationStatus::ExpiringSoon => write!(f, "expiring soon"),
+            PendingOperationStatus::InsufficientFee => write!(f, "insufficient fee"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use jsonrpsee::core::__reexports::serde_json::{self, Value};
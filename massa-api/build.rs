@@ -0,0 +1,307 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Generates the OpenRPC document served by `rpc.discover`.
+//!
+//! The method set, the method descriptions and the public/private tags are taken from the
+//! `MassaRpc` and `MassaApi` traits and their implementations, so that the document always
+//! matches the methods actually exposed by the node.
+//! The schemas of the parameters and results are taken from the hand-written specification
+//! in `massa-node/base_config/openrpc.json` when it documents the method.
+//! The methods listed in `DEPRECATED_METHODS` (see `src/versioning.rs`) are flagged `deprecated`.
+
+use quote::ToTokens;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use syn::{Attribute, Expr, FnArg, ImplItem, Item, Lit, Meta, Pat, ReturnType, TraitItem};
+
+const BASE_SPEC: &str = "../massa-node/base_config/openrpc.json";
+const RPC_TRAIT: &str = "src/lib.rs";
+const RPC_PUBLIC: &str = "src/public.rs";
+const RPC_PRIVATE: &str = "src/private.rs";
+const API_TRAIT: &str = "src/api_trait.rs";
+const VERSIONING: &str = "src/versioning.rs";
+
+/// A method exposed by one of the API traits
+struct RpcMethod {
+    /// JSON-RPC name of the method
+    name: String,
+    /// name of the trait function implementing it
+    fn_name: String,
+    /// doc comment of the trait function
+    description: String,
+    /// parameter names and rust types
+    params: Vec<(String, String)>,
+    /// rust result type
+    result: String,
+}
+
+fn main() {
+    for path in [
+        BASE_SPEC,
+        RPC_TRAIT,
+        RPC_PUBLIC,
+        RPC_PRIVATE,
+        API_TRAIT,
+        VERSIONING,
+    ] {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+
+    let mut spec: Value =
+        serde_json::from_str(&read(BASE_SPEC)).expect("invalid base OpenRPC spec");
+    let documented: Map<String, Value> = spec["methods"]
+        .as_array()
+        .expect("base OpenRPC spec has no methods")
+        .iter()
+        .map(|method| {
+            (
+                method["name"].as_str().unwrap_or_default().to_string(),
+                method.clone(),
+            )
+        })
+        .collect();
+
+    // methods of the JSON-RPC API, served either by the public or by the private server
+    let public_fns = served_fns(RPC_PUBLIC);
+    let private_fns = served_fns(RPC_PRIVATE);
+    let deprecated = deprecated_methods();
+    let mut methods = Vec::new();
+    for method in trait_methods(RPC_TRAIT, "MassaRpc") {
+        let tags = if public_fns.contains(&method.fn_name) {
+            vec![json!({"name": "public", "description": "Massa public api"})]
+        } else if private_fns.contains(&method.fn_name) {
+            vec![json!({"name": "private", "description": "Massa private api"})]
+        } else {
+            println!(
+                "cargo:warning=rpc method {} is not served by any API",
+                method.name
+            );
+            continue;
+        };
+        let mut spec = method_spec(&method, tags, &documented);
+        if let Some((_, replacement)) = deprecated.iter().find(|(name, _)| *name == method.name) {
+            spec["deprecated"] = json!(true);
+            spec["description"] = json!(format!(
+                "{}\nDeprecated: use `{}` instead.",
+                method.description, replacement
+            ));
+        }
+        methods.push(spec);
+    }
+
+    // methods of the API V2
+    for method in trait_methods(API_TRAIT, "MassaApi") {
+        let mut tags = vec![
+            json!({"name": "api", "description": "Massa api V2"}),
+            json!({"name": "experimental", "description": "Experimental APIs. They might disappear, and they will change"}),
+        ];
+        if method.result.contains("SubscriptionResult") {
+            tags.push(json!({"name": "websocket", "description": "WebSocket subscription"}));
+        }
+        methods.push(method_spec(&method, tags, &documented));
+    }
+
+    for name in documented.keys() {
+        if !methods.iter().any(|method| method["name"] == *name) {
+            println!(
+                "cargo:warning=OpenRPC method {} is not exposed anymore and was left out",
+                name
+            );
+        }
+    }
+    spec["methods"] = Value::Array(methods);
+
+    let out_path = Path::new(&std::env::var("OUT_DIR").unwrap()).join("openrpc.json");
+    std::fs::write(out_path, serde_json::to_string_pretty(&spec).unwrap())
+        .expect("failed to write the OpenRPC spec");
+}
+
+fn read(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+}
+
+/// Builds the OpenRPC entry of a method, reusing the documented schemas when there are some
+fn method_spec(method: &RpcMethod, tags: Vec<Value>, documented: &Map<String, Value>) -> Value {
+    let mut spec = documented.get(&method.name).cloned().unwrap_or_else(|| {
+        json!({
+            "params": method.params.iter().map(|(name, ty)| json!({
+                "name": name,
+                "description": format!("`{}`", ty),
+                "schema": {},
+                "required": !ty.starts_with("Option <"),
+            })).collect::<Vec<_>>(),
+            "result": {
+                "name": "result",
+                "description": format!("`{}`", method.result),
+                "schema": {},
+            },
+            "summary": method.description.lines().next().unwrap_or_default().trim_end_matches('.'),
+        })
+    });
+    spec["name"] = json!(method.name);
+    spec["tags"] = json!(tags);
+    spec["description"] = json!(method.description);
+    spec
+}
+
+/// Lists the methods of an API trait
+fn trait_methods(path: &str, trait_name: &str) -> Vec<RpcMethod> {
+    let file =
+        syn::parse_file(&read(path)).unwrap_or_else(|e| panic!("failed to parse {}: {}", path, e));
+    let api_trait = file
+        .items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::Trait(item) if item.ident == trait_name => Some(item),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("trait {} not found in {}", trait_name, path));
+
+    let mut methods = Vec::new();
+    for item in api_trait.items {
+        let TraitItem::Fn(function) = item else {
+            continue;
+        };
+        let description = doc(&function.attrs);
+        let params: Vec<(String, String)> = function
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(arg) => Some((
+                    match &*arg.pat {
+                        Pat::Ident(ident) => ident.ident.to_string(),
+                        pat => pat.to_token_stream().to_string(),
+                    },
+                    arg.ty.to_token_stream().to_string(),
+                )),
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        let result = match &function.sig.output {
+            ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
+            ReturnType::Default => "()".to_string(),
+        };
+        for attr in &function.attrs {
+            let Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            let tokens = list.tokens.to_string();
+            let names = if list.path.is_ident("method") {
+                vec![attr_value(&tokens, "name")]
+            } else if list.path.is_ident("subscription") {
+                vec![
+                    attr_value(&tokens, "name"),
+                    attr_value(&tokens, "unsubscribe"),
+                ]
+            } else {
+                continue;
+            };
+            for name in names.into_iter().flatten() {
+                methods.push(RpcMethod {
+                    name,
+                    fn_name: function.sig.ident.to_string(),
+                    description: description.clone(),
+                    params: params.clone(),
+                    result: result.clone(),
+                });
+            }
+        }
+    }
+    methods
+}
+
+/// Lists the trait functions an API implementation actually serves (the others answer `wrong_api`)
+fn served_fns(path: &str) -> Vec<String> {
+    let file =
+        syn::parse_file(&read(path)).unwrap_or_else(|e| panic!("failed to parse {}: {}", path, e));
+    file.items
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Impl(item)
+                if item
+                    .trait_
+                    .as_ref()
+                    .map_or(false, |(_, path, _)| path.is_ident("MassaRpcServer")) =>
+            {
+                Some(item.items)
+            }
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            ImplItem::Fn(function)
+                if !function
+                    .block
+                    .to_token_stream()
+                    .to_string()
+                    .contains("wrong_api") =>
+            {
+                Some(function.sig.ident.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Lists the deprecated methods and their replacements declared in `DEPRECATED_METHODS`
+fn deprecated_methods() -> Vec<(String, String)> {
+    let file = syn::parse_file(&read(VERSIONING))
+        .unwrap_or_else(|e| panic!("failed to parse {}: {}", VERSIONING, e));
+    let Some(Expr::Reference(list)) = file.items.into_iter().find_map(|item| match item {
+        Item::Const(item) if item.ident == "DEPRECATED_METHODS" => Some(*item.expr),
+        _ => None,
+    }) else {
+        panic!("DEPRECATED_METHODS not found in {}", VERSIONING);
+    };
+    let Expr::Array(list) = *list.expr else {
+        panic!("DEPRECATED_METHODS is not an array");
+    };
+    list.elems
+        .iter()
+        .filter_map(|elem| match elem {
+            Expr::Struct(method) => Some((
+                str_field(method, "name")?,
+                str_field(method, "replacement")?,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reads a string literal field of a struct expression
+fn str_field(expr: &syn::ExprStruct, name: &str) -> Option<String> {
+    expr.fields
+        .iter()
+        .find_map(|field| match (&field.member, &field.expr) {
+            (syn::Member::Named(ident), Expr::Lit(value)) if ident == name => match &value.lit {
+                Lit::Str(value) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Joins the doc comment lines of an item
+fn doc(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(line) => Some(line.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Reads the string value of `key = "value"` in attribute arguments
+fn attr_value(tokens: &str, key: &str) -> Option<String> {
+    let start = tokens.find(&format!("{} = \"", key))? + key.len() + 4;
+    let len = tokens[start..].find('"')?;
+    Some(tokens[start..start + len].to_string())
+}
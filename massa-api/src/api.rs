@@ -53,7 +53,7 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(self.into_rpc(), url, api_config, None).await
     }
 }
 
@@ -143,6 +143,21 @@ impl MassaApiServer for API<ApiV2> {
         .await
     }
 
+    async fn subscribe_new_reorgs(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        broadcast_via_ws(self.0.consensus_channels.reorg_sender.clone(), pending).await
+    }
+
+    async fn subscribe_new_discarded_blocks(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.consensus_channels.discarded_block_sender.clone(),
+            pending,
+        )
+        .await
+    }
+
     async fn subscribe_new_operations(
         &self,
         pending: PendingSubscriptionSink,
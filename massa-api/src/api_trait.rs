@@ -50,6 +50,22 @@ pub trait MassaApi {
 	)]
     async fn subscribe_new_filled_blocks(&self) -> SubscriptionResult;
 
+    /// Blockclique changes reverting blocks, for indexers to roll back.
+    #[subscription(
+		name = "subscribe_new_reorgs" => "new_reorgs",
+		unsubscribe = "unsubscribe_new_reorgs",
+		item = ReorgEvent
+	)]
+    async fn subscribe_new_reorgs(&self) -> SubscriptionResult;
+
+    /// Blocks discarded by the consensus, with the reason why.
+    #[subscription(
+		name = "subscribe_new_discarded_blocks" => "new_discarded_blocks",
+		unsubscribe = "unsubscribe_new_discarded_blocks",
+		item = DiscardedBlockEvent
+	)]
+    async fn subscribe_new_discarded_blocks(&self) -> SubscriptionResult;
+
     /// New produced operations.
     #[subscription(
 		name = "subscribe_new_operations" => "new_operations",
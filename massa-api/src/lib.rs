@@ -4,6 +4,7 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 use api_trait::MassaApiServer;
+use hyper::header::{HeaderName, HeaderValue};
 use hyper::Method;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
@@ -11,47 +12,72 @@ use jsonrpsee::server::{AllowHosts, BatchRequestConfig, ServerBuilder, ServerHan
 use jsonrpsee::RpcModule;
 use massa_api_exports::{
     address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
+    block::{BlockFinalityInfo, BlockInfo, BlockInfoV2, BlockSummary, SlotBlock, SlotBlockOptions},
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimate, GasEstimationRequest, OperationTrace,
+        ReadOnlyBytecodeExecution, ReadOnlyCall, SCOutputEventPage, SCOutputEventQuery,
+    },
+    graph::GraphExportFormat,
+    node::{BanInfo, BanInput, BanTarget, GrpcDrainStatus, NodeStatus},
+    operation::{
+        AddressOperations, FeeEstimate, OperationInfo, OperationInput, PoolStatistics, PoolStatus,
+    },
     page::{PageRequest, PagedVec},
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
+use massa_db::MassaArchive;
 use massa_execution_exports::ExecutionController;
-use massa_models::clique::Clique;
+use massa_grpc::drain::DrainController;
+use massa_models::clique::{Clique, CliqueInspection};
 use massa_models::composite::PubkeySig;
+use massa_models::ip_range::IpRange;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::stats::BootstrapStats;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
-    execution::EventFilter, slot::Slot, version::Version,
+    address::Address,
+    block::{ArchivedBlock, Block},
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    execution::EventFilter,
+    slot::Slot,
+    version::Version,
 };
 use massa_pool_exports::{PoolChannels, PoolController};
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{CycleInfo, SelectorController};
 use massa_protocol_exports::{ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use serde_json::Value;
 use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+use tower_http::validate_request::ValidateRequestHeaderLayer;
 use tracing::{info, warn};
+use versioning::{DeprecationLayer, DEPRECATED_METHODS_HEADER, DEPRECATION_HEADER};
 
 mod api;
 mod api_trait;
 mod private;
 mod public;
+mod versioning;
+
+/// OpenRPC specification of the API served by `rpc.discover`, generated at build time from the API traits
+pub const OPENRPC_SPEC: &str = include_str!(concat!(env!("OUT_DIR"), "/openrpc.json"));
 
 /// Public API component
 pub struct Public {
@@ -77,6 +103,10 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// last progress report of the bootstrap of the node, none if it did not bootstrap
+    pub bootstrap_stats: Option<BootstrapStats>,
+    /// archive of the pruned blocks and cycles, in archival mode
+    pub archive: Option<Arc<MassaArchive>>,
 }
 
 /// Private API content
@@ -85,12 +115,16 @@ pub struct Private {
     pub protocol_controller: Box<dyn ProtocolController>,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// link to the pool component
+    pub pool_controller: Box<dyn PoolController>,
     /// API settings
     pub api_settings: APIConfig,
     /// stop channel
     pub stop_node_channel: mpsc::Sender<()>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// gRPC API drain mode, none if the gRPC API is disabled
+    pub grpc_drain_controller: Option<DrainController>,
 }
 
 /// API v2 content
@@ -134,10 +168,13 @@ pub trait ApiServer: MassaApiServer {
     ) -> Result<StopHandle, JsonRpseeError>;
 }
 
+/// Starts a server for an API module.
+/// If a bearer token is given, requests without an `Authorization: Bearer <token>` header are rejected.
 async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
+    bearer_token: Option<&str>,
 ) -> Result<StopHandle, JsonRpseeError> {
     let allowed_hosts = if api_config.allow_hosts.is_empty() {
         AllowHosts::Any
@@ -171,14 +208,43 @@ async fn serve<T>(
         panic!("wrong server configuration, you can't disable both http and ws");
     }
 
+    let allowed_origins = if api_config.cors_allowed_origins.is_empty() {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(api_config.cors_allowed_origins.iter().map(|origin| {
+            HeaderValue::from_str(origin).unwrap_or_else(|_| {
+                panic!("wrong server configuration, invalid CORS origin {}", origin)
+            })
+        }))
+    };
+    let mut allowed_headers: Vec<HeaderName> = api_config
+        .cors_allowed_headers
+        .iter()
+        .map(|header| {
+            HeaderName::from_str(header).unwrap_or_else(|_| {
+                panic!("wrong server configuration, invalid CORS header {}", header)
+            })
+        })
+        .collect();
+    if bearer_token.is_some() {
+        allowed_headers.push(hyper::header::AUTHORIZATION);
+    }
+
     let cors = CorsLayer::new()
         // Allow `POST` and `OPTIONS` when accessing the resource
         .allow_methods([Method::POST, Method::OPTIONS])
-        // Allow requests from any origin
-        .allow_origin(Any)
-        .allow_headers([hyper::header::CONTENT_TYPE]);
-
-    let middleware = tower::ServiceBuilder::new().layer(cors);
+        .allow_origin(allowed_origins)
+        .allow_headers(AllowHeaders::list(allowed_headers))
+        .expose_headers([
+            HeaderName::from_static(DEPRECATION_HEADER),
+            HeaderName::from_static(DEPRECATED_METHODS_HEADER),
+        ]);
+
+    // CORS is the outer layer so that preflight requests, which carry no credentials, are answered
+    let middleware = tower::ServiceBuilder::new()
+        .layer(cors)
+        .option_layer(bearer_token.map(ValidateRequestHeaderLayer::bearer))
+        .layer(DeprecationLayer::new(api_config.max_request_body_size));
 
     let server = server_builder
         .set_middleware(middleware)
@@ -241,6 +307,11 @@ pub trait MassaRpc {
         arg: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
 
+    /// Estimate the max gas of an unsigned `CallSC` or `ExecuteSC` operation,
+    /// looking for the minimal gas with which it succeeds in read-only executions.
+    #[method(name = "estimate_gas")]
+    async fn estimate_gas(&self, arg: GasEstimationRequest) -> RpcResult<GasEstimate>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[method(name = "remove_staking_addresses")]
@@ -278,7 +349,7 @@ pub trait MassaRpc {
 
     /// Returns node bootstrap whitelist IP address(es).
     #[method(name = "node_bootstrap_whitelist")]
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpRange>>;
 
     /// Allow everyone to bootstrap from the node.
     /// remove bootstrap whitelist configuration file.
@@ -287,23 +358,45 @@ pub trait MassaRpc {
 
     /// Add IP address(es) to node bootstrap whitelist.
     #[method(name = "node_add_to_bootstrap_whitelist")]
-    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_whitelist(&self, arg: Vec<IpRange>) -> RpcResult<()>;
 
     /// Remove IP address(es) to bootstrap whitelist.
     #[method(name = "node_remove_from_bootstrap_whitelist")]
-    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_whitelist(&self, arg: Vec<IpRange>) -> RpcResult<()>;
 
     /// Returns node bootstrap blacklist IP address(es).
     #[method(name = "node_bootstrap_blacklist")]
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>>;
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpRange>>;
 
     /// Add IP address(es) to node bootstrap blacklist.
     #[method(name = "node_add_to_bootstrap_blacklist")]
-    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_add_to_bootstrap_blacklist(&self, arg: Vec<IpRange>) -> RpcResult<()>;
 
     /// Remove IP address(es) to bootstrap blacklist.
     #[method(name = "node_remove_from_bootstrap_blacklist")]
-    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpAddr>) -> RpcResult<()>;
+    async fn node_remove_from_bootstrap_blacklist(&self, arg: Vec<IpRange>) -> RpcResult<()>;
+
+    /// Returns the sender addresses whose operations the node rejects from its pool and does not propagate.
+    /// This is a local policy of the node only.
+    #[method(name = "node_pool_denylist")]
+    async fn node_pool_denylist(&self) -> RpcResult<Vec<Address>>;
+
+    /// Add sender address(es) to the pool denylist. Their pending operations are evicted from the pool.
+    #[method(name = "node_add_to_pool_denylist")]
+    async fn node_add_to_pool_denylist(&self, arg: Vec<Address>) -> RpcResult<()>;
+
+    /// Remove sender address(es) from the pool denylist.
+    #[method(name = "node_remove_from_pool_denylist")]
+    async fn node_remove_from_pool_denylist(&self, arg: Vec<Address>) -> RpcResult<()>;
+
+    /// Put the gRPC API in drain mode: new requests and streams are rejected,
+    /// running streams are closed once the given grace period is over.
+    #[method(name = "node_start_grpc_drain")]
+    async fn node_start_grpc_drain(&self, arg: MassaTime) -> RpcResult<GrpcDrainStatus>;
+
+    /// Returns the gRPC API drain progress.
+    #[method(name = "node_grpc_drain_status")]
+    async fn node_grpc_drain_status(&self) -> RpcResult<GrpcDrainStatus>;
 
     /// Unban given IP address(es).
     /// No confirmation to expect.
@@ -315,6 +408,19 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Bans node id(s) and IP address(es) for the given duration, or until they are unbanned.
+    /// The bans are persisted and survive restarts.
+    #[method(name = "node_add_bans")]
+    async fn node_add_bans(&self, arg: BanInput) -> RpcResult<()>;
+
+    /// Returns the bans set with `node_add_bans` or `node_ban_by_ip` that are still in force.
+    #[method(name = "node_bans")]
+    async fn node_bans(&self) -> RpcResult<Vec<BanInfo>>;
+
+    /// Lifts the bans set with `node_add_bans` or `node_ban_by_ip` on node id(s) and IP address(es).
+    #[method(name = "node_remove_bans")]
+    async fn node_remove_bans(&self, arg: Vec<BanTarget>) -> RpcResult<()>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -323,6 +429,10 @@ pub trait MassaRpc {
     #[method(name = "get_cliques")]
     async fn get_cliques(&self) -> RpcResult<Vec<Clique>>;
 
+    /// Inspect the cliques: fitness of their blocks, blocks exclusive to each clique, and tie-breaking hash sums.
+    #[method(name = "get_cliques_inspection")]
+    async fn get_cliques_inspection(&self) -> RpcResult<Vec<CliqueInspection>>;
+
     /// Returns the active stakers and their active roll counts for the current cycle.
     #[method(name = "get_stakers")]
     async fn get_stakers(
@@ -334,6 +444,23 @@ pub trait MassaRpc {
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
 
+    /// Returns the final executed operations involving an address, most recent first.
+    /// Pass the `next_cursor` of a page to get the following one.
+    #[method(name = "get_operations_by_address")]
+    async fn get_operations_by_address(
+        &self,
+        address: Address,
+        cursor: Option<String>,
+        limit: Option<u64>,
+    ) -> RpcResult<AddressOperations>;
+
+    /// Returns the trace of the last execution of an operation: its call tree,
+    /// with the gas, datastore reads and writes and coin transfers of each call.
+    /// Null if the operation was not traced or its trace was forgotten.
+    #[method(name = "trace_operation")]
+    async fn trace_operation(&self, operation_id: OperationId)
+        -> RpcResult<Option<OperationTrace>>;
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     #[method(name = "get_endorsements")]
     async fn get_endorsements(&self, arg: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>>;
@@ -342,11 +469,61 @@ pub trait MassaRpc {
     #[method(name = "get_blocks")]
     async fn get_blocks(&self, arg: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>>;
 
+    /// Returns the status in the graph of each of the given blocks, with the block itself if it is known.
+    /// Unlike `get_blocks`, unknown blocks are reported instead of being left out.
+    #[method(name = "v2_get_blocks")]
+    async fn get_blocks_v2(&self, arg: Vec<BlockId>) -> RpcResult<Vec<BlockInfoV2>>;
+
+    /// Returns the finality of each of the given blocks: candidate, in the blockclique, final or discarded,
+    /// with the number of blocks confirming it and the fitness it still misses to become final.
+    #[method(name = "get_blocks_finality")]
+    async fn get_blocks_finality(&self, arg: Vec<BlockId>) -> RpcResult<Vec<BlockFinalityInfo>>;
+
+    /// Exports the block graph of the slots in `[start, end)` in DOT or GraphML,
+    /// with the parent links between the exported blocks, their fitness and the cliques they belong to.
+    #[method(name = "get_block_graph_export")]
+    async fn get_block_graph_export(
+        &self,
+        start: Slot,
+        end: Slot,
+        format: GraphExportFormat,
+    ) -> RpcResult<String>;
+
+    /// Returns the metadata of the blocks pruned from the consensus graph with a slot in `[start, end)`, sorted by slot.
+    /// Only available on nodes in archival mode.
+    #[method(name = "get_archived_blocks")]
+    async fn get_archived_blocks(&self, start: Slot, end: Slot) -> RpcResult<Vec<ArchivedBlock>>;
+
+    /// Returns a cycle pruned from the final state, if it was archived.
+    /// Only available on nodes in archival mode.
+    #[method(name = "get_archived_cycle")]
+    async fn get_archived_cycle(&self, cycle: u64) -> RpcResult<Option<CycleInfo>>;
+
     /// Get information on the block at a slot in the blockclique.
     /// If there is no block at this slot a `None` is returned.
     #[method(name = "get_blockclique_block_by_slot")]
     async fn get_blockclique_block_by_slot(&self, arg: Slot) -> RpcResult<Option<Block>>;
 
+    /// Get the block of the blockclique at a slot, or the final block at this slot,
+    /// with its header only or with the content of its operations.
+    /// If there is no block at this slot a `None` is returned.
+    #[method(name = "get_block_by_slot")]
+    async fn get_block_by_slot(
+        &self,
+        slot: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Option<SlotBlock>>;
+
+    /// Get the blocks of the blockclique, or final blocks, from slot `start` (included)
+    /// to slot `end` (excluded), sorted by slot.
+    #[method(name = "get_blocks_by_slot_range")]
+    async fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Vec<SlotBlock>>;
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     #[method(name = "get_graph_interval")]
@@ -359,10 +536,30 @@ pub trait MassaRpc {
         arg: Vec<DatastoreEntryInput>,
     ) -> RpcResult<Vec<DatastoreEntryOutput>>;
 
+    /// List the datastore keys of an address starting with a prefix, sorted by key.
+    /// Pass the `next_key` of a page as `start_after` to get the following one.
+    #[method(name = "get_datastore_keys")]
+    async fn get_datastore_keys(&self, arg: DatastoreKeysInput) -> RpcResult<DatastoreKeysOutput>;
+
     /// Get addresses.
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Suggests a fee for an operation to be included within `target_inclusion_slots` slots,
+    /// from the fees of the operations included in the recent blocks and the current pool composition.
+    #[method(name = "get_fee_estimate")]
+    async fn get_fee_estimate(&self, target_inclusion_slots: u64) -> RpcResult<FeeEstimate>;
+
+    /// Returns the size of the operation pool, the fee percentiles and the number of operations
+    /// by expire period of its operations, and the fill rate of the endorsement pool.
+    #[method(name = "get_pool_statistics")]
+    async fn get_pool_statistics(&self) -> RpcResult<PoolStatistics>;
+
+    /// Returns the pool statistics and the operations created by `addresses` waiting in the pool,
+    /// each with the reason why it may not be included yet.
+    #[method(name = "get_pool_status")]
+    async fn get_pool_status(&self, addresses: Option<Vec<Address>>) -> RpcResult<PoolStatus>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[method(name = "send_operations")]
     async fn send_operations(&self, arg: Vec<OperationInput>) -> RpcResult<Vec<OperationId>>;
@@ -377,6 +574,12 @@ pub trait MassaRpc {
     async fn get_filtered_sc_output_event(&self, arg: EventFilter)
         -> RpcResult<Vec<SCOutputEvent>>;
 
+    /// Get a page of the events kept by the node, sorted by slot, filtered by slot range,
+    /// emitter address, original caller address, data prefix and status.
+    /// Pass the `next_cursor` of a page to get the following one.
+    #[method(name = "get_sc_output_events")]
+    async fn get_sc_output_events(&self, arg: SCOutputEventQuery) -> RpcResult<SCOutputEventPage>;
+
     /// Get OpenRPC specification.
     #[method(name = "rpc.discover")]
     async fn get_openrpc_spec(&self) -> RpcResult<Value>;
@@ -6,26 +6,48 @@ use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
+    block::{BlockFinalityInfo, BlockInfo, BlockInfoV2, BlockSummary, SlotBlock, SlotBlockOptions},
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimate, GasEstimationRequest, OperationTrace,
+        ReadOnlyBytecodeExecution, ReadOnlyCall, SCOutputEventPage, SCOutputEventQuery,
+    },
+    graph::GraphExportFormat,
+    node::{BanInfo, BanInput, BanTarget, GrpcDrainStatus, NodeStatus},
+    operation::{
+        AddressOperations, FeeEstimate, OperationInfo, OperationInput, PoolStatistics, PoolStatus,
+    },
     page::{PageRequest, PagedVec},
     ListType, ScrudOperation, TimeInterval,
 };
 use massa_execution_exports::ExecutionController;
+use massa_grpc::drain::DrainController;
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::{ArchivedBlock, Block},
+    block_id::BlockId,
+    clique::{Clique, CliqueInspection},
+    composite::PubkeySig,
+    endorsement::EndorsementId,
+    execution::EventFilter,
+    ip_range::IpRange,
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
 };
-use massa_protocol_exports::{PeerId, ProtocolController};
+use massa_pool_exports::PoolController;
+use massa_pos_exports::CycleInfo;
+use massa_protocol_exports::{BanTarget as PeerBanTarget, PeerId, ProtocolController};
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::collections::BTreeSet;
@@ -36,22 +58,29 @@ use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+/// Reason of the bans set by the node operator without a reason
+const OPERATOR_BAN_REASON: &str = "banned by the node operator";
+
 impl API<Private> {
     /// generate a new private API
     pub fn new(
         protocol_controller: Box<dyn ProtocolController>,
         execution_controller: Box<dyn ExecutionController>,
+        pool_controller: Box<dyn PoolController>,
         api_settings: APIConfig,
         node_wallet: Arc<RwLock<Wallet>>,
+        grpc_drain_controller: Option<DrainController>,
     ) -> (Self, mpsc::Receiver<()>) {
         let (stop_node_channel, rx) = mpsc::channel(1);
         (
             API(Private {
                 protocol_controller,
                 execution_controller,
+                pool_controller,
                 api_settings,
                 stop_node_channel,
                 node_wallet,
+                grpc_drain_controller,
             }),
             rx,
         )
@@ -65,7 +94,7 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(self.into_rpc(), url, settings, None).await
     }
 }
 
@@ -128,6 +157,10 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    async fn estimate_gas(&self, _: GasEstimationRequest) -> RpcResult<GasEstimate> {
+        crate::wrong_api::<GasEstimate>()
+    }
+
     async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
         let node_wallet = self.0.node_wallet.clone();
         let mut w_wallet = node_wallet.write();
@@ -142,16 +175,15 @@ impl MassaRpcServer for API<Private> {
         Ok(w_wallet.get_wallet_address_list())
     }
 
-    async fn node_ban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //    .node_ban_by_ips(ips)
-        //    .await
-        //    .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .add_bans(
+                ips.into_iter().map(PeerBanTarget::Ip).collect(),
+                None,
+                OPERATOR_BAN_REASON.to_string(),
+            )
+            .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
     async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
@@ -178,16 +210,77 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
-    async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
-        //TODO: Reinvoke
-        // let network_command_sender = self.0.network_command_sender.clone();
-        // network_command_sender
-        //     .node_unban_ips(ips)
-        //     .await
-        //     .map_err(|e| ApiError::NetworkError(e).into())
-        return Err(
-            ApiError::BadRequest("This request is currently not available".to_string()).into(),
-        );
+    async fn node_start_grpc_drain(&self, grace_period: MassaTime) -> RpcResult<GrpcDrainStatus> {
+        let drain_controller =
+            self.0.grpc_drain_controller.as_ref().ok_or_else(|| {
+                ApiError::MissingConfig("the gRPC API is not enabled".to_string())
+            })?;
+        drain_controller
+            .start_drain(grace_period)
+            .map_err(|e| ApiError::InternalServerError(e.to_string()).into())
+    }
+
+    async fn node_grpc_drain_status(&self) -> RpcResult<GrpcDrainStatus> {
+        let drain_controller =
+            self.0.grpc_drain_controller.as_ref().ok_or_else(|| {
+                ApiError::MissingConfig("the gRPC API is not enabled".to_string())
+            })?;
+        drain_controller
+            .status()
+            .map_err(|e| ApiError::InternalServerError(e.to_string()).into())
+    }
+
+    async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .remove_bans(ips.into_iter().map(PeerBanTarget::Ip).collect())
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_add_bans(
+        &self,
+        BanInput {
+            targets,
+            duration,
+            reason,
+        }: BanInput,
+    ) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .add_bans(
+                targets.into_iter().map(peer_ban_target).collect(),
+                duration,
+                reason.unwrap_or_else(|| OPERATOR_BAN_REASON.to_string()),
+            )
+            .map_err(|e| ApiError::ProtocolError(e).into())
+    }
+
+    async fn node_bans(&self) -> RpcResult<Vec<BanInfo>> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        let bans = protocol_controller
+            .get_bans()
+            .map_err(ApiError::ProtocolError)?;
+        Ok(bans
+            .into_iter()
+            .map(|ban| BanInfo {
+                target: match ban.target {
+                    PeerBanTarget::PeerId(peer_id) => {
+                        BanTarget::NodeId(NodeId::new(peer_id.get_public_key()))
+                    }
+                    PeerBanTarget::Ip(ip) => BanTarget::Ip(ip),
+                },
+                reason: ban.reason,
+                banned_at: ban.banned_at,
+                expires_at: ban.expires_at,
+            })
+            .collect())
+    }
+
+    async fn node_remove_bans(&self, targets: Vec<BanTarget>) -> RpcResult<()> {
+        let protocol_controller = self.0.protocol_controller.clone();
+        protocol_controller
+            .remove_bans(targets.into_iter().map(peer_ban_target).collect())
+            .map_err(|e| ApiError::ProtocolError(e).into())
     }
 
     async fn get_status(&self) -> RpcResult<NodeStatus> {
@@ -198,6 +291,10 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<Clique>>()
     }
 
+    async fn get_cliques_inspection(&self) -> RpcResult<Vec<CliqueInspection>> {
+        crate::wrong_api::<Vec<CliqueInspection>>()
+    }
+
     async fn get_stakers(&self, _: Option<PageRequest>) -> RpcResult<PagedVec<(Address, u64)>> {
         crate::wrong_api::<PagedVec<(Address, u64)>>()
     }
@@ -206,6 +303,19 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
 
+    async fn get_operations_by_address(
+        &self,
+        _: Address,
+        _: Option<String>,
+        _: Option<u64>,
+    ) -> RpcResult<AddressOperations> {
+        crate::wrong_api::<AddressOperations>()
+    }
+
+    async fn trace_operation(&self, _: OperationId) -> RpcResult<Option<OperationTrace>> {
+        crate::wrong_api::<Option<OperationTrace>>()
+    }
+
     async fn get_endorsements(&self, _: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         crate::wrong_api::<Vec<EndorsementInfo>>()
     }
@@ -214,10 +324,52 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<BlockInfo>>()
     }
 
+    async fn get_blocks_v2(&self, _: Vec<BlockId>) -> RpcResult<Vec<BlockInfoV2>> {
+        crate::wrong_api::<Vec<BlockInfoV2>>()
+    }
+
+    async fn get_blocks_finality(&self, _: Vec<BlockId>) -> RpcResult<Vec<BlockFinalityInfo>> {
+        crate::wrong_api::<Vec<BlockFinalityInfo>>()
+    }
+
+    async fn get_block_graph_export(
+        &self,
+        _: Slot,
+        _: Slot,
+        _: GraphExportFormat,
+    ) -> RpcResult<String> {
+        crate::wrong_api::<String>()
+    }
+
+    async fn get_archived_blocks(&self, _: Slot, _: Slot) -> RpcResult<Vec<ArchivedBlock>> {
+        crate::wrong_api::<Vec<ArchivedBlock>>()
+    }
+
+    async fn get_archived_cycle(&self, _: u64) -> RpcResult<Option<CycleInfo>> {
+        crate::wrong_api::<Option<CycleInfo>>()
+    }
+
     async fn get_blockclique_block_by_slot(&self, _: Slot) -> RpcResult<Option<Block>> {
         crate::wrong_api::<Option<Block>>()
     }
 
+    async fn get_block_by_slot(
+        &self,
+        _: Slot,
+        _: Option<SlotBlockOptions>,
+    ) -> RpcResult<Option<SlotBlock>> {
+        crate::wrong_api::<Option<SlotBlock>>()
+    }
+
+    async fn get_blocks_by_slot_range(
+        &self,
+        _: Slot,
+        _: Slot,
+        _: Option<SlotBlockOptions>,
+    ) -> RpcResult<Vec<SlotBlock>> {
+        crate::wrong_api::<Vec<SlotBlock>>()
+    }
+
     async fn get_graph_interval(&self, _: TimeInterval) -> RpcResult<Vec<BlockSummary>> {
         crate::wrong_api::<Vec<BlockSummary>>()
     }
@@ -229,10 +381,26 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api()
     }
 
+    async fn get_datastore_keys(&self, _: DatastoreKeysInput) -> RpcResult<DatastoreKeysOutput> {
+        crate::wrong_api::<DatastoreKeysOutput>()
+    }
+
     async fn get_addresses(&self, _: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_fee_estimate(&self, _: u64) -> RpcResult<FeeEstimate> {
+        crate::wrong_api::<FeeEstimate>()
+    }
+
+    async fn get_pool_statistics(&self) -> RpcResult<PoolStatistics> {
+        crate::wrong_api::<PoolStatistics>()
+    }
+
+    async fn get_pool_status(&self, _: Option<Vec<Address>>) -> RpcResult<PoolStatus> {
+        crate::wrong_api::<PoolStatus>()
+    }
+
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         crate::wrong_api::<Vec<OperationId>>()
     }
@@ -241,6 +409,10 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
+    async fn get_sc_output_events(&self, _: SCOutputEventQuery) -> RpcResult<SCOutputEventPage> {
+        crate::wrong_api::<SCOutputEventPage>()
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -278,7 +450,7 @@ impl MassaRpcServer for API<Private> {
         );
     }
 
-    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpRange>> {
         read_ips_from_jsonfile(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
             &ListType::Whitelist,
@@ -295,7 +467,7 @@ impl MassaRpcServer for API<Private> {
         })
     }
 
-    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
             ips,
@@ -304,7 +476,7 @@ impl MassaRpcServer for API<Private> {
         )
     }
 
-    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_whitelist_path.clone(),
             ips,
@@ -313,14 +485,14 @@ impl MassaRpcServer for API<Private> {
         )
     }
 
-    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpRange>> {
         read_ips_from_jsonfile(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
             &ListType::Blacklist,
         )
     }
 
-    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
             ips,
@@ -329,7 +501,7 @@ impl MassaRpcServer for API<Private> {
         )
     }
 
-    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         run_scrud_operation(
             self.0.api_settings.bootstrap_blacklist_path.clone(),
             ips,
@@ -338,6 +510,30 @@ impl MassaRpcServer for API<Private> {
         )
     }
 
+    async fn node_pool_denylist(&self) -> RpcResult<Vec<Address>> {
+        Ok(self.0.pool_controller.get_denied_senders())
+    }
+
+    async fn node_add_to_pool_denylist(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.0
+            .pool_controller
+            .add_denied_senders(addresses)
+            .map_err(|e| {
+                ApiError::InternalServerError(format!("failed to save the pool denylist: {}", e))
+                    .into()
+            })
+    }
+
+    async fn node_remove_from_pool_denylist(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.0
+            .pool_controller
+            .remove_denied_senders(addresses)
+            .map_err(|e| {
+                ApiError::InternalServerError(format!("failed to save the pool denylist: {}", e))
+                    .into()
+            })
+    }
+
     async fn get_openrpc_spec(&self) -> RpcResult<Value> {
         crate::wrong_api::<Value>()
     }
@@ -346,7 +542,7 @@ impl MassaRpcServer for API<Private> {
 /// Run Search, Create, Read, Update, Delete operation on bootstrap list of IP(s)
 fn run_scrud_operation(
     bootstrap_list_file: PathBuf,
-    ips: Vec<IpAddr>,
+    ips: Vec<IpRange>,
     list_type: ListType,
     scrud_operation: ScrudOperation,
 ) -> RpcResult<()> {
@@ -358,7 +554,7 @@ fn run_scrud_operation(
                 } else {
                     read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
                         .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
+                        .and_then(|mut list_ips: BTreeSet<IpRange>| {
                             list_ips.extend(ips);
                             write_ips_to_jsonfile(bootstrap_list_file, list_ips, &list_type)
                         })
@@ -375,7 +571,7 @@ fn run_scrud_operation(
                 } else {
                     read_ips_from_jsonfile(bootstrap_list_file.clone(), &list_type)
                         .map(BTreeSet::from_iter)
-                        .and_then(|mut list_ips: BTreeSet<IpAddr>| {
+                        .and_then(|mut list_ips: BTreeSet<IpRange>| {
                             if list_ips.is_empty() {
                                 return Err(ApiError::InternalServerError(format!(
                                     "failed to execute delete operation, bootstrap {} is empty",
@@ -430,7 +626,7 @@ fn get_file_len(
 fn read_ips_from_jsonfile(
     bootstrap_list_file: PathBuf,
     list_type: &ListType,
-) -> RpcResult<Vec<IpAddr>> {
+) -> RpcResult<Vec<IpRange>> {
     std::fs::read_to_string(bootstrap_list_file)
         .map_err(|e| {
             ApiError::InternalServerError(format!(
@@ -450,10 +646,21 @@ fn read_ips_from_jsonfile(
         })
 }
 
+/// Translates the target of a ban from node ids to peer ids
+fn peer_ban_target(target: BanTarget) -> PeerBanTarget {
+    match target {
+        //TODO: Change when unify node id and peer id
+        BanTarget::NodeId(node_id) => {
+            PeerBanTarget::PeerId(PeerId::from_public_key(node_id.get_public_key()))
+        }
+        BanTarget::Ip(ip) => PeerBanTarget::Ip(ip),
+    }
+}
+
 /// Write bootstrap list IP(s) from json file
 fn write_ips_to_jsonfile(
     bootstrap_list_file: PathBuf,
-    ips: BTreeSet<IpAddr>,
+    ips: BTreeSet<IpRange>,
     list_type: &ListType,
 ) -> RpcResult<()> {
     OpenOptions::new()
@@ -7,31 +7,49 @@ use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::AddressInfo,
-    block::{BlockInfo, BlockInfoContent, BlockSummary},
+    block::{
+        BlockFinalityInfo, BlockInfo, BlockInfoContent, BlockInfoV2, BlockSummary, SlotBlock,
+        SlotBlockOptions,
+    },
     config::APIConfig,
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeyEntry, DatastoreKeysInput,
+        DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimate, GasEstimationRequest, GasEstimationTarget,
+        OperationTrace, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult,
+        ReadOnlyStateOverride, SCOutputEventPage, SCOutputEventQuery,
+    },
+    graph::{BlockGraph, GraphExportFormat},
+    node::{BanInfo, BanInput, BanTarget, ChainStats, FeePercentiles, GrpcDrainStatus, NodeStatus},
+    operation::{
+        AddressOperationInfo, AddressOperations, FeeEstimate, OperationInfo, OperationInput,
+        PendingOperation, PendingOperationStatus, PoolStatistics, PoolStatus,
+    },
     page::{PageRequest, PagedVec},
     slot::SlotAmount,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
+use massa_db::MassaArchive;
 use massa_execution_exports::{
     ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ReadOnlyLedgerOverride,
 };
 use massa_models::{
     address::Address,
-    block::{Block, BlockGraphStatus},
+    amount::Amount,
+    block::{ArchivedBlock, Block, BlockGraphStatus},
     block_id::BlockId,
-    clique::Clique,
+    bytecode::Bytecode,
+    clique::{Clique, CliqueInspection},
     composite::PubkeySig,
     config::CompactConfig,
-    datastore::DatastoreDeserializer,
+    datastore::{Datastore, DatastoreDeserializer},
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
@@ -44,12 +62,13 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShareDeserializer,
     slot::Slot,
+    stats::{BootstrapStats, ConsensusStats, ExecutionStats},
     timeslots,
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
-use massa_pool_exports::PoolController;
-use massa_pos_exports::SelectorController;
+use massa_pool_exports::{FeeEstimate as PoolFeeEstimate, PoolController};
+use massa_pos_exports::{CycleInfo, SelectorController};
 use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
@@ -60,6 +79,12 @@ use massa_versioning::{
 };
 use std::collections::BTreeMap;
 use std::net::{IpAddr, SocketAddr};
+use std::ops::Bound;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Number of periods before expiry under which a pending operation is reported as expiring soon
+const EXPIRING_SOON_PERIODS: u64 = 2;
 
 impl API<Public> {
     /// generate a new public API
@@ -75,6 +100,8 @@ impl API<Public> {
         node_id: NodeId,
         storage: Storage,
         mip_store: MipStore,
+        bootstrap_stats: Option<BootstrapStats>,
+        archive: Option<Arc<MassaArchive>>,
     ) -> Self {
         API(Public {
             consensus_controller,
@@ -88,6 +115,8 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            bootstrap_stats,
+            archive,
         })
     }
 }
@@ -99,7 +128,13 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(
+            self.into_rpc(),
+            url,
+            api_config,
+            api_config.public_bearer_token.as_deref(),
+        )
+        .await
     }
 }
 
@@ -132,7 +167,9 @@ impl MassaRpcServer for API<Public> {
             address,
             bytecode,
             operation_datastore,
+            fee,
             is_final,
+            overrides,
         } in reqs
         {
             let address = if let Some(addr) = address {
@@ -149,26 +186,9 @@ impl MassaRpcServer for API<Public> {
                 Address::from_public_key(&keypair.get_public_key())
             };
 
-            let op_datastore = match operation_datastore {
-                Some(v) => {
-                    let deserializer = DatastoreDeserializer::new(
-                        self.0.api_settings.max_op_datastore_entry_count,
-                        self.0.api_settings.max_op_datastore_key_length,
-                        self.0.api_settings.max_op_datastore_value_length,
-                    );
-                    match deserializer.deserialize::<DeserializeError>(&v) {
-                        Ok((_, deserialized)) => Some(deserialized),
-                        Err(e) => {
-                            return Err(ApiError::InconsistencyError(format!(
-                                "Operation datastore error: {}",
-                                e
-                            ))
-                            .into())
-                        }
-                    }
-                }
-                None => None,
-            };
+            let op_datastore = operation_datastore
+                .map(|v| deserialize_operation_datastore(&v, &self.0.api_settings))
+                .transpose()?;
 
             // TODO:
             // * set a maximum gas value for read-only executions to prevent attacks
@@ -184,8 +204,11 @@ impl MassaRpcServer for API<Public> {
                     coins: Default::default(),
                     owned_addresses: vec![address],
                     operation_datastore: op_datastore,
+                    reentrancy_locked: false,
                 }],
+                fee,
                 is_final,
+                ledger_overrides: ledger_overrides(overrides, &self.0.api_settings)?,
             };
 
             // run
@@ -229,7 +252,10 @@ impl MassaRpcServer for API<Public> {
             target_function,
             parameter,
             caller_address,
+            coins,
+            fee,
             is_final,
+            overrides,
         } in reqs
         {
             let caller_address = if let Some(addr) = caller_address {
@@ -265,15 +291,19 @@ impl MassaRpcServer for API<Public> {
                         coins: Default::default(),
                         owned_addresses: vec![caller_address],
                         operation_datastore: None, // should always be None
+                        reentrancy_locked: false,
                     },
                     ExecutionStackElement {
                         address: target_address,
-                        coins: Default::default(),
+                        coins,
                         owned_addresses: vec![target_address],
                         operation_datastore: None, // should always be None
+                        reentrancy_locked: false,
                     },
                 ],
+                fee,
                 is_final,
+                ledger_overrides: ledger_overrides(overrides, &self.0.api_settings)?,
             };
 
             // run
@@ -302,6 +332,73 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn estimate_gas(&self, req: GasEstimationRequest) -> RpcResult<GasEstimate> {
+        let api_settings = &self.0.api_settings;
+        let operation_datastore = match &req.target {
+            GasEstimationTarget::ExecuteSC {
+                operation_datastore: Some(v),
+                ..
+            } => Some(deserialize_operation_datastore(v, api_settings)?),
+            _ => None,
+        };
+
+        // the compilation of the bytecode is paid on top of its execution
+        let compilation_cost = match &req.target {
+            GasEstimationTarget::ExecuteSC { .. } => api_settings.sp_compilation_cost,
+            GasEstimationTarget::CallSC { .. } => 0,
+        };
+        let max_gas = api_settings
+            .gas_estimation_max_gas
+            .saturating_sub(compilation_cost);
+        let run = |max_gas: u64| {
+            self.0
+                .execution_controller
+                .execute_readonly_request(gas_estimation_request(
+                    &req,
+                    operation_datastore.clone(),
+                    max_gas,
+                ))
+        };
+
+        // the operation must succeed with the max gas
+        let mut execution_count = 1;
+        let gas_cost = run(max_gas)
+            .map_err(|err| {
+                ApiError::BadRequest(format!(
+                    "the operation fails with the max gas of {}: {}",
+                    api_settings.gas_estimation_max_gas, err
+                ))
+            })?
+            .gas_cost;
+
+        // binary search of the minimal gas between a failing and a succeeding value,
+        // starting from the gas consumed with the max gas
+        let mut failing = gas_cost.saturating_sub(1);
+        let mut succeeding = max_gas;
+        let mut next = gas_cost;
+        while succeeding - failing > 1
+            && execution_count < api_settings.max_gas_estimation_executions
+        {
+            execution_count += 1;
+            if run(next).is_ok() {
+                succeeding = next;
+            } else {
+                failing = next;
+            }
+            next = failing + (succeeding - failing) / 2;
+        }
+
+        let min_gas = succeeding.saturating_add(compilation_cost);
+        let margin = min_gas.saturating_mul(api_settings.gas_estimation_margin_percent) / 100;
+        Ok(GasEstimate {
+            min_gas,
+            max_gas: min_gas
+                .saturating_add(margin)
+                .min(api_settings.gas_estimation_max_gas),
+            execution_count,
+        })
+    }
+
     async fn remove_staking_addresses(&self, _: Vec<Address>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -318,6 +415,14 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_start_grpc_drain(&self, _: MassaTime) -> RpcResult<GrpcDrainStatus> {
+        crate::wrong_api::<GrpcDrainStatus>()
+    }
+
+    async fn node_grpc_drain_status(&self) -> RpcResult<GrpcDrainStatus> {
+        crate::wrong_api::<GrpcDrainStatus>()
+    }
+
     async fn node_unban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -326,6 +431,18 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_add_bans(&self, _: BanInput) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_bans(&self) -> RpcResult<Vec<BanInfo>> {
+        crate::wrong_api::<Vec<BanInfo>>()
+    }
+
+    async fn node_remove_bans(&self, _: Vec<BanTarget>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
     async fn get_status(&self) -> RpcResult<NodeStatus> {
         let execution_controller = self.0.execution_controller.clone();
         let consensus_controller = self.0.consensus_controller.clone();
@@ -369,6 +486,14 @@ impl MassaRpcServer for API<Public> {
             pool_command_sender.get_endorsement_count(),
         );
 
+        let chain_stats = chain_stats(
+            &consensus_stats,
+            &execution_stats,
+            pool_command_sender.get_operation_fee_percentiles(&[10, 50, 90]),
+            last_slot,
+            api_settings.thread_count,
+        );
+
         let next_slot_result = last_slot
             .unwrap_or_else(|| Slot::new(0, 0))
             .get_next_slot(api_settings.thread_count);
@@ -434,6 +559,8 @@ impl MassaRpcServer for API<Public> {
             consensus_stats,
             network_stats,
             pool_stats,
+            chain_stats,
+            bootstrap_stats: self.0.bootstrap_stats.clone(),
             config,
             current_cycle,
         })
@@ -444,6 +571,10 @@ impl MassaRpcServer for API<Public> {
         Ok(consensus_controller.get_cliques())
     }
 
+    async fn get_cliques_inspection(&self) -> RpcResult<Vec<CliqueInspection>> {
+        Ok(self.0.consensus_controller.get_cliques_inspection())
+    }
+
     async fn get_stakers(
         &self,
         page_request: Option<PageRequest>,
@@ -560,6 +691,57 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_operations_by_address(
+        &self,
+        address: Address,
+        cursor: Option<String>,
+        limit: Option<u64>,
+    ) -> RpcResult<AddressOperations> {
+        let max_limit = self.0.api_settings.max_arguments;
+        let limit = limit.unwrap_or(max_limit);
+        if limit > max_limit {
+            return Err(ApiError::BadRequest("too many operations requested".into()).into());
+        }
+        let cursor = cursor.as_deref().map(parse_operation_cursor).transpose()?;
+
+        let Some(operations) = self.0.execution_controller.get_operations_by_address(
+            &address,
+            cursor,
+            limit as usize,
+        ) else {
+            return Err(ApiError::MissingConfig("the operation index is disabled".into()).into());
+        };
+
+        let operations: Vec<AddressOperationInfo> = operations
+            .into_iter()
+            .map(|op| AddressOperationInfo {
+                id: op.op_id,
+                slot: op.slot,
+                block_id: op.block_id,
+                op_exec_status: op.success,
+            })
+            .collect();
+        // a full page may be followed by more operations
+        let next_cursor = match operations.last() {
+            Some(last) if operations.len() as u64 == limit => Some(last.cursor()),
+            _ => None,
+        };
+        Ok(AddressOperations {
+            operations,
+            next_cursor,
+        })
+    }
+
+    async fn trace_operation(
+        &self,
+        operation_id: OperationId,
+    ) -> RpcResult<Option<OperationTrace>> {
+        self.0
+            .execution_controller
+            .get_operation_trace(&operation_id)
+            .ok_or_else(|| ApiError::MissingConfig("operation tracing is disabled".into()).into())
+    }
+
     async fn get_endorsements(&self, eds: Vec<EndorsementId>) -> RpcResult<Vec<EndorsementInfo>> {
         // get the endorsements and the list of blocks that contain them from storage
         let storage_info: Vec<(SecureShareEndorsement, PreHashSet<BlockId>)> = {
@@ -683,6 +865,70 @@ impl MassaRpcServer for API<Public> {
         Ok(blocks)
     }
 
+    async fn get_blocks_v2(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfoV2>> {
+        if ids.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let statuses = self.0.consensus_controller.get_block_statuses(&ids);
+        let blocks = self.0.storage.read_blocks();
+        Ok(ids
+            .into_iter()
+            .zip(statuses)
+            .map(|(id, status)| BlockInfoV2 {
+                id,
+                status,
+                block: blocks.get(&id).map(|block| block.content.clone()),
+            })
+            .collect())
+    }
+
+    async fn get_blocks_finality(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockFinalityInfo>> {
+        if ids.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(ids
+            .into_iter()
+            .map(|id| BlockFinalityInfo {
+                id,
+                finality: self.0.consensus_controller.get_block_status(&id),
+            })
+            .collect())
+    }
+
+    async fn get_block_graph_export(
+        &self,
+        start: Slot,
+        end: Slot,
+        format: GraphExportFormat,
+    ) -> RpcResult<String> {
+        let graph = self
+            .0
+            .consensus_controller
+            .get_block_graph_status(Some(start), Some(end))
+            .map_err(ApiError::ConsensusError)?;
+        Ok(BlockGraph::from(graph).render(format))
+    }
+
+    async fn get_archived_blocks(&self, start: Slot, end: Slot) -> RpcResult<Vec<ArchivedBlock>> {
+        let Some(archive) = &self.0.archive else {
+            return Err(ApiError::MissingConfig("the archival mode is disabled".into()).into());
+        };
+        Ok(
+            archive.get_blocks_by_slot_range(
+                start,
+                end,
+                self.0.api_settings.max_arguments as usize,
+            ),
+        )
+    }
+
+    async fn get_archived_cycle(&self, cycle: u64) -> RpcResult<Option<CycleInfo>> {
+        let Some(archive) = &self.0.archive else {
+            return Err(ApiError::MissingConfig("the archival mode is disabled".into()).into());
+        };
+        Ok(archive.get_cycle(cycle))
+    }
+
     async fn get_blockclique_block_by_slot(&self, slot: Slot) -> RpcResult<Option<Block>> {
         let consensus_controller = self.0.consensus_controller.clone();
         let storage = self.0.storage.clone_without_refs();
@@ -701,6 +947,56 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_block_by_slot(
+        &self,
+        slot: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Option<SlotBlock>> {
+        if slot.thread >= self.0.api_settings.thread_count {
+            return Err(ApiError::BadRequest(format!("invalid thread in slot {}", slot)).into());
+        }
+        Ok(slot_block(
+            &*self.0.consensus_controller,
+            &self.0.storage,
+            slot,
+            &options.unwrap_or_default(),
+        ))
+    }
+
+    async fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Vec<SlotBlock>> {
+        let api_settings = self.0.api_settings.clone();
+        if start.thread >= api_settings.thread_count || end.thread >= api_settings.thread_count {
+            return Err(ApiError::BadRequest("invalid thread in slot range".into()).into());
+        }
+        let slot_count = end
+            .slots_since(&start, api_settings.thread_count)
+            .unwrap_or_default();
+        if slot_count > api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many slots requested".into()).into());
+        }
+
+        let options = options.unwrap_or_default();
+        let mut blocks = Vec::new();
+        let mut slot = start;
+        while slot < end {
+            blocks.extend(slot_block(
+                &*self.0.consensus_controller,
+                &self.0.storage,
+                slot,
+                &options,
+            ));
+            slot = slot
+                .get_next_slot(api_settings.thread_count)
+                .map_err(ApiError::ModelsError)?;
+        }
+        Ok(blocks)
+    }
+
     /// gets an interval of the block graph from consensus, with time filtering
     /// time filtering is done consensus-side to prevent communication overhead
     async fn get_graph_interval(&self, time: TimeInterval) -> RpcResult<Vec<BlockSummary>> {
@@ -779,6 +1075,72 @@ impl MassaRpcServer for API<Public> {
             .collect())
     }
 
+    async fn get_datastore_keys(
+        &self,
+        DatastoreKeysInput {
+            address,
+            prefix,
+            start_after,
+            limit,
+            is_final,
+            include_values,
+        }: DatastoreKeysInput,
+    ) -> RpcResult<DatastoreKeysOutput> {
+        let max_limit = self.0.api_settings.max_arguments;
+        let limit = limit.unwrap_or(max_limit);
+        if limit > max_limit {
+            return Err(ApiError::BadRequest("too many datastore keys requested".into()).into());
+        }
+
+        let (final_keys, candidate_keys) = self
+            .0
+            .execution_controller
+            .get_final_and_candidate_datastore_keys(&address, &prefix);
+        let keys = if is_final { final_keys } else { candidate_keys };
+        let lower_bound = match start_after {
+            Some(key) => Bound::Excluded(key),
+            None => Bound::Unbounded,
+        };
+        let keys: Vec<Vec<u8>> = keys
+            .range((lower_bound, Bound::Unbounded))
+            .take(limit as usize)
+            .cloned()
+            .collect();
+
+        let values = if include_values {
+            self.0
+                .execution_controller
+                .get_final_and_active_data_entry(
+                    keys.iter().map(|key| (address, key.clone())).collect(),
+                )
+                .into_iter()
+                .map(|(final_value, candidate_value)| {
+                    if is_final {
+                        final_value
+                    } else {
+                        candidate_value
+                    }
+                })
+                .collect()
+        } else {
+            vec![None; keys.len()]
+        };
+
+        // a full page may be followed by more keys
+        let next_key = match keys.last() {
+            Some(last) if keys.len() as u64 == limit => Some(last.clone()),
+            _ => None,
+        };
+        Ok(DatastoreKeysOutput {
+            entries: keys
+                .into_iter()
+                .zip(values)
+                .map(|(key, value)| DatastoreKeyEntry { key, value })
+                .collect(),
+            next_key,
+        })
+    }
+
     async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         // get info from storage about which blocks the addresses have created
         let created_blocks: Vec<PreHashSet<BlockId>> = {
@@ -879,6 +1241,7 @@ impl MassaRpcServer for API<Public> {
                     .final_datastore_keys
                     .into_iter()
                     .collect::<Vec<_>>(),
+                final_delegation: execution_infos.final_delegation,
 
                 // candidate execution info
                 candidate_balance: execution_infos.candidate_balance,
@@ -887,6 +1250,7 @@ impl MassaRpcServer for API<Public> {
                     .candidate_datastore_keys
                     .into_iter()
                     .collect::<Vec<_>>(),
+                candidate_delegation: execution_infos.candidate_delegation,
 
                 // deferred credits
                 deferred_credits: execution_infos
@@ -912,6 +1276,121 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    async fn get_fee_estimate(&self, target_inclusion_slots: u64) -> RpcResult<FeeEstimate> {
+        if target_inclusion_slots == 0 {
+            return Err(
+                ApiError::BadRequest("target_inclusion_slots must be at least 1".into()).into(),
+            );
+        }
+        let pool_command_sender = self.0.pool_command_sender.clone();
+        let included_fees = recent_included_fees(&*self.0.consensus_controller, &self.0.storage)?;
+        let recent_operation_count = included_fees.len();
+        let pool_fee = pool_command_sender.get_operation_inclusion_fee(target_inclusion_slots);
+        let estimate = PoolFeeEstimate::new(pool_fee, included_fees);
+
+        Ok(FeeEstimate {
+            fee: estimate.fee,
+            target_inclusion_slots,
+            pool_fee: estimate.pool_fee,
+            pool_operation_count: pool_command_sender.get_operation_count(),
+            recent_median_fee: estimate.recent_median_fee,
+            recent_operation_count,
+        })
+    }
+
+    async fn get_pool_statistics(&self) -> RpcResult<PoolStatistics> {
+        Ok(self.0.pool_command_sender.get_pool_statistics())
+    }
+
+    async fn get_pool_status(&self, addresses: Option<Vec<Address>>) -> RpcResult<PoolStatus> {
+        let addresses = addresses.unwrap_or_default();
+        let pool_command_sender = self.0.pool_command_sender.clone();
+        let api_settings = self.0.api_settings.clone();
+        if addresses.len() as u64 > api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        // operations of the requested addresses that are still in the pool
+        let created_ops: Vec<(OperationId, Address, Amount, u8, u64)> = {
+            let lck = self.0.storage.read_operations();
+            addresses
+                .iter()
+                .filter_map(|address| lck.get_operations_created_by(address))
+                .flatten()
+                .filter_map(|op_id| lck.get(op_id))
+                .map(|op| {
+                    (
+                        op.id,
+                        op.content_creator_address,
+                        op.content.fee,
+                        op.content_creator_address
+                            .get_thread(api_settings.thread_count),
+                        op.content.expire_period,
+                    )
+                })
+                .collect()
+        };
+        let op_ids: Vec<OperationId> = created_ops.iter().map(|(id, ..)| *id).collect();
+        let in_pool = pool_command_sender.contains_operations(&op_ids);
+
+        let current_period = timeslots::get_current_latest_block_slot(
+            api_settings.thread_count,
+            api_settings.t0,
+            api_settings.genesis_timestamp,
+        )
+        .map_err(ApiError::ModelsError)?
+        .map_or(0, |slot| slot.period);
+
+        // fee needed to be included before expiry, by number of remaining periods
+        let mut inclusion_fees: BTreeMap<u64, Option<Amount>> = BTreeMap::new();
+        let mut pending_operations = Vec::new();
+        for ((id, creator, fee, thread, expire_period), in_pool) in
+            created_ops.into_iter().zip(in_pool)
+        {
+            if !in_pool {
+                continue;
+            }
+            let remaining_periods = expire_period.saturating_sub(current_period);
+            let inclusion_fee = *inclusion_fees.entry(remaining_periods).or_insert_with(|| {
+                pool_command_sender.get_operation_inclusion_fee(
+                    remaining_periods
+                        .max(1)
+                        .saturating_mul(api_settings.thread_count as u64),
+                )
+            });
+            let status = if inclusion_fee.map_or(false, |inclusion_fee| fee < inclusion_fee) {
+                PendingOperationStatus::InsufficientFee
+            } else if remaining_periods <= EXPIRING_SOON_PERIODS {
+                PendingOperationStatus::ExpiringSoon
+            } else {
+                PendingOperationStatus::Pending
+            };
+            pending_operations.push(PendingOperation {
+                id,
+                creator,
+                fee,
+                thread,
+                expire_period,
+                status,
+            });
+        }
+
+        Ok(PoolStatus {
+            operation_count: pool_command_sender.get_operation_count(),
+            endorsement_count: pool_command_sender.get_endorsement_count(),
+            denunciation_count: pool_command_sender.get_denunciation_count(),
+            operation_fee_percentiles: match pool_command_sender
+                .get_operation_fee_percentiles(&[10, 50, 90])[..]
+            {
+                [p10, p50, p90] => Some(FeePercentiles { p10, p50, p90 }),
+                _ => None,
+            },
+            next_slot_inclusion_fee: pool_command_sender.get_operation_inclusion_fee(1),
+            quotas: pool_command_sender.get_operation_pool_usage(),
+            pending_operations,
+        })
+    }
+
     async fn send_operations(&self, ops: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let protocol_sender = self.0.protocol_controller.clone();
@@ -961,6 +1440,21 @@ impl MassaRpcServer for API<Public> {
                 Err(e) => Err(e),
             })
             .collect::<RpcResult<Vec<SecureShareOperation>>>()?;
+        let senders: Vec<Address> = verified_ops
+            .iter()
+            .map(|op| op.content_creator_address)
+            .collect();
+        if let Some(sender) = senders
+            .iter()
+            .zip(cmd_sender.contains_denied_senders(&senders))
+            .find_map(|(sender, denied)| denied.then_some(sender))
+        {
+            return Err(ApiError::BadRequest(format!(
+                "operations of {} are rejected by the local policy of this node",
+                sender
+            ))
+            .into());
+        }
         to_send.store_operations(verified_ops.clone());
         let ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
         cmd_sender.add_operations(to_send.clone());
@@ -993,6 +1487,61 @@ impl MassaRpcServer for API<Public> {
         Ok(events)
     }
 
+    async fn get_sc_output_events(
+        &self,
+        SCOutputEventQuery {
+            start,
+            end,
+            emitter_address,
+            original_caller_address,
+            data_prefix,
+            is_final,
+            cursor,
+            limit,
+        }: SCOutputEventQuery,
+    ) -> RpcResult<SCOutputEventPage> {
+        let max_limit = self.0.api_settings.max_arguments;
+        let limit = limit.unwrap_or(max_limit);
+        if limit > max_limit {
+            return Err(ApiError::BadRequest("too many events requested".into()).into());
+        }
+        let cursor = cursor.as_deref().map(parse_event_cursor).transpose()?;
+
+        // resume from the slot of the cursor, the events before it in that slot are skipped below
+        let start = std::cmp::max(start, cursor.map(|(slot, _)| slot));
+        let mut events = self
+            .0
+            .execution_controller
+            .get_filtered_sc_output_event(EventFilter {
+                start,
+                end,
+                emitter_address,
+                original_caller_address,
+                original_operation_id: None,
+                is_final,
+                is_error: None,
+            });
+        events.retain(|event| {
+            cursor.map_or(true, |cursor| {
+                (event.context.slot, event.context.index_in_slot) > cursor
+            }) && data_prefix
+                .as_ref()
+                .map_or(true, |prefix| event.data.starts_with(prefix.as_str()))
+        });
+        events.sort_by_key(|event| (event.context.slot, event.context.index_in_slot));
+        events.truncate(limit as usize);
+
+        // a full page may be followed by more events
+        let next_cursor = match events.last() {
+            Some(last) if events.len() as u64 == limit => Some(SCOutputEventPage::cursor(last)),
+            _ => None,
+        };
+        Ok(SCOutputEventPage {
+            events,
+            next_cursor,
+        })
+    }
+
     async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         crate::wrong_api::<Vec<IpAddr>>()
     }
@@ -1033,26 +1582,261 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_pool_denylist(&self) -> RpcResult<Vec<Address>> {
+        crate::wrong_api::<Vec<Address>>()
+    }
+
+    async fn node_add_to_pool_denylist(&self, _: Vec<Address>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_remove_from_pool_denylist(&self, _: Vec<Address>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
     async fn get_openrpc_spec(&self) -> RpcResult<Value> {
-        let openrpc_spec_path = self.0.api_settings.openrpc_spec_path.clone();
-        let openrpc: RpcResult<Value> = std::fs::read_to_string(openrpc_spec_path)
-            .map_err(|e| {
-                ApiError::InternalServerError(format!(
-                    "failed to read OpenRPC specification: {}",
-                    e
-                ))
+        serde_json::from_str(crate::OPENRPC_SPEC).map_err(|e| {
+            ApiError::InternalServerError(format!("failed to parse OpenRPC specification: {}", e))
                 .into()
+        })
+    }
+}
+
+/// Deserializes the operation datastore of a read-only execution request
+fn deserialize_operation_datastore(
+    bytes: &[u8],
+    api_settings: &APIConfig,
+) -> Result<Datastore, ApiError> {
+    DatastoreDeserializer::new(
+        api_settings.max_op_datastore_entry_count,
+        api_settings.max_op_datastore_key_length,
+        api_settings.max_op_datastore_value_length,
+    )
+    .deserialize::<DeserializeError>(bytes)
+    .map(|(_, datastore)| datastore)
+    .map_err(|e| ApiError::InconsistencyError(format!("Operation datastore error: {}", e)))
+}
+
+/// Read-only execution of the operation of a gas estimation request with `max_gas`
+fn gas_estimation_request(
+    req: &GasEstimationRequest,
+    operation_datastore: Option<Datastore>,
+    max_gas: u64,
+) -> ReadOnlyExecutionRequest {
+    let caller = ExecutionStackElement {
+        address: req.caller_address,
+        coins: Default::default(),
+        owned_addresses: vec![req.caller_address],
+        operation_datastore,
+        reentrancy_locked: false,
+    };
+    let (target, call_stack) = match &req.target {
+        GasEstimationTarget::ExecuteSC { bytecode, .. } => (
+            ReadOnlyExecutionTarget::BytecodeExecution(bytecode.clone()),
+            vec![caller],
+        ),
+        GasEstimationTarget::CallSC {
+            target_address,
+            target_function,
+            parameter,
+            coins,
+        } => (
+            ReadOnlyExecutionTarget::FunctionCall {
+                target_func: target_function.clone(),
+                target_addr: *target_address,
+                parameter: parameter.clone(),
+            },
+            vec![
+                caller,
+                ExecutionStackElement {
+                    address: *target_address,
+                    coins: *coins,
+                    owned_addresses: vec![*target_address],
+                    operation_datastore: None,
+                    reentrancy_locked: false,
+                },
+            ],
+        ),
+    };
+    ReadOnlyExecutionRequest {
+        max_gas,
+        call_stack,
+        target,
+        fee: Default::default(),
+        is_final: req.is_final,
+        ledger_overrides: Vec::new(),
+    }
+}
+
+/// Checks and translates the ledger overrides of a read-only execution request
+fn ledger_overrides(
+    overrides: BTreeMap<Address, ReadOnlyStateOverride>,
+    api_settings: &APIConfig,
+) -> Result<Vec<ReadOnlyLedgerOverride>, ApiError> {
+    if overrides.len() as u64 > api_settings.max_arguments {
+        return Err(ApiError::BadRequest("too many overridden addresses".into()));
+    }
+    overrides
+        .into_iter()
+        .map(|(address, state_override)| {
+            if state_override.datastore.len() as u64 > api_settings.max_op_datastore_entry_count {
+                return Err(ApiError::BadRequest(format!(
+                    "too many datastore entries overridden for address {}",
+                    address
+                )));
+            }
+            for (key, value) in &state_override.datastore {
+                if key.len() > api_settings.max_op_datastore_key_length as usize
+                    || value.len() as u64 > api_settings.max_datastore_value_length
+                {
+                    return Err(ApiError::BadRequest(format!(
+                        "overridden datastore entry too large for address {}",
+                        address
+                    )));
+                }
+            }
+            Ok(ReadOnlyLedgerOverride {
+                address,
+                balance: state_override.balance,
+                bytecode: state_override.bytecode.map(Bytecode),
+                datastore: state_override.datastore.into_iter().collect(),
             })
-            .and_then(|openrpc_str| {
-                serde_json::from_str(&openrpc_str).map_err(|e| {
-                    ApiError::InternalServerError(format!(
-                        "failed to parse OpenRPC specification: {}",
-                        e
-                    ))
-                    .into()
-                })
-            });
+        })
+        .collect()
+}
+
+/// Parses a cursor returned by `get_operations_by_address` (see `AddressOperationInfo::cursor`)
+fn parse_operation_cursor(cursor: &str) -> Result<(Slot, OperationId), ApiError> {
+    let invalid = || ApiError::BadRequest(format!("invalid operation cursor: {}", cursor));
+    let mut parts = cursor.splitn(3, ':');
+    let (Some(period), Some(thread), Some(op_id)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    Ok((
+        Slot::new(
+            period.parse().map_err(|_| invalid())?,
+            thread.parse().map_err(|_| invalid())?,
+        ),
+        OperationId::from_str(op_id).map_err(|_| invalid())?,
+    ))
+}
+
+/// Parses a cursor returned by `get_sc_output_events` (see `SCOutputEventPage::cursor`)
+fn parse_event_cursor(cursor: &str) -> Result<(Slot, u64), ApiError> {
+    let invalid = || ApiError::BadRequest(format!("invalid event cursor: {}", cursor));
+    let mut parts = cursor.splitn(3, ':');
+    let (Some(period), Some(thread), Some(index)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    Ok((
+        Slot::new(
+            period.parse().map_err(|_| invalid())?,
+            thread.parse().map_err(|_| invalid())?,
+        ),
+        index.parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// Gets the block of the blockclique, or the final block, at a slot
+fn slot_block(
+    consensus_controller: &dyn ConsensusController,
+    storage: &Storage,
+    slot: Slot,
+    options: &SlotBlockOptions,
+) -> Option<SlotBlock> {
+    let block_id = consensus_controller.get_blockclique_block_at_slot(slot)?;
+    let is_final = consensus_controller.get_block_statuses(&[block_id]).first()
+        == Some(&BlockGraphStatus::Final);
+    if options.final_only && !is_final {
+        return None;
+    }
+    let blocks = storage.read_blocks();
+    let block = blocks.get(&block_id)?;
+    let operations = options.filled.then(|| {
+        let operations = storage.read_operations();
+        block
+            .content
+            .operations
+            .iter()
+            .map(|op_id| (*op_id, operations.get(op_id).cloned()))
+            .collect()
+    });
+    Some(SlotBlock {
+        id: block_id,
+        is_final,
+        header: block.content.header.clone(),
+        operations,
+    })
+}
+
+/// Collects the fees of the operations included in the blocks kept in the graph
+fn recent_included_fees(
+    consensus_controller: &dyn ConsensusController,
+    storage: &Storage,
+) -> Result<Vec<Amount>, ApiError> {
+    let graph = consensus_controller
+        .get_block_graph_status(None, None)
+        .map_err(ApiError::ConsensusError)?;
+    let blocks = storage.read_blocks();
+    let operations = storage.read_operations();
+    Ok(graph
+        .active_blocks
+        .keys()
+        .filter_map(|block_id| blocks.get(block_id))
+        .flat_map(|block| block.content.operations.iter())
+        .filter_map(|op_id| operations.get(op_id))
+        .map(|op| op.content.fee)
+        .collect())
+}
 
-        openrpc
+/// Computes the headline chain statistics from the consensus and execution stats,
+/// the pool fee percentiles (10th, 50th and 90th) and the latest slot
+fn chain_stats(
+    consensus_stats: &ConsensusStats,
+    execution_stats: &ExecutionStats,
+    fee_percentiles: Vec<Amount>,
+    last_slot: Option<Slot>,
+    thread_count: u8,
+) -> ChainStats {
+    let window_secs = consensus_stats
+        .end_timespan
+        .saturating_sub(consensus_stats.start_timespan)
+        .to_millis() as f64
+        / 1000.0;
+    let block_production_rate = if window_secs > 0.0 {
+        consensus_stats.final_block_count as f64 / window_secs
+    } else {
+        0.0
+    };
+    let average_operations_per_block = if execution_stats.final_block_count > 0 {
+        execution_stats.final_executed_operations_count as f64
+            / execution_stats.final_block_count as f64
+    } else {
+        0.0
+    };
+    let operation_fee_percentiles = match fee_percentiles[..] {
+        [p10, p50, p90] => Some(FeePercentiles { p10, p50, p90 }),
+        _ => None,
+    };
+
+    // the execution cursor can be ahead of the latest slot if the clock is slightly behind
+    let last_slot = last_slot.unwrap_or_else(|| Slot::new(0, 0));
+    let cursor = execution_stats.active_cursor;
+    let execution_backlog = last_slot.slots_since(&cursor, thread_count).unwrap_or(0);
+    let genesis = Slot::new(0, 0);
+    let bootstrap_progress = match (
+        cursor.slots_since(&genesis, thread_count),
+        last_slot.slots_since(&genesis, thread_count),
+    ) {
+        (Ok(executed), Ok(total)) if total > executed => executed as f64 * 100.0 / total as f64,
+        _ => 100.0,
+    };
+
+    ChainStats {
+        block_production_rate,
+        average_operations_per_block,
+        operation_fee_percentiles,
+        execution_backlog,
+        bootstrap_progress,
     }
 }
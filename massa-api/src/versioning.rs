@@ -0,0 +1,141 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Versioning of the JSON-RPC API.
+//!
+//! Methods whose response shape changes in a breaking way are served under the `v2_` namespace
+//! (for example `v2_get_blocks`), while the original v1 method keeps working unchanged.
+//! The v1 methods superseded this way are listed in `DEPRECATED_METHODS`:
+//! they are flagged `deprecated` in the OpenRPC document, and the HTTP responses to requests
+//! calling them carry a `Deprecation: true` header along with the replacement methods.
+
+use futures::future::BoxFuture;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_LENGTH};
+use hyper::{Body, Method, Request, Response};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// A v1 method superseded by a method of a later version
+pub(crate) struct DeprecatedMethod {
+    /// name of the deprecated method
+    pub name: &'static str,
+    /// name of the method replacing it
+    pub replacement: &'static str,
+}
+
+/// Deprecated methods, kept for backward compatibility
+pub(crate) const DEPRECATED_METHODS: &[DeprecatedMethod] = &[DeprecatedMethod {
+    name: "get_blocks",
+    replacement: "v2_get_blocks",
+}];
+
+/// Header set to `true` on the responses to requests calling deprecated methods
+pub(crate) const DEPRECATION_HEADER: &str = "deprecation";
+/// Header listing the deprecated methods called by a request, as `method=replacement` pairs
+pub(crate) const DEPRECATED_METHODS_HEADER: &str = "x-massa-deprecated-methods";
+
+/// Layer signaling the calls to deprecated methods in the HTTP response headers
+#[derive(Clone)]
+pub(crate) struct DeprecationLayer {
+    /// requests with a larger body are not inspected
+    max_body_size: u32,
+}
+
+impl DeprecationLayer {
+    pub(crate) fn new(max_body_size: u32) -> Self {
+        DeprecationLayer { max_body_size }
+    }
+}
+
+impl<S> Layer<S> for DeprecationLayer {
+    type Service = DeprecationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeprecationService {
+            inner,
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+/// Service signaling the calls to deprecated methods, see `DeprecationLayer`
+#[derive(Clone)]
+pub(crate) struct DeprecationService<S> {
+    inner: S,
+    max_body_size: u32,
+}
+
+impl<S> Service<Request<Body>> for DeprecationService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: From<hyper::Error>,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // use the service that was polled ready, leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        // only plain HTTP calls with a known and acceptable body size are inspected
+        let body_size = request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if request.method() != Method::POST
+            || !body_size.map_or(false, |size| size <= self.max_body_size as u64)
+        {
+            return Box::pin(inner.call(request));
+        }
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let deprecated = called_deprecated_methods(&body);
+            let mut response = inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await?;
+            if !deprecated.is_empty() {
+                let replacements = deprecated
+                    .iter()
+                    .map(|method| format!("{}={}", method.name, method.replacement))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let headers = response.headers_mut();
+                headers.insert(
+                    HeaderName::from_static(DEPRECATION_HEADER),
+                    HeaderValue::from_static("true"),
+                );
+                if let Ok(value) = HeaderValue::from_str(&replacements) {
+                    headers.insert(HeaderName::from_static(DEPRECATED_METHODS_HEADER), value);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Lists the deprecated methods called by a JSON-RPC request or batch of requests
+fn called_deprecated_methods(body: &[u8]) -> Vec<&'static DeprecatedMethod> {
+    let Ok(request) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return Vec::new();
+    };
+    let calls = match &request {
+        serde_json::Value::Array(calls) => calls.iter().collect(),
+        call => vec![call],
+    };
+    DEPRECATED_METHODS
+        .iter()
+        .filter(|method| {
+            calls
+                .iter()
+                .any(|call| call.get("method").and_then(|name| name.as_str()) == Some(method.name))
+        })
+        .collect()
+}
@@ -1,12 +1,16 @@
 mod client;
+#[cfg(feature = "server")]
 mod server;
+mod throttle;
 use std::{
     io::{self, ErrorKind},
     time::{Duration, Instant},
 };
 
 pub(crate) use client::*;
+#[cfg(feature = "server")]
 pub(crate) use server::*;
+pub(crate) use throttle::*;
 
 trait BindingReadExact: io::Read {
     /// similar to std::io::Read::read_exact, but with a timeout that is function-global instead of per-individual-read
@@ -1,10 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::bindings::BindingReadExact;
+use crate::bindings::{BandwidthLimits, BindingReadExact, Throttle};
 use crate::error::BootstrapError;
 use crate::messages::{
-    BootstrapClientMessage, BootstrapClientMessageSerializer, BootstrapServerMessage,
-    BootstrapServerMessageDeserializer,
+    decompress_message, BootstrapClientMessage, BootstrapClientMessageSerializer,
+    BootstrapServerMessage, BootstrapServerMessageDeserializer, HANDSHAKE_ZSTD_FLAG,
 };
 use crate::settings::BootstrapClientConfig;
 use massa_hash::Hash;
@@ -17,12 +17,13 @@ use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::{PublicKey, Signature};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use std::time::Instant;
-use std::{io::Write, net::TcpStream, time::Duration};
+use std::{net::TcpStream, time::Duration};
 
 /// Bootstrap client binder
 pub struct BootstrapClientBinder {
     remote_pubkey: PublicKey,
     duplex: TcpStream,
+    throttle: Throttle,
     prev_message: Option<Hash>,
     version_serializer: VersionSerializer,
     cfg: BootstrapClientConfig,
@@ -46,25 +47,35 @@ impl BootstrapClientBinder {
         BootstrapClientBinder {
             remote_pubkey,
             duplex,
+            throttle: Throttle::new(cfg.max_bytes_read_write),
             prev_message: None,
             version_serializer: VersionSerializer::new(),
             cfg,
         }
     }
 
+    /// Sets the bandwidth limits shared with the other connections of the client
+    pub(crate) fn set_global_limits(&mut self, limits: BandwidthLimits) {
+        self.throttle.set_global_limits(limits);
+    }
+
     /// Performs a handshake. Should be called after connection
     /// NOT cancel-safe
     pub fn handshake(&mut self, version: Version) -> Result<(), BootstrapError> {
-        // send version and randomn bytes
+        // send version, flags and randomn bytes
         let msg_hash = {
             let mut version_ser = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_ser)?;
             let mut version_random_bytes =
-                vec![0u8; version_ser.len() + self.cfg.randomness_size_bytes];
+                vec![0u8; version_ser.len() + 1 + self.cfg.randomness_size_bytes];
             version_random_bytes[..version_ser.len()].clone_from_slice(&version_ser);
-            StdRng::from_entropy().fill_bytes(&mut version_random_bytes[version_ser.len()..]);
-            self.duplex.write_all(&version_random_bytes)?;
+            if self.cfg.stream_compression {
+                version_random_bytes[version_ser.len()] = HANDSHAKE_ZSTD_FLAG;
+            }
+            StdRng::from_entropy().fill_bytes(&mut version_random_bytes[version_ser.len() + 1..]);
+            self.throttle
+                .write_all(&mut self.duplex, &version_random_bytes)?;
             Hash::compute_from(&version_random_bytes)
         };
 
@@ -135,7 +146,23 @@ impl BootstrapClientBinder {
                 msg
             }
         };
-        Ok(message)
+
+        // the state and consensus parts may come compressed
+        match message {
+            BootstrapServerMessage::Compressed { data } => {
+                let msg_bytes = decompress_message(&data)?;
+                let (_, msg) = message_deserializer
+                    .deserialize::<DeserializeError>(&msg_bytes)
+                    .map_err(|err| BootstrapError::DeserializeError(format!("{}", err)))?;
+                if let BootstrapServerMessage::Compressed { .. } = msg {
+                    return Err(BootstrapError::DeserializeError(
+                        "nested compressed message".to_string(),
+                    ));
+                }
+                Ok(msg)
+            }
+            message => Ok(message),
+        }
     }
 
     // TODO: use a proper (de)serializer: https://github.com/massalabs/massa/pull/3745#discussion_r1169733161
@@ -180,7 +207,7 @@ impl BootstrapClientBinder {
         write_buf.extend(&msg_bytes);
 
         // And send it off
-        self.duplex.write_all(&write_buf)?;
+        self.throttle.write_all(&mut self.duplex, &write_buf)?;
         Ok(())
     }
 
@@ -210,6 +237,8 @@ impl crate::bindings::BindingReadExact for BootstrapClientBinder {
 
 impl std::io::Read for BootstrapClientBinder {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
-        self.duplex.read(buf)
+        let count = self.duplex.read(buf)?;
+        self.throttle.downloaded(count);
+        Ok(count)
     }
 }
@@ -1,10 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::bindings::BindingReadExact;
+use crate::bindings::{BandwidthLimits, BindingReadExact, Throttle};
 use crate::error::BootstrapError;
 use crate::messages::{
-    BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapServerMessage,
-    BootstrapServerMessageSerializer,
+    compress_message, BootstrapClientMessage, BootstrapClientMessageDeserializer,
+    BootstrapServerMessage, BootstrapServerMessageSerializer, HANDSHAKE_ZSTD_FLAG,
 };
 use crate::settings::BootstrapSrvBindCfg;
 use massa_hash::Hash;
@@ -19,7 +19,7 @@ use std::io;
 use std::time::Instant;
 use std::{
     convert::TryInto,
-    io::{ErrorKind, Read, Write},
+    io::{ErrorKind, Read},
     net::{SocketAddr, TcpStream},
     thread,
     time::Duration,
@@ -40,8 +40,12 @@ pub struct BootstrapServerBinder {
     max_datastore_key_length: u8,
     randomness_size_bytes: usize,
     local_keypair: KeyPair,
-    // TODO: Reintroduce bandwidth limits
     duplex: TcpStream,
+    throttle: Throttle,
+    /// compression enabled on this server
+    stream_compression: bool,
+    /// true once the client announced in its handshake that it accepts compressed messages
+    compress_parts: bool,
     prev_message: Option<Hash>,
     version_serializer: VersionSerializer,
     version_deserializer: VersionDeserializer,
@@ -58,18 +62,24 @@ impl BootstrapServerBinder {
     #[allow(clippy::too_many_arguments)]
     pub fn new(duplex: TcpStream, local_keypair: KeyPair, cfg: BootstrapSrvBindCfg) -> Self {
         let BootstrapSrvBindCfg {
-            // TODO: Reintroduce bandwidth limits
-            max_bytes_read_write: _limit,
+            max_bytes_read_write,
+            min_session_bytes_per_second,
+            stream_compression,
             thread_count,
             max_datastore_key_length,
             randomness_size_bytes,
             consensus_bootstrap_part_size,
             write_error_timeout,
         } = cfg;
+        let mut throttle = Throttle::new(max_bytes_read_write);
+        throttle.set_min_throughput(min_session_bytes_per_second);
         BootstrapServerBinder {
             max_consensus_block_ids: consensus_bootstrap_part_size,
             local_keypair,
             duplex,
+            throttle,
+            stream_compression,
+            compress_parts: false,
             prev_message: None,
             thread_count,
             max_datastore_key_length,
@@ -79,6 +89,12 @@ impl BootstrapServerBinder {
             write_error_timeout,
         }
     }
+
+    /// Sets the bandwidth limits shared with the other sessions of the server
+    pub(crate) fn set_global_limits(&mut self, limits: BandwidthLimits) {
+        self.throttle.set_global_limits(limits);
+    }
+
     /// Performs a handshake. Should be called after connection
     /// MUST always be followed by a send of the `BootstrapMessage::BootstrapTime`
     pub fn handshake_timeout(
@@ -86,12 +102,12 @@ impl BootstrapServerBinder {
         version: Version,
         duration: Option<Duration>,
     ) -> Result<(), BootstrapError> {
-        // read version and random bytes, send signature
+        // read version, flags and random bytes, send signature
         let msg_hash = {
             let mut version_bytes = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_bytes)?;
-            let mut msg_bytes = vec![0u8; version_bytes.len() + self.randomness_size_bytes];
+            let mut msg_bytes = vec![0u8; version_bytes.len() + 1 + self.randomness_size_bytes];
             self.duplex.set_read_timeout(duration)?;
             self.duplex.read_exact(&mut msg_bytes)?;
             let (_, received_version) = self
@@ -101,6 +117,8 @@ impl BootstrapServerBinder {
             if !received_version.is_compatible(&version) {
                 return Err(BootstrapError::IncompatibleVersionError(format!("Received a bad incompatible version in handshake. (excepted: {}, received: {})", version, received_version)));
             }
+            self.compress_parts = self.stream_compression
+                && msg_bytes[version_bytes.len()] & HANDSHAKE_ZSTD_FLAG != 0;
             Hash::compute_from(&msg_bytes)
         };
 
@@ -185,6 +203,22 @@ impl BootstrapServerBinder {
         // serialize the message to bytes
         let mut msg_bytes = Vec::new();
         BootstrapServerMessageSerializer::new().serialize(&msg, &mut msg_bytes)?;
+
+        // compress the state and consensus parts if the client accepts it
+        if self.compress_parts
+            && matches!(
+                msg,
+                BootstrapServerMessage::BootstrapPart { .. }
+                    | BootstrapServerMessage::StateRangePart { .. }
+            )
+        {
+            let data = compress_message(&msg_bytes)?;
+            if data.len() < msg_bytes.len() {
+                msg_bytes.clear();
+                BootstrapServerMessageSerializer::new()
+                    .serialize(&BootstrapServerMessage::Compressed { data }, &mut msg_bytes)?;
+            }
+        }
         let msg_len: u32 = msg_bytes.len().try_into().map_err(|e| {
             BootstrapError::GeneralError(format!("bootstrap message too large to encode: {}", e))
         })?;
@@ -212,7 +246,7 @@ impl BootstrapServerBinder {
 
         // send the data
         self.duplex.set_write_timeout(duration)?;
-        self.duplex.write_all(&stream_data)?;
+        self.throttle.write_all(&mut self.duplex, &stream_data)?;
 
         // update prev sig
         self.prev_message = Some(Hash::compute_from(&sig.to_bytes()));
@@ -307,7 +341,9 @@ impl BootstrapServerBinder {
 
 impl io::Read for BootstrapServerBinder {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.duplex.read(buf)
+        let count = self.duplex.read(buf)?;
+        self.throttle.downloaded(count);
+        Ok(count)
     }
 }
 
@@ -0,0 +1,204 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bandwidth limits of the bootstrap connections.
+//!
+//! Each connection is limited on its own, and all the connections of the server (or of the client)
+//! share global limits. Transfers are paced by token buckets allowing bursts of one second of transfer:
+//! writes wait before sending, reads wait after receiving, so that TCP slows the remote sender down.
+//!
+//! A connection can also require a minimum throughput from the remote side, so that a client reading
+//! very slowly cannot hold a session of the server: it is measured over the time spent blocked in writes only,
+//! so that the pacing and the preparation of the messages do not count.
+
+use parking_lot::Mutex;
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Size of the chunks written between two waits, to smooth the upload of large messages
+const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Time spent blocked in writes before the minimum throughput is enforced
+const MIN_THROUGHPUT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Token bucket limiting a transfer rate
+struct RateLimiter {
+    /// bytes per second, None if unlimited
+    rate: Option<f64>,
+    /// bytes that can be transferred without waiting (negative when in debt), and time of the last update
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter of `rate` bytes per second, unlimited if `rate` is not a positive finite number
+    fn new(rate: f64) -> Self {
+        let rate = (rate.is_finite() && rate > 0.0).then_some(rate);
+        RateLimiter {
+            rate,
+            state: Mutex::new((rate.unwrap_or_default(), Instant::now())),
+        }
+    }
+
+    /// Accounts for `amount` transferred bytes, and returns how long to wait to respect the rate
+    fn consume(&self, amount: usize) -> Duration {
+        let Some(rate) = self.rate else {
+            return Duration::ZERO;
+        };
+        let mut state = self.state.lock();
+        let now = Instant::now();
+        let (available, last_update) = *state;
+        let available = (available + now.duration_since(last_update).as_secs_f64() * rate)
+            .min(rate)
+            - amount as f64;
+        *state = (available, now);
+        if available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-available / rate)
+        }
+    }
+}
+
+/// Upload and download limits, in bytes per second
+#[derive(Clone)]
+pub(crate) struct BandwidthLimits {
+    upload: Arc<RateLimiter>,
+    download: Arc<RateLimiter>,
+    /// bytes received through these limits
+    received: Arc<AtomicU64>,
+}
+
+impl BandwidthLimits {
+    /// Creates limits of `upload_rate` and `download_rate` bytes per second, 0 or infinity meaning unlimited
+    pub(crate) fn new(upload_rate: f64, download_rate: f64) -> Self {
+        BandwidthLimits {
+            upload: Arc::new(RateLimiter::new(upload_rate)),
+            download: Arc::new(RateLimiter::new(download_rate)),
+            received: Default::default(),
+        }
+    }
+
+    /// Returns the number of bytes received through these limits
+    pub(crate) fn received_bytes(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    /// Creates limits that never wait
+    pub(crate) fn unlimited() -> Self {
+        BandwidthLimits::new(f64::INFINITY, f64::INFINITY)
+    }
+}
+
+/// Minimum rate at which the remote side must read what is written to it
+struct MinThroughput {
+    /// bytes per second
+    rate: f64,
+    /// bytes written, and time spent blocked writing them
+    state: Mutex<(u64, Duration)>,
+}
+
+impl MinThroughput {
+    /// Accounts for `amount` bytes written in `elapsed`, and fails if the remote side is too slow
+    fn written(&self, amount: usize, elapsed: Duration) -> io::Result<()> {
+        let mut state = self.state.lock();
+        state.0 += amount as u64;
+        state.1 += elapsed;
+        let (written, blocked) = *state;
+        if blocked >= MIN_THROUGHPUT_GRACE_PERIOD
+            && (written as f64) < self.rate * blocked.as_secs_f64()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "remote side too slow: {} bytes read in {:.1} s, {} bytes per second required",
+                    written,
+                    blocked.as_secs_f64(),
+                    self.rate
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Limits of a connection: its own limits, along with the limits shared with the other connections
+pub(crate) struct Throttle {
+    session: BandwidthLimits,
+    global: BandwidthLimits,
+    min_throughput: Option<MinThroughput>,
+}
+
+impl Throttle {
+    /// Creates the throttle of a connection limited to `session_rate` bytes per second in each direction
+    pub(crate) fn new(session_rate: f64) -> Self {
+        Throttle {
+            session: BandwidthLimits::new(session_rate, session_rate),
+            global: BandwidthLimits::unlimited(),
+            min_throughput: None,
+        }
+    }
+
+    /// Requires the remote side to read at least `rate` bytes per second, 0 or infinity meaning no minimum
+    pub(crate) fn set_min_throughput(&mut self, rate: f64) {
+        self.min_throughput = (rate.is_finite() && rate > 0.0).then(|| MinThroughput {
+            rate,
+            state: Mutex::new((0, Duration::ZERO)),
+        });
+    }
+
+    /// Sets the limits shared with the other connections
+    pub(crate) fn set_global_limits(&mut self, global: BandwidthLimits) {
+        self.global = global;
+    }
+
+    /// Waits as needed after receiving `amount` bytes
+    pub(crate) fn downloaded(&self, amount: usize) {
+        self.global
+            .received
+            .fetch_add(amount as u64, Ordering::Relaxed);
+        let wait = std::cmp::max(
+            self.session.download.consume(amount),
+            self.global.download.consume(amount),
+        );
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Writes all of `data`, waiting as needed before each chunk
+    pub(crate) fn write_all(&self, writer: &mut impl Write, data: &[u8]) -> io::Result<()> {
+        for chunk in data.chunks(WRITE_CHUNK_SIZE) {
+            let wait = std::cmp::max(
+                self.session.upload.consume(chunk.len()),
+                self.global.upload.consume(chunk.len()),
+            );
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
+            }
+            let Some(min_throughput) = &self.min_throughput else {
+                writer.write_all(chunk)?;
+                continue;
+            };
+            // each write returns at the latest after the write timeout, so that a slow remote side is noticed
+            let mut remaining = chunk;
+            while !remaining.is_empty() {
+                let start = Instant::now();
+                match writer.write(remaining) {
+                    Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                    Ok(n) => {
+                        remaining = &remaining[n..];
+                        min_throughput.written(n, start.elapsed())?;
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,16 @@
+use crossbeam::channel::Sender;
 use humantime::format_duration;
 use massa_db::{DBBatch, CHANGE_ID_DESER_ERROR};
 use massa_final_state::{FinalState, FinalStateError};
 use massa_logging::massa_trace;
-use massa_models::{node::NodeId, slot::Slot, streaming_step::StreamingStep, version::Version};
+use massa_models::{
+    node::NodeId,
+    slot::Slot,
+    stats::{BootstrapPhase, BootstrapStats},
+    streaming_step::StreamingStep,
+    version::Version,
+};
+use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{ComponentStateTypeId, MipInfo, MipState, StateAtError};
@@ -21,10 +29,22 @@ use std::{
 };
 use tracing::{debug, info, warn};
 
+pub(crate) mod attestation;
+mod light;
+pub(crate) mod parallel;
+mod progress;
+
+use attestation::attest_final_state;
+pub use light::{get_light_state, LightBootstrapState};
+use progress::ProgressReporter;
+
 use crate::{
-    bindings::BootstrapClientBinder,
+    bindings::{BandwidthLimits, BootstrapClientBinder},
     error::BootstrapError,
-    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    messages::{
+        BootstrapClientMessage, BootstrapClientMessageDeserializer,
+        BootstrapClientMessageSerializer, BootstrapServerMessage,
+    },
     settings::IpType,
     BootstrapConfig, GlobalBootstrapState,
 };
@@ -69,6 +89,7 @@ fn stream_final_state_and_consensus(
     client: &mut BootstrapClientBinder,
     next_bootstrap_message: &mut BootstrapClientMessage,
     global_bootstrap_state: &mut GlobalBootstrapState,
+    progress: &ProgressReporter,
 ) -> Result<(), BootstrapError> {
     if let BootstrapClientMessage::AskBootstrapPart { .. } = &next_bootstrap_message {
         client.send_timeout(
@@ -146,6 +167,17 @@ fn stream_final_state_and_consensus(
                         "client final state bootstrap cursors: {:?}",
                         next_bootstrap_message
                     );
+
+                    // Persist the cursors in case the node stops before the end of the bootstrap
+                    save_bootstrap_checkpoint(cfg, next_bootstrap_message)?;
+
+                    // The consensus graph is streamed once the final state is complete
+                    progress.set_phase(if state_part_finished(next_bootstrap_message) {
+                        BootstrapPhase::DownloadingConsensus
+                    } else {
+                        BootstrapPhase::DownloadingState
+                    });
+                    progress.part_received(slot, None);
                 }
                 BootstrapServerMessage::BootstrapFinished => {
                     info!("State bootstrap complete");
@@ -164,6 +196,7 @@ fn stream_final_state_and_consensus(
 
                     // Compute the db hash
                     info!("Computing the db hash");
+                    progress.set_phase(BootstrapPhase::ComputingHash);
                     let slot = guard
                         .db
                         .read()
@@ -181,10 +214,12 @@ fn stream_final_state_and_consensus(
                             )))
                         })?;
 
+                    remove_bootstrap_checkpoint(cfg);
                     return Ok(());
                 }
                 BootstrapServerMessage::SlotTooOld => {
                     info!("Slot is too old retry bootstrap from scratch");
+                    remove_bootstrap_checkpoint(cfg);
                     *next_bootstrap_message = BootstrapClientMessage::AskBootstrapPart {
                         last_slot: None,
                         last_state_step: StreamingStep::Started,
@@ -215,6 +250,14 @@ fn stream_final_state_and_consensus(
     }
 }
 
+/// Returns true if the final state part of a bootstrap request is complete
+fn state_part_finished(message: &BootstrapClientMessage) -> bool {
+    matches!(
+        message,
+        BootstrapClientMessage::AskBootstrapPart { last_state_step, .. } if last_state_step.finished()
+    )
+}
+
 /// Gets the state from a bootstrap server (internal private function)
 /// needs to be CANCELLABLE
 fn bootstrap_from_server(
@@ -223,9 +266,112 @@ fn bootstrap_from_server(
     next_bootstrap_message: &mut BootstrapClientMessage,
     global_bootstrap_state: &mut GlobalBootstrapState,
     our_version: Version,
+    progress: &ProgressReporter,
 ) -> Result<(), BootstrapError> {
     massa_trace!("bootstrap.lib.bootstrap_from_server", {});
 
+    handshake_with_server(cfg, client, our_version)?;
+
+    let write_timeout: std::time::Duration = cfg.write_timeout.into();
+    // Loop to ask data to the server depending on the last message we sent
+    loop {
+        match next_bootstrap_message {
+            BootstrapClientMessage::AskBootstrapPart { .. } => {
+                stream_final_state_and_consensus(
+                    cfg,
+                    client,
+                    next_bootstrap_message,
+                    global_bootstrap_state,
+                    progress,
+                )?;
+            }
+            BootstrapClientMessage::AskBootstrapPeers => {
+                progress.set_phase(BootstrapPhase::FetchingPeers);
+                let peers = match send_client_message(
+                    next_bootstrap_message,
+                    client,
+                    write_timeout,
+                    cfg.read_timeout.into(),
+                    "ask bootstrap peers timed out",
+                )? {
+                    BootstrapServerMessage::BootstrapPeers { peers } => peers,
+                    BootstrapServerMessage::BootstrapError { error } => {
+                        return Err(BootstrapError::ReceivedError(error))
+                    }
+                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+                };
+                global_bootstrap_state.peers = Some(peers);
+                *next_bootstrap_message = BootstrapClientMessage::BootstrapSuccess;
+            }
+            BootstrapClientMessage::AskResync { last_slot, .. } => {
+                let last_slot = *last_slot;
+                let resync_slot = match send_client_message(
+                    next_bootstrap_message,
+                    client,
+                    write_timeout,
+                    cfg.read_timeout.into(),
+                    "ask re-sync timed out",
+                )? {
+                    BootstrapServerMessage::ResyncStart { slot } => slot,
+                    BootstrapServerMessage::BootstrapError { error } => {
+                        return Err(BootstrapError::ReceivedError(error))
+                    }
+                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+                };
+                *next_bootstrap_message = match resync_slot {
+                    Some(slot) if slot == last_slot => {
+                        info!("Re-syncing the local final state from slot {}", slot);
+                        BootstrapClientMessage::AskBootstrapPart {
+                            last_slot: Some(slot),
+                            last_state_step: StreamingStep::Finished(None),
+                            last_versioning_step: StreamingStep::Finished(None),
+                            last_consensus_step: StreamingStep::Started,
+                            send_last_start_period: true,
+                        }
+                    }
+                    _ => {
+                        warn!(
+                            "The bootstrap server cannot re-sync the local final state at slot {}, starting a full bootstrap",
+                            last_slot
+                        );
+                        global_bootstrap_state.final_state.write().reset();
+                        BootstrapClientMessage::AskBootstrapPart {
+                            last_slot: None,
+                            last_state_step: StreamingStep::Started,
+                            last_versioning_step: StreamingStep::Started,
+                            last_consensus_step: StreamingStep::Started,
+                            send_last_start_period: true,
+                        }
+                    }
+                };
+            }
+            BootstrapClientMessage::BootstrapSuccess => {
+                client.send_timeout(next_bootstrap_message, Some(write_timeout))?;
+                break;
+            }
+            BootstrapClientMessage::BootstrapError { error: _ } => {
+                panic!("The next message to send shouldn't be BootstrapError");
+            }
+            BootstrapClientMessage::AskStateSplit { .. }
+            | BootstrapClientMessage::AskStateRange { .. }
+            | BootstrapClientMessage::AskLightState
+            | BootstrapClientMessage::AskStateHash { .. } => {
+                panic!(
+                    "The next message to send shouldn't be a state range, light state or state hash request"
+                );
+            }
+        };
+    }
+    info!("Successful bootstrap");
+    Ok(())
+}
+
+/// Checks that the server did not refuse the connection, then syncs the versions and the clocks
+fn handshake_with_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match client.next_timeout(Some(cfg.read_error_timeout.to_duration())) {
@@ -303,46 +449,6 @@ fn bootstrap_from_server(
         );
         return Err(BootstrapError::ClockError(message));
     }
-
-    let write_timeout: std::time::Duration = cfg.write_timeout.into();
-    // Loop to ask data to the server depending on the last message we sent
-    loop {
-        match next_bootstrap_message {
-            BootstrapClientMessage::AskBootstrapPart { .. } => {
-                stream_final_state_and_consensus(
-                    cfg,
-                    client,
-                    next_bootstrap_message,
-                    global_bootstrap_state,
-                )?;
-            }
-            BootstrapClientMessage::AskBootstrapPeers => {
-                let peers = match send_client_message(
-                    next_bootstrap_message,
-                    client,
-                    write_timeout,
-                    cfg.read_timeout.into(),
-                    "ask bootstrap peers timed out",
-                )? {
-                    BootstrapServerMessage::BootstrapPeers { peers } => peers,
-                    BootstrapServerMessage::BootstrapError { error } => {
-                        return Err(BootstrapError::ReceivedError(error))
-                    }
-                    other => return Err(BootstrapError::UnexpectedServerMessage(other)),
-                };
-                global_bootstrap_state.peers = Some(peers);
-                *next_bootstrap_message = BootstrapClientMessage::BootstrapSuccess;
-            }
-            BootstrapClientMessage::BootstrapSuccess => {
-                client.send_timeout(next_bootstrap_message, Some(write_timeout))?;
-                break;
-            }
-            BootstrapClientMessage::BootstrapError { error: _ } => {
-                panic!("The next message to send shouldn't be BootstrapError");
-            }
-        };
-    }
-    info!("Successful bootstrap");
     Ok(())
 }
 
@@ -370,14 +476,13 @@ fn connect_to_server(
     bootstrap_config: &BootstrapConfig,
     addr: &SocketAddr,
     pub_key: &PublicKey,
+    bandwidth_limits: &BandwidthLimits,
 ) -> Result<BootstrapClientBinder, BootstrapError> {
     let socket = connector.connect_timeout(*addr, Some(bootstrap_config.connect_timeout))?;
     socket.set_nonblocking(false)?;
-    Ok(BootstrapClientBinder::new(
-        socket,
-        *pub_key,
-        bootstrap_config.into(),
-    ))
+    let mut client = BootstrapClientBinder::new(socket, *pub_key, bootstrap_config.into());
+    client.set_global_limits(bandwidth_limits.clone());
+    Ok(client)
 }
 
 fn filter_bootstrap_list(
@@ -418,6 +523,7 @@ pub fn get_state(
     genesis_timestamp: MassaTime,
     end_timestamp: Option<MassaTime>,
     restart_from_snapshot_at_period: Option<u64>,
+    progress_sender: Option<Sender<BootstrapStats>>,
     interupted: Arc<(Mutex<bool>, Condvar)>,
 ) -> Result<GlobalBootstrapState, BootstrapError> {
     massa_trace!("bootstrap.lib.get_state", {});
@@ -435,6 +541,11 @@ pub fn get_state(
         {
             let mut final_state_guard = final_state.write();
 
+            // the local final state kept for a differential bootstrap is not relevant before genesis
+            if bootstrap_config.differential_sync {
+                final_state_guard.reset();
+            }
+
             let only_use_xor = final_state_guard.get_only_use_xor(&Slot::new(0, 31));
 
             if !bootstrap_config.keep_ledger {
@@ -473,14 +584,39 @@ pub fn get_state(
     // If the two conditions above are not verified, we need to bootstrap
     // we filter the bootstrap list to keep only the ip addresses we are compatible with
     let filtered_bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
+    // bandwidth limits shared by all the connections to the bootstrap servers
+    let bandwidth_limits = BandwidthLimits::new(
+        bootstrap_config.max_total_upload_bytes_per_second,
+        bootstrap_config.max_total_download_bytes_per_second,
+    );
+    let progress = ProgressReporter::new(progress_sender, bandwidth_limits.clone());
 
+    // servers that sent parts of the state, not asked to attest it
+    let mut state_sources: Vec<SocketAddr> = Vec::new();
     let mut next_bootstrap_message: BootstrapClientMessage =
-        BootstrapClientMessage::AskBootstrapPart {
-            last_slot: None,
-            last_state_step: StreamingStep::Started,
-            last_versioning_step: StreamingStep::Started,
-            last_consensus_step: StreamingStep::Started,
-            send_last_start_period: true,
+        match load_bootstrap_checkpoint(bootstrap_config) {
+            Some(message) => message,
+            None => get_differential_sync_start(bootstrap_config, &final_state)
+                .or_else(|| {
+                    get_parallel_download_start(
+                        bootstrap_config,
+                        &final_state,
+                        &mut connector,
+                        &filtered_bootstrap_list,
+                        &bandwidth_limits,
+                        version,
+                        &progress,
+                        &interupted,
+                        &mut state_sources,
+                    )
+                })
+                .unwrap_or(BootstrapClientMessage::AskBootstrapPart {
+                    last_slot: None,
+                    last_state_step: StreamingStep::Started,
+                    last_versioning_step: StreamingStep::Started,
+                    last_consensus_step: StreamingStep::Started,
+                    send_last_start_period: true,
+                }),
         };
     let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
 
@@ -498,14 +634,20 @@ pub fn get_state(
                 }
             }
             info!("Start bootstrapping from {}", addr);
+            progress.set_server(Some(*addr));
+            progress.set_phase(BootstrapPhase::Connecting);
             match connect_to_server(
                 &mut connector,
                 bootstrap_config,
                 addr,
                 &node_id.get_public_key(),
+                &bandwidth_limits,
             ) {
                 Ok(mut client) => {
-                    match bootstrap_from_server(bootstrap_config, &mut client, &mut next_bootstrap_message, &mut global_bootstrap_state,version)
+                    if !state_sources.contains(addr) {
+                        state_sources.push(*addr);
+                    }
+                    match bootstrap_from_server(bootstrap_config, &mut client, &mut next_bootstrap_message, &mut global_bootstrap_state, version, &progress)
                       // cancellable
                     {
                         Err(BootstrapError::ReceivedError(error)) => warn!("Error received from bootstrap server: {}", error),
@@ -515,6 +657,18 @@ pub fn get_state(
                             let _ = client.send_timeout(&BootstrapClientMessage::BootstrapError { error: e.to_string() }, Some(bootstrap_config.write_error_timeout.into()));
                         }
                         Ok(()) => {
+                            progress.set_phase(BootstrapPhase::AttestingState);
+                            attest_final_state(
+                                bootstrap_config,
+                                &mut connector,
+                                &filtered_bootstrap_list,
+                                &state_sources,
+                                &bandwidth_limits,
+                                version,
+                                &global_bootstrap_state.final_state,
+                                &interupted,
+                            )?;
+                            progress.set_phase(BootstrapPhase::Finished);
                             return Ok(global_bootstrap_state)
                         }
                     }
@@ -560,6 +714,206 @@ pub fn get_state(
     }
 }
 
+/// Returns the first message of a differential bootstrap, negotiating with the server a re-sync from the last slot
+/// of the local final state. The server accepts it if the hash of the local final state is the one it had at that slot,
+/// and then only streams the final state changes made since. The consensus graph is streamed as in a full bootstrap.
+///
+/// Returns None, after resetting the local final state, if differential sync is disabled or the local final state is unusable.
+/// If the server refuses the re-sync, the client resets its final state and falls back to a full bootstrap.
+fn get_differential_sync_start(
+    bootstrap_config: &BootstrapConfig,
+    final_state: &Arc<RwLock<FinalState>>,
+) -> Option<BootstrapClientMessage> {
+    if !bootstrap_config.differential_sync {
+        return None;
+    }
+    let mut final_state_guard = final_state.write();
+    let local_state = {
+        let db = final_state_guard.db.read();
+        db.get_change_id().map(|slot| (slot, db.get_db_hash()))
+    };
+    match local_state {
+        Ok((slot, state_hash)) => {
+            info!(
+                "Local final state is at slot {}, trying a differential bootstrap",
+                slot
+            );
+            Some(BootstrapClientMessage::AskResync {
+                last_slot: slot,
+                state_hash,
+            })
+        }
+        Err(e) => {
+            warn!(
+                "Cannot resume from the local final state ({}), starting a full bootstrap",
+                e
+            );
+            final_state_guard.reset();
+            None
+        }
+    }
+}
+
+/// Downloads the state from several bootstrap servers at once, then returns the first message of a bootstrap
+/// asking the server only for the state changes made since the download, along with the versioning state and the consensus graph.
+///
+/// The servers the ranges were downloaded from are added to `state_sources`. The part hashes of the ranges come from
+/// the sending servers, so the parallel download requires other servers to attest the whole state at the end of the bootstrap.
+///
+/// Returns None, after resetting the local final state, if the parallel download is disabled or did not complete.
+#[allow(clippy::too_many_arguments)]
+fn get_parallel_download_start(
+    bootstrap_config: &BootstrapConfig,
+    final_state: &Arc<RwLock<FinalState>>,
+    connector: &mut impl BSConnector,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    bandwidth_limits: &BandwidthLimits,
+    version: Version,
+    progress: &ProgressReporter,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+    state_sources: &mut Vec<SocketAddr>,
+) -> Option<BootstrapClientMessage> {
+    if bootstrap_config.parallel_download_servers < 2 {
+        return None;
+    }
+    if bootstrap_config.state_attestations == 0 {
+        warn!("The state is only downloaded in parallel when other servers attest it, starting a regular bootstrap");
+        return None;
+    }
+    match parallel::download_state(
+        bootstrap_config,
+        final_state,
+        connector,
+        bootstrap_list,
+        bandwidth_limits,
+        version,
+        progress,
+        interupted,
+    ) {
+        Ok((slot, servers)) => {
+            info!(
+                "State downloaded, fetching the changes made since slot {}",
+                slot
+            );
+            state_sources.extend(servers);
+            Some(BootstrapClientMessage::AskBootstrapPart {
+                last_slot: Some(slot),
+                last_state_step: StreamingStep::Finished(None),
+                last_versioning_step: StreamingStep::Started,
+                last_consensus_step: StreamingStep::Started,
+                send_last_start_period: true,
+            })
+        }
+        Err(e) => {
+            warn!(
+                "Parallel download of the state failed ({}), starting a regular bootstrap",
+                e
+            );
+            final_state.write().reset();
+            None
+        }
+    }
+}
+
+/// Writes the cursors of an ongoing bootstrap to the checkpoint file, so that an interrupted bootstrap
+/// resumes from the last received part instead of starting over.
+///
+/// The consensus blocks are kept in memory only: the checkpoint asks for them again from the start,
+/// along with the last start period. The file is replaced atomically to never leave a truncated checkpoint.
+fn save_bootstrap_checkpoint(
+    bootstrap_config: &BootstrapConfig,
+    message: &BootstrapClientMessage,
+) -> Result<(), BootstrapError> {
+    let BootstrapClientMessage::AskBootstrapPart {
+        last_slot,
+        last_state_step,
+        last_versioning_step,
+        ..
+    } = message
+    else {
+        return Ok(());
+    };
+    let checkpoint = BootstrapClientMessage::AskBootstrapPart {
+        last_slot: *last_slot,
+        last_state_step: last_state_step.clone(),
+        last_versioning_step: last_versioning_step.clone(),
+        last_consensus_step: StreamingStep::Started,
+        send_last_start_period: true,
+    };
+    let mut bytes = Vec::new();
+    BootstrapClientMessageSerializer::new().serialize(&checkpoint, &mut bytes)?;
+    let path = &bootstrap_config.checkpoint_path;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Reads the checkpoint left by an interrupted bootstrap, if any.
+///
+/// An unreadable checkpoint is removed, and the bootstrap starts as if there was none.
+fn load_bootstrap_checkpoint(bootstrap_config: &BootstrapConfig) -> Option<BootstrapClientMessage> {
+    let path = &bootstrap_config.checkpoint_path;
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!(
+                "Cannot read the bootstrap checkpoint {}: {}",
+                path.display(),
+                e
+            );
+            remove_bootstrap_checkpoint(bootstrap_config);
+            return None;
+        }
+    };
+    match BootstrapClientMessageDeserializer::new(
+        bootstrap_config.thread_count,
+        bootstrap_config.max_datastore_key_length,
+        bootstrap_config.max_consensus_block_ids,
+    )
+    .deserialize::<DeserializeError>(&bytes)
+    {
+        Ok((rest, message @ BootstrapClientMessage::AskBootstrapPart { .. }))
+            if rest.is_empty() =>
+        {
+            if let BootstrapClientMessage::AskBootstrapPart {
+                last_slot: Some(slot),
+                ..
+            } = &message
+            {
+                info!("Resuming the interrupted bootstrap from slot {}", slot);
+            }
+            Some(message)
+        }
+        _ => {
+            warn!(
+                "Invalid bootstrap checkpoint {}, starting a new bootstrap",
+                path.display()
+            );
+            remove_bootstrap_checkpoint(bootstrap_config);
+            None
+        }
+    }
+}
+
+/// Removes the bootstrap checkpoint, once the bootstrap is complete or has to start over
+fn remove_bootstrap_checkpoint(bootstrap_config: &BootstrapConfig) {
+    let path = &bootstrap_config.checkpoint_path;
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != io::ErrorKind::NotFound {
+            warn!(
+                "Cannot remove the bootstrap checkpoint {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
 fn get_bootstrap_list_iter(
     bootstrap_config: &BootstrapConfig,
 ) -> Result<Vec<(SocketAddr, NodeId)>, BootstrapError> {
@@ -0,0 +1,257 @@
+//! Attestation of a bootstrapped final state by other servers of the bootstrap list.
+//!
+//! The hash of the final state is recomputed locally at the end of the bootstrap. The other servers are then asked for
+//! the hash of their own final state at the same slot, kept in the hash history of their database for a few slots,
+//! so that a single malicious server cannot feed a forged state: the state is accepted once a quorum of servers attested it,
+//! more servers than the ones attesting another hash, and the bootstrap fails if the quorum cannot be reached anymore
+//! or after a bounded number of attempts.
+//! Only the servers that did not send any part of the state are asked, which also checks the ranges of a parallel download
+//! against a hash independent from the servers that sent them.
+//! The hash can also be compared with a trusted one, known out-of-band, at the slot of the bootstrapped state
+//! or at an earlier slot still in the hash history of the bootstrapped database.
+
+use massa_final_state::FinalState;
+use massa_hash::Hash;
+use massa_models::{node::NodeId, slot::Slot, version::Version};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Condvar, Mutex},
+};
+use tracing::{info, warn};
+
+use super::{connect_to_server, handshake_with_server, send_client_message, BSConnector};
+use crate::{
+    bindings::{BandwidthLimits, BootstrapClientBinder},
+    error::BootstrapError,
+    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    BootstrapConfig,
+};
+
+/// Checks the hash of the bootstrapped final state against the trusted one, if any, then waits until a quorum of `state_attestations`
+/// servers of the bootstrap list, other than the `sources` the state was downloaded from, attest it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn attest_final_state(
+    cfg: &BootstrapConfig,
+    connector: &mut impl BSConnector,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    sources: &[SocketAddr],
+    bandwidth_limits: &BandwidthLimits,
+    version: Version,
+    final_state: &Arc<RwLock<FinalState>>,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), BootstrapError> {
+    let (slot, state_hash, trusted_slot_hash) = {
+        let final_state_read = final_state.read();
+        let db = final_state_read.db.read();
+        let slot = db.get_change_id()?;
+        let state_hash = db.get_db_hash();
+        // hash of the bootstrapped state at the trusted slot, if still in its history
+        let trusted_slot_hash = cfg.trusted_state_hash.and_then(|(trusted_slot, _)| {
+            if trusted_slot == slot {
+                Some(state_hash)
+            } else {
+                db.hash_history.get(&trusted_slot).copied()
+            }
+        });
+        (slot, state_hash, trusted_slot_hash)
+    };
+    if let Some((trusted_slot, trusted_hash)) = cfg.trusted_state_hash {
+        if trusted_slot > slot {
+            return Err(BootstrapError::GeneralError(format!(
+                "the trusted final state hash is at slot {}, after the slot {} the final state was bootstrapped at from {:?}",
+                trusted_slot, slot, sources
+            )));
+        }
+        match trusted_slot_hash {
+            None => {
+                return Err(BootstrapError::GeneralError(format!(
+                    "the trusted final state hash is at slot {}, which is not in the hash history of the final state bootstrapped at slot {} from {:?}",
+                    trusted_slot, slot, sources
+                )));
+            }
+            Some(hash) if hash != trusted_hash => {
+                return Err(BootstrapError::GeneralError(format!(
+                    "the final state hash at slot {} is {} according to the trusted hash, but {} was bootstrapped from {:?}",
+                    trusted_slot, trusted_hash, hash, sources
+                )));
+            }
+            Some(_) => info!(
+                "Final state hash at slot {} matches the trusted hash, the final state was bootstrapped at slot {}",
+                trusted_slot, slot
+            ),
+        }
+    }
+    attest_state_hash(
+        cfg,
+        connector,
+        bootstrap_list,
+        sources,
+        bandwidth_limits,
+        version,
+        slot,
+        state_hash,
+        interupted,
+    )
+}
+
+/// Waits until `state_attestations` servers of the bootstrap list, other than the `sources` of the state, attest `state_hash` at `slot`,
+/// and more servers attest it than attest another hash.
+///
+/// The servers the state was downloaded from are not asked: a server that sent a forged part of the state,
+/// or a forged range of a parallel download, knows the hash of the forged state.
+/// A server attesting another hash is outvoted by the quorum, but the attestation fails as soon as
+/// too few of the remaining servers are left to reach it.
+///
+/// Servers that could not be reached, or that do not have `slot` in their history, are asked again after `retry_delay`,
+/// up to `state_attestation_attempts` times in total before the attestation fails.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn attest_state_hash(
+    cfg: &BootstrapConfig,
+    connector: &mut impl BSConnector,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    sources: &[SocketAddr],
+    bandwidth_limits: &BandwidthLimits,
+    version: Version,
+    slot: Slot,
+    state_hash: Hash,
+    interupted: &Arc<(Mutex<bool>, Condvar)>,
+) -> Result<(), BootstrapError> {
+    let required = cfg.state_attestations as usize;
+    if required == 0 {
+        return Ok(());
+    }
+    let witnesses: Vec<&(SocketAddr, NodeId)> = bootstrap_list
+        .iter()
+        .filter(|(addr, _)| !sources.contains(addr))
+        .collect();
+    if witnesses.len() < required {
+        return Err(BootstrapError::GeneralError(format!(
+            "{} state attestations required, but only {} servers of the bootstrap list did not send the state",
+            required,
+            witnesses.len()
+        )));
+    }
+    info!(
+        "Asking {} other bootstrap servers to attest the final state hash {} at slot {}",
+        required, state_hash, slot
+    );
+
+    // hash attested by each server that answered
+    let mut attestations: HashMap<SocketAddr, Hash> = HashMap::new();
+    for attempt in 1..=cfg.state_attestation_attempts {
+        // every server answered, without reaching the quorum
+        if attestations.len() == witnesses.len() {
+            break;
+        }
+        if attempt > 1 {
+            // wait before asking the servers again, unless interrupted
+            let int_sig = interupted
+                .0
+                .lock()
+                .expect("double-lock() on interupted signal mutex");
+            let wake = interupted
+                .1
+                .wait_timeout(int_sig, cfg.retry_delay.to_duration())
+                .expect("interupt signal mutex poisoned");
+            if *wake.0 {
+                return Err(BootstrapError::Interupted(
+                    "Sig INT during state attestation retry-wait".to_string(),
+                ));
+            }
+        }
+
+        for (addr, node_id) in witnesses.iter() {
+            if attestations.contains_key(addr) {
+                continue;
+            }
+            let attested_hash = connect_to_server(
+                connector,
+                cfg,
+                addr,
+                &node_id.get_public_key(),
+                bandwidth_limits,
+            )
+            .and_then(|mut client| ask_state_hash(cfg, &mut client, version, slot));
+            match attested_hash {
+                Ok(Some(hash)) => {
+                    if hash == state_hash {
+                        info!("Final state hash attested by {}", addr);
+                    } else {
+                        warn!(
+                            "The final state hash at slot {} is {} according to {}, but {} was bootstrapped from {:?}",
+                            slot, hash, addr, state_hash, sources
+                        );
+                    }
+                    attestations.insert(*addr, hash);
+                }
+                Ok(None) => info!("{} does not have the final state at slot {}", addr, slot),
+                Err(e) => warn!("Could not get the final state hash from {}: {}", addr, e),
+            }
+            let attested = attestations
+                .values()
+                .filter(|hash| **hash == state_hash)
+                .count();
+            let disputed = attestations.len() - attested;
+            if attested >= required && attested > disputed {
+                return Ok(());
+            }
+            if witnesses.len() - disputed < required {
+                return Err(BootstrapError::GeneralError(format!(
+                    "the final state hash {} at slot {} bootstrapped from {:?} was disputed by {} of the {} other servers, {} attestations required",
+                    state_hash,
+                    slot,
+                    sources,
+                    disputed,
+                    witnesses.len(),
+                    required
+                )));
+            }
+        }
+    }
+    let attested = attestations
+        .values()
+        .filter(|hash| **hash == state_hash)
+        .count();
+    Err(BootstrapError::GeneralError(format!(
+        "the final state hash {} at slot {} was attested by {} servers and disputed by {} after {} attempts, {} required",
+        state_hash,
+        slot,
+        attested,
+        attestations.len() - attested,
+        cfg.state_attestation_attempts,
+        required
+    )))
+}
+
+/// Asks a server for the hash of its final state at `slot`
+fn ask_state_hash(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    version: Version,
+    slot: Slot,
+) -> Result<Option<Hash>, BootstrapError> {
+    handshake_with_server(cfg, client, version)?;
+    let state_hash = match send_client_message(
+        &BootstrapClientMessage::AskStateHash { slot },
+        client,
+        cfg.write_timeout.into(),
+        cfg.read_timeout.into(),
+        "ask state hash timed out",
+    )? {
+        BootstrapServerMessage::StateHash {
+            slot: attested_slot,
+            state_hash,
+        } if attested_slot == slot => state_hash,
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+    client.send_timeout(
+        &BootstrapClientMessage::BootstrapSuccess,
+        Some(cfg.write_timeout.into()),
+    )?;
+    Ok(state_hash)
+}
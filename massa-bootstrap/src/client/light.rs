@@ -0,0 +1,194 @@
+//! Light bootstrap, for the clients that follow the chain without holding the ledger.
+//!
+//! Only the headers of the final blocks, the creators drawn for their slots and the hash of the final state are synced.
+//! Each header is checked against its signature and against the creators drawn for its slot.
+//! As in a full bootstrap, the selections and the state hash are trusted because the messages are signed by a server
+//! of the bootstrap list: a light client cannot recompute them.
+
+use massa_hash::Hash;
+use massa_models::{block_header::SecuredHeader, slot::Slot, version::Version};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Condvar, Mutex},
+};
+use tracing::{info, warn};
+
+use super::{
+    connect_to_server, get_bootstrap_list_iter, handshake_with_server, send_client_message,
+    BSConnector,
+};
+use crate::{
+    bindings::{BandwidthLimits, BootstrapClientBinder},
+    error::BootstrapError,
+    messages::{BootstrapClientMessage, BootstrapServerMessage, LightSelection},
+    BootstrapConfig,
+};
+
+/// State synced by a light bootstrap
+#[derive(Debug, Clone)]
+pub struct LightBootstrapState {
+    /// Slot of the final state
+    pub slot: Slot,
+    /// Hash of the final state at `slot`
+    pub state_hash: Hash,
+    /// Headers of the final blocks, sorted by slot
+    pub final_headers: Vec<SecuredHeader>,
+    /// Creators drawn for the slots of the headers
+    pub selections: BTreeMap<Slot, LightSelection>,
+}
+
+/// Gets the light state from the servers of the bootstrap list, one after the other until one of them succeeds.
+pub fn get_light_state(
+    bootstrap_config: &BootstrapConfig,
+    mut connector: impl BSConnector,
+    version: Version,
+    interupted: Arc<(Mutex<bool>, Condvar)>,
+) -> Result<LightBootstrapState, BootstrapError> {
+    let bootstrap_list = get_bootstrap_list_iter(bootstrap_config)?;
+    let bandwidth_limits = BandwidthLimits::new(
+        bootstrap_config.max_total_upload_bytes_per_second,
+        bootstrap_config.max_total_download_bytes_per_second,
+    );
+    loop {
+        for (addr, node_id) in bootstrap_list.iter() {
+            info!("Start light bootstrapping from {}", addr);
+            match connect_to_server(
+                &mut connector,
+                bootstrap_config,
+                addr,
+                &node_id.get_public_key(),
+                &bandwidth_limits,
+            ) {
+                Ok(mut client) => {
+                    match light_bootstrap_from_server(bootstrap_config, &mut client, version) {
+                        Err(BootstrapError::ReceivedError(error)) => {
+                            warn!("Error received from bootstrap server: {}", error)
+                        }
+                        Err(e) => {
+                            warn!("Error while light bootstrapping: {}", e);
+                            // We allow unused result because the socket is closed anyway
+                            let _ = client.send_timeout(
+                                &BootstrapClientMessage::BootstrapError {
+                                    error: e.to_string(),
+                                },
+                                Some(bootstrap_config.write_error_timeout.into()),
+                            );
+                        }
+                        Ok(state) => return Ok(state),
+                    }
+                }
+                Err(e) => warn!("Error while connecting to bootstrap server: {}", e),
+            }
+
+            // wait before trying the next server, unless interrupted
+            let int_sig = interupted
+                .0
+                .lock()
+                .expect("double-lock() on interupted signal mutex");
+            let wake = interupted
+                .1
+                .wait_timeout(int_sig, bootstrap_config.retry_delay.to_duration())
+                .expect("interupt signal mutex poisoned");
+            if *wake.0 {
+                return Err(BootstrapError::Interupted(
+                    "Sig INT during light bootstrap retry-wait".to_string(),
+                ));
+            }
+        }
+    }
+}
+
+/// Asks a server for its light state and checks the received headers
+fn light_bootstrap_from_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    version: Version,
+) -> Result<LightBootstrapState, BootstrapError> {
+    handshake_with_server(cfg, client, version)?;
+    let (slot, state_hash, mut final_headers, selections) = match send_client_message(
+        &BootstrapClientMessage::AskLightState,
+        client,
+        cfg.write_timeout.into(),
+        cfg.read_timeout.into(),
+        "ask light state timed out",
+    )? {
+        BootstrapServerMessage::LightState {
+            slot,
+            state_hash,
+            headers,
+            selections,
+        } => (slot, state_hash, headers, selections),
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+    let selections: BTreeMap<Slot, LightSelection> = selections
+        .into_iter()
+        .map(|selection| (selection.slot, selection))
+        .collect();
+    final_headers.sort_unstable_by_key(|header| header.content.slot);
+    for header in &final_headers {
+        check_header(header, &selections, slot)?;
+    }
+    client.send_timeout(
+        &BootstrapClientMessage::BootstrapSuccess,
+        Some(cfg.write_timeout.into()),
+    )?;
+    info!(
+        "Successful light bootstrap: {} final headers up to slot {}",
+        final_headers.len(),
+        slot
+    );
+    Ok(LightBootstrapState {
+        slot,
+        state_hash,
+        final_headers,
+        selections,
+    })
+}
+
+/// Checks the signatures of a header and of its endorsements, and that they were created by the drawn creators.
+///
+/// Genesis blocks, which have no parents, are not drawn.
+fn check_header(
+    header: &SecuredHeader,
+    selections: &BTreeMap<Slot, LightSelection>,
+    state_slot: Slot,
+) -> Result<(), BootstrapError> {
+    let header_slot = header.content.slot;
+    let invalid = |reason: &str| {
+        BootstrapError::GeneralError(format!(
+            "invalid final header {} at slot {}: {}",
+            header.id, header_slot, reason
+        ))
+    };
+    if header_slot > state_slot {
+        return Err(invalid("after the slot of the final state"));
+    }
+    header
+        .verify_signature()
+        .map_err(|e| invalid(&e.to_string()))?;
+    if header.content.parents.is_empty() {
+        return Ok(());
+    }
+    let selection = selections
+        .get(&header_slot)
+        .ok_or_else(|| invalid("no selection for its slot"))?;
+    if header.content_creator_address != selection.producer {
+        return Err(invalid("not created by the drawn producer"));
+    }
+    for endorsement in &header.content.endorsements {
+        endorsement
+            .verify_signature()
+            .map_err(|e| invalid(&e.to_string()))?;
+        if selection
+            .endorsements
+            .get(endorsement.content.index as usize)
+            != Some(&endorsement.content_creator_address)
+        {
+            return Err(invalid("endorsement not created by the drawn endorser"));
+        }
+    }
+    Ok(())
+}
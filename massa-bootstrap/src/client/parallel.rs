@@ -0,0 +1,408 @@
+//! Download of the state from several bootstrap servers at once.
+//!
+//! The state is split in ranges of keys, at the keys given by the first server, and each connected
+//! server streams ranges one after the other. Each part of a range comes with its hash, checked before writing it.
+//! This hash is computed by the sending server and only detects corrupted parts: the whole state is checked at the end
+//! of the bootstrap against the hash attested by servers that did not send any range.
+//!
+//! A range follows the changes made on its keys until its last part, so each downloaded range is attached to a slot.
+//! A server without range left to download refreshes the range attached to the oldest slot, until all the ranges
+//! are attached to a slot at least as recent as the one of the last completed range.
+//! The regular bootstrap then asks for the changes made on the whole state since the oldest of these slots.
+
+use massa_db::StreamBatch;
+use massa_final_state::FinalState;
+use massa_models::{
+    node::NodeId, slot::Slot, stats::BootstrapPhase, streaming_step::StreamingStep,
+    version::Version,
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar,
+    },
+    time::Duration,
+};
+use tracing::{debug, info, warn};
+
+use super::{
+    connect_to_server, handshake_with_server, send_client_message, BSConnector, ProgressReporter,
+};
+use crate::{
+    bindings::{BandwidthLimits, BootstrapClientBinder},
+    error::BootstrapError,
+    messages::{
+        compute_state_part_hash, next_state_range_step, BootstrapClientMessage,
+        BootstrapServerMessage, MAX_STATE_RANGES,
+    },
+    BootstrapConfig,
+};
+
+/// Number of ranges of the state per server, so that the fastest servers download more ranges
+const RANGES_PER_SERVER: u32 = 4;
+
+/// Delay before looking again for a range to download when all of them are being downloaded
+const RANGE_WAIT_DELAY: Duration = Duration::from_millis(100);
+
+/// Download progress of a range of the state
+pub(crate) struct StateRange {
+    /// first key of the range
+    pub(crate) start_key: Vec<u8>,
+    /// first key after the range, None for the last range
+    pub(crate) end_key: Option<Vec<u8>>,
+    /// last received key of the range
+    pub(crate) last_step: StreamingStep<Vec<u8>>,
+    /// slot the received part of the range is attached to
+    pub(crate) last_slot: Option<Slot>,
+    /// true while a server downloads the range
+    pub(crate) busy: bool,
+}
+
+/// Ranges of the state, shared by the download threads
+pub(crate) struct StateRanges {
+    pub(crate) ranges: Vec<StateRange>,
+    /// slot of the last completed range, once all ranges are downloaded: the other ranges are refreshed up to it
+    pub(crate) target_slot: Option<Slot>,
+}
+
+impl StateRanges {
+    /// Creates the ranges delimited by the given sorted keys
+    pub(crate) fn new(split_keys: Vec<Vec<u8>>) -> Self {
+        let mut start_keys = vec![Vec::new()];
+        start_keys.extend(split_keys.iter().cloned());
+        let end_keys = split_keys
+            .into_iter()
+            .map(Some)
+            .chain(std::iter::once(None));
+        StateRanges {
+            ranges: start_keys
+                .into_iter()
+                .zip(end_keys)
+                .map(|(start_key, end_key)| StateRange {
+                    start_key,
+                    end_key,
+                    last_step: StreamingStep::Started,
+                    last_slot: None,
+                    busy: false,
+                })
+                .collect(),
+            target_slot: None,
+        }
+    }
+
+    /// Picks the next range to download and marks it as busy: a range not downloaded yet,
+    /// otherwise the downloaded range attached to the oldest slot
+    pub(crate) fn pick(&mut self) -> Option<usize> {
+        let index = match self
+            .ranges
+            .iter()
+            .position(|range| !range.busy && !range.last_step.finished())
+        {
+            Some(index) => Some(index),
+            None => self
+                .ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, range)| {
+                    !range.busy
+                        && self
+                            .target_slot
+                            .map_or(true, |target| range.last_slot < Some(target))
+                })
+                .min_by_key(|(_, range)| range.last_slot)
+                .map(|(index, _)| index),
+        }?;
+        self.ranges[index].busy = true;
+        Some(index)
+    }
+
+    /// Records the progress of a range after one of its parts
+    pub(crate) fn update(&mut self, index: usize, last_step: StreamingStep<Vec<u8>>, slot: Slot) {
+        let range = &mut self.ranges[index];
+        range.last_step = last_step;
+        range.last_slot = Some(slot);
+    }
+
+    /// Marks a range as no longer downloaded
+    pub(crate) fn release(&mut self, index: usize) {
+        self.ranges[index].busy = false;
+        if self.target_slot.is_none() && self.ranges.iter().all(|r| r.last_step.finished()) {
+            self.target_slot = self.ranges.iter().filter_map(|r| r.last_slot).max();
+        }
+    }
+
+    /// Returns the fraction of the ranges downloaded at least once
+    pub(crate) fn done_fraction(&self) -> f64 {
+        let done = self
+            .ranges
+            .iter()
+            .filter(|range| range.last_step.finished())
+            .count();
+        done as f64 / self.ranges.len() as f64
+    }
+
+    /// Returns the oldest slot the ranges are attached to, once all of them are downloaded and refreshed
+    pub(crate) fn completed_slot(&self) -> Option<Slot> {
+        let target_slot = self.target_slot?;
+        let mut oldest_slot = target_slot;
+        for range in &self.ranges {
+            match range.last_slot {
+                Some(slot) if !range.busy && range.last_step.finished() && slot >= target_slot => {
+                    oldest_slot = std::cmp::min(oldest_slot, slot)
+                }
+                _ => return None,
+            }
+        }
+        Some(oldest_slot)
+    }
+}
+
+/// Downloads the state by ranges from `parallel_download_servers` servers of the bootstrap list at once.
+///
+/// Returns the oldest slot the downloaded ranges are attached to, and the servers the ranges were downloaded from.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn download_state(
+    cfg: &BootstrapConfig,
+    final_state: &Arc<RwLock<FinalState>>,
+    connector: &mut impl BSConnector,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    bandwidth_limits: &BandwidthLimits,
+    version: Version,
+    progress: &ProgressReporter,
+    interupted: &Arc<(std::sync::Mutex<bool>, Condvar)>,
+) -> Result<(Slot, Vec<SocketAddr>), BootstrapError> {
+    progress.set_server(None);
+    progress.set_phase(BootstrapPhase::DownloadingStateRanges);
+    let mut clients = Vec::new();
+    let mut servers = Vec::new();
+    for (addr, node_id) in bootstrap_list {
+        if clients.len() >= cfg.parallel_download_servers as usize {
+            break;
+        }
+        let client = connect_to_server(
+            connector,
+            cfg,
+            addr,
+            &node_id.get_public_key(),
+            bandwidth_limits,
+        )
+        .and_then(|mut client| {
+            handshake_with_server(cfg, &mut client, version)?;
+            Ok(client)
+        });
+        match client {
+            Ok(client) => {
+                clients.push(client);
+                servers.push(*addr);
+            }
+            Err(e) => warn!("Cannot download the state from {}: {}", addr, e),
+        }
+    }
+    if clients.len() < 2 {
+        return Err(BootstrapError::GeneralError(
+            "not enough bootstrap servers available to download the state in parallel".into(),
+        ));
+    }
+
+    let count = (clients.len() as u32)
+        .saturating_mul(RANGES_PER_SERVER)
+        .min(MAX_STATE_RANGES);
+    let split_keys = match send_client_message(
+        &BootstrapClientMessage::AskStateSplit { count },
+        &mut clients[0],
+        cfg.write_timeout.into(),
+        cfg.read_timeout.into(),
+        "ask state split timed out",
+    )? {
+        BootstrapServerMessage::StateSplit { keys } => keys,
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+    if split_keys.first().map_or(false, |key| key.is_empty())
+        || split_keys.windows(2).any(|keys| keys[0] >= keys[1])
+    {
+        return Err(BootstrapError::GeneralError(
+            "the keys splitting the state are not sorted".into(),
+        ));
+    }
+    info!(
+        "Downloading the state in {} ranges from {} bootstrap servers",
+        split_keys.len() + 1,
+        clients.len()
+    );
+
+    let ranges = Mutex::new(StateRanges::new(split_keys));
+    let abort = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        for client in clients {
+            let (ranges, abort) = (&ranges, &abort);
+            scope.spawn(move || {
+                download_ranges(
+                    cfg,
+                    client,
+                    final_state,
+                    ranges,
+                    abort,
+                    progress,
+                    interupted,
+                )
+            });
+        }
+    });
+
+    if *interupted.0.lock().expect("double-lock on interupt-mutex") {
+        return Err(BootstrapError::Interupted(
+            "Sig INT received while downloading the state".to_string(),
+        ));
+    }
+    let slot = ranges.into_inner().completed_slot().ok_or_else(|| {
+        BootstrapError::GeneralError("the download of some ranges of the state failed".into())
+    })?;
+    Ok((slot, servers))
+}
+
+/// Downloads ranges of the state from a server until all of them are complete.
+///
+/// The server is dropped on its first error. The whole download is aborted if the server
+/// no longer has the changes made since a downloaded range.
+fn download_ranges(
+    cfg: &BootstrapConfig,
+    mut client: BootstrapClientBinder,
+    final_state: &Arc<RwLock<FinalState>>,
+    ranges: &Mutex<StateRanges>,
+    abort: &AtomicBool,
+    progress: &ProgressReporter,
+    interupted: &Arc<(std::sync::Mutex<bool>, Condvar)>,
+) {
+    let result = loop {
+        if abort.load(Ordering::Relaxed)
+            || *interupted.0.lock().expect("double-lock on interupt-mutex")
+        {
+            break Ok(());
+        }
+        let Some(index) = ranges.lock().pick() else {
+            if ranges.lock().completed_slot().is_some() {
+                break Ok(());
+            }
+            std::thread::sleep(RANGE_WAIT_DELAY);
+            continue;
+        };
+        let result = download_range(
+            cfg,
+            &mut client,
+            final_state,
+            ranges,
+            index,
+            abort,
+            progress,
+        );
+        ranges.lock().release(index);
+        if let Err(e) = result {
+            break Err(e);
+        }
+    };
+    match result {
+        Ok(()) => {
+            // We allow unused result because the server closes the session anyway
+            let _ = client.send_timeout(
+                &BootstrapClientMessage::BootstrapSuccess,
+                Some(cfg.write_timeout.into()),
+            );
+        }
+        Err(e) => {
+            warn!("Error while downloading the state: {}", e);
+            let _ = client.send_timeout(
+                &BootstrapClientMessage::BootstrapError {
+                    error: e.to_string(),
+                },
+                Some(cfg.write_error_timeout.into()),
+            );
+        }
+    }
+}
+
+/// Downloads the parts of a range of the state from its current cursor
+fn download_range(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    final_state: &Arc<RwLock<FinalState>>,
+    ranges: &Mutex<StateRanges>,
+    index: usize,
+    abort: &AtomicBool,
+    progress: &ProgressReporter,
+) -> Result<(), BootstrapError> {
+    let (request, mut last_step) = {
+        let ranges = ranges.lock();
+        let range = &ranges.ranges[index];
+        let request = BootstrapClientMessage::AskStateRange {
+            last_slot: range.last_slot,
+            start_key: range.start_key.clone(),
+            end_key: range.end_key.clone(),
+            last_step: range.last_step.clone(),
+        };
+        (request, range.last_step.clone())
+    };
+    client.send_timeout(&request, Some(cfg.write_timeout.into()))?;
+
+    loop {
+        match client.next_timeout(Some(cfg.read_timeout.into()))? {
+            BootstrapServerMessage::StateRangePart { slot, part, hash } => {
+                if compute_state_part_hash(&part)? != hash {
+                    return Err(BootstrapError::GeneralError(
+                        "received a state part not matching its hash".into(),
+                    ));
+                }
+                last_step = next_state_range_step(&last_step, &part);
+                write_range_part(final_state, part)?;
+                debug!(
+                    "state range {} cursor: {:?} at slot {}",
+                    index, last_step, slot
+                );
+                let done = {
+                    let mut ranges = ranges.lock();
+                    ranges.update(index, last_step.clone(), slot);
+                    ranges.done_fraction()
+                };
+                progress.part_received(slot, Some(done));
+            }
+            BootstrapServerMessage::BootstrapFinished => return Ok(()),
+            BootstrapServerMessage::SlotTooOld => {
+                abort.store(true, Ordering::Relaxed);
+                return Err(BootstrapError::GeneralError(String::from("Slot too old")));
+            }
+            BootstrapServerMessage::BootstrapError { error } => {
+                return Err(BootstrapError::ReceivedError(error))
+            }
+            other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+        }
+    }
+}
+
+/// Writes a part of a range of the state, without moving the slot of the final state
+fn write_range_part(
+    final_state: &Arc<RwLock<FinalState>>,
+    part: StreamBatch<Slot>,
+) -> Result<(), BootstrapError> {
+    let mut changes: BTreeMap<Vec<u8>, Option<Vec<u8>>> = part.updates_on_previous_elements;
+    changes.extend(
+        part.new_elements
+            .into_iter()
+            .map(|(key, value)| (key, Some(value))),
+    );
+    final_state
+        .read()
+        .db
+        .write()
+        .write_changes(changes, BTreeMap::new(), None, true, false, false)
+        .map_err(|e| {
+            BootstrapError::GeneralError(format!(
+                "Cannot write received state range part to disk: {}",
+                e
+            ))
+        })
+}
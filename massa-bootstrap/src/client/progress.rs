@@ -0,0 +1,120 @@
+//! Progress of a bootstrap, reported on a channel so that operators can tell a stuck bootstrap from a slow one.
+//!
+//! A report is sent on each change of phase, and at most every `REPORT_INTERVAL` within a phase.
+//! The estimated time left is only known while the state is downloaded by ranges, as the size
+//! of the state is not known beforehand.
+
+use crossbeam::channel::Sender;
+use massa_models::{
+    slot::Slot,
+    stats::{BootstrapPhase, BootstrapStats},
+};
+use massa_time::MassaTime;
+use parking_lot::Mutex;
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::bindings::BandwidthLimits;
+
+/// Minimum delay between two reports within the same phase
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reports the progress of a bootstrap, shared by the threads downloading the state
+#[derive(Clone)]
+pub(crate) struct ProgressReporter {
+    sender: Option<Sender<BootstrapStats>>,
+    /// limits of the bootstrap connections, counting the received bytes
+    bandwidth_limits: BandwidthLimits,
+    state: Arc<Mutex<ProgressState>>,
+}
+
+struct ProgressState {
+    start: Instant,
+    phase: BootstrapPhase,
+    phase_start: Instant,
+    server: Option<SocketAddr>,
+    streamed_slot: Option<Slot>,
+    /// fraction of the current phase completed, when known
+    done: Option<f64>,
+    last_report: Option<Instant>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter sending its reports on `sender`, if any
+    pub(crate) fn new(
+        sender: Option<Sender<BootstrapStats>>,
+        bandwidth_limits: BandwidthLimits,
+    ) -> Self {
+        let now = Instant::now();
+        ProgressReporter {
+            sender,
+            bandwidth_limits,
+            state: Arc::new(Mutex::new(ProgressState {
+                start: now,
+                phase: BootstrapPhase::Connecting,
+                phase_start: now,
+                server: None,
+                streamed_slot: None,
+                done: None,
+                last_report: None,
+            })),
+        }
+    }
+
+    /// Sets the bootstrap server the node is connected to, none during a download from several servers
+    pub(crate) fn set_server(&self, server: Option<SocketAddr>) {
+        self.state.lock().server = server;
+    }
+
+    /// Enters a phase, reported right away if it changed
+    pub(crate) fn set_phase(&self, phase: BootstrapPhase) {
+        let mut state = self.state.lock();
+        let changed = state.phase != phase || state.last_report.is_none();
+        if changed {
+            state.phase = phase;
+            state.phase_start = Instant::now();
+            state.done = None;
+        }
+        self.report(&mut state, changed);
+    }
+
+    /// Records a received part of the final state attached to `slot`.
+    /// `done` is the fraction of the current phase completed, if known.
+    pub(crate) fn part_received(&self, slot: Slot, done: Option<f64>) {
+        let mut state = self.state.lock();
+        state.streamed_slot = Some(slot);
+        state.done = done;
+        self.report(&mut state, false);
+    }
+
+    fn report(&self, state: &mut ProgressState, force: bool) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let now = Instant::now();
+        if !force
+            && state
+                .last_report
+                .map_or(false, |last| now.duration_since(last) < REPORT_INTERVAL)
+        {
+            return;
+        }
+        state.last_report = Some(now);
+        let eta = state.done.filter(|done| *done > 0.0).map(|done| {
+            let phase_elapsed = now.duration_since(state.phase_start).as_secs_f64();
+            MassaTime::from_millis((phase_elapsed * (1.0 - done.min(1.0)) / done * 1000.0) as u64)
+        });
+        // reports are dropped if the consumer is late, the next one supersedes them
+        let _ = sender.try_send(BootstrapStats {
+            phase: state.phase,
+            server: state.server,
+            received_bytes: self.bandwidth_limits.received_bytes(),
+            streamed_slot: state.streamed_slot,
+            elapsed: MassaTime::from_millis(now.duration_since(state.start).as_millis() as u64),
+            eta,
+        });
+    }
+}
@@ -25,23 +25,29 @@ mod bindings;
 mod client;
 mod error;
 pub use error::BootstrapError;
+#[cfg(feature = "server")]
 mod listener;
 mod messages;
+#[cfg(feature = "server")]
 mod server;
 mod settings;
+#[cfg(feature = "server")]
 mod tools;
 
-pub use client::{get_state, DefaultConnector};
+pub use client::{get_light_state, get_state, DefaultConnector, LightBootstrapState};
+#[cfg(feature = "server")]
 pub use listener::BootstrapTcpListener;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
     BootstrapServerMessage, BootstrapServerMessageDeserializer, BootstrapServerMessageSerializer,
+    LightSelection,
 };
+#[cfg(feature = "server")]
 pub use server::{start_bootstrap_server, BootstrapManager};
 pub use settings::IpType;
 pub use settings::{BootstrapConfig, BootstrapServerMessageDeserializerArgs};
 
-#[cfg(test)]
+#[cfg(all(test, feature = "server"))]
 pub(crate) mod tests;
 
 /// a collection of the bootstrap state snapshots of all relevant modules
@@ -1,4 +1,5 @@
 use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
 
 use mio::net::TcpListener as MioTcpListener;
 
@@ -80,8 +81,8 @@ impl BootstrapTcpListener {
 }
 
 impl BSEventPoller for BootstrapTcpListener {
-    fn poll(&mut self) -> Result<PollEvent, BootstrapError> {
-        self.poll.poll(&mut self.events, None).unwrap();
+    fn poll(&mut self, timeout: Option<Duration>) -> Result<PollEvent, BootstrapError> {
+        self.poll.poll(&mut self.events, timeout).unwrap();
 
         // Confirm that we are not being signalled to shut down
         if self.events.iter().any(|ev| ev.token() == STOP_LISTENER) {
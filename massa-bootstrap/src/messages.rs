@@ -5,8 +5,13 @@ use massa_consensus_exports::bootstrapable_graph::{
     BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
 };
 use massa_db::StreamBatch;
+use massa_hash::{Hash, HashDeserializer, HashSerializer};
+use massa_models::address::{Address, AddressDeserializer, AddressSerializer};
+use massa_models::block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader};
 use massa_models::block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer};
+use massa_models::config::MAX_BOOTSTRAP_MESSAGE_SIZE;
 use massa_models::prehash::PreHashSet;
+use massa_models::secure_share::{SecureShareDeserializer, SecureShareSerializer};
 use massa_models::serialization::{
     PreHashSetDeserializer, PreHashSetSerializer, VecU8Deserializer, VecU8Serializer,
 };
@@ -34,8 +39,18 @@ use nom::{
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::convert::TryInto;
+use std::io::Read;
 use std::ops::Bound::{Excluded, Included};
 
+/// Maximum number of ranges of the state downloaded at once
+pub(crate) const MAX_STATE_RANGES: u32 = 1024;
+
+/// Handshake flag of the clients accepting server messages compressed with zstd
+pub(crate) const HANDSHAKE_ZSTD_FLAG: u8 = 1;
+
+/// zstd level of the compressed server messages
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
 /// Messages used during bootstrap by server
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
@@ -78,6 +93,49 @@ pub enum BootstrapServerMessage {
         /// Error message
         error: String,
     },
+    /// Keys splitting the state in ranges, to download them from several servers at once
+    StateSplit {
+        /// Sorted first keys of the ranges, except the first range starting at the first key
+        keys: Vec<Vec<u8>>,
+    },
+    /// Part of a range of the state
+    StateRangePart {
+        /// Slot the state changes are attached to
+        slot: Slot,
+        /// Part of the range of the state in a serialized way
+        part: StreamBatch<Slot>,
+        /// Hash of the part, checked by the client before writing it
+        hash: Hash,
+    },
+    /// Final block headers, selections and state commitment, for a light client
+    LightState {
+        /// Slot of the final state
+        slot: Slot,
+        /// Hash of the final state at `slot`
+        state_hash: Hash,
+        /// Headers of the final blocks of the consensus graph
+        headers: Vec<SecuredHeader>,
+        /// Creators drawn for the slots of the headers
+        selections: Vec<LightSelection>,
+    },
+    /// Another message, serialized then compressed with zstd.
+    /// Only sent to the clients setting `HANDSHAKE_ZSTD_FLAG` in their handshake.
+    Compressed {
+        /// Compressed serialized message
+        data: Vec<u8>,
+    },
+    /// Hash of the final state of the server at a slot, attesting the state bootstrapped by the client
+    StateHash {
+        /// Slot of the final state
+        slot: Slot,
+        /// Hash of the final state at `slot`, None if the slot is not in the history of the server
+        state_hash: Option<Hash>,
+    },
+    /// Answer to a re-sync request
+    ResyncStart {
+        /// Slot from which the final state changes will be streamed, None if the client needs a full bootstrap
+        slot: Option<Slot>,
+    },
 }
 
 impl ToString for BootstrapServerMessage {
@@ -91,6 +149,12 @@ impl ToString for BootstrapServerMessage {
             BootstrapServerMessage::BootstrapError { error } => {
                 format!("BootstrapError {{ error: {} }}", error)
             }
+            BootstrapServerMessage::StateSplit { .. } => "StateSplit".to_string(),
+            BootstrapServerMessage::StateRangePart { .. } => "StateRangePart".to_string(),
+            BootstrapServerMessage::LightState { .. } => "LightState".to_string(),
+            BootstrapServerMessage::Compressed { .. } => "Compressed".to_string(),
+            BootstrapServerMessage::StateHash { .. } => "StateHash".to_string(),
+            BootstrapServerMessage::ResyncStart { .. } => "ResyncStart".to_string(),
         }
     }
 }
@@ -104,6 +168,12 @@ enum MessageServerTypeId {
     FinalStateFinished = 3u32,
     SlotTooOld = 4u32,
     BootstrapError = 5u32,
+    StateSplit = 6u32,
+    StateRangePart = 7u32,
+    Compressed = 8u32,
+    LightState = 9u32,
+    StateHash = 10u32,
+    ResyncStart = 11u32,
 }
 
 /// Serializer for `BootstrapServerMessage`
@@ -121,6 +191,11 @@ pub struct BootstrapServerMessageSerializer {
     opt_last_start_period_serializer: OptionSerializer<u64, U64VarIntSerializer>,
     opt_last_slot_before_downtime_serializer:
         OptionSerializer<Option<Slot>, OptionSerializer<Slot, SlotSerializer>>,
+    hash_serializer: HashSerializer,
+    opt_hash_serializer: OptionSerializer<Hash, HashSerializer>,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+    header_serializer: SecureShareSerializer,
+    address_serializer: AddressSerializer,
 }
 
 impl Default for BootstrapServerMessageSerializer {
@@ -147,8 +222,53 @@ impl BootstrapServerMessageSerializer {
             opt_last_slot_before_downtime_serializer: OptionSerializer::new(OptionSerializer::new(
                 SlotSerializer::new(),
             )),
+            hash_serializer: HashSerializer::new(),
+            opt_hash_serializer: OptionSerializer::new(HashSerializer::new()),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+            header_serializer: SecureShareSerializer::new(),
+            address_serializer: AddressSerializer::new(),
         }
     }
+
+    /// Serializes a batch of state entries
+    fn serialize_stream_batch(
+        &self,
+        batch: &StreamBatch<Slot>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer
+            .serialize(&(batch.new_elements.len() as u64), buffer)?;
+        for (key, value) in batch.new_elements.iter() {
+            self.vec_u8_serializer.serialize(key, buffer)?;
+            self.vec_u8_serializer.serialize(value, buffer)?;
+        }
+        self.u64_serializer
+            .serialize(&(batch.updates_on_previous_elements.len() as u64), buffer)?;
+        for (key, value) in batch.updates_on_previous_elements.iter() {
+            self.vec_u8_serializer.serialize(key, buffer)?;
+            self.opt_vec_u8_serializer.serialize(value, buffer)?;
+        }
+        self.slot_serializer.serialize(&batch.change_id, buffer)
+    }
+}
+
+/// Computes the hash of a part of a range of the state, sent along with it
+pub(crate) fn compute_state_part_hash(part: &StreamBatch<Slot>) -> Result<Hash, SerializeError> {
+    let mut buffer = Vec::new();
+    BootstrapServerMessageSerializer::new().serialize_stream_batch(part, &mut buffer)?;
+    Ok(Hash::compute_from(&buffer))
+}
+
+/// Cursor of a range of the state after one of its parts: the range is finished
+/// once a part brings no new key
+pub(crate) fn next_state_range_step(
+    last_step: &StreamingStep<Vec<u8>>,
+    part: &StreamBatch<Slot>,
+) -> StreamingStep<Vec<u8>> {
+    match (last_step, part.new_elements.last_key_value()) {
+        (StreamingStep::Finished(_), _) | (_, None) => StreamingStep::Finished(None),
+        (_, Some((last_key, _))) => StreamingStep::Ongoing(last_key.clone()),
+    }
 }
 
 impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
@@ -268,6 +388,72 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 )?;
                 buffer.extend(error.as_bytes())
             }
+            BootstrapServerMessage::StateSplit { keys } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateSplit), buffer)?;
+                self.u64_serializer
+                    .serialize(&(keys.len() as u64), buffer)?;
+                for key in keys {
+                    self.vec_u8_serializer.serialize(key, buffer)?;
+                }
+            }
+            BootstrapServerMessage::StateRangePart { slot, part, hash } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateRangePart), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.serialize_stream_batch(part, buffer)?;
+                self.hash_serializer.serialize(hash, buffer)?;
+            }
+            BootstrapServerMessage::Compressed { data } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::Compressed), buffer)?;
+                self.u32_serializer.serialize(
+                    &data.len().try_into().map_err(|_| {
+                        SerializeError::GeneralError("Fail to convert usize to u32".to_string())
+                    })?,
+                    buffer,
+                )?;
+                buffer.extend(data)
+            }
+            BootstrapServerMessage::StateHash { slot, state_hash } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::StateHash), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.opt_hash_serializer.serialize(state_hash, buffer)?;
+            }
+            BootstrapServerMessage::ResyncStart { slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::ResyncStart), buffer)?;
+                self.opt_slot_serializer.serialize(slot, buffer)?;
+            }
+            BootstrapServerMessage::LightState {
+                slot,
+                state_hash,
+                headers,
+                selections,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::LightState), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                self.hash_serializer.serialize(state_hash, buffer)?;
+                self.u32_serializer
+                    .serialize(&(headers.len() as u32), buffer)?;
+                for header in headers {
+                    self.header_serializer.serialize(header, buffer)?;
+                }
+                self.u32_serializer
+                    .serialize(&(selections.len() as u32), buffer)?;
+                for selection in selections {
+                    self.slot_serializer.serialize(&selection.slot, buffer)?;
+                    self.address_serializer
+                        .serialize(&selection.producer, buffer)?;
+                    self.u32_serializer
+                        .serialize(&(selection.endorsements.len() as u32), buffer)?;
+                    for address in &selection.endorsements {
+                        self.address_serializer.serialize(address, buffer)?;
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -290,6 +476,15 @@ pub struct BootstrapServerMessageDeserializer {
     opt_last_start_period_deserializer: OptionDeserializer<u64, U64VarIntDeserializer>,
     opt_last_slot_before_downtime_deserializer:
         OptionDeserializer<Option<Slot>, OptionDeserializer<Slot, SlotDeserializer>>,
+    state_split_length_deserializer: U64VarIntDeserializer,
+    hash_deserializer: HashDeserializer,
+    opt_hash_deserializer: OptionDeserializer<Hash, HashDeserializer>,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+    compressed_length_deserializer: U32VarIntDeserializer,
+    light_length_deserializer: U32VarIntDeserializer,
+    header_deserializer: SecureShareDeserializer<BlockHeader, BlockHeaderDeserializer>,
+    address_deserializer: AddressDeserializer,
+    endorsements_length_deserializer: U32VarIntDeserializer,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -349,8 +544,110 @@ impl BootstrapServerMessageDeserializer {
                     (Included(0), Excluded(args.thread_count)),
                 )),
             ),
+            state_split_length_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_STATE_RANGES as u64),
+            ),
+            hash_deserializer: HashDeserializer::new(),
+            opt_hash_deserializer: OptionDeserializer::new(HashDeserializer::new()),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(args.thread_count)),
+            )),
+            compressed_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_BOOTSTRAP_MESSAGE_SIZE),
+            ),
+            light_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.max_bootstrap_blocks_length),
+            ),
+            header_deserializer: SecureShareDeserializer::new(BlockHeaderDeserializer::new(
+                args.thread_count,
+                args.endorsement_count,
+                args.max_denunciations_per_block_header,
+                None,
+            )),
+            address_deserializer: AddressDeserializer::new(),
+            endorsements_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.endorsement_count),
+            ),
         }
     }
+
+    /// Deserializes the creators drawn for a slot
+    fn deserialize_light_selection<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LightSelection, E> {
+        tuple((
+            context("Failed slot deserialization", |input| {
+                self.slot_deserializer.deserialize(input)
+            }),
+            context("Failed producer deserialization", |input| {
+                self.address_deserializer.deserialize(input)
+            }),
+            context(
+                "Failed endorsements deserialization",
+                length_count(
+                    context("Failed length deserialization", |input| {
+                        self.endorsements_length_deserializer.deserialize(input)
+                    }),
+                    |input| self.address_deserializer.deserialize(input),
+                ),
+            ),
+        ))
+        .map(|(slot, producer, endorsements)| LightSelection {
+            slot,
+            producer,
+            endorsements,
+        })
+        .parse(buffer)
+    }
+
+    /// Deserializes a batch of state entries
+    fn deserialize_stream_batch<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], StreamBatch<Slot>, E> {
+        tuple((
+            context(
+                "Failed new_elements deserialization",
+                length_count(
+                    context("Failed length deserialization", |input| {
+                        self.state_new_elements_length_deserializer
+                            .deserialize(input)
+                    }),
+                    tuple((
+                        |input| self.vec_u8_deserializer.deserialize(input),
+                        |input| self.vec_u8_deserializer.deserialize(input),
+                    )),
+                ),
+            ),
+            context(
+                "Failed updates deserialization",
+                length_count(
+                    context("Failed length deserialization", |input| {
+                        self.state_updates_length_deserializer.deserialize(input)
+                    }),
+                    tuple((
+                        |input| self.vec_u8_deserializer.deserialize(input),
+                        |input| self.opt_vec_u8_deserializer.deserialize(input),
+                    )),
+                ),
+            ),
+            context("Failed slot deserialization", |input| {
+                self.slot_deserializer.deserialize(input)
+            }),
+        ))
+        .map(|(new_elements, updates, change_id)| StreamBatch::<Slot> {
+            new_elements: new_elements.into_iter().collect(),
+            updates_on_previous_elements: updates.into_iter().collect(),
+            change_id,
+        })
+        .parse(buffer)
+    }
 }
 
 impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer {
@@ -575,12 +872,135 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                     error: String::from_utf8_lossy(error).into_owned(),
                 })
                 .parse(input),
+                MessageServerTypeId::StateSplit => context(
+                    "Failed StateSplit deserialization",
+                    length_count(
+                        context("Failed length deserialization", |input| {
+                            self.state_split_length_deserializer.deserialize(input)
+                        }),
+                        |input| self.vec_u8_deserializer.deserialize(input),
+                    ),
+                )
+                .map(|keys| BootstrapServerMessage::StateSplit { keys })
+                .parse(input),
+                MessageServerTypeId::StateRangePart => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed part deserialization", |input| {
+                        self.deserialize_stream_batch(input)
+                    }),
+                    context("Failed hash deserialization", |input| {
+                        self.hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(slot, part, hash)| BootstrapServerMessage::StateRangePart {
+                        slot,
+                        part,
+                        hash,
+                    },
+                )
+                .parse(input),
+                MessageServerTypeId::Compressed => context(
+                    "Failed Compressed deserialization",
+                    length_data(context("Failed length deserialization", |input| {
+                        self.compressed_length_deserializer.deserialize(input)
+                    })),
+                )
+                .map(|data: &[u8]| BootstrapServerMessage::Compressed {
+                    data: data.to_vec(),
+                })
+                .parse(input),
+                MessageServerTypeId::LightState => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed state_hash deserialization", |input| {
+                        self.hash_deserializer.deserialize(input)
+                    }),
+                    context(
+                        "Failed headers deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.light_length_deserializer.deserialize(input)
+                            }),
+                            |input| self.header_deserializer.deserialize(input),
+                        ),
+                    ),
+                    context(
+                        "Failed selections deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.light_length_deserializer.deserialize(input)
+                            }),
+                            |input| self.deserialize_light_selection(input),
+                        ),
+                    ),
+                ))
+                .map(
+                    |(slot, state_hash, headers, selections)| BootstrapServerMessage::LightState {
+                        slot,
+                        state_hash,
+                        headers,
+                        selections,
+                    },
+                )
+                .parse(input),
+                MessageServerTypeId::StateHash => tuple((
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed state_hash deserialization", |input| {
+                        self.opt_hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(slot, state_hash)| BootstrapServerMessage::StateHash { slot, state_hash })
+                .parse(input),
+                MessageServerTypeId::ResyncStart => {
+                    context("Failed slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    })
+                    .map(|slot| BootstrapServerMessage::ResyncStart { slot })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
     }
 }
 
+/// Creators drawn for a slot, sent to light clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightSelection {
+    /// Slot of the selection
+    pub slot: Slot,
+    /// Block producer
+    pub producer: Address,
+    /// Endorsement creators, by endorsement index
+    pub endorsements: Vec<Address>,
+}
+
+/// Compresses a serialized server message
+pub(crate) fn compress_message(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    zstd::bulk::compress(bytes, ZSTD_COMPRESSION_LEVEL)
+}
+
+/// Decompresses a serialized server message, failing if it is larger than `MAX_BOOTSTRAP_MESSAGE_SIZE`
+pub(crate) fn decompress_message(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    zstd::stream::read::Decoder::new(data)?
+        .take(MAX_BOOTSTRAP_MESSAGE_SIZE as u64 + 1)
+        .read_to_end(&mut bytes)?;
+    if bytes.len() > MAX_BOOTSTRAP_MESSAGE_SIZE as usize {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "decompressed message too large",
+        ));
+    }
+    Ok(bytes)
+}
+
 /// Messages used during bootstrap by client
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
@@ -607,6 +1027,36 @@ pub enum BootstrapClientMessage {
     },
     /// Bootstrap succeed
     BootstrapSuccess,
+    /// Ask for the keys splitting the state in ranges
+    AskStateSplit {
+        /// Number of ranges wanted
+        count: u32,
+    },
+    /// Ask for the parts of a range of the state
+    AskStateRange {
+        /// Slot we are attached to for changes
+        last_slot: Option<Slot>,
+        /// First key of the range
+        start_key: Vec<u8>,
+        /// First key after the range, None for the last range
+        end_key: Option<Vec<u8>>,
+        /// Last received key of the range
+        last_step: StreamingStep<Vec<u8>>,
+    },
+    /// Ask for the final block headers, selections and state commitment of a light client
+    AskLightState,
+    /// Ask for the hash of the final state at a slot, to check a bootstrapped state
+    AskStateHash {
+        /// Slot of the bootstrapped final state
+        slot: Slot,
+    },
+    /// Ask to re-sync a final state kept across a restart, by streaming only the changes made since its slot
+    AskResync {
+        /// Slot of the local final state
+        last_slot: Slot,
+        /// Hash of the local final state, that must match the one of the server at `last_slot`
+        state_hash: Hash,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -616,6 +1066,11 @@ enum MessageClientTypeId {
     AskFinalStatePart = 1u32,
     BootstrapError = 2u32,
     BootstrapSuccess = 3u32,
+    AskStateSplit = 4u32,
+    AskStateRange = 5u32,
+    AskLightState = 6u32,
+    AskStateHash = 7u32,
+    AskResync = 8u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -628,6 +1083,10 @@ pub struct BootstrapClientMessageSerializer {
         PreHashSetSerializer<BlockId, BlockIdSerializer>,
     >,
     bool_serializer: BoolSerializer,
+    opt_slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+    vec_u8_serializer: VecU8Serializer,
+    opt_vec_u8_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    hash_serializer: HashSerializer,
 }
 
 impl BootstrapClientMessageSerializer {
@@ -641,6 +1100,10 @@ impl BootstrapClientMessageSerializer {
                 BlockIdSerializer::new(),
             )),
             bool_serializer: BoolSerializer::new(),
+            opt_slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+            vec_u8_serializer: VecU8Serializer::new(),
+            opt_vec_u8_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            hash_serializer: HashSerializer::new(),
         }
     }
 }
@@ -711,6 +1174,42 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::BootstrapSuccess), buffer)?;
             }
+            BootstrapClientMessage::AskStateSplit { count } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateSplit), buffer)?;
+                self.u32_serializer.serialize(count, buffer)?;
+            }
+            BootstrapClientMessage::AskStateRange {
+                last_slot,
+                start_key,
+                end_key,
+                last_step,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateRange), buffer)?;
+                self.opt_slot_serializer.serialize(last_slot, buffer)?;
+                self.vec_u8_serializer.serialize(start_key, buffer)?;
+                self.opt_vec_u8_serializer.serialize(end_key, buffer)?;
+                self.state_step_serializer.serialize(last_step, buffer)?;
+            }
+            BootstrapClientMessage::AskLightState => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskLightState), buffer)?;
+            }
+            BootstrapClientMessage::AskStateHash { slot } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskStateHash), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
+            BootstrapClientMessage::AskResync {
+                last_slot,
+                state_hash,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskResync), buffer)?;
+                self.slot_serializer.serialize(last_slot, buffer)?;
+                self.hash_serializer.serialize(state_hash, buffer)?;
+            }
         }
         Ok(())
     }
@@ -727,6 +1226,11 @@ pub struct BootstrapClientMessageDeserializer {
         PreHashSetDeserializer<BlockId, BlockIdDeserializer>,
     >,
     bool_deserializer: BoolDeserializer,
+    state_split_count_deserializer: U32VarIntDeserializer,
+    opt_slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+    key_deserializer: VecU8Deserializer,
+    opt_key_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    hash_deserializer: HashDeserializer,
 }
 
 impl BootstrapClientMessageDeserializer {
@@ -755,6 +1259,23 @@ impl BootstrapClientMessageDeserializer {
                 ),
             ),
             bool_deserializer: BoolDeserializer::new(),
+            state_split_count_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_STATE_RANGES),
+            ),
+            opt_slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
+            key_deserializer: VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            ),
+            opt_key_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(0),
+                Included(max_datastore_value_length as u64),
+            )),
+            hash_deserializer: HashDeserializer::new(),
         }
     }
 }
@@ -864,6 +1385,61 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::BootstrapSuccess => {
                     Ok((input, BootstrapClientMessage::BootstrapSuccess))
                 }
+                MessageClientTypeId::AskStateSplit => {
+                    context("Failed count deserialization", |input| {
+                        self.state_split_count_deserializer.deserialize(input)
+                    })
+                    .map(|count| BootstrapClientMessage::AskStateSplit { count })
+                    .parse(input)
+                }
+                MessageClientTypeId::AskStateRange => tuple((
+                    context("Failed last_slot deserialization", |input| {
+                        self.opt_slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed start_key deserialization", |input| {
+                        self.key_deserializer.deserialize(input)
+                    }),
+                    context("Failed end_key deserialization", |input| {
+                        self.opt_key_deserializer.deserialize(input)
+                    }),
+                    context("Failed last_step deserialization", |input| {
+                        self.state_step_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(last_slot, start_key, end_key, last_step)| {
+                    BootstrapClientMessage::AskStateRange {
+                        last_slot,
+                        start_key,
+                        end_key,
+                        last_step,
+                    }
+                })
+                .parse(input),
+                MessageClientTypeId::AskLightState => {
+                    Ok((input, BootstrapClientMessage::AskLightState))
+                }
+                MessageClientTypeId::AskStateHash => {
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|slot| BootstrapClientMessage::AskStateHash { slot })
+                    .parse(input)
+                }
+                MessageClientTypeId::AskResync => tuple((
+                    context("Failed last_slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                    context("Failed state_hash deserialization", |input| {
+                        self.hash_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(last_slot, state_hash)| BootstrapClientMessage::AskResync {
+                        last_slot,
+                        state_hash,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
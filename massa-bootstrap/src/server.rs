@@ -20,10 +20,13 @@
 //!
 //! 1. Checks if the stopper has been invoked.
 //! 2. Checks if the client is permited under the white/black list rules
-//! 3. Checks if there are not too many active sessions already
-//! 4. Checks if the client has attempted too recently
-//! 5. All checks have passed: spawn a thread on which to run the bootstrap session
-//!    This thread creates a new tokio runtime, and runs it with `block_on`
+//! 3. Checks if there are not too many sessions from the IP of the client already
+//! 4. Checks if there are not too many active and queued sessions already
+//! 5. Checks if the client has attempted too recently
+//! 6. All checks have passed: queue the connection until a session slot is available.
+//!    Connections waiting longer than `queue_timeout` are refused.
+//! 7. While slots are available, spawn a thread on which to run the next queued session,
+//!    picking the IPs with the fewest running sessions first so that a single IP cannot take all the slots
 mod white_black_list;
 
 use crossbeam::channel::tick;
@@ -41,9 +44,9 @@ use massa_protocol_exports::ProtocolController;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap, VecDeque},
     net::{IpAddr, SocketAddr},
     sync::Arc,
     thread,
@@ -53,17 +56,24 @@ use tracing::{debug, error, info, warn};
 use white_black_list::*;
 
 use crate::{
-    bindings::BootstrapServerBinder,
+    bindings::{BandwidthLimits, BootstrapServerBinder},
     error::BootstrapError,
     listener::{BootstrapListenerStopHandle, PollEvent},
-    messages::{BootstrapClientMessage, BootstrapServerMessage},
+    messages::{
+        compute_state_part_hash, next_state_range_step, BootstrapClientMessage,
+        BootstrapServerMessage, LightSelection,
+    },
     BootstrapConfig,
 };
 
+/// Delay between two checks of the queued connections, while some are waiting for a session slot
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Specifies a common interface that can be used by standard, or mockers
 #[cfg_attr(test, mockall::automock)]
 pub trait BSEventPoller {
-    fn poll(&mut self) -> Result<PollEvent, BootstrapError>;
+    /// Waits for new connections, at most `timeout` if any
+    fn poll(&mut self, timeout: Option<Duration>) -> Result<PollEvent, BootstrapError>;
 }
 /// Abstraction layer over data produced by the listener, and transported
 /// over to the worker via a channel
@@ -180,6 +190,10 @@ pub fn start_bootstrap_server<L: BSEventPoller + Send + 'static>(
                 keypair,
                 version,
                 ip_hist_map: HashMap::with_capacity(config.ip_list_max_size),
+                bandwidth_limits: BandwidthLimits::new(
+                    config.max_total_upload_bytes_per_second,
+                    config.max_total_download_bytes_per_second,
+                ),
                 bootstrap_config: config,
             }
             .event_loop(max_bootstraps)
@@ -204,6 +218,59 @@ struct BootstrapServer<'a, L: BSEventPoller> {
     bootstrap_config: BootstrapConfig,
     version: Version,
     ip_hist_map: HashMap<IpAddr, Instant>,
+    /// bandwidth limits shared by all the sessions
+    bandwidth_limits: BandwidthLimits,
+}
+
+/// Connection waiting for a session slot
+struct QueuedSession {
+    binder: BootstrapServerBinder,
+    remote_addr: SocketAddr,
+    /// arrival time of the connection
+    since: Instant,
+}
+
+/// Number of running sessions, by IP
+#[derive(Clone, Default)]
+struct ActiveSessions(Arc<Mutex<HashMap<IpAddr, usize>>>);
+
+impl ActiveSessions {
+    /// Number of running sessions
+    fn total(&self) -> usize {
+        self.0.lock().values().sum()
+    }
+
+    /// Number of running sessions from `ip`
+    fn of(&self, ip: &IpAddr) -> usize {
+        self.0.lock().get(ip).copied().unwrap_or_default()
+    }
+
+    /// Accounts for a new session from `ip`, until the returned token is dropped
+    fn start(&self, ip: IpAddr) -> SessionToken {
+        *self.0.lock().entry(ip).or_default() += 1;
+        SessionToken {
+            sessions: self.clone(),
+            ip,
+        }
+    }
+}
+
+/// Slot of a running session, freed when dropped
+struct SessionToken {
+    sessions: ActiveSessions,
+    ip: IpAddr,
+}
+
+impl Drop for SessionToken {
+    fn drop(&mut self) {
+        let mut sessions = self.sessions.0.lock();
+        if let Some(count) = sessions.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                sessions.remove(&self.ip);
+            }
+        }
+    }
 }
 
 impl<L: BSEventPoller> BootstrapServer<'_, L> {
@@ -222,20 +289,29 @@ impl<L: BSEventPoller> BootstrapServer<'_, L> {
                         Err(e) => return Err(BootstrapError::GeneralError(format!("update stopper error : {}", e))),
                     }
                 },
-                recv(ticker) -> _ => {list.update()?;},
+                recv(ticker) -> _ => {
+                    // a malformed list is reported, and the previous lists are kept until the file changes again
+                    if let Err(e) = list.update() {
+                        warn!("failed to reload the bootstrap white/black lists: {}", e);
+                    }
+                },
             }
         }
     }
 
     fn event_loop(mut self, max_bootstraps: usize) -> Result<(), BootstrapError> {
-        // Use the strong-count of this variable to track the session count
-        let bootstrap_sessions_counter: Arc<()> = Arc::new(());
+        let sessions = ActiveSessions::default();
+        let mut queue: VecDeque<QueuedSession> = VecDeque::new();
         let per_ip_min_interval = self.bootstrap_config.per_ip_min_interval.to_duration();
+        let max_sessions_per_ip = self.bootstrap_config.max_sessions_per_ip as usize;
+        let max_queued_sessions = self.bootstrap_config.max_queued_sessions as usize;
+        let queue_timeout = self.bootstrap_config.queue_timeout.to_duration();
         // TODO: Work out how to integration-test this
         loop {
-            // block until we have a connection to work with, or break out of main-loop
-
-            let connections = match self.ev_poller.poll() {
+            // block until we have a connection to work with, or break out of main-loop.
+            // While connections are queued, wake up regularly to start them once slots are freed.
+            let poll_timeout = (!queue.is_empty()).then_some(QUEUE_POLL_INTERVAL);
+            let connections = match self.ev_poller.poll(poll_timeout) {
                 Ok(PollEvent::Stop) => return Ok(()),
                 Ok(PollEvent::NewConnections(connections)) => connections,
                 Err(e) => {
@@ -245,12 +321,12 @@ impl<L: BSEventPoller> BootstrapServer<'_, L> {
             };
 
             for (dplx, remote_addr) in connections {
-                // claim a slot in the max_bootstrap_sessions
-                let server_binding = BootstrapServerBinder::new(
+                let mut server_binding = BootstrapServerBinder::new(
                     dplx,
                     self.keypair.clone(),
                     (&self.bootstrap_config).into(),
                 );
+                server_binding.set_global_limits(self.bandwidth_limits.clone());
 
                 // check whether incoming peer IP is allowed.
                 if let Err(error_msg) = self.white_black_list.is_ip_allowed(&remote_addr) {
@@ -262,92 +338,144 @@ impl<L: BSEventPoller> BootstrapServer<'_, L> {
                     continue;
                 };
 
-                // the `- 1` is to account for the top-level Arc that is created at the top
-                // of this method. subsequent counts correspond to each `clone` that is passed
-                // into a thread
-                // TODO: If we don't find a way to handle the counting automagically, make
-                //       a dedicated wrapper-type with doc-comments, manual drop impl that
-                //       integrates logging, etc...
-                if Arc::strong_count(&bootstrap_sessions_counter) - 1 < max_bootstraps {
-                    massa_trace!("bootstrap.lib.run.select.accept", {
-                        "remote_addr": remote_addr
-                    });
-                    let now = Instant::now();
-
-                    // clear IP history if necessary
-                    if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                        self.ip_hist_map
-                            .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
-                        if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
-                            // too many IPs are spamming us: clear cache
-                            warn!("high bootstrap load: at least {} different IPs attempted bootstrap in the last {}", self.ip_hist_map.len(),format_duration(self.bootstrap_config.per_ip_min_interval.to_duration()).to_string());
-                            self.ip_hist_map.clear();
-                        }
-                    }
-
-                    // check IP's bootstrap attempt history
-                    if let Err(msg) = BootstrapServer::<L>::greedy_client_check(
-                        &mut self.ip_hist_map,
-                        remote_addr,
-                        now,
-                        per_ip_min_interval,
-                    ) {
-                        // Client has been too greedy: send out the bad-news :(
-                        let msg = format!(
-                            "Your last bootstrap on this server was {} ago and you have to wait {} before retrying.",
-                            format_duration(msg),
-                            format_duration(per_ip_min_interval.saturating_sub(msg))
+                // check the sessions of the IP, queued ones included
+                if max_sessions_per_ip > 0 {
+                    let queued = queue
+                        .iter()
+                        .filter(|queued| queued.remote_addr.ip() == remote_addr.ip())
+                        .count();
+                    if sessions.of(&remote_addr.ip()) + queued >= max_sessions_per_ip {
+                        server_binding.close_and_send_error(
+                            "Bootstrap failed because too many bootstrap sessions are already open from your IP.".to_string(),
+                            remote_addr,
+                            move || debug!("did not bootstrap {}: too many sessions from its IP", remote_addr),
                         );
-                        let tracer = move || {
-                            massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
-                                "remote_addr": remote_addr
-                            })
-                        };
-                        server_binding.close_and_send_error(msg, remote_addr, tracer);
                         continue;
-                    };
+                    }
+                }
 
-                    // Clients Option<last-attempt> is good, and has been updated
-                    massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
-
-                    // launch bootstrap
-                    let version = self.version;
-                    let data_execution = self.final_state.clone();
-                    let consensus_command_sender = self.consensus_controller.clone();
-                    let protocol_controller = self.protocol_controller.clone();
-                    let config = self.bootstrap_config.clone();
-
-                    let bootstrap_count_token = bootstrap_sessions_counter.clone();
-
-                    let _ = thread::Builder::new()
-                        .name(format!("bootstrap thread, peer: {}", remote_addr))
-                        .spawn(move || {
-                            run_bootstrap_session(
-                                server_binding,
-                                bootstrap_count_token,
-                                config,
-                                remote_addr,
-                                data_execution,
-                                version,
-                                consensus_command_sender,
-                                protocol_controller,
-                            )
-                        });
-
-                    massa_trace!("bootstrap.session.started", {
-                        "active_count": Arc::strong_count(&bootstrap_sessions_counter) - 1
-                    });
-                } else {
+                // claim a slot in the max_bootstrap_sessions, or in the queue
+                if sessions.total() + queue.len() >= max_bootstraps + max_queued_sessions {
                     server_binding.close_and_send_error(
                         "Bootstrap failed because the bootstrap server currently has no slots available.".to_string(),
                         remote_addr,
                         move || debug!("did not bootstrap {}: no available slots", remote_addr),
                     );
+                    continue;
                 }
+                massa_trace!("bootstrap.lib.run.select.accept", {
+                    "remote_addr": remote_addr
+                });
+                let now = Instant::now();
+
+                // clear IP history if necessary
+                if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
+                    self.ip_hist_map
+                        .retain(|_k, v| now.duration_since(*v) <= per_ip_min_interval);
+                    if self.ip_hist_map.len() > self.bootstrap_config.ip_list_max_size {
+                        // too many IPs are spamming us: clear cache
+                        warn!("high bootstrap load: at least {} different IPs attempted bootstrap in the last {}", self.ip_hist_map.len(),format_duration(self.bootstrap_config.per_ip_min_interval.to_duration()).to_string());
+                        self.ip_hist_map.clear();
+                    }
+                }
+
+                // check IP's bootstrap attempt history
+                if let Err(msg) = BootstrapServer::<L>::greedy_client_check(
+                    &mut self.ip_hist_map,
+                    remote_addr,
+                    now,
+                    per_ip_min_interval,
+                ) {
+                    // Client has been too greedy: send out the bad-news :(
+                    let msg = format!(
+                        "Your last bootstrap on this server was {} ago and you have to wait {} before retrying.",
+                        format_duration(msg),
+                        format_duration(per_ip_min_interval.saturating_sub(msg))
+                    );
+                    let tracer = move || {
+                        massa_trace!("bootstrap.lib.run.select.accept.refuse_limit", {
+                            "remote_addr": remote_addr
+                        })
+                    };
+                    server_binding.close_and_send_error(msg, remote_addr, tracer);
+                    continue;
+                };
+
+                // Clients Option<last-attempt> is good, and has been updated
+                massa_trace!("bootstrap.lib.run.select.accept.cache_available", {});
+                queue.push_back(QueuedSession {
+                    binder: server_binding,
+                    remote_addr,
+                    since: now,
+                });
+            }
+
+            // refuse the connections that waited too long for a slot, they can retry right away
+            let now = Instant::now();
+            while let Some(index) = queue
+                .iter()
+                .position(|queued| now.duration_since(queued.since) > queue_timeout)
+            {
+                let queued = queue
+                    .remove(index)
+                    .expect("queued session index out of range");
+                self.ip_hist_map.remove(&queued.remote_addr.ip());
+                let remote_addr = queued.remote_addr;
+                queued.binder.close_and_send_error(
+                    "Bootstrap failed because no bootstrap slot was freed in time.".to_string(),
+                    remote_addr,
+                    move || debug!("did not bootstrap {}: queue timeout", remote_addr),
+                );
+            }
+
+            // start the queued connections while slots are available:
+            // the IPs with the fewest running sessions first, then in their order of arrival
+            while sessions.total() < max_bootstraps {
+                let Some(index) = (0..queue.len())
+                    .min_by_key(|index| (sessions.of(&queue[*index].remote_addr.ip()), *index)) else {
+                    break;
+                };
+                let queued = queue
+                    .remove(index)
+                    .expect("queued session index out of range");
+                let session_token = sessions.start(queued.remote_addr.ip());
+                self.start_session(queued.binder, queued.remote_addr, session_token);
+                massa_trace!("bootstrap.session.started", {
+                    "active_count": sessions.total()
+                });
             }
         }
     }
 
+    /// Runs a bootstrap session on a dedicated thread
+    fn start_session(
+        &self,
+        server_binding: BootstrapServerBinder,
+        remote_addr: SocketAddr,
+        session_token: SessionToken,
+    ) {
+        let version = self.version;
+        let data_execution = self.final_state.clone();
+        let consensus_command_sender = self.consensus_controller.clone();
+        let protocol_controller = self.protocol_controller.clone();
+        let config = self.bootstrap_config.clone();
+
+        let _ = thread::Builder::new()
+            .name(format!("bootstrap thread, peer: {}", remote_addr))
+            .spawn(move || {
+                run_bootstrap_session(
+                    server_binding,
+                    session_token,
+                    config,
+                    remote_addr,
+                    data_execution,
+                    version,
+                    consensus_command_sender,
+                    protocol_controller,
+                )
+            });
+    }
+
     /// Checks latest attempt. If too recent, provides the bad news (as an error).
     /// Updates the latest attempt to "now" if it's all good.
     ///
@@ -382,12 +510,11 @@ impl<L: BSEventPoller> BootstrapServer<'_, L> {
 /// a multi-thread-aware tokio runtime (the bs-main-loop runtime, to be exact). When this
 /// function blocks in the `block_on`, it should thread-block, and switch to another session
 ///
-/// The arc_counter variable is used as a proxy to keep track the number of active bootstrap
-/// sessions.
+/// The session token keeps the slot of the session until it is dropped.
 #[allow(clippy::too_many_arguments)]
 fn run_bootstrap_session(
     mut server: BootstrapServerBinder,
-    arc_counter: Arc<()>,
+    session_token: SessionToken,
     config: BootstrapConfig,
     remote_addr: SocketAddr,
     data_execution: Arc<RwLock<FinalState>>,
@@ -411,9 +538,9 @@ fn run_bootstrap_session(
     // This drop allows the server to accept new connections before having to complete the error notifications
     // account for this session being finished, as well as the root-instance
     massa_trace!("bootstrap.session.finished", {
-        "sessions_remaining": Arc::strong_count(&arc_counter) - 2
+        "sessions_remaining": session_token.sessions.total() - 1
     });
-    drop(arc_counter);
+    drop(session_token);
     match res {
         Err(BootstrapError::TimedOut(_)) => {
             debug!("bootstrap timeout for peer {}", remote_addr);
@@ -466,7 +593,14 @@ pub fn stream_bootstrap_information(
         let last_start_period;
         let last_slot_before_downtime;
 
-        let slot_too_old = false;
+        // The changes since the client cursor must still be in our history, otherwise the client has to start over
+        let slot_too_old = match last_slot {
+            Some(slot) => !final_state.read().db.read().can_stream_changes_since(&slot),
+            None => false,
+        };
+        if slot_too_old {
+            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+        }
 
         // Scope of the final state read
         {
@@ -590,10 +724,6 @@ pub fn stream_bootstrap_information(
             send_last_start_period = false;
         }
 
-        if slot_too_old {
-            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
-        }
-
         // Setup final state global cursor
         let final_state_global_step =
             if last_state_step.finished() && last_versioning_step.finished() {
@@ -656,6 +786,130 @@ pub fn stream_bootstrap_information(
     Ok(())
 }
 
+/// Streams a range of the state to a client downloading the state from several servers at once,
+/// up to the end of the range, then sends `BootstrapFinished`.
+///
+/// For a range that was already streamed, only the changes made on its keys since `last_slot` are sent.
+#[allow(clippy::too_many_arguments)]
+pub fn stream_state_range(
+    server: &mut BootstrapServerBinder,
+    final_state: Arc<RwLock<FinalState>>,
+    mut last_slot: Option<Slot>,
+    start_key: Vec<u8>,
+    end_key: Option<Vec<u8>>,
+    mut last_step: StreamingStep<Vec<u8>>,
+    bs_deadline: &Instant,
+    write_timeout: Duration,
+) -> Result<(), BootstrapError> {
+    loop {
+        // The changes since the client cursor must still be in our history, otherwise the client has to start over
+        let slot_too_old = match last_slot {
+            Some(slot) => !final_state.read().db.read().can_stream_changes_since(&slot),
+            None => false,
+        };
+        if slot_too_old {
+            return server.send_msg(write_timeout, BootstrapServerMessage::SlotTooOld);
+        }
+
+        let (slot, part) = {
+            let final_state_read = final_state.read();
+            let db = final_state_read.db.read();
+            let part = db
+                .get_range_batch_to_stream(&start_key, end_key.as_deref(), &last_step, last_slot)
+                .map_err(|e| {
+                    BootstrapError::GeneralError(format!("Error get_range_batch_to_stream: {}", e))
+                })?;
+            (db.get_change_id().expect(CHANGE_ID_DESER_ERROR), part)
+        };
+        last_step = next_state_range_step(&last_step, &part);
+        last_slot = Some(slot);
+
+        let hash = compute_state_part_hash(&part)?;
+        let Some(part_write_timeout) = step_timeout_duration(bs_deadline, &write_timeout) else {
+            return Err(BootstrapError::Interupted("insufficient time left to provide next state range part".to_string()));
+        };
+        server.send_msg(
+            part_write_timeout,
+            BootstrapServerMessage::StateRangePart { slot, part, hash },
+        )?;
+
+        if last_step.finished() {
+            return server.send_msg(write_timeout, BootstrapServerMessage::BootstrapFinished);
+        }
+    }
+}
+
+/// Sends the headers of the final blocks, the creators drawn for their slots and the hash of the final state to a light client.
+///
+/// Only the `max_headers` most recent headers are sent.
+fn send_light_state(
+    server: &mut BootstrapServerBinder,
+    final_state: &Arc<RwLock<FinalState>>,
+    consensus_controller: &dyn ConsensusController,
+    max_headers: usize,
+    write_timeout: Duration,
+) -> Result<(), BootstrapError> {
+    let (slot, state_hash) = {
+        let final_state_read = final_state.read();
+        let db = final_state_read.db.read();
+        (
+            db.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+            db.get_db_hash(),
+        )
+    };
+
+    let mut headers = Vec::new();
+    let mut cursor = StreamingStep::Started;
+    loop {
+        let (graph, _outdated_ids, new_cursor) =
+            consensus_controller.get_bootstrap_part(cursor, StreamingStep::Finished(Some(slot)))?;
+        headers.extend(
+            graph
+                .final_blocks
+                .into_iter()
+                .map(|block| block.block.content.header),
+        );
+        if new_cursor.finished() {
+            break;
+        }
+        cursor = new_cursor;
+    }
+    headers.sort_unstable_by_key(|header| header.content.slot);
+    headers.drain(..headers.len().saturating_sub(max_headers));
+
+    let selections = {
+        let final_state_read = final_state.read();
+        headers
+            .iter()
+            .map(|header| header.content.slot)
+            .collect::<BTreeSet<Slot>>()
+            .into_iter()
+            .filter_map(|slot| {
+                let selection = final_state_read
+                    .pos_state
+                    .selector
+                    .get_selection(slot)
+                    .ok()?;
+                Some(LightSelection {
+                    slot,
+                    producer: selection.producer,
+                    endorsements: selection.endorsements,
+                })
+            })
+            .collect()
+    };
+
+    server.send_msg(
+        write_timeout,
+        BootstrapServerMessage::LightState {
+            slot,
+            state_hash,
+            headers,
+            selections,
+        },
+    )
+}
+
 // derives the duration allowed for a step in the bootstrap process.
 // Returns None if the deadline for the entire bs-process has been reached
 fn step_timeout_duration(bs_deadline: &Instant, step_timeout: &Duration) -> Option<Duration> {
@@ -755,6 +1009,79 @@ fn manage_bootstrap(
                         bootstrap_config.write_timeout.to_duration(),
                     )?;
                 }
+                BootstrapClientMessage::AskStateSplit { count } => {
+                    let Some(write_timeout) = step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration()) else {
+                        return Err(BootstrapError::Interupted("insufficient time left to respond to the request for the state split".to_string()));
+                    };
+                    let keys = final_state
+                        .read()
+                        .db
+                        .read()
+                        .get_state_split_keys(count as usize);
+                    server.send_msg(write_timeout, BootstrapServerMessage::StateSplit { keys })?;
+                }
+                BootstrapClientMessage::AskStateRange {
+                    last_slot,
+                    start_key,
+                    end_key,
+                    last_step,
+                } => {
+                    stream_state_range(
+                        server,
+                        final_state.clone(),
+                        last_slot,
+                        start_key,
+                        end_key,
+                        last_step,
+                        &deadline,
+                        bootstrap_config.write_timeout.to_duration(),
+                    )?;
+                }
+                BootstrapClientMessage::AskLightState => {
+                    let Some(write_timeout) = step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration()) else {
+                        return Err(BootstrapError::Interupted("insufficient time left to respond to the request for the light state".to_string()));
+                    };
+                    send_light_state(
+                        server,
+                        &final_state,
+                        consensus_controller.as_ref(),
+                        bootstrap_config.max_bootstrap_blocks_length as usize,
+                        write_timeout,
+                    )?;
+                }
+                BootstrapClientMessage::AskStateHash { slot } => {
+                    let Some(write_timeout) = step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration()) else {
+                        return Err(BootstrapError::Interupted("insufficient time left to respond to the request for the state hash".to_string()));
+                    };
+                    let state_hash = final_state
+                        .read()
+                        .db
+                        .read()
+                        .hash_history
+                        .get(&slot)
+                        .copied();
+                    server.send_msg(
+                        write_timeout,
+                        BootstrapServerMessage::StateHash { slot, state_hash },
+                    )?;
+                }
+                BootstrapClientMessage::AskResync {
+                    last_slot,
+                    state_hash,
+                } => {
+                    let Some(write_timeout) = step_timeout_duration(&deadline, &bootstrap_config.write_timeout.to_duration()) else {
+                        return Err(BootstrapError::Interupted("insufficient time left to respond to the re-sync request".to_string()));
+                    };
+                    // the changes since `last_slot` can only be streamed if the state of the client is the one the server had at that slot
+                    let resync = final_state.read().db.read().hash_history.get(&last_slot)
+                        == Some(&state_hash);
+                    server.send_msg(
+                        write_timeout,
+                        BootstrapServerMessage::ResyncStart {
+                            slot: resync.then_some(last_slot),
+                        },
+                    )?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
@@ -1,13 +1,21 @@
+//! White and black lists of the bootstrap server.
+//!
+//! The lists are JSON arrays of IP addresses or CIDR ranges (`["203.0.113.7", "10.0.0.0/8", "2001:db8::/32"]`).
+//! They are reloaded without restarting the node once their files change, including when they are edited
+//! through the private API.
+
 use std::{
     borrow::Cow,
     collections::HashSet,
-    net::{IpAddr, SocketAddr},
+    net::SocketAddr,
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 
 use crate::error::BootstrapError;
 use massa_logging::massa_trace;
+use massa_models::ip_range::IpRange;
 use parking_lot::RwLock;
 use tracing::{info, warn};
 
@@ -20,6 +28,8 @@ pub(crate) struct SharedWhiteBlackList<'a> {
     inner: Arc<RwLock<WhiteBlackListInner>>,
     white_path: Cow<'a, Path>,
     black_path: Cow<'a, Path>,
+    /// last modification times of the white and black list files, when they were last loaded
+    modified: (Option<SystemTime>, Option<SystemTime>),
 }
 
 impl SharedWhiteBlackList<'_> {
@@ -30,14 +40,23 @@ impl SharedWhiteBlackList<'_> {
                 white_list,
                 black_list,
             })),
+            modified: (modified_time(&white_path), modified_time(&black_path)),
             white_path: Cow::from(white_path),
             black_path: Cow::from(black_path),
         })
     }
 
-    /// Checks if the white/black list is up to date with a read-lock
+    /// Checks if the white/black list files changed since they were loaded
     /// Creates a new list, and replaces the old one in a write-lock
     pub(crate) fn update(&mut self) -> Result<(), BootstrapError> {
+        let modified = (
+            modified_time(&self.white_path),
+            modified_time(&self.black_path),
+        );
+        if modified == self.modified {
+            return Ok(());
+        }
+        self.modified = modified;
         let read_lock = self.inner.read();
         let (new_white, new_black) =
             WhiteBlackListInner::update_list(&self.white_path, &self.black_path)?;
@@ -70,11 +89,11 @@ impl SharedWhiteBlackList<'_> {
         let ip = normalize_ip(remote_addr.ip());
         // whether the peer IP address is blacklisted
         let read = self.inner.read();
-        if let Some(ip_list) = &read.black_list && ip_list.contains(&ip) {
+        if let Some(ip_list) = &read.black_list && ip_list.iter().any(|range| range.contains(&ip)) {
             massa_trace!("bootstrap.lib.run.select.accept.refuse_blacklisted", {"remote_addr": remote_addr});
             Err(BootstrapError::BlackListed(ip.to_string()))
             // whether the peer IP address is not present in the whitelist
-        } else if let Some(ip_list) = &read.white_list && !ip_list.contains(&ip) {
+        } else if let Some(ip_list) = &read.white_list && !ip_list.iter().any(|range| range.contains(&ip)) {
             massa_trace!("bootstrap.lib.run.select.accept.refuse_not_whitelisted", {"remote_addr": remote_addr});
             Err(BootstrapError::WhiteListed(ip.to_string()))
         } else {
@@ -88,7 +107,7 @@ impl WhiteBlackListInner {
     fn update_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<HashSet<IpRange>>, Option<HashSet<IpRange>>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, false)?,
             Self::load_list(blacklist_path, false)?,
@@ -99,7 +118,7 @@ impl WhiteBlackListInner {
     fn init_list(
         whitelist_path: &Path,
         blacklist_path: &Path,
-    ) -> Result<(Option<HashSet<IpAddr>>, Option<HashSet<IpAddr>>), BootstrapError> {
+    ) -> Result<(Option<HashSet<IpRange>>, Option<HashSet<IpRange>>), BootstrapError> {
         Ok((
             Self::load_list(whitelist_path, true)?,
             Self::load_list(blacklist_path, true)?,
@@ -109,7 +128,7 @@ impl WhiteBlackListInner {
     fn load_list(
         list_path: &Path,
         is_init: bool,
-    ) -> Result<Option<HashSet<IpAddr>>, BootstrapError> {
+    ) -> Result<Option<HashSet<IpRange>>, BootstrapError> {
         match std::fs::read_to_string(list_path) {
             Err(e) => {
                 if is_init {
@@ -123,16 +142,12 @@ impl WhiteBlackListInner {
             }
             Ok(list) => {
                 let res = Some(
-                    serde_json::from_str::<HashSet<IpAddr>>(list.as_str())
-                        .map_err(|e| {
-                            BootstrapError::InitListError(format!(
-                                "Failed to parse bootstrap whitelist : {}",
-                                e
-                            ))
-                        })?
-                        .into_iter()
-                        .map(normalize_ip)
-                        .collect(),
+                    serde_json::from_str::<HashSet<IpRange>>(list.as_str()).map_err(|e| {
+                        BootstrapError::InitListError(format!(
+                            "Failed to parse bootstrap whitelist : {}",
+                            e
+                        ))
+                    })?,
                 );
                 Ok(res)
             }
@@ -142,6 +157,13 @@ impl WhiteBlackListInner {
 
 #[derive(Default)]
 pub(crate) struct WhiteBlackListInner {
-    white_list: Option<HashSet<IpAddr>>,
-    black_list: Option<HashSet<IpAddr>>,
+    white_list: Option<HashSet<IpRange>>,
+    black_list: Option<HashSet<IpRange>>,
+}
+
+/// Returns the last modification time of a file, None if it does not exist
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
 }
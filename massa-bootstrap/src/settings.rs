@@ -1,7 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
 use massa_models::block::BlockDeserializerArgs;
 use massa_models::node::NodeId;
+use massa_models::slot::Slot;
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::{net::SocketAddr, path::PathBuf};
@@ -55,14 +57,44 @@ pub struct BootstrapConfig {
     pub cache_duration: MassaTime,
     /// Keep ledger or not if not bootstrap
     pub keep_ledger: bool,
+    /// Keep the local final state and only ask the bootstrap server for the changes since its last slot.
+    /// The starting point is negotiated with the server, which checks the hash of the local final state at that slot.
+    /// Falls back to a full bootstrap if the server no longer has these changes or if the local final state diverged.
+    pub differential_sync: bool,
+    /// File where the client keeps the progress of an ongoing bootstrap, to resume it after an interruption
+    pub checkpoint_path: PathBuf,
+    /// Number of bootstrap servers the state is downloaded from at once, 1 to download it from a single server
+    pub parallel_download_servers: u32,
+    /// Number of other servers of the bootstrap list that must attest the hash of the bootstrapped final state,
+    /// more than the servers attesting another hash, 0 to trust the bootstrap server
+    pub state_attestations: u32,
+    /// Number of times the servers of the bootstrap list are asked to attest the bootstrapped final state before the bootstrap fails
+    pub state_attestation_attempts: u32,
+    /// Slot and hash of a final state known out-of-band, that the bootstrapped final state must match
+    /// at its slot or at an earlier slot of its hash history
+    pub trusted_state_hash: Option<(Slot, Hash)>,
     /// Max simultaneous bootstraps
     pub max_simultaneous_bootstraps: u32,
+    /// Max simultaneous bootstraps from a given IP, queued ones included (0 for no limit)
+    pub max_sessions_per_ip: u32,
+    /// Max number of connections waiting for a bootstrap slot, 0 to refuse them when all the slots are taken
+    pub max_queued_sessions: u32,
+    /// Max time a connection waits for a bootstrap slot before being refused
+    pub queue_timeout: MassaTime,
+    /// Minimum rate at which a client must read the bootstrap data, in bytes per second, measured while the server waits on it (0 for no minimum)
+    pub min_session_bytes_per_second: f64,
     /// Minimum interval between two bootstrap attempts from a given IP
     pub per_ip_min_interval: MassaTime,
     /// Max size of the IP list
     pub ip_list_max_size: usize,
-    /// Read-Write limitation for a connection in bytes per seconds
+    /// Read-Write limitation for a connection in bytes per seconds, in each direction (0 or infinity for no limit)
     pub max_bytes_read_write: f64,
+    /// Upload limitation of all the connections together in bytes per seconds (0 or infinity for no limit)
+    pub max_total_upload_bytes_per_second: f64,
+    /// Download limitation of all the connections together in bytes per seconds (0 or infinity for no limit)
+    pub max_total_download_bytes_per_second: f64,
+    /// Compress the state and consensus parts with zstd when the other side supports it
+    pub stream_compression: bool,
     /// thread count
     pub thread_count: u8,
     /// period per cycle
@@ -134,11 +166,14 @@ pub struct BootstrapConfig {
 }
 
 /// Bootstrap server binding
+#[cfg(feature = "server")]
 #[allow(missing_docs)]
 #[derive(Debug, Deserialize, Clone, SubStruct)]
 #[parent(type = "BootstrapConfig")]
 pub struct BootstrapSrvBindCfg {
     pub max_bytes_read_write: f64,
+    pub min_session_bytes_per_second: f64,
+    pub stream_compression: bool,
     pub thread_count: u8,
     pub max_datastore_key_length: u8,
     pub randomness_size_bytes: usize,
@@ -152,6 +187,7 @@ pub struct BootstrapSrvBindCfg {
 #[parent(type = "BootstrapConfig")]
 pub struct BootstrapClientConfig {
     pub max_bytes_read_write: f64,
+    pub stream_compression: bool,
     pub endorsement_count: u32,
     pub max_listeners_per_peer: u32,
     pub max_advertise_length: u32,
@@ -0,0 +1,220 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::bindings::{BandwidthLimits, BootstrapServerBinder};
+use crate::client::attestation::attest_state_hash;
+use crate::messages::{BootstrapClientMessage, BootstrapServerMessage};
+use crate::settings::BootstrapSrvBindCfg;
+use crate::tests::tools::get_bootstrap_config;
+use crate::{BootstrapConfig, BootstrapError, DefaultConnector};
+use massa_hash::Hash;
+use massa_models::config::{
+    BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CONSENSUS_BOOTSTRAP_PART_SIZE, MAX_DATASTORE_KEY_LENGTH,
+    THREAD_COUNT,
+};
+use massa_models::{node::NodeId, slot::Slot, version::Version};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use std::net::{SocketAddr, TcpListener};
+use std::str::FromStr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+/// Starts a bootstrap server answering `connections` requests for the state hash with `state_hash`.
+/// Returns its address and node id, and the thread to join once the client is done.
+fn start_witness(
+    state_hash: Option<Hash>,
+    connections: usize,
+) -> ((SocketAddr, NodeId), JoinHandle<()>) {
+    let keypair = KeyPair::generate(0).unwrap();
+    let node_id = NodeId::new(keypair.get_public_key());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let handle = std::thread::Builder::new()
+        .name("test_attestation::witness".to_string())
+        .spawn(move || {
+            for _ in 0..connections {
+                let mut server = BootstrapServerBinder::new(
+                    listener.accept().unwrap().0,
+                    keypair.clone(),
+                    BootstrapSrvBindCfg {
+                        max_bytes_read_write: f64::INFINITY,
+                        min_session_bytes_per_second: 0.0,
+                        stream_compression: true,
+                        thread_count: THREAD_COUNT,
+                        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+                        randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+                        consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+                        write_error_timeout: MassaTime::from_millis(1000),
+                    },
+                );
+                server
+                    .handshake_timeout(Version::from_str("TEST.1.10").unwrap(), None)
+                    .unwrap();
+                let slot = match server.next_timeout(None).unwrap() {
+                    BootstrapClientMessage::AskStateHash { slot } => slot,
+                    _ => panic!("expected a request for the state hash"),
+                };
+                server
+                    .send_timeout(BootstrapServerMessage::StateHash { slot, state_hash }, None)
+                    .unwrap();
+                assert!(matches!(
+                    server.next_timeout(None).unwrap(),
+                    BootstrapClientMessage::BootstrapSuccess
+                ));
+            }
+        })
+        .unwrap();
+    ((addr, node_id), handle)
+}
+
+fn attest(
+    bootstrap_config: &BootstrapConfig,
+    bootstrap_list: &[(SocketAddr, NodeId)],
+    sources: &[SocketAddr],
+    state_hash: Hash,
+) -> Result<(), BootstrapError> {
+    attest_state_hash(
+        bootstrap_config,
+        &mut DefaultConnector,
+        bootstrap_list,
+        sources,
+        &BandwidthLimits::unlimited(),
+        Version::from_str("TEST.1.10").unwrap(),
+        Slot::new(10, 0),
+        state_hash,
+        &Arc::new((Mutex::new(false), Condvar::new())),
+    )
+}
+
+fn attestation_config(state_attestations: u32) -> BootstrapConfig {
+    let keypair = KeyPair::generate(0).unwrap();
+    BootstrapConfig {
+        state_attestations,
+        state_attestation_attempts: 2,
+        retry_delay: MassaTime::from_millis(10),
+        ..get_bootstrap_config(NodeId::new(keypair.get_public_key()))
+    }
+}
+
+#[test]
+fn test_state_attested_by_witnesses() {
+    let state_hash = Hash::compute_from(b"final state");
+    let (source, source_thread) = start_witness(Some(state_hash), 0);
+    let (witness_1, witness_1_thread) = start_witness(Some(state_hash), 1);
+    let (witness_2, witness_2_thread) = start_witness(Some(state_hash), 1);
+    let bootstrap_list = vec![source, witness_1, witness_2];
+
+    // the source is not asked to attest its own state
+    attest(
+        &attestation_config(2),
+        &bootstrap_list,
+        &[source.0],
+        state_hash,
+    )
+    .expect("the state should be attested");
+    for thread in [source_thread, witness_1_thread, witness_2_thread] {
+        thread.join().unwrap();
+    }
+
+    // too few witnesses in the bootstrap list
+    assert!(attest(
+        &attestation_config(3),
+        &bootstrap_list,
+        &[source.0],
+        state_hash
+    )
+    .is_err());
+
+    // nor are the servers that sent ranges of the state
+    assert!(attest(
+        &attestation_config(2),
+        &bootstrap_list,
+        &[source.0, witness_1.0],
+        state_hash
+    )
+    .is_err());
+}
+
+#[test]
+fn test_forged_state_rejected_by_witness() {
+    let state_hash = Hash::compute_from(b"final state");
+    let forged_hash = Hash::compute_from(b"forged final state");
+    let (source, _) = start_witness(Some(forged_hash), 0);
+    let (witness, witness_thread) = start_witness(Some(state_hash), 1);
+
+    let res = attest(
+        &attestation_config(1),
+        &[source, witness],
+        &[source.0],
+        forged_hash,
+    );
+    assert!(matches!(res, Err(BootstrapError::GeneralError(_))));
+    witness_thread.join().unwrap();
+}
+
+#[test]
+fn test_dissenting_witness_outvoted_by_quorum() {
+    let state_hash = Hash::compute_from(b"final state");
+    let forged_hash = Hash::compute_from(b"forged final state");
+    let (source, _) = start_witness(Some(state_hash), 0);
+    let (witness_1, witness_1_thread) = start_witness(Some(state_hash), 1);
+    let (dissenter, dissenter_thread) = start_witness(Some(forged_hash), 1);
+    let (witness_2, witness_2_thread) = start_witness(Some(state_hash), 1);
+
+    // a single server attesting another hash does not fail the bootstrap
+    attest(
+        &attestation_config(2),
+        &[source, witness_1, dissenter, witness_2],
+        &[source.0],
+        state_hash,
+    )
+    .expect("the state should be attested by the quorum");
+    for thread in [witness_1_thread, dissenter_thread, witness_2_thread] {
+        thread.join().unwrap();
+    }
+
+    // but the quorum must outnumber the servers attesting another hash
+    let (witness, witness_thread) = start_witness(Some(state_hash), 1);
+    let (dissenter_1, dissenter_1_thread) = start_witness(Some(forged_hash), 1);
+    let (dissenter_2, dissenter_2_thread) = start_witness(Some(forged_hash), 1);
+    let res = attest(
+        &attestation_config(1),
+        &[source, dissenter_1, dissenter_2, witness],
+        &[source.0],
+        state_hash,
+    );
+    assert!(matches!(res, Err(BootstrapError::GeneralError(_))));
+    for thread in [witness_thread, dissenter_1_thread, dissenter_2_thread] {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn test_attestation_attempts_bounded() {
+    let state_hash = Hash::compute_from(b"final state");
+    let (source, _) = start_witness(Some(state_hash), 0);
+
+    // a witness that no longer has the slot of the state in its history is asked once per attempt
+    let (witness, witness_thread) = start_witness(None, 2);
+    let res = attest(
+        &attestation_config(1),
+        &[source, witness],
+        &[source.0],
+        state_hash,
+    );
+    assert!(matches!(res, Err(BootstrapError::GeneralError(_))));
+    witness_thread.join().unwrap();
+
+    // an unreachable witness does not make the bootstrap wait forever
+    let unreachable = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    };
+    let res = attest(
+        &attestation_config(1),
+        &[source, (unreachable, witness.1)],
+        &[source.0],
+        state_hash,
+    );
+    assert!(matches!(res, Err(BootstrapError::GeneralError(_))));
+}
@@ -2,7 +2,7 @@ use crate::messages::{BootstrapClientMessage, BootstrapServerMessage};
 use crate::settings::{BootstrapClientConfig, BootstrapSrvBindCfg};
 use crate::BootstrapConfig;
 use crate::{
-    bindings::{BootstrapClientBinder, BootstrapServerBinder},
+    bindings::{BootstrapClientBinder, BootstrapServerBinder, Throttle},
     tests::tools::get_bootstrap_config,
     BootstrapPeers,
 };
@@ -25,6 +25,7 @@ use massa_time::MassaTime;
 use std::collections::HashMap;
 use std::net::TcpStream;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 lazy_static::lazy_static! {
     pub static ref BOOTSTRAP_CONFIG_KEYPAIR: (BootstrapConfig, KeyPair) = {
@@ -37,6 +38,7 @@ impl BootstrapClientBinder {
     pub fn test_default(client_duplex: TcpStream, remote_pubkey: PublicKey) -> Self {
         let cfg = BootstrapClientConfig {
             max_bytes_read_write: f64::INFINITY,
+            stream_compression: true,
             max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
             endorsement_count: ENDORSEMENT_COUNT,
             max_advertise_length: MAX_ADVERTISE_LENGTH,
@@ -82,6 +84,8 @@ fn test_binders() {
         server_keypair.clone(),
         BootstrapSrvBindCfg {
             max_bytes_read_write: f64::INFINITY,
+            min_session_bytes_per_second: 0.0,
+            stream_compression: true,
             thread_count: THREAD_COUNT,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
@@ -232,6 +236,8 @@ fn test_binders_double_send_server_works() {
         server_keypair.clone(),
         BootstrapSrvBindCfg {
             max_bytes_read_write: f64::INFINITY,
+            min_session_bytes_per_second: 0.0,
+            stream_compression: true,
             thread_count: THREAD_COUNT,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
@@ -363,6 +369,8 @@ fn test_binders_try_double_send_client_works() {
         server_keypair.clone(),
         BootstrapSrvBindCfg {
             max_bytes_read_write: f64::INFINITY,
+            min_session_bytes_per_second: 0.0,
+            stream_compression: true,
             thread_count: THREAD_COUNT,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
@@ -484,3 +492,20 @@ fn test_binders_try_double_send_client_works() {
     server_thread.join().unwrap();
     client_thread.join().unwrap();
 }
+
+/// Writes are paced by the upload limit once the first second of transfer is spent
+#[test]
+fn test_throttle_upload() {
+    let throttle = Throttle::new(100_000.0);
+    let mut sink = Vec::new();
+    let start = Instant::now();
+    throttle.write_all(&mut sink, &vec![0u8; 150_000]).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert_eq!(sink.len(), 150_000);
+
+    // no limit
+    let throttle = Throttle::new(0.0);
+    let start = Instant::now();
+    throttle.write_all(&mut sink, &vec![0u8; 150_000]).unwrap();
+    assert!(start.elapsed() < Duration::from_millis(400));
+}
@@ -1,5 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+mod attestation;
 mod binders;
+mod parallel;
 mod scenarios;
 pub(crate) mod tools;
@@ -0,0 +1,65 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::client::parallel::StateRanges;
+use massa_models::{slot::Slot, streaming_step::StreamingStep};
+
+#[test]
+fn test_state_ranges_split() {
+    let ranges = StateRanges::new(vec![vec![3], vec![7]]);
+    let bounds: Vec<(Vec<u8>, Option<Vec<u8>>)> = ranges
+        .ranges
+        .iter()
+        .map(|range| (range.start_key.clone(), range.end_key.clone()))
+        .collect();
+    assert_eq!(
+        bounds,
+        vec![
+            (vec![], Some(vec![3])),
+            (vec![3], Some(vec![7])),
+            (vec![7], None)
+        ]
+    );
+    assert_eq!(StateRanges::new(Vec::new()).ranges.len(), 1);
+}
+
+#[test]
+fn test_state_ranges_refresh_and_completion() {
+    let mut ranges = StateRanges::new(vec![vec![3], vec![7]]);
+
+    // the ranges not downloaded yet are picked first, one server per range
+    assert_eq!(ranges.pick(), Some(0));
+    assert_eq!(ranges.pick(), Some(1));
+    assert_eq!(ranges.pick(), Some(2));
+    assert_eq!(ranges.pick(), None);
+
+    // an interrupted range is picked again from its cursor
+    ranges.update(1, StreamingStep::Ongoing(vec![4]), Slot::new(1, 0));
+    ranges.release(1);
+    assert_eq!(ranges.pick(), Some(1));
+    ranges.update(1, StreamingStep::Finished(None), Slot::new(2, 0));
+    ranges.release(1);
+    ranges.update(0, StreamingStep::Finished(None), Slot::new(1, 1));
+    ranges.release(0);
+    assert!(ranges.target_slot.is_none());
+    assert!((ranges.done_fraction() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+    // once all the ranges are downloaded, they are refreshed up to the slot of the last one, oldest first
+    ranges.update(2, StreamingStep::Finished(None), Slot::new(3, 0));
+    ranges.release(2);
+    assert_eq!(ranges.target_slot, Some(Slot::new(3, 0)));
+    assert_eq!(ranges.done_fraction(), 1.0);
+    assert!(ranges.completed_slot().is_none());
+    assert_eq!(ranges.pick(), Some(0));
+    assert_eq!(ranges.pick(), Some(1));
+    assert_eq!(ranges.pick(), None);
+    ranges.update(0, StreamingStep::Finished(None), Slot::new(3, 1));
+    ranges.release(0);
+    // a range being refreshed is not complete
+    assert!(ranges.completed_slot().is_none());
+    ranges.update(1, StreamingStep::Finished(None), Slot::new(3, 1));
+    ranges.release(1);
+
+    // the state is attached to the oldest slot of its ranges
+    assert_eq!(ranges.pick(), None);
+    assert_eq!(ranges.completed_slot(), Some(Slot::new(3, 0)));
+}
@@ -30,7 +30,7 @@ use massa_models::config::{
 };
 use massa_models::{
     address::Address, config::MAX_DATASTORE_VALUE_LENGTH, node::NodeId, slot::Slot,
-    streaming_step::StreamingStep, version::Version,
+    stats::BootstrapPhase, streaming_step::StreamingStep, version::Version,
 };
 use massa_models::{
     config::{
@@ -300,7 +300,7 @@ fn test_bootstrap_server() {
 
         final_write
             .pos_state
-            .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
+            .apply_changes_to_batch(changes.pos_changes.clone(), next, false, 0, &mut batch)
             .unwrap();
         final_write
             .ledger
@@ -440,7 +440,7 @@ fn test_bootstrap_server() {
 
                 final_write
                     .pos_state
-                    .apply_changes_to_batch(changes.pos_changes.clone(), next, false, &mut batch)
+                    .apply_changes_to_batch(changes.pos_changes.clone(), next, false, 0, &mut batch)
                     .unwrap();
                 final_write
                     .ledger
@@ -479,6 +479,7 @@ fn test_bootstrap_server() {
         .unwrap();
 
     // launch the get_state process
+    let (progress_tx, progress_rx) = crossbeam::channel::unbounded();
     let bootstrap_res = get_state(
         bootstrap_config,
         final_state_client_clone,
@@ -489,9 +490,13 @@ fn test_bootstrap_server() {
             .saturating_sub(MassaTime::from_millis(1000)),
         None,
         None,
+        Some(progress_tx),
         Arc::new((Mutex::new(false), Condvar::new())),
     )
     .unwrap();
+    let last_progress = progress_rx.try_iter().last().unwrap();
+    assert_eq!(last_progress.phase, BootstrapPhase::Finished);
+    assert!(last_progress.received_bytes > 0);
 
     // Make sure the modifier thread has done its job
     mod_thread.join().unwrap();
@@ -571,13 +576,13 @@ fn conn_establishment_mocks() -> (MockBSEventPoller, MockBSConnector) {
         .expect_poll()
         .times(1)
         // Mock the `accept` method here by receiving from the listen-loop thread
-        .returning(move || Ok(PollEvent::NewConnections(vec![conn_rx.recv().unwrap()])))
+        .returning(move |_| Ok(PollEvent::NewConnections(vec![conn_rx.recv().unwrap()])))
         .in_sequence(&mut seq);
     mock_bs_listener
         .expect_poll()
         .times(1)
         // Mock the `accept` method here by receiving from the listen-loop thread
-        .returning(move || Ok(PollEvent::Stop))
+        .returning(move |_| Ok(PollEvent::Stop))
         .in_sequence(&mut seq);
 
     let mut seq = Sequence::new();
@@ -173,6 +173,7 @@ fn get_random_pos_state(r_limit: u64, mut pos: PoSFinalState) -> PoSFinalState {
         roll_changes: roll_counts.into_iter().collect(),
         production_stats,
         deferred_credits,
+        delegation_changes: Default::default(),
     };
 
     let mut batch = DBBatch::new();
@@ -185,7 +186,7 @@ fn get_random_pos_state(r_limit: u64, mut pos: PoSFinalState) -> PoSFinalState {
 
     let mut batch = DBBatch::new();
 
-    pos.apply_changes_to_batch(changes, Slot::new(0, 0), false, &mut batch)
+    pos.apply_changes_to_batch(changes, Slot::new(0, 0), false, 0, &mut batch)
         .expect("Critical: Error while applying changes to pos_state");
 
     pos.db
@@ -204,6 +205,7 @@ pub fn get_random_pos_changes(r_limit: u64) -> PoSChanges {
         roll_changes: roll_counts.into_iter().collect(),
         production_stats,
         deferred_credits,
+        delegation_changes: Default::default(),
     }
 }
 
@@ -389,6 +391,15 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
             bootstrap_public_key,
         )],
         keep_ledger: false,
+        differential_sync: false,
+        checkpoint_path: tempfile::TempDir::new()
+            .unwrap()
+            .into_path()
+            .join("bootstrap_checkpoint"),
+        parallel_download_servers: 1,
+        state_attestations: 0,
+        state_attestation_attempts: 3,
+        trusted_state_hash: None,
         bootstrap_whitelist_path: PathBuf::from(
             "../massa-node/base_config/bootstrap_whitelist.json",
         ),
@@ -398,9 +409,16 @@ pub fn get_bootstrap_config(bootstrap_public_key: NodeId) -> BootstrapConfig {
         max_clock_delta: MassaTime::from_millis(1000),
         cache_duration: MassaTime::from_millis(10000),
         max_simultaneous_bootstraps: 2,
+        max_sessions_per_ip: 0,
+        max_queued_sessions: 0,
+        queue_timeout: MassaTime::from_millis(10000),
+        min_session_bytes_per_second: 0.0,
         ip_list_max_size: 10,
         per_ip_min_interval: MassaTime::from_millis(10000),
         max_bytes_read_write: std::f64::INFINITY,
+        max_total_upload_bytes_per_second: std::f64::INFINITY,
+        max_total_download_bytes_per_second: std::f64::INFINITY,
+        stream_compression: true,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -0,0 +1,162 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! massa-cipher detached encryption module.
+//!
+//! Unlike [`crate::encrypt`], the key derivation parameters and the nonce are not prepended to the
+//! encrypted data but kept apart from it, so that they can be stored in a self-describing
+//! format such as a keystore file. Associated data, e.g. a file header, can be authenticated
+//! along with the encrypted data.
+//!
+//! The key is derived from the password with Argon2id (RFC 9106), or with PBKDF2-HMAC-SHA256
+//! for the formats that already use it.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::Argon2;
+use pbkdf2::password_hash::{PasswordHasher, SaltString};
+use pbkdf2::{Params, Pbkdf2};
+use rand::{thread_rng, RngCore};
+
+use crate::constants::NONCE_SIZE;
+use crate::error::CipherError;
+
+/// Size of the random salt of a detached encryption, in bytes
+const DETACHED_SALT_SIZE: usize = 16;
+
+/// Size of the derived AES-256 key, in bytes
+pub const KEY_LENGTH: usize = 32;
+
+/// Key derivation function of a detached encryption, with its cost parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// PBKDF2-HMAC-SHA256
+    Pbkdf2HmacSha256 {
+        /// number of rounds
+        rounds: u32,
+    },
+    /// Argon2id, version 0x13
+    Argon2id {
+        /// memory cost, in KiB
+        memory_kib: u32,
+        /// number of passes over the memory
+        iterations: u32,
+        /// number of lanes
+        parallelism: u32,
+    },
+}
+
+/// Parameters of an AES-256-GCM encryption under a key derived from a password
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DetachedParams {
+    /// key derivation function
+    pub kdf: Kdf,
+    /// KDF salt, encoded in unpadded base64 as in the PHC string format
+    pub salt: String,
+    /// AES-GCM nonce
+    pub nonce: Vec<u8>,
+}
+
+impl DetachedParams {
+    /// Generates a random salt and nonce, the key being derived with `kdf`
+    pub fn generate(kdf: Kdf) -> Result<Self, CipherError> {
+        let mut salt_bytes = [0u8; DETACHED_SALT_SIZE];
+        thread_rng().fill_bytes(&mut salt_bytes);
+        let salt = SaltString::b64_encode(&salt_bytes)
+            .map_err(|e| CipherError::EncryptionError(e.to_string()))?;
+        let mut nonce = vec![0u8; NONCE_SIZE];
+        thread_rng().fill_bytes(&mut nonce);
+        Ok(DetachedParams {
+            kdf,
+            salt: salt.as_str().to_string(),
+            nonce,
+        })
+    }
+
+    /// Derives the AES-256 key of a password
+    fn cipher(&self, password: &str) -> Result<Aes256Gcm, String> {
+        if self.nonce.len() != NONCE_SIZE {
+            return Err(format!(
+                "invalid nonce size {}, expected {}",
+                self.nonce.len(),
+                NONCE_SIZE
+            ));
+        }
+        let salt = SaltString::new(&self.salt).map_err(|e| e.to_string())?;
+        let key = match self.kdf {
+            Kdf::Pbkdf2HmacSha256 { rounds } => {
+                let params = Params {
+                    rounds,
+                    output_length: KEY_LENGTH,
+                };
+                let hash = Pbkdf2
+                    .hash_password_customized(password.as_bytes(), None, None, params, &salt)
+                    .map_err(|e| e.to_string())?
+                    .hash
+                    .expect("content is missing after a successful hash");
+                hash.as_bytes().to_vec()
+            }
+            Kdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                let params =
+                    argon2::Params::new(memory_kib, iterations, parallelism, Some(KEY_LENGTH))
+                        .map_err(|e| e.to_string())?;
+                let mut salt_bytes = [0u8; DETACHED_SALT_SIZE];
+                let salt_bytes = salt
+                    .as_salt()
+                    .b64_decode(&mut salt_bytes)
+                    .map_err(|e| e.to_string())?;
+                let mut key = vec![0u8; KEY_LENGTH];
+                Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                    .hash_password_into(password.as_bytes(), salt_bytes, &mut key)
+                    .map_err(|e| e.to_string())?;
+                key
+            }
+        };
+        Ok(Aes256Gcm::new_from_slice(&key).expect("invalid key length"))
+    }
+}
+
+/// Encrypts `data`, authenticating `associated_data` along with it.
+///
+/// The result ends with the 16 bytes AES-GCM authentication tag.
+pub fn encrypt_detached(
+    password: &str,
+    params: &DetachedParams,
+    data: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    params
+        .cipher(password)
+        .map_err(CipherError::EncryptionError)?
+        .encrypt(
+            Nonce::from_slice(&params.nonce),
+            Payload {
+                msg: data,
+                aad: associated_data,
+            },
+        )
+        .map_err(|e| CipherError::EncryptionError(e.to_string()))
+}
+
+/// Decrypts data encrypted by [`encrypt_detached`] with the same parameters and associated data.
+pub fn decrypt_detached(
+    password: &str,
+    params: &DetachedParams,
+    data: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    params
+        .cipher(password)
+        .map_err(CipherError::DecryptionError)?
+        .decrypt(
+            Nonce::from_slice(&params.nonce),
+            Payload {
+                msg: data,
+                aad: associated_data,
+            },
+        )
+        .map_err(|_| CipherError::DecryptionError("wrong password or corrupted data".to_string()))
+}
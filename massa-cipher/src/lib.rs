@@ -10,13 +10,20 @@
 //! To hash the password before using it as a cipher key, we use the `PBKDF2` key derivation function
 //! as specified in [RFC 2898](https://datatracker.ietf.org/doc/html/rfc2898).
 //!
+//! [`encrypt_detached`] and [`decrypt_detached`] use the same cipher with a caller-chosen key derivation,
+//! `Argon2id` or `PBKDF2`, keep the parameters separately from the encrypted data and authenticate
+//! associated data along with it.
+//!
 //! The AES-GCM crate we use has received one security audit by NCC Group, with no significant findings.
 
 mod constants;
 mod decrypt;
+mod detached;
 mod encrypt;
 mod error;
 
+pub use constants::NONCE_SIZE;
 pub use decrypt::decrypt;
+pub use detached::{decrypt_detached, encrypt_detached, DetachedParams, Kdf, KEY_LENGTH};
 pub use encrypt::encrypt;
 pub use error::CipherError;
@@ -0,0 +1,113 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Human-readable aliases of addresses.
+//!
+//! The aliases are stored in the `address_book_file_path` file of the client configuration
+//! and can be used instead of an address in any command parameter expecting one.
+
+use crate::settings::SETTINGS;
+use anyhow::{anyhow, bail, Result};
+use massa_models::address::Address;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Aliases of addresses, sorted by alias
+#[derive(Debug, Serialize)]
+pub(crate) struct AddressBook {
+    /// addresses by alias
+    pub entries: BTreeMap<String, Address>,
+    /// file storing the address book
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl AddressBook {
+    /// Loads the address book of the client configuration, empty if the file does not exist yet
+    pub(crate) fn load() -> Result<AddressBook> {
+        Self::load_from(&SETTINGS.address_book_file_path)
+    }
+
+    /// Loads the address book stored at `path`, empty if the file does not exist yet
+    pub(crate) fn load_from(path: &Path) -> Result<AddressBook> {
+        let entries = if path.is_file() {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+            serde_json::from_str(&content)
+                .map_err(|e| anyhow!("invalid address book {}: {}", path.display(), e))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(AddressBook {
+            entries,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Writes the address book to its file
+    fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)
+            .map_err(|e| anyhow!("failed to write {}: {}", self.path.display(), e))
+    }
+
+    /// Adds or replaces an alias
+    pub(crate) fn add(&mut self, alias: &str, address: Address) -> Result<()> {
+        if alias.parse::<Address>().is_ok() {
+            bail!("alias {} is an address", alias);
+        }
+        if !alias.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            bail!(
+                "invalid alias {}: it must start with a letter and contain only letters, digits, '_' and '-'",
+                alias
+            );
+        }
+        self.entries.insert(alias.to_string(), address);
+        self.save()
+    }
+
+    /// Removes an alias
+    pub(crate) fn remove(&mut self, alias: &str) -> Result<()> {
+        if self.entries.remove(alias).is_none() {
+            bail!("alias {} not found", alias);
+        }
+        self.save()
+    }
+
+    /// Parses an address or the alias of one
+    pub(crate) fn resolve(&self, value: &str) -> Result<Address> {
+        match value.parse::<Address>() {
+            Ok(address) => Ok(address),
+            Err(e) => self
+                .entries
+                .get(value)
+                .copied()
+                .ok_or_else(|| anyhow!("failed to parse \"{}\" due to: {}", value, e)),
+        }
+    }
+
+    /// Describes an address with its alias if it has one, e.g. `alice (AU12...)`
+    pub(crate) fn describe(&self, address: &Address) -> String {
+        match self.entries.iter().find(|(_, addr)| *addr == address) {
+            Some((alias, _)) => format!("{} ({})", alias, address),
+            None => address.to_string(),
+        }
+    }
+}
+
+/// Parses an address or an alias of the address book
+pub(crate) fn parse_address(value: &str) -> Result<Address> {
+    AddressBook::load()?.resolve(value)
+}
+
+/// Parses a list of addresses or aliases of the address book
+pub(crate) fn parse_addresses(values: &[String]) -> Result<Vec<Address>> {
+    let address_book = AddressBook::load()?;
+    values
+        .iter()
+        .map(|value| address_book.resolve(value))
+        .collect()
+}
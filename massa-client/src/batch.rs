@@ -0,0 +1,123 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Batches of transactions: payment files read by `send_batch`, and outcomes of `sweep`.
+//!
+//! A payment file is either a JSON array of `{"recipient": ..., "amount": ...}` objects,
+//! or a CSV file of `recipient,amount` rows. In CSV files, empty lines, lines starting with `#`
+//! and a `recipient,amount` header are ignored. Recipients can be aliases of the address book.
+
+use crate::address_book::AddressBook;
+use anyhow::{anyhow, bail, Result};
+use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A payment of a batch
+#[derive(Debug, Deserialize)]
+struct PaymentRow {
+    /// recipient address or alias
+    recipient: String,
+    /// amount sent, in coins
+    amount: String,
+}
+
+/// A parsed payment of a batch
+#[derive(Debug)]
+pub(crate) struct Payment {
+    /// row of the payment in the file, starting at 1
+    pub row: usize,
+    /// recipient of the payment
+    pub recipient: Address,
+    /// amount sent
+    pub amount: Amount,
+}
+
+/// Outcome of a payment of a batch
+#[derive(Debug, Serialize)]
+pub(crate) struct PaymentResult {
+    /// row of the payment in the file, starting at 1
+    pub row: usize,
+    /// recipient of the payment
+    pub recipient: Address,
+    /// amount sent
+    pub amount: Amount,
+    /// id of the sent operation, if it was accepted by the node
+    pub operation_id: Option<OperationId>,
+    /// reason of the failure, if it was not
+    pub error: Option<String>,
+}
+
+/// Outcome of the transfer of the balance of an address by `sweep`
+#[derive(Debug, Serialize)]
+pub(crate) struct SweepResult {
+    /// swept address
+    pub address: Address,
+    /// amount transferred
+    pub amount: Amount,
+    /// fee paid
+    pub fee: Amount,
+    /// id of the sent operation, if it was accepted by the node
+    pub operation_id: Option<OperationId>,
+    /// reason of the failure, if it was not
+    pub error: Option<String>,
+}
+
+/// Reads the payments of a batch file, failing on the first invalid row
+pub(crate) fn read_payments(path: &Path, address_book: &AddressBook) -> Result<Vec<Payment>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+    let rows: Vec<(usize, PaymentRow)> = if content.trim_start().starts_with('[') {
+        serde_json::from_str::<Vec<PaymentRow>>(&content)
+            .map_err(|e| anyhow!("invalid batch file {}: {}", path.display(), e))?
+            .into_iter()
+            .enumerate()
+            .map(|(index, row)| (index + 1, row))
+            .collect()
+    } else {
+        csv_rows(&content)?
+    };
+    if rows.is_empty() {
+        bail!("batch file {} has no payment", path.display());
+    }
+    rows.into_iter()
+        .map(|(row, payment)| {
+            Ok(Payment {
+                row,
+                recipient: address_book
+                    .resolve(payment.recipient.trim())
+                    .map_err(|e| anyhow!("row {}: {}", row, e))?,
+                amount: payment
+                    .amount
+                    .trim()
+                    .parse::<Amount>()
+                    .map_err(|e| anyhow!("row {}: invalid amount: {}", row, e))?,
+            })
+        })
+        .collect()
+}
+
+/// Splits the `recipient,amount` rows of a CSV file
+fn csv_rows(content: &str) -> Result<Vec<(usize, PaymentRow)>> {
+    let mut rows = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.as_slice() == ["recipient", "amount"] {
+            continue;
+        }
+        let [recipient, amount] = fields.as_slice() else {
+            bail!("row {}: expected \"recipient,amount\"", index + 1);
+        };
+        rows.push((
+            index + 1,
+            PaymentRow {
+                recipient: recipient.to_string(),
+                amount: amount.to_string(),
+            },
+        ));
+    }
+    Ok(rows)
+}
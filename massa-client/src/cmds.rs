@@ -1,15 +1,31 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::address_book::{parse_address, parse_addresses, AddressBook};
+use crate::batch::{read_payments, PaymentResult, SweepResult};
+use crate::deploy::{
+    deployer_datastore, parse_hex, serialize_arg, DeployedContract, DEPLOYED_EVENT_PREFIX,
+};
 use crate::display::Output;
+use crate::offline::{read_file, write_file, SignedOperation, UnsignedOperation};
+use crate::rotation::{KeyRotation, RotationAction, RotationStage};
+use crate::settings::SETTINGS;
+use crate::staking::StakingInfo;
+use crate::watch::{watch_address, watch_blocks, watch_events};
 use crate::{client_warning, grpc_error, rpc_error};
 use anyhow::{anyhow, bail, Result};
 use console::style;
+use dialoguer::Password;
 use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
     datastore::DatastoreEntryInput,
-    execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
+    graph::GraphExportFormat,
+    node::{BanInput, BanTarget},
     operation::OperationInput,
 };
+use massa_models::config::{LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_COST, MAX_GAS_PER_BLOCK};
+use massa_models::datastore::DatastoreSerializer;
+use massa_models::ip_range::IpRange;
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -27,17 +43,23 @@ use massa_proto_rs::massa::model::v1 as grpc_model;
 use massa_sdk::Client;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
-use massa_wallet::Wallet;
+use massa_wallet::{AddressMetadata, HdWallet, Keystore, Signer, Wallet, HD_DEFAULT_GAP_LIMIT};
 
 use serde::Serialize;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write as _;
 use std::fmt::{Debug, Display};
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
 use strum_macros::{Display, EnumIter, EnumString};
 
+/// Number of slots within which the transactions of `sweep` should be included, to estimate their fee
+const SWEEP_FEE_TARGET_SLOTS: u64 = 10;
+
+/// Gas reserved by `deploy_sc`, in percent of the gas used by its read-only execution
+const DEPLOY_GAS_MARGIN_PERCENT: u64 = 120;
+
 /// All the client commands
 /// the order they are defined is the order they are displayed in so be careful
 /// Maybe it would be worth renaming some of them for consistency
@@ -60,6 +82,13 @@ pub enum Command {
     )]
     exit,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[GracePeriodMillis]", pwd_not_needed = "true"),
+        message = "put the gRPC API in drain mode with the given grace period. No args returns the drain progress"
+    )]
+    node_grpc_drain,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "IpAddr1 IpAddr2 ...", pwd_not_needed = "true"),
@@ -88,6 +117,30 @@ pub enum Command {
     )]
     node_ban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "DurationMillis|forever Reason Id|IpAddr1 Id|IpAddr2 ...",
+            pwd_not_needed = "true"
+        ),
+        message = "ban given id(s) and IP address(es) for the given duration, bans are kept across restarts"
+    )]
+    node_add_bans,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Id|IpAddr1 Id|IpAddr2 ...", pwd_not_needed = "true"),
+        message = "lift the bans of given id(s) and IP address(es)"
+    )]
+    node_remove_bans,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the bans in force"
+    )]
+    node_bans,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -125,18 +178,31 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add, remove or allow-all) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap whitelist IP address(es). No args returns the bootstrap whitelist"
+        props(
+            args = "(add, remove or allow-all) [IpAddr or CIDR range]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap whitelist IP address(es) and ranges. No args returns the bootstrap whitelist"
     )]
     node_bootstrap_whitelist,
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
-        message = "Manage bootstrap blacklist IP address(es). No args returns the bootstrap blacklist"
+        props(
+            args = "(add or remove) [IpAddr or CIDR range]",
+            pwd_not_needed = "true"
+        ),
+        message = "Manage bootstrap blacklist IP address(es) and ranges. No args returns the bootstrap blacklist"
     )]
     node_bootstrap_blacklist,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(add or remove) [Address]", pwd_not_needed = "true"),
+        message = "Manage the sender address(es) whose operations this node rejects from its pool and does not propagate (local policy only). No args returns the pool denylist"
+    )]
+    node_pool_denylist,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "(add or remove) [IpAddr]", pwd_not_needed = "true"),
@@ -172,6 +238,16 @@ pub enum Command {
     )]
     get_blocks,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "StartPeriod,StartThread EndPeriod,EndThread dot|graphml OutputFile(optional)",
+            pwd_not_needed = "true"
+        ),
+        message = "export the block graph of a slot range (end excluded) in DOT or GraphML, with the parent links, fitness and cliques of the blocks"
+    )]
+    export_block_graph,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "EndorsementId1 EndorsementId2 ...", pwd_not_needed = "true"),
@@ -237,6 +313,27 @@ pub enum Command {
     )]
     wallet_remove_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address PathToKeystore"),
+        message = "export the key of an address to a password-encrypted keystore file (versioned JSON, AES-256-GCM with PBKDF2-HMAC-SHA256, parameters embedded)"
+    )]
+    wallet_export_keystore,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToKeystore"),
+        message = "decrypt a keystore file and add its key to the wallet"
+    )]
+    wallet_import_keystore,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(init [Seed], derive [Count], or scan [GapLimit])"),
+        message = "Manage the hierarchical-deterministic accounts of the wallet: init adds a seed (random if not given), derive adds the next accounts, scan recovers the accounts of a seed until GapLimit (default 20) consecutive empty addresses. No args lists the derived accounts"
+    )]
+    wallet_hd,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address string"),
@@ -244,6 +341,41 @@ pub enum Command {
     )]
     wallet_sign,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address [Label]"),
+        message = "set the label of an address of the wallet, or remove it if no label is given"
+    )]
+    wallet_label,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address [Key [Value]]"),
+        message = "set a metadata field of an address of the wallet, remove it if no value is given, or list the metadata if no key is given"
+    )]
+    wallet_metadata,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[Address1 Address2 ...]"),
+        message = "show the rolls, next draws, deferred credits, missed blocks and estimated rewards of the given addresses, or of the wallet addresses by default"
+    )]
+    staking_info,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(start OldAddress Fee, continue Fee, or status)"),
+        message = "rotate a staking key: start generates a new key, registers it for staking and sells the rolls of the old address, continue moves the coins and buys rolls with the new address once possible, then retires the old key once the new rolls are active. No args or status shows the progress"
+    )]
+    rotate_staking_key,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "(add Alias Address, or remove Alias)", pwd_not_needed = "true"),
+        message = "Manage the aliases usable instead of addresses in the commands. No args lists the aliases"
+    )]
+    address_book,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address RollCount Fee"),
@@ -258,6 +390,20 @@ pub enum Command {
     )]
     sell_rolls,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address OperatorAddress Fee"),
+        message = "delegate the block production and endorsement of the rolls of a wallet address to an operator"
+    )]
+    delegate_stake,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address Fee"),
+        message = "revoke the stake delegation of a wallet address"
+    )]
+    revoke_stake_delegation,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress ReceiverAddress Amount Fee"),
@@ -265,6 +411,54 @@ pub enum Command {
     )]
     send_transaction,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "SenderAddress ReceiverAddress Amount Fee OutputFile ExpirePeriod",
+            pwd_not_needed = "true"
+        ),
+        message = "write an unsigned transaction to a file, to be signed with sign_file. ExpirePeriod is optional unless --offline is given, in which case the node is not contacted"
+    )]
+    create_transaction,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "UnsignedFile SignedFile"),
+        message = "sign an operation file written by create_transaction with a wallet address, without contacting the node"
+    )]
+    sign_file,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SignedFile", pwd_not_needed = "true"),
+        message = "send an operation file signed by sign_file"
+    )]
+    broadcast_file,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "blocks, events [--address Address] or address Address",
+            pwd_not_needed = "true"
+        ),
+        message = "print the new blocks, the final events or the balance changes of an address as they come, until CTRL+C (requires the grpc api)"
+    )]
+    watch,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "--to Address [--fee Fee] [Address1 Address2 ...]"),
+        message = "send the whole balance of the given wallet addresses, or of all the other wallet addresses by default, to the target address. The fee is estimated from the pool if not given"
+    )]
+    sweep,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "File SenderAddress Fee"),
+        message = "send the coins listed in a CSV (recipient,amount rows) or JSON file, one transaction per row with the given fee"
+    )]
+    send_batch,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress PathToBytecode MaxGas MaxCoins Fee"),
@@ -282,20 +476,29 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "PathToBytecode MaxGas Address IsFinal",
+            args = "SenderAddress PathToBytecode Fee [--coins Coins] [--max-gas MaxGas] [--deployer PathToDeployer] [--arg type:value ...] [--raw-args Hex]"
+        ),
+        message = "deploy a smart contract with the standard deployer, calling its constructor with the given arguments (types: string, address, bool, u8, u32, u64, i32, i64, f32, f64, bytes) and coins. The gas is estimated if not given"
+    )]
+    deploy_sc,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "PathToBytecode MaxGas Address IsFinal Fee",
             pwd_not_needed = "true"
         ),
-        message = "execute byte code, address is optional, is_final is optional. Nothing is really executed on chain"
+        message = "execute byte code, address is optional, is_final is optional, fee is optional and debited from the address. Nothing is really executed on chain"
     )]
     read_only_execute_smart_contract,
 
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "TargetAddress TargetFunction Parameter MaxGas SenderAddress IsFinal",
+            args = "TargetAddress TargetFunction Parameter MaxGas SenderAddress IsFinal Coins Fee",
             pwd_not_needed = "true"
         ),
-        message = "call a smart contract function, sender address is optional, is_final is optional. Nothing is really executed on chain"
+        message = "call a smart contract function as the sender, sender address is optional, is_final is optional, coins sent to the target and fee debited from the sender are optional. Nothing is really executed on chain"
     )]
     read_only_call,
 
@@ -338,6 +541,17 @@ pub enum ListOperation {
     Remove,
 }
 
+/// Help of a command, as listed by `help` in JSON mode
+#[derive(Debug, Serialize)]
+pub(crate) struct CommandHelp {
+    /// name of the command
+    pub name: String,
+    /// arguments of the command, if it takes some
+    pub args: Option<String>,
+    /// what the command does
+    pub description: String,
+}
+
 /// Used to have a shinny json output
 /// TODO re-factor me
 #[derive(Debug, Serialize)]
@@ -348,6 +562,8 @@ pub(crate) struct ExtendedWalletEntry {
     pub address_info: CompactAddressInfo,
     /// whether to display the public/secret keys or just the address info
     pub show_keys: bool,
+    /// label and metadata of the address
+    pub metadata: Option<AddressMetadata>,
 }
 
 impl Display for ExtendedWalletEntry {
@@ -384,6 +600,7 @@ impl ExtendedWallet {
                             keypair: keypair.clone(),
                             address_info: x.compact(),
                             show_keys,
+                            metadata: wallet.get_metadata(&x.address).cloned(),
                         },
                     ))
                 })
@@ -432,6 +649,15 @@ impl Command {
             && self.get_str("pwd_not_needed").unwrap() == "true")
     }
 
+    /// Help of the command as a structured entry, for the JSON output
+    pub(crate) fn help_entry(&self) -> CommandHelp {
+        CommandHelp {
+            name: self.to_string(),
+            args: self.get_str("args").map(str::to_string),
+            description: self.get_message().unwrap_or_default().to_string(),
+        }
+    }
+
     /// run a given command
     ///
     /// # parameters
@@ -449,23 +675,46 @@ impl Command {
     ) -> Result<Box<dyn Output>> {
         match self {
             Command::help => {
-                if !json {
-                    if !parameters.is_empty() {
-                        if let Ok(c) = parameters[0].parse::<Command>() {
-                            c.help();
-                        } else {
-                            println!(
-                                "Command not found!\ntype \"help\" to get the list of commands"
-                            );
-                            help();
-                        }
+                if json {
+                    let commands = match parameters.first() {
+                        Some(name) => match name.parse::<Command>() {
+                            Ok(c) => vec![c.help_entry()],
+                            Err(_) => bail!("command {} not found", name),
+                        },
+                        None => Command::iter().map(|c| c.help_entry()).collect(),
+                    };
+                    return Ok(Box::new(commands));
+                }
+                if !parameters.is_empty() {
+                    if let Ok(c) = parameters[0].parse::<Command>() {
+                        c.help();
                     } else {
+                        println!("Command not found!\ntype \"help\" to get the list of commands");
                         help();
                     }
+                } else {
+                    help();
                 }
                 Ok(Box::new(()))
             }
 
+            Command::node_grpc_drain => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let res = match parameters.first() {
+                    Some(grace_period) => {
+                        let grace_period = MassaTime::from_millis(grace_period.parse::<u64>()?);
+                        client.private.node_start_grpc_drain(grace_period).await
+                    }
+                    None => client.private.node_grpc_drain_status().await,
+                };
+                match res {
+                    Ok(drain_status) => Ok(Box::new(drain_status)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::node_unban_by_ip => {
                 let ips = parse_vec::<IpAddr>(parameters)?;
                 match client.private.node_unban_by_ip(ips).await {
@@ -518,6 +767,48 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_add_bans => {
+                if parameters.len() < 3 {
+                    bail!("wrong number of parameters");
+                }
+                let duration = match parameters[0].as_str() {
+                    "forever" => None,
+                    millis => Some(MassaTime::from_millis(millis.parse::<u64>()?)),
+                };
+                let bans = BanInput {
+                    targets: parse_ban_targets(&parameters[2..])?,
+                    duration,
+                    reason: Some(parameters[1].clone()),
+                };
+                match client.private.node_add_bans(bans).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Request of banning successfully sent!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_remove_bans => {
+                let targets = parse_ban_targets(parameters)?;
+                match client.private.node_remove_bans(targets).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Request of unbanning successfully sent!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_bans => match client.private.node_bans().await {
+                Ok(bans) => Ok(Box::new(bans)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::node_stop => {
                 match client.private.stop_node().await {
                     Ok(()) => {
@@ -544,7 +835,7 @@ impl Command {
                     bail!("wrong number of parameters");
                 }
                 // parse
-                let addr = parameters[0].parse::<Address>()?;
+                let addr = parse_address(&parameters[0])?;
                 let msg = parameters[1].as_bytes().to_vec();
                 // get address signature
                 if let Some(addr_sig) = wallet.sign_message(&addr, msg.clone()) {
@@ -576,7 +867,7 @@ impl Command {
             },
 
             Command::get_addresses => {
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
                 match client.public.get_addresses(addresses).await {
                     Ok(addresses_info) => Ok(Box::new(addresses_info)),
                     Err(e) => rpc_error!(e),
@@ -587,7 +878,7 @@ impl Command {
                 if parameters.len() != 2 {
                     bail!("invalid number of parameters");
                 }
-                let address = parameters[0].parse::<Address>()?;
+                let address = parse_address(&parameters[0])?;
                 let key = parameters[1].as_bytes().to_vec();
                 match client
                     .public
@@ -626,6 +917,36 @@ impl Command {
                 }
             }
 
+            Command::export_block_graph => {
+                if parameters.len() != 3 && parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let start = parameters[0].parse::<Slot>()?;
+                let end = parameters[1].parse::<Slot>()?;
+                let format = parameters[2].parse::<GraphExportFormat>().map_err(|_| {
+                    anyhow!("unknown format {}, expecting dot or graphml", parameters[2])
+                })?;
+                let graph = match client
+                    .public
+                    .get_block_graph_export(start, end, format)
+                    .await
+                {
+                    Ok(graph) => graph,
+                    Err(e) => rpc_error!(e),
+                };
+                match parameters.get(3) {
+                    Some(path) => {
+                        std::fs::write(path, graph)
+                            .map_err(|e| anyhow!("failed to write {}: {}", path, e))?;
+                        if !json {
+                            println!("Block graph written to {}", path);
+                        }
+                        Ok(Box::new(()))
+                    }
+                    None => Ok(Box::new(graph)),
+                }
+            }
+
             Command::get_filtered_sc_output_event => {
                 let p_list: [&str; 7] = [
                     "start",
@@ -685,10 +1006,33 @@ impl Command {
                 }
             }
 
+            Command::staking_info => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let addresses = if parameters.is_empty() {
+                    wallet.get_wallet_address_list().into_iter().collect()
+                } else {
+                    parse_addresses(parameters)?
+                };
+                let block_reward = match client.public.get_status().await {
+                    Ok(node_status) => node_status.config.block_reward,
+                    Err(e) => rpc_error!(e),
+                };
+                match client.public.get_addresses(addresses).await {
+                    Ok(addresses_info) => Ok(Box::new(
+                        addresses_info
+                            .iter()
+                            .map(|info| StakingInfo::new(info, block_reward))
+                            .collect::<Vec<_>>(),
+                    )),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::wallet_get_public_key => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
 
                 let hashset: HashSet<_> = addresses.into_iter().collect();
 
@@ -713,7 +1057,7 @@ impl Command {
                     client_warning!("do not share your secret key");
                 }
 
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
 
                 let hashset: HashSet<_> = addresses.into_iter().collect();
 
@@ -734,7 +1078,18 @@ impl Command {
             Command::node_start_staking => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
+                // staking requires the node to hold the secret key, which hardware signers never reveal
+                if let Some(addr) = addresses.iter().find(|addr| {
+                    wallet
+                        .get_signer(addr)
+                        .map_or(false, |signer| !signer.can_export_secret_key())
+                }) {
+                    bail!(
+                        "the key of {} is held by an external signer and cannot be used for staking",
+                        addr
+                    );
+                }
                 let secret: Vec<Option<&KeyPair>> = addresses
                     .iter()
                     .map(|addr| wallet.get_full_wallet().get(addr))
@@ -757,7 +1112,7 @@ impl Command {
             }
 
             Command::node_stop_staking => {
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
                 match client.private.remove_staking_addresses(addresses).await {
                     Ok(()) => {
                         if !json {
@@ -850,7 +1205,7 @@ impl Command {
                 let wallet = wallet_opt.as_mut().unwrap();
 
                 let mut res = "".to_string();
-                let addresses = parse_vec::<Address>(parameters)?;
+                let addresses = parse_addresses(parameters)?;
                 match wallet.remove_addresses(&addresses) {
                     Ok(_) => {
                         let _ = writeln!(res, "Addresses removed from the wallet");
@@ -865,76 +1220,466 @@ impl Command {
                 Ok(Box::new(()))
             }
 
-            Command::buy_rolls => {
+            Command::wallet_label => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                if parameters.len() != 3 {
+                if parameters.is_empty() || parameters.len() > 2 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
-                let roll_count = parameters[1].parse::<u64>()?;
-                let fee = parameters[2].parse::<Amount>()?;
-
+                let addr = parse_address(&parameters[0])?;
+                let label = parameters.get(1).cloned();
                 if !json {
-                    let roll_price = match client.public.get_status().await {
-                        Err(e) => bail!("RpcError: {}", e),
-                        Ok(status) => status.config.roll_price,
-                    };
-                    match roll_price
-                        .checked_mul_u64(roll_count)
-                        .and_then(|x| x.checked_add(fee))
-                    {
-                        Some(total) => {
-                            if let Ok(addresses_info) =
-                                client.public.get_addresses(vec![addr]).await
-                            {
-                                match addresses_info.get(0) {
-                                    Some(info) => {
-                                        if info.candidate_balance < total {
-                                            client_warning!("this operation may be rejected due to insufficient balance");
-                                        }
-                                    }
-                                    None => {
-                                        client_warning!(format!("address {} not found", addr))
-                                    }
-                                }
-                            }
-                        }
-                        None => {
-                            client_warning!("the total amount hit the limit overflow, operation will be rejected");
-                        }
-                    }
-                    if let Ok(staked_keys) = client.private.get_staking_addresses().await {
-                        if !staked_keys.contains(&addr) {
-                            client_warning!("You are buying rolls with an address not registered for staking. Don't forget to run 'node_start_staking <address>'");
-                        }
+                    match &label {
+                        Some(label) => println!("Labelled {} as {}", addr, label),
+                        None => println!("Removed the label of {}", addr),
                     }
                 }
-                send_operation(
-                    client,
-                    wallet,
-                    OperationType::RollBuy { roll_count },
-                    fee,
-                    addr,
-                    json,
-                )
-                .await
+                wallet.set_label(&addr, label)?;
+                Ok(Box::new(()))
             }
 
-            Command::sell_rolls => {
+            Command::wallet_metadata => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
-                if parameters.len() != 3 {
+                if parameters.is_empty() || parameters.len() > 3 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
-                let roll_count = parameters[1].parse::<u64>()?;
-                let fee = parameters[2].parse::<Amount>()?;
+                let addr = parse_address(&parameters[0])?;
+                if let Some(key) = parameters.get(1) {
+                    wallet.set_metadata_field(&addr, key.clone(), parameters.get(2).cloned())?;
+                }
+                match wallet.get_metadata(&addr) {
+                    Some(metadata) => Ok(Box::new(metadata.clone())),
+                    None => bail!("address {} is not in the wallet", addr),
+                }
+            }
+
+            Command::wallet_export_keystore => {
+                let wallet = wallet_opt.as_mut().unwrap();
 
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let path = PathBuf::from(&parameters[1]);
+                if path.exists() {
+                    bail!("{} already exists", path.display());
+                }
+                let password = Password::new()
+                    .with_prompt("Enter new password for the keystore")
+                    .with_confirmation("Confirm password", "Passwords mismatching")
+                    .interact()?;
+                let keystore = wallet.export_keystore(&addr, &password)?;
+                std::fs::write(&path, serde_json::to_string_pretty(&keystore)?)
+                    .map_err(|e| anyhow!("failed to write {}: {}", path.display(), e))?;
                 if !json {
-                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
-                        match addresses_info.get(0) {
-                            Some(info) => {
+                    println!("Exported the key of {} to {}", addr, path.display());
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_import_keystore => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let path = PathBuf::from(&parameters[0]);
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+                let keystore: Keystore = serde_json::from_str(&content)
+                    .map_err(|e| anyhow!("invalid keystore {}: {}", path.display(), e))?;
+                let password = Password::new()
+                    .with_prompt(format!(
+                        "Enter password of the keystore of {}",
+                        keystore.address
+                    ))
+                    .interact()?;
+                let address = wallet.import_keystore(&keystore, &password)?;
+                if json {
+                    return Ok(Box::new(address.to_string()));
+                }
+                println!("Imported address {} to the wallet.", address);
+                Ok(Box::new(()))
+            }
+
+            Command::wallet_hd => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let accounts: BTreeMap<u32, Address> = match parameters.first().map(String::as_str)
+                {
+                    None => match wallet.hd_wallet() {
+                        Some(hd) => hd.accounts.clone(),
+                        None => bail!("the wallet has no HD seed, use `wallet_hd init` to add one"),
+                    },
+                    Some("init") => {
+                        let seed = match parameters.get(1) {
+                            Some(seed) => Some(HdWallet::parse_seed(seed)?),
+                            None => None,
+                        };
+                        let seed = wallet.enable_hd(seed)?.seed_hex();
+                        if json {
+                            return Ok(Box::new(seed));
+                        }
+                        println!("HD seed: {}", seed);
+                        client_warning!("write down this seed: anyone knowing it controls all the HD accounts of the wallet");
+                        return Ok(Box::new(()));
+                    }
+                    Some("derive") => {
+                        let count = match parameters.get(1) {
+                            Some(count) => count.parse::<u32>()?,
+                            None => 1,
+                        };
+                        wallet.derive_hd_accounts(count)?.into_iter().collect()
+                    }
+                    Some("scan") => {
+                        let gap_limit = match parameters.get(1) {
+                            Some(gap_limit) => gap_limit.parse::<u32>()?,
+                            None => HD_DEFAULT_GAP_LIMIT,
+                        };
+                        if gap_limit == 0 {
+                            bail!("the gap limit must be positive");
+                        }
+                        // slide a window of gap_limit addresses after the last used one
+                        let mut from = 0;
+                        let mut used = Vec::new();
+                        loop {
+                            let window: HashMap<Address, u32> = wallet
+                                .hd_lookahead(from, gap_limit)?
+                                .into_iter()
+                                .map(|(index, address)| (address, index))
+                                .collect();
+                            let infos = match client
+                                .public
+                                .get_addresses(window.keys().copied().collect())
+                                .await
+                            {
+                                Ok(infos) => infos,
+                                Err(e) => rpc_error!(e),
+                            };
+                            let Some(last_used) = infos
+                                .iter()
+                                .filter(|info| is_address_used(info))
+                                .filter_map(|info| window.get(&info.address).copied())
+                                .inspect(|index| used.push(*index))
+                                .max()
+                            else {
+                                break;
+                            };
+                            from = last_used.saturating_add(1);
+                        }
+                        wallet.add_hd_accounts(&used)?;
+                        wallet
+                            .hd_wallet()
+                            .map(|hd| {
+                                hd.accounts
+                                    .iter()
+                                    .filter(|(index, _)| used.contains(index))
+                                    .map(|(index, address)| (*index, *address))
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    }
+                    Some(_) => {
+                        bail!("failed to parse operation, supported operations are: [init, derive, scan]")
+                    }
+                };
+                Ok(Box::new(accounts))
+            }
+
+            Command::rotate_staking_key => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let mut rotation = KeyRotation::load()?;
+                match parameters.first().map(String::as_str) {
+                    None | Some("status") => {}
+                    Some("start") => {
+                        if parameters.len() != 3 {
+                            bail!("wrong number of parameters");
+                        }
+                        if let Some(rotation) = &rotation {
+                            if rotation.stage != RotationStage::Done {
+                                bail!(
+                                    "the rotation of {} is in progress, use `rotate_staking_key continue`",
+                                    rotation.old_address
+                                );
+                            }
+                        }
+                        let old_address = parse_address(&parameters[1])?;
+                        let fee = parameters[2].parse::<Amount>()?;
+                        let version = wallet
+                            .find_associated_keypair(&old_address)
+                            .ok_or_else(|| anyhow!("address {} is not in the wallet", old_address))?
+                            .get_version();
+                        let old_info = match client.public.get_addresses(vec![old_address]).await {
+                            Ok(infos) => infos
+                                .into_iter()
+                                .next()
+                                .ok_or_else(|| anyhow!("address {} not found", old_address))?,
+                            Err(e) => rpc_error!(e),
+                        };
+
+                        let keypair = KeyPair::generate(version)?;
+                        let new_address = wallet.add_keypairs(vec![keypair.clone()])?[0];
+                        if let Err(e) = client
+                            .private
+                            .add_staking_secret_keys(vec![keypair.to_string()])
+                            .await
+                        {
+                            rpc_error!(e);
+                        }
+                        if !json {
+                            println!(
+                                "Generated {}, added it to the wallet and registered it for staking",
+                                new_address
+                            );
+                        }
+                        if old_info.candidate_roll_count > 0 {
+                            let signer = wallet.get_signer(&old_address).ok_or_else(|| {
+                                anyhow!("address {} is not in the wallet", old_address)
+                            })?;
+                            let operation_id = sign_and_send(
+                                client,
+                                &signer,
+                                old_address,
+                                fee,
+                                OperationType::RollSell {
+                                    roll_count: old_info.candidate_roll_count,
+                                },
+                            )
+                            .await?;
+                            if !json {
+                                println!(
+                                    "Selling the {} rolls of {}, operation ID: {}",
+                                    old_info.candidate_roll_count, old_address, operation_id
+                                );
+                            }
+                        }
+                        let started = KeyRotation {
+                            old_address,
+                            new_address,
+                            stage: RotationStage::SellingRolls,
+                        };
+                        started.save()?;
+                        rotation = Some(started);
+                    }
+                    Some("continue") => {
+                        if parameters.len() != 2 {
+                            bail!("wrong number of parameters");
+                        }
+                        let fee = parameters[1].parse::<Amount>()?;
+                        let Some(rotation) = rotation.as_mut() else {
+                            bail!("no rotation in progress, use `rotate_staking_key start`");
+                        };
+                        let infos = match client
+                            .public
+                            .get_addresses(vec![rotation.old_address, rotation.new_address])
+                            .await
+                        {
+                            Ok(infos) => infos,
+                            Err(e) => rpc_error!(e),
+                        };
+                        let [old_info, new_info] = infos.as_slice() else {
+                            bail!("the node did not return the info of both addresses");
+                        };
+                        let roll_price = match client.public.get_status().await {
+                            Ok(status) => status.config.roll_price,
+                            Err(e) => rpc_error!(e),
+                        };
+                        match rotation.next_action(old_info, new_info, roll_price, fee) {
+                            RotationAction::Wait(condition) => {
+                                if !json {
+                                    println!("Waiting until {}", condition);
+                                }
+                            }
+                            RotationAction::Transfer(amount) => {
+                                let signer =
+                                    wallet.get_signer(&rotation.old_address).ok_or_else(|| {
+                                        anyhow!(
+                                            "address {} is not in the wallet",
+                                            rotation.old_address
+                                        )
+                                    })?;
+                                let operation_id = sign_and_send(
+                                    client,
+                                    &signer,
+                                    rotation.old_address,
+                                    fee,
+                                    OperationType::Transaction {
+                                        recipient_address: rotation.new_address,
+                                        amount,
+                                    },
+                                )
+                                .await?;
+                                if !json {
+                                    println!(
+                                        "Transferring {} to {}, operation ID: {}",
+                                        amount, rotation.new_address, operation_id
+                                    );
+                                }
+                                rotation.stage = RotationStage::Transferred;
+                            }
+                            RotationAction::BuyRolls(roll_count) => {
+                                let signer =
+                                    wallet.get_signer(&rotation.new_address).ok_or_else(|| {
+                                        anyhow!(
+                                            "address {} is not in the wallet",
+                                            rotation.new_address
+                                        )
+                                    })?;
+                                let operation_id = sign_and_send(
+                                    client,
+                                    &signer,
+                                    rotation.new_address,
+                                    fee,
+                                    OperationType::RollBuy { roll_count },
+                                )
+                                .await?;
+                                if !json {
+                                    println!(
+                                        "Buying {} rolls with {}, operation ID: {}",
+                                        roll_count, rotation.new_address, operation_id
+                                    );
+                                }
+                                rotation.stage = RotationStage::RollsBought;
+                            }
+                            RotationAction::Retire => {
+                                if let Err(e) = client
+                                    .private
+                                    .remove_staking_addresses(vec![rotation.old_address])
+                                    .await
+                                {
+                                    rpc_error!(e);
+                                }
+                                if !json {
+                                    println!(
+                                        "Removed {} from the staking keys of the node, it stays in the wallet",
+                                        rotation.old_address
+                                    );
+                                }
+                                rotation.stage = RotationStage::Done;
+                            }
+                        }
+                        rotation.save()?;
+                    }
+                    Some(_) => {
+                        bail!("failed to parse operation, supported operations are: [start, continue, status]")
+                    }
+                }
+                match rotation {
+                    Some(rotation) => Ok(Box::new(rotation)),
+                    None => bail!("no rotation in progress, use `rotate_staking_key start`"),
+                }
+            }
+
+            Command::address_book => {
+                let mut address_book = AddressBook::load()?;
+                if parameters.is_empty() {
+                    return Ok(Box::new(address_book));
+                }
+                let cli_op = match parameters[0].parse::<ListOperation>() {
+                    Ok(op) => op,
+                    Err(_) => {
+                        bail!("failed to parse operation, supported operations are: [add, remove]")
+                    }
+                };
+                match cli_op {
+                    ListOperation::Add => {
+                        if parameters.len() != 3 {
+                            bail!("wrong number of parameters");
+                        }
+                        let address = parameters[2].parse::<Address>()?;
+                        address_book.add(&parameters[1], address)?;
+                        if !json {
+                            println!("Alias {} added", address_book.describe(&address));
+                        }
+                    }
+                    ListOperation::Remove => {
+                        if parameters.len() != 2 {
+                            bail!("wrong number of parameters");
+                        }
+                        address_book.remove(&parameters[1])?;
+                        if !json {
+                            println!("Alias {} removed", parameters[1]);
+                        }
+                    }
+                    ListOperation::AllowAll => {
+                        bail!("\"allow-all\" is not supported by the address book")
+                    }
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::buy_rolls => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let roll_count = parameters[1].parse::<u64>()?;
+                let fee = parameters[2].parse::<Amount>()?;
+
+                if !json {
+                    let roll_price = match client.public.get_status().await {
+                        Err(e) => bail!("RpcError: {}", e),
+                        Ok(status) => status.config.roll_price,
+                    };
+                    match roll_price
+                        .checked_mul_u64(roll_count)
+                        .and_then(|x| x.checked_add(fee))
+                    {
+                        Some(total) => {
+                            if let Ok(addresses_info) =
+                                client.public.get_addresses(vec![addr]).await
+                            {
+                                match addresses_info.get(0) {
+                                    Some(info) => {
+                                        if info.candidate_balance < total {
+                                            client_warning!("this operation may be rejected due to insufficient balance");
+                                        }
+                                    }
+                                    None => {
+                                        client_warning!(format!("address {} not found", addr))
+                                    }
+                                }
+                            }
+                        }
+                        None => {
+                            client_warning!("the total amount hit the limit overflow, operation will be rejected");
+                        }
+                    }
+                    if let Ok(staked_keys) = client.private.get_staking_addresses().await {
+                        if !staked_keys.contains(&addr) {
+                            client_warning!("You are buying rolls with an address not registered for staking. Don't forget to run 'node_start_staking <address>'");
+                        }
+                    }
+                }
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::RollBuy { roll_count },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+
+            Command::sell_rolls => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let roll_count = parameters[1].parse::<u64>()?;
+                let fee = parameters[2].parse::<Amount>()?;
+
+                if !json {
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match addresses_info.get(0) {
+                            Some(info) => {
                                 if info.candidate_balance < fee
                                     || roll_count > info.candidate_roll_count
                                 {
@@ -957,18 +1702,94 @@ impl Command {
                 .await
             }
 
+            Command::delegate_stake => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let operator = parse_address(&parameters[1])?;
+                let fee = parameters[2].parse::<Amount>()?;
+
+                if !json {
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match addresses_info.get(0) {
+                            Some(info) => {
+                                if info.candidate_balance < fee {
+                                    client_warning!("this operation may be rejected due to insufficient balance");
+                                }
+                            }
+                            None => client_warning!(format!("address {} not found", addr)),
+                        }
+                    }
+                }
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::StakeDelegation {
+                        operator: Some(operator),
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+
+            Command::revoke_stake_delegation => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let fee = parameters[1].parse::<Amount>()?;
+
+                if !json {
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match addresses_info.get(0) {
+                            Some(info) => {
+                                if info.candidate_balance < fee {
+                                    client_warning!("this operation may be rejected due to insufficient balance");
+                                }
+                            }
+                            None => client_warning!(format!("address {} not found", addr)),
+                        }
+                    }
+                }
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::StakeDelegation { operator: None },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+
             Command::send_transaction => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
                 if parameters.len() != 4 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
-                let recipient_address = parameters[1].parse::<Address>()?;
+                let address_book = AddressBook::load()?;
+                let addr = address_book.resolve(&parameters[0])?;
+                let recipient_address = address_book.resolve(&parameters[1])?;
                 let amount = parameters[2].parse::<Amount>()?;
                 let fee = parameters[3].parse::<Amount>()?;
 
                 if !json {
+                    println!(
+                        "Sending {} coins from {} to {}",
+                        amount,
+                        address_book.describe(&addr),
+                        address_book.describe(&recipient_address)
+                    );
                     if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
                         match addresses_info.get(0) {
                             Some(info) => {
@@ -996,6 +1817,268 @@ impl Command {
                 )
                 .await
             }
+            Command::create_transaction => {
+                // with --offline, the node is not contacted and the expire period must be given
+                let offline = parameters.iter().any(|p| p == "--offline");
+                let parameters: Vec<&String> =
+                    parameters.iter().filter(|p| *p != "--offline").collect();
+                if parameters.len() != 5 && parameters.len() != 6 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let recipient_address = parse_address(&parameters[1])?;
+                let amount = parameters[2].parse::<Amount>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+                let path = PathBuf::from(parameters[4]);
+                let expire_period = match parameters.get(5) {
+                    Some(expire_period) => expire_period.parse::<u64>()?,
+                    None if offline => bail!("the expire period is required with --offline"),
+                    None => get_expire_period(client, addr).await?,
+                };
+
+                let unsigned = UnsignedOperation {
+                    creator_address: addr,
+                    operation: Operation {
+                        fee,
+                        expire_period,
+                        op: OperationType::Transaction {
+                            recipient_address,
+                            amount,
+                        },
+                    },
+                };
+                write_file(&path, &unsigned)?;
+                if !json {
+                    println!("Unsigned operation written to {}", path.display());
+                }
+                Ok(Box::new(unsigned))
+            }
+            Command::sign_file => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 2 {
+                    bail!("wrong number of parameters");
+                }
+                let unsigned: UnsignedOperation = read_file(Path::new(&parameters[0]))?;
+                let signed_path = PathBuf::from(&parameters[1]);
+                let signed = unsigned.sign(wallet)?;
+                write_file(&signed_path, &signed)?;
+                if !json {
+                    println!("Signed operation written to {}", signed_path.display());
+                }
+                Ok(Box::new(signed))
+            }
+            Command::broadcast_file => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let signed: SignedOperation = read_file(Path::new(&parameters[0]))?;
+                match client.public.send_operations(vec![signed.operation]).await {
+                    Ok(operation_ids) => {
+                        if !json {
+                            println!("Sent operation IDs:");
+                        }
+                        Ok(Box::new(operation_ids))
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+            }
+            Command::send_batch => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 3 {
+                    bail!("wrong number of parameters");
+                }
+                let address_book = AddressBook::load()?;
+                let payments = read_payments(Path::new(&parameters[0]), &address_book)?;
+                let addr = address_book.resolve(&parameters[1])?;
+                let fee = parameters[2].parse::<Amount>()?;
+                let signer = wallet
+                    .get_signer(&addr)
+                    .ok_or_else(|| anyhow!("address {} is not in the wallet", addr))?;
+
+                if !json {
+                    let total = payments.iter().try_fold(Amount::zero(), |total, payment| {
+                        total.checked_add(payment.amount)?.checked_add(fee)
+                    });
+                    if let Ok(addresses_info) = client.public.get_addresses(vec![addr]).await {
+                        match (addresses_info.get(0), total) {
+                            (Some(info), Some(total)) if info.candidate_balance >= total => {}
+                            (Some(_), _) => client_warning!(
+                                "some operations may be rejected due to insufficient balance"
+                            ),
+                            (None, _) => {
+                                client_warning!(format!("address {} not found", addr))
+                            }
+                        }
+                    }
+                    if signer.requires_confirmation() {
+                        client_warning!("please confirm each operation on your device");
+                    }
+                }
+
+                // operations are sent one by one so that a failure only affects its own row
+                let mut results = Vec::with_capacity(payments.len());
+                for payment in payments {
+                    let sent = sign_and_send(
+                        client,
+                        &signer,
+                        addr,
+                        fee,
+                        OperationType::Transaction {
+                            recipient_address: payment.recipient,
+                            amount: payment.amount,
+                        },
+                    )
+                    .await;
+                    let (operation_id, error) = match sent {
+                        Ok(operation_id) => (Some(operation_id), None),
+                        Err(e) => (None, Some(e.to_string())),
+                    };
+                    results.push(PaymentResult {
+                        row: payment.row,
+                        recipient: payment.recipient,
+                        amount: payment.amount,
+                        operation_id,
+                        error,
+                    });
+                }
+                Ok(Box::new(results))
+            }
+            Command::sweep => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                let address_book = AddressBook::load()?;
+                let mut target = None;
+                let mut fee = None;
+                let mut sources = Vec::new();
+                let mut params = parameters.iter();
+                while let Some(param) = params.next() {
+                    match param.as_str() {
+                        "--to" => {
+                            let value = params
+                                .next()
+                                .ok_or_else(|| anyhow!("--to needs an address"))?;
+                            target = Some(address_book.resolve(value)?);
+                        }
+                        "--fee" => {
+                            let value = params
+                                .next()
+                                .ok_or_else(|| anyhow!("--fee needs an amount"))?;
+                            fee = Some(value.parse::<Amount>()?);
+                        }
+                        value => sources.push(address_book.resolve(value)?),
+                    }
+                }
+                let Some(target) = target else {
+                    bail!("the target address must be given with --to");
+                };
+                if sources.is_empty() {
+                    sources = wallet
+                        .get_wallet_address_list()
+                        .into_iter()
+                        .filter(|addr| *addr != target)
+                        .collect();
+                }
+                if sources.contains(&target) {
+                    bail!("the target address cannot be swept");
+                }
+                let fee = match fee {
+                    Some(fee) => fee,
+                    None => match client.public.get_fee_estimate(SWEEP_FEE_TARGET_SLOTS).await {
+                        Ok(estimate) => estimate.fee,
+                        Err(e) => rpc_error!(e),
+                    },
+                };
+
+                let mut addresses = sources.clone();
+                addresses.push(target);
+                let addresses_info = match client.public.get_addresses(addresses).await {
+                    Ok(addresses_info) => addresses_info,
+                    Err(e) => rpc_error!(e),
+                };
+                // a transfer creating the ledger entry of the target must pay for it
+                let mut target_exists = addresses_info
+                    .iter()
+                    .find(|info| info.address == target)
+                    .map_or(false, |info| !info.candidate_balance.is_zero());
+
+                let mut results = Vec::with_capacity(sources.len());
+                for info in addresses_info.iter().filter(|info| info.address != target) {
+                    let addr = info.address;
+                    let amount = info.candidate_balance.saturating_sub(fee);
+                    let minimum = if target_exists {
+                        Amount::zero()
+                    } else {
+                        LEDGER_ENTRY_BASE_COST
+                    };
+                    let sent = match wallet.get_signer(&addr) {
+                        None => Err(anyhow!("address {} is not in the wallet", addr)),
+                        Some(_) if amount.is_zero() || amount < minimum => Err(anyhow!(
+                            "balance {} too low to pay the fee {}",
+                            info.candidate_balance,
+                            fee
+                        )),
+                        Some(signer) => {
+                            if !json
+                                && (info.candidate_roll_count > 0
+                                    || !info.deferred_credits.is_empty())
+                            {
+                                client_warning!(format!(
+                                    "{} still has rolls or deferred credits, they are not swept",
+                                    addr
+                                ));
+                            }
+                            sign_and_send(
+                                client,
+                                &signer,
+                                addr,
+                                fee,
+                                OperationType::Transaction {
+                                    recipient_address: target,
+                                    amount,
+                                },
+                            )
+                            .await
+                        }
+                    };
+                    if sent.is_ok() {
+                        target_exists = true;
+                    }
+                    let (operation_id, error) = match sent {
+                        Ok(operation_id) => (Some(operation_id), None),
+                        Err(e) => (None, Some(e.to_string())),
+                    };
+                    results.push(SweepResult {
+                        address: addr,
+                        amount,
+                        fee,
+                        operation_id,
+                        error,
+                    });
+                }
+                if !json {
+                    println!("Sweeping to {}", address_book.describe(&target));
+                }
+                Ok(Box::new(results))
+            }
+            Command::watch => {
+                match parameters.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+                    ["blocks"] => watch_blocks(client, json).await?,
+                    ["events"] => watch_events(client, None, json).await?,
+                    ["events", "--address", address] => {
+                        watch_events(client, Some(parse_address(address)?), json).await?
+                    }
+                    ["address", address] => {
+                        watch_address(client, parse_address(address)?, json).await?
+                    }
+                    _ => bail!(
+                        "wrong parameters, expected: {}",
+                        self.get_str("args").unwrap()
+                    ),
+                }
+                Ok(Box::new(()))
+            }
             Command::when_episode_ends => {
                 let end = match client.public.get_status().await {
                     Ok(node_status) => node_status.config.end_timestamp,
@@ -1028,7 +2111,7 @@ impl Command {
                 if parameters.len() != 5 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
+                let addr = parse_address(&parameters[0])?;
                 let path = parameters[1].parse::<PathBuf>()?;
                 let max_gas = parameters[2].parse::<u64>()?;
                 let max_coins = parameters[3].parse::<Amount>()?;
@@ -1080,8 +2163,8 @@ impl Command {
                 if parameters.len() != 7 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
-                let target_addr = parameters[1].parse::<Address>()?;
+                let addr = parse_address(&parameters[0])?;
+                let target_addr = parse_address(&parameters[1])?;
                 let target_func = parameters[2].clone();
                 let param = parameters[3].clone().into_bytes();
                 let max_gas = parameters[4].parse::<u64>()?;
@@ -1135,7 +2218,7 @@ impl Command {
                 if parameters.len() != 2 {
                     bail!("wrong number of parameters");
                 }
-                let addr = parameters[0].parse::<Address>()?;
+                let addr = parse_address(&parameters[0])?;
                 let msg = parameters[1].clone();
                 if let Some(signed) = wallet.sign_message(&addr, msg.into_bytes()) {
                     Ok(Box::new(signed))
@@ -1143,15 +2226,157 @@ impl Command {
                     bail!("Missing public key")
                 }
             }
+            Command::deploy_sc => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() < 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parse_address(&parameters[0])?;
+                let path = parameters[1].parse::<PathBuf>()?;
+                let fee = parameters[2].parse::<Amount>()?;
+                let mut coins = Amount::zero();
+                let mut max_gas = None;
+                let mut deployer_path = SETTINGS.deployer_file_path.clone();
+                let mut constructor_args = Vec::new();
+                let mut options = parameters[3..].iter();
+                while let Some(option) = options.next() {
+                    let mut value = || {
+                        options
+                            .next()
+                            .ok_or_else(|| anyhow!("{} needs a value", option))
+                    };
+                    match option.as_str() {
+                        "--coins" => coins = value()?.parse::<Amount>()?,
+                        "--max-gas" => max_gas = Some(value()?.parse::<u64>()?),
+                        "--deployer" => deployer_path = PathBuf::from(value()?),
+                        "--arg" => constructor_args.extend(serialize_arg(value()?)?),
+                        "--raw-args" => constructor_args.extend(parse_hex(value()?)?),
+                        _ => bail!("unknown option {}", option),
+                    }
+                }
+                let signer = wallet
+                    .get_signer(&addr)
+                    .ok_or_else(|| anyhow!("address {} is not in the wallet", addr))?;
+
+                let bytecode = get_file_as_byte_vec(&path).await?;
+                let deployer = get_file_as_byte_vec(&deployer_path).await.map_err(|e| {
+                    anyhow!(
+                        "failed to read the deployer {}: {}",
+                        deployer_path.display(),
+                        e
+                    )
+                })?;
+                // the caller pays for the storage of the contract bytecode on top of the constructor coins
+                let max_coins = coins
+                    .saturating_add(LEDGER_COST_PER_BYTE.saturating_mul_u64(bytecode.len() as u64))
+                    .saturating_add(LEDGER_ENTRY_BASE_COST);
+                let datastore = deployer_datastore(bytecode, constructor_args, coins);
+
+                let max_gas = match max_gas {
+                    Some(max_gas) => max_gas,
+                    None => {
+                        let estimation = match client
+                            .public
+                            .execute_read_only_bytecode(ReadOnlyBytecodeExecution {
+                                max_gas: MAX_GAS_PER_BLOCK,
+                                bytecode: deployer.clone(),
+                                address: Some(addr),
+                                operation_datastore: Some(
+                                    DatastoreSerializer::new().serialize_to_vec(&datastore)?,
+                                ),
+                                is_final: false,
+                                overrides: Default::default(),
+                            })
+                            .await
+                        {
+                            Ok(estimation) => estimation,
+                            Err(e) => rpc_error!(e),
+                        };
+                        if let ReadOnlyResult::Error(e) = estimation.result {
+                            bail!("the deployment fails in a read-only execution: {}", e);
+                        }
+                        // keep a margin as the state may change before the execution
+                        (estimation
+                            .gas_cost
+                            .saturating_mul(DEPLOY_GAS_MARGIN_PERCENT)
+                            / 100)
+                            .min(MAX_GAS_PER_BLOCK)
+                    }
+                };
+
+                let operation_id = sign_and_send(
+                    client,
+                    &signer,
+                    addr,
+                    fee,
+                    OperationType::ExecuteSC {
+                        data: deployer,
+                        max_gas,
+                        max_coins,
+                        datastore,
+                    },
+                )
+                .await?;
+                if !json {
+                    println!(
+                        "Deployment operation {} sent, waiting for its execution...",
+                        operation_id
+                    );
+                }
+
+                // wait for the event giving the contract address, at most until the operation expires
+                let cfg = match client.public.get_status().await {
+                    Ok(node_status) => node_status.config,
+                    Err(e) => rpc_error!(e),
+                };
+                let mut deployed = DeployedContract {
+                    operation_id,
+                    address: None,
+                    max_gas,
+                    events: Vec::new(),
+                };
+                for _ in 0..cfg.operation_validity_periods * cfg.thread_count as u64 {
+                    tokio::time::sleep(
+                        cfg.t0
+                            .checked_div_u64(cfg.thread_count as u64)?
+                            .to_duration(),
+                    )
+                    .await;
+                    let events = match client
+                        .public
+                        .get_filtered_sc_output_event(EventFilter {
+                            original_operation_id: Some(operation_id),
+                            ..Default::default()
+                        })
+                        .await
+                    {
+                        Ok(events) => events,
+                        Err(e) => rpc_error!(e),
+                    };
+                    if events.is_empty() {
+                        continue;
+                    }
+                    deployed.address = events.iter().find_map(|event| {
+                        event
+                            .data
+                            .strip_prefix(DEPLOYED_EVENT_PREFIX)
+                            .and_then(|address| address.trim().parse::<Address>().ok())
+                    });
+                    deployed.events = events.into_iter().map(|event| event.data).collect();
+                    break;
+                }
+                Ok(Box::new(deployed))
+            }
             Command::read_only_execute_smart_contract => {
-                if parameters.len() < 2 || parameters.len() > 4 {
+                if parameters.len() < 2 || parameters.len() > 5 {
                     bail!("wrong number of parameters");
                 }
 
                 let path = parameters[0].parse::<PathBuf>()?;
                 let max_gas = parameters[1].parse::<u64>()?;
                 let address = if let Some(adr) = parameters.get(2) {
-                    Some(adr.parse::<Address>()?)
+                    Some(parse_address(adr)?)
                 } else {
                     None
                 };
@@ -1160,6 +2385,11 @@ impl Command {
                 } else {
                     false
                 };
+                let fee = if let Some(fee) = parameters.get(4) {
+                    fee.parse::<Amount>()?
+                } else {
+                    Amount::zero()
+                };
                 let bytecode = get_file_as_byte_vec(&path).await?;
                 match client
                     .public
@@ -1168,7 +2398,9 @@ impl Command {
                         bytecode,
                         address,
                         operation_datastore: None, // TODO - #3072
+                        fee,
                         is_final,
+                        overrides: Default::default(),
                     })
                     .await
                 {
@@ -1177,16 +2409,16 @@ impl Command {
                 }
             }
             Command::read_only_call => {
-                if parameters.len() < 4 || parameters.len() > 6 {
+                if parameters.len() < 4 || parameters.len() > 8 {
                     bail!("wrong number of parameters");
                 }
 
-                let target_address = parameters[0].parse::<Address>()?;
+                let target_address = parse_address(&parameters[0])?;
                 let target_function = parameters[1].parse::<String>()?;
                 let parameter = parameters[2].parse::<String>()?.into_bytes();
                 let max_gas = parameters[3].parse::<u64>()?;
                 let caller_address = if let Some(addr) = parameters.get(4) {
-                    Some(addr.parse::<Address>()?)
+                    Some(parse_address(addr)?)
                 } else {
                     None
                 };
@@ -1195,6 +2427,16 @@ impl Command {
                 } else {
                     false
                 };
+                let coins = if let Some(coins) = parameters.get(6) {
+                    coins.parse::<Amount>()?
+                } else {
+                    Amount::zero()
+                };
+                let fee = if let Some(fee) = parameters.get(7) {
+                    fee.parse::<Amount>()?
+                } else {
+                    Amount::zero()
+                };
                 match client
                     .public
                     .execute_read_only_call(ReadOnlyCall {
@@ -1203,7 +2445,10 @@ impl Command {
                         target_function,
                         parameter,
                         max_gas,
+                        coins,
+                        fee,
                         is_final,
+                        overrides: Default::default(),
                     })
                     .await
                 {
@@ -1226,9 +2471,9 @@ impl Command {
                     };
                     let args = &parameters[1..];
                     if args.is_empty() {
-                        bail!("[IpAddr] parameter shouldn't be empty");
+                        bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                     }
-                    let ips = parse_vec::<IpAddr>(args)?;
+                    let ips = parse_vec::<IpRange>(args)?;
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
                             match client.private.node_add_to_bootstrap_blacklist(ips).await {
@@ -1265,6 +2510,58 @@ impl Command {
                     res
                 }
             }
+            Command::node_pool_denylist => {
+                if parameters.is_empty() {
+                    match client.private.node_pool_denylist().await {
+                        Ok(addresses) => Ok(Box::new(addresses)),
+                        Err(e) => rpc_error!(e),
+                    }
+                } else {
+                    let cli_op = match parameters[0].parse::<ListOperation>() {
+                        Ok(op) => op,
+                        Err(_) => bail!(
+                            "failed to parse operation, supported operations are: [add, remove]"
+                        ),
+                    };
+                    let args = &parameters[1..];
+                    if args.is_empty() {
+                        bail!("[Address] parameter shouldn't be empty");
+                    }
+                    let addresses = parse_vec::<Address>(args)?;
+                    let res: Result<Box<dyn Output>> = match cli_op {
+                        ListOperation::Add => {
+                            match client.private.node_add_to_pool_denylist(addresses).await {
+                                Ok(()) => {
+                                    if !json {
+                                        println!("Request of pool denylisting successfully sent!")
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::Remove => {
+                            match client
+                                .private
+                                .node_remove_from_pool_denylist(addresses)
+                                .await
+                            {
+                                Ok(()) => {
+                                    if !json {
+                                        println!("Request of remove from pool denylist successfully sent!")
+                                    }
+                                    Ok(Box::new(()))
+                                }
+                                Err(e) => rpc_error!(e),
+                            }
+                        }
+                        ListOperation::AllowAll => {
+                            bail!("\"allow-all\" command is not implemented")
+                        }
+                    };
+                    res
+                }
+            }
             Command::node_bootstrap_whitelist => {
                 if parameters.is_empty() {
                     match client.private.node_bootstrap_whitelist().await {
@@ -1285,11 +2582,11 @@ impl Command {
                     let res: Result<Box<dyn Output>> = match cli_op {
                         ListOperation::Add => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_add_to_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_add_to_bootstrap_whitelist(parse_vec::<IpRange>(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1305,11 +2602,11 @@ impl Command {
                         }
                         ListOperation::Remove => {
                             if args.is_empty() {
-                                bail!("[IpAddr] parameter shouldn't be empty");
+                                bail!("[IpAddr or CIDR range] parameter shouldn't be empty");
                             }
                             match client
                                 .private
-                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpAddr>(args)?)
+                                .node_remove_from_bootstrap_whitelist(parse_vec::<IpRange>(args)?)
                                 .await
                             {
                                 Ok(()) => {
@@ -1402,27 +2699,19 @@ async fn send_operation(
     addr: Address,
     json: bool,
 ) -> Result<Box<dyn Output>> {
-    let cfg = match client.public.get_status().await {
-        Ok(node_status) => node_status,
-        Err(e) => rpc_error!(e),
-    }
-    .config;
+    let signer = wallet
+        .get_signer(&addr)
+        .ok_or_else(|| anyhow!("address {} is not in the wallet", addr))?;
+    let expire_period = get_expire_period(client, addr).await?;
 
-    let slot = get_current_latest_block_slot(cfg.thread_count, cfg.t0, cfg.genesis_timestamp)?
-        .unwrap_or_else(|| Slot::new(0, 0));
-    let mut expire_period = slot.period + cfg.operation_validity_periods;
-    if slot.thread >= addr.get_thread(cfg.thread_count) {
-        expire_period += 1;
-    };
-
-    let op = wallet.create_operation(
-        Operation {
-            fee,
-            expire_period,
-            op,
-        },
-        addr,
-    )?;
+    if signer.requires_confirmation() {
+        client_warning!("please confirm the operation on your device");
+    }
+    let op = signer.sign_operation(Operation {
+        fee,
+        expire_period,
+        op,
+    })?;
 
     match client
         .public
@@ -1443,6 +2732,64 @@ async fn send_operation(
     }
 }
 
+/// Signs and sends an operation, with an expire period computed when it is signed.
+/// Returns the id of the operation.
+async fn sign_and_send(
+    client: &Client,
+    signer: &dyn Signer,
+    addr: Address,
+    fee: Amount,
+    op: OperationType,
+) -> Result<OperationId> {
+    let expire_period = get_expire_period(client, addr).await?;
+    let op = signer.sign_operation(Operation {
+        fee,
+        expire_period,
+        op,
+    })?;
+    match client
+        .public
+        .send_operations(vec![OperationInput {
+            creator_public_key: op.content_creator_pub_key,
+            serialized_content: op.serialized_data,
+            signature: op.signature,
+        }])
+        .await
+    {
+        Ok(operation_ids) => operation_ids
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("the node returned no operation id")),
+        Err(e) => rpc_error!(e),
+    }
+}
+
+/// Computes the expire period of an operation created now by `addr`, from the node status
+async fn get_expire_period(client: &Client, addr: Address) -> Result<u64> {
+    let cfg = match client.public.get_status().await {
+        Ok(node_status) => node_status,
+        Err(e) => rpc_error!(e),
+    }
+    .config;
+
+    let slot = get_current_latest_block_slot(cfg.thread_count, cfg.t0, cfg.genesis_timestamp)?
+        .unwrap_or_else(|| Slot::new(0, 0));
+    let mut expire_period = slot.period + cfg.operation_validity_periods;
+    if slot.thread >= addr.get_thread(cfg.thread_count) {
+        expire_period += 1;
+    };
+    Ok(expire_period)
+}
+
+/// True if an address holds coins, rolls or deferred credits
+fn is_address_used(info: &AddressInfo) -> bool {
+    !info.final_balance.is_zero()
+        || !info.candidate_balance.is_zero()
+        || info.final_roll_count > 0
+        || info.candidate_roll_count > 0
+        || !info.deferred_credits.is_empty()
+}
+
 /// TODO: ugly utilities functions
 /// takes a slice of string and makes it into a `Vec<T>`
 pub fn parse_vec<T: std::str::FromStr>(args: &[String]) -> anyhow::Result<Vec<T>, anyhow::Error>
@@ -1457,6 +2804,19 @@ where
         .collect()
 }
 
+/// Parses ban targets, each one being either an IP address or a node id
+fn parse_ban_targets(args: &[String]) -> Result<Vec<BanTarget>> {
+    args.iter()
+        .map(|arg| match arg.parse::<IpAddr>() {
+            Ok(ip) => Ok(BanTarget::Ip(ip)),
+            Err(_) => arg
+                .parse::<NodeId>()
+                .map(BanTarget::NodeId)
+                .map_err(|e| anyhow!("failed to parse \"{}\" due to: {}", arg, e)),
+        })
+        .collect()
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
@@ -1484,5 +2844,9 @@ where
 /// Display the help of all commands
 pub fn help() {
     println!("HELP of Massa client (list of available commands):");
-    Command::iter().map(|c| c.help()).collect()
+    Command::iter().for_each(|c| c.help());
+    println!(
+        "Amounts (Amount, Fee, Coins, MaxCoins) are expressed in MAS by default. \
+        An explicit unit can be appended without space: 1.5MAS or 1500nMAS"
+    );
 }
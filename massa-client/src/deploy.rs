@@ -0,0 +1,162 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Smart contract deployment through the standard deployer, used by `deploy_sc`.
+//!
+//! The deployer (`deployer.wasm` of the massa smart contract toolkit) is executed with an
+//! operation datastore holding the contracts to create:
+//! * key `[0]`: number of contracts, as a little endian u64
+//! * key `u64(i + 1)`: bytecode of the contract `i`
+//! * key `u64(i + 1) ++ [0]`: serialized arguments of its `constructor`
+//! * key `u64(i + 1) ++ [1]`: coins sent to its `constructor`, as a little endian u64
+//!
+//! It creates each contract, calls its constructor and emits an event with the contract address.
+
+use anyhow::{anyhow, bail, Result};
+use massa_models::{address::Address, amount::Amount, datastore::Datastore};
+use serde::Serialize;
+
+/// Prefix of the event emitted by the deployer for each created contract
+pub(crate) const DEPLOYED_EVENT_PREFIX: &str = "Contract deployed at address: ";
+
+/// Result of `deploy_sc`
+#[derive(Debug, Serialize)]
+pub(crate) struct DeployedContract {
+    /// id of the deployment operation
+    pub operation_id: massa_models::operation::OperationId,
+    /// address of the created contract, if the deployment was executed in time
+    pub address: Option<Address>,
+    /// gas reserved for the deployment
+    pub max_gas: u64,
+    /// events emitted by the deployment
+    pub events: Vec<String>,
+}
+
+/// Builds the operation datastore deploying a single contract
+pub(crate) fn deployer_datastore(
+    bytecode: Vec<u8>,
+    constructor_args: Vec<u8>,
+    coins: Amount,
+) -> Datastore {
+    let index = 1u64.to_le_bytes().to_vec();
+    let mut datastore = Datastore::new();
+    datastore.insert(vec![0], 1u64.to_le_bytes().to_vec());
+    datastore.insert([&index[..], &[0]].concat(), constructor_args);
+    datastore.insert(
+        [&index[..], &[1]].concat(),
+        coins.to_raw().to_le_bytes().to_vec(),
+    );
+    datastore.insert(index, bytecode);
+    datastore
+}
+
+/// Serializes a constructor argument given as `type:value`, the way the smart contracts `Args` do.
+///
+/// Supported types: `string`, `address`, `bool`, `u8`, `u32`, `u64`, `i32`, `i64`, `f32`, `f64`
+/// and `bytes` (hexadecimal).
+pub(crate) fn serialize_arg(arg: &str) -> Result<Vec<u8>> {
+    let (kind, value) = arg
+        .split_once(':')
+        .ok_or_else(|| anyhow!("invalid argument {}, expected type:value", arg))?;
+    let invalid = |e: &dyn std::fmt::Display| anyhow!("invalid {} argument {}: {}", kind, value, e);
+    Ok(match kind {
+        "string" => with_length(value.as_bytes()),
+        "address" => {
+            let address = value.parse::<Address>().map_err(|e| invalid(&e))?;
+            with_length(address.to_string().as_bytes())
+        }
+        "bool" => vec![value.parse::<bool>().map_err(|e| invalid(&e))? as u8],
+        "u8" => vec![value.parse::<u8>().map_err(|e| invalid(&e))?],
+        "u32" => value
+            .parse::<u32>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "u64" => value
+            .parse::<u64>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "i32" => value
+            .parse::<i32>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "i64" => value
+            .parse::<i64>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "f32" => value
+            .parse::<f32>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "f64" => value
+            .parse::<f64>()
+            .map_err(|e| invalid(&e))?
+            .to_le_bytes()
+            .to_vec(),
+        "bytes" => with_length(&parse_hex(value)?),
+        _ => bail!("unsupported argument type {}", kind),
+    })
+}
+
+/// Parses raw bytes given in hexadecimal
+pub(crate) fn parse_hex(value: &str) -> Result<Vec<u8>> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if value.len() % 2 != 0 {
+        bail!("invalid hexadecimal bytes {}", value);
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&value[i..i + 2], 16)
+                .map_err(|e| anyhow!("invalid hexadecimal bytes {}: {}", value, e))
+        })
+        .collect()
+}
+
+/// Prefixes bytes with their length as a little endian u32
+fn with_length(bytes: &[u8]) -> Vec<u8> {
+    [&(bytes.len() as u32).to_le_bytes()[..], bytes].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_constructor_args() {
+        assert_eq!(
+            serialize_arg("string:ab").unwrap(),
+            vec![2, 0, 0, 0, b'a', b'b']
+        );
+        assert_eq!(
+            serialize_arg("u64:1").unwrap(),
+            vec![1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(serialize_arg("bool:true").unwrap(), vec![1]);
+        assert_eq!(
+            serialize_arg("bytes:0x0aff").unwrap(),
+            vec![2, 0, 0, 0, 10, 255]
+        );
+        assert!(serialize_arg("u8:256").is_err());
+        assert!(serialize_arg("u256:1").is_err());
+        assert!(serialize_arg("no_type").is_err());
+    }
+
+    #[test]
+    fn test_deployer_datastore() {
+        let datastore = deployer_datastore(vec![42], vec![7], Amount::from_raw(3));
+        assert_eq!(datastore.get(&vec![0]), Some(&1u64.to_le_bytes().to_vec()));
+        assert_eq!(datastore.get(&1u64.to_le_bytes().to_vec()), Some(&vec![42]));
+        assert_eq!(
+            datastore.get(&vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Some(&vec![7])
+        );
+        assert_eq!(
+            datastore.get(&vec![1, 0, 0, 0, 0, 0, 0, 0, 1]),
+            Some(&3u64.to_le_bytes().to_vec())
+        );
+    }
+}
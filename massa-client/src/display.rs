@@ -1,20 +1,33 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::cmds::ExtendedWallet;
+use crate::address_book::AddressBook;
+use crate::batch::{PaymentResult, SweepResult};
+use crate::cmds::{CommandHelp, ExtendedWallet};
+use crate::deploy::DeployedContract;
+use crate::offline::{SignedOperation, UnsignedOperation};
+use crate::rotation::{KeyRotation, RotationStage};
+use crate::staking::StakingInfo;
 use console::style;
-use erased_serde::{Serialize, Serializer};
+use erased_serde::Serialize;
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::AddressInfo,
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::ExecuteReadOnlyResponse,
+    node::{BanInfo, GrpcDrainStatus, NodeStatus},
     operation::OperationInfo,
 };
 use massa_models::composite::PubkeySig;
+use massa_models::ip_range::IpRange;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
-use massa_wallet::Wallet;
+use massa_time::MassaTime;
+use massa_wallet::{AddressMetadata, Wallet};
+use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::str;
 
@@ -50,10 +63,10 @@ macro_rules! grpc_error {
 }
 
 #[macro_export]
-/// print a yellow warning
+/// print a yellow warning, on stderr so that it does not mix with the JSON output
 macro_rules! client_warning {
     ($e:expr) => {
-        println!("{}: {}", style("WARNING").yellow(), $e)
+        eprintln!("{}: {}", style("WARNING").yellow(), $e)
     };
 }
 
@@ -113,15 +126,69 @@ pub trait Output: Serialize {
     fn pretty_print(&self);
 }
 
-impl dyn Output {
-    pub(crate) fn stdout_json(&self) -> anyhow::Result<()> {
-        let json = &mut serde_json::Serializer::new(std::io::stdout());
-        let mut format: Box<dyn Serializer> = Box::new(<dyn Serializer>::erase(json));
-        self.erased_serialize(&mut format)?;
+erased_serde::serialize_trait_object!(Output);
+
+/// Envelope of the JSON output of a command, shared by all the commands:
+/// `{"command": <name>, "result": <output>}` on success, `{"command": <name>, "error": <message>}` on failure
+#[derive(serde::Serialize)]
+pub(crate) struct JsonOutput<'a> {
+    /// name of the command
+    command: String,
+    /// output of the command, if it succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'a dyn Output>,
+    /// error message, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl<'a> JsonOutput<'a> {
+    /// Envelope of a command output
+    pub(crate) fn result(command: String, result: &'a dyn Output) -> Self {
+        JsonOutput {
+            command,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Envelope of a command error
+    pub(crate) fn error(command: String, error: &anyhow::Error) -> Self {
+        JsonOutput {
+            command,
+            result: None,
+            error: Some(format!("{:#}", error)),
+        }
+    }
+
+    /// Writes the envelope on stdout, on a single line
+    pub(crate) fn print(&self) -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string(self)?);
         Ok(())
     }
 }
 
+impl Output for AddressBook {
+    fn pretty_print(&self) {
+        if self.entries.is_empty() {
+            println!(
+                "The address book is empty, use 'address_book add Alias Address' to add an alias"
+            );
+        }
+        for (alias, address) in &self.entries {
+            println!("{}: {}", alias, address);
+        }
+    }
+}
+
+impl Output for Vec<CommandHelp> {
+    fn pretty_print(&self) {
+        for command in self {
+            println!("{}: {}", command.name, command.description);
+        }
+    }
+}
+
 impl Output for Wallet {
     fn pretty_print(&self) {
         println!("{}", self);
@@ -147,6 +214,9 @@ impl Output for ExtendedWallet {
                 Style::Wallet.style(entry.address_info.address),
                 Style::Protocol.style(entry.address_info.thread),
             );
+            if let Some(metadata) = &entry.metadata {
+                metadata.pretty_print();
+            }
             println!(
                 "\tBalance: {}={}, {}={}",
                 Style::Finished.style("final"),
@@ -260,6 +330,10 @@ impl Output for NodeStatus {
 
         self.network_stats.pretty_print();
         self.execution_stats.pretty_print();
+        println!("{}", self.chain_stats);
+        if let Some(bootstrap_stats) = &self.bootstrap_stats {
+            println!("{}", bootstrap_stats);
+        }
 
         if !self.connected_nodes.is_empty() {
             println!("Connected nodes:");
@@ -275,6 +349,12 @@ impl Output for NodeStatus {
     }
 }
 
+impl Output for GrpcDrainStatus {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
 impl Output for ExecutionStats {
     fn pretty_print(&self) {
         println!("Execution stats:");
@@ -479,6 +559,14 @@ impl Output for Vec<EndorsementInfo> {
     }
 }
 
+impl Output for Vec<BanInfo> {
+    fn pretty_print(&self) {
+        for ban in self {
+            print!("{}", ban);
+        }
+    }
+}
+
 impl Output for Vec<IpAddr> {
     fn pretty_print(&self) {
         for ips in self {
@@ -487,6 +575,14 @@ impl Output for Vec<IpAddr> {
     }
 }
 
+impl Output for Vec<IpRange> {
+    fn pretty_print(&self) {
+        for range in self {
+            println!("{}", range);
+        }
+    }
+}
+
 impl Output for Vec<OperationInfo> {
     fn pretty_print(&self) {
         for info in self {
@@ -564,6 +660,168 @@ impl Output for Vec<OperationId> {
     }
 }
 
+impl Output for UnsignedOperation {
+    fn pretty_print(&self) {
+        println!("Creator address: {}", self.creator_address);
+        println!("{}", self.operation);
+    }
+}
+
+impl Output for SignedOperation {
+    fn pretty_print(&self) {
+        println!("Operation ID: {}", self.operation_id);
+        println!("Creator address: {}", self.creator_address);
+    }
+}
+
+impl Output for Vec<StakingInfo> {
+    fn pretty_print(&self) {
+        for info in self {
+            println!("Address: {}", style(info.address).green());
+            println!(
+                "\tRolls: final={}, candidate={}, active={}",
+                info.final_rolls,
+                info.candidate_rolls,
+                info.active_rolls
+                    .map_or_else(|| "unknown".to_string(), |rolls| rolls.to_string())
+            );
+            println!(
+                "\tBlocks: produced={}, missed={}, missed ratio={}",
+                info.produced_blocks,
+                info.missed_blocks,
+                info.missed_block_ratio.map_or_else(
+                    || "n/a".to_string(),
+                    |ratio| format!("{:.2}%", ratio * 100.0)
+                )
+            );
+            println!(
+                "\tNext block draws: {}",
+                info.next_block_draws
+                    .iter()
+                    .map(|slot| slot.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            println!(
+                "\tNext endorsement draws: {}",
+                info.next_endorsement_draws
+                    .iter()
+                    .map(|slot| slot.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            for credit in &info.deferred_credits {
+                println!(
+                    "\tDeferred credit: {} at slot {}",
+                    credit.amount, credit.slot
+                );
+            }
+            println!(
+                "\tEstimated rewards of the next block draws: {}",
+                info.estimated_block_rewards
+            );
+        }
+    }
+}
+
+impl Output for AddressMetadata {
+    fn pretty_print(&self) {
+        if let Some(label) = &self.label {
+            println!("\tLabel: {}", label);
+        }
+        if let Some(created_at) = self.created_at {
+            println!(
+                "\tCreated: {}",
+                MassaTime::from_millis(created_at).format_instant()
+            );
+        }
+        for (key, value) in &self.fields {
+            println!("\t{}: {}", key, value);
+        }
+    }
+}
+
+impl Output for KeyRotation {
+    fn pretty_print(&self) {
+        println!("Rotation of {} to {}", self.old_address, self.new_address);
+        let stage = match self.stage {
+            RotationStage::SellingRolls => "selling the rolls of the old address",
+            RotationStage::Transferred => "coins transferred to the new address",
+            RotationStage::RollsBought => "rolls bought, waiting for them to be active",
+            RotationStage::Done => "done",
+        };
+        println!("Stage: {}", stage);
+    }
+}
+
+impl Output for DeployedContract {
+    fn pretty_print(&self) {
+        println!("Operation ID: {}", self.operation_id);
+        println!("Max gas: {}", self.max_gas);
+        match (&self.address, self.events.is_empty()) {
+            (Some(address), _) => println!("Contract address: {}", style(address).green()),
+            (None, true) => println!(
+                "{}",
+                style("The deployment was not executed before the operation expired").red()
+            ),
+            (None, false) => println!("{}", style("The deployment failed").red()),
+        }
+        for event in &self.events {
+            println!("Event: {}", event);
+        }
+    }
+}
+
+impl Output for Vec<SweepResult> {
+    fn pretty_print(&self) {
+        for result in self {
+            match (&result.operation_id, &result.error) {
+                (Some(operation_id), _) => println!(
+                    "{}: sent {} (fee {}), operation ID: {}",
+                    result.address, result.amount, result.fee, operation_id
+                ),
+                (None, error) => println!(
+                    "{}: {}: {}",
+                    result.address,
+                    style("skipped").red(),
+                    error.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+    }
+}
+
+impl Output for Vec<PaymentResult> {
+    fn pretty_print(&self) {
+        for result in self {
+            match (&result.operation_id, &result.error) {
+                (Some(operation_id), _) => println!(
+                    "Row {}: sent {} to {}, operation ID: {}",
+                    result.row, result.amount, result.recipient, operation_id
+                ),
+                (None, error) => println!(
+                    "Row {}: {} to {} {}: {}",
+                    result.row,
+                    result.amount,
+                    result.recipient,
+                    style("failed").red(),
+                    error.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+        let failed = self.iter().filter(|r| r.operation_id.is_none()).count();
+        println!("{} sent, {} failed", self.len() - failed, failed);
+    }
+}
+
+impl Output for BTreeMap<u32, Address> {
+    fn pretty_print(&self) {
+        for (index, address) in self {
+            println!("#{}: {}", index, address);
+        }
+    }
+}
+
 impl Output for Vec<Address> {
     fn pretty_print(&self) {
         for addr in self {
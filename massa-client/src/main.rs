@@ -7,20 +7,28 @@ use anyhow::Result;
 use atty::Stream;
 use cmds::Command;
 use console::style;
-use dialoguer::Password;
+use dialoguer::{Confirm, Password};
+use display::JsonOutput;
 use massa_sdk::{Client, ClientConfig, HttpConfig};
-use massa_wallet::Wallet;
-use serde::Serialize;
+use massa_wallet::{AuditLog, TransferLimit, Wallet};
 use std::env;
 use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use structopt::StructOpt;
 
+mod address_book;
+mod batch;
 mod cmds;
+mod deploy;
 mod display;
+mod offline;
 mod repl;
+mod rotation;
 mod settings;
+mod staking;
+mod watch;
 
 #[cfg(test)]
 pub mod tests;
@@ -45,27 +53,24 @@ struct Args {
     /// Optional command parameter (as a JSON string)
     #[structopt(name = "PARAMETERS")]
     parameters: Vec<String>,
-    /// Path of wallet file
-    #[structopt(
-        short = "w",
-        long = "wallet",
-        parse(from_os_str),
-        default_value = "wallet.dat"
-    )]
-    wallet: PathBuf,
-    /// Enable a mode where input/output are serialized as JSON
+    /// Path of wallet file (default: the wallet of the profile, or wallet.dat)
+    #[structopt(short = "w", long = "wallet", parse(from_os_str))]
+    wallet: Option<PathBuf>,
+    /// Profile of the configuration to use, giving the wallet file and the node
+    #[structopt(long = "profile")]
+    profile: Option<String>,
+    /// Enable a mode where input/output are serialized as JSON:
+    /// the output is a single `{"command": ..., "result": ...}` or `{"command": ..., "error": ...}` object
     #[structopt(short = "j", long = "json")]
     json: bool,
+    /// Do not contact the node (create_transaction)
+    #[structopt(long = "offline")]
+    offline: bool,
     #[structopt(short = "p", long = "pwd")]
     /// Wallet password
     password: Option<String>,
 }
 
-#[derive(Serialize)]
-struct JsonError {
-    error: String,
-}
-
 /// Ask for the wallet password
 /// If the wallet does not exist, it will require password confirmation
 pub(crate) fn ask_password(wallet_path: &Path) -> String {
@@ -83,6 +88,28 @@ pub(crate) fn ask_password(wallet_path: &Path) -> String {
     }
 }
 
+/// Opens the wallet with the signing hooks of the settings
+pub(crate) fn open_wallet(wallet_path: PathBuf, password: String) -> anyhow::Result<Wallet> {
+    let mut wallet = Wallet::new(wallet_path, password)?;
+    if let Some(path) = &SETTINGS.signing.audit_log_path {
+        wallet.add_signing_hook(Arc::new(AuditLog::new(path.clone())));
+    }
+    if let Some(threshold) = SETTINGS.signing.transfer_confirmation_threshold {
+        wallet.add_signing_hook(Arc::new(TransferLimit::new(threshold, |request| {
+            Confirm::new()
+                .with_prompt(format!(
+                    "Sign {} with {}?",
+                    serde_json::to_string(&request.summary).unwrap_or_default(),
+                    request.address
+                ))
+                .default(false)
+                .interact()
+                .unwrap_or(false)
+        })));
+    }
+    Ok(wallet)
+}
+
 #[paw::main]
 fn main(args: Args) -> anyhow::Result<()> {
     let tokio_rt = tokio::runtime::Builder::new_multi_thread()
@@ -116,23 +143,26 @@ async fn run(args: Args) -> Result<()> {
 
     // TODO: move settings loading in another crate ... see #1277
     let settings = SETTINGS.clone();
-
-    let address = match args.ip {
-        Some(ip) => ip,
-        None => settings.default_node.ip,
-    };
-    let public_port = match args.public_port {
-        Some(public_port) => public_port,
-        None => settings.default_node.public_port,
-    };
-    let private_port = match args.private_port {
-        Some(private_port) => private_port,
-        None => settings.default_node.private_port,
-    };
-    let grpc_port = match args.grpc_port {
-        Some(grpc_port) => grpc_port,
-        None => settings.default_node.grpc_port,
-    };
+    // command line arguments take precedence over the profile, which takes precedence over the global settings
+    let profile = settings.profile(args.profile.as_deref())?;
+
+    let address = args.ip.or(profile.ip).unwrap_or(settings.default_node.ip);
+    let public_port = args
+        .public_port
+        .or(profile.public_port)
+        .unwrap_or(settings.default_node.public_port);
+    let private_port = args
+        .private_port
+        .or(profile.private_port)
+        .unwrap_or(settings.default_node.private_port);
+    let grpc_port = args
+        .grpc_port
+        .or(profile.grpc_port)
+        .unwrap_or(settings.default_node.grpc_port);
+    let wallet_path = args
+        .wallet
+        .or(profile.wallet)
+        .unwrap_or_else(|| PathBuf::from("wallet.dat"));
 
     // Setup panic handlers,
     // and when a panic occurs,
@@ -149,7 +179,7 @@ async fn run(args: Args) -> Result<()> {
         Client::new(address, public_port, private_port, grpc_port, &http_config).await?;
     if atty::is(Stream::Stdout) && args.command == Command::help && !args.json {
         // Interactive mode
-        repl::run(&mut client, &args.wallet, args.password).await?;
+        repl::run(&mut client, &wallet_path, args.password).await?;
     } else {
         // Non-Interactive mode
 
@@ -159,24 +189,30 @@ async fn run(args: Args) -> Result<()> {
                 let password = match (args.password, env::var("MASSA_CLIENT_PASSWORD")) {
                     (Some(pwd), _) => pwd,
                     (_, Ok(pwd)) => pwd,
-                    _ => ask_password(&args.wallet),
+                    _ => ask_password(&wallet_path),
                 };
 
-                let wallet = Wallet::new(args.wallet, password)?;
+                let wallet = open_wallet(wallet_path, password)?;
                 Some(wallet)
             }
             false => None,
         };
 
+        let mut parameters = args.parameters;
+        if args.offline {
+            parameters.push("--offline".to_string());
+        }
+
+        let command = args.command.to_string();
         match args
             .command
-            .run(&mut client, &mut wallet_opt, &args.parameters, args.json)
+            .run(&mut client, &mut wallet_opt, &parameters, args.json)
             .await
         {
             Ok(output) => {
                 if args.json {
-                    output
-                        .stdout_json()
+                    JsonOutput::result(command, &*output)
+                        .print()
                         .expect("fail to serialize to JSON command output")
                 } else {
                     output.pretty_print();
@@ -184,11 +220,9 @@ async fn run(args: Args) -> Result<()> {
             }
             Err(e) => {
                 if args.json {
-                    let error = serde_json::to_string(&JsonError {
-                        error: format!("{:?}", e),
-                    })
-                    .expect("fail to serialize to JSON error");
-                    println!("{}", error);
+                    JsonOutput::error(command, &e)
+                        .print()
+                        .expect("fail to serialize to JSON error");
                 } else {
                     println!("{}", style(format!("Error: {}", e)).red());
                 }
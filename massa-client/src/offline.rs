@@ -0,0 +1,68 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Files exchanged by the offline signing workflow.
+//!
+//! `create_transaction` writes an unsigned operation, `sign_file` signs it with a wallet key,
+//! possibly on a machine without network access, and `broadcast_file` sends the signed operation.
+
+use anyhow::{anyhow, Result};
+use massa_api_exports::operation::OperationInput;
+use massa_models::{
+    address::Address,
+    operation::{Operation, OperationId},
+};
+use massa_wallet::Wallet;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+/// Operation waiting to be signed, written by `create_transaction`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct UnsignedOperation {
+    /// address whose key must sign the operation
+    pub creator_address: Address,
+    /// content of the operation
+    pub operation: Operation,
+}
+
+/// Signed operation ready to be sent, written by `sign_file`
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SignedOperation {
+    /// id of the operation
+    pub operation_id: OperationId,
+    /// address that signed the operation
+    pub creator_address: Address,
+    /// signed operation, as expected by `send_operations`
+    pub operation: OperationInput,
+}
+
+impl UnsignedOperation {
+    /// Signs the operation with the key of its creator address, which must be in the wallet
+    pub(crate) fn sign(self, wallet: &Wallet) -> Result<SignedOperation> {
+        let op = wallet.create_operation(self.operation, self.creator_address)?;
+        Ok(SignedOperation {
+            operation_id: op.id,
+            creator_address: op.content_creator_address,
+            operation: OperationInput {
+                creator_public_key: op.content_creator_pub_key,
+                signature: op.signature,
+                serialized_content: op.serialized_data,
+            },
+        })
+    }
+}
+
+/// Reads an operation file
+pub(crate) fn read_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("invalid file {}: {}", path.display(), e))
+}
+
+/// Writes an operation file, refusing to overwrite an existing one
+pub(crate) fn write_file<T: Serialize>(path: &Path, content: &T) -> Result<()> {
+    if path.exists() {
+        return Err(anyhow!("{} already exists", path.display()));
+    }
+    std::fs::write(path, serde_json::to_string_pretty(content)?)
+        .map_err(|e| anyhow!("failed to write {}: {}", path.display(), e))
+}
@@ -1,22 +1,26 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::address_book::AddressBook;
 use crate::ask_password;
 use crate::cmds::Command;
 use crate::massa_fancy_ascii_art_logo;
+use crate::open_wallet;
 use crate::settings::SETTINGS;
 use anyhow::Result;
 use console::style;
 use massa_sdk::Client;
-use massa_wallet::Wallet;
 use rustyline::completion::{Completer, FilenameCompleter, Pair};
 use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
 use rustyline::validate::MatchingBracketValidator;
 use rustyline::{CompletionType, Config, Editor};
-use rustyline_derive::{Completer, Helper, Highlighter, Hinter, Validator};
+use rustyline_derive::{Completer, Helper, Validator};
+use std::borrow::Cow;
 use std::env;
 use std::path::Path;
-use strum::IntoEnumIterator;
 use strum::ParseError;
+use strum::{EnumProperty, IntoEnumIterator};
 
 fn group_parameters(parameters: Vec<String>) -> Vec<String> {
     let mut new_parameters = Vec::new();
@@ -68,7 +72,7 @@ fn group_parameters(parameters: Vec<String>) -> Vec<String> {
     new_parameters
 }
 
-#[derive(Helper, Completer, Hinter, Validator, Highlighter)]
+#[derive(Helper, Completer, Validator)]
 struct MyHelper {
     #[rustyline(Completer)]
     completer: MassaCompleter,
@@ -76,6 +80,33 @@ struct MyHelper {
     validator: MatchingBracketValidator,
 }
 
+/// Shows the parameters of the command being typed
+impl Hinter for MyHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &rustyline::Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        let name = line.trim_end();
+        if name.contains(' ') {
+            return None;
+        }
+        let args = name.parse::<Command>().ok()?.get_str("args")?;
+        if line.ends_with(' ') {
+            Some(args.to_string())
+        } else {
+            Some(format!(" {}", args))
+        }
+    }
+}
+
+impl Highlighter for MyHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(style(hint).dim().to_string())
+    }
+}
+
 pub(crate) async fn run(
     client: &mut Client,
     wallet_path: &Path,
@@ -84,7 +115,8 @@ pub(crate) async fn run(
     massa_fancy_ascii_art_logo!();
     println!("Use 'exit' or 'CTRL+D or CTRL+C' to quit the prompt");
     println!("Use the Up/Down arrows to scroll through history");
-    println!("Use the Right arrow or Tab to complete your command");
+    println!("Use the Right arrow or Tab to complete your command, its addresses and files");
+    println!("The parameters of a command are shown as you type it");
     println!("Use the Enter key to execute your command");
     crate::cmds::help();
     let h = MyHelper {
@@ -129,7 +161,7 @@ pub(crate) async fn run(
                                     _ => ask_password(wallet_path),
                                 };
 
-                            let wallet = Wallet::new(wallet_path.to_path_buf(), password)?;
+                            let wallet = open_wallet(wallet_path.to_path_buf(), password)?;
                             wallet_opt = Some(wallet);
                         }
 
@@ -140,6 +172,19 @@ pub(crate) async fn run(
                             Ok(output) => output.pretty_print(),
                             Err(e) => println!("{}", style(format!("Error: {}", e)).red()),
                         }
+
+                        // complete the addresses of the wallet once it is unlocked, keys may have changed
+                        if let (Some(helper), Some(wallet)) = (rl.helper_mut(), &wallet_opt) {
+                            helper.completer.wallet_addresses = wallet
+                                .get_wallet_address_list()
+                                .iter()
+                                .map(|addr| addr.to_string())
+                                .collect();
+                        }
+                        // save the history as we go so that it survives a crash
+                        if let Err(e) = rl.append_history(&SETTINGS.history_file_path) {
+                            println!("Failed to save the history: {}", e);
+                        }
                     }
                     Err(_) => {
                         println!("Command not found!\ntype \"help\" to get the list of commands")
@@ -158,18 +203,21 @@ pub(crate) async fn run(
             }
         }
     }
-    rl.append_history(&SETTINGS.history_file_path).unwrap();
+    rl.append_history(&SETTINGS.history_file_path)?;
     Ok(())
 }
 
 struct MassaCompleter {
     file_completer: FilenameCompleter,
+    /// addresses of the wallet, known once it is unlocked
+    wallet_addresses: Vec<String>,
 }
 
 impl MassaCompleter {
     fn new() -> Self {
         Self {
             file_completer: FilenameCompleter::new(),
+            wallet_addresses: Vec::new(),
         }
     }
 }
@@ -183,7 +231,27 @@ impl Completer for MassaCompleter {
         ctx: &rustyline::Context<'_>,
     ) -> rustyline::Result<(usize, Vec<Self::Candidate>)> {
         if line.contains(' ') {
-            self.file_completer.complete(line, pos, ctx)
+            // parameters: wallet addresses and address book aliases, or else file names
+            let start = line[..pos].rfind(' ').map_or(0, |i| i + 1);
+            let word = &line[start..pos];
+            let aliases: Vec<String> = AddressBook::load()
+                .map(|address_book| address_book.entries.into_keys().collect())
+                .unwrap_or_else(|_| Vec::new());
+            let candidates: Vec<Pair> = self
+                .wallet_addresses
+                .iter()
+                .chain(aliases.iter())
+                .filter(|candidate| candidate.starts_with(word))
+                .map(|candidate| Pair {
+                    display: candidate.clone(),
+                    replacement: candidate.clone(),
+                })
+                .collect();
+            if candidates.is_empty() {
+                self.file_completer.complete(line, pos, ctx)
+            } else {
+                Ok((start, candidates))
+            }
         } else {
             let mut candidates = Vec::new();
             for cmd in Command::iter() {
@@ -0,0 +1,155 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Guided rotation of a staking key, driven by `rotate_staking_key`.
+//!
+//! Rolls cannot be transferred between addresses, so a rotation goes through their sale:
+//! 1. a new key is generated, added to the wallet and registered for staking on the node,
+//!    and all the rolls of the old address are sold. The node keeps staking with the old key,
+//!    whose sold rolls still count in the draws until they leave the selection lookback;
+//! 2. once the coins of the sold rolls are credited back, the balance of the old address is
+//!    transferred to the new one;
+//! 3. the new address buys rolls with it;
+//! 4. once the rolls of the new address are active and the old address has none left,
+//!    the old key is removed from the staking keys of the node. It stays in the wallet.
+//!
+//! Each step waits for the final state of the previous one, and the progress is stored in the
+//! `key_rotation_file_path` file of the client configuration, so `rotate_staking_key continue`
+//! can be run at any time, e.g. once per cycle.
+
+use crate::settings::SETTINGS;
+use anyhow::{anyhow, Result};
+use massa_api_exports::address::AddressInfo;
+use massa_models::{address::Address, amount::Amount};
+use serde::{Deserialize, Serialize};
+
+/// Step of a key rotation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RotationStage {
+    /// the rolls of the old address were sold, their coins are not credited back yet
+    SellingRolls,
+    /// the balance of the old address was transferred to the new one
+    Transferred,
+    /// the new address bought rolls, they are not active yet
+    RollsBought,
+    /// the old key was removed from the staking keys of the node
+    Done,
+}
+
+/// Progress of a key rotation
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct KeyRotation {
+    /// rotated address
+    pub old_address: Address,
+    /// address of the new key
+    pub new_address: Address,
+    /// current step
+    pub stage: RotationStage,
+}
+
+/// Next action of a key rotation
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RotationAction {
+    /// nothing to do until the given condition is met
+    Wait(String),
+    /// transfer this amount from the old address to the new one
+    Transfer(Amount),
+    /// buy this number of rolls with the new address
+    BuyRolls(u64),
+    /// remove the old key from the staking keys of the node
+    Retire,
+}
+
+impl KeyRotation {
+    /// Loads the rotation in progress, if any
+    pub(crate) fn load() -> Result<Option<KeyRotation>> {
+        let path = &SETTINGS.key_rotation_file_path;
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| anyhow!("invalid key rotation file {}: {}", path.display(), e))
+    }
+
+    /// Stores the progress of the rotation
+    pub(crate) fn save(&self) -> Result<()> {
+        let path = &SETTINGS.key_rotation_file_path;
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| anyhow!("failed to write {}: {}", path.display(), e))
+    }
+
+    /// Next action of the rotation, given the current state of both addresses
+    ///
+    /// # Arguments
+    /// * `old`, `new`: info of the old and new addresses
+    /// * `roll_price`: price of a roll
+    /// * `fee`: fee of the operations sent by the rotation
+    pub(crate) fn next_action(
+        &self,
+        old: &AddressInfo,
+        new: &AddressInfo,
+        roll_price: Amount,
+        fee: Amount,
+    ) -> RotationAction {
+        match self.stage {
+            RotationStage::SellingRolls => {
+                if old.final_roll_count > 0 || !old.deferred_credits.is_empty() {
+                    return RotationAction::Wait(format!(
+                        "the coins of the sold rolls of {} are credited back",
+                        self.old_address
+                    ));
+                }
+                match old.final_balance.checked_sub(fee) {
+                    Some(amount) if !amount.is_zero() => RotationAction::Transfer(amount),
+                    _ => RotationAction::Wait(format!(
+                        "{} has more than the fee on its final balance",
+                        self.old_address
+                    )),
+                }
+            }
+            RotationStage::Transferred => {
+                let roll_count = match new.final_balance.checked_sub(fee) {
+                    Some(balance) if !roll_price.is_zero() => {
+                        balance.to_raw() / roll_price.to_raw()
+                    }
+                    _ => 0,
+                };
+                if roll_count == 0 {
+                    return RotationAction::Wait(format!(
+                        "the transfer to {} is final",
+                        self.new_address
+                    ));
+                }
+                RotationAction::BuyRolls(roll_count)
+            }
+            RotationStage::RollsBought => {
+                let active_rolls = |info: &AddressInfo| {
+                    info.cycle_infos
+                        .iter()
+                        .filter_map(|cycle| cycle.active_rolls)
+                        .last()
+                        .unwrap_or_default()
+                };
+                if new.final_roll_count == 0 || active_rolls(new) == 0 {
+                    return RotationAction::Wait(format!(
+                        "the rolls of {} are active",
+                        self.new_address
+                    ));
+                }
+                if active_rolls(old) > 0 {
+                    return RotationAction::Wait(format!(
+                        "the sold rolls of {} leave the selection",
+                        self.old_address
+                    ));
+                }
+                RotationAction::Retire
+            }
+            RotationStage::Done => {
+                RotationAction::Wait("nothing, the rotation is done".to_string())
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 //! Build here the default client settings from the configuration file toml
+use massa_models::amount::Amount;
 use massa_models::config::build_massa_settings;
 use massa_time::MassaTime;
 use serde::Deserialize;
-use std::{net::IpAddr, path::PathBuf};
+use std::{collections::HashMap, net::IpAddr, path::PathBuf};
 
 lazy_static::lazy_static! {
     pub static ref SETTINGS: Settings = build_massa_settings("massa-client", "MASSA_CLIENT");
@@ -15,8 +16,55 @@ pub struct Settings {
     pub default_node: DefaultNode,
     pub history: usize,
     pub history_file_path: PathBuf,
+    pub address_book_file_path: PathBuf,
+    pub deployer_file_path: PathBuf,
+    pub key_rotation_file_path: PathBuf,
     pub timeout: MassaTime,
     pub client: ClientSettings,
+    /// hooks on the signatures made with the wallet
+    #[serde(default)]
+    pub signing: SigningSettings,
+    /// profile used when `--profile` is not given
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    /// named profiles, selected with `--profile`
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+}
+
+/// Hooks on the signatures made with the wallet
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct SigningSettings {
+    /// append-only log of the signature requests
+    pub audit_log_path: Option<PathBuf>,
+    /// operations moving more coins need a second confirmation
+    pub transfer_confirmation_threshold: Option<Amount>,
+}
+
+/// Wallet and node of a named profile.
+/// The values left out fall back to the command line arguments or to the global settings.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Profile {
+    pub wallet: Option<PathBuf>,
+    pub ip: Option<IpAddr>,
+    pub private_port: Option<u16>,
+    pub public_port: Option<u16>,
+    pub grpc_port: Option<u16>,
+}
+
+impl Settings {
+    /// Gets the profile named `name`, or the default profile if there is no name.
+    /// Without any of them, the profile is empty so that the global settings apply.
+    pub fn profile(&self, name: Option<&str>) -> anyhow::Result<Profile> {
+        match name.or(self.default_profile.as_deref()) {
+            Some(name) => {
+                self.profiles.get(name).cloned().ok_or_else(|| {
+                    anyhow::anyhow!("profile {} not found in the configuration", name)
+                })
+            }
+            None => Ok(Profile::default()),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -54,3 +102,26 @@ pub struct HttpSettings {
 fn test_load_client_config() {
     let _ = *SETTINGS;
 }
+
+#[cfg(test)]
+#[test]
+fn test_profile_selection() {
+    let mut settings = SETTINGS.clone();
+    settings.profiles.insert(
+        "staking".to_string(),
+        Profile {
+            wallet: Some(PathBuf::from("wallet_staking.dat")),
+            ..Default::default()
+        },
+    );
+    settings.default_profile = None;
+    assert!(settings.profile(None).unwrap().wallet.is_none());
+    assert_eq!(
+        settings.profile(Some("staking")).unwrap().wallet,
+        Some(PathBuf::from("wallet_staking.dat"))
+    );
+    assert!(settings.profile(Some("hot")).is_err());
+
+    settings.default_profile = Some("staking".to_string());
+    assert!(settings.profile(None).unwrap().wallet.is_some());
+}
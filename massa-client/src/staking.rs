@@ -0,0 +1,91 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Staking summary of addresses, shown by `staking_info`.
+
+use massa_api_exports::{address::AddressInfo, slot::SlotAmount};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    slot::{IndexedSlot, Slot},
+};
+use serde::Serialize;
+
+/// Maximum number of upcoming draws listed per address
+const MAX_LISTED_DRAWS: usize = 10;
+
+/// Staking summary of an address
+#[derive(Debug, Serialize)]
+pub(crate) struct StakingInfo {
+    /// address
+    pub address: Address,
+    /// rolls of the address in the final state
+    pub final_rolls: u64,
+    /// rolls of the address in the candidate state
+    pub candidate_rolls: u64,
+    /// rolls taken into account for the selection of the current cycle
+    pub active_rolls: Option<u64>,
+    /// next slots at which the address creates a block
+    pub next_block_draws: Vec<Slot>,
+    /// next slots at which the address creates an endorsement
+    pub next_endorsement_draws: Vec<IndexedSlot>,
+    /// coins credited to the address later on, e.g. after selling rolls
+    pub deferred_credits: Vec<SlotAmount>,
+    /// blocks created over the cycles known by the node
+    pub produced_blocks: u64,
+    /// blocks missed over the cycles known by the node
+    pub missed_blocks: u64,
+    /// `missed_blocks / (produced_blocks + missed_blocks)`, if the address was selected
+    pub missed_block_ratio: Option<f64>,
+    /// block rewards of the upcoming block draws, reduced by the missed block ratio
+    pub estimated_block_rewards: Amount,
+}
+
+impl StakingInfo {
+    /// Builds the staking summary of an address
+    ///
+    /// # Arguments
+    /// * `info`: address info returned by the node
+    /// * `block_reward`: reward of a block creator
+    pub(crate) fn new(info: &AddressInfo, block_reward: Amount) -> Self {
+        let (produced_blocks, missed_blocks) =
+            info.cycle_infos.iter().fold((0, 0), |(ok, nok), cycle| {
+                (ok + cycle.ok_count, nok + cycle.nok_count)
+            });
+        let missed_block_ratio = match produced_blocks + missed_blocks {
+            0 => None,
+            total => Some(missed_blocks as f64 / total as f64),
+        };
+        // count the upcoming draws at the rate at which the address produced its past blocks
+        let expected_blocks = match produced_blocks + missed_blocks {
+            0 => info.next_block_draws.len() as u64,
+            total => info.next_block_draws.len() as u64 * produced_blocks / total,
+        };
+        StakingInfo {
+            address: info.address,
+            final_rolls: info.final_roll_count,
+            candidate_rolls: info.candidate_roll_count,
+            active_rolls: info
+                .cycle_infos
+                .iter()
+                .filter_map(|cycle| cycle.active_rolls)
+                .last(),
+            next_block_draws: info
+                .next_block_draws
+                .iter()
+                .take(MAX_LISTED_DRAWS)
+                .copied()
+                .collect(),
+            next_endorsement_draws: info
+                .next_endorsement_draws
+                .iter()
+                .take(MAX_LISTED_DRAWS)
+                .cloned()
+                .collect(),
+            deferred_credits: info.deferred_credits.clone(),
+            produced_blocks,
+            missed_blocks,
+            missed_block_ratio,
+            estimated_block_rewards: block_reward.saturating_mul_u64(expected_blocks),
+        }
+    }
+}
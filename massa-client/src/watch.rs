@@ -0,0 +1,261 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Live views of the node, printed as the updates come until CTRL+C.
+//!
+//! The updates are read from the gRPC streams of the node. In JSON mode, each update is printed
+//! as a JSON object on its own line.
+
+use crate::{grpc_error, rpc_error};
+use anyhow::{anyhow, bail, Result};
+use console::style;
+use massa_api_exports::address::AddressInfo;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_sdk::{Client, SubscriptionRequest};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// A new block
+#[derive(Debug, Serialize)]
+struct WatchedBlock {
+    id: String,
+    period: u64,
+    thread: u32,
+    creator: String,
+    operation_count: usize,
+}
+
+impl Display for WatchedBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Block {} at slot ({}, {}) by {}, {} operations",
+            self.id, self.period, self.thread, self.creator, self.operation_count
+        )
+    }
+}
+
+/// A final smart contract event
+#[derive(Debug, Serialize)]
+struct WatchedEvent {
+    period: u64,
+    thread: u32,
+    block_id: Option<String>,
+    call_stack: Vec<String>,
+    is_error: bool,
+    data: String,
+}
+
+impl Display for WatchedEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Event at slot ({}, {})", self.period, self.thread)?;
+        if let Some(caller) = self.call_stack.last() {
+            write!(f, " from {}", caller)?;
+        }
+        if self.is_error {
+            write!(f, " {}", style("(error)").red())?;
+        }
+        write!(f, ": {}", self.data)
+    }
+}
+
+/// Balances and rolls of an address, at a final slot
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct WatchedAddress {
+    address: Address,
+    final_balance: Amount,
+    candidate_balance: Amount,
+    final_roll_count: u64,
+    candidate_roll_count: u64,
+}
+
+impl From<&AddressInfo> for WatchedAddress {
+    fn from(info: &AddressInfo) -> Self {
+        WatchedAddress {
+            address: info.address,
+            final_balance: info.final_balance,
+            candidate_balance: info.candidate_balance,
+            final_roll_count: info.final_roll_count,
+            candidate_roll_count: info.candidate_roll_count,
+        }
+    }
+}
+
+impl Display for WatchedAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: balance {} (candidate {}), rolls {} (candidate {})",
+            self.address,
+            self.final_balance,
+            self.candidate_balance,
+            self.final_roll_count,
+            self.candidate_roll_count
+        )
+    }
+}
+
+/// Prints an update
+fn print_update<T: Serialize + Display>(update: &T, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(update)?);
+    } else {
+        println!("{}", update);
+    }
+    Ok(())
+}
+
+/// Prints the new blocks of the node
+pub(crate) async fn watch_blocks(client: &mut Client, json: bool) -> Result<()> {
+    let Some(grpc) = client.grpc.as_mut() else {
+        bail!("watch requires the grpc api of the node");
+    };
+    let mut stream = match grpc
+        .new_blocks(SubscriptionRequest::new(
+            grpc_api::NewBlocksRequest::default(),
+        ))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(e) => grpc_error!(e),
+    };
+    loop {
+        let response = tokio::select! {
+            message = stream.message() => message,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let Some(response) = response.map_err(|e| anyhow!("stream error: {}", e))? else {
+            return Ok(());
+        };
+        let Some(block) = response.block else {
+            continue;
+        };
+        let content = block.content.unwrap_or_default();
+        let slot = content
+            .header
+            .and_then(|header| header.content)
+            .and_then(|header| header.slot)
+            .unwrap_or_default();
+        print_update(
+            &WatchedBlock {
+                id: block.id,
+                period: slot.period,
+                thread: slot.thread,
+                creator: block.content_creator_address,
+                operation_count: content.operations.len(),
+            },
+            json,
+        )?;
+    }
+}
+
+/// Prints the final events of the node, only the ones involving `address` if given
+pub(crate) async fn watch_events(
+    client: &mut Client,
+    address: Option<Address>,
+    json: bool,
+) -> Result<()> {
+    let Some(grpc) = client.grpc.as_mut() else {
+        bail!("watch requires the grpc api of the node");
+    };
+    let mut stream = match grpc
+        .new_slot_execution_outputs(SubscriptionRequest::new(
+            grpc_api::NewSlotExecutionOutputsRequest::default(),
+        ))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(e) => grpc_error!(e),
+    };
+    let address = address.map(|address| address.to_string());
+    loop {
+        let response = tokio::select! {
+            message = stream.message() => message,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let Some(response) = response.map_err(|e| anyhow!("stream error: {}", e))? else {
+            return Ok(());
+        };
+        let Some(output) = final_execution_output(response.output) else {
+            continue;
+        };
+        for event in output.events {
+            let context = event.context.unwrap_or_default();
+            if let Some(address) = &address {
+                if !context.call_stack.contains(address) {
+                    continue;
+                }
+            }
+            let slot = context.origin_slot.unwrap_or_default();
+            print_update(
+                &WatchedEvent {
+                    period: slot.period,
+                    thread: slot.thread,
+                    block_id: context.block_id,
+                    is_error: context
+                        .status
+                        .contains(&(grpc_model::ScExecutionEventStatus::Failure as i32)),
+                    call_stack: context.call_stack,
+                    data: event.data,
+                },
+                json,
+            )?;
+        }
+    }
+}
+
+/// Prints the balances and rolls of an address each time they change, checked at each final slot
+pub(crate) async fn watch_address(client: &mut Client, address: Address, json: bool) -> Result<()> {
+    let Some(grpc) = client.grpc.as_mut() else {
+        bail!("watch requires the grpc api of the node");
+    };
+    let mut stream = match grpc
+        .new_slot_execution_outputs(SubscriptionRequest::new(
+            grpc_api::NewSlotExecutionOutputsRequest::default(),
+        ))
+        .await
+    {
+        Ok(response) => response.into_inner(),
+        Err(e) => grpc_error!(e),
+    };
+    let mut last: Option<WatchedAddress> = None;
+    loop {
+        let response = tokio::select! {
+            message = stream.message() => message,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let Some(response) = response.map_err(|e| anyhow!("stream error: {}", e))? else {
+            return Ok(());
+        };
+        if final_execution_output(response.output).is_none() {
+            continue;
+        }
+        let info = match client.public.get_addresses(vec![address]).await {
+            Ok(infos) => match infos.first() {
+                Some(info) => WatchedAddress::from(info),
+                None => bail!("address {} not found", address),
+            },
+            Err(e) => rpc_error!(e),
+        };
+        if last.as_ref() != Some(&info) {
+            print_update(&info, json)?;
+            last = Some(info);
+        }
+    }
+}
+
+/// Keeps the execution output of a final slot
+fn final_execution_output(
+    output: Option<grpc_model::SlotExecutionOutput>,
+) -> Option<grpc_model::ExecutionOutput> {
+    let output = output?;
+    if !output
+        .status
+        .contains(&(grpc_model::ExecutionOutputStatus::Final as i32))
+    {
+        return None;
+    }
+    output.execution_output
+}
@@ -1,6 +1,6 @@
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionController;
-use massa_models::block::{FilledBlock, SecureShareBlock};
+use massa_models::block::{DiscardedBlockEvent, FilledBlock, ReorgEvent, SecureShareBlock};
 use massa_models::block_header::BlockHeader;
 use massa_models::block_id::BlockId;
 use massa_models::secure_share::SecureShare;
@@ -29,4 +29,8 @@ pub struct ConsensusChannels {
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
     pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    /// Channel used to broadcast the blockclique changes reverting blocks
+    pub reorg_sender: tokio::sync::broadcast::Sender<ReorgEvent>,
+    /// Channel used to broadcast the blocks discarded by the consensus, with the reason why
+    pub discarded_block_sender: tokio::sync::broadcast::Sender<DiscardedBlockEvent>,
 }
@@ -3,8 +3,13 @@ use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    block::{BlockFinality, BlockGraphStatus},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    clique::{Clique, CliqueInspection},
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::ConsensusStats,
 };
 use massa_storage::Storage;
 
@@ -33,12 +38,28 @@ pub trait ConsensusController: Send + Sync {
     /// The statuses of the blocks sorted by the order of the input list
     fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus>;
 
+    /// Get the finality of a block
+    ///
+    /// # Arguments
+    /// * `block_id`: the id of the block
+    ///
+    /// # Returns
+    /// The status of the block, its number of confirmations and the fitness it still misses to become final,
+    /// None if the block is unknown
+    fn get_block_status(&self, block_id: &BlockId) -> Option<BlockFinality>;
+
     /// Get all the cliques of the graph
     ///
     /// # Returns
     /// The list of cliques
     fn get_cliques(&self) -> Vec<Clique>;
 
+    /// Inspect the maximal cliques of the graph
+    ///
+    /// # Returns
+    /// The list of cliques, with the fitness of their blocks, the blocks exclusive to each of them and their tie-breaking hash sums
+    fn get_cliques_inspection(&self) -> Vec<CliqueInspection>;
+
     /// Get a part of the graph to send to a node for it to setup its graph.
     /// Used for bootstrap.
     ///
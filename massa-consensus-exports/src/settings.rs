@@ -1,3 +1,4 @@
+use massa_models::{block_id::BlockId, slot::Slot};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,8 @@ pub struct ConsensusConfig {
     pub max_future_processing_blocks: usize,
     /// Maximum number of blocks allowed in `DependencyWaitingBlocks`.
     pub max_dependency_blocks: usize,
+    /// Maximum number of orphan headers kept when there are too many blocks waiting for dependencies.
+    pub max_orphan_headers: usize,
     /// old blocks are pruned every `block_db_prune_interval`
     pub block_db_prune_interval: MassaTime,
     /// Max gas per block for the execution configuration
@@ -50,6 +53,20 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// reorgs channel capacity
+    pub broadcast_reorgs_channel_capacity: usize,
+    /// discarded blocks channel capacity
+    pub broadcast_discarded_blocks_channel_capacity: usize,
+    /// maximum number of pending incoming headers whose signatures are verified together
+    pub header_verification_batch_size: usize,
     /// last start period
     pub last_start_period: u64,
+    /// trusted final block and its slot: the block and its ancestors are not validated,
+    /// and no other block is accepted at its slot
+    pub trusted_checkpoint: Option<(Slot, BlockId)>,
+    /// ancestors of the trusted checkpoint, trusted before they are received
+    pub trusted_checkpoint_ancestors: Vec<BlockId>,
+    /// header-only relay mode: execution is disabled and does not feed the selector,
+    /// so the PoS draws of the blocks and of their endorsements are not checked
+    pub header_only: bool,
 }
@@ -19,6 +19,7 @@ impl Default for ConsensusConfig {
             max_discarded_blocks: 10000,
             max_future_processing_blocks: 100,
             max_dependency_blocks: 2048,
+            max_orphan_headers: 1024,
             block_db_prune_interval: MassaTime::from_millis(5000),
             max_gas_per_block: MAX_GAS_PER_BLOCK,
             delta_f0: DELTA_F0,
@@ -35,7 +36,13 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_reorgs_channel_capacity: 128,
+            broadcast_discarded_blocks_channel_capacity: 128,
+            header_verification_batch_size: 64,
             last_start_period: 0,
+            trusted_checkpoint: None,
+            trusted_checkpoint_ancestors: Vec::new(),
+            header_only: false,
         }
     }
 }
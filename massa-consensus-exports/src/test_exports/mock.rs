@@ -6,8 +6,14 @@ use std::sync::{
 };
 
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    prehash::PreHashSet, secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    block::{BlockFinality, BlockGraphStatus},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    clique::{Clique, CliqueInspection},
+    prehash::PreHashSet,
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::ConsensusStats,
     streaming_step::StreamingStep,
 };
 use massa_storage::Storage;
@@ -31,6 +37,10 @@ pub enum MockConsensusControllerMessage {
         block_ids: Vec<BlockId>,
         response_tx: mpsc::Sender<Vec<BlockGraphStatus>>,
     },
+    GetBlockStatus {
+        block_id: BlockId,
+        response_tx: mpsc::Sender<Option<BlockFinality>>,
+    },
     GetBlockGraphStatuses {
         start_slot: Option<Slot>,
         end_slot: Option<Slot>,
@@ -39,6 +49,9 @@ pub enum MockConsensusControllerMessage {
     GetCliques {
         response_tx: mpsc::Sender<Vec<Clique>>,
     },
+    GetCliquesInspection {
+        response_tx: mpsc::Sender<Vec<CliqueInspection>>,
+    },
     GetBootstrapableGraph {
         cursor: StreamingStep<PreHashSet<BlockId>>,
         execution_cursor: StreamingStep<Slot>,
@@ -105,8 +118,12 @@ mockall::mock! {
 
         fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus>;
 
+        fn get_block_status(&self, block_id: &BlockId) -> Option<BlockFinality>;
+
         fn get_cliques(&self) -> Vec<Clique>;
 
+        fn get_cliques_inspection(&self) -> Vec<CliqueInspection>;
+
         fn get_bootstrap_part(
             &self,
             cursor: StreamingStep<PreHashSet<BlockId>>,
@@ -200,6 +217,19 @@ impl ConsensusController for ConsensusControllerImpl {
         response_rx.recv().unwrap()
     }
 
+    fn get_block_status(&self, block_id: &BlockId) -> Option<BlockFinality> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.0
+            .lock()
+            .unwrap()
+            .send(MockConsensusControllerMessage::GetBlockStatus {
+                block_id: *block_id,
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn get_cliques(&self) -> Vec<Clique> {
         let (response_tx, response_rx) = mpsc::channel();
         self.0
@@ -210,6 +240,16 @@ impl ConsensusController for ConsensusControllerImpl {
         response_rx.recv().unwrap()
     }
 
+    fn get_cliques_inspection(&self) -> Vec<CliqueInspection> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.0
+            .lock()
+            .unwrap()
+            .send(MockConsensusControllerMessage::GetCliquesInspection { response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn get_bootstrap_part(
         &self,
         cursor: StreamingStep<PreHashSet<BlockId>>,
@@ -6,10 +6,10 @@ use massa_consensus_exports::{
 };
 use massa_models::denunciation::DenunciationPrecursor;
 use massa_models::{
-    block::{BlockGraphStatus, FilledBlock},
+    block::{BlockFinality, BlockGraphStatus, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueInspection},
     operation::{Operation, OperationId},
     prehash::PreHashSet,
     secure_share::SecureShare,
@@ -91,6 +91,17 @@ impl ConsensusController for ConsensusControllerImpl {
             .collect()
     }
 
+    /// Get the finality of a block
+    ///
+    /// # Arguments:
+    /// * `block_id`: the id of the block
+    ///
+    /// # Returns:
+    /// The finality of the block, None if it is unknown
+    fn get_block_status(&self, block_id: &BlockId) -> Option<BlockFinality> {
+        self.shared_state.read().get_block_finality(block_id)
+    }
+
     /// Get all the cliques possible in the block graph.
     ///
     /// # Returns:
@@ -99,6 +110,14 @@ impl ConsensusController for ConsensusControllerImpl {
         self.shared_state.read().max_cliques.clone()
     }
 
+    /// Inspect the maximal cliques of the graph.
+    ///
+    /// # Returns:
+    /// The cliques with the breakdown of their fitness
+    fn get_cliques_inspection(&self) -> Vec<CliqueInspection> {
+        self.shared_state.read().inspect_cliques()
+    }
+
     /// Get a part of the graph to send to a node so that he can setup his graph.
     /// Used for bootstrap.
     ///
@@ -0,0 +1,206 @@
+use massa_models::{
+    block_header::SecuredHeader, block_id::BlockId, prehash::PreHashMap, slot::Slot,
+};
+
+/// Trusted checkpoint: a final block known in advance, whose header and ancestors are not validated.
+///
+/// The blocks are trusted by id, never by slot. The checkpoint and the chain of its ancestors given
+/// in the configuration are trusted before the sync starts, so that they are recognized as they arrive,
+/// oldest first. The parents of a trusted header also become trusted when the header is noted, so that
+/// the trust only extends to the actual ancestors of the checkpoint. The id of a header is the hash of
+/// its content, so a trusted id commits to the whole content of the header, including its parents.
+/// Any other block, at any slot, is validated as usual.
+#[derive(Clone)]
+pub(crate) struct TrustedCheckpoint {
+    /// slot and id of the checkpoint
+    checkpoint: Option<(Slot, BlockId)>,
+    /// the checkpoint and its ancestors known so far, with their slot once their header is noted
+    trusted_blocks: PreHashMap<BlockId, Option<Slot>>,
+}
+
+impl TrustedCheckpoint {
+    /// Creates the trust set of `checkpoint` and of its configured `ancestors`,
+    /// or an empty one if there is no checkpoint
+    pub(crate) fn new(
+        checkpoint: Option<(Slot, BlockId)>,
+        ancestors: impl IntoIterator<Item = BlockId>,
+    ) -> Self {
+        let mut trusted_blocks = PreHashMap::default();
+        if let Some((slot, block_id)) = checkpoint {
+            trusted_blocks.extend(ancestors.into_iter().map(|ancestor| (ancestor, None)));
+            trusted_blocks.insert(block_id, Some(slot));
+        }
+        TrustedCheckpoint {
+            checkpoint,
+            trusted_blocks,
+        }
+    }
+
+    /// Whether the header of `block_id` is the checkpoint or one of its ancestors
+    pub(crate) fn is_trusted(&self, block_id: &BlockId, header: &SecuredHeader) -> bool {
+        header.id == *block_id && self.trusted_blocks.contains_key(block_id)
+    }
+
+    /// Notes an incoming header: the parents of a trusted header are trusted in turn
+    pub(crate) fn note_header(&mut self, block_id: &BlockId, header: &SecuredHeader) {
+        if self.is_trusted(block_id, header) {
+            self.trusted_blocks
+                .insert(*block_id, Some(header.content.slot));
+            for parent in header.content.parents.iter() {
+                self.trusted_blocks.entry(*parent).or_insert(None);
+            }
+        }
+    }
+
+    /// Forgets the trusted blocks that are final, given the latest final block and period of each thread.
+    /// All of them are forgotten once the checkpoint is final, as its ancestors are final too.
+    pub(crate) fn prune_final(&mut self, latest_final_blocks_periods: &[(BlockId, u64)]) {
+        let is_final = |slot: &Slot| {
+            latest_final_blocks_periods
+                .get(slot.thread as usize)
+                .map_or(false, |(_, period)| slot.period <= *period)
+        };
+        if self
+            .checkpoint
+            .map_or(false, |(checkpoint_slot, _)| is_final(&checkpoint_slot))
+        {
+            self.trusted_blocks.clear();
+            return;
+        }
+        self.trusted_blocks
+            .retain(|_, slot| !slot.as_ref().map_or(false, is_final));
+    }
+
+    /// Returns the checkpoint if the header of `block_id` is another block at its slot
+    pub(crate) fn conflicting_checkpoint(
+        &self,
+        block_id: &BlockId,
+        header: &SecuredHeader,
+    ) -> Option<(Slot, BlockId)> {
+        self.checkpoint.filter(|(checkpoint_slot, checkpoint_id)| {
+            header.content.slot == *checkpoint_slot && *block_id != *checkpoint_id
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::{
+        block_header::{BlockHeader, BlockHeaderSerializer},
+        secure_share::SecureShareContent,
+    };
+    use massa_signature::KeyPair;
+
+    fn header(slot: Slot, parents: Vec<BlockId>, seed: &[u8]) -> (BlockId, SecuredHeader) {
+        let keypair = KeyPair::generate(0).unwrap();
+        let header = BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: 0,
+                slot,
+                parents,
+                operation_merkle_root: Hash::compute_from(seed),
+                endorsements: Vec::new(),
+                denunciations: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        (header.id, header)
+    }
+
+    #[test]
+    fn test_trusted_checkpoint_ancestry() {
+        let (grandparent_id, grandparent) = header(Slot::new(1, 0), vec![], b"grandparent");
+        let (parent_id, parent) = header(Slot::new(2, 0), vec![grandparent_id], b"parent");
+        let (checkpoint_id, checkpoint) = header(Slot::new(3, 0), vec![parent_id], b"checkpoint");
+        let mut trust = TrustedCheckpoint::new(Some((Slot::new(3, 0), checkpoint_id)), []);
+
+        // without a configured chain, the ancestors are only trusted once their trusted descendant is noted
+        assert!(trust.is_trusted(&checkpoint_id, &checkpoint));
+        assert!(!trust.is_trusted(&parent_id, &parent));
+        trust.note_header(&checkpoint_id, &checkpoint);
+        assert!(trust.is_trusted(&parent_id, &parent));
+        assert!(!trust.is_trusted(&grandparent_id, &grandparent));
+        trust.note_header(&parent_id, &parent);
+        assert!(trust.is_trusted(&grandparent_id, &grandparent));
+        assert!(trust
+            .conflicting_checkpoint(&checkpoint_id, &checkpoint)
+            .is_none());
+    }
+
+    #[test]
+    fn test_forged_block_at_past_slot_not_trusted() {
+        let (parent_id, parent) = header(Slot::new(2, 0), vec![], b"parent");
+        let (checkpoint_id, checkpoint) = header(Slot::new(3, 0), vec![parent_id], b"checkpoint");
+        let mut trust = TrustedCheckpoint::new(Some((Slot::new(3, 0), checkpoint_id)), []);
+        trust.note_header(&checkpoint_id, &checkpoint);
+
+        // a forged block at a slot before the checkpoint is not one of its ancestors
+        let (forged_id, forged) = header(Slot::new(2, 0), vec![], b"forged");
+        assert!(!trust.is_trusted(&forged_id, &forged));
+        // nor are the blocks it claims as parents
+        let (forged_parent_id, forged_parent) = header(Slot::new(1, 0), vec![], b"forged parent");
+        let (forged_child_id, forged_child) =
+            header(Slot::new(2, 1), vec![forged_parent_id], b"forged child");
+        trust.note_header(&forged_child_id, &forged_child);
+        assert!(!trust.is_trusted(&forged_parent_id, &forged_parent));
+
+        // a header presented under the id of a trusted block is not trusted
+        assert!(!trust.is_trusted(&parent_id, &forged));
+        assert!(trust.is_trusted(&parent_id, &parent));
+
+        // another block at the slot of the checkpoint conflicts with it
+        let (other_id, other) = header(Slot::new(3, 0), vec![parent_id], b"other");
+        assert_eq!(
+            trust.conflicting_checkpoint(&other_id, &other),
+            Some((Slot::new(3, 0), checkpoint_id))
+        );
+        assert!(!trust.is_trusted(&other_id, &other));
+    }
+
+    #[test]
+    fn test_no_checkpoint() {
+        let (block_id, block) = header(Slot::new(1, 0), vec![], b"block");
+        let mut trust = TrustedCheckpoint::new(None, [block_id]);
+        trust.note_header(&block_id, &block);
+        assert!(!trust.is_trusted(&block_id, &block));
+        assert!(trust.conflicting_checkpoint(&block_id, &block).is_none());
+    }
+
+    #[test]
+    fn test_configured_chain_trusted_on_arrival_and_pruned_once_final() {
+        let (grandparent_id, grandparent) = header(Slot::new(1, 0), vec![], b"grandparent");
+        let (parent_id, parent) = header(Slot::new(2, 0), vec![grandparent_id], b"parent");
+        let (checkpoint_id, checkpoint) = header(Slot::new(3, 0), vec![parent_id], b"checkpoint");
+        let mut trust = TrustedCheckpoint::new(
+            Some((Slot::new(3, 0), checkpoint_id)),
+            [grandparent_id, parent_id],
+        );
+
+        // the configured ancestors are trusted as they arrive, oldest first
+        assert!(trust.is_trusted(&grandparent_id, &grandparent));
+        trust.note_header(&grandparent_id, &grandparent);
+        assert!(trust.is_trusted(&parent_id, &parent));
+        trust.note_header(&parent_id, &parent);
+        trust.note_header(&checkpoint_id, &checkpoint);
+
+        // the final ones are forgotten
+        trust.prune_final(&[(grandparent_id, 1), (grandparent_id, 0)]);
+        assert!(!trust.is_trusted(&grandparent_id, &grandparent));
+        assert!(trust.is_trusted(&parent_id, &parent));
+        assert!(trust.is_trusted(&checkpoint_id, &checkpoint));
+
+        // all of them once the checkpoint is final, even those never received
+        let (unseen_id, unseen) = header(Slot::new(1, 1), vec![], b"unseen");
+        let mut trust = TrustedCheckpoint::new(Some((Slot::new(3, 0), checkpoint_id)), [unseen_id]);
+        trust.prune_final(&[(parent_id, 2), (parent_id, 2)]);
+        assert!(trust.is_trusted(&unseen_id, &unseen));
+        trust.prune_final(&[(checkpoint_id, 3), (parent_id, 2)]);
+        assert!(!trust.is_trusted(&unseen_id, &unseen));
+        assert!(!trust.is_trusted(&checkpoint_id, &checkpoint));
+    }
+}
@@ -5,7 +5,12 @@ use massa_consensus_exports::{
     error::ConsensusError,
 };
 use massa_logging::massa_trace;
-use massa_models::{block_id::BlockId, clique::Clique, prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    block_id::BlockId,
+    clique::{Clique, CliqueBlock, CliqueInspection},
+    prehash::PreHashSet,
+    slot::Slot,
+};
 
 use super::ConsensusState;
 
@@ -76,6 +81,55 @@ impl ConsensusState {
         Ok(blockclique_i)
     }
 
+    /// Inspect the maximal cliques: the fitness of their blocks, the blocks exclusive to each clique,
+    /// and the hash sum breaking the ties between the cliques of equal fitness
+    pub fn inspect_cliques(&self) -> Vec<CliqueInspection> {
+        let blockclique_fitness = self
+            .max_cliques
+            .iter()
+            .find(|clique| clique.is_blockclique)
+            .map_or(0, |clique| clique.fitness);
+        self.max_cliques
+            .iter()
+            .enumerate()
+            .map(|(index, clique)| {
+                let mut hash_sum = num::BigInt::default();
+                let mut blocks: Vec<CliqueBlock> = clique
+                    .block_ids
+                    .iter()
+                    .filter_map(|block_id| {
+                        hash_sum += num::BigInt::from_bytes_be(
+                            num::bigint::Sign::Plus,
+                            block_id.to_bytes(),
+                        );
+                        match self.block_statuses.get(block_id) {
+                            Some(BlockStatus::Active { a_block, .. }) => Some(CliqueBlock {
+                                block_id: *block_id,
+                                slot: a_block.slot,
+                                fitness: a_block.fitness,
+                                exclusive: self.max_cliques.iter().enumerate().all(
+                                    |(other_index, other)| {
+                                        other_index == index || !other.block_ids.contains(block_id)
+                                    },
+                                ),
+                            }),
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                blocks.sort_unstable_by_key(|block| (block.slot, block.block_id));
+                CliqueInspection {
+                    index,
+                    is_blockclique: clique.is_blockclique,
+                    fitness: clique.fitness,
+                    fitness_gap: blockclique_fitness.saturating_sub(clique.fitness),
+                    hash_sum: hash_sum.to_string(),
+                    blocks,
+                }
+            })
+            .collect()
+    }
+
     pub fn list_stale_blocks(&self, fitness_threshold: u64) -> PreHashSet<BlockId> {
         // iterate from largest to smallest to minimize reallocations
         let mut indices: Vec<usize> = (0..self.max_cliques.len()).collect();
@@ -150,6 +204,12 @@ impl ConsensusState {
             });
 
             // mark as stale
+            self.notify_discarded_block(
+                *block_id,
+                active_block.slot,
+                active_block.creator_address,
+                &DiscardReason::Stale,
+            );
             self.new_stale_blocks
                 .insert(*block_id, (active_block.creator_address, active_block.slot));
             self.block_statuses.insert(
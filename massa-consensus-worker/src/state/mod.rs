@@ -1,19 +1,21 @@
 use std::{
     collections::{HashMap, VecDeque},
+    sync::Arc,
     vec,
 };
 
 use massa_consensus_exports::{
     block_graph_export::BlockGraphExport,
-    block_status::{BlockStatus, ExportCompiledBlock, HeaderOrBlock},
+    block_status::{BlockStatus, DiscardReason, ExportCompiledBlock, HeaderOrBlock},
     error::ConsensusError,
     ConsensusChannels, ConsensusConfig,
 };
+use massa_db::MassaArchive;
 use massa_metrics::MassaMetrics;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
-    block::BlockGraphStatus,
+    block::{BlockFinality, BlockFinalityStatus, BlockGraphStatus},
     block_header::SecuredHeader,
     block_id::BlockId,
     clique::Clique,
@@ -24,8 +26,10 @@ use massa_storage::Storage;
 use massa_time::MassaTime;
 use tracing::debug;
 
+mod checkpoint;
 mod clique_computation;
 mod graph;
+mod orphans;
 mod process;
 mod process_commands;
 mod prune;
@@ -33,6 +37,9 @@ mod stats;
 mod tick;
 mod verifications;
 
+pub(crate) use checkpoint::TrustedCheckpoint;
+pub(crate) use orphans::OrphanHeaders;
+
 #[derive(Clone)]
 pub struct ConsensusState {
     /// Configuration
@@ -99,6 +106,25 @@ pub struct ConsensusState {
     pub nonfinal_active_blocks_per_slot: HashMap<Slot, PreHashSet<BlockId>>,
     /// massa metrics
     pub(crate) massa_metrics: MassaMetrics,
+    /// archive of the pruned blocks, in archival mode
+    pub archive: Option<Arc<MassaArchive>>,
+    /// headers evicted from the blocks waiting for dependencies, given back to the graph when their dependencies arrive
+    pub(crate) orphan_headers: OrphanHeaders,
+    /// trusted checkpoint and its known ancestors, whose headers are not validated
+    pub(crate) trusted_checkpoint: TrustedCheckpoint,
+}
+
+/// Finality status of a discarded block
+fn discarded_finality_status(reason: &DiscardReason) -> BlockFinalityStatus {
+    match reason {
+        DiscardReason::Final => BlockFinalityStatus::Final,
+        DiscardReason::Stale => BlockFinalityStatus::Discarded {
+            reason: "stale".to_string(),
+        },
+        DiscardReason::Invalid(reason) => BlockFinalityStatus::Discarded {
+            reason: format!("invalid: {}", reason),
+        },
+    }
 }
 
 impl ConsensusState {
@@ -225,6 +251,81 @@ impl ConsensusState {
         }
     }
 
+    /// Gets the finality of a block, None if the block is unknown.
+    ///
+    /// The confirmations and the fitness margin are only computed while the block is active:
+    /// they are 0 for blocks that are discarded, including final blocks pruned from the graph.
+    pub fn get_block_finality(&self, block_id: &BlockId) -> Option<BlockFinality> {
+        let Some(block_status) = self.block_statuses.get(block_id) else {
+            // blocks pruned from the graph are only known by archival nodes
+            let archived_block = self.archive.as_ref()?.get_block(block_id)?;
+            return Some(BlockFinality {
+                status: archived_block.status,
+                confirmations: 0,
+                fitness_margin: 0,
+            });
+        };
+        let a_block = match block_status {
+            BlockStatus::Active { a_block, .. } => a_block,
+            BlockStatus::Discarded { reason, .. } => {
+                return Some(BlockFinality {
+                    status: discarded_finality_status(reason),
+                    confirmations: 0,
+                    fitness_margin: 0,
+                });
+            }
+            BlockStatus::Incoming(_)
+            | BlockStatus::WaitingForSlot(_)
+            | BlockStatus::WaitingForDependencies { .. } => {
+                return Some(BlockFinality {
+                    status: BlockFinalityStatus::Candidate,
+                    confirmations: 0,
+                    fitness_margin: self.config.delta_f0.saturating_add(1),
+                })
+            }
+        };
+        let blockclique = &self
+            .max_cliques
+            .iter()
+            .find(|clique| clique.is_blockclique)
+            .expect("blockclique absent")
+            .block_ids;
+        let mut confirmations = 0;
+        let mut descendants_fitness: u64 = 0;
+        for descendant_id in a_block.descendants.iter() {
+            let descendant = match self.block_statuses.get(descendant_id) {
+                Some(BlockStatus::Active { a_block, .. }) => a_block,
+                _ => continue,
+            };
+            if blockclique.contains(descendant_id) {
+                confirmations += 1;
+                descendants_fitness = descendants_fitness.saturating_add(descendant.fitness);
+            } else if descendant.is_final {
+                confirmations += 1;
+            }
+        }
+        let (status, fitness_margin) = if a_block.is_final {
+            (BlockFinalityStatus::Final, 0)
+        } else {
+            // a block becomes final once its descendants in the blockclique weigh more than `delta_f0`
+            let fitness_margin = self
+                .config
+                .delta_f0
+                .saturating_add(1)
+                .saturating_sub(descendants_fitness);
+            if blockclique.contains(block_id) {
+                (BlockFinalityStatus::InBlockclique, fitness_margin)
+            } else {
+                (BlockFinalityStatus::Candidate, fitness_margin)
+            }
+        };
+        Some(BlockFinality {
+            status,
+            confirmations,
+            fitness_margin,
+        })
+    }
+
     /// list the latest final blocks at the given slot
     ///
     /// exclusively used by `list_required_active_blocks`
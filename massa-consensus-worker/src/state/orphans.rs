@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use massa_models::{
+    block_header::SecuredHeader,
+    block_id::BlockId,
+    prehash::{PreHashMap, PreHashSet},
+};
+
+/// Orphan header, waiting for one of its missing dependencies
+#[derive(Clone)]
+struct OrphanHeader {
+    header: SecuredHeader,
+    missing_dependencies: PreHashSet<BlockId>,
+    sequence_number: u64,
+}
+
+/// Bounded pool of the headers whose dependencies are unknown, evicted from the blocks waiting for dependencies
+/// when there are too many of them.
+///
+/// The orphans are kept out of the graph, and given back to it as soon as one of their missing dependencies becomes active,
+/// instead of being forgotten until they are asked again to the network.
+/// When the pool is full, the oldest orphans are dropped first.
+#[derive(Clone)]
+pub(crate) struct OrphanHeaders {
+    max_size: usize,
+    orphans: PreHashMap<BlockId, OrphanHeader>,
+    /// orphans indexed by missing dependency
+    by_dependency: PreHashMap<BlockId, PreHashSet<BlockId>>,
+    /// orphans by insertion order
+    by_sequence: BTreeMap<u64, BlockId>,
+    sequence_counter: u64,
+}
+
+impl OrphanHeaders {
+    /// Creates a pool of at most `max_size` orphans
+    pub(crate) fn new(max_size: usize) -> Self {
+        OrphanHeaders {
+            max_size,
+            orphans: Default::default(),
+            by_dependency: Default::default(),
+            by_sequence: Default::default(),
+            sequence_counter: 0,
+        }
+    }
+
+    /// Number of orphans in the pool
+    pub(crate) fn len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    /// Adds an orphan header waiting for `missing_dependencies`, dropping the oldest orphans if the pool is full
+    pub(crate) fn insert(
+        &mut self,
+        block_id: BlockId,
+        header: SecuredHeader,
+        missing_dependencies: PreHashSet<BlockId>,
+    ) {
+        if self.max_size == 0 || missing_dependencies.is_empty() {
+            return;
+        }
+        self.remove(&block_id);
+        while self.orphans.len() >= self.max_size {
+            match self.by_sequence.first_key_value() {
+                Some((_, oldest)) => {
+                    let oldest = *oldest;
+                    self.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        self.sequence_counter += 1;
+        for dependency in missing_dependencies.iter() {
+            self.by_dependency
+                .entry(*dependency)
+                .or_default()
+                .insert(block_id);
+        }
+        self.by_sequence.insert(self.sequence_counter, block_id);
+        self.orphans.insert(
+            block_id,
+            OrphanHeader {
+                header,
+                missing_dependencies,
+                sequence_number: self.sequence_counter,
+            },
+        );
+    }
+
+    /// Removes an orphan from the pool and returns its header
+    pub(crate) fn remove(&mut self, block_id: &BlockId) -> Option<SecuredHeader> {
+        let orphan = self.orphans.remove(block_id)?;
+        self.by_sequence.remove(&orphan.sequence_number);
+        for dependency in orphan.missing_dependencies.iter() {
+            if let Some(dependents) = self.by_dependency.get_mut(dependency) {
+                dependents.remove(block_id);
+                if dependents.is_empty() {
+                    self.by_dependency.remove(dependency);
+                }
+            }
+        }
+        Some(orphan.header)
+    }
+
+    /// Removes and returns the orphans waiting for `dependency`, which just became available
+    pub(crate) fn take_dependents(
+        &mut self,
+        dependency: &BlockId,
+    ) -> Vec<(BlockId, SecuredHeader)> {
+        let Some(dependents) = self.by_dependency.remove(dependency) else {
+            return Vec::new();
+        };
+        dependents
+            .into_iter()
+            .filter_map(|block_id| self.remove(&block_id).map(|header| (block_id, header)))
+            .collect()
+    }
+
+    /// Keeps only the orphans for which `keep` returns true
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&SecuredHeader) -> bool) {
+        let to_remove: Vec<BlockId> = self
+            .orphans
+            .iter()
+            .filter(|(_, orphan)| !keep(&orphan.header))
+            .map(|(block_id, _)| *block_id)
+            .collect();
+        for block_id in to_remove {
+            self.remove(&block_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::{
+        block_header::{BlockHeader, BlockHeaderSerializer},
+        secure_share::SecureShareContent,
+        slot::Slot,
+    };
+    use massa_signature::KeyPair;
+
+    fn orphan(seed: &[u8], parent: BlockId) -> (BlockId, SecuredHeader) {
+        let keypair = KeyPair::generate(0).unwrap();
+        let header = BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: 0,
+                slot: Slot::new(1, 0),
+                parents: vec![parent],
+                operation_merkle_root: Hash::compute_from(seed),
+                endorsements: Vec::new(),
+                denunciations: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        (header.id, header)
+    }
+
+    #[test]
+    fn test_orphan_headers() {
+        let parent_a = BlockId(Hash::compute_from(b"a"));
+        let parent_b = BlockId(Hash::compute_from(b"b"));
+        let mut pool = OrphanHeaders::new(2);
+        let (id_1, header_1) = orphan(b"1", parent_a);
+        let (id_2, header_2) = orphan(b"2", parent_a);
+        let (id_3, header_3) = orphan(b"3", parent_b);
+        pool.insert(id_1, header_1, [parent_a].into_iter().collect());
+        pool.insert(id_2, header_2, [parent_a].into_iter().collect());
+        // the oldest orphan is evicted
+        pool.insert(id_3, header_3, [parent_b].into_iter().collect());
+        assert_eq!(pool.len(), 2);
+
+        let released = pool.take_dependents(&parent_a);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].0, id_2);
+        assert!(pool.take_dependents(&parent_a).is_empty());
+
+        pool.retain(|_| false);
+        assert_eq!(pool.len(), 0);
+        assert!(pool.take_dependents(&parent_b).is_empty());
+    }
+}
@@ -11,6 +11,7 @@ use massa_logging::massa_trace;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
+    block::{BlockDiscardReason, DiscardedBlockEvent, ReorgEvent},
     block_header::SecuredHeader,
     block_id::BlockId,
     clique::Clique,
@@ -258,6 +259,18 @@ impl ConsensusState {
                     }
                 }
             }
+            // give the orphan headers waiting for this block back to the graph
+            for (orphan_id, header) in self.orphan_headers.take_dependents(&block_id) {
+                if self.block_statuses.contains_key(&orphan_id) {
+                    continue;
+                }
+                reprocess.insert((header.content.slot, orphan_id));
+                self.block_statuses.insert(
+                    orphan_id,
+                    BlockStatus::Incoming(HeaderOrBlock::Header(header)),
+                );
+                self.incoming_index.insert(orphan_id);
+            }
         }
 
         Ok(reprocess)
@@ -516,6 +529,32 @@ impl ConsensusState {
         }
     }
 
+    /// Count a discarded block in the metrics, and broadcast why it was discarded unless it is final.
+    pub(crate) fn notify_discarded_block(
+        &self,
+        block_id: BlockId,
+        slot: Slot,
+        creator: Address,
+        reason: &DiscardReason,
+    ) {
+        self.count_discarded_block(reason);
+        let reason = match reason {
+            DiscardReason::Invalid(reason) => BlockDiscardReason::Invalid(reason.clone()),
+            DiscardReason::Stale => BlockDiscardReason::Stale,
+            DiscardReason::Final => return,
+        };
+        // no receiver is not an error
+        let _ = self
+            .channels
+            .discarded_block_sender
+            .send(DiscardedBlockEvent {
+                block_id,
+                slot,
+                creator,
+                reason,
+            });
+    }
+
     /// Notify execution about blockclique changes and finalized blocks.
     ///
     /// # Arguments:
@@ -541,6 +580,13 @@ impl ConsensusState {
             })
             .collect();
 
+        // Latest block of the previous blockclique, reported if blocks are reverted.
+        let old_tip = self
+            .prev_blockclique
+            .iter()
+            .max_by_key(|(_b_id, slot)| **slot)
+            .map(|(b_id, _slot)| *b_id);
+
         // Get new blockclique block list with slots.
         let mut blockclique_changed = false;
         let new_blockclique: PreHashMap<BlockId, Slot> = self
@@ -570,6 +616,9 @@ impl ConsensusState {
             // If `prev_blockclique` is not empty here, it means that it contained elements that are not in the new blockclique anymore.
             // In that case, we mark the blockclique as having changed.
             blockclique_changed = true;
+            if let Some(old_tip) = old_tip {
+                self.notify_reorg(old_tip, &new_blockclique, &finalized_blocks);
+            }
         }
         // Overwrite previous blockclique.
         // Should still be done even if unchanged because elements were removed from it above.
@@ -594,6 +643,54 @@ impl ConsensusState {
             );
     }
 
+    /// Broadcast the blocks left in `prev_blockclique` that were reverted rather than finalized, if any.
+    ///
+    /// # Arguments:
+    /// * `old_tip`: latest block of the previous blockclique
+    /// * `new_blockclique`: blocks of the new blockclique with their slots
+    /// * `finalized_blocks`: blocks that became final since the previous blockclique
+    fn notify_reorg(
+        &self,
+        old_tip: BlockId,
+        new_blockclique: &PreHashMap<BlockId, Slot>,
+        finalized_blocks: &HashMap<Slot, BlockId>,
+    ) {
+        if !self.config.broadcast_enabled {
+            return;
+        }
+        let finalized: PreHashSet<BlockId> = finalized_blocks.values().copied().collect();
+        let mut reverted: Vec<(Slot, BlockId)> = self
+            .prev_blockclique
+            .iter()
+            .filter(|(b_id, _slot)| !finalized.contains(b_id))
+            .map(|(b_id, slot)| (*slot, *b_id))
+            .collect();
+        if reverted.is_empty() {
+            return;
+        }
+        reverted.sort_unstable_by(|a, b| b.cmp(a));
+        let Some(new_tip) = new_blockclique
+            .iter()
+            .max_by_key(|(_b_id, slot)| **slot)
+            .map(|(b_id, _slot)| *b_id)
+        else {
+            return;
+        };
+        debug!(
+            "blockclique reorg from {} to {}: {} blocks reverted",
+            old_tip,
+            new_tip,
+            reverted.len()
+        );
+        // no receiver is not an error
+        let _ = self.channels.reorg_sender.send(ReorgEvent {
+            old_tip,
+            new_tip,
+            depth: reverted.len() as u64,
+            reverted_blocks: reverted.into_iter().map(|(_slot, b_id)| b_id).collect(),
+        });
+    }
+
     /// call me if the block database changed
     /// Processing of final blocks, pruning.
     ///
@@ -5,7 +5,7 @@ use massa_consensus_exports::{
     error::ConsensusError,
 };
 use massa_logging::massa_trace;
-use massa_models::{block_header::SecuredHeader, block_id::BlockId, slot::Slot};
+use massa_models::{block_header::SecuredHeader, block_id::BlockId, slot::Slot, timeslots};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use tracing::debug;
@@ -32,16 +32,26 @@ impl ConsensusState {
         if self.genesis_hashes.contains(&block_id) {
             return Ok(());
         }
+        self.orphan_headers.remove(&block_id);
 
         debug!(
             "received header {} for slot {}",
             block_id, header.content.slot
         );
         massa_trace!("consensus.block_graph.incoming_header", {"block_id": block_id, "header": header});
+        let slot_timestamp = timeslots::get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            header.content.slot,
+        )?;
+        let latency = MassaTime::now()?.saturating_sub(slot_timestamp);
         let mut to_ack: BTreeSet<(Slot, BlockId)> = BTreeSet::new();
         match self.block_statuses.entry(block_id) {
             // if absent => add as Incoming, call rec_ack on it
             Entry::Vacant(vac) => {
+                self.massa_metrics
+                    .observe_consensus_header_latency(latency.to_duration());
                 to_ack.insert((header.content.slot, block_id));
                 vac.insert(BlockStatus::Incoming(HeaderOrBlock::Header(header)));
                 self.incoming_index.insert(block_id);
@@ -91,6 +101,7 @@ impl ConsensusState {
         if self.genesis_hashes.contains(&block_id) {
             return Ok(());
         }
+        self.orphan_headers.remove(&block_id);
 
         // Block is coming from protocol mark it for desync calculation
         if !created {
@@ -164,6 +175,12 @@ impl ConsensusState {
     pub fn mark_invalid_block(&mut self, block_id: &BlockId, header: SecuredHeader) {
         let reason = DiscardReason::Invalid("invalid".to_string());
         self.maybe_note_attack_attempt(&reason, block_id);
+        self.notify_discarded_block(
+            *block_id,
+            header.content.slot,
+            header.content_creator_address,
+            &reason,
+        );
         massa_trace!("consensus.block_graph.process.invalid_block", {"block_id": block_id, "reason": reason});
         match self.block_statuses.get(block_id) {
             Some(BlockStatus::WaitingForDependencies { .. }) => {
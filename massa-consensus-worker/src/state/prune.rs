@@ -5,13 +5,14 @@ use massa_consensus_exports::{
 use massa_logging::massa_trace;
 use massa_models::{
     active_block::ActiveBlock,
+    block::ArchivedBlock,
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
     slot::Slot,
 };
 use tracing::debug;
 
-use super::ConsensusState;
+use super::{discarded_finality_status, ConsensusState};
 
 impl ConsensusState {
     /// prune active blocks and return final blocks, return discarded final blocks
@@ -167,10 +168,31 @@ impl ConsensusState {
             .collect();
         discard_hashes.sort_unstable();
         discard_hashes.truncate(self.discarded_index.len() - self.config.max_discarded_blocks);
+        let mut archived_blocks = Vec::new();
         for (_, block_id) in discard_hashes.iter() {
-            self.block_statuses.remove(block_id);
+            if let Some(BlockStatus::Discarded {
+                slot,
+                creator,
+                parents,
+                reason,
+                ..
+            }) = self.block_statuses.remove(block_id)
+            {
+                if self.archive.is_some() {
+                    archived_blocks.push(ArchivedBlock {
+                        id: *block_id,
+                        slot,
+                        creator,
+                        parents,
+                        status: discarded_finality_status(&reason),
+                    });
+                }
+            }
             self.discarded_index.remove(block_id);
         }
+        if let Some(archive) = &self.archive {
+            archive.archive_blocks(&archived_blocks);
+        }
         Ok(())
     }
 
@@ -288,10 +310,12 @@ impl ConsensusState {
             break;
         }
 
-        // transition states to Discarded if there is a reason, otherwise just drop
+        // transition states to Discarded if there is a reason, otherwise keep the header as an orphan
         for (block_id, reason_opt) in to_discard.drain() {
             if let Some(BlockStatus::WaitingForDependencies {
-                header_or_block, ..
+                header_or_block,
+                unsatisfied_dependencies,
+                ..
             }) = self.block_statuses.remove(&block_id)
             {
                 self.waiting_for_dependencies_index.remove(&block_id);
@@ -314,6 +338,12 @@ impl ConsensusState {
                 massa_trace!("consensus.block_graph.prune_waiting_for_dependencies", {"hash": block_id, "reason": reason_opt});
 
                 if let Some(reason) = reason_opt {
+                    self.notify_discarded_block(
+                        block_id,
+                        header.content.slot,
+                        header.content_creator_address,
+                        &reason,
+                    );
                     // add to stats if reason is Stale
                     if reason == DiscardReason::Stale {
                         self.new_stale_blocks.insert(
@@ -336,6 +366,12 @@ impl ConsensusState {
                         },
                     );
                     self.discarded_index.insert(block_id);
+                } else {
+                    // the block itself is not a missing dependency of its orphan header
+                    let mut missing_dependencies = unsatisfied_dependencies;
+                    missing_dependencies.remove(&block_id);
+                    self.orphan_headers
+                        .insert(block_id, header, missing_dependencies);
                 }
             }
         }
@@ -343,6 +379,23 @@ impl ConsensusState {
         Ok(())
     }
 
+    /// Drop the orphan headers that can not become active anymore because their slot is final in their thread.
+    fn prune_orphan_headers(&mut self) {
+        let latest_final_periods: Vec<u64> = self
+            .latest_final_blocks_periods
+            .iter()
+            .map(|(_, period)| *period)
+            .collect();
+        self.orphan_headers.retain(|header| {
+            latest_final_periods
+                .get(header.content.slot.thread as usize)
+                .map_or(true, |period| header.content.slot.period > *period)
+        });
+        if self.orphan_headers.len() > 0 {
+            debug!("{} orphan headers kept", self.orphan_headers.len());
+        }
+    }
+
     /// Clear the cache of blocks indexed by slot.
     /// Slot are not saved anymore, when the block in the same thread with a equal or greater period is finalized.
     fn prune_nonfinal_blocks_per_slot(&mut self) {
@@ -368,6 +421,13 @@ impl ConsensusState {
         // Step 5: prune nonfinal blocks per slot
         self.prune_nonfinal_blocks_per_slot();
 
+        // Step 6: prune orphan headers
+        self.prune_orphan_headers();
+
+        // Step 7: forget the trusted checkpoint blocks that are final
+        self.trusted_checkpoint
+            .prune_final(&self.latest_final_blocks_periods);
+
         let after = self.max_cliques.len();
         if before != after {
             debug!(
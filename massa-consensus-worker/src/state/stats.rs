@@ -1,5 +1,5 @@
 use super::ConsensusState;
-use massa_consensus_exports::error::ConsensusError;
+use massa_consensus_exports::{block_status::DiscardReason, error::ConsensusError};
 use massa_models::stats::ConsensusStats;
 use massa_time::MassaTime;
 use std::cmp::max;
@@ -38,6 +38,30 @@ impl ConsensusState {
         })
     }
 
+    /// Counts a discarded block in the metrics, by discard reason
+    pub(crate) fn count_discarded_block(&self, reason: &DiscardReason) {
+        let reason = match reason {
+            DiscardReason::Invalid(_) => "invalid",
+            DiscardReason::Stale => "stale",
+            DiscardReason::Final => "final",
+        };
+        self.massa_metrics.inc_consensus_discarded_blocks(reason);
+    }
+
+    /// Updates the health metrics of the consensus: stale block rate and clique count
+    pub(crate) fn update_health_metrics(&self) -> Result<(), ConsensusError> {
+        let stats = self.get_stats()?;
+        let seen_blocks = stats.final_block_count + stats.stale_block_count;
+        let stale_block_rate = if seen_blocks == 0 {
+            0.0
+        } else {
+            stats.stale_block_count as f64 / seen_blocks as f64
+        };
+        self.massa_metrics
+            .set_consensus_health(stale_block_rate, self.get_clique_count());
+        Ok(())
+    }
+
     /// Must be called each tick to update stats. Will detect if a desynchronization happened
     pub fn stats_tick(&mut self) -> Result<(), ConsensusError> {
         #[cfg(not(feature = "sandbox"))]
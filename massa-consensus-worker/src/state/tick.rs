@@ -87,6 +87,7 @@ impl ConsensusState {
             self.block_statuses.len(),
             self.active_index_without_ops.len(),
         );
+        self.update_health_metrics()?;
 
         Ok(())
     }
@@ -80,6 +80,8 @@ impl ConsensusState {
         stored_block: SecureShareBlock,
         current_slot: Option<Slot>,
     ) -> Result<Option<BlockInfos>, ConsensusError> {
+        self.trusted_checkpoint
+            .note_header(&block_id, &stored_block.content.header);
         let header_outcome =
             self.check_header(&block_id, &stored_block.content.header, current_slot)?;
         match header_outcome {
@@ -147,6 +149,7 @@ impl ConsensusState {
         header: SecuredHeader,
         current_slot: Option<Slot>,
     ) -> Result<(), ConsensusError> {
+        self.trusted_checkpoint.note_header(&block_id, &header);
         let header_outcome = self.check_header(&block_id, &header, current_slot)?;
         match header_outcome {
             HeaderCheckOutcome::Proceed { .. } => {
@@ -260,6 +263,12 @@ impl ConsensusState {
         header: SecuredHeader,
     ) {
         self.maybe_note_attack_attempt(&reason, &block_id);
+        self.notify_discarded_block(
+            block_id,
+            header.content.slot,
+            header.content_creator_address,
+            &reason,
+        );
         massa_trace!("consensus.block_graph.process.incoming_header.discarded", {"block_id": block_id, "reason": reason});
         // count stales
         if reason == DiscardReason::Stale {
@@ -292,10 +301,12 @@ impl ConsensusState {
     /// - Slot above 0.
     /// - Valid thread.
     /// - Check that the block is older than the latest final one in thread.
-    /// - Check if it was the creator's turn to create this block.
+    /// - Check that the block is the trusted checkpoint, if at its slot.
+    /// - Check if it was the creator's turn to create this block, unless the trusted checkpoint or one of its ancestors,
+    ///   or on a header-only relay node.
     /// - Check parents are present.
     /// - Check the topological consistency of the parents.
-    /// - Check endorsements.
+    /// - Check endorsements, unless the trusted checkpoint or one of its ancestors, or on a header-only relay node.
     /// - Check thread incompatibility test.
     /// - Check grandpa incompatibility test.
     /// - Check if the block is incompatible with a parent.
@@ -323,22 +334,41 @@ impl ConsensusState {
             return Ok(HeaderCheckOutcome::Discard(DiscardReason::Stale));
         }
 
-        // check if it was the creator's turn to create this block
-        // (step 1 in consensus/pos.md)
-        let slot_draw_address = match self
-            .channels
-            .selector_controller
-            .get_producer(header.content.slot)
+        // the trusted checkpoint is the only block accepted at its slot
+        if let Some((checkpoint_slot, checkpoint_id)) = self
+            .trusted_checkpoint
+            .conflicting_checkpoint(block_id, header)
         {
-            Ok(draw) => draw,
-            Err(_) => return Ok(HeaderCheckOutcome::WaitForSlot), // TODO properly handle PoS errors
-        };
-        if creator_addr != slot_draw_address {
-            // it was not the creator's turn to create a block for this slot
             return Ok(HeaderCheckOutcome::Discard(DiscardReason::Invalid(
-                format!("Bad creator turn for the slot:{}", header.content.slot),
+                format!(
+                    "conflicts with the trusted checkpoint {} at slot {}",
+                    checkpoint_id, checkpoint_slot
+                ),
             )));
         }
+        // the trusted checkpoint and its ancestors are not validated
+        let trusted = self.trusted_checkpoint.is_trusted(block_id, header);
+        // nor are the draws on header-only relay nodes, whose selector is not fed by execution
+        let check_draws = !trusted && !self.config.header_only;
+
+        // check if it was the creator's turn to create this block
+        // (step 1 in consensus/pos.md)
+        if check_draws {
+            let slot_draw_address = match self
+                .channels
+                .selector_controller
+                .get_producer(header.content.slot)
+            {
+                Ok(draw) => draw,
+                Err(_) => return Ok(HeaderCheckOutcome::WaitForSlot), // TODO properly handle PoS errors
+            };
+            if creator_addr != slot_draw_address {
+                // it was not the creator's turn to create a block for this slot
+                return Ok(HeaderCheckOutcome::Discard(DiscardReason::Invalid(
+                    format!("Bad creator turn for the slot:{}", header.content.slot),
+                )));
+            }
+        }
 
         // check if block is in the future: queue it
         // note: do it after testing signature + draw to prevent queue flooding/DoS
@@ -486,12 +516,16 @@ impl ConsensusState {
         })?;
 
         // check endorsements
-        match self.check_endorsements(header)? {
-            EndorsementsCheckOutcome::Proceed => {}
-            EndorsementsCheckOutcome::Discard(reason) => {
-                return Ok(HeaderCheckOutcome::Discard(reason))
+        if check_draws {
+            match self.check_endorsements(header)? {
+                EndorsementsCheckOutcome::Proceed => {}
+                EndorsementsCheckOutcome::Discard(reason) => {
+                    return Ok(HeaderCheckOutcome::Discard(reason))
+                }
+                EndorsementsCheckOutcome::WaitForSlot => {
+                    return Ok(HeaderCheckOutcome::WaitForSlot)
+                }
             }
-            EndorsementsCheckOutcome::WaitForSlot => return Ok(HeaderCheckOutcome::WaitForSlot),
         }
 
         // thread incompatibility test
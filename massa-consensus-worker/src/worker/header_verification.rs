@@ -0,0 +1,195 @@
+//! Signature verification of the incoming headers.
+//!
+//! The headers waiting in the command channel are verified together, before the lock of the graph is taken:
+//! their signatures and the signatures of their endorsements are batch-verified on the rayon thread pool,
+//! which speeds up the processing of the many headers received while catching up.
+
+use massa_hash::Hash;
+use massa_models::{block_header::SecuredHeader, block_id::BlockId};
+use massa_signature::{verify_signature_batch, PublicKey, Signature};
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
+use tracing::log::warn;
+
+/// Minimum number of headers verified by a thread of the pool, below which batching is not worth it
+const MIN_HEADERS_PER_CHUNK: usize = 4;
+
+/// Signatures of a header: the signature of the header itself, then the signatures of its endorsements
+fn header_signatures(header: &SecuredHeader) -> Vec<(Hash, Signature, PublicKey)> {
+    std::iter::once((
+        header.compute_signed_hash(),
+        header.signature,
+        header.content_creator_pub_key,
+    ))
+    .chain(header.content.endorsements.iter().map(|endorsement| {
+        (
+            endorsement.compute_signed_hash(),
+            endorsement.signature,
+            endorsement.content_creator_pub_key,
+        )
+    }))
+    .collect()
+}
+
+/// Verifies the signatures of the headers and of their endorsements.
+///
+/// Returns, for each header in order, whether all its signatures are valid.
+/// The headers of a chunk are checked one by one only if the verification of the whole chunk fails.
+/// The headers flagged in `trusted`, the trusted checkpoint and its ancestors, are not verified and considered valid.
+pub(crate) fn verify_header_signatures(
+    headers: &[(BlockId, SecuredHeader)],
+    trusted: &[bool],
+) -> Vec<bool> {
+    let threads = rayon::current_num_threads();
+    let chunk_size = std::cmp::max(
+        MIN_HEADERS_PER_CHUNK,
+        (headers.len() + threads - 1) / threads,
+    );
+    headers
+        .par_chunks(chunk_size)
+        .zip(trusted.par_chunks(chunk_size))
+        .flat_map_iter(|(chunk, trusted_chunk)| {
+            let batch: Vec<(Hash, Signature, PublicKey)> = chunk
+                .iter()
+                .zip(trusted_chunk)
+                .filter(|(_, trusted)| !**trusted)
+                .flat_map(|((_, header), _)| header_signatures(header))
+                .collect();
+            if verify_signature_batch(&batch).is_ok() {
+                vec![true; chunk.len()]
+            } else {
+                chunk
+                    .iter()
+                    .zip(trusted_chunk)
+                    .map(|((_, header), trusted)| {
+                        *trusted || verify_signature_batch(&header_signatures(header)).is_ok()
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Keeps the headers whose signatures are valid, in order, and discards the others.
+///
+/// A header with an invalid signature is not marked as invalid: its id does not commit to the signature,
+/// so it may be a forged copy of an honest block, which would be blacklisted along with it.
+pub(crate) fn filter_valid_headers(
+    headers: Vec<(BlockId, SecuredHeader)>,
+    trusted: &[bool],
+) -> Vec<(BlockId, SecuredHeader)> {
+    let valid_signatures = verify_header_signatures(&headers, trusted);
+    headers
+        .into_iter()
+        .zip(valid_signatures)
+        .filter_map(|((block_id, header), valid_signature)| {
+            if !valid_signature {
+                warn!(
+                    "discarding block header {} with an invalid signature",
+                    block_id
+                );
+                return None;
+            }
+            Some((block_id, header))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::{
+        block_header::{BlockHeader, BlockHeaderSerializer},
+        secure_share::SecureShareContent,
+        slot::Slot,
+    };
+    use massa_signature::KeyPair;
+
+    fn header(slot: Slot, seed: &[u8]) -> (BlockId, SecuredHeader) {
+        let keypair = KeyPair::generate(0).unwrap();
+        let header = BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: 0,
+                slot,
+                parents: Vec::new(),
+                operation_merkle_root: Hash::compute_from(seed),
+                endorsements: Vec::new(),
+                denunciations: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        (header.id, header)
+    }
+
+    #[test]
+    fn test_forged_header_at_past_slot_rejected() {
+        let (checkpoint_id, checkpoint) = header(Slot::new(10, 0), b"checkpoint");
+        let (ancestor_id, ancestor) = header(Slot::new(5, 0), b"ancestor");
+        // a header at a slot before the checkpoint, signed by another key
+        let (forged_id, mut forged) = header(Slot::new(5, 1), b"forged");
+        forged.signature = ancestor.signature;
+
+        let headers = vec![
+            (checkpoint_id, checkpoint),
+            (ancestor_id, ancestor),
+            (forged_id, forged),
+        ];
+        assert_eq!(
+            verify_header_signatures(&headers, &[true, true, false]),
+            vec![true, true, false]
+        );
+        assert_eq!(
+            verify_header_signatures(&headers, &[false, false, false]),
+            vec![true, true, false]
+        );
+    }
+
+    #[test]
+    fn test_batched_verification_matches_single() {
+        // enough headers for several chunks per thread, with invalid ones in some of them
+        let count = MIN_HEADERS_PER_CHUNK * rayon::current_num_threads() * 3 + 1;
+        let mut headers: Vec<(BlockId, SecuredHeader)> = (0..count)
+            .map(|index| header(Slot::new(index as u64 + 1, 0), &index.to_be_bytes()))
+            .collect();
+        let invalid = [0, 5, count / 2, count - 1];
+        for index in invalid {
+            let other_signature = headers[(index + 1) % count].1.signature;
+            headers[index].1.signature = other_signature;
+        }
+        let expected: Vec<bool> = (0..count).map(|index| !invalid.contains(&index)).collect();
+        assert_eq!(
+            verify_header_signatures(&headers, &vec![false; count]),
+            expected
+        );
+
+        // the trusted headers are not verified
+        let mut trusted = vec![false; count];
+        trusted[invalid[1]] = true;
+        let mut expected = expected;
+        expected[invalid[1]] = true;
+        assert_eq!(verify_header_signatures(&headers, &trusted), expected);
+    }
+
+    #[test]
+    fn test_forged_copy_discarded_honest_header_kept() {
+        let (honest_id, honest) = header(Slot::new(3, 0), b"honest");
+        let (_, other) = header(Slot::new(3, 1), b"other");
+        // the same content under another signature has the same id
+        let mut forged = honest.clone();
+        forged.signature = other.signature;
+        assert_eq!(forged.id, honest_id);
+
+        let valid_headers = filter_valid_headers(
+            vec![(honest_id, forged), (honest_id, honest.clone())],
+            &[false, false],
+        );
+        assert_eq!(valid_headers.len(), 1);
+        assert_eq!(valid_headers[0].0, honest_id);
+        assert_eq!(valid_headers[0].1.signature, honest.signature);
+    }
+}
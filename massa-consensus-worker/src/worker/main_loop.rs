@@ -2,6 +2,8 @@ use std::time::Instant;
 
 use massa_consensus_exports::{error::ConsensusError, events::ConsensusEvent};
 use massa_models::{
+    block_header::SecuredHeader,
+    block_id::BlockId,
     slot::Slot,
     timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
 };
@@ -10,7 +12,7 @@ use tracing::log::{info, warn};
 
 use crate::commands::ConsensusCommand;
 
-use super::ConsensusWorker;
+use super::{header_verification::filter_valid_headers, ConsensusWorker};
 
 enum WaitingStatus {
     Ended,
@@ -30,8 +32,9 @@ impl ConsensusWorker {
         let mut write_shared_state = self.shared_state.write();
         match command {
             ConsensusCommand::RegisterBlockHeader(block_id, header) => {
-                write_shared_state.register_block_header(block_id, header, self.previous_slot)?;
-                write_shared_state.block_db_changed()
+                drop(write_shared_state);
+                self.manage_block_headers(vec![(block_id, header)]);
+                Ok(())
             }
             ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created) => {
                 write_shared_state.register_block(
@@ -50,6 +53,55 @@ impl ConsensusWorker {
         }
     }
 
+    /// Register incoming block headers in the graph, after verifying their signatures outside of the graph lock.
+    /// Headers with an invalid signature are discarded, but not marked as invalid.
+    /// The headers of the trusted checkpoint and of its known ancestors, if any, are not verified.
+    fn manage_block_headers(&mut self, headers: Vec<(BlockId, SecuredHeader)>) {
+        let trusted: Vec<bool> = {
+            let read_shared_state = self.shared_state.read();
+            headers
+                .iter()
+                .map(|(block_id, header)| {
+                    read_shared_state
+                        .trusted_checkpoint
+                        .is_trusted(block_id, header)
+                })
+                .collect()
+        };
+        let valid_headers = filter_valid_headers(headers, &trusted);
+        let mut write_shared_state = self.shared_state.write();
+        for (block_id, header) in valid_headers {
+            if let Err(err) =
+                write_shared_state.register_block_header(block_id, header, self.previous_slot)
+            {
+                warn!("Error in consensus: {}", err);
+            }
+        }
+        if let Err(err) = write_shared_state.block_db_changed() {
+            warn!("Error in consensus: {}", err);
+        }
+    }
+
+    /// Collect the block headers waiting in the command channel after a first one, up to `header_verification_batch_size`.
+    /// Returns the headers, and the first other command received if any, to be managed after them.
+    fn collect_block_headers(
+        &mut self,
+        block_id: BlockId,
+        header: SecuredHeader,
+    ) -> (Vec<(BlockId, SecuredHeader)>, Option<ConsensusCommand>) {
+        let mut headers = vec![(block_id, header)];
+        while headers.len() < self.config.header_verification_batch_size {
+            match self.command_receiver.try_recv() {
+                Ok(ConsensusCommand::RegisterBlockHeader(block_id, header)) => {
+                    headers.push((block_id, header))
+                }
+                Ok(command) => return (headers, Some(command)),
+                Err(_) => break,
+            }
+        }
+        (headers, None)
+    }
+
     /// Wait and interrupt if we receive a command, a stop signal or we reach the `instant`
     ///
     /// # Return:
@@ -58,6 +110,17 @@ impl ConsensusWorker {
     /// WaitingStatus::Disconnected => if we received a stop signal
     fn wait_slot_or_command(&mut self, deadline: Instant) -> WaitingStatus {
         match self.command_receiver.recv_deadline(deadline) {
+            // headers received => verify the pending ones together, then register them
+            Ok(ConsensusCommand::RegisterBlockHeader(block_id, header)) => {
+                let (headers, next_command) = self.collect_block_headers(block_id, header);
+                self.manage_block_headers(headers);
+                if let Some(command) = next_command {
+                    if let Err(err) = self.manage_command(command) {
+                        warn!("Error in consensus: {}", err);
+                    }
+                }
+                WaitingStatus::Interrupted
+            }
             // message received => manage it
             Ok(command) => {
                 if let Err(err) = self.manage_command(command) {
@@ -3,6 +3,7 @@ use massa_consensus_exports::{
     bootstrapable_graph::BootstrapableGraph, ConsensusChannels, ConsensusConfig,
     ConsensusController, ConsensusManager,
 };
+use massa_db::MassaArchive;
 use massa_metrics::MassaMetrics;
 use massa_models::block_id::BlockId;
 use massa_models::clique::Clique;
@@ -19,7 +20,7 @@ use std::time::Instant;
 use crate::commands::ConsensusCommand;
 use crate::controller::ConsensusControllerImpl;
 use crate::manager::ConsensusManagerImpl;
-use crate::state::ConsensusState;
+use crate::state::{ConsensusState, OrphanHeaders, TrustedCheckpoint};
 
 /// The consensus worker structure that contains all information and tools for the consensus worker thread.
 pub struct ConsensusWorker {
@@ -37,6 +38,7 @@ pub struct ConsensusWorker {
     next_instant: Instant,
 }
 
+mod header_verification;
 mod init;
 mod main_loop;
 
@@ -47,6 +49,8 @@ mod main_loop;
 /// * `channels`: Channels to communicate with others modules
 /// * `init_graph`: Optional initial graph to bootstrap the graph. if None, the graph will have only genesis blocks.
 /// * `storage`: Storage to use for the consensus
+/// * `massa_metrics`: Metrics of the node
+/// * `archive`: Archive of the pruned blocks, in archival mode only
 ///
 /// # Returns:
 /// * The consensus controller to communicate with the consensus worker thread
@@ -57,6 +61,7 @@ pub fn start_consensus_worker(
     init_graph: Option<BootstrapableGraph>,
     storage: Storage,
     massa_metrics: MassaMetrics,
+    archive: Option<Arc<MassaArchive>>,
 ) -> (Box<dyn ConsensusController>, Box<dyn ConsensusManager>) {
     let (tx, rx) = MassaChannel::new("consensus_command".to_string(), Some(CHANNEL_SIZE));
     // desync detection timespan
@@ -102,6 +107,12 @@ pub fn start_consensus_worker(
         prev_blockclique: Default::default(),
         nonfinal_active_blocks_per_slot: Default::default(),
         massa_metrics,
+        archive,
+        orphan_headers: OrphanHeaders::new(config.max_orphan_headers),
+        trusted_checkpoint: TrustedCheckpoint::new(
+            config.trusted_checkpoint,
+            config.trusted_checkpoint_ancestors.iter().copied(),
+        ),
     }));
 
     let shared_state_cloned = shared_state.clone();
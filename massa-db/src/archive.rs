@@ -0,0 +1,179 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Archive of the data pruned from the node, for archival nodes serving explorers.
+//!
+//! In the default pruned mode, the consensus graph forgets the blocks some time after they are final or discarded,
+//! and the final state only keeps the last cycles of the proof-of-stake history.
+//! In archival mode, the metadata of the pruned blocks and the pruned cycles are written here before being forgotten.
+//! The archive is local to the node: it is neither part of the final state nor bootstrapped.
+//!
+//! Keys are prefixed by kind:
+//! * `b | block id` holds an archived block,
+//! * `s | slot | block id` indexes the archived blocks by slot, with an empty value,
+//! * `c | cycle` holds an archived cycle.
+
+use crate::{CRUD_ERROR, OPEN_ERROR};
+use massa_models::{
+    block::ArchivedBlock,
+    block_id::{BlockId, BLOCK_ID_SIZE_BYTES},
+    slot::{Slot, SLOT_KEY_SIZE},
+};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+const BLOCK_PREFIX: u8 = b'b';
+const SLOT_INDEX_PREFIX: u8 = b's';
+const CYCLE_PREFIX: u8 = b'c';
+
+const ARCHIVE_SER_ERROR: &str = "critical: archive serialization failed";
+const ARCHIVE_DESER_ERROR: &str = "critical: archive deserialization failed";
+
+/// Archive of the pruned blocks and cycles
+pub struct MassaArchive {
+    /// RocksDB database
+    db: DB,
+}
+
+impl MassaArchive {
+    /// Open or create the archive stored at `path`
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+        }
+    }
+
+    /// Archive pruned blocks, indexed by id and by slot
+    pub fn archive_blocks(&self, blocks: &[ArchivedBlock]) {
+        let mut batch = WriteBatch::default();
+        for block in blocks {
+            batch.put(
+                block_key(&block.id),
+                serde_json::to_vec(block).expect(ARCHIVE_SER_ERROR),
+            );
+            batch.put(slot_index_key(&block.slot, &block.id), []);
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get an archived block by id
+    pub fn get_block(&self, block_id: &BlockId) -> Option<ArchivedBlock> {
+        self.db
+            .get(block_key(block_id))
+            .expect(CRUD_ERROR)
+            .map(|value| serde_json::from_slice(&value).expect(ARCHIVE_DESER_ERROR))
+    }
+
+    /// Get up to `limit` archived blocks with a slot in `[start, end)`, sorted by slot
+    pub fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        limit: usize,
+    ) -> Vec<ArchivedBlock> {
+        let start_key = [&[SLOT_INDEX_PREFIX][..], &start.to_bytes_key()].concat();
+        let end_key = [&[SLOT_INDEX_PREFIX][..], &end.to_bytes_key()].concat();
+        self.db
+            .iterator(IteratorMode::From(&start_key, Direction::Forward))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(key, _)| key[..] < end_key[..])
+            .take(limit)
+            .filter_map(|(key, _)| {
+                let block_id = BlockId::from_bytes(
+                    key[1 + SLOT_KEY_SIZE..1 + SLOT_KEY_SIZE + BLOCK_ID_SIZE_BYTES]
+                        .try_into()
+                        .unwrap(),
+                );
+                self.get_block(&block_id)
+            })
+            .collect()
+    }
+
+    /// Archive a pruned cycle
+    pub fn archive_cycle<T: Serialize>(&self, cycle: u64, cycle_info: &T) {
+        self.db
+            .put(
+                cycle_key(cycle),
+                serde_json::to_vec(cycle_info).expect(ARCHIVE_SER_ERROR),
+            )
+            .expect(CRUD_ERROR);
+    }
+
+    /// Get an archived cycle
+    pub fn get_cycle<T: DeserializeOwned>(&self, cycle: u64) -> Option<T> {
+        self.db
+            .get(cycle_key(cycle))
+            .expect(CRUD_ERROR)
+            .map(|value| serde_json::from_slice(&value).expect(ARCHIVE_DESER_ERROR))
+    }
+}
+
+/// Key of an archived block
+fn block_key(block_id: &BlockId) -> Vec<u8> {
+    [&[BLOCK_PREFIX][..], &block_id.to_bytes()[..]].concat()
+}
+
+/// Key of the entry of a block in the slot index
+fn slot_index_key(slot: &Slot, block_id: &BlockId) -> Vec<u8> {
+    [
+        &[SLOT_INDEX_PREFIX][..],
+        &slot.to_bytes_key(),
+        &block_id.to_bytes()[..],
+    ]
+    .concat()
+}
+
+/// Key of an archived cycle, big endian so that the cycles are sorted
+fn cycle_key(cycle: u64) -> Vec<u8> {
+    [&[CYCLE_PREFIX][..], &cycle.to_be_bytes()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::{address::Address, block::BlockFinalityStatus};
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    fn archived_block(seed: u8, slot: Slot) -> ArchivedBlock {
+        ArchivedBlock {
+            id: BlockId(Hash::compute_from(&[seed])),
+            slot,
+            creator: Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()),
+            parents: vec![],
+            status: BlockFinalityStatus::Final,
+        }
+    }
+
+    #[test]
+    fn test_archive_blocks_by_slot() {
+        let dir = TempDir::new().unwrap();
+        let archive = MassaArchive::new(dir.path().to_path_buf());
+        let blocks = vec![
+            archived_block(0, Slot::new(1, 0)),
+            archived_block(1, Slot::new(1, 1)),
+            archived_block(2, Slot::new(2, 0)),
+            archived_block(3, Slot::new(3, 0)),
+        ];
+        archive.archive_blocks(&blocks);
+
+        assert_eq!(archive.get_block(&blocks[2].id), Some(blocks[2].clone()));
+        assert_eq!(
+            archive.get_block(&BlockId(Hash::compute_from(b"unknown"))),
+            None
+        );
+        assert_eq!(
+            archive.get_blocks_by_slot_range(Slot::new(1, 1), Slot::new(3, 0), 10),
+            blocks[1..3].to_vec()
+        );
+        assert_eq!(
+            archive.get_blocks_by_slot_range(Slot::new(0, 0), Slot::new(4, 0), 2),
+            blocks[..2].to_vec()
+        );
+
+        archive.archive_cycle(5, &vec![1u64, 2]);
+        assert_eq!(archive.get_cycle::<Vec<u64>>(5), Some(vec![1, 2]));
+        assert_eq!(archive.get_cycle::<Vec<u64>>(6), None);
+    }
+}
@@ -13,6 +13,13 @@ pub const STATE_HASH_KEY_IS_XOR_KEY: &[u8; 6] = b"is_xor";
 pub const STATE_HASH_INITIAL_BYTES: &[u8; 32] = &[0; HASH_SIZE_BYTES];
 pub const CHANGE_ID_KEY: &[u8; 1] = b"c";
 
+// Snapshots
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"MASSASNP";
+pub const SNAPSHOT_VERSION: u8 = 0;
+pub const SNAPSHOT_END_TAG: u8 = 0;
+pub const SNAPSHOT_STATE_TAG: u8 = 1;
+pub const SNAPSHOT_VERSIONING_TAG: u8 = 2;
+
 pub const CHANGE_ID_DESER_ERROR: &str = "critical: change_id deserialization failed";
 pub const CHANGE_ID_SER_ERROR: &str = "critical: change_id serialization failed";
 
@@ -17,4 +17,8 @@ pub enum MassaDBError {
     RocksDBError(String),
     /// hash error: {0}
     HashError(String),
+    /// invalid key range: {0}
+    InvalidRange(String),
+    /// snapshot error: {0}
+    SnapshotError(String),
 }
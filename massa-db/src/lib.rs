@@ -1,9 +1,11 @@
 #![feature(btree_cursors)]
 
+mod archive;
 mod constants;
 mod error;
 mod massa_db;
 
 pub use crate::massa_db::*;
+pub use archive::*;
 pub use constants::*;
 pub use error::*;
@@ -1,11 +1,12 @@
 use crate::{
     MassaDBError, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY, CHANGE_ID_SER_ERROR, CRUD_ERROR,
-    LSMTREE_ERROR, LSMTREE_NODES_CF, LSMTREE_VALUES_CF, METADATA_CF, OPEN_ERROR, STATE_CF,
+    LSMTREE_ERROR, LSMTREE_NODES_CF, LSMTREE_VALUES_CF, METADATA_CF, OPEN_ERROR, SNAPSHOT_END_TAG,
+    SNAPSHOT_MAGIC, SNAPSHOT_STATE_TAG, SNAPSHOT_VERSION, SNAPSHOT_VERSIONING_TAG, STATE_CF,
     STATE_HASH_ERROR, STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, STATE_HASH_KEY_IS_XOR_KEY,
     STATE_HASH_XOR_KEY, VERSIONING_CF,
 };
-use lsmtree::{bytes::Bytes, BadProof, KVStore, SparseMerkleTree};
-use massa_hash::{Hash, SmtHasher};
+use lsmtree::{bytes::Bytes, BadProof, KVStore, SparseMerkleProof, SparseMerkleTree};
+use massa_hash::{Hash, SmtHasher, HASH_SIZE_BYTES};
 use massa_models::{
     error::ModelsError,
     slot::{Slot, SlotDeserializer, SlotSerializer},
@@ -20,8 +21,10 @@ use rocksdb::{
 use std::{
     collections::{BTreeMap, HashMap},
     format,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
     ops::Bound::{self, Excluded, Included, Unbounded},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -70,6 +73,36 @@ impl<ChangeID: PartialOrd + Ord + PartialEq + Eq + Clone + std::fmt::Debug> Stre
         self.updates_on_previous_elements.is_empty() && self.new_elements.is_empty()
     }
 }
+/// Proof of the value of a key, or of its absence, against the state hash.
+///
+/// The leaves of the Sparse Merkle Tree are the hashes of the keys, associated to the hashes of the values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateProof {
+    /// side nodes on the path from the leaf to the root
+    pub side_nodes: Vec<Vec<u8>>,
+    /// data of the leaf found in place of the key when proving its absence
+    pub non_membership_leaf_data: Option<Vec<u8>>,
+    /// data of the sibling of the leaf
+    pub sibling_data: Option<Vec<u8>>,
+}
+
+impl StateProof {
+    /// Checks that `key` has `value` (or is absent if `value` is `None`) in the state whose hash is `state_hash`
+    pub fn verify(&self, state_hash: &Hash, key: &[u8], value: Option<&[u8]>) -> bool {
+        let proof = SparseMerkleProof::<SmtHasher>::new(
+            self.side_nodes.iter().cloned().map(Bytes::from).collect(),
+            self.non_membership_leaf_data.clone().map(Bytes::from),
+            self.sibling_data.clone().map(Bytes::from),
+        );
+        let value_hash = value.map(|value| Hash::compute_from(value).to_bytes().to_vec());
+        proof.verify(
+            state_hash.to_bytes(),
+            Hash::compute_from(key).to_bytes(),
+            value_hash.unwrap_or_default(),
+        )
+    }
+}
+
 /// A generic wrapped RocksDB database.
 ///
 /// The added features are:
@@ -89,6 +122,8 @@ pub struct RawMassaDB<
     pub change_history: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
     /// same as change_history but for versioning
     pub change_history_versioning: BTreeMap<ChangeID, BTreeMap<Key, Option<Value>>>,
+    /// In hash_history, we keep the hashes of the database after the latest changes, to attest the states bootstrapped by clients.
+    pub hash_history: BTreeMap<ChangeID, Hash>,
     /// A serializer for the ChangeID type
     change_id_serializer: ChangeIDSerializer,
     /// A deserializer for the ChangeID type
@@ -200,6 +235,20 @@ where
     ChangeIDSerializer: Serializer<ChangeID>,
     ChangeIDDeserializer: Deserializer<ChangeID>,
 {
+    /// Used for bootstrap servers: returns true if all the changes made after `change_id` can still be streamed,
+    /// meaning that `change_id` is the current one or is covered by the change history.
+    pub fn can_stream_changes_since(&self, change_id: &ChangeID) -> bool {
+        if let Ok(current_change_id) = self.get_change_id() {
+            if &current_change_id == change_id {
+                return true;
+            }
+        }
+        match self.change_history.first_key_value() {
+            Some((first_change_id, _)) => first_change_id <= change_id,
+            None => false,
+        }
+    }
+
     /// Used for bootstrap servers (get a new batch to stream to the client)
     ///
     /// Returns a StreamBatch<ChangeID>
@@ -208,9 +257,44 @@ where
         last_state_step: &StreamingStep<Vec<u8>>,
         last_change_id: Option<ChangeID>,
     ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        self.get_range_batch_to_stream(&[], None, last_state_step, last_change_id)
+    }
+
+    /// Used for bootstrap servers streaming a range of the state, the keys from `start_key` (included)
+    /// to `end_key` (excluded, or up to the last key if None).
+    ///
+    /// Returns a StreamBatch<ChangeID> with the keys of the range following the cursor, and the changes
+    /// made since `last_change_id` on the keys of the range that were already streamed
+    pub fn get_range_batch_to_stream(
+        &self,
+        start_key: &[u8],
+        end_key: Option<&[u8]>,
+        last_state_step: &StreamingStep<Vec<u8>>,
+        last_change_id: Option<ChangeID>,
+    ) -> Result<StreamBatch<ChangeID>, MassaDBError> {
+        if let Some(end_key) = end_key {
+            if end_key <= start_key {
+                return Err(MassaDBError::InvalidRange(String::from(
+                    "the end of the range should be after its start",
+                )));
+            }
+        }
+        if let StreamingStep::Ongoing(max_key) = &last_state_step {
+            if max_key.as_slice() < start_key
+                || end_key.map_or(false, |end_key| max_key.as_slice() >= end_key)
+            {
+                return Err(MassaDBError::InvalidRange(String::from(
+                    "the streaming cursor is out of the range",
+                )));
+            }
+        }
+
         let bound_key_for_changes = match &last_state_step {
             StreamingStep::Ongoing(max_key) => Included(max_key.clone()),
-            _ => Unbounded,
+            _ => match end_key {
+                Some(end_key) => Excluded(end_key.to_vec()),
+                None => Unbounded,
+            },
         };
 
         let updates_on_previous_elements = match (&last_state_step, last_change_id) {
@@ -256,7 +340,7 @@ where
                                     updates.extend(
                                         changes
                                             .range((
-                                                Bound::<Vec<u8>>::Unbounded,
+                                                Included(start_key.to_vec()),
                                                 bound_key_for_changes.clone(),
                                             ))
                                             .map(|(k, v)| (k.clone(), v.clone())),
@@ -291,10 +375,15 @@ where
                     iter.next();
                     iter
                 }
-                _ => self.db.iterator_cf(handle, IteratorMode::Start),
+                _ => self
+                    .db
+                    .iterator_cf(handle, IteratorMode::From(start_key, Direction::Forward)),
             };
 
             for (serialized_key, serialized_value) in db_iterator.flatten() {
+                if end_key.map_or(false, |end_key| serialized_key.as_ref() >= end_key) {
+                    break;
+                }
                 if new_elements.len() < self.config.max_new_elements {
                     new_elements.insert(serialized_key.to_vec(), serialized_value.to_vec());
                 } else {
@@ -310,6 +399,41 @@ where
         })
     }
 
+    /// Used for bootstrap servers: returns at most `count - 1` sorted keys splitting the state in ranges
+    /// of similar sizes, so that a client can download the ranges from several servers at once.
+    ///
+    /// The keys are the first keys of the RocksDB files of the state, which makes them cheap to get.
+    pub fn get_state_split_keys(&self, count: usize) -> Vec<Vec<u8>> {
+        let Ok(live_files) = self.db.live_files() else {
+            return Vec::new();
+        };
+        let mut files: Vec<(Vec<u8>, usize)> = live_files
+            .into_iter()
+            .filter(|file| file.column_family_name == STATE_CF)
+            .filter_map(|file| Some((file.start_key?, file.size)))
+            .collect();
+        files.sort();
+        let total_size: usize = files.iter().map(|(_, size)| size).sum();
+        if count < 2 || total_size == 0 {
+            return Vec::new();
+        }
+        let range_size = total_size / count;
+        let mut keys: Vec<Vec<u8>> = Vec::new();
+        let mut current_size = 0;
+        for (start_key, size) in files {
+            if current_size > 0
+                && current_size >= range_size
+                && keys.len() < count - 1
+                && keys.last() != Some(&start_key)
+            {
+                keys.push(start_key);
+                current_size = 0;
+            }
+            current_size += size;
+        }
+        keys
+    }
+
     /// Used for bootstrap servers (get a new batch to stream to the client)
     ///
     /// Returns a StreamBatch<ChangeID>
@@ -565,13 +689,24 @@ where
             .and_modify(|map| map.extend(versioning_changes.clone().into_iter()))
             .or_insert(versioning_changes);
 
+        if compute_hash {
+            self.hash_history.insert(
+                self.get_change_id().expect(CHANGE_ID_DESER_ERROR),
+                self.get_db_hash(),
+            );
+        }
+
         if reset_history {
             self.change_history.clear();
+            self.hash_history.clear();
         }
 
         while self.change_history.len() > self.config.max_history_length {
             self.change_history.pop_first();
         }
+        while self.hash_history.len() > self.config.max_history_length {
+            self.hash_history.pop_first();
+        }
         Ok(())
     }
 
@@ -592,6 +727,47 @@ where
         Ok(change_id)
     }
 
+    /// Get a human readable summary of the RocksDB statistics, used for diagnostics.
+    ///
+    /// Contains the estimated number of keys and the SST files size of each column family,
+    /// followed by the RocksDB internal statistics.
+    pub fn get_db_stats(&self) -> String {
+        let mut stats = String::new();
+        for cf in [
+            STATE_CF,
+            VERSIONING_CF,
+            METADATA_CF,
+            LSMTREE_NODES_CF,
+            LSMTREE_VALUES_CF,
+        ] {
+            let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+            let estimated_keys = self
+                .db
+                .property_int_value_cf(handle, "rocksdb.estimate-num-keys")
+                .ok()
+                .flatten();
+            let sst_files_size = self
+                .db
+                .property_int_value_cf(handle, "rocksdb.total-sst-files-size")
+                .ok()
+                .flatten();
+            stats.push_str(&format!(
+                "{}: estimated keys: {:?}, SST files size: {:?}\n",
+                cf, estimated_keys, sst_files_size
+            ));
+        }
+        stats.push_str(&format!(
+            "change history length: {}, versioning change history length: {}\n",
+            self.change_history.len(),
+            self.change_history_versioning.len()
+        ));
+        if let Ok(Some(rocksdb_stats)) = self.db.property_value("rocksdb.stats") {
+            stats.push('\n');
+            stats.push_str(&rocksdb_stats);
+        }
+        stats
+    }
+
     /// Set the initial change_id. This function should only be called at startup/reset, as it does not batch this set with other changes.
     pub fn set_initial_change_id(&self, change_id: ChangeID) {
         self.current_batch.lock().clear();
@@ -759,6 +935,39 @@ where
             })
     }
 
+    /// Get the proof of the value of a key, or of its absence, against the current state hash.
+    ///
+    /// Proofs are only available while the state hash is the root of the Sparse Merkle Tree:
+    /// once the xor hash is used, the tree is no longer maintained.
+    pub fn get_state_proof(&self, key: &[u8]) -> Result<StateProof, MassaDBError> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        if self
+            .db
+            .get_cf(handle_metadata, STATE_HASH_KEY_IS_XOR_KEY)
+            .expect(CRUD_ERROR)
+            .is_some()
+        {
+            return Err(MassaDBError::HashError(String::from(
+                "state proofs are unavailable when the state hash is a xor hash",
+            )));
+        }
+
+        let key_hash = Hash::compute_from(key);
+        let proof = self
+            .lsmtree
+            .prove(key_hash.to_bytes())
+            .map_err(|e| MassaDBError::HashError(format!("failed to prove key: {}", e)))?;
+        Ok(StateProof {
+            side_nodes: proof
+                .side_nodes()
+                .iter()
+                .map(|node| node.to_vec())
+                .collect(),
+            non_membership_leaf_data: proof.non_membership_leaf_data().map(|data| data.to_vec()),
+            sibling_data: proof.sibling_data().map(|data| data.to_vec()),
+        })
+    }
+
     /// Get the current state hash xor of the database
     pub fn get_db_hash_xor(&self) -> Hash {
         self.get_db_hash_opt_xor()
@@ -846,6 +1055,7 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             config,
             change_history: BTreeMap::new(),
             change_history_versioning: BTreeMap::new(),
+            hash_history: BTreeMap::new(),
             change_id_serializer: SlotSerializer::new(),
             change_id_deserializer,
             lsmtree,
@@ -875,6 +1085,151 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             .expect("Failed to create checkpoint");
     }
 
+    /// Writes the state and versioning entries of the DB to a snapshot file, along with the slot
+    /// and the hash of the state, so that another node can start from it with `import_snapshot`.
+    ///
+    /// Format: magic, version, slot, xor hash flag, state hash, then `tag, key, value` records
+    /// (lengths as little endian u32) ending with `SNAPSHOT_END_TAG`.
+    pub fn export_snapshot(&self, path: &Path) -> Result<(), MassaDBError> {
+        let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        let slot = self
+            .get_change_id()
+            .map_err(|e| MassaDBError::InvalidChangeID(e.to_string()))?;
+        let mut slot_bytes = Vec::new();
+        self.change_id_serializer
+            .serialize(&slot, &mut slot_bytes)
+            .expect(CHANGE_ID_SER_ERROR);
+        let only_use_xor = self
+            .db
+            .get_cf(handle_metadata, STATE_HASH_KEY_IS_XOR_KEY)
+            .expect(CRUD_ERROR)
+            .is_some();
+
+        let file = File::create(path).map_err(|e| {
+            MassaDBError::SnapshotError(format!("cannot create {}: {}", path.display(), e))
+        })?;
+        let mut writer = BufWriter::new(file);
+        let write_error = |e: std::io::Error| {
+            MassaDBError::SnapshotError(format!("cannot write snapshot: {}", e))
+        };
+        writer.write_all(SNAPSHOT_MAGIC).map_err(write_error)?;
+        writer.write_all(&[SNAPSHOT_VERSION]).map_err(write_error)?;
+        write_snapshot_bytes(&mut writer, &slot_bytes).map_err(write_error)?;
+        writer
+            .write_all(&[only_use_xor as u8])
+            .map_err(write_error)?;
+        writer
+            .write_all(self.get_db_hash().to_bytes())
+            .map_err(write_error)?;
+
+        for (tag, cf) in [
+            (SNAPSHOT_STATE_TAG, STATE_CF),
+            (SNAPSHOT_VERSIONING_TAG, VERSIONING_CF),
+        ] {
+            let handle = self.db.cf_handle(cf).expect(CF_ERROR);
+            for item in self.db.iterator_cf(handle, IteratorMode::Start) {
+                let (key, value) = item
+                    .map_err(|e| MassaDBError::RocksDBError(format!("cannot read entry: {}", e)))?;
+                writer.write_all(&[tag]).map_err(write_error)?;
+                write_snapshot_bytes(&mut writer, &key).map_err(write_error)?;
+                write_snapshot_bytes(&mut writer, &value).map_err(write_error)?;
+            }
+        }
+        writer.write_all(&[SNAPSHOT_END_TAG]).map_err(write_error)?;
+        writer.flush().map_err(write_error)
+    }
+
+    /// Fills the empty DB with the entries of a snapshot file written by `export_snapshot`,
+    /// then checks that the recomputed state hash matches the one of the snapshot.
+    ///
+    /// Returns the slot of the snapshot.
+    pub fn import_snapshot(&mut self, path: &Path) -> Result<Slot, MassaDBError> {
+        let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        if self
+            .db
+            .iterator_cf(handle_state, IteratorMode::Start)
+            .next()
+            .is_some()
+        {
+            return Err(MassaDBError::SnapshotError(
+                "a snapshot can only be imported in an empty database".to_string(),
+            ));
+        }
+
+        let file = File::open(path).map_err(|e| {
+            MassaDBError::SnapshotError(format!("cannot open {}: {}", path.display(), e))
+        })?;
+        let mut reader = BufReader::new(file);
+        let read_error =
+            |e: std::io::Error| MassaDBError::SnapshotError(format!("cannot read snapshot: {}", e));
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).map_err(read_error)?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(MassaDBError::SnapshotError(format!(
+                "{} is not a snapshot",
+                path.display()
+            )));
+        }
+        let version = read_snapshot_u8(&mut reader).map_err(read_error)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(MassaDBError::SnapshotError(format!(
+                "unsupported snapshot version {}",
+                version
+            )));
+        }
+        let slot_bytes = read_snapshot_bytes(&mut reader).map_err(read_error)?;
+        let (_, slot) = self
+            .change_id_deserializer
+            .deserialize::<DeserializeError>(&slot_bytes)
+            .map_err(|e| MassaDBError::SnapshotError(format!("invalid snapshot slot: {}", e)))?;
+        let only_use_xor = read_snapshot_u8(&mut reader).map_err(read_error)? != 0;
+        let mut hash_bytes = [0u8; HASH_SIZE_BYTES];
+        reader.read_exact(&mut hash_bytes).map_err(read_error)?;
+        let state_hash = Hash::from_bytes(&hash_bytes);
+
+        let mut changes = BTreeMap::new();
+        let mut versioning_changes = BTreeMap::new();
+        loop {
+            let tag = read_snapshot_u8(&mut reader).map_err(read_error)?;
+            if tag == SNAPSHOT_END_TAG {
+                break;
+            }
+            let key = read_snapshot_bytes(&mut reader).map_err(read_error)?;
+            let value = read_snapshot_bytes(&mut reader).map_err(read_error)?;
+            match tag {
+                SNAPSHOT_STATE_TAG => changes.insert(key, Some(value)),
+                SNAPSHOT_VERSIONING_TAG => versioning_changes.insert(key, Some(value)),
+                _ => {
+                    return Err(MassaDBError::SnapshotError(format!(
+                        "invalid snapshot entry tag {}",
+                        tag
+                    )))
+                }
+            };
+            if changes.len() + versioning_changes.len() >= self.config.max_new_elements {
+                self.write_changes(
+                    std::mem::take(&mut changes),
+                    std::mem::take(&mut versioning_changes),
+                    None,
+                    true,
+                    false,
+                    false,
+                )?;
+            }
+        }
+        self.write_changes(changes, versioning_changes, Some(slot), true, false, false)?;
+
+        self.recompute_db_hash(only_use_xor)?;
+        if self.get_db_hash() != state_hash {
+            return Err(MassaDBError::SnapshotError(format!(
+                "state hash mismatch: the snapshot expects {}, the imported state has {}",
+                state_hash,
+                self.get_db_hash()
+            )));
+        }
+        Ok(slot)
+    }
+
     /// Writes the batch to the DB
     pub fn write_batch(
         &mut self,
@@ -942,3 +1297,124 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.current_hashmap.write().clear();
     }
 }
+
+/// Writes bytes to a snapshot, prefixed with their length as a little endian u32
+fn write_snapshot_bytes(writer: &mut impl Write, bytes: &[u8]) -> std::io::Result<()> {
+    let length = u32::try_from(bytes.len())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "entry too long"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// Reads bytes written by `write_snapshot_bytes`
+fn read_snapshot_bytes(reader: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let mut bytes = Vec::new();
+    reader
+        .take(u32::from_le_bytes(length) as u64)
+        .read_to_end(&mut bytes)?;
+    if bytes.len() != u32::from_le_bytes(length) as usize {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// Reads a byte of a snapshot
+fn read_snapshot_u8(reader: &mut impl Read) -> std::io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Streams a whole range of the state, part after part, as a bootstrap client does
+    fn stream_range(db: &MassaDB, start_key: &[u8], end_key: Option<&[u8]>) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        let mut last_step = StreamingStep::Started;
+        let mut last_change_id = None;
+        while !last_step.finished() {
+            let part = db
+                .get_range_batch_to_stream(start_key, end_key, &last_step, last_change_id)
+                .unwrap();
+            last_step = match part.new_elements.last_key_value() {
+                Some((key, _)) => StreamingStep::Ongoing(key.clone()),
+                None => StreamingStep::Finished(None),
+            };
+            last_change_id = Some(part.change_id);
+            keys.extend(part.new_elements.into_keys());
+        }
+        keys
+    }
+
+    #[test]
+    fn test_stream_state_by_ranges() {
+        let dir = TempDir::new().unwrap();
+        let mut db = MassaDB::new(MassaDBConfig {
+            path: dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_new_elements: 3,
+            thread_count: 2,
+        });
+        let mut batch = DBBatch::new();
+        for key in 0..10u8 {
+            db.put_or_update_entry_value(&mut batch, vec![key], &[key]);
+        }
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)), false);
+
+        // the ranges split the state without overlapping
+        let split_keys: [&[u8]; 2] = [&[3], &[7]];
+        assert_eq!(
+            stream_range(&db, &[], Some(split_keys[0])),
+            vec![vec![0], vec![1], vec![2]]
+        );
+        assert_eq!(
+            stream_range(&db, split_keys[0], Some(split_keys[1])),
+            vec![vec![3], vec![4], vec![5], vec![6]]
+        );
+        assert_eq!(
+            stream_range(&db, split_keys[1], None),
+            vec![vec![7], vec![8], vec![9]]
+        );
+        assert!(db
+            .get_range_batch_to_stream(
+                split_keys[1],
+                Some(split_keys[0]),
+                &StreamingStep::Started,
+                None
+            )
+            .is_err());
+        assert!(db
+            .get_range_batch_to_stream(
+                &[],
+                Some(split_keys[0]),
+                &StreamingStep::Ongoing(vec![5]),
+                Some(Slot::new(1, 0))
+            )
+            .is_err());
+
+        // refreshing a downloaded range only returns the changes made on its keys
+        let mut batch = DBBatch::new();
+        db.put_or_update_entry_value(&mut batch, vec![1], &[10]);
+        db.put_or_update_entry_value(&mut batch, vec![5], &[50]);
+        db.write_batch(batch, DBBatch::new(), Some(Slot::new(1, 1)), false);
+        let refresh = db
+            .get_range_batch_to_stream(
+                &[],
+                Some(split_keys[0]),
+                &StreamingStep::Finished(None),
+                Some(Slot::new(1, 0)),
+            )
+            .unwrap();
+        assert!(refresh.new_elements.is_empty());
+        assert_eq!(
+            refresh.updates_on_previous_elements,
+            BTreeMap::from([(vec![1], Some(vec![10]))])
+        );
+        assert_eq!(refresh.change_id, Slot::new(1, 1));
+    }
+}
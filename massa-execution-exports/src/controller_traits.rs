@@ -4,7 +4,10 @@
 
 use crate::types::ReadOnlyExecutionRequest;
 use crate::ExecutionError;
-use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use crate::{
+    EventProof, ExecutionAddressInfo, IndexedOperation, OperationTrace, ReadOnlyExecutionOutput,
+};
+use massa_ledger_exports::LedgerEntryProof;
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
@@ -16,8 +19,8 @@ use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
 use massa_storage::Storage;
-use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
 /// interface that communicates with the execution worker thread
@@ -43,6 +46,37 @@ pub trait ExecutionController: Send + Sync {
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent>;
 
+    /// Get the Merkle inclusion proof of the event emitted at `index_in_slot` during the execution of `slot`.
+    ///
+    /// Returns `None` if the events of that slot are not (or no longer entirely) available.
+    fn get_event_proof(&self, slot: Slot, index_in_slot: u64) -> Option<EventProof>;
+
+    /// Get the final balance, bytecode and given datastore entries of an address,
+    /// with the proof of each value against the final state hash.
+    fn get_ledger_entry_proof(
+        &self,
+        address: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, ExecutionError>;
+
+    /// Get up to `limit` final executed operations involving `address`, most recent first.
+    /// If a cursor (slot and id of an operation) is given, only the operations before it are returned.
+    ///
+    /// Returns `None` if the operation index is disabled.
+    fn get_operations_by_address(
+        &self,
+        address: &Address,
+        cursor: Option<(Slot, OperationId)>,
+        limit: usize,
+    ) -> Option<Vec<IndexedOperation>>;
+
+    /// Get the trace of the last execution of an operation: its call tree, with the gas,
+    /// datastore accesses and coin transfers of each call.
+    /// Only the last executed operations are kept, the inner option is `None` if the operation was not traced or was forgotten.
+    ///
+    /// Returns `None` if operation tracing is disabled.
+    fn get_operation_trace(&self, operation_id: &OperationId) -> Option<Option<OperationTrace>>;
+
     /// Get the final and active values of balance.
     ///
     /// # Return value
@@ -71,6 +105,16 @@ pub trait ExecutionController: Send + Sync {
         input: Vec<(Address, Vec<u8>)>,
     ) -> Vec<(Option<Vec<u8>>, Option<Vec<u8>>)>;
 
+    /// Get the final and candidate datastore keys of an address starting with `prefix`
+    ///
+    /// # Return value
+    /// * `(final_keys, candidate_keys)`
+    fn get_final_and_candidate_datastore_keys(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+    ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>);
+
     /// Returns for a given cycle the stakers taken into account
     /// by the selector. That correspond to the `roll_counts` in `cycle - 3`.
     ///
@@ -30,6 +30,9 @@ pub enum ExecutionError {
     /// `RollSell` error: {0}
     RollSellError(String),
 
+    /// `StakeDelegation` error: {0}
+    StakeDelegationError(String),
+
     /// Slash roll or deferred credits  error: {0}
     SlashError(String),
 
@@ -73,4 +76,7 @@ pub enum ExecutionError {
 
     /// Factory error: {0}
     FactoryError(#[from] FactoryError),
+
+    /// Ledger error: {0}
+    LedgerError(String),
 }
@@ -3,6 +3,7 @@
 //! This module represents an event store allowing to store, search and retrieve
 //! a config-limited number of execution-generated events
 
+use massa_hash::{compute_merkle_root, Hash, MerkleProof};
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
 use std::collections::VecDeque;
@@ -46,6 +47,37 @@ impl EventStore {
         }
     }
 
+    /// Compute the Merkle root committing to the events of this store, in order.
+    ///
+    /// Meant to be called on the events of a single slot.
+    pub fn compute_merkle_root(&self) -> Hash {
+        compute_merkle_root(&self.commitment_hashes())
+    }
+
+    /// Compute the Merkle inclusion proof of the event emitted at `index_in_slot`.
+    ///
+    /// Meant to be called on the complete list of events of a single slot:
+    /// returns `None` if the event is not found or if the events are not contiguous
+    /// (for example because the store was pruned).
+    pub fn compute_event_proof(&self, index_in_slot: u64) -> Option<MerkleProof> {
+        let is_contiguous = self
+            .0
+            .iter()
+            .enumerate()
+            .all(|(index, event)| event.context.index_in_slot == index as u64);
+        if !is_contiguous {
+            return None;
+        }
+        MerkleProof::compute(&self.commitment_hashes(), index_in_slot as usize)
+    }
+
+    fn commitment_hashes(&self) -> Vec<Hash> {
+        self.0
+            .iter()
+            .map(|event| event.compute_commitment_hash())
+            .collect()
+    }
+
     /// Get events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -56,44 +88,7 @@ impl EventStore {
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
         self.0
             .iter()
-            .filter(|x| {
-                if let Some(start) = filter.start {
-                    if x.context.slot < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = filter.end {
-                    if x.context.slot >= end {
-                        return false;
-                    }
-                }
-                if let Some(is_final) = filter.is_final {
-                    if x.context.is_final != is_final {
-                        return false;
-                    }
-                }
-                if let Some(is_error) = filter.is_error {
-                    if x.context.is_error != is_error {
-                        return false;
-                    }
-                }
-                match (filter.emitter_address, x.context.call_stack.front()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_caller_address, x.context.call_stack.back()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_operation_id, x.context.origin_operation_id) {
-                    (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                true
-            })
+            .filter(|x| filter.matches(x))
             .cloned()
             .collect()
     }
@@ -34,6 +34,9 @@
 //! ## `event_store.rs`
 //! Defines an indexed, finite-size storage system for execution events.
 //!
+//! ## `trace.rs`
+//! Defines the traces of the execution of operations.
+//!
 //! ## `types.rs`
 //! Defines useful shared structures.
 //!
@@ -50,6 +53,7 @@ mod error;
 mod event_store;
 mod mapping_grpc;
 mod settings;
+mod trace;
 mod types;
 
 pub use channels::ExecutionChannels;
@@ -60,10 +64,11 @@ pub use error::ExecutionError;
 pub use event_store::EventStore;
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
+pub use trace::{CallTrace, CoinTransfer, DatastoreAccess, OperationTrace};
 pub use types::{
-    ExecutionAddressInfo, ExecutionOutput, ExecutionStackElement, ReadOnlyCallRequest,
-    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
-    SlotExecutionOutput,
+    EventProof, ExecutionAddressInfo, ExecutionOutput, ExecutionStackElement, IndexedOperation,
+    ReadOnlyCallRequest, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, ReadOnlyLedgerOverride, SlotExecutionOutput,
 };
 
 #[cfg(any(feature = "testing", feature = "gas_calibration"))]
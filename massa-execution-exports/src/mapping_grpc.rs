@@ -1,6 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::{ExecutionOutput, SlotExecutionOutput};
+use crate::{ExecutionOutput, ReadOnlyExecutionOutput, SlotExecutionOutput};
 use massa_proto_rs::massa::model::v1 as grpc_model;
 
 impl From<SlotExecutionOutput> for grpc_model::SlotExecutionOutput {
@@ -37,3 +37,13 @@ impl From<ExecutionOutput> for grpc_model::ExecutionOutput {
         }
     }
 }
+
+impl From<ReadOnlyExecutionOutput> for grpc_model::ReadOnlyExecutionOutput {
+    fn from(value: ReadOnlyExecutionOutput) -> Self {
+        grpc_model::ReadOnlyExecutionOutput {
+            out: Some(value.out.into()),
+            used_gas: value.gas_cost,
+            call_result: value.call_result,
+        }
+    }
+}
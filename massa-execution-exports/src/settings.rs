@@ -2,11 +2,12 @@
 
 //! This module provides the structures used to provide configuration parameters to the Execution system
 
+use massa_hash::Hash;
 use massa_models::amount::Amount;
 use massa_sc_runtime::GasCosts;
 use massa_time::MassaTime;
 use num::rational::Ratio;
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 /// Storage cost constants
 #[derive(Debug, Clone, Copy)]
@@ -24,7 +25,7 @@ pub struct StorageCostsConstants {
 pub struct ExecutionConfig {
     /// read-only execution request queue length
     pub readonly_queue_length: usize,
-    /// maximum number of SC output events kept in cache
+    /// maximum number of SC output events kept in cache, or returned by a query to the persistent event store
     pub max_final_events: usize,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
@@ -56,6 +57,8 @@ pub struct ExecutionConfig {
     pub max_datastore_key_length: u8,
     /// Max bytecode size
     pub max_bytecode_size: u64,
+    /// Max depth of nested smart contract calls
+    pub max_call_depth: u16,
     /// Max datastore value size
     pub max_datastore_value_size: u64,
     /// Storage cost constants
@@ -64,6 +67,12 @@ pub struct ExecutionConfig {
     pub max_read_only_gas: u64,
     /// Gas costs
     pub gas_costs: GasCosts,
+    /// Hash of the gas cost files, the cached modules compiled with other gas costs are not reused
+    pub gas_costs_version: Hash,
+    /// Gas costs replacing `gas_costs` from the activation of a version of the `GasCosts` MIP component,
+    /// by component version, with the hash of their files (see `gas_costs_version`).
+    /// They are built into the node, since all the nodes must apply the same ones.
+    pub gas_costs_schedules: BTreeMap<u32, (GasCosts, Hash)>,
     /// Path to the initial vesting file
     pub initial_vesting_path: PathBuf,
     /// last start period, used to attach to the correct execution slot if the network has restarted
@@ -74,8 +83,20 @@ pub struct ExecutionConfig {
     pub lru_cache_size: u32,
     /// Maximum number of entries we want to keep in the HD cache
     pub hd_cache_size: usize,
+    /// Maximum total size in bytes of the compiled modules we want to keep in the HD cache
+    pub hd_cache_max_byte_size: u64,
     /// Amount of entries removed when `hd_cache_size` is reached
     pub snip_amount: usize,
+    /// Path to the index of the final operations by address, none to disable it
+    pub operation_index_path: Option<PathBuf>,
+    /// Path to the persistent store of the final events, none to keep them in memory (see `max_final_events`)
+    pub event_store_path: Option<PathBuf>,
+    /// Number of periods the final events are kept for in the persistent store, 0 to keep them forever
+    pub event_store_retention_periods: u64,
+    /// path of the record of the final slots (blocks, operations and final state hashes) replayed by the `replay` command, none to disable it
+    pub final_slots_record_path: Option<PathBuf>,
+    /// path of the file the state changes of the final slots are appended to, none to disable their export
+    pub state_changes_export_path: Option<PathBuf>,
     /// Number of roll to remove per denunciation
     pub roll_count_to_slash_on_denunciation: u64,
     /// Denunciation expire delta
@@ -84,4 +105,8 @@ pub struct ExecutionConfig {
     pub broadcast_enabled: bool,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// max number of operation traces kept, 0 to disable the tracing of the executed operations
+    pub max_operation_traces: usize,
+    /// max number of threads executing the independent operations of a block in parallel, 0 or 1 to execute them serially
+    pub parallel_execution_threads: usize,
 }
@@ -3,6 +3,7 @@
 //! This file defines testing tools related to the configuration
 
 use crate::{ExecutionConfig, StorageCostsConstants};
+use massa_hash::Hash;
 use massa_models::config::*;
 use massa_sc_runtime::GasCosts;
 use massa_time::MassaTime;
@@ -39,6 +40,7 @@ impl Default for ExecutionConfig {
             max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
             max_bytecode_size: MAX_BYTECODE_LENGTH,
+            max_call_depth: 32,
             max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
             storage_costs_constants,
             max_read_only_gas: 100_000_000,
@@ -55,16 +57,26 @@ impl Default for ExecutionConfig {
                 .into(),
             )
             .unwrap(),
+            gas_costs_version: Hash::compute_from(b"test_gas_costs"),
+            gas_costs_schedules: Default::default(),
             initial_vesting_path: PathBuf::default(),
             last_start_period: 0,
             hd_cache_path: TempDir::new().unwrap().path().to_path_buf(),
             lru_cache_size: 1000,
             hd_cache_size: 10_000,
+            hd_cache_max_byte_size: 1_000_000_000,
             snip_amount: 10,
+            operation_index_path: None,
+            event_store_path: None,
+            event_store_retention_periods: 0,
+            final_slots_record_path: None,
+            state_changes_export_path: None,
             roll_count_to_slash_on_denunciation: 1,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             broadcast_enabled: true,
             broadcast_slot_execution_output_channel_capacity: 5000,
+            max_operation_traces: 0,
+            parallel_execution_threads: 0,
         }
     }
 }
@@ -3,10 +3,10 @@
 //! This file defines utilities to mock the crate for testing purposes
 
 use crate::{
-    ExecutionAddressInfo, ExecutionController, ExecutionError, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest,
+    EventProof, ExecutionAddressInfo, ExecutionController, ExecutionError, IndexedOperation,
+    OperationTrace, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
 };
-use massa_ledger_exports::LedgerEntry;
+use massa_ledger_exports::{LedgerEntry, LedgerEntryProof};
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::{
     address::Address,
@@ -23,7 +23,7 @@ use massa_storage::Storage;
 use massa_time::MassaTime;
 use parking_lot::Mutex;
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     sync::{
         mpsc::{self, Receiver},
         Arc,
@@ -160,6 +160,33 @@ impl ExecutionController for MockExecutionController {
         response_rx.recv().unwrap()
     }
 
+    fn get_event_proof(&self, _slot: Slot, _index_in_slot: u64) -> Option<EventProof> {
+        None
+    }
+
+    fn get_ledger_entry_proof(
+        &self,
+        _address: &Address,
+        _datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, ExecutionError> {
+        Err(ExecutionError::LedgerError(
+            "ledger proofs are not available in the mock".to_string(),
+        ))
+    }
+
+    fn get_operations_by_address(
+        &self,
+        _address: &Address,
+        _cursor: Option<(Slot, OperationId)>,
+        _limit: usize,
+    ) -> Option<Vec<IndexedOperation>> {
+        None
+    }
+
+    fn get_operation_trace(&self, _operation_id: &OperationId) -> Option<Option<OperationTrace>> {
+        None
+    }
+
     fn get_final_and_candidate_balance(
         &self,
         addresses: &[Address],
@@ -185,6 +212,14 @@ impl ExecutionController for MockExecutionController {
         Vec::default()
     }
 
+    fn get_final_and_candidate_datastore_keys(
+        &self,
+        _addr: &Address,
+        _prefix: &[u8],
+    ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>) {
+        (BTreeSet::default(), BTreeSet::default())
+    }
+
     fn get_addresses_infos(&self, _addresses: &[Address]) -> Vec<ExecutionAddressInfo> {
         Vec::default()
     }
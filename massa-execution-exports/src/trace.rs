@@ -0,0 +1,94 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the traces of the execution of operations
+
+use massa_models::{
+    address::Address, amount::Amount, block_id::BlockId, operation::OperationId, slot::Slot,
+};
+use serde::{Deserialize, Serialize};
+
+/// Access to a datastore entry during a call
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DatastoreAccess {
+    /// address owning the datastore
+    pub address: Address,
+    /// key of the entry
+    pub key: Vec<u8>,
+    /// value read or written, none if the entry was missing or deleted
+    pub value: Option<Vec<u8>>,
+}
+
+/// Coin transfer made during a call
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CoinTransfer {
+    /// debited address, none if the coins were created
+    pub from: Option<Address>,
+    /// credited address, none if the coins were destroyed
+    pub to: Option<Address>,
+    /// amount of coins transferred
+    pub amount: Amount,
+}
+
+/// Trace of a call and of the calls it made
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CallTrace {
+    /// address that made the call, none for the root call of the operation
+    pub caller: Option<Address>,
+    /// address in the context of which the call was executed
+    pub address: Address,
+    /// called function, only known for the function called by the operation itself
+    pub function: Option<String>,
+    /// coins transferred to the called address with the call
+    pub coins: Amount,
+    /// gas used by the call, including its nested calls.
+    /// Only known for the call run by the operation itself, the VM meters the nested calls internally.
+    pub gas_used: Option<u64>,
+    /// datastore entries read by the call, in order
+    pub storage_reads: Vec<DatastoreAccess>,
+    /// datastore entries written by the call, in order
+    pub storage_writes: Vec<DatastoreAccess>,
+    /// coin transfers made by the call, in order
+    pub transfers: Vec<CoinTransfer>,
+    /// calls made by the call, in order
+    pub calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    /// Creates the trace of a call that did nothing yet
+    pub fn new(
+        caller: Option<Address>,
+        address: Address,
+        function: Option<String>,
+        coins: Amount,
+    ) -> Self {
+        CallTrace {
+            caller,
+            address,
+            function,
+            coins,
+            gas_used: None,
+            storage_reads: Vec::new(),
+            storage_writes: Vec::new(),
+            transfers: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+}
+
+/// Trace of the execution of an operation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationTrace {
+    /// operation id
+    pub operation_id: OperationId,
+    /// slot at which the operation was executed
+    pub slot: Slot,
+    /// block including the operation
+    pub block_id: Option<BlockId>,
+    /// whether the execution succeeded. The effects of a failed execution are reverted,
+    /// except for the fees, but the trace keeps what happened until the failure.
+    pub success: bool,
+    /// execution error, if any
+    pub error: Option<String>,
+    /// root call, in the context of the operation sender
+    pub root: CallTrace,
+}
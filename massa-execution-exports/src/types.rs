@@ -4,10 +4,13 @@
 
 use crate::event_store::EventStore;
 use massa_final_state::StateChanges;
+use massa_hash::{Hash, MerkleProof};
+use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
+use massa_models::output_event::SCOutputEvent;
 use massa_models::{
     address::Address, address::ExecutionAddressCycleInfo, amount::Amount, block_id::BlockId,
-    slot::Slot,
+    operation::OperationId, slot::Slot,
 };
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -23,11 +26,15 @@ pub struct ExecutionAddressInfo {
     pub final_roll_count: u64,
     /// final datastore keys of the address
     pub final_datastore_keys: BTreeSet<Vec<u8>>,
+    /// final operator the address delegated its stake to
+    pub final_delegation: Option<Address>,
 
     /// candidate number of rolls the address has
     pub candidate_roll_count: u64,
     /// candidate datastore keys of the address
     pub candidate_datastore_keys: BTreeSet<Vec<u8>>,
+    /// candidate operator the address delegated its stake to
+    pub candidate_delegation: Option<Address>,
 
     /// future deferred credits
     pub future_deferred_credits: BTreeMap<Slot, Amount>,
@@ -57,6 +64,32 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// Merkle root of the events emitted by the execution step
+    pub events_merkle_root: Hash,
+}
+
+/// Proof that an event was emitted at a given slot
+#[derive(Debug, Clone)]
+pub struct EventProof {
+    /// proven event
+    pub event: SCOutputEvent,
+    /// Merkle root of all the events emitted at the slot of the event
+    pub events_merkle_root: Hash,
+    /// inclusion proof of the event commitment hash in the events Merkle tree
+    pub proof: MerkleProof,
+}
+
+/// Final executed operation involving an address, as kept in the operation index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedOperation {
+    /// operation id
+    pub op_id: OperationId,
+    /// slot of the block that executed the operation
+    pub slot: Slot,
+    /// block that executed the operation
+    pub block_id: BlockId,
+    /// true if the operation execution succeeded
+    pub success: bool,
 }
 
 /// structure describing the output of a read only execution
@@ -79,10 +112,30 @@ pub struct ReadOnlyExecutionRequest {
     pub call_stack: Vec<ExecutionStackElement>,
     /// Target of the request
     pub target: ReadOnlyExecutionTarget,
+    /// Fee debited from the caller before the execution, as for an operation
+    pub fee: Amount,
     /// execution start state
     ///
     /// Whether to start execution from final or active state
     pub is_final: bool,
+    /// Ledger entries overridden before the execution starts
+    pub ledger_overrides: Vec<ReadOnlyLedgerOverride>,
+}
+
+/// Override of a ledger entry applied before a read-only execution,
+/// allowing to simulate an execution on a hypothetical state.
+///
+/// Overrides bypass all storage costs and checks. The entry is created if it does not exist.
+#[derive(Debug, Clone)]
+pub struct ReadOnlyLedgerOverride {
+    /// Overridden address
+    pub address: Address,
+    /// Balance to set, if any
+    pub balance: Option<Amount>,
+    /// Bytecode to set, if any
+    pub bytecode: Option<Bytecode>,
+    /// Datastore entries to set
+    pub datastore: BTreeMap<Vec<u8>, Vec<u8>>,
 }
 
 /// structure describing different possible targets of a read-only execution request
@@ -144,4 +197,7 @@ pub struct ExecutionStackElement {
     pub owned_addresses: Vec<Address>,
     /// Datastore (key value store) for `ExecuteSC` Operation
     pub operation_datastore: Option<Datastore>,
+    /// Whether the called address took the reentrancy lock.
+    /// While set, the address cannot be called again until this element is popped from the stack.
+    pub reentrancy_locked: bool,
 }
@@ -197,6 +197,24 @@ impl ActiveHistory {
         })
     }
 
+    /// Lazily query (from end to beginning) the latest stake delegation change of an address.
+    ///
+    /// Returns `None` if the delegation of the address was not changed in the active history,
+    /// `Some(None)` if it was revoked.
+    ///
+    /// # Arguments
+    /// * `addr`: address delegating its stake
+    pub fn fetch_delegation(&self, addr: &Address) -> Option<Option<Address>> {
+        self.0.iter().rev().find_map(|output| {
+            output
+                .state_changes
+                .pos_changes
+                .delegation_changes
+                .get(addr)
+                .cloned()
+        })
+    }
+
     /// Gets all the deferred credits that will be credited until a given slot (included)
     pub fn get_all_deferred_credits_until(&self, slot: &Slot) -> DeferredCredits {
         self.0
@@ -11,12 +11,14 @@ use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::speculative_executed_denunciations::SpeculativeExecutedDenunciations;
 use crate::speculative_executed_ops::SpeculativeExecutedOps;
 use crate::speculative_ledger::SpeculativeLedger;
+use crate::trace::CallTracer;
 use crate::vesting_manager::VestingManager;
 use crate::{active_history::ActiveHistory, speculative_roll_state::SpeculativeRollState};
 use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
 use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
 use massa_execution_exports::{
     EventStore, ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement,
+    OperationTrace, ReadOnlyLedgerOverride,
 };
 use massa_final_state::{FinalState, StateChanges};
 use massa_hash::Hash;
@@ -31,12 +33,13 @@ use massa_models::{
     block_id::BlockId,
     operation::OperationId,
     output_event::{EventExecutionContext, SCOutputEvent},
+    prehash::PreHashSet,
     slot::Slot,
 };
-use massa_module_cache::controller::ModuleCache;
+use massa_module_cache::{controller::ModuleCache, types::GasCostsSchedule};
 use massa_pos_exports::PoSChanges;
 use massa_versioning::address_factory::{AddressArgs, AddressFactory};
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_versioning::versioning_factory::{FactoryStrategy, VersioningFactory};
 use parking_lot::RwLock;
 use rand::SeedableRng;
@@ -148,12 +151,22 @@ pub struct ExecutionContext {
     /// Unsafe random state (can be predicted and manipulated)
     pub unsafe_rng: Xoshiro256PlusPlus,
 
+    /// gas costs applied to the executions and to the compilation of the modules they load,
+    /// see `get_gas_costs_schedule`
+    pub gas_costs_schedule: GasCostsSchedule,
+
     /// Creator address. The bytecode of this address can't be modified
     pub creator_address: Option<Address>,
 
     /// operation id that originally caused this execution (if any)
     pub origin_operation_id: Option<OperationId>,
 
+    /// call tree of the operation being executed, if operation tracing is enabled
+    pub operation_tracer: Option<CallTracer>,
+
+    /// traces of the operations executed so far in the slot
+    pub operation_traces: Vec<OperationTrace>,
+
     // cache of compiled runtime modules
     pub module_cache: Arc<RwLock<ModuleCache>>,
 
@@ -219,8 +232,14 @@ impl ExecutionContext {
             read_only: Default::default(),
             events: Default::default(),
             unsafe_rng: Xoshiro256PlusPlus::from_seed([0u8; 32]),
+            gas_costs_schedule: GasCostsSchedule {
+                gas_costs: config.gas_costs.clone(),
+                version: config.gas_costs_version,
+            },
             creator_address: Default::default(),
             origin_operation_id: Default::default(),
+            operation_tracer: None,
+            operation_traces: Default::default(),
             module_cache,
             config,
             vesting_manager,
@@ -283,6 +302,71 @@ impl ExecutionContext {
         ));
     }
 
+    /// Creates a fork of the context to execute the operation `op_id` apart from it (see `merge_fork`).
+    /// The operation must touch only the ledger entries of `addresses`: the fork carries the speculative changes
+    /// made to these entries and whether the operation was executed, but none of the other speculative changes,
+    /// so that forking does not depend on the size of the speculative state.
+    /// The fork starts with no events and no operation traces.
+    pub(crate) fn fork(
+        &self,
+        addresses: &PreHashSet<Address>,
+        op_id: &OperationId,
+    ) -> ExecutionContext {
+        ExecutionContext {
+            config: self.config.clone(),
+            speculative_ledger: self.speculative_ledger.fork(addresses),
+            speculative_async_pool: self.speculative_async_pool.fork_untouched(),
+            speculative_roll_state: self.speculative_roll_state.fork_untouched(),
+            speculative_executed_ops: self.speculative_executed_ops.fork(op_id),
+            speculative_executed_denunciations: self
+                .speculative_executed_denunciations
+                .fork_untouched(),
+            max_gas: self.max_gas,
+            creator_coin_spending_allowance: self.creator_coin_spending_allowance,
+            slot: self.slot,
+            created_addr_index: self.created_addr_index,
+            created_event_index: self.created_event_index,
+            created_message_index: self.created_message_index,
+            opt_block_id: self.opt_block_id,
+            stack: self.stack.clone(),
+            read_only: self.read_only,
+            events: Default::default(),
+            unsafe_rng: self.unsafe_rng.clone(),
+            gas_costs_schedule: self.gas_costs_schedule.clone(),
+            creator_address: self.creator_address,
+            origin_operation_id: self.origin_operation_id,
+            operation_tracer: None,
+            operation_traces: Default::default(),
+            module_cache: self.module_cache.clone(),
+            vesting_manager: self.vesting_manager.clone(),
+            address_factory: self.address_factory.clone(),
+        }
+    }
+
+    /// Merges a fork of the context (see `fork`) that executed an operation touching only the ledger entries of `addresses`,
+    /// as if the operation was executed in this context: the ledger changes to `addresses` and the operation state are taken,
+    /// and the events emitted by the fork are emitted again with the indexes of this context.
+    pub(crate) fn merge_fork(
+        &mut self,
+        fork: ExecutionContext,
+        addresses: &PreHashSet<Address>,
+        op_id: &OperationId,
+    ) {
+        self.speculative_ledger
+            .merge_fork(&fork.speculative_ledger, addresses);
+        self.speculative_executed_ops
+            .merge_fork(&fork.speculative_executed_ops, op_id);
+        self.max_gas = fork.max_gas;
+        self.creator_coin_spending_allowance = fork.creator_coin_spending_allowance;
+        self.creator_address = fork.creator_address;
+        self.origin_operation_id = fork.origin_operation_id;
+        self.stack = fork.stack;
+        for event in fork.events.0 {
+            self.event_emit(event);
+        }
+        self.operation_traces.extend(fork.operation_traces);
+    }
+
     /// Create a new `ExecutionContext` for read-only execution
     /// This should be used before performing a read-only execution.
     ///
@@ -327,6 +411,7 @@ impl ExecutionContext {
             stack: call_stack,
             read_only: true,
             unsafe_rng,
+            gas_costs_schedule: get_gas_costs_schedule(&config, &mip_store, slot),
             ..ExecutionContext::new(
                 config,
                 final_state,
@@ -399,6 +484,7 @@ impl ExecutionContext {
             slot,
             opt_block_id,
             unsafe_rng,
+            gas_costs_schedule: get_gas_costs_schedule(&config, &mip_store, slot),
             ..ExecutionContext::new(
                 config,
                 final_state,
@@ -446,6 +532,22 @@ impl ExecutionContext {
         self.stack.iter().map(|v| v.address).collect()
     }
 
+    /// Checks whether the call guards (max call depth and reentrancy lock) are active at the current slot.
+    /// They are enabled by the activation of the version 1 of the VM component.
+    pub fn call_guards_active(&self) -> bool {
+        let slot_timestamp = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            self.slot,
+        )
+        .expect("could not compute current slot timestamp");
+        self.address_factory
+            .mip_store
+            .get_latest_component_version_at(&MipComponent::VM, slot_timestamp)
+            >= 1
+    }
+
     /// Checks whether the context currently grants write access to a given address
     pub fn has_write_rights_on(&self, addr: &Address) -> bool {
         self.stack
@@ -616,9 +718,21 @@ impl ExecutionContext {
         // append data
         res_data.extend(data);
 
-        // set data entry
-        self.speculative_ledger
-            .set_data_entry(&self.get_current_address()?, address, key, res_data)
+        // set data entry, keeping the resulting value for the trace
+        let traced = self
+            .operation_tracer
+            .is_some()
+            .then(|| (key.clone(), res_data.clone()));
+        self.speculative_ledger.set_data_entry(
+            &self.get_current_address()?,
+            address,
+            key,
+            res_data,
+        )?;
+        if let (Some(tracer), Some((key, value))) = (self.operation_tracer.as_mut(), traced) {
+            tracer.record_write(*address, &key, Some(value));
+        }
+        Ok(())
     }
 
     /// Deletes a datastore entry for an address.
@@ -698,6 +812,11 @@ impl ExecutionContext {
             *allowance = allowance.saturating_sub(amount);
         }
 
+        // trace the transfer
+        if let Some(tracer) = self.operation_tracer.as_mut() && result.is_ok() {
+            tracer.record_transfer(from_addr, to_addr, amount);
+        }
+
         result
     }
 
@@ -733,6 +852,23 @@ impl ExecutionContext {
             .add_rolls(buyer_addr, roll_count);
     }
 
+    /// Get the operator an address delegated its stake to
+    ///
+    /// # Arguments
+    /// * `owner`: address owning the rolls
+    pub fn get_delegation(&self, owner: &Address) -> Option<Address> {
+        self.speculative_roll_state.get_delegation(owner)
+    }
+
+    /// Delegate the stake of an address to an operator, or revoke its delegation (`None` operator)
+    ///
+    /// # Arguments
+    /// * `owner`: address owning the rolls
+    /// * `operator`: address producing the blocks and endorsements of the owner
+    pub fn set_delegation(&mut self, owner: &Address, operator: Option<Address>) {
+        self.speculative_roll_state.set_delegation(owner, operator);
+    }
+
     /// Try to sell `roll_count` rolls from the seller address.
     ///
     /// # Arguments
@@ -882,7 +1018,7 @@ impl ExecutionContext {
         {
             let mut cache_write_lock = self.module_cache.write();
             for bytecode in bc_updates {
-                cache_write_lock.save_module(&bytecode.0);
+                cache_write_lock.save_module(&bytecode.0, &self.gas_costs_schedule);
             }
         }
 
@@ -908,14 +1044,33 @@ impl ExecutionContext {
             executed_ops_changes: self.speculative_executed_ops.take(),
             executed_denunciations_changes: self.speculative_executed_denunciations.take(),
         };
+        let events = std::mem::take(&mut self.events);
         ExecutionOutput {
             slot,
             block_id: std::mem::take(&mut self.opt_block_id),
             state_changes,
-            events: std::mem::take(&mut self.events),
+            events_merkle_root: events.compute_merkle_root(),
+            events,
         }
     }
 
+    /// Applies ledger overrides to the speculative ledger of a read-only context.
+    /// Fails if the context is not read-only.
+    pub fn apply_ledger_overrides(
+        &mut self,
+        ledger_overrides: Vec<ReadOnlyLedgerOverride>,
+    ) -> Result<(), ExecutionError> {
+        if !self.read_only && !ledger_overrides.is_empty() {
+            return Err(ExecutionError::RuntimeError(
+                "ledger overrides are only allowed in read-only executions".to_string(),
+            ));
+        }
+        for ledger_override in ledger_overrides {
+            self.speculative_ledger.apply_override(ledger_override);
+        }
+        Ok(())
+    }
+
     /// Sets a bytecode for an address in the speculative ledger.
     /// Fail if the address is absent from the ledger.
     ///
@@ -1042,3 +1197,28 @@ impl ExecutionContext {
             .get_address_deferred_credits(address, min_slot)
     }
 }
+
+/// Gas costs schedule of the latest version of the `GasCosts` MIP component active at `slot`,
+/// among the schedules of the configuration. The version 0 of the component uses the default gas costs.
+pub(crate) fn get_gas_costs_schedule(
+    config: &ExecutionConfig,
+    mip_store: &MipStore,
+    slot: Slot,
+) -> GasCostsSchedule {
+    let slot_timestamp = get_block_slot_timestamp(
+        config.thread_count,
+        config.t0,
+        config.genesis_timestamp,
+        slot,
+    )
+    .expect("could not compute current slot timestamp");
+    let version =
+        mip_store.get_latest_component_version_at(&MipComponent::GasCosts, slot_timestamp);
+    let (gas_costs, version) = config
+        .gas_costs_schedules
+        .range(..=version)
+        .next_back()
+        .map(|(_, schedule)| schedule.clone())
+        .unwrap_or_else(|| (config.gas_costs.clone(), config.gas_costs_version));
+    GasCostsSchedule { gas_costs, version }
+}
@@ -7,9 +7,11 @@ use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
-    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    EventProof, ExecutionAddressInfo, ExecutionConfig, ExecutionController, ExecutionError,
+    ExecutionManager, IndexedOperation, OperationTrace, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest,
 };
+use massa_ledger_exports::LedgerEntryProof;
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
@@ -19,7 +21,7 @@ use massa_models::{address::Address, amount::Amount, operation::OperationId};
 use massa_models::{block_id::BlockId, slot::Slot};
 use massa_storage::Storage;
 use parking_lot::{Condvar, Mutex, RwLock};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Display;
 use std::sync::Arc;
 use tracing::info;
@@ -139,6 +141,43 @@ impl ExecutionController for ExecutionControllerImpl {
             .get_filtered_sc_output_event(filter)
     }
 
+    /// Get the Merkle inclusion proof of an event emitted at a given slot
+    fn get_event_proof(&self, slot: Slot, index_in_slot: u64) -> Option<EventProof> {
+        self.execution_state
+            .read()
+            .get_event_proof(slot, index_in_slot)
+    }
+
+    /// Get the final ledger entry of an address with the proofs of its values
+    fn get_ledger_entry_proof(
+        &self,
+        address: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, ExecutionError> {
+        self.execution_state
+            .read()
+            .get_ledger_entry_proof(address, datastore_keys)
+    }
+
+    /// Get the final executed operations involving an address, most recent first
+    fn get_operations_by_address(
+        &self,
+        address: &Address,
+        cursor: Option<(Slot, OperationId)>,
+        limit: usize,
+    ) -> Option<Vec<IndexedOperation>> {
+        self.execution_state
+            .read()
+            .get_operations_by_address(address, cursor, limit)
+    }
+
+    /// Get the trace of the last execution of an operation
+    fn get_operation_trace(&self, operation_id: &OperationId) -> Option<Option<OperationTrace>> {
+        self.execution_state
+            .read()
+            .get_operation_trace(operation_id)
+    }
+
     /// Get the final and candidate values of balance.
     ///
     /// # Return value
@@ -171,6 +210,20 @@ impl ExecutionController for ExecutionControllerImpl {
         result
     }
 
+    /// Get the final and candidate datastore keys of an address starting with `prefix`
+    ///
+    /// # Return value
+    /// * `(final_keys, candidate_keys)`
+    fn get_final_and_candidate_datastore_keys(
+        &self,
+        addr: &Address,
+        prefix: &[u8],
+    ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>) {
+        self.execution_state
+            .read()
+            .get_final_and_candidate_datastore_keys(addr, prefix)
+    }
+
     /// Return the active rolls distribution for the given `cycle`
     fn get_cycle_active_rolls(&self, cycle: u64) -> BTreeMap<Address, u64> {
         self.execution_state.read().get_cycle_active_rolls(cycle)
@@ -229,14 +282,18 @@ impl ExecutionController for ExecutionControllerImpl {
         let exec_state = self.execution_state.read();
         for addr in addresses {
             let (final_datastore_keys, candidate_datastore_keys) =
-                exec_state.get_final_and_candidate_datastore_keys(addr);
+                exec_state.get_final_and_candidate_datastore_keys(addr, &[]);
             let (final_balance, candidate_balance) =
                 exec_state.get_final_and_candidate_balance(addr);
             let (final_roll_count, candidate_roll_count) =
                 exec_state.get_final_and_candidate_rolls(addr);
+            let (final_delegation, candidate_delegation) =
+                exec_state.get_final_and_candidate_delegation(addr);
             res.push(ExecutionAddressInfo {
                 final_datastore_keys,
                 candidate_datastore_keys,
+                final_delegation,
+                candidate_delegation,
                 final_balance: final_balance.unwrap_or_default(),
                 candidate_balance: candidate_balance.unwrap_or_default(),
                 final_roll_count,
@@ -0,0 +1,134 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Engine running the smart contract bytecodes.
+//!
+//! The execution runs the bytecode of the operations, asynchronous messages and read-only requests
+//! through an `ExecutionEngine`, so that the Wasmer-based runtime of `massa-sc-runtime`
+//! can be swapped or compared with another one.
+//! The engine is given the bytecode, and compiles and caches it as it sees fit:
+//! the `WasmerEngine` compiles it with Wasmer and keeps the compiled modules in the module cache of the execution.
+//! The nested calls are run by the engine of the top-level call, through the `Interface`.
+//!
+//! Other engines are plugged with `start_execution_worker_with_engine`, behind the `execution_engine` feature,
+//! and `tests::tests_differential` checks that two engines agree on the outcome of the same executions.
+
+use massa_execution_exports::ExecutionError;
+use massa_module_cache::{controller::ModuleCache, types::GasCostsSchedule};
+use massa_sc_runtime::{Interface, Response, VMError};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Failure of an engine
+#[derive(Debug)]
+pub enum EngineError {
+    /// the bytecode could not be compiled or loaded
+    Load(ExecutionError),
+    /// the execution of the bytecode failed
+    Run(VMError),
+}
+
+impl EngineError {
+    /// Execution error of a run described by `context`
+    pub(crate) fn into_execution_error(self, context: &str) -> ExecutionError {
+        match self {
+            EngineError::Load(error) => error,
+            EngineError::Run(error) => ExecutionError::VMError {
+                context: context.to_string(),
+                error,
+            },
+        }
+    }
+}
+
+/// Runs the smart contract bytecodes
+pub trait ExecutionEngine: Send + Sync {
+    /// Compiles a bytecode run once, within `compilation_limit` gas, and runs its main function with `max_gas`
+    fn run_main(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        compilation_limit: u64,
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError>;
+
+    /// Runs a function of the bytecode of a smart contract with a parameter
+    fn run_function(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        function: &str,
+        parameter: &[u8],
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError>;
+}
+
+/// Wasmer-based runtime of `massa-sc-runtime`, the engine of the node
+pub struct WasmerEngine {
+    /// cache of the compiled modules, shared with the execution context for the nested calls
+    module_cache: Arc<RwLock<ModuleCache>>,
+}
+
+impl WasmerEngine {
+    /// Creates an engine keeping the compiled modules in `module_cache`
+    pub fn new(module_cache: Arc<RwLock<ModuleCache>>) -> Self {
+        WasmerEngine { module_cache }
+    }
+}
+
+impl ExecutionEngine for WasmerEngine {
+    fn run_main(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        compilation_limit: u64,
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError> {
+        // the bytecode is run once: it is compiled apart from the cache
+        let module = self
+            .module_cache
+            .read()
+            .load_tmp_module(bytecode, schedule, compilation_limit)
+            .map_err(|err| EngineError::Load(err.into()))?;
+        massa_sc_runtime::run_main(interface, module, max_gas, schedule.gas_costs.clone())
+            .map_err(EngineError::Run)
+    }
+
+    fn run_function(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        function: &str,
+        parameter: &[u8],
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError> {
+        // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
+        let module = self
+            .module_cache
+            .write()
+            .load_module(bytecode, schedule, max_gas)
+            .map_err(|err| EngineError::Load(err.into()))?;
+        let response = massa_sc_runtime::run_function(
+            interface,
+            module,
+            function,
+            parameter,
+            max_gas,
+            schedule.gas_costs.clone(),
+        );
+        // cache the instance creation cost of the module, known once it ran
+        match response {
+            Ok(Response { init_gas_cost, .. })
+            | Err(VMError::ExecutionError { init_gas_cost, .. }) => {
+                self.module_cache
+                    .write()
+                    .set_init_cost(bytecode, schedule, init_gas_cost);
+            }
+            _ => (),
+        }
+        response.map_err(EngineError::Run)
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent store of the final execution events.
+//!
+//! When enabled, it replaces the in-memory store of the final events bounded by `max_final_events`:
+//! the events are kept on disk for `event_store_retention_periods` periods.
+//! The store is local to the node: it is neither part of the final state nor bootstrapped,
+//! so it only covers the slots finalized while the store was enabled.
+//!
+//! Events are stored under `EVENT_IDENT | slot | index in slot`, so that they are sorted by slot.
+//! They are indexed by emitter and original caller address under
+//! `EMITTER_IDENT | address length | address | slot | index in slot` and the same with `CALLER_IDENT`,
+//! so that the events of an address are contiguous and sorted by slot.
+
+use massa_execution_exports::EventStore;
+use massa_models::{
+    address::Address,
+    execution::EventFilter,
+    output_event::SCOutputEvent,
+    slot::{Slot, SLOT_KEY_SIZE},
+};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const EVENT_SER_ERROR: &str = "critical: event serialization failed";
+const EVENT_DESER_ERROR: &str = "critical: event deserialization failed";
+const EVENT_IDENT: u8 = 0u8;
+const EMITTER_IDENT: u8 = 1u8;
+const CALLER_IDENT: u8 = 2u8;
+
+pub(crate) struct EventDB {
+    /// RocksDB database
+    db: DB,
+    /// Number of periods the events are kept for, 0 to keep them forever
+    retention_periods: u64,
+}
+
+impl EventDB {
+    /// Open or create the event store stored at `path`
+    pub fn new(path: PathBuf, retention_periods: u64) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+            retention_periods,
+        }
+    }
+
+    /// Store the final events of a slot, and remove the events that are no longer retained
+    pub fn insert_slot_events(&self, slot: Slot, events: &EventStore) {
+        let mut batch = WriteBatch::default();
+        for event in events.0.iter() {
+            let location = event_location(event);
+            batch.put(
+                [&[EVENT_IDENT][..], &location].concat(),
+                serde_json::to_vec(event).expect(EVENT_SER_ERROR),
+            );
+            for key in index_keys(event, &location) {
+                batch.put(key, b"");
+            }
+        }
+        if self.retention_periods > 0 && slot.period > self.retention_periods {
+            self.prune(
+                &mut batch,
+                Slot::new(slot.period - self.retention_periods, 0),
+            );
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Remove the events of the slots before `first_kept_slot`, with their index entries
+    fn prune(&self, batch: &mut WriteBatch, first_kept_slot: Slot) {
+        let end_key = [&[EVENT_IDENT][..], &first_kept_slot.to_bytes_key()].concat();
+        for (key, value) in self
+            .db
+            .iterator(IteratorMode::From(&[EVENT_IDENT], Direction::Forward))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(key, _)| **key < *end_key)
+        {
+            let event: SCOutputEvent = serde_json::from_slice(&value).expect(EVENT_DESER_ERROR);
+            for index_key in index_keys(&event, &key[1..]) {
+                batch.delete(index_key);
+            }
+            batch.delete(key);
+        }
+    }
+
+    /// Get up to `limit` events matching `filter`, oldest first.
+    /// The emitter and caller indexes are used when the filter has an emitter or caller address.
+    pub fn get_filtered_events(&self, filter: &EventFilter, limit: usize) -> Vec<SCOutputEvent> {
+        let (prefix, indexed) = match (filter.emitter_address, filter.original_caller_address) {
+            (Some(address), _) => (index_prefix(EMITTER_IDENT, &address), true),
+            (None, Some(address)) => (index_prefix(CALLER_IDENT, &address), true),
+            (None, None) => (vec![EVENT_IDENT], false),
+        };
+        let start_key = match filter.start {
+            Some(start) => [&prefix[..], &start.to_bytes_key()].concat(),
+            None => prefix.clone(),
+        };
+        let end_key = filter
+            .end
+            .map(|end| [&prefix[..], &end.to_bytes_key()].concat());
+
+        self.db
+            .iterator(IteratorMode::From(&start_key, Direction::Forward))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(key, _)| {
+                key.starts_with(&prefix) && end_key.as_ref().map_or(true, |end| **key < **end)
+            })
+            .filter_map(|(key, value)| {
+                let event = if indexed {
+                    let event_key = [&[EVENT_IDENT][..], &key[prefix.len()..]].concat();
+                    self.db.get(event_key).expect(CRUD_ERROR)?
+                } else {
+                    value.into_vec()
+                };
+                Some(serde_json::from_slice::<SCOutputEvent>(&event).expect(EVENT_DESER_ERROR))
+            })
+            .filter(|event| filter.matches(event))
+            .take(limit)
+            .collect()
+    }
+}
+
+/// `slot | index in slot` of an event, sorted as the events
+fn event_location(event: &SCOutputEvent) -> Vec<u8> {
+    [
+        &event.context.slot.to_bytes_key()[..],
+        &event.context.index_in_slot.to_be_bytes(),
+    ]
+    .concat()
+}
+
+/// Prefix of the entries of an address in an index, with a length-prefixed address
+/// so that no address prefix matches another address
+fn index_prefix(ident: u8, address: &Address) -> Vec<u8> {
+    let bytes = address.to_prefixed_bytes();
+    [&[ident, bytes.len() as u8][..], &bytes].concat()
+}
+
+/// Keys of the index entries of an event at `location`
+fn index_keys(event: &SCOutputEvent, location: &[u8]) -> Vec<Vec<u8>> {
+    debug_assert_eq!(location.len(), SLOT_KEY_SIZE + 8);
+    let mut keys = Vec::new();
+    if let Some(emitter) = event.context.call_stack.front() {
+        keys.push([&index_prefix(EMITTER_IDENT, emitter)[..], location].concat());
+    }
+    if let Some(caller) = event.context.call_stack.back() {
+        keys.push([&index_prefix(CALLER_IDENT, caller)[..], location].concat());
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::output_event::EventExecutionContext;
+    use massa_signature::KeyPair;
+    use std::collections::VecDeque;
+    use tempfile::TempDir;
+
+    fn address() -> Address {
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    fn event(slot: Slot, index_in_slot: u64, call_stack: Vec<Address>) -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot,
+                block: None,
+                read_only: false,
+                index_in_slot,
+                call_stack: VecDeque::from(call_stack),
+                origin_operation_id: None,
+                is_final: true,
+                is_error: false,
+            },
+            data: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_event_db_queries_and_retention() {
+        let dir = TempDir::new().unwrap();
+        let db = EventDB::new(dir.path().to_path_buf(), 2);
+        let alice = address();
+        let bob = address();
+
+        db.insert_slot_events(
+            Slot::new(1, 0),
+            &EventStore(VecDeque::from(vec![
+                event(Slot::new(1, 0), 0, vec![alice, bob]),
+                event(Slot::new(1, 0), 1, vec![bob]),
+            ])),
+        );
+        db.insert_slot_events(
+            Slot::new(2, 1),
+            &EventStore(VecDeque::from(vec![event(Slot::new(2, 1), 0, vec![alice])])),
+        );
+
+        // all events, oldest first
+        let all = db.get_filtered_events(&EventFilter::default(), 10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].context.slot, Slot::new(2, 1));
+        assert_eq!(db.get_filtered_events(&EventFilter::default(), 2).len(), 2);
+
+        // by emitter and caller
+        let alice_emitted = EventFilter {
+            emitter_address: Some(alice),
+            ..Default::default()
+        };
+        assert_eq!(db.get_filtered_events(&alice_emitted, 10).len(), 2);
+        let bob_called = EventFilter {
+            original_caller_address: Some(bob),
+            ..Default::default()
+        };
+        assert_eq!(db.get_filtered_events(&bob_called, 10).len(), 2);
+
+        // by slot range
+        let second_slot = EventFilter {
+            emitter_address: Some(alice),
+            start: Some(Slot::new(1, 1)),
+            end: Some(Slot::new(3, 0)),
+            ..Default::default()
+        };
+        let events = db.get_filtered_events(&second_slot, 10);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].context.slot, Slot::new(2, 1));
+
+        // the events of period 1 are no longer retained at period 4
+        db.insert_slot_events(Slot::new(4, 0), &EventStore::default());
+        let all = db.get_filtered_events(&EventFilter::default(), 10);
+        assert_eq!(all.len(), 1);
+        assert!(db.get_filtered_events(&bob_called, 10).is_empty());
+    }
+}
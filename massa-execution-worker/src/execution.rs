@@ -10,18 +10,25 @@
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::engine::{ExecutionEngine, WasmerEngine};
+use crate::event_db::EventDB;
 use crate::interface_impl::InterfaceImpl;
+use crate::operation_index::OperationIndex;
+use crate::parallel::{operation_access_set, OperationBatch};
+use crate::replay::FinalSlotRecorder;
+use crate::state_changes_export::{FileSink, StateChangesSink};
 use crate::stats::ExecutionStatsCounter;
+use crate::trace::{CallTracer, OperationTraceStore};
 use crate::vesting_manager::VestingManager;
 use massa_async_pool::AsyncMessage;
 use massa_db::DBBatch;
 use massa_execution_exports::{
-    EventStore, ExecutionChannels, ExecutionConfig, ExecutionError, ExecutionOutput,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    EventProof, EventStore, ExecutionChannels, ExecutionConfig, ExecutionError, ExecutionOutput,
+    ExecutionStackElement, IndexedOperation, OperationTrace, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 use massa_final_state::FinalState;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_ledger_exports::{LedgerEntryProof, SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
@@ -39,7 +46,7 @@ use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
 use massa_pos_exports::SelectorController;
-use massa_sc_runtime::{Interface, Response, VMError};
+use massa_sc_runtime::Interface;
 use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 use parking_lot::{Mutex, RwLock};
@@ -71,12 +78,24 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // persistent store of the final events, replacing `final_events` if enabled
+    event_db: Option<EventDB>,
+    // persistent index of the final executed operations by address, if enabled
+    operation_index: Option<OperationIndex>,
+    // record of the final slots for their replay, if enabled
+    final_slot_recorder: Option<FinalSlotRecorder>,
+    // destinations of the state changes of the final slots, if their export is enabled
+    state_changes_sinks: Vec<Box<dyn StateChangesSink>>,
+    // traces of the last executed operations, if operation tracing is enabled
+    operation_traces: OperationTraceStore,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
     execution_context: Arc<Mutex<ExecutionContext>>,
     // execution interface allowing the VM runtime to access the Massa context
     execution_interface: Box<dyn Interface>,
+    // engine running the smart contract bytecodes
+    engine: Arc<dyn ExecutionEngine>,
     // execution statistics
     stats_counter: ExecutionStatsCounter,
     // cache of pre compiled sc modules
@@ -93,12 +112,25 @@ pub(crate) struct ExecutionState {
     massa_metrics: MassaMetrics,
 }
 
+/// Creates the cache of the compiled SC modules described by `config`
+pub(crate) fn new_module_cache(config: &ExecutionConfig) -> Arc<RwLock<ModuleCache>> {
+    Arc::new(RwLock::new(ModuleCache::new(ModuleCacheConfig {
+        hd_cache_path: config.hd_cache_path.clone(),
+        compilation_gas: config.max_gas_per_block,
+        lru_cache_size: config.lru_cache_size,
+        hd_cache_size: config.hd_cache_size,
+        hd_cache_max_byte_size: config.hd_cache_max_byte_size,
+        snip_amount: config.snip_amount,
+    })))
+}
+
 impl ExecutionState {
     /// Create a new execution state. This should be called only once at the start of the execution worker.
     ///
     /// # Arguments
     /// * `config`: execution configuration
     /// * `final_state`: atomic access to the final state
+    /// * `engine`: engine running the smart contract bytecodes, a `WasmerEngine` over the module cache of the execution if none
     ///
     /// # returns
     /// A new `ExecutionState`
@@ -109,6 +141,7 @@ impl ExecutionState {
         selector: Box<dyn SelectorController>,
         channels: ExecutionChannels,
         massa_metrics: MassaMetrics,
+        engine: Option<Arc<dyn ExecutionEngine>>,
     ) -> ExecutionState {
         // Get the slot at the output of which the final state is attached.
         // This should be among the latest final slots.
@@ -137,14 +170,8 @@ impl ExecutionState {
         };
 
         // Initialize the SC module cache
-        let module_cache = Arc::new(RwLock::new(ModuleCache::new(ModuleCacheConfig {
-            hd_cache_path: config.hd_cache_path.clone(),
-            gas_costs: config.gas_costs.clone(),
-            compilation_gas: config.max_gas_per_block,
-            lru_cache_size: config.lru_cache_size,
-            hd_cache_size: config.hd_cache_size,
-            snip_amount: config.snip_amount,
-        })));
+        let module_cache = new_module_cache(&config);
+        let engine = engine.unwrap_or_else(|| Arc::new(WasmerEngine::new(module_cache.clone())));
 
         // Create an empty placeholder execution context, with shared atomic access
         let execution_context = Arc::new(Mutex::new(ExecutionContext::new(
@@ -167,10 +194,30 @@ impl ExecutionState {
             final_state,
             execution_context,
             execution_interface,
+            engine,
             // empty execution output history: it is not recovered through bootstrap
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            event_db: config
+                .event_store_path
+                .clone()
+                .map(|path| EventDB::new(path, config.event_store_retention_periods)),
+            operation_index: config.operation_index_path.clone().map(OperationIndex::new),
+            final_slot_recorder: config
+                .final_slots_record_path
+                .clone()
+                .map(FinalSlotRecorder::new),
+            state_changes_sinks: config
+                .state_changes_export_path
+                .iter()
+                .map(|path| {
+                    Box::new(
+                        FileSink::new(path).expect("could not open the state changes export file"),
+                    ) as Box<dyn StateChangesSink>
+                })
+                .collect(),
+            operation_traces: OperationTraceStore::new(config.max_operation_traces),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -211,6 +258,28 @@ impl ExecutionState {
             );
         }
 
+        // export the state changes before they are consumed by the final state
+        let stake_delegation_version = if self.state_changes_sinks.is_empty() {
+            0
+        } else {
+            self.final_state
+                .read()
+                .get_stake_delegation_version(&exec_out.slot)
+        };
+        for sink in self.state_changes_sinks.iter_mut() {
+            if let Err(err) = sink.export(
+                &exec_out.slot,
+                &exec_out.block_id,
+                &exec_out.state_changes,
+                stake_delegation_version,
+            ) {
+                warn!(
+                    "failed to export the state changes of final slot {}: {}",
+                    exec_out.slot, err
+                );
+            }
+        }
+
         // apply state changes to the final ledger
         self.final_state
             .write()
@@ -227,8 +296,12 @@ impl ExecutionState {
 
         // append generated events to the final event store
         exec_out.events.finalize();
-        self.final_events.extend(exec_out.events);
-        self.final_events.prune(self.config.max_final_events);
+        if let Some(event_db) = &self.event_db {
+            event_db.insert_slot_events(exec_out.slot, &exec_out.events);
+        } else {
+            self.final_events.extend(exec_out.events);
+            self.final_events.prune(self.config.max_final_events);
+        }
 
         // update the prometheus metrics
         self.massa_metrics
@@ -270,11 +343,21 @@ impl ExecutionState {
         operation: &SecureShareOperation,
         sender_addr: Address,
     ) -> Result<ExecutionContextSnapshot, ExecutionError> {
-        let operation_id = operation.id;
-
         // lock execution context
         let mut context = context_guard!(self);
 
+        Self::prepare_operation_in_context(&self.config, &mut context, operation, sender_addr)
+    }
+
+    /// Same as `prepare_operation_for_execution`, in a given execution context
+    fn prepare_operation_in_context(
+        config: &ExecutionConfig,
+        context: &mut ExecutionContext,
+        operation: &SecureShareOperation,
+        sender_addr: Address,
+    ) -> Result<ExecutionContextSnapshot, ExecutionError> {
+        let operation_id = operation.id;
+
         // ignore the operation if it was already executed
         if context.is_op_executed(&operation_id) {
             return Err(ExecutionError::IncludeOperationError(
@@ -285,7 +368,7 @@ impl ExecutionState {
         // Set the creator coin spending allowance.
         // Note that this needs to be initialized before any spending from the creator.
         context.creator_coin_spending_allowance =
-            Some(operation.get_max_spending(self.config.roll_price));
+            Some(operation.get_max_spending(config.roll_price));
 
         // debit the fee from the operation sender
         // fail execution if there are not enough coins
@@ -312,6 +395,11 @@ impl ExecutionState {
         // set the context origin operation ID
         context.origin_operation_id = Some(operation_id);
 
+        // start tracing the execution if enabled
+        if config.max_operation_traces > 0 {
+            context.operation_tracer = Some(CallTracer::new(sender_addr));
+        }
+
         Ok(context_snapshot)
     }
 
@@ -330,38 +418,13 @@ impl ExecutionState {
         remaining_block_gas: &mut u64,
         block_credits: &mut Amount,
     ) -> Result<(), ExecutionError> {
-        // check validity period
-        if !(operation
-            .get_validity_range(self.config.operation_validity_period)
-            .contains(&block_slot.period))
-        {
-            return Err(ExecutionError::InvalidSlotRange);
-        }
-
-        // check remaining block gas
-        let op_gas = operation.get_gas_usage();
-        let new_remaining_block_gas = remaining_block_gas.checked_sub(op_gas).ok_or_else(|| {
-            ExecutionError::NotEnoughGas(
-                "not enough remaining block gas to execute operation".to_string(),
-            )
-        })?;
+        // check the inclusion of the operation in the block
+        let new_remaining_block_gas =
+            self.check_operation_inclusion(operation, block_slot, *remaining_block_gas)?;
 
         // get the operation's sender address
         let sender_addr = operation.content_creator_address;
 
-        // get the thread to which the operation belongs
-        let op_thread = sender_addr.get_thread(self.config.thread_count);
-
-        // check block/op thread compatibility
-        if op_thread != block_slot.thread {
-            return Err(ExecutionError::IncludeOperationError(
-                "operation vs block thread mismatch".to_string(),
-            ));
-        }
-
-        // get operation ID
-        let operation_id = operation.id;
-
         // Add fee from operation.
         let new_block_credits = block_credits.saturating_add(operation.content.fee);
 
@@ -390,39 +453,345 @@ impl ExecutionState {
             OperationType::Transaction { .. } => {
                 self.execute_transaction_op(&operation.content.op, sender_addr)
             }
+            OperationType::StakeDelegation { .. } => {
+                self.execute_stake_delegation_op(&operation.content.op, sender_addr)
+            }
         };
 
+        // lock execution context
+        let mut context = context_guard!(self);
+
+        Self::finalize_operation_in_context(
+            &mut context,
+            operation,
+            block_slot,
+            context_snapshot,
+            execution_result,
+        );
+
+        Ok(())
+    }
+
+    /// Executes the operations of a block in the order in which they appear in the block.
+    /// Errors are logged but do not interrupt the execution of the slot.
+    ///
+    /// When parallel execution is enabled, consecutive independent operations are executed in parallel
+    /// (see the `parallel` module), with the same result.
+    fn execute_block_operations(
+        &self,
+        operations: Vec<SecureShareOperation>,
+        block_slot: Slot,
+        block_id: &BlockId,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) {
+        let mut batch = OperationBatch::default();
+        for operation in operations {
+            let access_set = if self.config.parallel_execution_threads > 1 {
+                operation_access_set(&operation)
+            } else {
+                None
+            };
+            match access_set {
+                Some(addresses)
+                    if batch.accepts(
+                        &addresses,
+                        operation.get_gas_usage(),
+                        *remaining_block_gas,
+                    ) =>
+                {
+                    batch.push(operation, addresses);
+                }
+                Some(addresses) => {
+                    // conflict with the current batch: execute it and start a new one
+                    self.execute_operation_batch(
+                        std::mem::take(&mut batch),
+                        block_slot,
+                        block_id,
+                        remaining_block_gas,
+                        block_credits,
+                    );
+                    batch.push(operation, addresses);
+                }
+                None => {
+                    if !batch.is_empty() {
+                        self.execute_operation_batch(
+                            std::mem::take(&mut batch),
+                            block_slot,
+                            block_id,
+                            remaining_block_gas,
+                            block_credits,
+                        );
+                    }
+                    self.execute_block_operation(
+                        &operation,
+                        block_slot,
+                        block_id,
+                        remaining_block_gas,
+                        block_credits,
+                    );
+                }
+            }
+        }
+        if !batch.is_empty() {
+            self.execute_operation_batch(
+                batch,
+                block_slot,
+                block_id,
+                remaining_block_gas,
+                block_credits,
+            );
+        }
+    }
+
+    /// Executes an operation of a block with `execute_operation`, logging its failure
+    fn execute_block_operation(
+        &self,
+        operation: &SecureShareOperation,
+        block_slot: Slot,
+        block_id: &BlockId,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) {
+        if let Err(err) =
+            self.execute_operation(operation, block_slot, remaining_block_gas, block_credits)
         {
-            // lock execution context
-            let mut context = context_guard!(self);
+            debug!(
+                "failed executing operation {} in block {}: {}",
+                operation.id, block_id, err
+            );
+        }
+    }
 
-            // check execution results
-            match execution_result {
-                Ok(_) => context.insert_executed_op(
-                    operation_id,
-                    true,
-                    Slot::new(operation.content.expire_period, op_thread),
+    /// Executes the operations of a batch on forks of the execution context, in parallel,
+    /// then merges the forks in the order of the operations, as if they were executed by `execute_block_operation`
+    fn execute_operation_batch(
+        &self,
+        batch: OperationBatch,
+        block_slot: Slot,
+        block_id: &BlockId,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) {
+        if batch.operations.len() <= 1 {
+            for (operation, _) in &batch.operations {
+                self.execute_block_operation(
+                    operation,
+                    block_slot,
+                    block_id,
+                    remaining_block_gas,
+                    block_credits,
+                );
+            }
+            return;
+        }
+
+        // the operations of the batch fit in the remaining block gas together,
+        // so the inclusion checks do not depend on the execution of the previous operations
+        let mut operations = Vec::with_capacity(batch.operations.len());
+        for (operation, addresses) in batch.operations {
+            match self.check_operation_inclusion(&operation, block_slot, *remaining_block_gas) {
+                Ok(_) => operations.push((operation, addresses)),
+                Err(err) => debug!(
+                    "failed executing operation {} in block {}: {}",
+                    operation.id, block_id, err
                 ),
-                Err(err) => {
-                    // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
-                        "runtime error when executing operation {}: {}",
-                        operation_id, &err
-                    ));
-                    debug!("{}", &err);
-                    context.reset_to_snapshot(context_snapshot, err);
+            }
+        }
 
-                    // Insert op AFTER the context has been restore (else it would be overwritten)
-                    context.insert_executed_op(
-                        operation_id,
-                        false,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    )
+        // fork the execution context for each operation
+        let mut jobs: Vec<(&SecureShareOperation, ExecutionContext)> = {
+            let context = context_guard!(self);
+            operations
+                .iter()
+                .map(|(operation, addresses)| (operation, context.fork(addresses, &operation.id)))
+                .collect()
+        };
+
+        // execute the operations, split in consecutive chunks between the threads
+        let config = &self.config;
+        let chunk_size = (jobs.len() + config.parallel_execution_threads - 1)
+            / config.parallel_execution_threads;
+        let executed: Vec<(ExecutionContext, Result<(), ExecutionError>)> =
+            std::thread::scope(|scope| {
+                let mut handles = Vec::new();
+                while !jobs.is_empty() {
+                    let chunk: Vec<_> = jobs.drain(..chunk_size.min(jobs.len())).collect();
+                    handles.push(scope.spawn(move || {
+                        chunk
+                            .into_iter()
+                            .map(|(operation, fork)| {
+                                Self::execute_transaction_in_fork(
+                                    config, fork, operation, block_slot,
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    }));
+                }
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .expect("parallel execution of operations panicked")
+                    })
+                    .collect()
+            });
+
+        // merge the forks in the order of the operations
+        let mut context = context_guard!(self);
+        for ((operation, addresses), (fork, result)) in operations.iter().zip(executed) {
+            context.merge_fork(fork, addresses, &operation.id);
+            match result {
+                Ok(()) => {
+                    *remaining_block_gas =
+                        remaining_block_gas.saturating_sub(operation.get_gas_usage());
+                    *block_credits = block_credits.saturating_add(operation.content.fee);
                 }
+                Err(err) => debug!(
+                    "failed executing operation {} in block {}: {}",
+                    operation.id, block_id, err
+                ),
             }
         }
+    }
 
-        Ok(())
+    /// Executes a `Transaction` operation in a fork of the execution context, as `execute_operation` does
+    /// after the inclusion checks.
+    ///
+    /// # Returns
+    /// The fork, and an error if the operation could not be included (its fees could not be spent for example)
+    fn execute_transaction_in_fork(
+        config: &ExecutionConfig,
+        mut fork: ExecutionContext,
+        operation: &SecureShareOperation,
+        block_slot: Slot,
+    ) -> (ExecutionContext, Result<(), ExecutionError>) {
+        let sender_addr = operation.content_creator_address;
+        let result = Self::prepare_operation_in_context(config, &mut fork, operation, sender_addr)
+            .map(|context_snapshot| {
+                let execution_result = Self::execute_transaction_in_context(
+                    &mut fork,
+                    &operation.content.op,
+                    sender_addr,
+                );
+                Self::finalize_operation_in_context(
+                    &mut fork,
+                    operation,
+                    block_slot,
+                    context_snapshot,
+                    execution_result,
+                );
+            });
+        (fork, result)
+    }
+
+    /// Checks the validity period, the gas and the thread of an operation included in a block at `block_slot`
+    ///
+    /// # Returns
+    /// The remaining block gas after the execution of the operation
+    fn check_operation_inclusion(
+        &self,
+        operation: &SecureShareOperation,
+        block_slot: Slot,
+        remaining_block_gas: u64,
+    ) -> Result<u64, ExecutionError> {
+        // check validity period
+        if !(operation
+            .get_validity_range(self.config.operation_validity_period)
+            .contains(&block_slot.period))
+        {
+            return Err(ExecutionError::InvalidSlotRange);
+        }
+
+        // check remaining block gas
+        let op_gas = operation.get_gas_usage();
+        let new_remaining_block_gas = remaining_block_gas.checked_sub(op_gas).ok_or_else(|| {
+            ExecutionError::NotEnoughGas(
+                "not enough remaining block gas to execute operation".to_string(),
+            )
+        })?;
+
+        // check block/op thread compatibility
+        if operation
+            .content_creator_address
+            .get_thread(self.config.thread_count)
+            != block_slot.thread
+        {
+            return Err(ExecutionError::IncludeOperationError(
+                "operation vs block thread mismatch".to_string(),
+            ));
+        }
+
+        // check that the operation type is active at the block slot
+        let slot_timestamp = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            block_slot,
+        )
+        .map_err(ExecutionError::ModelsError)?;
+        if !self
+            .mip_store
+            .is_operation_type_active_at(&operation.content.op, slot_timestamp)
+        {
+            return Err(ExecutionError::IncludeOperationError(
+                "operation type not active at the block slot".to_string(),
+            ));
+        }
+
+        Ok(new_remaining_block_gas)
+    }
+
+    /// Keeps the trace of an executed operation, if traced, reverts its effects on failure
+    /// and marks it as executed in a given execution context
+    fn finalize_operation_in_context(
+        context: &mut ExecutionContext,
+        operation: &SecureShareOperation,
+        block_slot: Slot,
+        context_snapshot: ExecutionContextSnapshot,
+        execution_result: Result<(), ExecutionError>,
+    ) {
+        let operation_id = operation.id;
+        let op_thread = block_slot.thread;
+
+        // keep the trace of the execution, if traced
+        if let Some(tracer) = context.operation_tracer.take() {
+            let trace = OperationTrace {
+                operation_id,
+                slot: block_slot,
+                block_id: context.opt_block_id,
+                success: execution_result.is_ok(),
+                error: execution_result.as_ref().err().map(|err| err.to_string()),
+                root: tracer.finish(),
+            };
+            context.operation_traces.push(trace);
+        }
+
+        // check execution results
+        match execution_result {
+            Ok(_) => context.insert_executed_op(
+                operation_id,
+                true,
+                Slot::new(operation.content.expire_period, op_thread),
+            ),
+            Err(err) => {
+                // an error occurred: emit error event and reset context to snapshot
+                let err = ExecutionError::RuntimeError(format!(
+                    "runtime error when executing operation {}: {}",
+                    operation_id, &err
+                ));
+                debug!("{}", &err);
+                context.reset_to_snapshot(context_snapshot, err);
+
+                // Insert op AFTER the context has been restore (else it would be overwritten)
+                context.insert_executed_op(
+                    operation_id,
+                    false,
+                    Slot::new(operation.content.expire_period, op_thread),
+                )
+            }
+        }
     }
 
     /// Execute a denunciation in the context of a block.
@@ -498,16 +867,19 @@ impl ExecutionState {
         //       include them in executed denunciation and prevent (by occupying the corresponding entry)
         //       any further 'real' denunciation.
 
-        match &denunciation {
+        // The denounced address is the one which signed, the rolls slashed are the ones of the drawn owner
+        // (they differ when the owner delegated its stake to an operator)
+        let selection = self
+            .selector
+            .get_selection(*de_slot)
+            .expect("Could not get producer from selector");
+        let owner_denounced = match &denunciation {
             Denunciation::Endorsement(_de) => {
                 // Get selected address from selector and check
-                let selection = self
-                    .selector
-                    .get_selection(*de_slot)
-                    .expect("Could not get producer from selector");
+                let index = *denunciation.get_index().unwrap_or(&0) as usize;
                 let selected_addr = selection
                     .endorsements
-                    .get(*denunciation.get_index().unwrap_or(&0) as usize)
+                    .get(index)
                     .expect("could not get selection for endorsement at index");
 
                 if *selected_addr != addr_denounced {
@@ -516,26 +888,26 @@ impl ExecutionState {
                             .to_string(),
                     ));
                 }
+                *selection
+                    .endorsement_owners
+                    .get(index)
+                    .expect("could not get selection owner for endorsement at index")
             }
             Denunciation::BlockHeader(_de) => {
-                let selected_addr = self
-                    .selector
-                    .get_producer(*de_slot)
-                    .expect("Cannot get producer from selector");
-
-                if selected_addr != addr_denounced {
+                if selection.producer != addr_denounced {
                     return Err(ExecutionError::IncludeDenunciationError(
                         "Attempt to execute a denunciation but address was not selected"
                             .to_string(),
                     ));
                 }
+                selection.producer_owner
             }
-        }
+        };
 
         context.insert_executed_denunciation(&de_idx);
 
         let slashed = context.try_slash_rolls(
-            &addr_denounced,
+            &owner_denounced,
             self.config.roll_count_to_slash_on_denunciation,
         );
 
@@ -585,6 +957,7 @@ impl ExecutionState {
             coins: Amount::default(),
             owned_addresses: vec![seller_addr],
             operation_datastore: None,
+            reentrancy_locked: false,
         }];
 
         // try to sell the rolls
@@ -597,6 +970,66 @@ impl ExecutionState {
         Ok(())
     }
 
+    /// Execute an operation of type `StakeDelegation`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be a `StakeDelegation`
+    /// * `owner_addr`: address of the sender, owning the delegated rolls
+    pub fn execute_stake_delegation_op(
+        &self,
+        operation: &OperationType,
+        owner_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process stake delegation operations only
+        let operator = match operation {
+            OperationType::StakeDelegation { operator } => operator,
+            _ => panic!("unexpected operation type"),
+        };
+
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: owner_addr,
+            coins: Amount::default(),
+            owned_addresses: vec![owner_addr],
+            operation_datastore: None,
+            reentrancy_locked: false,
+        }];
+
+        match operator {
+            Some(operator_addr) => {
+                // the operator signs blocks and endorsements, it cannot be a smart contract
+                if !matches!(operator_addr, Address::User(..)) {
+                    return Err(ExecutionError::StakeDelegationError(format!(
+                        "{} cannot delegate its stake to smart contract {}",
+                        owner_addr, operator_addr
+                    )));
+                }
+                if *operator_addr == owner_addr {
+                    return Err(ExecutionError::StakeDelegationError(format!(
+                        "{} cannot delegate its stake to itself",
+                        owner_addr
+                    )));
+                }
+                context.set_delegation(&owner_addr, Some(*operator_addr));
+            }
+            None => {
+                if context.get_delegation(&owner_addr).is_none() {
+                    return Err(ExecutionError::StakeDelegationError(format!(
+                        "{} has no stake delegation to revoke",
+                        owner_addr
+                    )));
+                }
+                context.set_delegation(&owner_addr, None);
+            }
+        }
+        Ok(())
+    }
+
     /// Execute an operation of type `RollBuy`
     /// Will panic if called with another operation type
     ///
@@ -634,6 +1067,7 @@ impl ExecutionState {
             coins: Default::default(),
             owned_addresses: vec![buyer_addr],
             operation_datastore: None,
+            reentrancy_locked: false,
         }];
 
         // compute the amount of coins to spend
@@ -672,6 +1106,18 @@ impl ExecutionState {
         &self,
         operation: &OperationType,
         sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        Self::execute_transaction_in_context(&mut context, operation, sender_addr)
+    }
+
+    /// Same as `execute_transaction_op`, in a given execution context
+    fn execute_transaction_in_context(
+        context: &mut ExecutionContext,
+        operation: &OperationType,
+        sender_addr: Address,
     ) -> Result<(), ExecutionError> {
         // process transaction operations only
         let (recipient_address, amount) = match operation {
@@ -682,9 +1128,6 @@ impl ExecutionState {
             _ => panic!("unexpected operation type"),
         };
 
-        // acquire write access to the context
-        let mut context = context_guard!(self);
-
         // Set call stack
         // This needs to be defined before anything can fail, so that the emitted event contains the right stack
         context.stack = vec![ExecutionStackElement {
@@ -692,6 +1135,7 @@ impl ExecutionState {
             coins: *amount,
             owned_addresses: vec![sender_addr],
             operation_datastore: None,
+            reentrancy_locked: false,
         }];
 
         // send `roll_price` * `roll_count` coins from the sender to the recipient
@@ -742,31 +1186,36 @@ impl ExecutionState {
                 coins: Amount::zero(),
                 owned_addresses: vec![sender_addr],
                 operation_datastore: Some(datastore.clone()),
+                reentrancy_locked: false,
             }];
+            if let Some(tracer) = context.operation_tracer.as_mut() {
+                tracer.current_call().function = Some("main".to_string());
+            }
         };
 
-        // load the tmp module
-        let module = self
-            .module_cache
-            .read()
-            .load_tmp_module(bytecode, *max_gas)?;
-        // sub tmp module compilation cost
+        // the compilation of the bytecode is paid from the gas of the operation
+        let schedule = context_guard!(self).gas_costs_schedule.clone();
         let remaining_gas = max_gas
-            .checked_sub(self.config.gas_costs.sp_compilation_cost)
+            .checked_sub(schedule.gas_costs.sp_compilation_cost)
             .ok_or(ExecutionError::RuntimeError(
                 "not enough gas to pay for singlepass compilation".to_string(),
             ))?;
         // run the VM
-        massa_sc_runtime::run_main(
-            &*self.execution_interface,
-            module,
-            remaining_gas,
-            self.config.gas_costs.clone(),
-        )
-        .map_err(|error| ExecutionError::VMError {
-            context: "ExecuteSC".to_string(),
-            error,
-        })?;
+        let response = self
+            .engine
+            .run_main(
+                &*self.execution_interface,
+                bytecode,
+                *max_gas,
+                remaining_gas,
+                &schedule,
+            )
+            .map_err(|err| err.into_execution_error("ExecuteSC"))?;
+
+        // trace the gas used, including the compilation
+        if let Some(tracer) = context_guard!(self).operation_tracer.as_mut() {
+            tracer.current_call().gas_used = Some(max_gas.saturating_sub(response.remaining_gas));
+        }
 
         Ok(())
     }
@@ -811,12 +1260,14 @@ impl ExecutionState {
                     coins: Default::default(),
                     owned_addresses: vec![sender_addr],
                     operation_datastore: None,
+                    reentrancy_locked: false,
                 },
                 ExecutionStackElement {
                     address: target_addr,
                     coins,
                     owned_addresses: vec![target_addr],
                     operation_datastore: None,
+                    reentrancy_locked: false,
                 },
             ];
 
@@ -841,34 +1292,33 @@ impl ExecutionState {
                 return Ok(());
             }
 
+            if let Some(tracer) = context.operation_tracer.as_mut() {
+                tracer.enter_call(target_addr, Some(target_func.clone()), coins);
+            }
+
             // Load bytecode. Assume empty bytecode if not found.
             bytecode = context.get_bytecode(&target_addr).unwrap_or_default().0;
         }
 
-        // load and execute the compiled module
+        // execute the bytecode
         // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-        let module = self.module_cache.write().load_module(&bytecode, max_gas)?;
-        let response = massa_sc_runtime::run_function(
-            &*self.execution_interface,
-            module,
-            target_func,
-            param,
-            max_gas,
-            self.config.gas_costs.clone(),
-        );
-        match response {
-            Ok(Response { init_gas_cost, .. })
-            | Err(VMError::ExecutionError { init_gas_cost, .. }) => {
-                self.module_cache
-                    .write()
-                    .set_init_cost(&bytecode, init_gas_cost);
-            }
-            _ => (),
+        let schedule = context_guard!(self).gas_costs_schedule.clone();
+        let response = self
+            .engine
+            .run_function(
+                &*self.execution_interface,
+                &bytecode,
+                target_func,
+                param,
+                max_gas,
+                &schedule,
+            )
+            .map_err(|err| err.into_execution_error("CallSC"))?;
+
+        // trace the gas used by the called function
+        if let Some(tracer) = context_guard!(self).operation_tracer.as_mut() {
+            tracer.current_call().gas_used = Some(max_gas.saturating_sub(response.remaining_gas));
         }
-        response.map_err(|error| ExecutionError::VMError {
-            context: "CallSC".to_string(),
-            error,
-        })?;
         Ok(())
     }
 
@@ -897,12 +1347,14 @@ impl ExecutionState {
                     coins: message.coins,
                     owned_addresses: vec![message.sender],
                     operation_datastore: None,
+                    reentrancy_locked: false,
                 },
                 ExecutionStackElement {
                     address: message.destination,
                     coins: message.coins,
                     owned_addresses: vec![message.destination],
                     operation_datastore: None,
+                    reentrancy_locked: false,
                 },
             ];
 
@@ -941,38 +1393,22 @@ impl ExecutionState {
             bytecode.0
         };
 
-        // load and execute the compiled module
+        // execute the bytecode
         // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-        let module = self
-            .module_cache
-            .write()
-            .load_module(&bytecode, message.max_gas)?;
-        let response = massa_sc_runtime::run_function(
+        let schedule = context_guard!(self).gas_costs_schedule.clone();
+        let response = self.engine.run_function(
             &*self.execution_interface,
-            module,
+            &bytecode,
             &message.handler,
             &message.data,
             message.max_gas,
-            self.config.gas_costs.clone(),
+            &schedule,
         );
         match response {
-            Ok(Response { init_gas_cost, .. }) => {
-                self.module_cache
-                    .write()
-                    .set_init_cost(&bytecode, init_gas_cost);
-                Ok(())
-            }
+            Ok(_) => Ok(()),
             Err(error) => {
-                if let VMError::ExecutionError { init_gas_cost, .. } = error {
-                    self.module_cache
-                        .write()
-                        .set_init_cost(&bytecode, init_gas_cost);
-                }
                 // execution failed: reset context to snapshot and reimburse sender
-                let err = ExecutionError::VMError {
-                    context: "Asynchronous Message".to_string(),
-                    error,
-                };
+                let err = error.into_execution_error("Asynchronous Message");
                 let mut context = context_guard!(self);
                 context.reset_to_snapshot(context_snapshot, err.clone());
                 context.cancel_async_message(&message);
@@ -1050,6 +1486,12 @@ impl ExecutionState {
 
             debug!("executing {} operations at slot {}", operations.len(), slot);
 
+            // the rewards go to the owners of the drawn rolls,
+            // who may have delegated the creation of their blocks and endorsements to an operator
+            let selection = selector
+                .get_selection(*slot)
+                .expect("couldn't get the selection of an executed block");
+
             // gather all available endorsement creators and target blocks
             let (endorsement_creators, endorsement_targets): &(Vec<Address>, Vec<BlockId>) =
                 &stored_block
@@ -1058,7 +1500,15 @@ impl ExecutionState {
                     .content
                     .endorsements
                     .iter()
-                    .map(|endo| (endo.content_creator_address, endo.content.endorsed_block))
+                    .map(|endo| {
+                        (
+                            selection.get_endorsement_owner(
+                                endo.content.index as usize,
+                                &endo.content_creator_address,
+                            ),
+                            endo.content.endorsed_block,
+                        )
+                    })
                     .unzip();
 
             // deduce endorsement target block creators
@@ -1067,10 +1517,13 @@ impl ExecutionState {
                 endorsement_targets
                     .iter()
                     .map(|b_id| {
-                        blocks
+                        let endorsed_block = blocks
                             .get(b_id)
-                            .expect("endorsed block absent from storage")
-                            .content_creator_address
+                            .expect("endorsed block absent from storage");
+                        selector
+                            .get_selection(endorsed_block.content.header.content.slot)
+                            .expect("couldn't get the selection of an endorsed block")
+                            .get_producer_owner(&endorsed_block.content_creator_address)
                     })
                     .collect::<Vec<_>>()
             };
@@ -1083,19 +1536,13 @@ impl ExecutionState {
 
             // Try executing the operations of this block in the order in which they appear in the block.
             // Errors are logged but do not interrupt the execution of the slot.
-            for operation in operations.into_iter() {
-                if let Err(err) = self.execute_operation(
-                    &operation,
-                    stored_block.content.header.content.slot,
-                    &mut remaining_block_gas,
-                    &mut block_credits,
-                ) {
-                    debug!(
-                        "failed executing operation {} in block {}: {}",
-                        operation.id, block_id, err
-                    );
-                }
-            }
+            self.execute_block_operations(
+                operations,
+                stored_block.content.header.content.slot,
+                block_id,
+                &mut remaining_block_gas,
+                &mut block_credits,
+            );
 
             // Try executing the denunciations of this block
             for denunciation in &stored_block.content.header.content.denunciations {
@@ -1112,7 +1559,8 @@ impl ExecutionState {
             }
 
             // Get block creator address
-            let block_creator_addr = stored_block.content_creator_address;
+            let block_creator_addr =
+                selection.get_producer_owner(&stored_block.content_creator_address);
 
             // acquire lock on execution context
             let mut context = context_guard!(self);
@@ -1178,8 +1626,9 @@ impl ExecutionState {
         } else {
             // the slot is a miss, check who was supposed to be the creator and update production stats
             let producer_addr = selector
-                .get_producer(*slot)
-                .expect("couldn't get the expected block producer for a missed slot");
+                .get_selection(*slot)
+                .expect("couldn't get the expected block producer for a missed slot")
+                .producer_owner;
             context_guard!(self).update_production_stats(&producer_addr, *slot, None);
         }
 
@@ -1240,6 +1689,7 @@ impl ExecutionState {
                 .expect("overflow when iterating on slots");
         }
         let exec_out = self.execute_slot(slot, exec_target, selector);
+        self.store_operation_traces();
 
         // apply execution output to active state
         self.apply_active_execution_output(exec_out);
@@ -1274,7 +1724,9 @@ impl ExecutionState {
                 // speculative execution front result matches what we want to compute
 
                 // apply the cached output and return
+                self.index_final_operations(exec_target, &exec_out);
                 self.apply_final_execution_output(exec_out.clone());
+                self.record_final_slot(slot, exec_target);
 
                 // update versioning stats
                 self.update_versioning_stats(exec_target, slot);
@@ -1317,9 +1769,12 @@ impl ExecutionState {
         // execute slot
         debug!("execute_final_slot: execution started");
         let exec_out = self.execute_slot(slot, exec_target, selector);
+        self.store_operation_traces();
 
         // apply execution output to final state
+        self.index_final_operations(exec_target, &exec_out);
         self.apply_final_execution_output(exec_out.clone());
+        self.record_final_slot(slot, exec_target);
 
         self.update_versioning_stats(exec_target, slot);
         debug!(
@@ -1379,8 +1834,17 @@ impl ExecutionState {
                 .expect("slot overflow in readonly execution from active slot")
         };
 
+        // the caller is the oldest element of the call stack,
+        // the coins attached to the call are the ones of the newest element
+        let caller_addr = req.call_stack.first().map(|element| element.address);
+        let call_coins = req
+            .call_stack
+            .last()
+            .map(|element| element.coins)
+            .unwrap_or_default();
+
         // create a readonly execution context
-        let execution_context = ExecutionContext::readonly(
+        let mut execution_context = ExecutionContext::readonly(
             self.config.clone(),
             slot,
             req.max_gas,
@@ -1392,34 +1856,53 @@ impl ExecutionState {
             self.mip_store.clone(),
         );
 
+        // simulate the requested ledger state
+        execution_context.apply_ledger_overrides(req.ledger_overrides)?;
+
+        // debit the fee from the caller, as for an operation
+        if !req.fee.is_zero() {
+            execution_context
+                .transfer_coins(caller_addr, None, req.fee, false)
+                .map_err(|err| {
+                    ExecutionError::RuntimeError(format!("could not spend fees: {}", err))
+                })?;
+        }
+
         // run the interpreter according to the target type
         let exec_response = match req.target {
             ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
                 // set the execution context
                 *context_guard!(self) = execution_context;
 
-                // load the tmp module
-                let module = self
-                    .module_cache
-                    .read()
-                    .load_tmp_module(&bytecode, req.max_gas)?;
                 // run the VM
-                massa_sc_runtime::run_main(
-                    &*self.execution_interface,
-                    module,
-                    req.max_gas,
-                    self.config.gas_costs.clone(),
-                )
-                .map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::BytecodeExecution".to_string(),
-                    error,
-                })?
+                let schedule = context_guard!(self).gas_costs_schedule.clone();
+                self.engine
+                    .run_main(
+                        &*self.execution_interface,
+                        &bytecode,
+                        req.max_gas,
+                        req.max_gas,
+                        &schedule,
+                    )
+                    .map_err(|err| {
+                        err.into_execution_error("ReadOnlyExecutionTarget::BytecodeExecution")
+                    })?
             }
             ReadOnlyExecutionTarget::FunctionCall {
                 target_addr,
                 target_func,
                 parameter,
             } => {
+                // transfer the attached coins from the caller to the target
+                if !call_coins.is_zero() {
+                    execution_context.transfer_coins(
+                        caller_addr,
+                        Some(target_addr),
+                        call_coins,
+                        false,
+                    )?;
+                }
+
                 // get the bytecode, default to an empty vector
                 let bytecode = execution_context
                     .get_bytecode(&target_addr)
@@ -1429,33 +1912,21 @@ impl ExecutionState {
                 // set the execution context
                 *context_guard!(self) = execution_context;
 
-                // load and execute the compiled module
+                // execute the bytecode
                 // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-                let module = self
-                    .module_cache
-                    .write()
-                    .load_module(&bytecode, req.max_gas)?;
-                let response = massa_sc_runtime::run_function(
-                    &*self.execution_interface,
-                    module,
-                    &target_func,
-                    &parameter,
-                    req.max_gas,
-                    self.config.gas_costs.clone(),
-                );
-                match response {
-                    Ok(Response { init_gas_cost, .. })
-                    | Err(VMError::ExecutionError { init_gas_cost, .. }) => {
-                        self.module_cache
-                            .write()
-                            .set_init_cost(&bytecode, init_gas_cost);
-                    }
-                    _ => (),
-                }
-                response.map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::FunctionCall".to_string(),
-                    error,
-                })?
+                let schedule = context_guard!(self).gas_costs_schedule.clone();
+                self.engine
+                    .run_function(
+                        &*self.execution_interface,
+                        &bytecode,
+                        &target_func,
+                        &parameter,
+                        req.max_gas,
+                        &schedule,
+                    )
+                    .map_err(|err| {
+                        err.into_execution_error("ReadOnlyExecutionTarget::FunctionCall")
+                    })?
             }
         };
 
@@ -1496,6 +1967,24 @@ impl ExecutionState {
         (final_rolls, active_rolls)
     }
 
+    /// Gets the operator an address delegated its stake to, both at the latest final and candidate executed slots
+    pub fn get_final_and_candidate_delegation(
+        &self,
+        address: &Address,
+    ) -> (Option<Address>, Option<Address>) {
+        let final_delegation = self
+            .final_state
+            .read()
+            .pos_state
+            .get_delegation_for(address);
+        let active_delegation = self
+            .active_history
+            .read()
+            .fetch_delegation(address)
+            .unwrap_or(final_delegation);
+        (final_delegation, active_delegation)
+    }
+
     /// Gets a data entry both at the latest final and active executed slots
     pub fn get_final_and_active_data_entry(
         &self,
@@ -1517,18 +2006,18 @@ impl ExecutionState {
         )
     }
 
-    /// Get every final and active datastore key of the given address
+    /// Get every final and active datastore key of the given address starting with `prefix`
     pub fn get_final_and_candidate_datastore_keys(
         &self,
         addr: &Address,
+        prefix: &[u8],
     ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>) {
         // here, get the final keys from the final ledger, and make a copy of it for the candidate list
-        // let final_keys = final_state.read().ledger.get_datastore_keys(addr);
         let final_keys = self
             .final_state
             .read()
             .ledger
-            .get_datastore_keys(addr)
+            .get_datastore_keys(addr, prefix)
             .unwrap_or_default();
         let mut candidate_keys = final_keys.clone();
 
@@ -1540,12 +2029,21 @@ impl ExecutionState {
 
                 // address ledger entry being reset to an absolute new list of keys
                 Some(SetUpdateOrDelete::Set(new_ledger_entry)) => {
-                    candidate_keys = new_ledger_entry.datastore.keys().cloned().collect();
+                    candidate_keys = new_ledger_entry
+                        .datastore
+                        .keys()
+                        .filter(|key| key.starts_with(prefix))
+                        .cloned()
+                        .collect();
                 }
 
                 // address ledger entry being updated
                 Some(SetUpdateOrDelete::Update(entry_updates)) => {
-                    for (ds_key, ds_update) in &entry_updates.datastore {
+                    for (ds_key, ds_update) in entry_updates
+                        .datastore
+                        .iter()
+                        .filter(|(key, _)| key.starts_with(prefix))
+                    {
                         match ds_update {
                             SetOrDelete::Set(_) => candidate_keys.insert(ds_key.clone()),
                             SetOrDelete::Delete => candidate_keys.remove(ds_key),
@@ -1588,11 +2086,7 @@ impl ExecutionState {
     /// * event state (final, candidate or both)
     pub fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent> {
         match filter.is_final {
-            Some(true) => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
-                .into_iter()
-                .collect(),
+            Some(true) => self.get_filtered_final_events(&filter),
             Some(false) => self
                 .active_history
                 .read()
@@ -1601,8 +2095,7 @@ impl ExecutionState {
                 .flat_map(|item| item.events.get_filtered_sc_output_events(&filter))
                 .collect(),
             None => self
-                .final_events
-                .get_filtered_sc_output_events(&filter)
+                .get_filtered_final_events(&filter)
                 .into_iter()
                 .chain(
                     self.active_history
@@ -1615,6 +2108,97 @@ impl ExecutionState {
         }
     }
 
+    /// Gets the final execution events matching a filter, from the event store if enabled.
+    /// At most `max_final_events` events are returned from the event store.
+    fn get_filtered_final_events(&self, filter: &EventFilter) -> Vec<SCOutputEvent> {
+        match &self.event_db {
+            Some(event_db) => event_db.get_filtered_events(filter, self.config.max_final_events),
+            None => self
+                .final_events
+                .get_filtered_sc_output_events(filter)
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Get the final balance, bytecode and given datastore entries of an address,
+    /// with the proof of each value against the final state hash
+    pub fn get_ledger_entry_proof(
+        &self,
+        address: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, ExecutionError> {
+        self.final_state
+            .read()
+            .ledger
+            .get_entry_proof(address, datastore_keys)
+            .map_err(|e| ExecutionError::LedgerError(e.to_string()))
+    }
+
+    /// Get the Merkle inclusion proof of the event emitted at `index_in_slot` during the execution of `slot`
+    pub fn get_event_proof(&self, slot: Slot, index_in_slot: u64) -> Option<EventProof> {
+        // candidate outputs hold the complete list of events of their slot
+        if let Some(output) = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .find(|output| output.slot == slot)
+        {
+            return Some(EventProof {
+                event: output.events.0.get(index_in_slot as usize)?.clone(),
+                events_merkle_root: output.events_merkle_root,
+                proof: output.events.compute_event_proof(index_in_slot)?,
+            });
+        }
+
+        // final events are only available as long as they are not pruned
+        let filter = EventFilter {
+            start: Some(slot),
+            end: slot.get_next_slot(self.config.thread_count).ok(),
+            ..Default::default()
+        };
+        let slot_events = EventStore(self.get_filtered_final_events(&filter).into());
+        Some(EventProof {
+            event: slot_events.0.get(index_in_slot as usize)?.clone(),
+            events_merkle_root: slot_events.compute_merkle_root(),
+            proof: slot_events.compute_event_proof(index_in_slot)?,
+        })
+    }
+
+    /// Get up to `limit` final executed operations involving `address` from the operation index,
+    /// most recent first, before `cursor` if given. Returns `None` if the index is disabled.
+    pub fn get_operations_by_address(
+        &self,
+        address: &Address,
+        cursor: Option<(Slot, OperationId)>,
+        limit: usize,
+    ) -> Option<Vec<IndexedOperation>> {
+        self.operation_index
+            .as_ref()
+            .map(|index| index.get_operations(address, cursor, limit))
+    }
+
+    /// Moves the traces of the operations executed at the last executed slot to the trace store
+    fn store_operation_traces(&mut self) {
+        let traces = std::mem::take(&mut context_guard!(self).operation_traces);
+        for trace in traces {
+            self.operation_traces.insert(trace);
+        }
+    }
+
+    /// Get the trace of the last execution of an operation, if it was traced and not forgotten yet.
+    /// Returns `None` if operation tracing is disabled.
+    pub fn get_operation_trace(
+        &self,
+        operation_id: &OperationId,
+    ) -> Option<Option<OperationTrace>> {
+        if self.config.max_operation_traces == 0 {
+            return None;
+        }
+        Some(self.operation_traces.get(operation_id).cloned())
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     pub fn is_denunciation_executed(&self, denunciation_index: &DenunciationIndex) -> bool {
         // check active history
@@ -1670,6 +2254,45 @@ impl ExecutionState {
             .collect()
     }
 
+    /// Record a final slot with the hash of the final state after it, if the record of the final slots is enabled
+    fn record_final_slot(&self, slot: &Slot, exec_target: Option<&(BlockId, Storage)>) {
+        if let Some(recorder) = &self.final_slot_recorder {
+            let state_hash = self.final_state.read().db.read().get_db_hash();
+            recorder.record(slot, exec_target, state_hash);
+        }
+    }
+
+    /// Index the operations executed by a final block under their involved addresses,
+    /// if the operation index is enabled
+    fn index_final_operations(
+        &self,
+        exec_target: Option<&(BlockId, Storage)>,
+        exec_out: &ExecutionOutput,
+    ) {
+        let (Some(index), Some((block_id, block_storage))) = (&self.operation_index, exec_target) else {
+            return;
+        };
+        let operations = {
+            let blocks = block_storage.read_blocks();
+            let ops = block_storage.read_operations();
+            let Some(block) = blocks.get(block_id) else {
+                return;
+            };
+            block
+                .content
+                .operations
+                .iter()
+                .filter_map(|op_id| {
+                    // operations that were not executed (e.g. expired or reused) are not indexed
+                    let (success, _) = exec_out.state_changes.executed_ops_changes.get(op_id)?;
+                    let op = ops.get(op_id)?;
+                    Some((*op_id, op.get_ledger_involved_addresses(), *success))
+                })
+                .collect()
+        };
+        index.insert_block(exec_out.slot, *block_id, operations);
+    }
+
     /// Update MipStore with block header stats
     pub fn update_versioning_stats(
         &mut self,
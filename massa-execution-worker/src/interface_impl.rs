@@ -58,6 +58,25 @@ impl InterfaceImpl {
         InterfaceImpl { config, context }
     }
 
+    /// Takes the reentrancy lock of the current address (top of the stack).
+    /// Until the current call returns, any call to this address is rejected.
+    ///
+    /// The smart contracts cannot take it yet: the pinned massa-sc-runtime does not declare
+    /// the matching host function in its `Interface`.
+    pub fn reentrancy_lock(&self) -> Result<()> {
+        let mut context = context_guard!(self);
+
+        if !context.call_guards_active() {
+            bail!("the reentrancy lock is not available before the activation of the VM version 1")
+        }
+        match context.stack.last_mut() {
+            Some(element) => element.reentrancy_locked = true,
+            None => bail!("failed to read call stack current address"),
+        }
+
+        Ok(())
+    }
+
     #[cfg(any(
         feature = "gas_calibration",
         feature = "benchmarking",
@@ -68,12 +87,34 @@ impl InterfaceImpl {
         sender_addr: Address,
         operation_datastore: Option<Datastore>,
     ) -> InterfaceImpl {
-        use massa_ledger_exports::{LedgerEntry, SetUpdateOrDelete};
         use massa_models::config::{
             MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX,
         };
-        use massa_module_cache::{config::ModuleCacheConfig, controller::ModuleCache};
         use massa_versioning::versioning::{MipStatsConfig, MipStore};
+
+        // create an empty default store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+        };
+        let mip_store =
+            MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
+        InterfaceImpl::new_default_with_mip_store(sender_addr, operation_datastore, mip_store)
+    }
+
+    #[cfg(any(
+        feature = "gas_calibration",
+        feature = "benchmarking",
+        feature = "testing"
+    ))]
+    /// Same as `new_default`, with the versions of `mip_store`
+    pub fn new_default_with_mip_store(
+        sender_addr: Address,
+        operation_datastore: Option<Datastore>,
+        mip_store: massa_versioning::versioning::MipStore,
+    ) -> InterfaceImpl {
+        use massa_ledger_exports::{LedgerEntry, SetUpdateOrDelete};
+        use massa_module_cache::{config::ModuleCacheConfig, controller::ModuleCache};
         use parking_lot::RwLock;
 
         let vesting_file = super::tests::get_initials_vesting(false);
@@ -81,10 +122,10 @@ impl InterfaceImpl {
         let (final_state, _tempfile, _tempdir) = super::tests::get_sample_state(0).unwrap();
         let module_cache = Arc::new(RwLock::new(ModuleCache::new(ModuleCacheConfig {
             hd_cache_path: config.hd_cache_path.clone(),
-            gas_costs: config.gas_costs.clone(),
             compilation_gas: config.max_gas_per_block,
             lru_cache_size: config.lru_cache_size,
             hd_cache_size: config.hd_cache_size,
+            hd_cache_max_byte_size: config.hd_cache_max_byte_size,
             snip_amount: config.snip_amount,
         })));
         let vesting_manager = Arc::new(
@@ -99,14 +140,6 @@ impl InterfaceImpl {
             .unwrap(),
         );
 
-        // create an empty default store
-        let mip_stats_config = MipStatsConfig {
-            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-            counters_max: MIP_STORE_STATS_COUNTERS_MAX,
-        };
-        let mip_store =
-            MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
-
         let mut execution_context = ExecutionContext::new(
             config.clone(),
             final_state,
@@ -120,6 +153,7 @@ impl InterfaceImpl {
             coins: Amount::zero(),
             owned_addresses: vec![sender_addr],
             operation_datastore,
+            reentrancy_locked: false,
         }];
         execution_context.speculative_ledger.added_changes.0.insert(
             sender_addr,
@@ -185,6 +219,27 @@ impl Interface for InterfaceImpl {
             _ => bail!("failed to read call stack current address"),
         };
 
+        if context.call_guards_active() {
+            // the bottom of the stack is the caller of the first contract, it does not count as a call
+            if context.stack.len() > self.config.max_call_depth as usize {
+                bail!(
+                    "call to {} rejected: the maximum call depth of {} is reached",
+                    to_address,
+                    self.config.max_call_depth
+                );
+            }
+            if context
+                .stack
+                .iter()
+                .any(|element| element.address == to_address && element.reentrancy_locked)
+            {
+                bail!(
+                    "reentrant call to {} rejected: the contract holds its reentrancy lock",
+                    to_address
+                );
+            }
+        }
+
         // transfer coins from caller to target address
         let coins = Amount::from_raw(raw_coins);
         if let Err(err) = context.transfer_coins(Some(from_address), Some(to_address), coins, true)
@@ -204,7 +259,11 @@ impl Interface for InterfaceImpl {
             coins,
             owned_addresses: vec![to_address],
             operation_datastore: None,
+            reentrancy_locked: false,
         });
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.enter_call(to_address, None, coins);
+        }
 
         // return the target bytecode
         Ok(bytecode.0)
@@ -218,6 +277,9 @@ impl Interface for InterfaceImpl {
         if context.stack.pop().is_none() {
             bail!("call stack out of bounds")
         }
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.exit_call();
+        }
 
         Ok(())
     }
@@ -228,7 +290,11 @@ impl Interface for InterfaceImpl {
     /// A `massa-sc-runtime` compiled module
     fn get_module(&self, bytecode: &[u8], limit: u64) -> Result<RuntimeModule> {
         let context = context_guard!(self);
-        let module = context.module_cache.write().load_module(bytecode, limit)?;
+        let module = context.module_cache.write().load_module(
+            bytecode,
+            &context.gas_costs_schedule,
+            limit,
+        )?;
         Ok(module)
     }
 
@@ -318,8 +384,12 @@ impl Interface for InterfaceImpl {
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> Result<Vec<u8>> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        let context = context_guard!(self);
-        match context.get_data_entry(addr, key) {
+        let mut context = context_guard!(self);
+        let value = context.get_data_entry(addr, key);
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_read(*addr, key, value.clone());
+        }
+        match value {
             Some(value) => Ok(value),
             _ => bail!("data entry not found"),
         }
@@ -337,6 +407,9 @@ impl Interface for InterfaceImpl {
         let addr = massa_models::address::Address::from_str(address)?;
         let mut context = context_guard!(self);
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_write(addr, key, Some(value.to_vec()));
+        }
         Ok(())
     }
 
@@ -361,7 +434,11 @@ impl Interface for InterfaceImpl {
     /// * key: string key of the datastore entry to delete
     fn raw_delete_data_for(&self, address: &str, key: &[u8]) -> Result<()> {
         let addr = &massa_models::address::Address::from_str(address)?;
-        context_guard!(self).delete_data_entry(addr, key)?;
+        let mut context = context_guard!(self);
+        context.delete_data_entry(addr, key)?;
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_write(*addr, key, None);
+        }
         Ok(())
     }
 
@@ -387,9 +464,13 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// The datastore value matching the provided key, if found, otherwise an error.
     fn raw_get_data(&self, key: &[u8]) -> Result<Vec<u8>> {
-        let context = context_guard!(self);
+        let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
-        match context.get_data_entry(&addr, key) {
+        let value = context.get_data_entry(&addr, key);
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_read(addr, key, value.clone());
+        }
+        match value {
             Some(data) => Ok(data),
             _ => bail!("data entry not found"),
         }
@@ -407,6 +488,9 @@ impl Interface for InterfaceImpl {
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         context.set_data_entry(&addr, key.to_vec(), value.to_vec())?;
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_write(addr, key, Some(value.to_vec()));
+        }
         Ok(())
     }
 
@@ -433,6 +517,9 @@ impl Interface for InterfaceImpl {
         let mut context = context_guard!(self);
         let addr = context.get_current_address()?;
         context.delete_data_entry(&addr, key)?;
+        if let Some(tracer) = context.operation_tracer.as_mut() {
+            tracer.record_write(addr, key, None);
+        }
         Ok(())
     }
 
@@ -62,6 +62,10 @@
 //! It also serves as an access point to the current execution state and speculative ledger
 //! as defined in `speculative_ledger.rs`.
 //!
+//! ## `engine.rs`
+//! Defines the `ExecutionEngine` trait compiling and running the smart contract bytecodes, implemented by the Wasmer-based runtime
+//! of massa-sc-runtime over the module cache. Another engine can be plugged behind the `execution_engine` feature.
+//!
 //! ## `speculative_ledger.rs`
 //! A speculative (non-final) ledger that supports canceling already-executed operations
 //! in the case of some blockclique changes.
@@ -88,8 +92,13 @@
 mod active_history;
 mod context;
 mod controller;
+mod engine;
+mod event_db;
 mod execution;
 mod interface_impl;
+mod operation_index;
+mod parallel;
+mod replay;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -97,12 +106,20 @@ mod speculative_executed_denunciations;
 mod speculative_executed_ops;
 mod speculative_ledger;
 mod speculative_roll_state;
+mod state_changes_export;
 mod stats;
+mod trace;
 mod vesting_manager;
 mod worker;
 
+pub use replay::{replay_final_slots, ReplayDivergence, ReplayReport};
 pub use worker::start_execution_worker;
 
+#[cfg(feature = "execution_engine")]
+pub use engine::{EngineError, ExecutionEngine, WasmerEngine};
+#[cfg(feature = "execution_engine")]
+pub use worker::start_execution_worker_with_engine;
+
 #[cfg(any(
     feature = "gas_calibration",
     feature = "benchmarking",
@@ -0,0 +1,176 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistent index of the final executed operations by involved address.
+//!
+//! Each operation executed in a final block is indexed under every address involved
+//! in it from a ledger point of view (see `get_ledger_involved_addresses`).
+//! The index is local to the node: it is neither part of the final state nor bootstrapped,
+//! so it only covers the slots finalized while the index was enabled.
+//!
+//! Keys are `address length | address | slot | operation id` and values are `block id | success`,
+//! so that the operations of an address are contiguous and sorted by slot.
+
+use massa_execution_exports::IndexedOperation;
+use massa_models::{
+    address::Address,
+    block_id::{BlockId, BLOCK_ID_SIZE_BYTES},
+    operation::{OperationId, OPERATION_ID_SIZE_BYTES},
+    prehash::PreHashSet,
+    slot::{Slot, SLOT_KEY_SIZE},
+};
+use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+
+pub(crate) struct OperationIndex {
+    /// RocksDB database
+    db: DB,
+}
+
+impl OperationIndex {
+    /// Open or create the operation index stored at `path`
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+        }
+    }
+
+    /// Index the operations executed by a final block
+    ///
+    /// # Arguments
+    /// * `slot`: slot of the block
+    /// * `block_id`: id of the block
+    /// * `operations`: executed operations with their involved addresses and execution success
+    pub fn insert_block(
+        &self,
+        slot: Slot,
+        block_id: BlockId,
+        operations: Vec<(OperationId, PreHashSet<Address>, bool)>,
+    ) {
+        let mut batch = WriteBatch::default();
+        for (op_id, addresses, success) in operations {
+            let value = [&block_id.to_bytes()[..], &[success as u8]].concat();
+            for address in addresses {
+                batch.put(entry_key(&address_prefix(&address), slot, &op_id), &value);
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+    }
+
+    /// Get up to `limit` operations involving `address`, most recent first.
+    /// If a cursor is given, only the operations strictly before it are returned.
+    pub fn get_operations(
+        &self,
+        address: &Address,
+        cursor: Option<(Slot, OperationId)>,
+        limit: usize,
+    ) -> Vec<IndexedOperation> {
+        let prefix = address_prefix(address);
+        let start_key = match &cursor {
+            Some((slot, op_id)) => entry_key(&prefix, *slot, op_id),
+            None => [
+                &prefix[..],
+                &[u8::MAX; SLOT_KEY_SIZE + OPERATION_ID_SIZE_BYTES],
+            ]
+            .concat(),
+        };
+
+        self.db
+            .iterator(IteratorMode::From(&start_key, Direction::Reverse))
+            .map(|item| item.expect(CRUD_ERROR))
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .filter(|(key, _)| cursor.is_none() || **key != *start_key)
+            .take(limit)
+            .map(|(key, value)| {
+                let suffix = &key[prefix.len()..];
+                let slot = Slot::from_bytes_key(suffix[..SLOT_KEY_SIZE].try_into().unwrap());
+                let op_id = OperationId::from_bytes(suffix[SLOT_KEY_SIZE..].try_into().unwrap());
+                let block_id =
+                    BlockId::from_bytes(value[..BLOCK_ID_SIZE_BYTES].try_into().unwrap());
+                IndexedOperation {
+                    op_id,
+                    slot,
+                    block_id,
+                    success: value[BLOCK_ID_SIZE_BYTES] != 0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Length-prefixed address bytes, so that no address prefix matches another address
+fn address_prefix(address: &Address) -> Vec<u8> {
+    let bytes = address.to_prefixed_bytes();
+    [&[bytes.len() as u8][..], &bytes].concat()
+}
+
+/// Key of the entry of an operation under an address
+fn entry_key(prefix: &[u8], slot: Slot, op_id: &OperationId) -> Vec<u8> {
+    [prefix, &slot.to_bytes_key(), &op_id.to_bytes()[..]].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_signature::KeyPair;
+    use tempfile::TempDir;
+
+    fn op_id(seed: u8) -> OperationId {
+        OperationId::from_bytes(Hash::compute_from(&[seed]).to_bytes())
+    }
+
+    fn address() -> Address {
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    #[test]
+    fn test_operation_index_pagination() {
+        let dir = TempDir::new().unwrap();
+        let index = OperationIndex::new(dir.path().to_path_buf());
+        let alice = address();
+        let bob = address();
+        let block_id = BlockId(Hash::compute_from(b"block"));
+
+        // op 0 involves both addresses, the others only alice
+        index.insert_block(
+            Slot::new(1, 0),
+            block_id,
+            vec![
+                (op_id(0), [alice, bob].into_iter().collect(), true),
+                (op_id(1), [alice].into_iter().collect(), false),
+            ],
+        );
+        index.insert_block(
+            Slot::new(2, 3),
+            block_id,
+            vec![(op_id(2), [alice].into_iter().collect(), true)],
+        );
+
+        // most recent first
+        let page = index.get_operations(&alice, None, 2);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].op_id, op_id(2));
+        assert_eq!(page[0].slot, Slot::new(2, 3));
+        assert!(page[0].success);
+        assert_eq!(page[1].slot, Slot::new(1, 0));
+
+        // next page starts strictly after the cursor
+        let cursor = (page[1].slot, page[1].op_id);
+        let next = index.get_operations(&alice, Some(cursor), 2);
+        assert_eq!(next.len(), 1);
+        assert_ne!(next[0].op_id, page[1].op_id);
+        assert_eq!(next[0].block_id, block_id);
+        assert!(index
+            .get_operations(&alice, Some((next[0].slot, next[0].op_id)), 2)
+            .is_empty());
+
+        // other addresses only see their own operations
+        let bob_ops = index.get_operations(&bob, None, 10);
+        assert_eq!(bob_ops.len(), 1);
+        assert_eq!(bob_ops[0].op_id, op_id(0));
+        assert!(index.get_operations(&address(), None, 10).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Parallel execution of the independent operations of a block.
+//!
+//! Consecutive operations of a block whose effects are limited to known ledger entries,
+//! and that touch disjoint sets of them, are gathered in an `OperationBatch`.
+//! The operations of a batch are executed at the same time on forks of the execution context,
+//! which are then merged in the order of the operations: the result is the one of their serial execution.
+//! The other operations are executed serially, and so is an operation conflicting with the current batch
+//! once the batch is executed.
+//!
+//! Only transactions are executed in parallel for now,
+//! the effects of the other operations are not limited to ledger entries known before their execution.
+
+use massa_models::{
+    address::Address,
+    operation::{OperationType, SecureShareOperation},
+    prehash::PreHashSet,
+};
+
+/// Addresses whose ledger entries an operation can touch,
+/// none if its effects are not limited to known ledger entries
+pub(crate) fn operation_access_set(
+    operation: &SecureShareOperation,
+) -> Option<PreHashSet<Address>> {
+    match &operation.content.op {
+        OperationType::Transaction {
+            recipient_address, ..
+        } => Some(
+            [operation.content_creator_address, *recipient_address]
+                .into_iter()
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Consecutive operations of a block touching disjoint sets of addresses
+#[derive(Default)]
+pub(crate) struct OperationBatch {
+    /// operations in block order, with the addresses they can touch
+    pub operations: Vec<(SecureShareOperation, PreHashSet<Address>)>,
+    /// addresses touched by the operations of the batch
+    addresses: PreHashSet<Address>,
+    /// total gas of the operations of the batch
+    gas: u64,
+}
+
+impl OperationBatch {
+    /// Whether an operation touching `addresses` can be added to the batch.
+    /// It must not conflict with the operations of the batch and all of them must fit in the remaining block gas,
+    /// so that the inclusion of an operation does not depend on the execution of the previous ones.
+    pub fn accepts(
+        &self,
+        addresses: &PreHashSet<Address>,
+        gas: u64,
+        remaining_block_gas: u64,
+    ) -> bool {
+        self.addresses.is_disjoint(addresses)
+            && self
+                .gas
+                .checked_add(gas)
+                .map_or(false, |total| total <= remaining_block_gas)
+    }
+
+    /// Adds an operation touching `addresses` to the batch
+    pub fn push(&mut self, operation: SecureShareOperation, addresses: PreHashSet<Address>) {
+        self.gas = self.gas.saturating_add(operation.get_gas_usage());
+        self.addresses.extend(addresses.iter().copied());
+        self.operations.push((operation, addresses));
+    }
+
+    /// Whether the batch has no operation
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Deterministic replay of the final slots, to debug consensus and execution mismatches.
+//!
+//! When enabled, the `FinalSlotRecorder` records each final slot executed by the node: its block with the operations
+//! of the block, or nothing for a miss, along with the hash of the final state after the slot.
+//! `replay_final_slots` executes the recorded slots again on top of a final state, usually imported from a snapshot,
+//! and compares the hash of the final state after each slot with the recorded one, stopping at the first divergence.
+//!
+//! The records are stored under `slot` and serialized in JSON, like the archive entries.
+
+use crate::execution::ExecutionState;
+use massa_execution_exports::{ExecutionChannels, ExecutionConfig, ExecutionError};
+use massa_final_state::FinalState;
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::{
+    block::SecureShareBlock, block_id::BlockId, operation::SecureShareOperation, slot::Slot,
+};
+use massa_pos_exports::SelectorController;
+use massa_storage::Storage;
+use massa_versioning::versioning::MipStore;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const RECORD_SER_ERROR: &str = "critical: final slot record serialization failed";
+const RECORD_DESER_ERROR: &str = "critical: final slot record deserialization failed";
+
+/// Recorded final slot
+#[derive(Serialize, Deserialize)]
+struct FinalSlotRecord {
+    /// block of the slot with its operations, none for a miss
+    block: Option<(SecureShareBlock, Vec<SecureShareOperation>)>,
+    /// hash of the final state after the slot
+    state_hash: Hash,
+}
+
+/// Record of the final slots executed by the node
+pub(crate) struct FinalSlotRecorder {
+    /// RocksDB database
+    db: DB,
+}
+
+impl FinalSlotRecorder {
+    /// Open or create the record stored at `path`
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            db: DB::open_default(path).expect(OPEN_ERROR),
+        }
+    }
+
+    /// Records a final slot executed on `exec_target`, with the hash of the final state after it
+    pub fn record(&self, slot: &Slot, exec_target: Option<&(BlockId, Storage)>, state_hash: Hash) {
+        let block = exec_target.and_then(|(block_id, storage)| {
+            let block = storage.read_blocks().get(block_id)?.clone();
+            let ops = storage.read_operations();
+            let operations = block
+                .content
+                .operations
+                .iter()
+                .filter_map(|op_id| ops.get(op_id).cloned())
+                .collect();
+            Some((block, operations))
+        });
+        let record = FinalSlotRecord { block, state_hash };
+        self.db
+            .put(
+                slot.to_bytes_key(),
+                serde_json::to_vec(&record).expect(RECORD_SER_ERROR),
+            )
+            .expect(CRUD_ERROR);
+    }
+
+    /// Get the record of a final slot
+    fn get(&self, slot: &Slot) -> Option<FinalSlotRecord> {
+        self.db
+            .get(slot.to_bytes_key())
+            .expect(CRUD_ERROR)
+            .map(|value| serde_json::from_slice(&value).expect(RECORD_DESER_ERROR))
+    }
+}
+
+/// First slot whose replay led to another final state than the recorded one
+#[derive(Debug, Clone)]
+pub struct ReplayDivergence {
+    /// diverging slot
+    pub slot: Slot,
+    /// block of the slot, none for a miss
+    pub block_id: Option<BlockId>,
+    /// recorded hash of the final state after the slot
+    pub recorded_state_hash: Hash,
+    /// hash of the final state after the replay of the slot
+    pub replayed_state_hash: Hash,
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    /// number of slots replayed, including the diverging one
+    pub replayed_slots: u64,
+    /// first divergence, none if all the replayed slots match their record
+    pub divergence: Option<ReplayDivergence>,
+}
+
+/// Replays the final slots recorded at `record_path`, from the slot following the one of `final_state` to `end_slot`,
+/// stopping at the first slot leading to another final state than the recorded one.
+/// The selector must be fed with the draws of `final_state`.
+///
+/// The replay executes the slots like the node, with `config`, but neither records nor indexes them,
+/// and it does not write to the stores of the node. The node must be stopped.
+pub fn replay_final_slots(
+    mut config: ExecutionConfig,
+    final_state: Arc<RwLock<FinalState>>,
+    selector: Box<dyn SelectorController>,
+    mip_store: MipStore,
+    record_path: PathBuf,
+    end_slot: Slot,
+) -> Result<ReplayReport, ExecutionError> {
+    let recorder = FinalSlotRecorder::new(record_path);
+    config.final_slots_record_path = None;
+    config.operation_index_path = None;
+    config.event_store_path = None;
+    config.state_changes_export_path = None;
+    config.broadcast_enabled = false;
+    let thread_count = config.thread_count;
+    let channels = ExecutionChannels {
+        slot_execution_output_sender: tokio::sync::broadcast::channel(1).0,
+    };
+    let mut execution_state = ExecutionState::new(
+        config,
+        final_state.clone(),
+        mip_store,
+        selector.clone(),
+        channels,
+        MassaMetrics::new(false, thread_count),
+        None,
+    );
+
+    let mut report = ReplayReport {
+        replayed_slots: 0,
+        divergence: None,
+    };
+    let mut slot = execution_state
+        .final_cursor
+        .get_next_slot(thread_count)
+        .map_err(ExecutionError::ModelsError)?;
+    info!("replaying the final slots from {} to {}", slot, end_slot);
+    while slot <= end_slot {
+        let record = recorder.get(&slot).ok_or_else(|| {
+            ExecutionError::RuntimeError(format!("no record of the final slot {}", slot))
+        })?;
+        let exec_target = record.block.map(|(block, operations)| {
+            let block_id = block.id;
+            let mut storage = Storage::create_root();
+            storage.store_operations(operations);
+            storage.store_block(block);
+            (block_id, storage)
+        });
+        execution_state.execute_final_slot(&slot, exec_target.as_ref(), selector.clone());
+        report.replayed_slots += 1;
+
+        let replayed_state_hash = final_state.read().db.read().get_db_hash();
+        if replayed_state_hash != record.state_hash {
+            report.divergence = Some(ReplayDivergence {
+                slot,
+                block_id: exec_target.map(|(block_id, _)| block_id),
+                recorded_state_hash: record.state_hash,
+                replayed_state_hash,
+            });
+            break;
+        }
+        slot = slot
+            .get_next_slot(thread_count)
+            .map_err(ExecutionError::ModelsError)?;
+    }
+    Ok(report)
+}
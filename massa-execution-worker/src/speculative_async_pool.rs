@@ -64,6 +64,17 @@ impl SpeculativeAsyncPool {
         }
     }
 
+    /// Forks the `SpeculativeAsyncPool` for an execution that does not touch the pool:
+    /// the fork has neither the changes nor the messages of the `SpeculativeAsyncPool`
+    pub fn fork_untouched(&self) -> SpeculativeAsyncPool {
+        SpeculativeAsyncPool {
+            final_state: self.final_state.clone(),
+            active_history: self.active_history.clone(),
+            pool_changes: Default::default(),
+            message_infos: Default::default(),
+        }
+    }
+
     /// Returns the changes caused to the `SpeculativeAsyncPool` since its creation,
     /// and resets their local value to nothing.
     /// This must be called after `settle_emitted_messages()`
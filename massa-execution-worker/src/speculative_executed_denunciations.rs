@@ -41,6 +41,12 @@ impl SpeculativeExecutedDenunciations {
         }
     }
 
+    /// Forks the `SpeculativeExecutedDenunciations` for an execution that does not execute denunciations:
+    /// the fork has none of the changes of the `SpeculativeExecutedDenunciations`
+    pub fn fork_untouched(&self) -> SpeculativeExecutedDenunciations {
+        SpeculativeExecutedDenunciations::new(self.final_state.clone(), self.active_history.clone())
+    }
+
     /// Returns the set of operation IDs caused to the `SpeculativeExecutedDenunciations` since
     /// its creation, and resets their local value to nothing
     pub fn take(&mut self) -> ExecutedDenunciationsChanges {
@@ -55,6 +55,27 @@ impl SpeculativeExecutedOps {
         self.executed_ops = snapshot;
     }
 
+    /// Forks the `SpeculativeExecutedOps` for the execution of the operation `op_id`:
+    /// the fork only knows whether this operation was executed by the `SpeculativeExecutedOps`
+    pub fn fork(&self, op_id: &OperationId) -> SpeculativeExecutedOps {
+        let mut executed_ops = ExecutedOpsChanges::default();
+        if let Some(execution) = self.executed_ops.get(op_id) {
+            executed_ops.insert(*op_id, *execution);
+        }
+        SpeculativeExecutedOps {
+            final_state: self.final_state.clone(),
+            active_history: self.active_history.clone(),
+            executed_ops,
+        }
+    }
+
+    /// Takes the execution of an operation by a fork of the `SpeculativeExecutedOps`, if it was executed in the fork
+    pub fn merge_fork(&mut self, fork: &SpeculativeExecutedOps, op_id: &OperationId) {
+        if let Some(execution) = fork.executed_ops.get(op_id) {
+            self.executed_ops.insert(*op_id, *execution);
+        }
+    }
+
     /// Checks if an operation was executed previously
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
         // check in the curent changes
@@ -7,11 +7,11 @@
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use massa_execution_exports::ExecutionError;
-use massa_execution_exports::StorageCostsConstants;
+use massa_execution_exports::{ReadOnlyLedgerOverride, StorageCostsConstants};
 use massa_final_state::FinalState;
 use massa_ledger_exports::{Applicable, LedgerChanges, SetOrDelete, SetUpdateOrDelete};
 use massa_models::bytecode::Bytecode;
-use massa_models::{address::Address, amount::Amount};
+use massa_models::{address::Address, amount::Amount, prehash::PreHashSet};
 use parking_lot::RwLock;
 use std::collections::BTreeSet;
 use std::sync::Arc;
@@ -99,6 +99,37 @@ impl SpeculativeLedger {
         self.added_changes = snapshot;
     }
 
+    /// Forks the `SpeculativeLedger` for an execution touching only the ledger entries of `addresses`:
+    /// the fork carries the changes made to these addresses only, so that forking does not depend on the size of the changes.
+    /// Reading another address from the fork ignores the changes made to it by the `SpeculativeLedger`.
+    pub fn fork(&self, addresses: &PreHashSet<Address>) -> SpeculativeLedger {
+        let mut added_changes = LedgerChanges::default();
+        for addr in addresses {
+            if let Some(change) = self.added_changes.0.get(addr) {
+                added_changes.0.insert(*addr, change.clone());
+            }
+        }
+        SpeculativeLedger {
+            final_state: self.final_state.clone(),
+            active_history: self.active_history.clone(),
+            added_changes,
+            max_datastore_key_length: self.max_datastore_key_length,
+            max_datastore_value_size: self.max_datastore_value_size,
+            max_bytecode_size: self.max_bytecode_size,
+            storage_costs_constants: self.storage_costs_constants,
+        }
+    }
+
+    /// Takes the changes made to some addresses by a fork of the `SpeculativeLedger` (see `fork`).
+    /// The fork must have been made for these addresses and no other change must have been made to them since.
+    pub fn merge_fork(&mut self, fork: &SpeculativeLedger, addresses: &PreHashSet<Address>) {
+        for addr in addresses {
+            if let Some(change) = fork.added_changes.0.get(addr) {
+                self.added_changes.0.insert(*addr, change.clone());
+            }
+        }
+    }
+
     /// Gets the effective balance of an address
     ///
     /// # Arguments:
@@ -233,6 +264,26 @@ impl SpeculativeLedger {
         Ok(())
     }
 
+    /// Forcefully overrides parts of a ledger entry, creating it if it does not exist.
+    /// No storage cost is charged and no size check is performed.
+    ///
+    /// Only meant to simulate a hypothetical ledger state in read-only executions.
+    pub fn apply_override(&mut self, ledger_override: ReadOnlyLedgerOverride) {
+        let addr = ledger_override.address;
+        if !self.entry_exists(&addr) {
+            self.added_changes.create_address(&addr);
+        }
+        if let Some(balance) = ledger_override.balance {
+            self.added_changes.set_balance(addr, balance);
+        }
+        if let Some(bytecode) = ledger_override.bytecode {
+            self.added_changes.set_bytecode(addr, bytecode);
+        }
+        for (key, value) in ledger_override.datastore {
+            self.added_changes.set_data_entry(addr, key, value);
+        }
+    }
+
     /// Checks if an address exists in the speculative ledger
     ///
     /// # Arguments:
@@ -387,7 +438,7 @@ impl SpeculativeLedger {
     /// `Some(Vec<Vec<u8>>)` for found keys, `None` if the address does not exist.
     pub fn get_keys(&self, addr: &Address) -> Option<BTreeSet<Vec<u8>>> {
         let mut keys: Option<BTreeSet<Vec<u8>>> =
-            self.final_state.read().ledger.get_datastore_keys(addr);
+            self.final_state.read().ledger.get_datastore_keys(addr, &[]);
 
         // here, traverse the history from oldest to newest with added_changes at the end, applying additions and deletions
         let active_history = self.active_history.read();
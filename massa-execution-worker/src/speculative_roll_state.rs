@@ -44,6 +44,12 @@ impl SpeculativeRollState {
         }
     }
 
+    /// Forks the `SpeculativeRollState` for an execution that does not touch the rolls:
+    /// the fork has none of the changes of the `SpeculativeRollState`
+    pub fn fork_untouched(&self) -> SpeculativeRollState {
+        SpeculativeRollState::new(self.final_state.clone(), self.active_history.clone())
+    }
+
     /// Returns the changes caused to the `SpeculativeRollState` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> PoSChanges {
@@ -94,6 +100,35 @@ impl SpeculativeRollState {
         *count = count.saturating_add(roll_count);
     }
 
+    /// Get the operator an address delegated its stake to
+    ///
+    /// # Arguments
+    /// * `owner`: address owning the rolls
+    pub fn get_delegation(&self, owner: &Address) -> Option<Address> {
+        self.added_changes
+            .delegation_changes
+            .get(owner)
+            .copied()
+            .unwrap_or_else(|| {
+                self.active_history
+                    .read()
+                    .fetch_delegation(owner)
+                    .unwrap_or_else(|| self.final_state.read().pos_state.get_delegation_for(owner))
+            })
+    }
+
+    /// Delegate the stake of an address to an operator, or revoke its delegation (`None` operator).
+    /// The delegation is used by the draws looking back at the current cycle.
+    ///
+    /// # Arguments
+    /// * `owner`: address owning the rolls
+    /// * `operator`: address producing the blocks and endorsements of the owner
+    pub fn set_delegation(&mut self, owner: &Address, operator: Option<Address>) {
+        self.added_changes
+            .delegation_changes
+            .insert(*owner, operator);
+    }
+
     /// Try to sell `roll_count` rolls from the seller address.
     ///
     /// # Arguments
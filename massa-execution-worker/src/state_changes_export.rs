@@ -0,0 +1,128 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Export of the state changes of the final slots, for off-chain analytics and audit.
+//!
+//! The full state changes of each finalized slot (ledger, async pool, PoS, executed operations and denunciations)
+//! are handed to the configured `StateChangesSink`s, in slot order, before being applied to the final state.
+//! The final slots are also streamed with their state changes by the gRPC `NewSlotExecutionOutputs` stream
+//! when the broadcast is enabled, but a lagging subscriber may miss some of them while a sink cannot.
+//!
+//! The file sink appends a record per slot: `record length (u32 BE) | slot | block id | state changes`,
+//! serialized with the `SlotSerializer`, an `OptionSerializer` of `BlockIdSerializer` and the `StateChangesSerializer`
+//! of the `StakeDelegation` MIP component version active at the slot.
+
+use massa_final_state::{StateChanges, StateChangesSerializer};
+use massa_models::{
+    block_id::{BlockId, BlockIdSerializer},
+    slot::{Slot, SlotSerializer},
+};
+use massa_serialization::{OptionSerializer, Serializer};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Destination of the state changes of the final slots
+pub(crate) trait StateChangesSink: Send + Sync {
+    /// Exports the state changes of a final slot,
+    /// `stake_delegation_version` being the version of the `StakeDelegation` MIP component at the slot
+    fn export(
+        &mut self,
+        slot: &Slot,
+        block_id: &Option<BlockId>,
+        state_changes: &StateChanges,
+        stake_delegation_version: u32,
+    ) -> std::io::Result<()>;
+}
+
+/// Sink appending the state changes of the final slots to a file
+pub(crate) struct FileSink {
+    writer: BufWriter<File>,
+    slot_serializer: SlotSerializer,
+    block_id_serializer: OptionSerializer<BlockId, BlockIdSerializer>,
+}
+
+impl FileSink {
+    /// Opens the file at `path`, creating it if needed, to append the state changes to it
+    pub fn new(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            slot_serializer: SlotSerializer::new(),
+            block_id_serializer: OptionSerializer::new(BlockIdSerializer::new()),
+        })
+    }
+}
+
+impl StateChangesSink for FileSink {
+    fn export(
+        &mut self,
+        slot: &Slot,
+        block_id: &Option<BlockId>,
+        state_changes: &StateChanges,
+        stake_delegation_version: u32,
+    ) -> std::io::Result<()> {
+        let to_io_error = |e| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let mut record = Vec::new();
+        self.slot_serializer
+            .serialize(slot, &mut record)
+            .map_err(to_io_error)?;
+        self.block_id_serializer
+            .serialize(block_id, &mut record)
+            .map_err(to_io_error)?;
+        StateChangesSerializer::new(stake_delegation_version)
+            .serialize(state_changes, &mut record)
+            .map_err(to_io_error)?;
+        let length = u32::try_from(record.len()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "record too large")
+        })?;
+        self.writer.write_all(&length.to_be_bytes())?;
+        self.writer.write_all(&record)?;
+        // flush every slot so that the file is complete up to the last final slot
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::slot::SlotDeserializer;
+    use massa_serialization::{DeserializeError, Deserializer};
+    use std::ops::Bound::Included;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_sink_appends_records() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("export").join("state_changes.bin");
+        let mut sink = FileSink::new(&path).unwrap();
+        sink.export(&Slot::new(1, 0), &None, &StateChanges::default(), 0)
+            .unwrap();
+        sink.export(&Slot::new(1, 1), &None, &StateChanges::default(), 1)
+            .unwrap();
+
+        let content = std::fs::read(&path).unwrap();
+        let slot_deserializer = SlotDeserializer::new(
+            (Included(0), Included(u64::MAX)),
+            (Included(0), Included(31)),
+        );
+        let mut rest = &content[..];
+        let mut slots = Vec::new();
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let record = &rest[4..4 + length];
+            let (record_rest, slot) = slot_deserializer
+                .deserialize::<DeserializeError>(record)
+                .unwrap();
+            // no block id
+            assert_eq!(record_rest[0], b'0');
+            slots.push(slot);
+            rest = &rest[4 + length..];
+        }
+        assert_eq!(slots, vec![Slot::new(1, 0), Slot::new(1, 1)]);
+    }
+}
@@ -3,11 +3,84 @@
 #[cfg(test)]
 mod tests {
     use hex_literal::hex;
+    use massa_execution_exports::ExecutionConfig;
     use massa_models::address::Address;
     use massa_sc_runtime::Interface;
+    use massa_time::MassaTime;
+    use massa_versioning::versioning::MipComponent;
+    use std::collections::BTreeMap;
     use std::str::FromStr;
 
     use crate::interface_impl::InterfaceImpl;
+    use crate::tests::mock::get_mip_store_with_active_mip;
+
+    /// Interface whose caller owns two smart contracts, with the call guards of the VM version 1 active or not
+    fn interface_with_contracts(call_guards: bool) -> (InterfaceImpl, String, String) {
+        let sender =
+            Address::from_str("AU12cMW9zRKFDS43Z2W88VCmdQFxmHjAo54XvuVV34UzJeXRLXW9M").unwrap();
+        let interface = if call_guards {
+            InterfaceImpl::new_default_with_mip_store(
+                sender,
+                None,
+                get_mip_store_with_active_mip(
+                    BTreeMap::from([(MipComponent::VM, 1)]),
+                    MassaTime::from_millis(0),
+                ),
+            )
+        } else {
+            InterfaceImpl::new_default(sender, None)
+        };
+        let contract_a = interface.create_module(b"contract a").unwrap();
+        let contract_b = interface.create_module(b"contract b").unwrap();
+        (interface, contract_a, contract_b)
+    }
+
+    #[test]
+    fn test_max_call_depth() {
+        let max_call_depth = ExecutionConfig::default().max_call_depth;
+        let (interface, contract, _) = interface_with_contracts(true);
+        for _ in 0..max_call_depth {
+            interface.init_call(&contract, 0).unwrap();
+        }
+        let err = interface.init_call(&contract, 0).unwrap_err();
+        assert!(err.to_string().contains("maximum call depth"), "{}", err);
+
+        // the depth is freed when a call returns
+        interface.finish_call().unwrap();
+        interface.init_call(&contract, 0).unwrap();
+
+        // the depth is not limited before the activation of the VM version 1
+        let (interface, contract, _) = interface_with_contracts(false);
+        for _ in 0..=max_call_depth {
+            interface.init_call(&contract, 0).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reentrancy_lock() {
+        let (interface, contract_a, contract_b) = interface_with_contracts(true);
+        interface.init_call(&contract_a, 0).unwrap();
+        interface.reentrancy_lock().unwrap();
+
+        // the locked contract can call other contracts, which cannot call it back
+        interface.init_call(&contract_b, 0).unwrap();
+        let err = interface.init_call(&contract_a, 0).unwrap_err();
+        assert!(err.to_string().contains("reentrant call"), "{}", err);
+        assert_eq!(interface.get_call_stack().unwrap().len(), 3);
+
+        // the lock is released when the locked call returns
+        interface.finish_call().unwrap();
+        interface.finish_call().unwrap();
+        interface.init_call(&contract_a, 0).unwrap();
+        interface.init_call(&contract_a, 0).unwrap();
+
+        // the lock is not available before the activation of the VM version 1
+        let (interface, contract_a, _) = interface_with_contracts(false);
+        interface.init_call(&contract_a, 0).unwrap();
+        assert!(interface.reentrancy_lock().is_err());
+        interface.init_call(&contract_a, 0).unwrap();
+    }
+
     #[test]
     fn test_hash_sha256() {
         let interface = InterfaceImpl::new_default(
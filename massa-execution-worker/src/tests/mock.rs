@@ -4,7 +4,10 @@ use massa_final_state::{FinalState, FinalStateConfig};
 use massa_hash::Hash;
 use massa_ledger_exports::{LedgerConfig, LedgerController, LedgerEntry, LedgerError};
 use massa_ledger_worker::FinalLedger;
-use massa_models::config::{ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, T0};
+use massa_models::config::{
+    ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MIP_STORE_STATS_BLOCK_CONSIDERED,
+    MIP_STORE_STATS_COUNTERS_MAX, T0,
+};
 use massa_models::denunciation::Denunciation;
 use massa_models::execution::TempFileVestingRange;
 use massa_models::prehash::PreHashMap;
@@ -22,7 +25,8 @@ use massa_pos_exports::SelectorConfig;
 use massa_pos_worker::start_selector_worker;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
-use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_versioning::test_helpers::versioning_helpers::advance_state_until_active;
+use massa_versioning::versioning::{MipComponent, MipInfo, MipStatsConfig, MipStore};
 use parking_lot::RwLock;
 use std::str::FromStr;
 use std::{
@@ -239,3 +243,29 @@ pub fn get_initials_vesting(with_value: bool) -> NamedTempFile {
 
     file
 }
+
+/// get a MIP store in which a MIP of the `components` versions is active from a few milliseconds after `start`
+#[allow(dead_code)]
+pub fn get_mip_store_with_active_mip(
+    components: BTreeMap<MipComponent, u32>,
+    start: MassaTime,
+) -> MipStore {
+    let mip_info = MipInfo {
+        name: "MIP-TEST".to_string(),
+        version: 1,
+        components,
+        start,
+        timeout: start.saturating_add(MassaTime::from_millis(1000)),
+        activation_delay: MassaTime::from_millis(1),
+    };
+    // the MIP is active from `start` + 4 ms
+    let mip_state = advance_state_until_active(&mip_info);
+    MipStore::try_from((
+        [(mip_info, mip_state)],
+        MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+        },
+    ))
+    .unwrap()
+}
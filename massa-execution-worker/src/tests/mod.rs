@@ -32,3 +32,12 @@ pub use mock::get_initials_vesting;
 
 #[cfg(any(test, feature = "testing"))]
 mod tests_vesting_manager;
+
+#[cfg(test)]
+mod tests_trace;
+
+#[cfg(test)]
+mod tests_differential;
+
+#[cfg(test)]
+mod tests_replay;
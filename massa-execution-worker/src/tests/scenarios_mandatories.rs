@@ -2,16 +2,19 @@
 #[cfg(test)]
 mod tests {
     use crate::active_history::ActiveHistory;
+    use crate::context::get_gas_costs_schedule;
     use crate::speculative_async_pool::SpeculativeAsyncPool;
     use crate::start_execution_worker;
     use crate::tests::mock::{
-        create_block, get_initials_vesting, get_random_address_full, get_sample_state,
+        create_block, get_initials_vesting, get_mip_store_with_active_mip, get_random_address_full,
+        get_sample_state,
     };
     use massa_async_pool::AsyncMessage;
     use massa_db::DBBatch;
     use massa_execution_exports::{
-        ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionError,
-        ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        ExecutionAddressInfo, ExecutionChannels, ExecutionConfig, ExecutionController,
+        ExecutionError, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+        ReadOnlyLedgerOverride,
     };
     use massa_hash::Hash;
     use massa_metrics::MassaMetrics;
@@ -27,17 +30,21 @@ mod tests {
         slot::Slot,
     };
     use massa_models::{
+        block::SecureShareBlock,
         block_id::BlockId,
+        bytecode::Bytecode,
         datastore::Datastore,
         denunciation::Denunciation,
         execution::EventFilter,
         operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
         secure_share::SecureShareContent,
+        timeslots::get_block_slot_timestamp,
     };
+    use massa_sc_runtime::GasCosts;
     use massa_signature::KeyPair;
     use massa_storage::Storage;
     use massa_time::MassaTime;
-    use massa_versioning::versioning::{MipStatsConfig, MipStore};
+    use massa_versioning::versioning::{MipComponent, MipStatsConfig, MipStore};
     use num::rational::Ratio;
     use parking_lot::RwLock;
     use serial_test::serial;
@@ -171,6 +178,8 @@ mod tests {
                     include_bytes!("./wasm/event_test.wasm").to_vec(),
                 ),
                 is_final: true,
+                ledger_overrides: vec![],
+                fee: Amount::zero(),
             })
             .expect("readonly execution failed");
 
@@ -186,11 +195,96 @@ mod tests {
                     include_bytes!("./wasm/event_test.wasm").to_vec(),
                 ),
                 is_final: false,
+                ledger_overrides: vec![],
+                fee: Amount::zero(),
             })
             .expect("readonly execution failed");
 
         assert!(res.out.slot.period > 8);
 
+        // the fee is debited from the impersonated caller, who cannot pay it
+        let caller = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let res = controller.execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 1_000_000,
+            call_stack: vec![ExecutionStackElement {
+                address: caller,
+                coins: Amount::zero(),
+                owned_addresses: vec![caller],
+                operation_datastore: None,
+                reentrancy_locked: false,
+            }],
+            target: ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/event_test.wasm").to_vec(),
+            ),
+            is_final: true,
+            ledger_overrides: vec![],
+            fee: Amount::from_str("1").unwrap(),
+        });
+        assert!(res.is_err(), "the caller should not be able to pay the fee");
+
+        // once funded, the impersonated caller pays the fee and the coins credited to the called contract
+        let target = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let coins = Amount::from_str("10").unwrap();
+        let fee = Amount::from_str("1").unwrap();
+        let res = controller
+            .execute_readonly_request(ReadOnlyExecutionRequest {
+                max_gas: 10_000_000,
+                call_stack: vec![
+                    ExecutionStackElement {
+                        address: caller,
+                        coins: Amount::zero(),
+                        owned_addresses: vec![caller],
+                        operation_datastore: None,
+                        reentrancy_locked: false,
+                    },
+                    ExecutionStackElement {
+                        address: target,
+                        coins,
+                        owned_addresses: vec![target],
+                        operation_datastore: None,
+                        reentrancy_locked: false,
+                    },
+                ],
+                // you can check the source code of the following wasm file in massa-unit-tests-src
+                target: ReadOnlyExecutionTarget::FunctionCall {
+                    target_addr: target,
+                    target_func: "test".to_string(),
+                    parameter: target.to_string().into_bytes(),
+                },
+                is_final: true,
+                ledger_overrides: vec![
+                    ReadOnlyLedgerOverride {
+                        address: caller,
+                        balance: Some(Amount::from_str("100").unwrap()),
+                        bytecode: None,
+                        datastore: BTreeMap::new(),
+                    },
+                    ReadOnlyLedgerOverride {
+                        address: target,
+                        balance: Some(Amount::zero()),
+                        bytecode: Some(Bytecode(
+                            include_bytes!("./wasm/get_call_coins_test.wasm").to_vec(),
+                        )),
+                        datastore: BTreeMap::new(),
+                    },
+                ],
+                fee,
+            })
+            .expect("the funded caller should be able to pay the fee and the coins");
+        let ledger_changes = &res.out.state_changes.ledger_changes;
+        assert_eq!(
+            ledger_changes.get_balance_or_else(&caller, || None),
+            Some(Amount::from_str("89").unwrap())
+        );
+        assert_eq!(
+            ledger_changes.get_balance_or_else(&target, || None),
+            Some(coins)
+        );
+        assert!(res.out.events.0[0].data.contains(&format!(
+            "tokens sent to the SC during the call : {}",
+            coins.to_raw()
+        )));
+
         manager.stop();
     }
 
@@ -1243,6 +1337,138 @@ mod tests {
         manager.stop();
     }
 
+    /// Executes `block` as a final block, with `parallel_execution_threads` threads,
+    /// and returns the exported state changes of the final slots
+    fn execute_block_exporting_state_changes(
+        parallel_execution_threads: usize,
+        block: &SecureShareBlock,
+        operations: &[SecureShareOperation],
+    ) -> Vec<u8> {
+        let vesting = get_initials_vesting(false);
+        let export_dir = tempfile::TempDir::new().unwrap();
+        let export_path = export_dir.path().join("state_changes.bin");
+        // setup the period duration, the parallel execution and the export of the state changes
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            initial_vesting_path: vesting.path().to_path_buf(),
+            parallel_execution_threads,
+            state_changes_export_path: Some(export_path.clone()),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the MIP store
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+        };
+        let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            MassaMetrics::new(false, 32),
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+
+        storage.store_operations(operations.to_vec());
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block so the transactions are processed
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_storage: PreHashMap<BlockId, Storage> = Default::default();
+        block_storage.insert(block.id, storage.clone());
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_storage.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        // stop the execution controller
+        manager.stop();
+
+        std::fs::read(export_path).unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn parallel_transactions() {
+        let thread_count = ExecutionConfig::default().thread_count;
+        // the senders must be in the thread of the block
+        let keypair_in_thread_0 = || loop {
+            let keypair = KeyPair::generate(0).unwrap();
+            if Address::from_public_key(&keypair.get_public_key()).get_thread(thread_count) == 0 {
+                break keypair;
+            }
+        };
+        // a funded sender, a sender without coins and two recipients which send coins afterwards
+        let sender_keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let poor_keypair = keypair_in_thread_0();
+        let keypair_a = keypair_in_thread_0();
+        let keypair_b = keypair_in_thread_0();
+        let address_a = Address::from_public_key(&keypair_a.get_public_key());
+        let address_b = Address::from_public_key(&keypair_b.get_public_key());
+        let (address_c, _keypair) = get_random_address_full();
+        let transaction =
+            |keypair: &KeyPair, recipient_address: Address, amount: &str, fee: &str| {
+                Operation::new_verifiable(
+                    Operation {
+                        fee: Amount::from_str(fee).unwrap(),
+                        expire_period: 10,
+                        op: OperationType::Transaction {
+                            recipient_address,
+                            amount: Amount::from_str(amount).unwrap(),
+                        },
+                    },
+                    OperationSerializer::new(),
+                    keypair,
+                )
+                .unwrap()
+            };
+
+        // the first two operations are independent and executed in parallel, the second one fails.
+        // The third one conflicts with both of them and starts another batch,
+        // in which the fourth one spends the coins received by A in the first batch.
+        // The fifth one conflicts with the third one and spends the coins received by B in the second batch.
+        let operations = vec![
+            transaction(&sender_keypair, address_a, "100", "0"),
+            transaction(&poor_keypair, address_b, "100", "0"),
+            transaction(&sender_keypair, address_b, "100", "0"),
+            transaction(&keypair_a, address_c, "10", "1"),
+            transaction(&keypair_b, address_a, "10", "1"),
+        ];
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            operations.clone(),
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+
+        // the parallel execution leads to the same state changes as the serial one
+        let serial_state_changes = execute_block_exporting_state_changes(1, &block, &operations);
+        let parallel_state_changes = execute_block_exporting_state_changes(4, &block, &operations);
+        assert!(!serial_state_changes.is_empty());
+        assert_eq!(serial_state_changes, parallel_state_changes);
+    }
+
     #[test]
     #[serial]
     fn vesting_transfer_coins() {
@@ -1542,6 +1768,130 @@ mod tests {
         manager.stop();
     }
 
+    /// Executes a block in which the owner of `TEST_SK_1` delegates its stake to `operator`,
+    /// then to itself which is rejected, and returns the infos of the owner
+    fn execute_stake_delegations(mip_store: MipStore, operator: Address) -> ExecutionAddressInfo {
+        let vesting = get_initials_vesting(false);
+        // setup the period duration
+        let exec_cfg = ExecutionConfig {
+            t0: MassaTime::from_millis(100),
+            cursor_delay: MassaTime::from_millis(0),
+            initial_vesting_path: vesting.path().to_path_buf(),
+            ..ExecutionConfig::default()
+        };
+        // get a sample final state
+        let (sample_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+
+        // init the storage
+        let mut storage = Storage::create_root();
+
+        let slot_execution_output_sender = broadcast::channel(5000).0;
+
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+        };
+
+        // start the execution worker
+        let (mut manager, controller) = start_execution_worker(
+            exec_cfg.clone(),
+            sample_state.clone(),
+            sample_state.read().pos_state.selector.clone(),
+            mip_store,
+            channels,
+            MassaMetrics::new(false, 32),
+        );
+        // initialize the execution system with genesis blocks
+        init_execution_worker(&exec_cfg, &storage, controller.clone());
+        // generate the keypair and its corresponding address
+        let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+        let address = Address::from_public_key(&keypair.get_public_key());
+        // create the operations: delegate to the operator, then delegate to itself which is rejected
+        let operation1 = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::StakeDelegation {
+                    operator: Some(operator),
+                },
+            },
+            OperationSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        let operation2 = Operation::new_verifiable(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::StakeDelegation {
+                    operator: Some(address),
+                },
+            },
+            OperationSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        // create the block containing the stake delegation operations
+        storage.store_operations(vec![operation1.clone(), operation2.clone()]);
+        let block = create_block(
+            KeyPair::generate(0).unwrap(),
+            vec![operation1, operation2],
+            vec![],
+            Slot::new(1, 0),
+        )
+        .unwrap();
+        // store the block in storage
+        storage.store_block(block.clone());
+        // set our block as a final block so the delegation is processed
+        let mut finalized_blocks: HashMap<Slot, BlockId> = Default::default();
+        finalized_blocks.insert(block.content.header.content.slot, block.id);
+        let mut block_storage: PreHashMap<BlockId, Storage> = Default::default();
+        block_storage.insert(block.id, storage.clone());
+        controller.update_blockclique_status(
+            finalized_blocks,
+            Default::default(),
+            block_storage.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(100));
+        // the final delegation is the one of the final state
+        let infos = controller.get_addresses_infos(&[address]).remove(0);
+        assert_eq!(
+            sample_state.read().pos_state.get_delegation_for(&address),
+            infos.final_delegation
+        );
+        // stop the execution controller
+        manager.stop();
+        infos
+    }
+
+    #[test]
+    #[serial]
+    pub fn stake_delegation() {
+        let (operator, _) = get_random_address_full();
+
+        // the stake delegation operations are rejected before the activation of the stake delegation MIP
+        let mip_store = MipStore::try_from((
+            [],
+            MipStatsConfig {
+                block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+                counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+            },
+        ))
+        .unwrap();
+        let infos = execute_stake_delegations(mip_store, operator);
+        assert_eq!(infos.final_delegation, None);
+        assert_eq!(infos.candidate_delegation, None);
+
+        // once it is active, the owner delegates its stake and its rolls are unchanged
+        let mip_store = get_mip_store_with_active_mip(
+            BTreeMap::from([(MipComponent::StakeDelegation, 1)]),
+            MassaTime::from_millis(0),
+        );
+        let infos = execute_stake_delegations(mip_store, operator);
+        assert_eq!(infos.final_delegation, Some(operator));
+        assert_eq!(infos.candidate_delegation, Some(operator));
+        assert_eq!(infos.final_roll_count, 100);
+    }
+
     #[test]
     #[serial]
     pub fn roll_sell() {
@@ -2986,4 +3336,48 @@ mod tests {
         speculative_pool.take_batch_to_execute(Slot::new(2, 0), 19);
         assert_eq!(speculative_pool.get_message_infos().len(), 4);
     }
+
+    #[test]
+    fn gas_costs_schedule_by_slot() {
+        let config = ExecutionConfig {
+            gas_costs_schedules: BTreeMap::from([(
+                1,
+                (GasCosts::default(), Hash::compute_from(b"gas costs v1")),
+            )]),
+            ..ExecutionConfig::default()
+        };
+        let activation_slot = Slot::new(10, 0);
+        let activation_timestamp = get_block_slot_timestamp(
+            config.thread_count,
+            config.t0,
+            config.genesis_timestamp,
+            activation_slot,
+        )
+        .unwrap();
+        let mip_store = get_mip_store_with_active_mip(
+            BTreeMap::from([(MipComponent::GasCosts, 1)]),
+            activation_timestamp,
+        );
+
+        // before the activation of the GasCosts component, the default gas costs apply
+        let schedule = get_gas_costs_schedule(&config, &mip_store, Slot::new(1, 0));
+        assert_eq!(schedule.version, config.gas_costs_version);
+        // once active, its schedule applies
+        let schedule = get_gas_costs_schedule(&config, &mip_store, Slot::new(20, 0));
+        assert_eq!(schedule.version, Hash::compute_from(b"gas costs v1"));
+
+        // without schedule for the active version, the latest earlier schedule applies
+        let mip_store = get_mip_store_with_active_mip(
+            BTreeMap::from([(MipComponent::GasCosts, 2)]),
+            activation_timestamp,
+        );
+        let schedule = get_gas_costs_schedule(&config, &mip_store, Slot::new(20, 0));
+        assert_eq!(schedule.version, Hash::compute_from(b"gas costs v1"));
+        let schedule =
+            get_gas_costs_schedule(&ExecutionConfig::default(), &mip_store, Slot::new(20, 0));
+        assert_eq!(
+            schedule.version,
+            ExecutionConfig::default().gas_costs_version
+        );
+    }
 }
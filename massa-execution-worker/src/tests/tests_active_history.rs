@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::active_history::ActiveHistory;
-    use massa_execution_exports::ExecutionOutput;
+    use massa_execution_exports::{EventStore, ExecutionOutput};
     use massa_models::slot::Slot;
     use std::collections::{BTreeMap, VecDeque};
 
@@ -52,11 +52,13 @@ mod tests {
                     roll_changes: Default::default(),
                     production_stats: Default::default(),
                     deferred_credits: credits,
+                    delegation_changes: Default::default(),
                 },
                 executed_ops_changes: Default::default(),
                 executed_denunciations_changes: Default::default(),
             },
             events: Default::default(),
+            events_merkle_root: EventStore::default().compute_merkle_root(),
         };
 
         let active_history = ActiveHistory {
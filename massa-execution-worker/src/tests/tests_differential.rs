@@ -0,0 +1,178 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Differential execution of the same requests by two execution engines,
+//! on two execution states built on identical final states.
+
+use crate::engine::{EngineError, ExecutionEngine, WasmerEngine};
+use crate::execution::{new_module_cache, ExecutionState};
+use crate::tests::mock::{get_initials_vesting, get_sample_state};
+use massa_execution_exports::{
+    ExecutionChannels, ExecutionConfig, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
+};
+use massa_metrics::MassaMetrics;
+use massa_models::amount::Amount;
+use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX};
+use massa_module_cache::types::GasCostsSchedule;
+use massa_sc_runtime::{Interface, Response};
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use serial_test::serial;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Executes `requests` with both engines, the `WasmerEngine` of the execution if none, and returns the first difference between their outputs, if any
+fn compare_engines(
+    engine_a: Option<Arc<dyn ExecutionEngine>>,
+    engine_b: Option<Arc<dyn ExecutionEngine>>,
+    requests: Vec<ReadOnlyExecutionRequest>,
+) -> Result<(), String> {
+    let vesting = get_initials_vesting(false);
+    let new_state = |engine| {
+        let config = ExecutionConfig {
+            initial_vesting_path: vesting.path().to_path_buf(),
+            ..ExecutionConfig::default()
+        };
+        let mip_store = MipStore::try_from((
+            [],
+            MipStatsConfig {
+                block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+                counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+            },
+        ))
+        .unwrap();
+        let (final_state, keep_file, keep_dir) = get_sample_state(0).unwrap();
+        let selector = final_state.read().pos_state.selector.clone();
+        let execution_state = ExecutionState::new(
+            config,
+            final_state,
+            mip_store,
+            selector,
+            ExecutionChannels {
+                slot_execution_output_sender: broadcast::channel(1).0,
+            },
+            MassaMetrics::new(false, 32),
+            engine,
+        );
+        (execution_state, keep_file, keep_dir)
+    };
+    let (state_a, _keep_file_a, _keep_dir_a) = new_state(engine_a);
+    let (state_b, _keep_file_b, _keep_dir_b) = new_state(engine_b);
+
+    for (index, request) in requests.into_iter().enumerate() {
+        let output_a = state_a.execute_readonly_request(request.clone());
+        let output_b = state_b.execute_readonly_request(request);
+        match (output_a, output_b) {
+            (Ok(output_a), Ok(output_b)) => compare_outputs(&output_a, &output_b)
+                .map_err(|difference| format!("request {}: {}", index, difference))?,
+            (Err(_), Err(_)) => {}
+            (output_a, output_b) => {
+                return Err(format!(
+                    "request {}: one engine failed: {:?} / {:?}",
+                    index,
+                    output_a.err(),
+                    output_b.err()
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compares the observable outcome of two executions
+fn compare_outputs(
+    output_a: &ReadOnlyExecutionOutput,
+    output_b: &ReadOnlyExecutionOutput,
+) -> Result<(), String> {
+    if output_a.gas_cost != output_b.gas_cost {
+        return Err(format!(
+            "gas costs differ: {} / {}",
+            output_a.gas_cost, output_b.gas_cost
+        ));
+    }
+    if output_a.call_result != output_b.call_result {
+        return Err("call results differ".to_string());
+    }
+    let events_a: Vec<_> = output_a.out.events.0.iter().map(|e| &e.data).collect();
+    let events_b: Vec<_> = output_b.out.events.0.iter().map(|e| &e.data).collect();
+    if events_a != events_b {
+        return Err(format!("events differ: {:?} / {:?}", events_a, events_b));
+    }
+    let changes_a = &output_a.out.state_changes;
+    let changes_b = &output_b.out.state_changes;
+    if changes_a.ledger_changes != changes_b.ledger_changes
+        || changes_a.async_pool_changes != changes_b.async_pool_changes
+    {
+        return Err("state changes differ".to_string());
+    }
+    Ok(())
+}
+
+fn bytecode_request(bytecode: &[u8]) -> ReadOnlyExecutionRequest {
+    ReadOnlyExecutionRequest {
+        max_gas: 1_000_000,
+        call_stack: vec![],
+        target: ReadOnlyExecutionTarget::BytecodeExecution(bytecode.to_vec()),
+        fee: Amount::zero(),
+        is_final: true,
+        ledger_overrides: vec![],
+    }
+}
+
+/// Wasmer engine given half of the gas, standing for a diverging engine
+struct HalfGasEngine(WasmerEngine);
+
+impl ExecutionEngine for HalfGasEngine {
+    fn run_main(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        compilation_limit: u64,
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError> {
+        self.0.run_main(
+            interface,
+            bytecode,
+            compilation_limit,
+            max_gas / 2,
+            schedule,
+        )
+    }
+
+    fn run_function(
+        &self,
+        interface: &dyn Interface,
+        bytecode: &[u8],
+        function: &str,
+        parameter: &[u8],
+        max_gas: u64,
+        schedule: &GasCostsSchedule,
+    ) -> Result<Response, EngineError> {
+        self.0.run_function(
+            interface,
+            bytecode,
+            function,
+            parameter,
+            max_gas / 2,
+            schedule,
+        )
+    }
+}
+
+#[test]
+#[serial]
+fn test_differential_execution() {
+    let requests = vec![
+        bytecode_request(include_bytes!("./wasm/event_test.wasm")),
+        bytecode_request(include_bytes!("./wasm/execution_error.wasm")),
+    ];
+
+    // the same engine agrees with itself
+    compare_engines(None, None, requests.clone()).expect("the engines should agree");
+
+    // a diverging engine is reported
+    let half_gas_engine = HalfGasEngine(WasmerEngine::new(new_module_cache(
+        &ExecutionConfig::default(),
+    )));
+    assert!(compare_engines(None, Some(Arc::new(half_gas_engine)), requests).is_err());
+}
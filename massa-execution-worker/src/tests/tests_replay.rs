@@ -0,0 +1,116 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::execution::ExecutionState;
+use crate::replay::{replay_final_slots, FinalSlotRecorder};
+use crate::tests::mock::{get_initials_vesting, get_sample_state};
+use massa_execution_exports::{ExecutionChannels, ExecutionConfig};
+use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
+use massa_models::config::{
+    MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX, THREAD_COUNT,
+};
+use massa_models::slot::Slot;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use serial_test::serial;
+use tempfile::TempDir;
+use tokio::sync::broadcast;
+
+fn mip_store() -> MipStore {
+    MipStore::try_from((
+        [],
+        MipStatsConfig {
+            block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+            counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+        },
+    ))
+    .unwrap()
+}
+
+#[test]
+#[serial]
+fn test_replay_reports_first_divergence() {
+    let vesting = get_initials_vesting(false);
+    let record_dir = TempDir::new().unwrap();
+    let record_path = record_dir.path().join("final_slots");
+    let config = ExecutionConfig {
+        initial_vesting_path: vesting.path().to_path_buf(),
+        ..ExecutionConfig::default()
+    };
+
+    // execute and record a few final slots
+    let slots = {
+        let (final_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        let selector = final_state.read().pos_state.selector.clone();
+        let mut execution_state = ExecutionState::new(
+            ExecutionConfig {
+                final_slots_record_path: Some(record_path.clone()),
+                ..config.clone()
+            },
+            final_state,
+            mip_store(),
+            selector.clone(),
+            ExecutionChannels {
+                slot_execution_output_sender: broadcast::channel(1).0,
+            },
+            MassaMetrics::new(false, THREAD_COUNT),
+            None,
+        );
+        let mut slots = Vec::new();
+        let mut slot = execution_state.final_cursor;
+        for _ in 0..4 {
+            slot = slot.get_next_slot(THREAD_COUNT).unwrap();
+            execution_state.execute_final_slot(&slot, None, selector.clone());
+            slots.push(slot);
+        }
+        slots
+    };
+    let end_slot = *slots.last().unwrap();
+
+    let replay = |end_slot: Slot| {
+        let (final_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+        let selector = final_state.read().pos_state.selector.clone();
+        replay_final_slots(
+            config.clone(),
+            final_state,
+            selector,
+            mip_store(),
+            record_path.clone(),
+            end_slot,
+        )
+        .unwrap()
+    };
+
+    // the recorded slots replay to the recorded final states
+    let report = replay(end_slot);
+    assert_eq!(report.replayed_slots, slots.len() as u64);
+    assert!(report.divergence.is_none());
+
+    // the tampered slot is reported as the first divergence, and the replay stops there
+    let tampered_hash = Hash::compute_from(b"tampered final state");
+    FinalSlotRecorder::new(record_path.clone()).record(&slots[2], None, tampered_hash);
+    let report = replay(end_slot);
+    assert_eq!(report.replayed_slots, 3);
+    let divergence = report.divergence.expect("the tampered slot should diverge");
+    assert_eq!(divergence.slot, slots[2]);
+    assert_eq!(divergence.block_id, None);
+    assert_eq!(divergence.recorded_state_hash, tampered_hash);
+    assert_ne!(divergence.replayed_state_hash, tampered_hash);
+
+    // once the record is restored, the replay fails on the slots that were not recorded
+    FinalSlotRecorder::new(record_path.clone()).record(
+        &slots[2],
+        None,
+        divergence.replayed_state_hash,
+    );
+    let (final_state, _keep_file, _keep_dir) = get_sample_state(0).unwrap();
+    let selector = final_state.read().pos_state.selector.clone();
+    assert!(replay_final_slots(
+        config,
+        final_state,
+        selector,
+        mip_store(),
+        record_path,
+        end_slot.get_next_slot(THREAD_COUNT).unwrap(),
+    )
+    .is_err());
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::trace::{CallTracer, OperationTraceStore};
+use massa_execution_exports::OperationTrace;
+use massa_hash::Hash;
+use massa_models::address::{Address, SCAddress, SCAddressV0, UserAddress, UserAddressV0};
+use massa_models::amount::Amount;
+use massa_models::operation::OperationId;
+use massa_models::slot::Slot;
+
+fn user_address(name: &str) -> Address {
+    Address::User(UserAddress::UserAddressV0(UserAddressV0(
+        Hash::compute_from(name.as_bytes()),
+    )))
+}
+
+fn sc_address(name: &str) -> Address {
+    Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::compute_from(
+        name.as_bytes(),
+    ))))
+}
+
+#[test]
+fn test_call_tracer_tree() {
+    let sender = user_address("sender");
+    let contract_a = sc_address("a");
+    let contract_b = sc_address("b");
+
+    let mut tracer = CallTracer::new(sender);
+    tracer.record_transfer(Some(sender), None, Amount::from_raw(10));
+    tracer.enter_call(contract_a, Some("main".to_string()), Amount::from_raw(5));
+    tracer.record_read(contract_a, b"counter", Some(vec![1]));
+    tracer.enter_call(contract_b, None, Amount::zero());
+    tracer.record_write(contract_b, b"owner", None);
+    tracer.exit_call();
+    tracer.record_write(contract_a, b"counter", Some(vec![2]));
+    // the call to contract_b from contract_a did not return, as after an error
+    tracer.enter_call(contract_b, None, Amount::zero());
+    let root = tracer.finish();
+
+    assert_eq!(root.address, sender);
+    assert_eq!(root.caller, None);
+    assert_eq!(root.transfers.len(), 1);
+    assert_eq!(root.calls.len(), 1);
+    let call_a = &root.calls[0];
+    assert_eq!(call_a.caller, Some(sender));
+    assert_eq!(call_a.function.as_deref(), Some("main"));
+    assert_eq!(call_a.coins, Amount::from_raw(5));
+    assert_eq!(call_a.storage_reads.len(), 1);
+    assert_eq!(call_a.storage_writes[0].value, Some(vec![2]));
+    assert_eq!(call_a.calls.len(), 2);
+    assert_eq!(call_a.calls[0].caller, Some(contract_a));
+    assert_eq!(call_a.calls[0].storage_writes[0].value, None);
+    assert!(call_a.calls[1].storage_writes.is_empty());
+}
+
+#[test]
+fn test_operation_trace_store_capacity() {
+    let sender = user_address("sender");
+    let trace = |index: u8, success: bool| OperationTrace {
+        operation_id: OperationId::new(Hash::compute_from(&[index])),
+        slot: Slot::new(1, 0),
+        block_id: None,
+        success,
+        error: None,
+        root: CallTracer::new(sender).finish(),
+    };
+    let mut store = OperationTraceStore::new(2);
+    store.insert(trace(0, true));
+    store.insert(trace(1, true));
+    // a new execution of an operation replaces its trace without taking more room
+    store.insert(trace(0, false));
+    assert!(!store.get(&trace(0, true).operation_id).unwrap().success);
+    store.insert(trace(2, true));
+    assert!(store.get(&trace(0, true).operation_id).is_none());
+    assert!(store.get(&trace(1, true).operation_id).is_some());
+    assert!(store.get(&trace(2, true).operation_id).is_some());
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Tracing of the execution of operations.
+//!
+//! When enabled, a `CallTracer` follows the call stack of the execution context during the execution of each operation,
+//! recording the calls, the datastore accesses and the coin transfers in the call that made them.
+//! The traces of the last executed operations are kept in an `OperationTraceStore`.
+
+use massa_execution_exports::{CallTrace, CoinTransfer, DatastoreAccess, OperationTrace};
+use massa_models::{address::Address, amount::Amount, operation::OperationId, prehash::PreHashMap};
+use std::collections::VecDeque;
+
+/// Call tree of the operation being executed
+pub(crate) struct CallTracer {
+    /// calls being executed, the root call at the bottom and the current one at the top
+    frames: Vec<CallTrace>,
+}
+
+impl CallTracer {
+    /// Starts tracing an operation executed in the context of `sender`
+    pub fn new(sender: Address) -> Self {
+        CallTracer {
+            frames: vec![CallTrace::new(None, sender, None, Amount::zero())],
+        }
+    }
+
+    /// Starts a call made by the current one
+    pub fn enter_call(&mut self, address: Address, function: Option<String>, coins: Amount) {
+        let caller = self.current_call().address;
+        self.frames
+            .push(CallTrace::new(Some(caller), address, function, coins));
+    }
+
+    /// Ends the current call, attaching it to its caller. The root call is never ended.
+    pub fn exit_call(&mut self) {
+        if self.frames.len() > 1 {
+            let call = self.frames.pop().expect("call tracer frames are empty");
+            self.current_call().calls.push(call);
+        }
+    }
+
+    /// Call being executed
+    pub fn current_call(&mut self) -> &mut CallTrace {
+        self.frames
+            .last_mut()
+            .expect("call tracer frames are empty")
+    }
+
+    /// Records a datastore read of the current call
+    pub fn record_read(&mut self, address: Address, key: &[u8], value: Option<Vec<u8>>) {
+        self.current_call().storage_reads.push(DatastoreAccess {
+            address,
+            key: key.to_vec(),
+            value,
+        });
+    }
+
+    /// Records a datastore write of the current call, with no value for a deletion
+    pub fn record_write(&mut self, address: Address, key: &[u8], value: Option<Vec<u8>>) {
+        self.current_call().storage_writes.push(DatastoreAccess {
+            address,
+            key: key.to_vec(),
+            value,
+        });
+    }
+
+    /// Records a coin transfer of the current call
+    pub fn record_transfer(&mut self, from: Option<Address>, to: Option<Address>, amount: Amount) {
+        self.current_call()
+            .transfers
+            .push(CoinTransfer { from, to, amount });
+    }
+
+    /// Ends the calls that did not return, for example because of an error, and returns the root call
+    pub fn finish(mut self) -> CallTrace {
+        while self.frames.len() > 1 {
+            self.exit_call();
+        }
+        self.frames.pop().expect("call tracer frames are empty")
+    }
+}
+
+/// Traces of the last executed operations
+pub(crate) struct OperationTraceStore {
+    /// max number of traces kept
+    capacity: usize,
+    /// traced operations, oldest first
+    order: VecDeque<OperationId>,
+    /// traces by operation
+    traces: PreHashMap<OperationId, OperationTrace>,
+}
+
+impl OperationTraceStore {
+    /// Creates an empty store keeping up to `capacity` traces
+    pub fn new(capacity: usize) -> Self {
+        OperationTraceStore {
+            capacity,
+            order: VecDeque::new(),
+            traces: PreHashMap::default(),
+        }
+    }
+
+    /// Adds the trace of an operation, replacing the one of a previous execution of the operation
+    /// and forgetting the oldest traces if the store is full
+    pub fn insert(&mut self, trace: OperationTrace) {
+        let operation_id = trace.operation_id;
+        if self.traces.insert(operation_id, trace).is_none() {
+            self.order.push_back(operation_id);
+        }
+        while self.order.len() > self.capacity {
+            if let Some(operation_id) = self.order.pop_front() {
+                self.traces.remove(&operation_id);
+            }
+        }
+    }
+
+    /// Trace of the last execution of an operation
+    pub fn get(&self, operation_id: &OperationId) -> Option<&OperationTrace> {
+        self.traces.get(operation_id)
+    }
+}
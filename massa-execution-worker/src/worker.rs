@@ -6,6 +6,7 @@
 //! and requests the execution of active and final slots from execution.rs.
 
 use crate::controller::{ExecutionControllerImpl, ExecutionInputData, ExecutionManagerImpl};
+use crate::engine::ExecutionEngine;
 use crate::execution::ExecutionState;
 use crate::request_queue::RequestQueue;
 use crate::slot_sequencer::SlotSequencer;
@@ -250,6 +251,49 @@ pub fn start_execution_worker(
     mip_store: MipStore,
     channels: ExecutionChannels,
     massa_metrics: MassaMetrics,
+) -> (Box<dyn ExecutionManager>, Box<dyn ExecutionController>) {
+    launch_execution_worker(
+        config,
+        final_state,
+        selector,
+        mip_store,
+        channels,
+        massa_metrics,
+        None,
+    )
+}
+
+/// Launches an execution worker thread running the smart contract bytecodes with `engine`,
+/// see `start_execution_worker`
+pub fn start_execution_worker_with_engine(
+    config: ExecutionConfig,
+    final_state: Arc<RwLock<FinalState>>,
+    selector: Box<dyn SelectorController>,
+    mip_store: MipStore,
+    channels: ExecutionChannels,
+    massa_metrics: MassaMetrics,
+    engine: Arc<dyn ExecutionEngine>,
+) -> (Box<dyn ExecutionManager>, Box<dyn ExecutionController>) {
+    launch_execution_worker(
+        config,
+        final_state,
+        selector,
+        mip_store,
+        channels,
+        massa_metrics,
+        Some(engine),
+    )
+}
+
+/// Launches an execution worker thread with `engine`, or the `WasmerEngine` if none
+fn launch_execution_worker(
+    config: ExecutionConfig,
+    final_state: Arc<RwLock<FinalState>>,
+    selector: Box<dyn SelectorController>,
+    mip_store: MipStore,
+    channels: ExecutionChannels,
+    massa_metrics: MassaMetrics,
+    engine: Option<Arc<dyn ExecutionEngine>>,
 ) -> (Box<dyn ExecutionManager>, Box<dyn ExecutionController>) {
     // create an execution state
     let execution_state = Arc::new(RwLock::new(ExecutionState::new(
@@ -259,6 +303,7 @@ pub fn start_execution_worker(
         selector.clone(),
         channels,
         massa_metrics,
+        engine,
     )));
 
     // define the input data interface
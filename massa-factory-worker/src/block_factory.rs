@@ -8,6 +8,7 @@ use massa_models::{
     block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
     block_id::BlockId,
     endorsement::SecureShareEndorsement,
+    operation::OperationId,
     prehash::PreHashSet,
     secure_share::SecureShareContent,
     slot::Slot,
@@ -189,12 +190,41 @@ impl BlockFactoryWorker {
         block_storage.extend(endo_storage);
 
         // gather operations and compute global operations hash
-        let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
+        let (mut op_ids, mut op_storage) = self.channels.pool.get_block_operations(&slot);
         if op_ids.len() > self.cfg.max_operations_per_block as usize {
             warn!("Too many operations returned");
             return;
         }
 
+        // leave out the operations whose type is not active at the slot, the block would be invalid
+        let slot_timestamp = get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            slot,
+        )
+        .expect("could not get block slot timestamp");
+        let inactive_op_ids: PreHashSet<OperationId> = {
+            let ops = op_storage.read_operations();
+            op_ids
+                .iter()
+                .filter(|op_id| {
+                    !self.mip_store.is_operation_type_active_at(
+                        &ops.get(op_id)
+                            .expect("could not retrieve operation")
+                            .content
+                            .op,
+                        slot_timestamp,
+                    )
+                })
+                .copied()
+                .collect()
+        };
+        if !inactive_op_ids.is_empty() {
+            op_ids.retain(|op_id| !inactive_op_ids.contains(op_id));
+            op_storage.drop_operation_refs(&inactive_op_ids);
+        }
+
         block_storage.extend(op_storage);
         let global_operations_hash = Hash::compute_from(
             &op_ids
@@ -156,6 +156,8 @@ impl TestFactory {
                         .send(Ok(Selection {
                             producer: producer_address,
                             endorsements: vec![producer_address; ENDORSEMENT_COUNT as usize],
+                            producer_owner: producer_address,
+                            endorsement_owners: vec![producer_address; ENDORSEMENT_COUNT as usize],
                         }))
                         .unwrap();
                 }
@@ -516,10 +516,14 @@ impl FinalState {
         self.pos_state
             .feed_cycle_state_hash(cycle, final_state_hash, only_use_xor);
 
+        let stake_delegation_version = self.get_stake_delegation_version_at_cycle_end(cycle);
         self.pos_state
-            .feed_selector(cycle.checked_add(2).ok_or_else(|| {
-                FinalStateError::PosError("cycle overflow when feeding selector".into())
-            })?)
+            .feed_selector(
+                cycle.checked_add(2).ok_or_else(|| {
+                    FinalStateError::PosError("cycle overflow when feeding selector".into())
+                })?,
+                stake_delegation_version,
+            )
             .map_err(|_| {
                 FinalStateError::PosError("cycle overflow when feeding selector".into())
             })?;
@@ -544,8 +548,11 @@ impl FinalState {
 
     /// Performs the initial draws.
     pub fn compute_initial_draws(&mut self) -> Result<(), FinalStateError> {
+        let (config, mip_store) = (&self.config, &self.mip_store);
         self.pos_state
-            .compute_initial_draws()
+            .compute_initial_draws(|cycle| {
+                stake_delegation_version_at_cycle_end(config, mip_store, cycle)
+            })
             .map_err(|err| FinalStateError::PosError(err.to_string()))
     }
 
@@ -572,8 +579,15 @@ impl FinalState {
 
         self.async_pool
             .apply_changes_to_batch(&changes.async_pool_changes, &mut db_batch);
+        let stake_delegation_version = self.get_stake_delegation_version(&slot);
         self.pos_state
-            .apply_changes_to_batch(changes.pos_changes.clone(), slot, true, &mut db_batch)
+            .apply_changes_to_batch(
+                changes.pos_changes.clone(),
+                slot,
+                true,
+                stake_delegation_version,
+                &mut db_batch,
+            )
             .expect("could not settle slot in final state proof-of-stake");
 
         // TODO:
@@ -750,4 +764,41 @@ impl FinalState {
         self.mip_store
             .get_latest_component_version_at(&MipComponent::FinalStateHashKind, ts)
     }
+
+    /// Get the version of the `StakeDelegation` MIP component at a given slot
+    pub fn get_stake_delegation_version(&self, slot: &Slot) -> u32 {
+        stake_delegation_version_at(&self.config, &self.mip_store, slot)
+    }
+
+    fn get_stake_delegation_version_at_cycle_end(&self, cycle: u64) -> u32 {
+        stake_delegation_version_at_cycle_end(&self.config, &self.mip_store, cycle)
+    }
+}
+
+/// Version of the `StakeDelegation` MIP component at a given slot
+fn stake_delegation_version_at(
+    config: &FinalStateConfig,
+    mip_store: &MipStore,
+    slot: &Slot,
+) -> u32 {
+    let ts = get_block_slot_timestamp(
+        config.thread_count,
+        config.t0,
+        config.genesis_timestamp,
+        *slot,
+    )
+    .unwrap();
+    mip_store.get_latest_component_version_at(&MipComponent::StakeDelegation, ts)
+}
+
+/// Version of the `StakeDelegation` MIP component at the last slot of a cycle,
+/// the one the draws fed when the cycle completes are made with
+fn stake_delegation_version_at_cycle_end(
+    config: &FinalStateConfig,
+    mip_store: &MipStore,
+    cycle: u64,
+) -> u32 {
+    Slot::new_last_of_cycle(cycle, config.periods_per_cycle, config.thread_count)
+        .map(|slot| stake_delegation_version_at(config, mip_store, &slot))
+        .unwrap_or(0)
 }
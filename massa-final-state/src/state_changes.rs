@@ -44,19 +44,16 @@ pub struct StateChangesSerializer {
     de_changes_serializer: ExecutedDenunciationsChangesSerializer,
 }
 
-impl Default for StateChangesSerializer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl StateChangesSerializer {
     /// Creates a `StateChangesSerializer`
-    pub fn new() -> Self {
+    ///
+    /// # Arguments
+    /// * `stake_delegation_version`: version of the `StakeDelegation` MIP component at the slot of the changes
+    pub fn new(stake_delegation_version: u32) -> Self {
         Self {
             ledger_changes_serializer: LedgerChangesSerializer::new(),
             async_pool_changes_serializer: AsyncPoolChangesSerializer::new(),
-            pos_changes_serializer: PoSChangesSerializer::new(),
+            pos_changes_serializer: PoSChangesSerializer::new(stake_delegation_version),
             ops_changes_serializer: ExecutedOpsChangesSerializer::new(),
             de_changes_serializer: ExecutedDenunciationsChangesSerializer::new(),
         }
@@ -110,7 +107,7 @@ impl Serializer<StateChanges> for StateChangesSerializer {
     /// );
     /// state_changes.ledger_changes = ledger_changes;
     /// let mut serialized = Vec::new();
-    /// StateChangesSerializer::new().serialize(&state_changes, &mut serialized).unwrap();
+    /// StateChangesSerializer::new(1).serialize(&state_changes, &mut serialized).unwrap();
     /// ```
     fn serialize(&self, value: &StateChanges, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
         self.ledger_changes_serializer
@@ -153,6 +150,7 @@ impl StateChangesDeserializer {
         max_ops_changes_length: u64,
         endorsement_count: u32,
         max_de_changes_length: u64,
+        stake_delegation_version: u32,
     ) -> Self {
         Self {
             ledger_changes_deserializer: LedgerChangesDeserializer::new(
@@ -172,6 +170,7 @@ impl StateChangesDeserializer {
                 max_rolls_length,
                 max_production_stats_length,
                 max_credits_length,
+                stake_delegation_version,
             ),
             ops_changes_deserializer: ExecutedOpsChangesDeserializer::new(
                 thread_count,
@@ -233,8 +232,8 @@ impl Deserializer<StateChanges> for StateChangesDeserializer {
     /// );
     /// state_changes.ledger_changes = ledger_changes;
     /// let mut serialized = Vec::new();
-    /// StateChangesSerializer::new().serialize(&state_changes, &mut serialized).unwrap();
-    /// let (rest, state_changes_deser) = StateChangesDeserializer::new(32, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 32, 1000).deserialize::<DeserializeError>(&serialized).unwrap();
+    /// StateChangesSerializer::new(1).serialize(&state_changes, &mut serialized).unwrap();
+    /// let (rest, state_changes_deser) = StateChangesDeserializer::new(32, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 32, 1000, 1).deserialize::<DeserializeError>(&serialized).unwrap();
     /// assert!(rest.is_empty());
     /// assert_eq!(state_changes_deser.ledger_changes, state_changes.ledger_changes);
     /// assert_eq!(state_changes_deser.async_pool_changes, state_changes.async_pool_changes);
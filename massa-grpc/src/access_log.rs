@@ -0,0 +1,276 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Access log of the gRPC API.
+//!
+//! A record is written for each call once its response is complete: method, client address,
+//! client certificate fingerprint when mTLS is enabled, time to the response headers,
+//! total duration (the lifetime of the stream for streaming calls) and gRPC status code.
+//! Records are logged under the `massa_grpc::access` target and, if a file is configured, appended to it as JSON lines.
+
+use crate::error::GrpcError;
+use futures_util::future::BoxFuture;
+use hyper::{HeaderMap, Request, Response};
+use massa_hash::Hash;
+use massa_time::MassaTime;
+use parking_lot::Mutex;
+use pin_project_lite::pin_project;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::time::Instant;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tower::{Layer, Service};
+use tracing::log::warn;
+
+/// One access log record
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AccessLogRecord {
+    /// time at which the call was received, in milliseconds since the UNIX epoch
+    pub(crate) timestamp: u64,
+    /// called method, as `/package.Service/Method`
+    pub(crate) method: String,
+    /// client address
+    pub(crate) peer: Option<SocketAddr>,
+    /// fingerprint of the client certificate
+    pub(crate) identity: Option<String>,
+    /// time to the response headers, in milliseconds
+    pub(crate) latency_ms: u64,
+    /// time to the end of the response, in milliseconds. For streams, the duration of the stream
+    pub(crate) duration_ms: u64,
+    /// HTTP status of the response
+    pub(crate) http_status: u16,
+    /// gRPC status code, missing if the call ended without status (client disconnection)
+    pub(crate) grpc_status: Option<i32>,
+}
+
+/// Where the records are written
+pub(crate) struct AccessLogSink {
+    /// optional JSON lines file
+    file: Option<Mutex<LineWriter<File>>>,
+}
+
+impl AccessLogSink {
+    /// Writes a record to the logs and to the file, if any
+    fn write(&self, record: &AccessLogRecord) {
+        tracing::info!(
+            target: "massa_grpc::access",
+            method = record.method.as_str(),
+            peer = ?record.peer,
+            identity = ?record.identity,
+            latency_ms = record.latency_ms,
+            duration_ms = record.duration_ms,
+            http_status = record.http_status,
+            grpc_status = ?record.grpc_status,
+            "gRPC call"
+        );
+        let Some(file) = &self.file else {
+            return;
+        };
+        match serde_json::to_string(record) {
+            Ok(line) => {
+                if let Err(e) = writeln!(file.lock(), "{}", line) {
+                    warn!("failed to write the gRPC access log: {}", e);
+                }
+            }
+            Err(e) => warn!("failed to serialize a gRPC access log record: {}", e),
+        }
+    }
+}
+
+/// Layer adding the access log to the gRPC services
+#[derive(Clone)]
+pub(crate) struct AccessLogLayer {
+    /// `None` if the access log is disabled
+    sink: Option<Arc<AccessLogSink>>,
+}
+
+impl AccessLogLayer {
+    /// Creates the layer, opening the access log file if one is given
+    pub(crate) fn new(enabled: bool, file: Option<&Path>) -> Result<Self, GrpcError> {
+        if !enabled {
+            return Ok(AccessLogLayer { sink: None });
+        }
+        let file = match file {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| {
+                        GrpcError::InternalServerError(format!(
+                            "failed to open the gRPC access log file {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })?;
+                Some(Mutex::new(LineWriter::new(file)))
+            }
+            None => None,
+        };
+        Ok(AccessLogLayer {
+            sink: Some(Arc::new(AccessLogSink { file })),
+        })
+    }
+}
+
+impl<S> Layer<S> for AccessLogLayer {
+    type Service = AccessLog<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessLog {
+            inner,
+            sink: self.sink.clone(),
+        }
+    }
+}
+
+/// Service writing an access log record for each call of the inner service
+#[derive(Clone)]
+pub(crate) struct AccessLog<S> {
+    /// inner service
+    inner: S,
+    /// `None` if the access log is disabled
+    sink: Option<Arc<AccessLogSink>>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for AccessLog<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<AccessLogBody<ResBody>>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let pending = self.sink.clone().map(|sink| PendingRecord {
+            record: request_record(&request),
+            start: Instant::now(),
+            sink,
+        });
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let response = future.await?;
+            let pending = pending.map(|mut pending| {
+                pending.record.latency_ms = pending.start.elapsed().as_millis() as u64;
+                pending.record.http_status = response.status().as_u16();
+                // errors returned before any message are sent in the headers
+                pending.record.grpc_status = grpc_status(response.headers());
+                pending
+            });
+            Ok(response.map(|inner| AccessLogBody { inner, pending }))
+        })
+    }
+}
+
+/// A record waiting for the end of its response
+struct PendingRecord {
+    /// record being filled
+    record: AccessLogRecord,
+    /// time at which the call was received
+    start: Instant,
+    /// where to write the record
+    sink: Arc<AccessLogSink>,
+}
+
+pin_project! {
+    /// Response body writing the access log record once the response is complete or dropped
+    pub(crate) struct AccessLogBody<B> {
+        #[pin]
+        inner: B,
+        pending: Option<PendingRecord>,
+    }
+
+    impl<B> PinnedDrop for AccessLogBody<B> {
+        fn drop(this: Pin<&mut Self>) {
+            if let Some(mut pending) = this.project().pending.take() {
+                pending.record.duration_ms = pending.start.elapsed().as_millis() as u64;
+                pending.sink.write(&pending.record);
+            }
+        }
+    }
+}
+
+impl<B: hyper::body::HttpBody> hyper::body::HttpBody for AccessLogBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        self.project().inner.poll_data(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let this = self.project();
+        let trailers = ready!(this.inner.poll_trailers(cx));
+        if let (Ok(Some(trailers)), Some(pending)) = (&trailers, this.pending.as_mut()) {
+            if let Some(status) = grpc_status(trailers) {
+                pending.record.grpc_status = Some(status);
+            }
+        }
+        Poll::Ready(trailers)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> hyper::body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Builds the record of a call from its request
+fn request_record<B>(request: &Request<B>) -> AccessLogRecord {
+    let (peer, identity) = match request.extensions().get::<TlsConnectInfo<TcpConnectInfo>>() {
+        Some(info) => (
+            info.get_ref().remote_addr(),
+            info.peer_certs().and_then(|certs| {
+                certs
+                    .first()
+                    .map(|cert| Hash::compute_from(cert.as_ref()).to_string())
+            }),
+        ),
+        None => (
+            request
+                .extensions()
+                .get::<TcpConnectInfo>()
+                .and_then(|info| info.remote_addr()),
+            None,
+        ),
+    };
+    AccessLogRecord {
+        timestamp: MassaTime::now()
+            .map(|now| now.to_millis())
+            .unwrap_or_default(),
+        method: request.uri().path().to_string(),
+        peer,
+        identity,
+        latency_ms: 0,
+        duration_ms: 0,
+        http_status: 0,
+        grpc_status: None,
+    }
+}
+
+/// Reads the gRPC status code of headers or trailers
+fn grpc_status(headers: &HeaderMap) -> Option<i32> {
+    headers
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
@@ -3,16 +3,24 @@
 use crate::error::GrpcError;
 use crate::server::MassaGrpc;
 use itertools::izip;
+use massa_execution_exports::{
+    ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ReadOnlyLedgerOverride,
+};
 use massa_models::address::Address;
-use massa_models::block::BlockGraphStatus;
-use massa_models::block_id::BlockId;
+use massa_models::amount::Amount;
+use massa_models::block::{BlockFinalityStatus, BlockGraphStatus};
+use massa_models::block_id::{BlockId, BLOCK_ID_SIZE_BYTES};
+use massa_models::bytecode::Bytecode;
 use massa_models::execution::EventFilter;
-use massa_models::operation::{OperationId, SecureShareOperation};
+use massa_models::operation::{OperationId, SecureShareOperation, OPERATION_ID_SIZE_BYTES};
 use massa_models::prehash::PreHashSet;
-use massa_models::slot::Slot;
+use massa_models::slot::{Slot, SLOT_KEY_SIZE};
 use massa_models::timeslots::{self, get_latest_block_slot_at_timestamp};
+use massa_pool_exports::FeeEstimate;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::str::FromStr;
 use tracing::log::warn;
@@ -22,6 +30,81 @@ const DEFAULT_OFFSET: u64 = 1;
 /// Default limit
 const DEFAULT_LIMIT: u64 = 50;
 
+/// Execute a read-only smart contract function call
+pub(crate) fn execute_read_only_call(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
+) -> Result<grpc_api::ExecuteReadOnlyCallResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let call = inner_req
+        .call
+        .ok_or_else(|| GrpcError::InvalidArgument("call is missing".to_string()))?;
+
+    let target_address = Address::from_str(&call.target_address)?;
+    // impersonate the requested caller, or use a random address
+    let caller_address = match call.caller_address {
+        Some(caller_address) => Address::from_str(&caller_address)?,
+        None => {
+            let keypair =
+                KeyPair::generate(0).map_err(|e| GrpcError::InternalServerError(e.to_string()))?;
+            Address::from_public_key(&keypair.get_public_key())
+        }
+    };
+    let coins = Amount::from_raw(call.coins.unwrap_or_default());
+
+    let ledger_overrides = call
+        .ledger_overrides
+        .into_iter()
+        .map(|ledger_override| {
+            Ok(ReadOnlyLedgerOverride {
+                address: Address::from_str(&ledger_override.address)?,
+                balance: ledger_override.balance.map(Amount::from_raw),
+                bytecode: ledger_override.bytecode.map(Bytecode),
+                datastore: ledger_override
+                    .datastore
+                    .into_iter()
+                    .map(|entry| (entry.key, entry.value))
+                    .collect(),
+            })
+        })
+        .collect::<Result<Vec<_>, GrpcError>>()?;
+
+    let req = ReadOnlyExecutionRequest {
+        max_gas: call.max_gas,
+        call_stack: vec![
+            ExecutionStackElement {
+                address: caller_address,
+                coins: Default::default(),
+                owned_addresses: vec![caller_address],
+                operation_datastore: None,
+                reentrancy_locked: false,
+            },
+            ExecutionStackElement {
+                address: target_address,
+                coins,
+                owned_addresses: vec![target_address],
+                operation_datastore: None,
+                reentrancy_locked: false,
+            },
+        ],
+        target: ReadOnlyExecutionTarget::FunctionCall {
+            target_addr: target_address,
+            target_func: call.target_function,
+            parameter: call.parameter,
+        },
+        fee: Amount::from_raw(call.fee.unwrap_or_default()),
+        is_final: call.is_final,
+        ledger_overrides,
+    };
+
+    let output = grpc.execution_controller.execute_readonly_request(req)?;
+
+    Ok(grpc_api::ExecuteReadOnlyCallResponse {
+        id: inner_req.id,
+        output: Some(output.into()),
+    })
+}
+
 /// Get blocks
 pub(crate) fn get_blocks(
     grpc: &MassaGrpc,
@@ -69,6 +152,11 @@ pub(crate) fn get_blocks(
         slot: Some(current_slot.into()),
     });
 
+    let cursor = inner_req
+        .cursor
+        .map(|cursor| decode_block_cursor(&cursor))
+        .transpose()?;
+
     let storage = grpc.storage.clone_without_refs();
     let blocks = blocks_ids
         .into_iter()
@@ -78,6 +166,7 @@ pub(crate) fn get_blocks(
             } else {
                 return None;
             };
+            let slot = content.header.content.slot;
 
             if let Some(graph_status) = grpc
                 .consensus_controller
@@ -102,21 +191,28 @@ pub(crate) fn get_blocks(
                     status.push(grpc_model::BlockStatus::Discarded.into());
                 };
 
-                return Some(grpc_model::BlockWrapper {
-                    id: id.to_string(),
-                    block: Some(content.into()),
-                    status,
-                });
+                return Some((
+                    (slot, id),
+                    grpc_model::BlockWrapper {
+                        id: id.to_string(),
+                        block: Some(content.into()),
+                        status,
+                    },
+                ));
             }
 
             None
         })
-        .collect::<Vec<grpc_model::BlockWrapper>>();
+        .collect::<Vec<((Slot, BlockId), grpc_model::BlockWrapper)>>();
+
+    // Blocks are returned ordered by slot then by id
+    let (blocks, next_cursor) = paginate(blocks, cursor.as_ref(), inner_req.limit);
 
     Ok(grpc_api::GetBlocksResponse {
         id: inner_req.id,
         context,
         blocks,
+        next_cursor: next_cursor.map(|cursor| encode_block_cursor(&cursor)),
     })
 }
 
@@ -164,6 +260,49 @@ pub(crate) fn get_blocks_by_slots(
     })
 }
 
+/// Get the best parents of the next block and the maximal cliques of compatible blocks.
+/// The blockclique, the clique of highest fitness, comes first.
+pub(crate) fn get_cliques(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::GetCliquesRequest>,
+) -> Result<grpc_api::GetCliquesResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let best_parents = grpc
+        .consensus_controller
+        .get_best_parents()
+        .into_iter()
+        .map(|(block_id, period)| grpc_api::BlockParent {
+            block_id: block_id.to_string(),
+            period,
+        })
+        .collect();
+
+    let mut cliques = grpc.consensus_controller.get_cliques();
+    cliques.sort_by_key(|clique| (!clique.is_blockclique, std::cmp::Reverse(clique.fitness)));
+    let cliques = cliques
+        .into_iter()
+        .map(|clique| {
+            let mut block_ids: Vec<String> = clique
+                .block_ids
+                .iter()
+                .map(|block_id| block_id.to_string())
+                .collect();
+            block_ids.sort_unstable();
+            grpc_api::Clique {
+                block_ids,
+                fitness: clique.fitness,
+                is_blockclique: clique.is_blockclique,
+            }
+        })
+        .collect();
+
+    Ok(grpc_api::GetCliquesResponse {
+        id: inner_req.id,
+        best_parents,
+        cliques,
+    })
+}
+
 /// Get multiple datastore entries
 pub(crate) fn get_datastore_entries(
     grpc: &MassaGrpc,
@@ -196,6 +335,177 @@ pub(crate) fn get_datastore_entries(
     Ok(grpc_api::GetDatastoreEntriesResponse { id, entries })
 }
 
+/// Get the Merkle inclusion proof of an event emitted at a given slot
+pub(crate) fn get_event_proof(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::GetEventProofRequest>,
+) -> Result<grpc_api::GetEventProofResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let slot: Slot = inner_req
+        .slot
+        .ok_or_else(|| GrpcError::InvalidArgument("slot is missing".to_string()))?
+        .into();
+    if slot.thread >= grpc.grpc_config.thread_count {
+        return Err(GrpcError::InvalidArgument(format!(
+            "invalid thread in slot {}",
+            slot
+        )));
+    }
+
+    let event_proof = grpc
+        .execution_controller
+        .get_event_proof(slot, inner_req.index_in_slot)
+        .ok_or_else(|| {
+            GrpcError::InvalidArgument(format!(
+                "event {} of slot {} is not available",
+                inner_req.index_in_slot, slot
+            ))
+        })?;
+
+    Ok(grpc_api::GetEventProofResponse {
+        id: inner_req.id,
+        event: Some(event_proof.event.into()),
+        events_merkle_root: event_proof.events_merkle_root.to_string(),
+        leaf_index: event_proof.proof.leaf_index,
+        leaf_count: event_proof.proof.leaf_count,
+        siblings: event_proof
+            .proof
+            .siblings
+            .iter()
+            .map(|sibling| sibling.to_string())
+            .collect(),
+    })
+}
+
+/// Suggest a fee for an operation to be included within a target number of slots,
+/// from the fees of the operations included in the blocks kept in the graph and the pool composition
+pub(crate) fn get_fee_estimate(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::GetFeeEstimateRequest>,
+) -> Result<grpc_api::GetFeeEstimateResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    if inner_req.target_inclusion_slots == 0 {
+        return Err(GrpcError::InvalidArgument(
+            "target_inclusion_slots must be at least 1".to_string(),
+        ));
+    }
+
+    let graph = grpc
+        .consensus_controller
+        .get_block_graph_status(None, None)?;
+    let included_fees: Vec<Amount> = {
+        let blocks = grpc.storage.read_blocks();
+        let operations = grpc.storage.read_operations();
+        graph
+            .active_blocks
+            .keys()
+            .filter_map(|block_id| blocks.get(block_id))
+            .flat_map(|block| block.content.operations.iter())
+            .filter_map(|op_id| operations.get(op_id))
+            .map(|op| op.content.fee)
+            .collect()
+    };
+    let recent_operation_count = included_fees.len() as u64;
+    let pool_fee = grpc
+        .pool_command_sender
+        .get_operation_inclusion_fee(inner_req.target_inclusion_slots);
+    let estimate = FeeEstimate::new(pool_fee, included_fees);
+
+    Ok(grpc_api::GetFeeEstimateResponse {
+        id: inner_req.id,
+        fee: estimate.fee.to_raw(),
+        pool_fee: estimate.pool_fee.map(|fee| fee.to_raw()),
+        pool_operation_count: grpc.pool_command_sender.get_operation_count() as u64,
+        recent_median_fee: estimate.recent_median_fee.map(|fee| fee.to_raw()),
+        recent_operation_count,
+    })
+}
+
+/// Get the finality of blocks: their status, the number of blocks confirming them and the fitness they still miss to become final
+pub(crate) fn get_blocks_finality(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::GetBlocksFinalityRequest>,
+) -> Result<grpc_api::GetBlocksFinalityResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    if inner_req.block_ids.len() as u32 > grpc.grpc_config.max_block_ids_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many block ids received. Only a maximum of {} block ids are accepted per request",
+            grpc.grpc_config.max_block_ids_per_request
+        )));
+    }
+    let block_ids: Vec<BlockId> = inner_req
+        .block_ids
+        .iter()
+        .map(|id| {
+            BlockId::from_str(id)
+                .map_err(|_| GrpcError::InvalidArgument(format!("invalid block id: {}", id)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let finalities = block_ids
+        .into_iter()
+        .map(|block_id| {
+            let mut finality = grpc_model::BlockFinality {
+                block_id: block_id.to_string(),
+                ..Default::default()
+            };
+            if let Some(block_finality) = grpc.consensus_controller.get_block_status(&block_id) {
+                let status = match block_finality.status {
+                    BlockFinalityStatus::Candidate => grpc_model::BlockStatus::Candidate,
+                    BlockFinalityStatus::InBlockclique => grpc_model::BlockStatus::InBlockclique,
+                    BlockFinalityStatus::Final => grpc_model::BlockStatus::Final,
+                    BlockFinalityStatus::Discarded { reason } => {
+                        finality.discard_reason = Some(reason);
+                        grpc_model::BlockStatus::Discarded
+                    }
+                };
+                finality.status = status.into();
+                finality.confirmations = block_finality.confirmations;
+                finality.fitness_margin = block_finality.fitness_margin;
+            }
+            finality
+        })
+        .collect();
+
+    Ok(grpc_api::GetBlocksFinalityResponse {
+        id: inner_req.id,
+        finalities,
+    })
+}
+
+/// Get the final ledger entry of an address with the proof of its values against the final state hash
+pub(crate) fn get_ledger_entry_proof(
+    grpc: &MassaGrpc,
+    request: tonic::Request<grpc_api::GetLedgerEntryProofRequest>,
+) -> Result<grpc_api::GetLedgerEntryProofResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let address = Address::from_str(&inner_req.address)?;
+    if inner_req.keys.len() as u32 > grpc.grpc_config.max_datastore_entries_per_request {
+        return Err(GrpcError::InvalidArgument(format!(
+            "too many keys received. Only a maximum of {} keys are accepted per request",
+            grpc.grpc_config.max_datastore_entries_per_request
+        )));
+    }
+
+    let entry_proof = grpc
+        .execution_controller
+        .get_ledger_entry_proof(&address, &inner_req.keys)?;
+
+    Ok(grpc_api::GetLedgerEntryProofResponse {
+        id: inner_req.id,
+        address: address.to_string(),
+        slot: Some(entry_proof.slot.into()),
+        final_state_hash: entry_proof.state_hash.to_string(),
+        balance: Some(entry_proof.balance.into()),
+        bytecode: Some(entry_proof.bytecode.into()),
+        datastore: entry_proof
+            .datastore
+            .into_iter()
+            .map(|key_proof| key_proof.into())
+            .collect(),
+    })
+}
+
 /// Get the largest stakers
 pub(crate) fn get_largest_stakers(
     grpc: &MassaGrpc,
@@ -394,8 +704,13 @@ pub(crate) fn get_operations(
         slot: Some(current_slot.into()),
     });
 
+    let cursor = inner_req
+        .cursor
+        .map(|cursor| decode_operation_cursor(&cursor))
+        .transpose()?;
+
     // Get the operations and the list of blocks that contain them from storage
-    let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
+    let storage_info: Vec<(OperationId, (SecureShareOperation, PreHashSet<BlockId>))> = {
         let read_blocks = storage.read_blocks();
         let read_ops = storage.read_operations();
         operations_ids
@@ -403,17 +718,23 @@ pub(crate) fn get_operations(
             .filter_map(|id| {
                 read_ops.get(id).cloned().map(|op| {
                     (
-                        op,
-                        read_blocks
-                            .get_blocks_by_operation(id)
-                            .cloned()
-                            .unwrap_or_default(),
+                        *id,
+                        (
+                            op,
+                            read_blocks
+                                .get_blocks_by_operation(id)
+                                .cloned()
+                                .unwrap_or_default(),
+                        ),
                     )
                 })
             })
             .collect()
     };
 
+    // Operations are returned ordered by id
+    let (storage_info, next_cursor) = paginate(storage_info, cursor.as_ref(), inner_req.limit);
+
     // Keep only the ops id (found in storage)
     let ops: Vec<OperationId> = storage_info.iter().map(|(op, _)| op.id).collect();
 
@@ -476,6 +797,7 @@ pub(crate) fn get_operations(
         id,
         context,
         operations,
+        next_cursor: next_cursor.map(|cursor| cursor.to_bytes().to_vec()),
     })
 }
 
@@ -635,3 +957,55 @@ pub(crate) fn get_version(
         version: grpc.version.to_string(),
     })
 }
+
+/// Sorts the items by key, skips the ones up to the cursor (included) and keeps at most `limit` of them.
+/// Returns the page and, if more items follow, the key of its last item to use as the next cursor.
+fn paginate<K: Ord + Clone, T>(
+    mut items: Vec<(K, T)>,
+    cursor: Option<&K>,
+    limit: Option<u32>,
+) -> (Vec<T>, Option<K>) {
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    items.dedup_by(|(a, _), (b, _)| a == b);
+    if let Some(cursor) = cursor {
+        items.retain(|(key, _)| key > cursor);
+    }
+    let limit = limit.map_or(items.len(), |limit| limit as usize);
+    let next_cursor = if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(|(key, _)| key.clone())
+    } else {
+        None
+    };
+    (
+        items.into_iter().map(|(_, item)| item).collect(),
+        next_cursor,
+    )
+}
+
+/// Encodes a blocks page cursor: the slot key followed by the block id
+fn encode_block_cursor((slot, block_id): &(Slot, BlockId)) -> Vec<u8> {
+    let mut cursor = slot.to_bytes_key().to_vec();
+    cursor.extend_from_slice(block_id.to_bytes());
+    cursor
+}
+
+/// Decodes a blocks page cursor
+fn decode_block_cursor(cursor: &[u8]) -> Result<(Slot, BlockId), GrpcError> {
+    let invalid_cursor = || GrpcError::InvalidArgument("invalid cursor".to_string());
+    if cursor.len() != SLOT_KEY_SIZE + BLOCK_ID_SIZE_BYTES {
+        return Err(invalid_cursor());
+    }
+    let (slot_key, block_id) = cursor.split_at(SLOT_KEY_SIZE);
+    let slot = Slot::from_bytes_key(slot_key.try_into().map_err(|_| invalid_cursor())?);
+    let block_id = BlockId::from_bytes(block_id.try_into().map_err(|_| invalid_cursor())?);
+    Ok((slot, block_id))
+}
+
+/// Decodes an operations page cursor
+fn decode_operation_cursor(cursor: &[u8]) -> Result<OperationId, GrpcError> {
+    let operation_id: &[u8; OPERATION_ID_SIZE_BYTES] = cursor
+        .try_into()
+        .map_err(|_| GrpcError::InvalidArgument("invalid cursor".to_string()))?;
+    Ok(OperationId::from_bytes(operation_id))
+}
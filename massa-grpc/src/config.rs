@@ -22,13 +22,16 @@ pub struct GrpcConfig {
     pub enable_mtls: bool,
     /// bind for the Massa gRPC API
     pub bind: SocketAddr,
+    /// bind for the private gRPC API managing the node, not served if none
+    pub private_bind: Option<SocketAddr>,
     /// which compression encodings does the server accept for requests
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
     pub send_compressed: Option<String>,
     /// limits the maximum size of a decoded message. Defaults to 4MB
     pub max_decoding_message_size: usize,
-    /// limits the maximum size of an encoded message. Defaults to 4MB
+    /// limits the maximum size of an encoded message. Defaults to 4MB.
+    /// Larger unary responses fail with a `resource_exhausted` status, larger stream items are split over several messages
     pub max_encoding_message_size: usize,
     /// set the concurrency limit applied to on requests inbound per connection. Defaults to 32
     pub concurrency_limit_per_connection: usize,
@@ -92,6 +95,20 @@ pub struct GrpcConfig {
     pub max_block_ids_per_request: u32,
     /// max number of operation ids that can be included in a single request
     pub max_operation_ids_per_request: u32,
+    /// max number of datastore entries returned for a single datastore entries stream request
+    pub max_datastore_entries_per_request: u32,
+    /// maximum number of operations followed at the same time by a SubmitOperations stream
+    pub max_tracked_operations_per_stream: usize,
+    /// what to do when a client does not read the messages of a subscription stream fast enough
+    pub stream_backpressure_policy: StreamBackpressurePolicy,
+    /// whether to serve the peer events stream, which exposes the ips of the peers
+    pub enable_peer_events_stream: bool,
+    /// whether to write an access log record for each call
+    pub enable_access_log: bool,
+    /// optional file to which the access log records are appended as JSON lines
+    pub access_log_file: Option<PathBuf>,
+    /// time given to the active streams to flush and end when the node shuts down
+    pub shutdown_drain_timeout: MassaTime,
     /// server certificate path
     pub server_certificate_path: PathBuf,
     /// server private key path
@@ -99,3 +116,17 @@ pub struct GrpcConfig {
     /// client certificate authority root path
     pub client_certificate_authority_root_path: PathBuf,
 }
+
+/// Policy applied to a stream whose client does not read the messages fast enough
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamBackpressurePolicy {
+    /// wait until the client reads the queued messages, delaying the new ones
+    Block,
+    /// drop the oldest queued message to make room for the new one
+    DropOldest,
+    /// drop the new message
+    DropNewest,
+    /// close the stream
+    Disconnect,
+}
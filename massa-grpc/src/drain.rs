@@ -0,0 +1,210 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use futures_util::StreamExt;
+use massa_api_exports::node::GrpcDrainStatus;
+use massa_time::MassaTime;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tonic::codegen::futures_core;
+use tracing::log::info;
+
+/// Interval at which the drain progress is checked while waiting for it to complete
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Drain parameters, set once when the drain is started
+#[derive(Debug, Clone, Copy)]
+struct DrainInfo {
+    /// drain start time
+    started_at: MassaTime,
+    /// time given to the running streams to finish
+    grace_period: MassaTime,
+}
+
+/// Used to put the gRPC API in drain mode and to follow the drain progress.
+///
+/// Once draining, new requests and streams are rejected with `UNAVAILABLE`,
+/// ongoing unary requests complete normally and ongoing streams are closed
+/// with a final `UNAVAILABLE` status when the grace period is over.
+#[derive(Debug, Clone)]
+pub struct DrainController {
+    /// drain parameters, none while serving normally
+    drain_sender: Arc<watch::Sender<Option<DrainInfo>>>,
+    /// number of requests and streams currently served
+    active_requests: Arc<AtomicUsize>,
+}
+
+impl Default for DrainController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrainController {
+    /// Creates a new controller, not draining
+    pub fn new() -> Self {
+        let (drain_sender, _) = watch::channel(None);
+        DrainController {
+            drain_sender: Arc::new(drain_sender),
+            active_requests: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Starts draining the gRPC API.
+    /// Calling it while already draining keeps the original drain parameters.
+    pub fn start_drain(&self, grace_period: MassaTime) -> Result<GrpcDrainStatus, GrpcError> {
+        let started_at = MassaTime::now()?;
+        let started = self.drain_sender.send_if_modified(|drain| {
+            if drain.is_some() {
+                return false;
+            }
+            *drain = Some(DrainInfo {
+                started_at,
+                grace_period,
+            });
+            true
+        });
+        if started {
+            info!(
+                "gRPC API entering drain mode with a grace period of {} ms",
+                grace_period.to_millis()
+            );
+        }
+        self.status()
+    }
+
+    /// Returns true if the gRPC API is draining
+    pub fn is_draining(&self) -> bool {
+        self.drain_sender.borrow().is_some()
+    }
+
+    /// Resolves once the drain is started
+    pub(crate) async fn drain_started(&self) {
+        let mut drain_receiver = self.drain_sender.subscribe();
+        while drain_receiver.borrow_and_update().is_none() {
+            if drain_receiver.changed().await.is_err() {
+                return std::future::pending().await;
+            }
+        }
+    }
+
+    /// Resolves once the drain is complete: no request or stream is active anymore, or the grace period is over
+    pub async fn wait_drained(&self) -> Result<GrpcDrainStatus, GrpcError> {
+        loop {
+            let status = self.status()?;
+            if status.drained {
+                return Ok(status);
+            }
+            tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Returns the drain progress
+    pub fn status(&self) -> Result<GrpcDrainStatus, GrpcError> {
+        let active_requests = self.active_requests.load(Ordering::Acquire);
+        let drain = *self.drain_sender.borrow();
+        let Some(drain) = drain else {
+            return Ok(GrpcDrainStatus {
+                draining: false,
+                active_requests,
+                elapsed: None,
+                remaining: None,
+                drained: false,
+            });
+        };
+        let elapsed = MassaTime::now()?.saturating_sub(drain.started_at);
+        let remaining = drain.grace_period.saturating_sub(elapsed);
+        Ok(GrpcDrainStatus {
+            draining: true,
+            active_requests,
+            elapsed: Some(elapsed),
+            remaining: Some(remaining),
+            drained: active_requests == 0 || remaining == MassaTime::from_millis(0),
+        })
+    }
+
+    /// Registers a new request or stream, fails if the gRPC API is draining.
+    /// The request is considered active until the returned guard is dropped.
+    pub(crate) fn enter(&self) -> Result<DrainGuard, GrpcError> {
+        if self.is_draining() {
+            return Err(GrpcError::Unavailable(
+                "the gRPC API is draining and does not accept new requests".to_string(),
+            ));
+        }
+        self.active_requests.fetch_add(1, Ordering::AcqRel);
+        Ok(DrainGuard {
+            active_requests: self.active_requests.clone(),
+        })
+    }
+
+    /// Wraps an outgoing stream so that it is counted as active until dropped
+    /// and ends with a final `UNAVAILABLE` status once the drain grace period is over.
+    /// The messages already queued for the client are sent until then.
+    pub(crate) fn wrap_stream<T: Send + 'static>(
+        &self,
+        guard: DrainGuard,
+        stream: Pin<
+            Box<dyn futures_core::Stream<Item = Result<T, tonic::Status>> + Send + 'static>,
+        >,
+    ) -> Pin<Box<dyn futures_core::Stream<Item = Result<T, tonic::Status>> + Send + 'static>> {
+        let closed_by_drain = Arc::new(AtomicBool::new(false));
+        let grace_period_over = {
+            let closed_by_drain = closed_by_drain.clone();
+            let drain_receiver = self.drain_sender.subscribe();
+            async move {
+                grace_period_elapsed(drain_receiver).await;
+                closed_by_drain.store(true, Ordering::Release);
+            }
+        };
+        let final_status = futures_util::stream::once(async move {
+            closed_by_drain.load(Ordering::Acquire).then(|| {
+                Err(tonic::Status::unavailable(
+                    "the gRPC API is draining, the stream is closed",
+                ))
+            })
+        })
+        .filter_map(std::future::ready);
+        Box::pin(
+            stream
+                .take_until(grace_period_over)
+                .chain(final_status)
+                .map(move |item| {
+                    // keep the guard alive as long as the stream
+                    let _ = &guard;
+                    item
+                }),
+        )
+    }
+}
+
+/// Resolves once a drain is started and its grace period is over
+async fn grace_period_elapsed(mut drain_receiver: watch::Receiver<Option<DrainInfo>>) {
+    let drain = loop {
+        if let Some(drain) = *drain_receiver.borrow_and_update() {
+            break drain;
+        }
+        if drain_receiver.changed().await.is_err() {
+            // the controller is gone, the drain can no longer be started
+            return std::future::pending().await;
+        }
+    };
+    let deadline = drain.started_at.saturating_add(drain.grace_period);
+    let now = MassaTime::now().unwrap_or(deadline);
+    tokio::time::sleep(deadline.saturating_sub(now).to_duration()).await;
+}
+
+/// Marks a request or stream as active while alive
+#[derive(Debug)]
+pub(crate) struct DrainGuard {
+    /// shared active requests counter
+    active_requests: Arc<AtomicUsize>,
+}
+
+impl Drop for DrainGuard {
+    fn drop(&mut self) {
+        self.active_requests.fetch_sub(1, Ordering::AcqRel);
+    }
+}
@@ -37,6 +37,10 @@ pub enum GrpcError {
     InternalServerError(String),
     /// Invalid argument error: {0}
     InvalidArgument(String),
+    /// Unavailable error: {0}
+    Unavailable(String),
+    /// Message too large error: {0}
+    MessageTooLarge(String),
 }
 
 impl From<GrpcError> for tonic::Status {
@@ -53,6 +57,8 @@ impl From<GrpcError> for tonic::Status {
             GrpcError::InternalServerError(e) => tonic::Status::internal(e),
             GrpcError::ReflectionError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::InvalidArgument(e) => tonic::Status::invalid_argument(e),
+            GrpcError::Unavailable(e) => tonic::Status::unavailable(e),
+            GrpcError::MessageTooLarge(e) => tonic::Status::resource_exhausted(e),
         }
     }
 }
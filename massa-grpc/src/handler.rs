@@ -3,32 +3,68 @@
 use massa_proto_rs::massa::api::v1 as grpc_api;
 
 use crate::api::{
-    get_blocks, get_blocks_by_slots, get_datastore_entries, get_largest_stakers, get_mip_status,
-    get_next_block_best_parents, get_operations, get_sc_execution_events, get_selector_draws,
-    get_transactions_throughput, get_version,
+    execute_read_only_call, get_blocks, get_blocks_by_slots, get_blocks_finality, get_cliques,
+    get_datastore_entries, get_event_proof, get_fee_estimate, get_largest_stakers,
+    get_ledger_entry_proof, get_mip_status, get_next_block_best_parents, get_operations,
+    get_sc_execution_events, get_selector_draws, get_transactions_throughput, get_version,
 };
-use crate::server::MassaGrpc;
+use crate::metrics::{measure_request, measure_stream};
+use crate::private::{
+    ban_nodes_by_ids, ban_nodes_by_ips, get_bans, unban_nodes_by_ids, unban_nodes_by_ips,
+};
+use crate::server::{MassaGrpc, MassaPrivateGrpc};
+use crate::size::check_response_size;
 use crate::stream::{
+    datastore_entries::{datastore_entries, DatastoreEntriesStreamType},
     new_blocks::{new_blocks, NewBlocksStreamType},
     new_blocks_headers::{new_blocks_headers, NewBlocksHeadersStreamType},
+    new_denunciations::{new_denunciations, NewDenunciationsStreamType},
     new_endorsements::{new_endorsements, NewEndorsementsStreamType},
     new_filled_blocks::{new_filled_blocks, NewFilledBlocksStreamType},
     new_operations::{new_operations, NewOperationsStreamType},
+    new_peer_events::{new_peer_events, NewPeerEventsStreamType},
+    new_reorgs::{new_reorgs, NewReorgsStreamType},
     new_slot_execution_outputs::{new_slot_execution_outputs, NewSlotExecutionOutputsStreamType},
+    replay_blocks::{replay_blocks, ReplayBlocksStreamType},
     send_blocks::{send_blocks, SendBlocksStreamType},
     send_endorsements::{send_endorsements, SendEndorsementsStreamType},
     send_operations::{send_operations, SendOperationsStreamType},
+    submit_operations::{submit_operations, SubmitOperationsStreamType},
     tx_throughput::{transactions_throughput, TransactionsThroughputStreamType},
 };
 
 #[tonic::async_trait]
 impl grpc_api::massa_service_server::MassaService for MassaGrpc {
+    /// handler for execute read-only call
+    async fn execute_read_only_call(
+        &self,
+        request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
+    ) -> Result<tonic::Response<grpc_api::ExecuteReadOnlyCallResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "execute_read_only_call",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "execute_read_only_call",
+                    execute_read_only_call(self, request),
+                )
+            },
+        )?))
+    }
+
     /// handler for get blocks
     async fn get_blocks(
         &self,
         request: tonic::Request<grpc_api::GetBlocksRequest>,
     ) -> Result<tonic::Response<grpc_api::GetBlocksResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_blocks(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_blocks",
+            || check_response_size(&self.grpc_config, "get_blocks", get_blocks(self, request)),
+        )?))
     }
 
     /// handler for get blocks by slots
@@ -36,7 +72,31 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetBlocksBySlotsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetBlocksBySlotsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_blocks_by_slots(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_blocks_by_slots",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_blocks_by_slots",
+                    get_blocks_by_slots(self, request),
+                )
+            },
+        )?))
+    }
+
+    /// handler for get best parents and cliques
+    async fn get_cliques(
+        &self,
+        request: tonic::Request<grpc_api::GetCliquesRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetCliquesResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_cliques",
+            || check_response_size(&self.grpc_config, "get_cliques", get_cliques(self, request)),
+        )?))
     }
 
     /// handler for get multiple datastore entries
@@ -44,7 +104,75 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetDatastoreEntriesRequest>,
     ) -> Result<tonic::Response<grpc_api::GetDatastoreEntriesResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_datastore_entries(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_datastore_entries",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_datastore_entries",
+                    get_datastore_entries(self, request),
+                )
+            },
+        )?))
+    }
+
+    /// handler for get event proof
+    async fn get_event_proof(
+        &self,
+        request: tonic::Request<grpc_api::GetEventProofRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetEventProofResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_event_proof",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_event_proof",
+                    get_event_proof(self, request),
+                )
+            },
+        )?))
+    }
+
+    /// handler for get blocks finality
+    async fn get_blocks_finality(
+        &self,
+        request: tonic::Request<grpc_api::GetBlocksFinalityRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetBlocksFinalityResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_blocks_finality",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_blocks_finality",
+                    get_blocks_finality(self, request),
+                )
+            },
+        )?))
+    }
+
+    /// handler for get fee estimate
+    async fn get_fee_estimate(
+        &self,
+        request: tonic::Request<grpc_api::GetFeeEstimateRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetFeeEstimateResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_fee_estimate",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_fee_estimate",
+                    get_fee_estimate(self, request),
+                )
+            },
+        )?))
     }
 
     /// handler for get largest stakers
@@ -52,7 +180,37 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetLargestStakersRequest>,
     ) -> Result<tonic::Response<grpc_api::GetLargestStakersResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_largest_stakers(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_largest_stakers",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_largest_stakers",
+                    get_largest_stakers(self, request),
+                )
+            },
+        )?))
+    }
+
+    /// handler for get ledger entry proof
+    async fn get_ledger_entry_proof(
+        &self,
+        request: tonic::Request<grpc_api::GetLedgerEntryProofRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetLedgerEntryProofResponse>, tonic::Status> {
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_ledger_entry_proof",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_ledger_entry_proof",
+                    get_ledger_entry_proof(self, request),
+                )
+            },
+        )?))
     }
 
     /// handler for get mip status (versioning)
@@ -60,7 +218,18 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetMipStatusRequest>,
     ) -> Result<tonic::Response<grpc_api::GetMipStatusResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_mip_status(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_mip_status",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_mip_status",
+                    get_mip_status(self, request),
+                )
+            },
+        )?))
     }
 
     /// handler for get next block best parents
@@ -68,8 +237,17 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetNextBlockBestParentsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetNextBlockBestParentsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_next_block_best_parents(
-            self, request,
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_next_block_best_parents",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_next_block_best_parents",
+                    get_next_block_best_parents(self, request),
+                )
+            },
         )?))
     }
 
@@ -78,7 +256,18 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetOperationsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetOperationsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_operations(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_operations",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_operations",
+                    get_operations(self, request),
+                )
+            },
+        )?))
     }
 
     /// handler for get smart contract execution events
@@ -86,8 +275,17 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetScExecutionEventsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_sc_execution_events(
-            self, request,
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_sc_execution_events",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_sc_execution_events",
+                    get_sc_execution_events(self, request),
+                )
+            },
         )?))
     }
 
@@ -96,7 +294,18 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetSelectorDrawsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetSelectorDrawsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_selector_draws(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_selector_draws",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_selector_draws",
+                    get_selector_draws(self, request),
+                )
+            },
+        )?))
     }
 
     /// handler for get transactions throughput
@@ -104,8 +313,17 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetTransactionsThroughputRequest>,
     ) -> Result<tonic::Response<grpc_api::GetTransactionsThroughputResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_transactions_throughput(
-            self, request,
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_transactions_throughput",
+            || {
+                check_response_size(
+                    &self.grpc_config,
+                    "get_transactions_throughput",
+                    get_transactions_throughput(self, request),
+                )
+            },
         )?))
     }
 
@@ -114,7 +332,12 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<grpc_api::GetVersionRequest>,
     ) -> Result<tonic::Response<grpc_api::GetVersionResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_version(self, request)?))
+        let _guard = self.drain_controller.enter()?;
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_version",
+            || check_response_size(&self.grpc_config, "get_version", get_version(self, request)),
+        )?))
     }
 
     // ███████╗████████╗██████╗ ███████╗ █████╗ ███╗   ███╗
@@ -123,6 +346,24 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
     // ╚════██║   ██║   ██╔══██╗██╔══╝  ██╔══██║██║╚██╔╝██║
     // ███████║   ██║   ██║  ██║███████╗██║  ██║██║ ╚═╝ ██║
 
+    type DatastoreEntriesStream = DatastoreEntriesStreamType;
+
+    /// handler for datastore entries stream
+    async fn datastore_entries(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::DatastoreEntriesRequest>>,
+    ) -> Result<tonic::Response<Self::DatastoreEntriesStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "datastore_entries",
+            datastore_entries(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
     type NewBlocksStream = NewBlocksStreamType;
 
     /// handler for subscribe new blocks
@@ -130,7 +371,11 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewBlocksRequest>>,
     ) -> Result<tonic::Response<Self::NewBlocksStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_blocks(self, request).await?))
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(&self.metrics, "new_blocks", new_blocks(self, request).await)?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
     }
 
     type NewBlocksHeadersStream = NewBlocksHeadersStreamType;
@@ -140,8 +385,32 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewBlocksHeadersRequest>>,
     ) -> Result<tonic::Response<Self::NewBlocksHeadersStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_blocks_headers",
+            new_blocks_headers(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
+    type NewDenunciationsStream = NewDenunciationsStreamType;
+
+    /// handler for subscribe new denunciations stream
+    async fn new_denunciations(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::NewDenunciationsRequest>>,
+    ) -> Result<tonic::Response<Self::NewDenunciationsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_denunciations",
+            new_denunciations(self, request).await,
+        )?;
         Ok(tonic::Response::new(
-            new_blocks_headers(self, request).await?,
+            self.drain_controller.wrap_stream(guard, stream),
         ))
     }
 
@@ -152,7 +421,15 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewEndorsementsRequest>>,
     ) -> Result<tonic::Response<Self::NewEndorsementsStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_endorsements(self, request).await?))
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_endorsements",
+            new_endorsements(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
     }
 
     type NewFilledBlocksStream = NewFilledBlocksStreamType;
@@ -162,8 +439,14 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewFilledBlocksRequest>>,
     ) -> Result<tonic::Response<Self::NewFilledBlocksStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_filled_blocks",
+            new_filled_blocks(self, request).await,
+        )?;
         Ok(tonic::Response::new(
-            new_filled_blocks(self, request).await?,
+            self.drain_controller.wrap_stream(guard, stream),
         ))
     }
 
@@ -174,7 +457,47 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewOperationsRequest>>,
     ) -> Result<tonic::Response<Self::NewOperationsStream>, tonic::Status> {
-        Ok(tonic::Response::new(new_operations(self, request).await?))
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_operations",
+            new_operations(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
+    type NewPeerEventsStream = NewPeerEventsStreamType;
+
+    /// handler for subscribe new peer events stream
+    async fn new_peer_events(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::NewPeerEventsRequest>>,
+    ) -> Result<tonic::Response<Self::NewPeerEventsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_peer_events",
+            new_peer_events(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
+    type NewReorgsStream = NewReorgsStreamType;
+
+    /// handler for subscribe new reorgs stream
+    async fn new_reorgs(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::NewReorgsRequest>>,
+    ) -> Result<tonic::Response<Self::NewReorgsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(&self.metrics, "new_reorgs", new_reorgs(self, request).await)?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
     }
 
     type NewSlotExecutionOutputsStream = NewSlotExecutionOutputsStreamType;
@@ -184,8 +507,32 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
     ) -> Result<tonic::Response<Self::NewSlotExecutionOutputsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "new_slot_execution_outputs",
+            new_slot_execution_outputs(self, request).await,
+        )?;
         Ok(tonic::Response::new(
-            new_slot_execution_outputs(self, request).await?,
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
+    type ReplayBlocksStream = ReplayBlocksStreamType;
+
+    /// handler for replay blocks
+    async fn replay_blocks(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::ReplayBlocksRequest>>,
+    ) -> Result<tonic::Response<Self::ReplayBlocksStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "replay_blocks",
+            replay_blocks(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
         ))
     }
 
@@ -196,7 +543,15 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::SendBlocksRequest>>,
     ) -> Result<tonic::Response<Self::SendBlocksStream>, tonic::Status> {
-        Ok(tonic::Response::new(send_blocks(self, request).await?))
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "send_blocks",
+            send_blocks(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
     }
 
     type SendEndorsementsStream = SendEndorsementsStreamType;
@@ -206,8 +561,14 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::SendEndorsementsRequest>>,
     ) -> Result<tonic::Response<Self::SendEndorsementsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "send_endorsements",
+            send_endorsements(self, request).await,
+        )?;
         Ok(tonic::Response::new(
-            send_endorsements(self, request).await?,
+            self.drain_controller.wrap_stream(guard, stream),
         ))
     }
 
@@ -218,7 +579,33 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::SendOperationsRequest>>,
     ) -> Result<tonic::Response<Self::SendOperationsStream>, tonic::Status> {
-        Ok(tonic::Response::new(send_operations(self, request).await?))
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "send_operations",
+            send_operations(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
+    }
+
+    type SubmitOperationsStream = SubmitOperationsStreamType;
+
+    /// handler for submit_operations
+    async fn submit_operations(
+        &self,
+        request: tonic::Request<tonic::Streaming<grpc_api::SubmitOperationsRequest>>,
+    ) -> Result<tonic::Response<Self::SubmitOperationsStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "submit_operations",
+            submit_operations(self, request).await,
+        )?;
+        Ok(tonic::Response::new(
+            self.drain_controller.wrap_stream(guard, stream),
+        ))
     }
 
     type TransactionsThroughputStream = TransactionsThroughputStreamType;
@@ -228,8 +615,78 @@ impl grpc_api::massa_service_server::MassaService for MassaGrpc {
         &self,
         request: tonic::Request<tonic::Streaming<grpc_api::TransactionsThroughputRequest>>,
     ) -> Result<tonic::Response<Self::TransactionsThroughputStream>, tonic::Status> {
+        let guard = self.drain_controller.enter()?;
+        let stream = measure_stream(
+            &self.metrics,
+            "transactions_throughput",
+            transactions_throughput(self, request).await,
+        )?;
         Ok(tonic::Response::new(
-            transactions_throughput(self, request).await?,
+            self.drain_controller.wrap_stream(guard, stream),
         ))
     }
 }
+
+// the private API stays available while the public one is drained, to manage the node until it stops
+#[tonic::async_trait]
+impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
+    /// handler for ban nodes by ids
+    async fn ban_nodes_by_ids(
+        &self,
+        request: tonic::Request<grpc_api::BanNodesByIdsRequest>,
+    ) -> Result<tonic::Response<grpc_api::BanNodesByIdsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "ban_nodes_by_ids",
+            || ban_nodes_by_ids(self, request),
+        )?))
+    }
+
+    /// handler for ban nodes by ips
+    async fn ban_nodes_by_ips(
+        &self,
+        request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
+    ) -> Result<tonic::Response<grpc_api::BanNodesByIpsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "ban_nodes_by_ips",
+            || ban_nodes_by_ips(self, request),
+        )?))
+    }
+
+    /// handler for get bans
+    async fn get_bans(
+        &self,
+        request: tonic::Request<grpc_api::GetBansRequest>,
+    ) -> Result<tonic::Response<grpc_api::GetBansResponse>, tonic::Status> {
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "get_bans",
+            || get_bans(self, request),
+        )?))
+    }
+
+    /// handler for unban nodes by ids
+    async fn unban_nodes_by_ids(
+        &self,
+        request: tonic::Request<grpc_api::UnbanNodesByIdsRequest>,
+    ) -> Result<tonic::Response<grpc_api::UnbanNodesByIdsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "unban_nodes_by_ids",
+            || unban_nodes_by_ids(self, request),
+        )?))
+    }
+
+    /// handler for unban nodes by ips
+    async fn unban_nodes_by_ips(
+        &self,
+        request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
+    ) -> Result<tonic::Response<grpc_api::UnbanNodesByIpsResponse>, tonic::Status> {
+        Ok(tonic::Response::new(measure_request(
+            &self.metrics,
+            "unban_nodes_by_ips",
+            || unban_nodes_by_ips(self, request),
+        )?))
+    }
+}
@@ -7,9 +7,14 @@
 //!
 //! ## **Structure**
 //!
+//! * `access_log.rs`: access log of the calls, with an optional JSON file sink.
 //! * `api.rs`: implements gRPC service methods without streams.
+//! * `drain.rs`: drain mode used to stop serving without cutting ongoing requests.
+//! * `metrics.rs`: request and stream metrics exported through massa-metrics.
 //! * `handler.rs`: defines the logic for handling incoming gRPC requests.
+//! * `private.rs`: implements the private gRPC service methods, managing the node.
 //! * `server`: initializes the gRPC service and serve It.
+//! * `size.rs`: response size checks and chunking of the large stream items.
 //! * `stream/`: contains the gRPC streaming methods implementations files.
 
 #![feature(async_closure)]
@@ -20,16 +25,26 @@ use tonic_health as _;
 use tonic_reflection as _;
 use tonic_web as _;
 
+/// gRPC API access log
+mod access_log;
 /// business code for non stream methods
 pub mod api;
 /// gRPC configuration
 pub mod config;
+/// gRPC API drain mode
+pub mod drain;
 /// models error
 pub mod error;
 /// gRPC API implementation
 pub mod handler;
+/// gRPC API metrics
+mod metrics;
+/// business code for private service methods
+pub mod private;
 /// gRPC service initialization and serve
 pub mod server;
+/// response size checks and chunking
+mod size;
 /// business code for stream methods
 pub mod stream;
 
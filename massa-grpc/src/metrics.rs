@@ -0,0 +1,56 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use futures_util::StreamExt;
+use massa_metrics::MassaMetrics;
+use std::pin::Pin;
+use std::time::Instant;
+use tonic::codegen::futures_core;
+
+/// Counts a unary request and records its processing time
+pub(crate) fn measure_request<T>(
+    metrics: &MassaMetrics,
+    method: &str,
+    handler: impl FnOnce() -> Result<T, GrpcError>,
+) -> Result<T, GrpcError> {
+    let start = Instant::now();
+    let result = handler();
+    metrics.observe_grpc_request_duration(method, start.elapsed());
+    metrics.inc_grpc_requests(method, result.is_ok());
+    result
+}
+
+/// Counts a stream request and, if the stream was opened,
+/// keeps it in the active streams gauge until it is dropped
+pub(crate) fn measure_stream<T: Send + 'static>(
+    metrics: &MassaMetrics,
+    method: &'static str,
+    stream: Result<Pin<Box<dyn futures_core::Stream<Item = T> + Send + 'static>>, GrpcError>,
+) -> Result<Pin<Box<dyn futures_core::Stream<Item = T> + Send + 'static>>, GrpcError> {
+    metrics.inc_grpc_requests(method, stream.is_ok());
+    let stream = stream?;
+    metrics.inc_grpc_active_streams(method);
+    let guard = ActiveStreamGuard {
+        metrics: metrics.clone(),
+        method,
+    };
+    Ok(Box::pin(stream.map(move |item| {
+        // keep the guard alive as long as the stream
+        let _ = &guard;
+        item
+    })))
+}
+
+/// Decrements the active streams gauge when dropped
+struct ActiveStreamGuard {
+    /// metrics handle
+    metrics: MassaMetrics,
+    /// stream method name
+    method: &'static str,
+}
+
+impl Drop for ActiveStreamGuard {
+    fn drop(&mut self) {
+        self.metrics.dec_grpc_active_streams(self.method);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use crate::server::MassaPrivateGrpc;
+use massa_models::node::NodeId;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_protocol_exports::{BanTarget, PeerId};
+use massa_time::MassaTime;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// Reason of the bans set by the node operator without a reason
+const OPERATOR_BAN_REASON: &str = "banned by the node operator";
+
+/// Ban nodes by their ids, for a duration or until they are unbanned
+pub(crate) fn ban_nodes_by_ids(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::BanNodesByIdsRequest>,
+) -> Result<grpc_api::BanNodesByIdsResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let targets = parse_node_ids(&inner_req.node_ids)?;
+    grpc.protocol_command_sender.add_bans(
+        targets,
+        inner_req.duration.map(MassaTime::from_millis),
+        inner_req
+            .reason
+            .unwrap_or_else(|| OPERATOR_BAN_REASON.to_string()),
+    )?;
+    Ok(grpc_api::BanNodesByIdsResponse {})
+}
+
+/// Ban all the nodes connecting from ips, for a duration or until they are unbanned
+pub(crate) fn ban_nodes_by_ips(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
+) -> Result<grpc_api::BanNodesByIpsResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let targets = parse_ips(&inner_req.ips)?;
+    grpc.protocol_command_sender.add_bans(
+        targets,
+        inner_req.duration.map(MassaTime::from_millis),
+        inner_req
+            .reason
+            .unwrap_or_else(|| OPERATOR_BAN_REASON.to_string()),
+    )?;
+    Ok(grpc_api::BanNodesByIpsResponse {})
+}
+
+/// Lift the bans set on nodes by their ids
+pub(crate) fn unban_nodes_by_ids(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::UnbanNodesByIdsRequest>,
+) -> Result<grpc_api::UnbanNodesByIdsResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let targets = parse_node_ids(&inner_req.node_ids)?;
+    grpc.protocol_command_sender.remove_bans(targets)?;
+    Ok(grpc_api::UnbanNodesByIdsResponse {})
+}
+
+/// Lift the bans set on ips
+pub(crate) fn unban_nodes_by_ips(
+    grpc: &MassaPrivateGrpc,
+    request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
+) -> Result<grpc_api::UnbanNodesByIpsResponse, GrpcError> {
+    let inner_req = request.into_inner();
+    let targets = parse_ips(&inner_req.ips)?;
+    grpc.protocol_command_sender.remove_bans(targets)?;
+    Ok(grpc_api::UnbanNodesByIpsResponse {})
+}
+
+/// Get the bans still in force
+pub(crate) fn get_bans(
+    grpc: &MassaPrivateGrpc,
+    _request: tonic::Request<grpc_api::GetBansRequest>,
+) -> Result<grpc_api::GetBansResponse, GrpcError> {
+    let bans = grpc.protocol_command_sender.get_bans()?;
+    Ok(grpc_api::GetBansResponse {
+        bans: bans
+            .into_iter()
+            .map(|ban| {
+                let (node_id, ip) = match ban.target {
+                    BanTarget::PeerId(peer_id) => (
+                        Some(NodeId::new(peer_id.get_public_key()).to_string()),
+                        None,
+                    ),
+                    BanTarget::Ip(ip) => (None, Some(ip.to_string())),
+                };
+                grpc_model::PeerBan {
+                    node_id,
+                    ip,
+                    reason: ban.reason,
+                    banned_at: ban.banned_at.to_millis(),
+                    expires_at: ban.expires_at.map(|expires_at| expires_at.to_millis()),
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Parses the ids of the nodes to ban or unban
+fn parse_node_ids(node_ids: &[String]) -> Result<Vec<BanTarget>, GrpcError> {
+    if node_ids.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "no node id received".to_string(),
+        ));
+    }
+    node_ids
+        .iter()
+        .map(|node_id| {
+            //TODO: Change when unify node id and peer id
+            let node_id = NodeId::from_str(node_id)?;
+            Ok(BanTarget::PeerId(PeerId::from_public_key(
+                node_id.get_public_key(),
+            )))
+        })
+        .collect()
+}
+
+/// Parses the ips to ban or unban
+fn parse_ips(ips: &[String]) -> Result<Vec<BanTarget>, GrpcError> {
+    if ips.is_empty() {
+        return Err(GrpcError::InvalidArgument("no ip received".to_string()));
+    }
+    ips.iter()
+        .map(|ip| {
+            IpAddr::from_str(ip)
+                .map(BanTarget::Ip)
+                .map_err(|e| GrpcError::InvalidArgument(format!("invalid ip {}: {}", ip, e)))
+        })
+        .collect()
+}
@@ -1,18 +1,24 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::access_log::AccessLogLayer;
 use crate::config::GrpcConfig;
+use crate::drain::DrainController;
 use crate::error::GrpcError;
 use futures_util::FutureExt;
 use hyper::Method;
 use massa_consensus_exports::{ConsensusChannels, ConsensusController};
 use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_metrics::MassaMetrics;
 use massa_pool_exports::{PoolChannels, PoolController};
 use massa_pos_exports::SelectorController;
 use massa_proto_rs::massa::api::v1::massa_service_server::MassaServiceServer;
+use massa_proto_rs::massa::api::v1::private_service_server::PrivateServiceServer;
 use massa_proto_rs::massa::api::v1::FILE_DESCRIPTOR_SET;
-use massa_protocol_exports::ProtocolController;
+use massa_protocol_exports::{PeerEvent, ProtocolController};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
+use std::net::SocketAddr;
 
 use tokio::sync::oneshot;
 use tonic::{
@@ -40,6 +46,8 @@ pub struct MassaGrpc {
     pub pool_command_sender: Box<dyn PoolController>,
     /// link to the protocol component
     pub protocol_command_sender: Box<dyn ProtocolController>,
+    /// link(channel) to the peer events of the protocol component
+    pub peer_event_sender: tokio::sync::broadcast::Sender<PeerEvent>,
     /// link to the selector component
     pub selector_controller: Box<dyn SelectorController>,
     /// link to the storage component
@@ -50,11 +58,50 @@ pub struct MassaGrpc {
     pub version: massa_models::version::Version,
     /// mip store
     pub mip_store: MipStore,
+    /// drain mode state, shared with the private API
+    pub drain_controller: DrainController,
+    /// metrics
+    pub metrics: MassaMetrics,
+}
+
+/// Private gRPC API content, managing the node
+pub struct MassaPrivateGrpc {
+    /// link to the protocol component
+    pub protocol_command_sender: Box<dyn ProtocolController>,
+    /// metrics
+    pub metrics: MassaMetrics,
+}
+
+impl MassaPrivateGrpc {
+    /// Start the private gRPC API on `bind`, returns the sender stopping it
+    fn serve(self, bind: SocketAddr, config: &GrpcConfig) -> oneshot::Sender<()> {
+        let svc = PrivateServiceServer::new(self)
+            .max_decoding_message_size(config.max_decoding_message_size)
+            .max_encoding_message_size(config.max_encoding_message_size);
+
+        let (shutdown_send, shutdown_recv) = oneshot::channel::<()>();
+        let router = tonic::transport::Server::builder()
+            .timeout(config.timeout)
+            .tcp_nodelay(config.tcp_nodelay)
+            .add_service(svc);
+        tokio::spawn(router.serve_with_shutdown(bind, shutdown_recv.map(drop)));
+        info!("gRPC private API listening on: {}", bind);
+
+        shutdown_send
+    }
 }
 
 impl MassaGrpc {
-    /// Start the gRPC API
+    /// Start the gRPC API, and the private gRPC API if it has a bind
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        let drain_controller = self.drain_controller.clone();
+        let private_stop_cmd_sender = config.private_bind.map(|bind| {
+            MassaPrivateGrpc {
+                protocol_command_sender: self.protocol_command_sender.clone(),
+                metrics: self.metrics.clone(),
+            }
+            .serve(bind, config)
+        });
         let mut svc = MassaServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -74,6 +121,10 @@ impl MassaGrpc {
         let (shutdown_send, shutdown_recv) = oneshot::channel::<()>();
 
         let mut server_builder = tonic::transport::Server::builder()
+            .layer(AccessLogLayer::new(
+                config.enable_access_log,
+                config.access_log_file.as_deref(),
+            )?)
             .concurrency_limit_per_connection(config.concurrency_limit_per_connection)
             .timeout(config.timeout)
             .initial_stream_window_size(config.initial_stream_window_size)
@@ -125,6 +176,10 @@ impl MassaGrpc {
                 .set_serving::<MassaServiceServer<MassaGrpc>>()
                 .await;
             tokio::spawn(massa_service_status(health_reporter.clone()));
+            tokio::spawn(massa_service_drain(
+                health_reporter.clone(),
+                drain_controller.clone(),
+            ));
             info!("gRPC health service enabled");
             Some(health_service)
         } else {
@@ -174,6 +229,9 @@ impl MassaGrpc {
 
         Ok(StopHandle {
             stop_cmd_sender: shutdown_send,
+            private_stop_cmd_sender,
+            drain_controller,
+            shutdown_drain_timeout: config.shutdown_drain_timeout,
         })
     }
 }
@@ -181,11 +239,41 @@ impl MassaGrpc {
 /// Used to be able to stop the gRPC API
 pub struct StopHandle {
     stop_cmd_sender: oneshot::Sender<()>,
+    /// stops the private gRPC API, if served
+    private_stop_cmd_sender: Option<oneshot::Sender<()>>,
+    /// drain mode state, used to drain the API before stopping it
+    drain_controller: DrainController,
+    /// time given to the active streams to end on shutdown
+    shutdown_drain_timeout: MassaTime,
 }
 
 impl StopHandle {
+    /// drain the gRPC API then stop it: new requests are rejected,
+    /// and the active streams get `shutdown_drain_timeout` to flush their messages before being closed
+    pub async fn shutdown(self) {
+        match self
+            .drain_controller
+            .start_drain(self.shutdown_drain_timeout)
+        {
+            Ok(_) => match self.drain_controller.wait_drained().await {
+                Ok(status) => info!(
+                    "gRPC API drained with {} active requests left",
+                    status.active_requests
+                ),
+                Err(e) => warn!("failed to wait for the gRPC API drain: {}", e),
+            },
+            Err(e) => warn!("failed to drain the gRPC API: {}", e),
+        }
+        self.stop();
+    }
+
     /// stop the gRPC API gracefully
     pub fn stop(self) {
+        if let Some(private_stop_cmd_sender) = self.private_stop_cmd_sender {
+            if let Err(e) = private_stop_cmd_sender.send(()) {
+                warn!("gRPC private API thread panicked: {:?}", e);
+            }
+        }
         if let Err(e) = self.stop_cmd_sender.send(()) {
             warn!("gRPC API thread panicked: {:?}", e);
         } else {
@@ -194,6 +282,15 @@ impl StopHandle {
     }
 }
 
+/// Reports the Massa service as not serving once the drain is started,
+/// so that load balancers stop routing new clients to this node
+async fn massa_service_drain(mut reporter: HealthReporter, drain_controller: DrainController) {
+    drain_controller.drain_started().await;
+    reporter
+        .set_not_serving::<MassaServiceServer<MassaGrpc>>()
+        .await;
+}
+
 /// Massa service health check implementation
 async fn massa_service_status(mut reporter: HealthReporter) {
     //TODO add a complete health check based on Massa modules health
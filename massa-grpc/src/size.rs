@@ -0,0 +1,61 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Response size checks and chunking.
+//!
+//! A message larger than `max_encoding_message_size` is rejected by the transport with an opaque error.
+//! Unary responses are checked beforehand to return a clear error instead,
+//! and the streams split their large items over several messages.
+
+use crate::config::GrpcConfig;
+use crate::error::GrpcError;
+use prost::Message;
+
+/// Room left in each chunk for the fields wrapping the chunked items (request id, cursor, block header...)
+const CHUNK_OVERHEAD: usize = 4096;
+
+/// Fails with a clear error if the encoded response exceeds `max_encoding_message_size`
+pub(crate) fn check_response_size<T: Message>(
+    config: &GrpcConfig,
+    method: &str,
+    response: Result<T, GrpcError>,
+) -> Result<T, GrpcError> {
+    let response = response?;
+    let size = response.encoded_len();
+    if size > config.max_encoding_message_size {
+        return Err(GrpcError::MessageTooLarge(format!(
+            "{} response is {} bytes, above the max_encoding_message_size of {} bytes: request fewer items or use the matching stream",
+            method, size, config.max_encoding_message_size
+        )));
+    }
+    Ok(response)
+}
+
+/// Maximum encoded size of the items carried by a single stream message
+pub(crate) fn chunk_size(config: &GrpcConfig) -> usize {
+    config
+        .max_encoding_message_size
+        .saturating_sub(CHUNK_OVERHEAD)
+        .max(1)
+}
+
+/// Splits items, in order, into groups whose encoded size as a repeated field fits in `max_size`.
+/// An item larger than `max_size` is alone in its group.
+pub(crate) fn split_by_size<T: Message>(items: Vec<T>, max_size: usize) -> Vec<Vec<T>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_size = 0;
+    for item in items {
+        // field tag, length prefix and content
+        let item_size = 1 + prost::length_delimiter_len(item.encoded_len()) + item.encoded_len();
+        if !current.is_empty() && current_size + item_size > max_size {
+            chunks.push(std::mem::take(&mut current));
+            current_size = 0;
+        }
+        current_size += item_size;
+        current.push(item);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
@@ -0,0 +1,115 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Filters and resume cursors shared by the `new_blocks`, `new_blocks_headers` and `new_filled_blocks` streams.
+//!
+//! Each message sent on these streams carries a cursor, the slot of its block.
+//! A client reconnecting with the last cursor it received first gets the blocks of the graph
+//! at a later slot, then the new blocks as they come.
+//! Competing blocks at the very slot of the cursor are not sent again.
+
+use crate::error::GrpcError;
+use massa_consensus_exports::ConsensusController;
+use massa_models::address::Address;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use std::str::FromStr;
+
+/// Filter applied to the blocks sent on a stream
+#[derive(Debug, Default)]
+pub(crate) struct BlocksFilter {
+    /// first slot of the range, included
+    start_slot: Option<Slot>,
+    /// last slot of the range, excluded
+    end_slot: Option<Slot>,
+    /// if not empty, only the blocks created by one of these addresses are sent
+    creators: PreHashSet<Address>,
+}
+
+impl BlocksFilter {
+    /// Builds the filter requested by a client, no filter lets all the blocks through
+    pub(crate) fn new(filter: Option<grpc_api::BlocksFilter>) -> Result<Self, GrpcError> {
+        let Some(filter) = filter else {
+            return Ok(BlocksFilter::default());
+        };
+        let (start_slot, end_slot) = match filter.slot_range {
+            Some(range) => (
+                range
+                    .start_slot
+                    .map(|slot| Slot::new(slot.period, slot.thread as u8)),
+                range
+                    .end_slot
+                    .map(|slot| Slot::new(slot.period, slot.thread as u8)),
+            ),
+            None => (None, None),
+        };
+        let creators = filter
+            .addresses
+            .iter()
+            .map(|address| {
+                Address::from_str(address).map_err(|e| {
+                    GrpcError::InvalidArgument(format!("invalid address {}: {}", address, e))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(BlocksFilter {
+            start_slot,
+            end_slot,
+            creators,
+        })
+    }
+
+    /// Checks whether a block at `slot` created by `creator` should be sent
+    pub(crate) fn matches(&self, slot: Slot, creator: &Address) -> bool {
+        self.start_slot.map_or(true, |start| slot >= start)
+            && self.end_slot.map_or(true, |end| slot < end)
+            && (self.creators.is_empty() || self.creators.contains(creator))
+    }
+}
+
+/// Returns the cursor of a block sent to a client
+pub(crate) fn cursor(slot: Slot) -> String {
+    format!("{}:{}", slot.period, slot.thread)
+}
+
+/// Parses a cursor previously returned by `cursor`
+pub(crate) fn parse_cursor(cursor: &str) -> Result<Slot, GrpcError> {
+    let invalid = || GrpcError::InvalidArgument(format!("invalid resume cursor: {}", cursor));
+    let (period, thread) = cursor.split_once(':').ok_or_else(invalid)?;
+    Ok(Slot::new(
+        period.parse().map_err(|_| invalid())?,
+        thread.parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// Returns the ids of the blocks of the graph at a slot later than the cursor, sorted by slot.
+/// Fails if the blocks following the cursor are no longer kept by the node.
+pub(crate) fn blocks_after_cursor(
+    consensus_controller: &dyn ConsensusController,
+    cursor_slot: Slot,
+) -> Result<Vec<BlockId>, GrpcError> {
+    let graph = consensus_controller.get_block_graph_status(None, None)?;
+    let mut blocks: Vec<(Slot, BlockId)> = graph
+        .active_blocks
+        .iter()
+        .map(|(block_id, block)| (block.header.content.slot, *block_id))
+        .collect();
+    blocks.sort_unstable();
+
+    if let Some((oldest_slot, oldest_id)) = blocks.first() {
+        if cursor_slot < *oldest_slot && !graph.genesis_blocks.contains(oldest_id) {
+            return Err(GrpcError::InvalidArgument(format!(
+                "resume cursor {} is older than the oldest block kept by the node at slot {}",
+                cursor(cursor_slot),
+                oldest_slot
+            )));
+        }
+    }
+
+    Ok(blocks
+        .into_iter()
+        .filter(|(slot, _)| *slot > cursor_slot)
+        .map(|(_, block_id)| block_id)
+        .collect())
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Bounded channel between the task producing the messages of a subscription stream and its client.
+//!
+//! When the client does not read its messages fast enough and the channel is full,
+//! the configured `StreamBackpressurePolicy` decides whether the producer waits,
+//! a message is dropped, or the stream is closed.
+//! Dropped messages and closed streams are counted in the metrics, by stream.
+
+use crate::config::{GrpcConfig, StreamBackpressurePolicy};
+use futures_util::Stream;
+use massa_metrics::MassaMetrics;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use tracing::log::warn;
+
+/// Error returned when sending a message on a closed stream
+#[derive(Debug)]
+pub(crate) struct StreamClosedError;
+
+impl std::fmt::Display for StreamClosedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "stream closed")
+    }
+}
+
+/// State shared by the senders and the receiver of a channel
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    /// notified when a message is queued or the channel is closed
+    readable: Notify,
+    /// notified when a message is read or the receiver is dropped
+    writable: Notify,
+    capacity: usize,
+    policy: StreamBackpressurePolicy,
+    /// name of the stream, used as metrics label
+    method: &'static str,
+    metrics: MassaMetrics,
+}
+
+struct State<T> {
+    queue: VecDeque<Result<T, tonic::Status>>,
+    /// number of live senders
+    senders: usize,
+    /// the client is gone
+    receiver_dropped: bool,
+    /// the stream was closed because the client was too slow
+    disconnected: bool,
+}
+
+/// Sending half of a stream channel
+pub(crate) struct StreamSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiving half of a stream channel
+pub(crate) struct StreamReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Creates the channel of a subscription stream, applying the backpressure policy of the configuration
+pub(crate) fn stream_channel<T>(
+    config: &GrpcConfig,
+    metrics: MassaMetrics,
+    method: &'static str,
+) -> (StreamSender<T>, StreamReceiver<T>) {
+    channel(
+        config.max_channel_size,
+        config.stream_backpressure_policy,
+        metrics,
+        method,
+    )
+}
+
+/// Creates a channel queuing at most `capacity` messages
+pub(crate) fn channel<T>(
+    capacity: usize,
+    policy: StreamBackpressurePolicy,
+    metrics: MassaMetrics,
+    method: &'static str,
+) -> (StreamSender<T>, StreamReceiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(capacity),
+            senders: 1,
+            receiver_dropped: false,
+            disconnected: false,
+        }),
+        readable: Notify::new(),
+        writable: Notify::new(),
+        capacity: capacity.max(1),
+        policy,
+        method,
+        metrics,
+    });
+    (
+        StreamSender {
+            shared: shared.clone(),
+        },
+        StreamReceiver { shared },
+    )
+}
+
+impl<T> StreamSender<T> {
+    /// Queues a message for the client, applying the backpressure policy if the channel is full.
+    /// Fails if the client is gone or was disconnected.
+    pub(crate) async fn send(
+        &self,
+        message: Result<T, tonic::Status>,
+    ) -> Result<(), StreamClosedError> {
+        let mut message = Some(message);
+        loop {
+            let notified = {
+                let mut state = self.shared.state.lock();
+                if state.receiver_dropped || state.disconnected {
+                    return Err(StreamClosedError);
+                }
+                if state.queue.len() < self.shared.capacity {
+                    state
+                        .queue
+                        .push_back(message.take().expect("message already sent"));
+                    drop(state);
+                    self.shared.readable.notify_waiters();
+                    return Ok(());
+                }
+                match self.shared.policy {
+                    StreamBackpressurePolicy::Block => self.shared.writable.notified(),
+                    StreamBackpressurePolicy::DropOldest => {
+                        state.queue.pop_front();
+                        state
+                            .queue
+                            .push_back(message.take().expect("message already sent"));
+                        drop(state);
+                        self.shared
+                            .metrics
+                            .inc_grpc_stream_dropped_messages(self.shared.method, 1);
+                        self.shared.readable.notify_waiters();
+                        return Ok(());
+                    }
+                    StreamBackpressurePolicy::DropNewest => {
+                        drop(state);
+                        self.shared
+                            .metrics
+                            .inc_grpc_stream_dropped_messages(self.shared.method, 1);
+                        return Ok(());
+                    }
+                    StreamBackpressurePolicy::Disconnect => {
+                        // the queued messages are dropped, the client only gets the reason of the disconnection
+                        let dropped = state.queue.len() as u64;
+                        state.queue.clear();
+                        state.queue.push_back(Err(tonic::Status::resource_exhausted(
+                            "the client does not read the stream fast enough",
+                        )));
+                        state.disconnected = true;
+                        drop(state);
+                        warn!(
+                            "closing {} stream: the client is too slow",
+                            self.shared.method
+                        );
+                        self.shared
+                            .metrics
+                            .inc_grpc_stream_dropped_messages(self.shared.method, dropped + 1);
+                        self.shared
+                            .metrics
+                            .inc_grpc_stream_slow_client_disconnections(self.shared.method);
+                        self.shared.readable.notify_waiters();
+                        return Err(StreamClosedError);
+                    }
+                }
+            };
+            notified.await;
+        }
+    }
+}
+
+impl<T> Clone for StreamSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.state.lock().senders += 1;
+        StreamSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for StreamSender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.readable.notify_waiters();
+        }
+    }
+}
+
+impl<T: Send + 'static> StreamReceiver<T> {
+    /// Returns the next message, or None once all the senders are dropped or the stream was closed
+    async fn recv(&self) -> Option<Result<T, tonic::Status>> {
+        loop {
+            let notified = {
+                let mut state = self.shared.state.lock();
+                if let Some(message) = state.queue.pop_front() {
+                    drop(state);
+                    self.shared.writable.notify_waiters();
+                    return Some(message);
+                }
+                if state.senders == 0 || state.disconnected {
+                    return None;
+                }
+                self.shared.readable.notified()
+            };
+            notified.await;
+        }
+    }
+
+    /// Converts the receiver into the stream of messages sent to the client
+    pub(crate) fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<T, tonic::Status>> + Send + 'static {
+        futures_util::stream::unfold(self, |receiver| async move {
+            receiver.recv().await.map(|message| (message, receiver))
+        })
+    }
+}
+
+impl<T> Drop for StreamReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.state.lock().receiver_dropped = true;
+        self.shared.writable.notify_waiters();
+    }
+}
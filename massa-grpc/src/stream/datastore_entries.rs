@@ -0,0 +1,227 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use crate::size::{chunk_size, split_by_size};
+use futures_util::StreamExt;
+use massa_execution_exports::ExecutionController;
+use massa_models::address::Address;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use std::io::ErrorKind;
+use std::ops::Bound::{Excluded, Unbounded};
+use std::pin::Pin;
+use std::str::FromStr;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Maximum number of datastore entries sent back in a single response message
+const ENTRIES_PER_MESSAGE: usize = 100;
+
+/// Type declaration for DatastoreEntries
+pub type DatastoreEntriesStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::DatastoreEntriesResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a stream of the datastore entries of an address.
+///
+/// Each incoming request describes a page (address, key prefix, exclusive start cursor, limit, final or candidate state).
+/// The matching entries are sent back in sorted key order, split across several response messages.
+/// The last message of a page carries the cursor to use to get the next page, if any.
+/// An entry whose value does not fit in a message is sent as consecutive `value_chunk`s to concatenate.
+pub(crate) async fn datastore_entries(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::DatastoreEntriesRequest>>,
+) -> Result<DatastoreEntriesStreamType, GrpcError> {
+    let execution_controller = grpc.execution_controller.clone();
+    let max_entries = grpc.grpc_config.max_datastore_entries_per_request as usize;
+    let max_message_size = chunk_size(&grpc.grpc_config);
+
+    // Create a channel to handle communication with the client
+    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+
+    tokio::spawn(async move {
+        while let Some(result) = in_stream.next().await {
+            match result {
+                Ok(req) => {
+                    let id = req.id.clone();
+                    let responses = match get_datastore_entries_page(
+                        &*execution_controller,
+                        max_entries,
+                        max_message_size,
+                        req,
+                    ) {
+                        Ok(responses) => responses,
+                        Err(err) => {
+                            error!("{}", err);
+                            if let Err(e) = tx.send(Err(err.into())).await {
+                                error!(
+                                    "failed to send back datastore_entries error response: {}",
+                                    e
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                    for chunk in responses {
+                        if let Err(e) = tx
+                            .send(Ok(grpc_api::DatastoreEntriesResponse {
+                                id: id.clone(),
+                                entries: chunk.entries,
+                                value_chunk: chunk.value_chunk,
+                                next_cursor: chunk.next_cursor,
+                            }))
+                            .await
+                        {
+                            error!("failed to send back datastore_entries response: {}", e);
+                            return;
+                        }
+                    }
+                }
+                Err(err) => {
+                    // Check if the error matches any IO errors
+                    if let Some(io_err) = match_for_io_error(&err) {
+                        if io_err.kind() == ErrorKind::BrokenPipe {
+                            warn!("client disconnected, broken pipe: {}", io_err);
+                            break;
+                        }
+                    }
+                    error!("{}", err);
+                    if let Err(e) = tx.send(Err(err)).await {
+                        error!(
+                            "failed to send back datastore_entries error response: {}",
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    Ok(Box::pin(out_stream) as DatastoreEntriesStreamType)
+}
+
+/// One response message of a page of datastore entries
+#[derive(Default)]
+struct DatastoreEntriesChunk {
+    /// complete entries
+    entries: Vec<grpc_model::BytesMapFieldEntry>,
+    /// part of an entry whose value does not fit in a single message
+    value_chunk: Option<grpc_api::DatastoreValueChunk>,
+    /// for the last chunk of a page, the cursor of the next page
+    next_cursor: Option<Vec<u8>>,
+}
+
+/// Collect one page of datastore entries, split into response-sized chunks
+fn get_datastore_entries_page(
+    execution_controller: &dyn ExecutionController,
+    max_entries: usize,
+    max_message_size: usize,
+    req: grpc_api::DatastoreEntriesRequest,
+) -> Result<Vec<DatastoreEntriesChunk>, GrpcError> {
+    let address = Address::from_str(&req.address)?;
+    let limit = req
+        .limit
+        .map_or(max_entries, |limit| (limit as usize).min(max_entries));
+    if limit == 0 {
+        return Err(GrpcError::InvalidArgument(
+            "limit must be strictly positive".to_string(),
+        ));
+    }
+
+    let (final_keys, candidate_keys) =
+        execution_controller.get_final_and_candidate_datastore_keys(&address, &req.prefix);
+    let keys = if req.is_final {
+        final_keys
+    } else {
+        candidate_keys
+    };
+
+    // the cursor is the last key of the previous page
+    let start = match req.cursor {
+        Some(cursor) => Excluded(cursor),
+        None => Unbounded,
+    };
+    let mut page_keys = keys.range((start, Unbounded));
+    let selected_keys: Vec<Vec<u8>> = page_keys.by_ref().take(limit).cloned().collect();
+    let next_cursor = match page_keys.next() {
+        Some(_) => selected_keys.last().cloned(),
+        None => None,
+    };
+
+    let values = execution_controller.get_final_and_active_data_entry(
+        selected_keys
+            .iter()
+            .map(|key| (address, key.clone()))
+            .collect(),
+    );
+    let entries: Vec<grpc_model::BytesMapFieldEntry> = selected_keys
+        .into_iter()
+        .zip(values)
+        .filter_map(|(key, (final_value, candidate_value))| {
+            let value = if req.is_final {
+                final_value
+            } else {
+                candidate_value
+            };
+            value.map(|value| grpc_model::BytesMapFieldEntry { key, value })
+        })
+        .collect();
+
+    let mut chunks: Vec<DatastoreEntriesChunk> = Vec::new();
+    for group in entries.chunks(ENTRIES_PER_MESSAGE) {
+        for mut sized_group in split_by_size(group.to_vec(), max_message_size) {
+            // a group larger than a message is made of a single entry: split its value
+            if sized_group.len() == 1 && sized_group[0].value.len() > max_message_size {
+                let entry = sized_group.remove(0);
+                chunks.extend(split_value(entry, max_message_size));
+            } else {
+                chunks.push(DatastoreEntriesChunk {
+                    entries: sized_group,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    match chunks.last_mut() {
+        Some(last) => last.next_cursor = next_cursor,
+        None => chunks.push(DatastoreEntriesChunk {
+            next_cursor,
+            ..Default::default()
+        }),
+    }
+    Ok(chunks)
+}
+
+/// Split the value of an entry into chunks of at most `max_message_size` bytes minus the size of the key
+fn split_value(
+    entry: grpc_model::BytesMapFieldEntry,
+    max_message_size: usize,
+) -> Vec<DatastoreEntriesChunk> {
+    let data_size = max_message_size.saturating_sub(entry.key.len()).max(1);
+    let total_size = entry.value.len() as u64;
+    entry
+        .value
+        .chunks(data_size)
+        .enumerate()
+        .map(|(index, data)| DatastoreEntriesChunk {
+            value_chunk: Some(grpc_api::DatastoreValueChunk {
+                key: entry.key.clone(),
+                offset: (index * data_size) as u64,
+                total_size,
+                data: data.to_vec(),
+            }),
+            ..Default::default()
+        })
+        .collect()
+}
@@ -1,22 +1,38 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+/// filters and resume cursors of the blocks streams
+pub mod blocks_filter;
+/// channels of the subscription streams, applying the backpressure policy
+pub mod channel;
+/// stream datastore entries of an address
+pub mod datastore_entries;
 /// stream new blocks
 pub mod new_blocks;
-/// stream new blocks with operations content
+/// stream new blocks headers
 pub mod new_blocks_headers;
+/// stream new created and received denunciations
+pub mod new_denunciations;
 /// stream new endorsements
 pub mod new_endorsements;
-/// stream new blocks headers
+/// stream new blocks with operations content
 pub mod new_filled_blocks;
 /// subscribe new operations
 pub mod new_operations;
+/// stream peer connections, disconnections and bans
+pub mod new_peer_events;
+/// stream blockclique reorgs
+pub mod new_reorgs;
 /// subscribe new slot execution outputs
 pub mod new_slot_execution_outputs;
+/// replay final blocks from a slot, then stream new blocks
+pub mod replay_blocks;
 /// send_blocks streaming
 pub mod send_blocks;
 /// send endorsements
 pub mod send_endorsements;
 /// send operations
 pub mod send_operations;
+/// submit operations and follow their status
+pub mod submit_operations;
 /// subscribe tx througput
 pub mod tx_throughput;
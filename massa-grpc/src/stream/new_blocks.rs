@@ -2,11 +2,17 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
+use crate::stream::blocks_filter::{blocks_after_cursor, cursor, parse_cursor, BlocksFilter};
+use crate::stream::channel::{stream_channel, StreamSender};
 use futures_util::StreamExt;
+use massa_models::block::SecureShareBlock;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -21,78 +27,170 @@ pub type NewBlocksStreamType = Pin<
 >;
 
 /// Creates a new stream of new produced and received blocks
+///
+/// The filter can be replaced by each message of the client.
+/// The resume cursor is only read from the first message: the blocks following it are sent before the new ones.
 pub(crate) async fn new_blocks(
     grpc: &MassaGrpc,
     request: Request<Streaming<grpc_api::NewBlocksRequest>>,
 ) -> Result<NewBlocksStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_blocks");
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new blocks channel
+    // Subscribe to the new blocks channel before collecting the missed blocks so that none is lost in between
     let mut subscriber = grpc.consensus_channels.block_sender.subscribe();
+    let consensus_controller = grpc.consensus_controller.clone();
+    let storage = grpc.storage.clone_without_refs();
+
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
 
     tokio::spawn(async move {
-        // Initialize the request_id string
-        let mut request_id = String::new();
+        let Some(Ok(request)) = in_stream.next().await else {
+            error!("empty request");
+            return;
+        };
+        let mut request_id = request.id;
+        let query = request.query.unwrap_or_default();
+        let mut filter = match BlocksFilter::new(query.filter) {
+            Ok(filter) => filter,
+            Err(err) => {
+                if let Err(e) = tx.send(Err(err.into())).await {
+                    error!("failed to send back new_blocks error response: {}", e);
+                }
+                return;
+            }
+        };
+
+        // Send the blocks missed since the resume cursor
+        let mut resent_ids = PreHashSet::<BlockId>::default();
+        if let Some(resume_cursor) = query.resume_cursor {
+            let missed_ids = match parse_cursor(&resume_cursor)
+                .and_then(|cursor_slot| blocks_after_cursor(&*consensus_controller, cursor_slot))
+            {
+                Ok(missed_ids) => missed_ids,
+                Err(err) => {
+                    if let Err(e) = tx.send(Err(err.into())).await {
+                        error!("failed to send back new_blocks error response: {}", e);
+                    }
+                    return;
+                }
+            };
+            let missed_blocks: Vec<SecureShareBlock> = {
+                let blocks = storage.read_blocks();
+                missed_ids
+                    .iter()
+                    .filter_map(|block_id| blocks.get(block_id).cloned())
+                    .collect()
+            };
+            for block in missed_blocks {
+                resent_ids.insert(block.id);
+                if !filter.matches(
+                    block.content.header.content.slot,
+                    &block.content_creator_address,
+                ) {
+                    continue;
+                }
+                if !send_block(&tx, request_id.clone(), block).await {
+                    return;
+                }
+            }
+        }
+
         loop {
             select! {
                 // Receive a new block from the subscriber
-                event = subscriber.recv() => {
+                 event = subscriber.recv() => {
                     match event {
                         Ok(massa_block) => {
+                            if resent_ids.remove(&massa_block.id)
+                                || !filter.matches(massa_block.content.header.content.slot, &massa_block.content_creator_address)
+                            {
+                                continue;
+                            }
                             // Send the new block through the channel
-                            if let Err(e) = tx.send(Ok(grpc_api::NewBlocksResponse {
-                                    id: request_id.clone(),
-                                    block: Some(massa_block.into())
-                            })).await {
-                                error!("failed to send new block : {}", e);
+                            if !send_block(&tx, request_id.clone(), massa_block).await {
                                 break;
                             }
                         },
-                        Err(e) => error!("error on receive new block : {}", e)
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_blocks", skipped);
+                            }
+                            error!("error on receive new block : {}", e)
+                        }
                     }
                 },
-                // Receive a new message from the in_stream
-                res = in_stream.next() => {
-                    match res {
-                        Some(res) => {
-                            match res {
-                                // Get the request_id from the received data
-                                Ok(data) => {
-                                    request_id = data.id
-                                },
-                                // Handle any errors that may occur during receiving the data
-                                Err(err) => {
-                                    // Check if the error matches any IO errors
-                                    if let Some(io_err) = match_for_io_error(&err) {
-                                        if io_err.kind() == ErrorKind::BrokenPipe {
-                                            warn!("client disconnected, broken pipe: {}", io_err);
-                                            break;
+            // Receive a new message from the in_stream
+            res = in_stream.next() => {
+                match res {
+                    Some(res) => {
+                        match res {
+                            // Update the request_id and the filter
+                            Ok(data) => {
+                                request_id = data.id;
+                                if let Some(query) = data.query {
+                                    match BlocksFilter::new(query.filter) {
+                                        Ok(new_filter) => filter = new_filter,
+                                        Err(err) => {
+                                            if let Err(e) = tx.send(Err(err.into())).await {
+                                                error!("failed to send back new_blocks error response: {}", e);
+                                                break;
+                                            }
                                         }
                                     }
-                                    error!("{}", err);
-                                    // Send the error response back to the client
-                                    if let Err(e) = tx.send(Err(err)).await {
-                                        error!("failed to send back new_blocks error response: {}", e);
+                                }
+                            },
+                            // Handle any errors that may occur during receiving the data
+                            Err(err) => {
+                                // Check if the error matches any IO errors
+                                if let Some(io_err) = match_for_io_error(&err) {
+                                    if io_err.kind() == ErrorKind::BrokenPipe {
+                                        warn!("client disconnected, broken pipe: {}", io_err);
                                         break;
                                     }
                                 }
+                                error!("{}", err);
+                                // Send the error response back to the client
+                                if let Err(e) = tx.send(Err(err)).await {
+                                    error!("failed to send back new_blocks error response: {}", e);
+                                    break;
+                                }
                             }
-                        },
-                        None => {
-                            // The client has disconnected
-                            break;
-                        },
-                    }
+                        }
+                    },
+                    None => {
+                        // The client has disconnected
+                        break;
+                    },
                 }
             }
+            }
         }
     });
 
-    // Create a new stream from the received channel
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
-
-    // Return the new stream of blocks
+    let out_stream = rx.into_stream();
     Ok(Box::pin(out_stream) as NewBlocksStreamType)
 }
+
+/// Sends a block with its cursor to the client, returns false if the client is gone
+async fn send_block(
+    tx: &StreamSender<Result<grpc_api::NewBlocksResponse, tonic::Status>>,
+    id: String,
+    massa_block: SecureShareBlock,
+) -> bool {
+    let cursor = cursor(massa_block.content.header.content.slot);
+    if let Err(e) = tx
+        .send(Ok(grpc_api::NewBlocksResponse {
+            id,
+            block: Some(massa_block.into()),
+            cursor,
+        }))
+        .await
+    {
+        error!("failed to send new block : {}", e);
+        return false;
+    }
+    true
+}
@@ -2,11 +2,17 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
+use crate::stream::blocks_filter::{blocks_after_cursor, cursor, parse_cursor, BlocksFilter};
+use crate::stream::channel::{stream_channel, StreamSender};
 use futures_util::StreamExt;
+use massa_models::block_header::SecuredHeader;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -21,36 +27,107 @@ pub type NewBlocksHeadersStreamType = Pin<
 >;
 
 /// Creates a new stream of new produced and received blocks headers
+///
+/// The filter can be replaced by each message of the client.
+/// The resume cursor is only read from the first message: the headers of the blocks following it are sent before the new ones.
 pub(crate) async fn new_blocks_headers(
     grpc: &MassaGrpc,
     request: Request<Streaming<grpc_api::NewBlocksHeadersRequest>>,
 ) -> Result<NewBlocksHeadersStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(
+        &grpc.grpc_config,
+        grpc.metrics.clone(),
+        "new_blocks_headers",
+    );
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new blocks headers channel
+    // Subscribe to the new blocks headers channel before collecting the missed headers so that none is lost in between
     let mut subscriber = grpc.consensus_channels.block_header_sender.subscribe();
+    let consensus_controller = grpc.consensus_controller.clone();
+    let storage = grpc.storage.clone_without_refs();
+
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
 
     tokio::spawn(async move {
-        // Initialize the request_id string
-        let mut request_id = String::new();
+        let Some(Ok(request)) = in_stream.next().await else {
+            error!("empty request");
+            return;
+        };
+        let mut request_id = request.id;
+        let query = request.query.unwrap_or_default();
+        let mut filter = match BlocksFilter::new(query.filter) {
+            Ok(filter) => filter,
+            Err(err) => {
+                if let Err(e) = tx.send(Err(err.into())).await {
+                    error!(
+                        "failed to send back new_blocks_headers error response: {}",
+                        e
+                    );
+                }
+                return;
+            }
+        };
+
+        // Send the headers missed since the resume cursor
+        let mut resent_ids = PreHashSet::<BlockId>::default();
+        if let Some(resume_cursor) = query.resume_cursor {
+            let missed_ids = match parse_cursor(&resume_cursor)
+                .and_then(|cursor_slot| blocks_after_cursor(&*consensus_controller, cursor_slot))
+            {
+                Ok(missed_ids) => missed_ids,
+                Err(err) => {
+                    if let Err(e) = tx.send(Err(err.into())).await {
+                        error!(
+                            "failed to send back new_blocks_headers error response: {}",
+                            e
+                        );
+                    }
+                    return;
+                }
+            };
+            let missed_headers: Vec<SecuredHeader> = {
+                let blocks = storage.read_blocks();
+                missed_ids
+                    .iter()
+                    .filter_map(|block_id| blocks.get(block_id))
+                    .map(|block| block.content.header.clone())
+                    .collect()
+            };
+            for header in missed_headers {
+                resent_ids.insert(header.id);
+                if !filter.matches(header.content.slot, &header.content_creator_address) {
+                    continue;
+                }
+                if !send_header(&tx, request_id.clone(), header).await {
+                    return;
+                }
+            }
+        }
+
         loop {
             select! {
                 // Receive a new block header from the subscriber
                  event = subscriber.recv() => {
                     match event {
                         Ok(massa_block_header) => {
+                            if resent_ids.remove(&massa_block_header.id)
+                                || !filter.matches(massa_block_header.content.slot, &massa_block_header.content_creator_address)
+                            {
+                                continue;
+                            }
                             // Send the new block header through the channel
-                            if let Err(e) = tx.send(Ok(grpc_api::NewBlocksHeadersResponse {
-                                    id: request_id.clone(),
-                                    block_header: Some(massa_block_header.into())
-                            })).await {
-                                error!("failed to send new block header : {}", e);
+                            if !send_header(&tx, request_id.clone(), massa_block_header).await {
                                 break;
                             }
                         },
-                        Err(e) => error!("error on receive new block header : {}", e)
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_blocks_headers", skipped);
+                            }
+                            error!("error on receive new block header : {}", e)
+                        }
                     }
                 },
             // Receive a new message from the in_stream
@@ -58,9 +135,20 @@ pub(crate) async fn new_blocks_headers(
                 match res {
                     Some(res) => {
                         match res {
-                            // Get the request_id from the received data
+                            // Update the request_id and the filter
                             Ok(data) => {
-                                request_id = data.id
+                                request_id = data.id;
+                                if let Some(query) = data.query {
+                                    match BlocksFilter::new(query.filter) {
+                                        Ok(new_filter) => filter = new_filter,
+                                        Err(err) => {
+                                            if let Err(e) = tx.send(Err(err.into())).await {
+                                                error!("failed to send back new_blocks_headers error response: {}", e);
+                                                break;
+                                            }
+                                        }
+                                    }
+                                }
                             },
                             // Handle any errors that may occur during receiving the data
                             Err(err) => {
@@ -90,6 +178,27 @@ pub(crate) async fn new_blocks_headers(
         }
     });
 
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
     Ok(Box::pin(out_stream) as NewBlocksHeadersStreamType)
 }
+
+/// Sends a block header with its cursor to the client, returns false if the client is gone
+async fn send_header(
+    tx: &StreamSender<Result<grpc_api::NewBlocksHeadersResponse, tonic::Status>>,
+    id: String,
+    header: SecuredHeader,
+) -> bool {
+    let cursor = cursor(header.content.slot);
+    if let Err(e) = tx
+        .send(Ok(grpc_api::NewBlocksHeadersResponse {
+            id,
+            block_header: Some(header.into()),
+            cursor,
+        }))
+        .await
+    {
+        error!("failed to send new block header : {}", e);
+        return false;
+    }
+    true
+}
@@ -0,0 +1,157 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
+use futures_util::StreamExt;
+use massa_models::address::Address;
+use massa_models::block_id::BlockId;
+use massa_models::denunciation::{Denunciation, DenunciationIndex};
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Type declaration for NewDenunciations
+pub type NewDenunciationsStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::NewDenunciationsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a new stream of new created and received denunciations
+///
+/// Denunciations created by the denunciation pool of the node are sent without block id.
+/// Denunciations found in the headers of the received blocks are sent with the id of the block denouncing them,
+/// so a denunciation created by the node is sent again once a block includes it.
+pub(crate) async fn new_denunciations(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::NewDenunciationsRequest>>,
+) -> Result<NewDenunciationsStreamType, GrpcError> {
+    // Create a channel to handle communication with the client
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_denunciations");
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+    // Subscribe to the new denunciations channel
+    let mut denunciation_subscriber = grpc.pool_channels.denunciation_sender.subscribe();
+    // Subscribe to the new blocks headers channel to get the denunciations included in blocks
+    let mut header_subscriber = grpc.consensus_channels.block_header_sender.subscribe();
+
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
+    tokio::spawn(async move {
+        // Initialize the request_id string
+        let mut request_id = String::new();
+        loop {
+            let denunciations = select! {
+                // Receive a new denunciation from the pool
+                event = denunciation_subscriber.recv() => {
+                    match event {
+                        Ok(denunciation) => vec![(denunciation, None)],
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_denunciations", skipped);
+                            }
+                            error!("error on receive new denunciation : {}", e);
+                            continue;
+                        }
+                    }
+                },
+                // Receive a new block header, possibly carrying denunciations
+                event = header_subscriber.recv() => {
+                    match event {
+                        Ok(header) => {
+                            let block_id = header.id;
+                            header
+                                .content
+                                .denunciations
+                                .into_iter()
+                                .map(|denunciation| (denunciation, Some(block_id)))
+                                .collect()
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_denunciations", skipped);
+                            }
+                            error!("error on receive new block header : {}", e);
+                            continue;
+                        }
+                    }
+                },
+                // Receive a new message from the in_stream
+                res = in_stream.next() => {
+                    match res {
+                        Some(res) => {
+                            match res {
+                                // Get the request_id from the received data
+                                Ok(data) => {
+                                    request_id = data.id;
+                                    continue;
+                                },
+                                // Handle any errors that may occur during receiving the data
+                                Err(err) => {
+                                    // Check if the error matches any IO errors
+                                    if let Some(io_err) = match_for_io_error(&err) {
+                                        if io_err.kind() == ErrorKind::BrokenPipe {
+                                            warn!("client disconnected, broken pipe: {}", io_err);
+                                            break;
+                                        }
+                                    }
+                                    error!("{}", err);
+                                    // Send the error response back to the client
+                                    if let Err(e) = tx.send(Err(err)).await {
+                                        error!("failed to send back new_denunciations error response: {}", e);
+                                        break;
+                                    }
+                                    continue;
+                                }
+                            }
+                        },
+                        None => {
+                            // The client has disconnected
+                            break;
+                        },
+                    }
+                }
+            };
+
+            for (denunciation, block_id) in denunciations {
+                // Send the new denunciation through the channel
+                if let Err(e) = tx
+                    .send(Ok(to_response(request_id.clone(), &denunciation, block_id)))
+                    .await
+                {
+                    error!("failed to send new denunciation : {}", e);
+                    return;
+                }
+            }
+        }
+    });
+
+    // Create a new stream from the received channel
+    let out_stream = rx.into_stream();
+
+    // Return the new stream of denunciations
+    Ok(Box::pin(out_stream) as NewDenunciationsStreamType)
+}
+
+/// Builds the message describing a denunciation: the denounced address, the slot and the type of the denunciation
+fn to_response(
+    id: String,
+    denunciation: &Denunciation,
+    block_id: Option<BlockId>,
+) -> grpc_api::NewDenunciationsResponse {
+    grpc_api::NewDenunciationsResponse {
+        id,
+        address: Address::from_public_key(denunciation.get_public_key()).to_string(),
+        index: Some(DenunciationIndex::from(denunciation).into()),
+        block_id: block_id.map(|block_id| block_id.to_string()),
+    }
+}
@@ -2,11 +2,13 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
 use futures_util::StreamExt;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -26,12 +28,15 @@ pub(crate) async fn new_endorsements(
     request: Request<Streaming<grpc_api::NewEndorsementsRequest>>,
 ) -> Result<NewEndorsementsStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_endorsements");
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new endorsements channel
     let mut subscriber = grpc.pool_channels.endorsement_sender.subscribe();
 
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
     tokio::spawn(async move {
         // Initialize the request_id string
         let mut request_id = String::new();
@@ -50,7 +55,12 @@ pub(crate) async fn new_endorsements(
                                 break;
                             }
                         },
-                        Err(e) => error!("error on receive new endorsement : {}", e)
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_endorsements", skipped);
+                            }
+                            error!("error on receive new endorsement : {}", e)
+                        }
                     }
                 },
                 // Receive a new message from the in_stream
@@ -91,7 +101,7 @@ pub(crate) async fn new_endorsements(
     });
 
     // Create a new stream from the received channel
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
 
     // Return the new stream of endorsements
     Ok(Box::pin(out_stream) as NewEndorsementsStreamType)
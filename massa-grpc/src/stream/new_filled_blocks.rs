@@ -2,11 +2,20 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
+use crate::size::{chunk_size, split_by_size};
+use crate::stream::blocks_filter::{blocks_after_cursor, cursor, parse_cursor, BlocksFilter};
+use crate::stream::channel::{stream_channel, StreamSender};
 use futures_util::StreamExt;
+use massa_models::block::FilledBlock;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
 use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use prost::Message;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -21,36 +30,122 @@ pub type NewFilledBlocksStreamType = Pin<
 >;
 
 /// Creates a new stream of new produced and received filled blocks
+///
+/// A filled block is complete: its header carries the endorsements,
+/// and the operations are sent with their content rather than only their ids.
+/// A filled block that does not fit in a message is sent over several messages sharing the header,
+/// each carrying a part of the operations and an `operations_chunk` giving its index and the number of parts.
+///
+/// The filter can be replaced by each message of the client.
+/// The resume cursor is only read from the first message: the filled blocks following it are sent before the new ones.
 pub(crate) async fn new_filled_blocks(
     grpc: &MassaGrpc,
     request: Request<Streaming<grpc_api::NewFilledBlocksRequest>>,
 ) -> Result<NewFilledBlocksStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_filled_blocks");
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
-    // Subscribe to the new filled blocks channel
+    // Subscribe to the new filled blocks channel before collecting the missed filled blocks so that none is lost in between
     let mut subscriber = grpc.consensus_channels.filled_block_sender.subscribe();
+    let consensus_controller = grpc.consensus_controller.clone();
+    let storage = grpc.storage.clone_without_refs();
+    let max_message_size = chunk_size(&grpc.grpc_config);
+
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
 
     tokio::spawn(async move {
-        // Initialize the request_id string
-        let mut request_id = String::new();
+        let Some(Ok(request)) = in_stream.next().await else {
+            error!("empty request");
+            return;
+        };
+        let mut request_id = request.id;
+        let query = request.query.unwrap_or_default();
+        let mut filter = match BlocksFilter::new(query.filter) {
+            Ok(filter) => filter,
+            Err(err) => {
+                if let Err(e) = tx.send(Err(err.into())).await {
+                    error!(
+                        "failed to send back new_filled_blocks error response: {}",
+                        e
+                    );
+                }
+                return;
+            }
+        };
+
+        // Send the filled blocks missed since the resume cursor
+        let mut resent_ids = PreHashSet::<BlockId>::default();
+        if let Some(resume_cursor) = query.resume_cursor {
+            let missed_ids = match parse_cursor(&resume_cursor)
+                .and_then(|cursor_slot| blocks_after_cursor(&*consensus_controller, cursor_slot))
+            {
+                Ok(missed_ids) => missed_ids,
+                Err(err) => {
+                    if let Err(e) = tx.send(Err(err.into())).await {
+                        error!(
+                            "failed to send back new_filled_blocks error response: {}",
+                            e
+                        );
+                    }
+                    return;
+                }
+            };
+            let missed_blocks: Vec<FilledBlock> = {
+                let blocks = storage.read_blocks();
+                let operations = storage.read_operations();
+                missed_ids
+                    .iter()
+                    .filter_map(|block_id| blocks.get(block_id))
+                    .map(|block| FilledBlock {
+                        header: block.content.header.clone(),
+                        operations: block
+                            .content
+                            .operations
+                            .iter()
+                            .map(|op_id| (*op_id, operations.get(op_id).cloned()))
+                            .collect(),
+                    })
+                    .collect()
+            };
+            for filled_block in missed_blocks {
+                resent_ids.insert(filled_block.header.id);
+                if !filter.matches(
+                    filled_block.header.content.slot,
+                    &filled_block.header.content_creator_address,
+                ) {
+                    continue;
+                }
+                if !send_filled_block(&tx, request_id.clone(), filled_block, max_message_size).await
+                {
+                    return;
+                }
+            }
+        }
+
         loop {
             select! {
                 // Receive a new filled block from the subscriber
                  event = subscriber.recv() => {
                     match event {
                         Ok(massa_filled_block) => {
+                            if resent_ids.remove(&massa_filled_block.header.id)
+                                || !filter.matches(massa_filled_block.header.content.slot, &massa_filled_block.header.content_creator_address)
+                            {
+                                continue;
+                            }
                             // Send the new filled block through the channel
-                            if let Err(e) = tx.send(Ok(grpc_api::NewFilledBlocksResponse {
-                                    id: request_id.clone(),
-                                    filled_block: Some(massa_filled_block.into())
-                            })).await {
-                                error!("failed to send new block : {}", e);
+                            if !send_filled_block(&tx, request_id.clone(), massa_filled_block, max_message_size).await {
                                 break;
                             }
                         },
-                        Err(e) => error!("error on receive new block : {}", e)
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_filled_blocks", skipped);
+                            }
+                            error!("error on receive new filled block : {}", e)
+                        }
                     }
                 },
             // Receive a new message from the in_stream
@@ -58,9 +153,20 @@ pub(crate) async fn new_filled_blocks(
                 match res {
                     Some(res) => {
                         match res {
-                            // Get the request_id from the received data
+                            // Update the request_id and the filter
                             Ok(data) => {
-                                request_id = data.id
+                                request_id = data.id;
+                                if let Some(query) = data.query {
+                                    match BlocksFilter::new(query.filter) {
+                                        Ok(new_filter) => filter = new_filter,
+                                        Err(err) => {
+                                            if let Err(e) = tx.send(Err(err.into())).await {
+                                                error!("failed to send back new_filled_blocks error response: {}", e);
+                                                break;
+                                            }
+                                        }
+                                    }
+                                }
                             },
                             // Handle any errors that may occur during receiving the data
                             Err(err) => {
@@ -90,6 +196,65 @@ pub(crate) async fn new_filled_blocks(
         }
     });
 
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
     Ok(Box::pin(out_stream) as NewFilledBlocksStreamType)
 }
+
+/// Sends a filled block with its cursor to the client, returns false if the client is gone
+async fn send_filled_block(
+    tx: &StreamSender<Result<grpc_api::NewFilledBlocksResponse, tonic::Status>>,
+    id: String,
+    massa_filled_block: FilledBlock,
+    max_message_size: usize,
+) -> bool {
+    let cursor = cursor(massa_filled_block.header.content.slot);
+    let filled_block: grpc_model::FilledBlock = massa_filled_block.into();
+    if filled_block.encoded_len() <= max_message_size {
+        return send_response(tx, id, filled_block, None, cursor).await;
+    }
+
+    // Split the operations over several messages
+    let header_size = filled_block.header.as_ref().map_or(0, |h| h.encoded_len());
+    let chunks = split_by_size(
+        filled_block.operations,
+        max_message_size.saturating_sub(header_size).max(1),
+    );
+    let count = chunks.len() as u32;
+    for (index, operations) in chunks.into_iter().enumerate() {
+        let part = grpc_model::FilledBlock {
+            header: filled_block.header.clone(),
+            operations,
+        };
+        let chunk = grpc_api::ChunkInfo {
+            index: index as u32,
+            count,
+        };
+        if !send_response(tx, id.clone(), part, Some(chunk), cursor.clone()).await {
+            return false;
+        }
+    }
+    true
+}
+
+/// Sends a filled block or a part of it, returns false if the client is gone
+async fn send_response(
+    tx: &StreamSender<Result<grpc_api::NewFilledBlocksResponse, tonic::Status>>,
+    id: String,
+    filled_block: grpc_model::FilledBlock,
+    operations_chunk: Option<grpc_api::ChunkInfo>,
+    cursor: String,
+) -> bool {
+    if let Err(e) = tx
+        .send(Ok(grpc_api::NewFilledBlocksResponse {
+            id,
+            filled_block: Some(filled_block),
+            operations_chunk,
+            cursor,
+        }))
+        .await
+    {
+        error!("failed to send new filled block : {}", e);
+        return false;
+    }
+    true
+}
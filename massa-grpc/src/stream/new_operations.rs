@@ -2,10 +2,12 @@
 
 use crate::error::GrpcError;
 use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
 use futures_util::StreamExt;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::error;
@@ -25,12 +27,15 @@ pub(crate) async fn new_operations(
     request: Request<Streaming<grpc_api::NewOperationsRequest>>,
 ) -> Result<NewOperationsStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_operations");
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new operations channel
     let mut subscriber = grpc.pool_channels.operation_sender.subscribe();
 
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
     tokio::spawn(async move {
         if let Some(Ok(request)) = in_stream.next().await {
             let mut request_id = request.id;
@@ -57,7 +62,12 @@ pub(crate) async fn new_operations(
                                     break;
                                 }
                             },
-                            Err(e) => error!("{}", e)
+                            Err(e) => {
+                                if let RecvError::Lagged(skipped) = e {
+                                    metrics.inc_grpc_stream_dropped_messages("new_operations", skipped);
+                                }
+                                error!("{}", e)
+                            }
                         }
                     },
                     // Receive a new message from the in_stream
@@ -90,7 +100,7 @@ pub(crate) async fn new_operations(
         }
     });
 
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
     Ok(Box::pin(out_stream) as NewOperationsStreamType)
 }
 
@@ -0,0 +1,130 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
+use futures_util::StreamExt;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_protocol_exports::{PeerEvent, PeerEventKind};
+use std::io::ErrorKind;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Type declaration for NewPeerEvents
+pub type NewPeerEventsStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::NewPeerEventsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a new stream of peer connections, disconnections and bans
+///
+/// The events carry the ips of the peers, so the stream is only served when `enable_peer_events_stream` is set.
+/// Connections and disconnections are detected by the connectivity loop of the protocol,
+/// so they are sent at most `try_connection_timer` after they happened.
+pub(crate) async fn new_peer_events(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::NewPeerEventsRequest>>,
+) -> Result<NewPeerEventsStreamType, GrpcError> {
+    if !grpc.grpc_config.enable_peer_events_stream {
+        return Err(GrpcError::Unavailable(
+            "the peer events stream is disabled on this node".to_string(),
+        ));
+    }
+    // Create a channel to handle communication with the client
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_peer_events");
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+    // Subscribe to the peer events channel
+    let mut subscriber = grpc.peer_event_sender.subscribe();
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
+    tokio::spawn(async move {
+        // Initialize the request_id string
+        let mut request_id = String::new();
+        loop {
+            select! {
+                // Receive a new peer event from the protocol
+                event = subscriber.recv() => {
+                    match event {
+                        Ok(peer_event) => {
+                            // Send the peer event through the channel
+                            if let Err(e) = tx.send(Ok(to_response(request_id.clone(), peer_event))).await {
+                                error!("failed to send new peer event : {}", e);
+                                break;
+                            }
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_peer_events", skipped);
+                            }
+                            error!("error on receive new peer event : {}", e);
+                        }
+                    }
+                },
+                // Receive a new message from the in_stream
+                res = in_stream.next() => {
+                    match res {
+                        Some(res) => {
+                            match res {
+                                // Get the request_id from the received data
+                                Ok(data) => {
+                                    request_id = data.id;
+                                },
+                                // Handle any errors that may occur during receiving the data
+                                Err(err) => {
+                                    // Check if the error matches any IO errors
+                                    if let Some(io_err) = match_for_io_error(&err) {
+                                        if io_err.kind() == ErrorKind::BrokenPipe {
+                                            warn!("client disconnected, broken pipe: {}", io_err);
+                                            break;
+                                        }
+                                    }
+                                    error!("{}", err);
+                                    // Send the error response back to the client
+                                    if let Err(e) = tx.send(Err(err)).await {
+                                        error!("failed to send back new_peer_events error response: {}", e);
+                                        break;
+                                    }
+                                }
+                            }
+                        },
+                        None => {
+                            // The client has disconnected
+                            break;
+                        },
+                    }
+                }
+            }
+        }
+    });
+
+    // Create a new stream from the received channel
+    let out_stream = rx.into_stream();
+
+    // Return the new stream of peer events
+    Ok(Box::pin(out_stream) as NewPeerEventsStreamType)
+}
+
+/// Builds the message describing a peer event
+fn to_response(id: String, peer_event: PeerEvent) -> grpc_api::NewPeerEventsResponse {
+    let kind = match peer_event.kind {
+        PeerEventKind::Connected => grpc_api::PeerEventKind::Connected,
+        PeerEventKind::Disconnected => grpc_api::PeerEventKind::Disconnected,
+        PeerEventKind::Banned => grpc_api::PeerEventKind::Banned,
+    };
+    grpc_api::NewPeerEventsResponse {
+        id,
+        peer_id: peer_event.peer_id.to_string(),
+        ip: peer_event.ip.map(|ip| ip.to_string()),
+        kind: kind as i32,
+        reason: peer_event.reason,
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
+use futures_util::StreamExt;
+use massa_models::block::ReorgEvent;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Type declaration for NewReorgs
+pub type NewReorgsStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::NewReorgsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a new stream of blockclique reorgs
+///
+/// A reorg is sent when blocks leave the blockclique without becoming final,
+/// with the reverted block ids ordered from the latest slot so that indexers can roll them back in order.
+pub(crate) async fn new_reorgs(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::NewReorgsRequest>>,
+) -> Result<NewReorgsStreamType, GrpcError> {
+    // Create a channel to handle communication with the client
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "new_reorgs");
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+    // Subscribe to the reorgs channel
+    let mut subscriber = grpc.consensus_channels.reorg_sender.subscribe();
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
+    tokio::spawn(async move {
+        // Initialize the request_id string
+        let mut request_id = String::new();
+        loop {
+            select! {
+                // Receive a new reorg from the consensus
+                event = subscriber.recv() => {
+                    match event {
+                        Ok(reorg) => {
+                            // Send the reorg through the channel
+                            if let Err(e) = tx.send(Ok(to_response(request_id.clone(), reorg))).await {
+                                error!("failed to send new reorg : {}", e);
+                                break;
+                            }
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("new_reorgs", skipped);
+                            }
+                            error!("error on receive new reorg : {}", e);
+                        }
+                    }
+                },
+                // Receive a new message from the in_stream
+                res = in_stream.next() => {
+                    match res {
+                        Some(res) => {
+                            match res {
+                                // Get the request_id from the received data
+                                Ok(data) => {
+                                    request_id = data.id;
+                                },
+                                // Handle any errors that may occur during receiving the data
+                                Err(err) => {
+                                    // Check if the error matches any IO errors
+                                    if let Some(io_err) = match_for_io_error(&err) {
+                                        if io_err.kind() == ErrorKind::BrokenPipe {
+                                            warn!("client disconnected, broken pipe: {}", io_err);
+                                            break;
+                                        }
+                                    }
+                                    error!("{}", err);
+                                    // Send the error response back to the client
+                                    if let Err(e) = tx.send(Err(err)).await {
+                                        error!("failed to send back new_reorgs error response: {}", e);
+                                        break;
+                                    }
+                                }
+                            }
+                        },
+                        None => {
+                            // The client has disconnected
+                            break;
+                        },
+                    }
+                }
+            }
+        }
+    });
+
+    // Create a new stream from the received channel
+    let out_stream = rx.into_stream();
+
+    // Return the new stream of reorgs
+    Ok(Box::pin(out_stream) as NewReorgsStreamType)
+}
+
+/// Builds the message describing a reorg
+fn to_response(id: String, reorg: ReorgEvent) -> grpc_api::NewReorgsResponse {
+    grpc_api::NewReorgsResponse {
+        id,
+        old_tip: reorg.old_tip.to_string(),
+        new_tip: reorg.new_tip.to_string(),
+        depth: reorg.depth,
+        reverted_block_ids: reorg
+            .reverted_blocks
+            .iter()
+            .map(|block_id| block_id.to_string())
+            .collect(),
+    }
+}
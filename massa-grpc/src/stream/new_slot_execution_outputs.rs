@@ -2,6 +2,7 @@
 
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
+use crate::stream::channel::stream_channel;
 use futures_util::StreamExt;
 use massa_execution_exports::SlotExecutionOutput;
 use massa_proto_rs::massa::api::v1 as grpc_api;
@@ -9,6 +10,7 @@ use massa_proto_rs::massa::model::v1 as grpc_model;
 use std::io::ErrorKind;
 use std::pin::Pin;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::codegen::futures_core;
 use tonic::{Request, Streaming};
 use tracing::log::{error, warn};
@@ -29,7 +31,11 @@ pub(crate) async fn new_slot_execution_outputs(
     request: Request<Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
 ) -> Result<NewSlotExecutionOutputsStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(
+        &grpc.grpc_config,
+        grpc.metrics.clone(),
+        "new_slot_execution_outputs",
+    );
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new slot execution events channel
@@ -38,6 +44,9 @@ pub(crate) async fn new_slot_execution_outputs(
         .slot_execution_output_sender
         .subscribe();
 
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
     tokio::spawn(async move {
         // Initialize the request_id string
         if let Some(Ok(request)) = in_stream.next().await {
@@ -63,7 +72,12 @@ pub(crate) async fn new_slot_execution_outputs(
                                 }
                             },
 
-                            Err(e) => error!("error on receive new slot execution output : {}", e)
+                            Err(e) => {
+                                if let RecvError::Lagged(skipped) = e {
+                                    metrics.inc_grpc_stream_dropped_messages("new_slot_execution_outputs", skipped);
+                                }
+                                error!("error on receive new slot execution output : {}", e)
+                            }
                         }
                     },
                     // Receive a new message from the in_stream
@@ -110,7 +124,7 @@ pub(crate) async fn new_slot_execution_outputs(
     });
 
     // Create a new stream from the received channel
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
 
     // Return the new stream of slot execution output
     Ok(Box::pin(out_stream) as NewSlotExecutionOutputsStreamType)
@@ -0,0 +1,246 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use crate::stream::channel::{stream_channel, StreamSender};
+use futures_util::StreamExt;
+use massa_consensus_exports::ConsensusController;
+use massa_models::block::FilledBlock;
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_storage::Storage;
+use std::io::ErrorKind;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Type declaration for ReplayBlocks
+pub type ReplayBlocksStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::ReplayBlocksResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Creates a stream replaying the final blocks from a start slot, then sending the new blocks.
+///
+/// The first request gives the start slot and whether blocks should be sent with their operations.
+/// The final blocks still kept by the node are replayed in slot order with `live` set to false,
+/// then the blocks integrated in the graph are sent as they come with `live` set to true, as `NewBlocks` does.
+/// Blocks received live while replaying are sent only once.
+pub(crate) async fn replay_blocks(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::ReplayBlocksRequest>>,
+) -> Result<ReplayBlocksStreamType, GrpcError> {
+    // Create a channel to handle communication with the client
+    let (tx, rx) = stream_channel(&grpc.grpc_config, grpc.metrics.clone(), "replay_blocks");
+    // Get the inner stream from the request
+    let mut in_stream = request.into_inner();
+    // Subscribe before collecting the history so that no block is missed in between
+    let mut block_subscriber = grpc.consensus_channels.block_sender.subscribe();
+    let mut filled_block_subscriber = grpc.consensus_channels.filled_block_sender.subscribe();
+    let consensus_controller = grpc.consensus_controller.clone();
+    let storage = grpc.storage.clone_without_refs();
+    // Used to count the messages dropped when the subscriber lags behind
+    let metrics = grpc.metrics.clone();
+
+    tokio::spawn(async move {
+        let Some(Ok(request)) = in_stream.next().await else {
+            error!("empty request");
+            return;
+        };
+        let request_id = request.id;
+        let filled = request.filled;
+        let start_slot = request
+            .start_slot
+            .map(|slot| Slot::new(slot.period, slot.thread as u8))
+            .unwrap_or_else(Slot::min);
+
+        // Replay the final blocks
+        let replayed =
+            match replay_final_blocks(&*consensus_controller, &storage, start_slot, filled) {
+                Ok(replayed) => replayed,
+                Err(err) => {
+                    if let Err(e) = tx.send(Err(err.into())).await {
+                        error!("failed to send back replay_blocks error response: {}", e);
+                    }
+                    return;
+                }
+            };
+        let mut replayed_ids = PreHashSet::<BlockId>::default();
+        for (block_id, response) in replayed {
+            replayed_ids.insert(block_id);
+            if !send_response(&tx, request_id.clone(), response, false).await {
+                return;
+            }
+        }
+
+        // Switch to live mode
+        loop {
+            let response = select! {
+                event = block_subscriber.recv(), if !filled => {
+                    match event {
+                        Ok(block) => {
+                            let block_id = block.id;
+                            if replayed_ids.remove(&block_id) || block.content.header.content.slot < start_slot {
+                                continue;
+                            }
+                            ReplayedBlock::Block(block.into())
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("replay_blocks", skipped);
+                            }
+                            error!("error on receive new block : {}", e);
+                            continue;
+                        }
+                    }
+                },
+                event = filled_block_subscriber.recv(), if filled => {
+                    match event {
+                        Ok(filled_block) => {
+                            let block_id = filled_block.header.id;
+                            if replayed_ids.remove(&block_id) || filled_block.header.content.slot < start_slot {
+                                continue;
+                            }
+                            ReplayedBlock::FilledBlock(filled_block.into())
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("replay_blocks", skipped);
+                            }
+                            error!("error on receive new filled block : {}", e);
+                            continue;
+                        }
+                    }
+                },
+                // Watch the in_stream to detect the client disconnection
+                res = in_stream.next() => {
+                    match res {
+                        Some(Ok(_)) => continue,
+                        Some(Err(err)) => {
+                            // Check if the error matches any IO errors
+                            if let Some(io_err) = match_for_io_error(&err) {
+                                if io_err.kind() == ErrorKind::BrokenPipe {
+                                    warn!("client disconnected, broken pipe: {}", io_err);
+                                    break;
+                                }
+                            }
+                            error!("{}", err);
+                            if let Err(e) = tx.send(Err(err)).await {
+                                error!("failed to send back replay_blocks error response: {}", e);
+                                break;
+                            }
+                            continue;
+                        },
+                        // The client has disconnected
+                        None => break,
+                    }
+                }
+            };
+            if !send_response(&tx, request_id.clone(), response, true).await {
+                break;
+            }
+        }
+    });
+
+    // Create a new stream from the received channel
+    let out_stream = rx.into_stream();
+
+    // Return the new stream of blocks
+    Ok(Box::pin(out_stream) as ReplayBlocksStreamType)
+}
+
+/// A block sent back to the client, with or without its operations
+enum ReplayedBlock {
+    /// block with operation ids
+    Block(massa_proto_rs::massa::model::v1::SignedBlock),
+    /// block with operations content
+    FilledBlock(massa_proto_rs::massa::model::v1::FilledBlock),
+}
+
+/// Sends a block to the client, returns false if the client is gone
+async fn send_response(
+    tx: &StreamSender<Result<grpc_api::ReplayBlocksResponse, tonic::Status>>,
+    id: String,
+    block: ReplayedBlock,
+    live: bool,
+) -> bool {
+    let (block, filled_block) = match block {
+        ReplayedBlock::Block(block) => (Some(block), None),
+        ReplayedBlock::FilledBlock(filled_block) => (None, Some(filled_block)),
+    };
+    if let Err(e) = tx
+        .send(Ok(grpc_api::ReplayBlocksResponse {
+            id,
+            block,
+            filled_block,
+            live,
+        }))
+        .await
+    {
+        error!("failed to send replayed block : {}", e);
+        return false;
+    }
+    true
+}
+
+/// Collects the final blocks from `start_slot`, sorted by slot.
+/// Fails if the node no longer keeps the blocks of the requested start slot.
+fn replay_final_blocks(
+    consensus_controller: &dyn ConsensusController,
+    storage: &Storage,
+    start_slot: Slot,
+    filled: bool,
+) -> Result<Vec<(BlockId, ReplayedBlock)>, GrpcError> {
+    let graph = consensus_controller.get_block_graph_status(None, None)?;
+    let mut final_blocks: Vec<(Slot, BlockId)> = graph
+        .active_blocks
+        .iter()
+        .filter(|(_, block)| block.is_final)
+        .map(|(block_id, block)| (block.header.content.slot, *block_id))
+        .collect();
+    final_blocks.sort_unstable();
+
+    if let Some((oldest_slot, _)) = final_blocks.first() {
+        if start_slot < *oldest_slot && !graph.genesis_blocks.contains(&final_blocks[0].1) {
+            return Err(GrpcError::InvalidArgument(format!(
+                "start slot {} is older than the oldest final block kept by the node at slot {}",
+                start_slot, oldest_slot
+            )));
+        }
+    }
+
+    let blocks = storage.read_blocks();
+    let operations = storage.read_operations();
+    Ok(final_blocks
+        .into_iter()
+        .filter(|(slot, _)| *slot >= start_slot)
+        .filter_map(|(_, block_id)| {
+            let block = blocks.get(&block_id)?;
+            let replayed = if filled {
+                ReplayedBlock::FilledBlock(
+                    FilledBlock {
+                        header: block.content.header.clone(),
+                        operations: block
+                            .content
+                            .operations
+                            .iter()
+                            .map(|op_id| (*op_id, operations.get(op_id).cloned()))
+                            .collect(),
+                    }
+                    .into(),
+                )
+            } else {
+                ReplayedBlock::Block(block.clone().into())
+            };
+            Some((block_id, replayed))
+        })
+        .collect())
+}
@@ -3,6 +3,7 @@
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaGrpc;
 use futures_util::StreamExt;
+use massa_models::address::Address;
 use massa_models::mapping_grpc::secure_share_to_vec;
 use massa_models::operation::{OperationDeserializer, SecureShareOperation};
 use massa_models::secure_share::SecureShareDeserializer;
@@ -104,6 +105,25 @@ pub(crate) async fn send_operations(
                             match verified_ops_res {
                                 // If all operations in the incoming message are valid, store and propagate them
                                 Ok(verified_ops) => {
+                                    // Reject the message if a sender is in the local denylist of the pool
+                                    let senders: Vec<Address> = verified_ops
+                                        .values()
+                                        .map(|op| op.content_creator_address)
+                                        .collect();
+                                    if let Some(sender) = senders
+                                        .iter()
+                                        .zip(pool_command_sender.contains_denied_senders(&senders))
+                                        .find_map(|(sender, denied)| denied.then_some(sender))
+                                    {
+                                        report_error(
+                                            req_content.id.clone(),
+                                            tx.clone(),
+                                            tonic::Code::PermissionDenied,
+                                            format!("operations of {} are rejected by the local policy of this node", sender),
+                                        )
+                                        .await;
+                                        continue;
+                                    }
                                     let mut operation_storage = storage.clone_without_refs();
                                     operation_storage
                                         .store_operations(verified_ops.values().cloned().collect());
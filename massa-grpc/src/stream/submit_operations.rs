@@ -0,0 +1,423 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::{match_for_io_error, GrpcError};
+use crate::server::MassaGrpc;
+use futures_util::StreamExt;
+use massa_execution_exports::SlotExecutionOutput;
+use massa_models::block_id::BlockId;
+use massa_models::mapping_grpc::secure_share_to_vec;
+use massa_models::operation::{OperationDeserializer, OperationId, SecureShareOperation};
+use massa_models::prehash::PreHashMap;
+use massa_models::secure_share::SecureShareDeserializer;
+use massa_pool_exports::OperationLifecycleStatus;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+use massa_serialization::{DeserializeError, Deserializer};
+use std::io::ErrorKind;
+use std::pin::Pin;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
+use tonic::codegen::futures_core;
+use tonic::{Request, Streaming};
+use tracing::log::{error, warn};
+
+/// Type declaration for SubmitOperations
+pub type SubmitOperationsStreamType = Pin<
+    Box<
+        dyn futures_core::Stream<Item = Result<grpc_api::SubmitOperationsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// An operation submitted through the stream and not yet final nor dropped
+struct TrackedOperation {
+    /// id of the request that submitted the operation
+    request_id: String,
+    /// last period at which the operation can be included in a block
+    expire_period: u64,
+}
+
+/// Creates a bidirectional stream in which the client submits operations
+/// and receives the status of each of them as it evolves.
+///
+/// Each operation is reported as accepted once added to the pool, then propagated once sent to the network.
+/// It is then reported as included each time a block containing it is integrated in the graph,
+/// and as final once executed in a final slot, at which point it is no longer tracked.
+/// Invalid operations, operations that failed to be propagated, operations that expired
+/// without being executed and operations evicted from the pool are reported as dropped with the reason.
+pub(crate) async fn submit_operations(
+    grpc: &MassaGrpc,
+    request: Request<Streaming<grpc_api::SubmitOperationsRequest>>,
+) -> Result<SubmitOperationsStreamType, GrpcError> {
+    let mut pool_command_sender = grpc.pool_command_sender.clone();
+    let protocol_command_sender = grpc.protocol_command_sender.clone();
+    let config = grpc.grpc_config.clone();
+    let storage = grpc.storage.clone_without_refs();
+
+    // Create a channel for sending responses to the client
+    let (tx, rx) = tokio::sync::mpsc::channel(config.max_channel_size);
+    // Extract the incoming stream of operations messages
+    let mut in_stream = request.into_inner();
+    // Subscribe to the new blocks and to the executed slots to follow the submitted operations
+    let mut block_subscriber = grpc.consensus_channels.block_sender.subscribe();
+    let mut slot_execution_output_subscriber = grpc
+        .execution_channels
+        .slot_execution_output_sender
+        .subscribe();
+    // Subscribe to the lifecycle of the operations in the pool to report the evicted ones
+    let mut operation_lifecycle_subscriber =
+        grpc.pool_channels.operation_lifecycle_sender.subscribe();
+    // Used to count the messages dropped when the subscribers lag behind
+    let metrics = grpc.metrics.clone();
+
+    tokio::spawn(async move {
+        let operation_deserializer = SecureShareDeserializer::new(OperationDeserializer::new(
+            config.max_datastore_value_length,
+            config.max_function_name_length,
+            config.max_parameter_size,
+            config.max_op_datastore_entry_count,
+            config.max_op_datastore_key_length,
+            config.max_op_datastore_value_length,
+        ));
+        let mut tracked_operations: PreHashMap<OperationId, TrackedOperation> =
+            PreHashMap::default();
+
+        loop {
+            select! {
+                // Receive the operations submitted by the client
+                res = in_stream.next() => {
+                    match res {
+                        Some(Ok(req_content)) => {
+                            if req_content.operations.is_empty() {
+                                report_error(
+                                    &tx,
+                                    req_content.id,
+                                    tonic::Code::InvalidArgument,
+                                    "the request payload is empty".to_owned(),
+                                )
+                                .await;
+                                continue;
+                            }
+                            if req_content.operations.len() as u32 > config.max_operations_per_message {
+                                report_error(
+                                    &tx,
+                                    req_content.id,
+                                    tonic::Code::InvalidArgument,
+                                    "too many operations per message".to_owned(),
+                                )
+                                .await;
+                                continue;
+                            }
+
+                            // Deserialize and verify each operation, the invalid ones are dropped
+                            let mut verified_ops = Vec::with_capacity(req_content.operations.len());
+                            for proto_operation in req_content.operations {
+                                match verify_operation(&operation_deserializer, proto_operation) {
+                                    Ok(operation) => {
+                                        if tracked_operations.contains_key(&operation.id) {
+                                            continue;
+                                        }
+                                        if pool_command_sender.contains_denied_senders(&[operation.content_creator_address])[0] {
+                                            let status = OperationStatus::Dropped(
+                                                "sender rejected by the local policy of this node".to_owned(),
+                                            );
+                                            if !send_status(&tx, req_content.id.clone(), operation.id.to_string(), status).await {
+                                                return;
+                                            }
+                                            continue;
+                                        }
+                                        if tracked_operations.len() + verified_ops.len()
+                                            >= config.max_tracked_operations_per_stream
+                                        {
+                                            let status = OperationStatus::Dropped(
+                                                "too many operations tracked by the stream".to_owned(),
+                                            );
+                                            if !send_status(&tx, req_content.id.clone(), operation.id.to_string(), status).await {
+                                                return;
+                                            }
+                                            continue;
+                                        }
+                                        verified_ops.push(operation);
+                                    }
+                                    Err(e) => {
+                                        let status = OperationStatus::Dropped(format!("invalid operation: {}", e));
+                                        if !send_status(&tx, req_content.id.clone(), String::new(), status).await {
+                                            return;
+                                        }
+                                    }
+                                }
+                            }
+                            if verified_ops.is_empty() {
+                                continue;
+                            }
+
+                            let op_ids: Vec<OperationId> = verified_ops.iter().map(|op| op.id).collect();
+                            for op in &verified_ops {
+                                tracked_operations.insert(
+                                    op.id,
+                                    TrackedOperation {
+                                        request_id: req_content.id.clone(),
+                                        expire_period: op.content.expire_period,
+                                    },
+                                );
+                            }
+                            let mut operation_storage = storage.clone_without_refs();
+                            operation_storage.store_operations(verified_ops);
+
+                            // Add the received operations to the operations pool
+                            pool_command_sender.add_operations(operation_storage.clone());
+                            for op_id in &op_ids {
+                                if !send_status(&tx, req_content.id.clone(), op_id.to_string(), OperationStatus::Accepted).await {
+                                    return;
+                                }
+                            }
+
+                            // Propagate the operations to the network
+                            let status = match protocol_command_sender.propagate_operations(operation_storage) {
+                                Ok(()) => OperationStatus::Propagated,
+                                Err(e) => {
+                                    let reason = format!("failed to propagate operation: {}", e);
+                                    error!("{}", reason);
+                                    for op_id in &op_ids {
+                                        tracked_operations.remove(op_id);
+                                    }
+                                    OperationStatus::Dropped(reason)
+                                }
+                            };
+                            for op_id in &op_ids {
+                                if !send_status(&tx, req_content.id.clone(), op_id.to_string(), status.clone()).await {
+                                    return;
+                                }
+                            }
+                        }
+                        Some(Err(err)) => {
+                            // Check if the error matches any IO errors
+                            if let Some(io_err) = match_for_io_error(&err) {
+                                if io_err.kind() == ErrorKind::BrokenPipe {
+                                    warn!("client disconnected, broken pipe: {}", io_err);
+                                    break;
+                                }
+                            }
+                            error!("{}", err);
+                            // Send the error response back to the client
+                            if let Err(e) = tx.send(Err(err)).await {
+                                error!("failed to send back submit_operations error response: {}", e);
+                                break;
+                            }
+                        }
+                        // The client has disconnected
+                        None => break,
+                    }
+                },
+                // Report the tracked operations included in the new blocks
+                event = block_subscriber.recv() => {
+                    match event {
+                        Ok(block) => {
+                            for op_id in &block.content.operations {
+                                if let Some(tracked) = tracked_operations.get(op_id) {
+                                    let status = OperationStatus::Included(block.id);
+                                    if !send_status(&tx, tracked.request_id.clone(), op_id.to_string(), status).await {
+                                        return;
+                                    }
+                                }
+                            }
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("submit_operations", skipped);
+                            }
+                            error!("error on receive new block : {}", e);
+                        }
+                    }
+                },
+                // Report the tracked operations executed or expired in the final slots
+                event = slot_execution_output_subscriber.recv() => {
+                    match event {
+                        Ok(SlotExecutionOutput::FinalizedSlot(output)) => {
+                            for (op_id, (success, _)) in &output.state_changes.executed_ops_changes {
+                                if let Some(tracked) = tracked_operations.remove(op_id) {
+                                    let status = OperationStatus::Final(output.block_id, *success);
+                                    if !send_status(&tx, tracked.request_id, op_id.to_string(), status).await {
+                                        return;
+                                    }
+                                }
+                            }
+                            // Once a slot of a later period is final, the expired operations can no longer be executed
+                            let expired: Vec<OperationId> = tracked_operations
+                                .iter()
+                                .filter(|(_, tracked)| tracked.expire_period < output.slot.period)
+                                .map(|(op_id, _)| *op_id)
+                                .collect();
+                            for op_id in expired {
+                                if let Some(tracked) = tracked_operations.remove(&op_id) {
+                                    let status = OperationStatus::Dropped("operation expired".to_owned());
+                                    if !send_status(&tx, tracked.request_id, op_id.to_string(), status).await {
+                                        return;
+                                    }
+                                }
+                            }
+                        },
+                        Ok(SlotExecutionOutput::ExecutedSlot(_)) => {},
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("submit_operations", skipped);
+                            }
+                            error!("error on receive new slot execution output : {}", e);
+                        }
+                    }
+                },
+                // Report the tracked operations expired or evicted from the pool,
+                // except the ones evicted because this node cannot include them
+                event = operation_lifecycle_subscriber.recv() => {
+                    match event {
+                        Ok(event) => {
+                            let reason = match event.status {
+                                OperationLifecycleStatus::Expired => "operation expired".to_owned(),
+                                OperationLifecycleStatus::Evicted { reason } if !reason.is_local() => {
+                                    format!("operation evicted from the pool: {}", reason)
+                                }
+                                _ => continue,
+                            };
+                            if let Some(tracked) = tracked_operations.remove(&event.operation_id) {
+                                let status = OperationStatus::Dropped(reason);
+                                if !send_status(&tx, tracked.request_id, event.operation_id.to_string(), status).await {
+                                    return;
+                                }
+                            }
+                        },
+                        Err(e) => {
+                            if let RecvError::Lagged(skipped) = e {
+                                metrics.inc_grpc_stream_dropped_messages("submit_operations", skipped);
+                            }
+                            error!("error on receive operation lifecycle event : {}", e);
+                        }
+                    }
+                },
+            }
+        }
+    });
+
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    Ok(Box::pin(out_stream) as SubmitOperationsStreamType)
+}
+
+/// Status of a submitted operation sent back to the client
+#[derive(Clone)]
+enum OperationStatus {
+    /// added to the operations pool
+    Accepted,
+    /// sent to the network
+    Propagated,
+    /// included in the given block
+    Included(BlockId),
+    /// executed in a final slot, with the block containing it and whether the execution succeeded
+    Final(Option<BlockId>, bool),
+    /// dropped for the given reason
+    Dropped(String),
+}
+
+/// Deserializes an operation and verifies its signature
+fn verify_operation(
+    operation_deserializer: &SecureShareDeserializer<OperationDeserializer>,
+    proto_operation: massa_proto_rs::massa::model::v1::SecureShare,
+) -> Result<SecureShareOperation, GrpcError> {
+    let op_serialized = secure_share_to_vec(proto_operation)?;
+    let (rest, operation): (&[u8], SecureShareOperation) = operation_deserializer
+        .deserialize::<DeserializeError>(&op_serialized)
+        .map_err(|e| {
+            GrpcError::InvalidArgument(format!("failed to deserialize operation: {}", e))
+        })?;
+    if !rest.is_empty() {
+        return Err(GrpcError::InvalidArgument(
+            "there is data left after operation deserialization".to_owned(),
+        ));
+    }
+    operation.verify_signature()?;
+    Ok(operation)
+}
+
+/// Sends the status of an operation to the client, returns false if the client is gone
+async fn send_status(
+    tx: &Sender<Result<grpc_api::SubmitOperationsResponse, tonic::Status>>,
+    id: String,
+    operation_id: String,
+    status: OperationStatus,
+) -> bool {
+    let (status, block_id, reason) = match status {
+        OperationStatus::Accepted => (
+            grpc_api::OperationSubmissionStatus::Accepted,
+            String::new(),
+            String::new(),
+        ),
+        OperationStatus::Propagated => (
+            grpc_api::OperationSubmissionStatus::Propagated,
+            String::new(),
+            String::new(),
+        ),
+        OperationStatus::Included(block_id) => (
+            grpc_api::OperationSubmissionStatus::Included,
+            block_id.to_string(),
+            String::new(),
+        ),
+        OperationStatus::Final(block_id, success) => (
+            grpc_api::OperationSubmissionStatus::Final,
+            block_id.map(|id| id.to_string()).unwrap_or_default(),
+            if success {
+                String::new()
+            } else {
+                "operation execution failed".to_owned()
+            },
+        ),
+        OperationStatus::Dropped(reason) => (
+            grpc_api::OperationSubmissionStatus::Dropped,
+            String::new(),
+            reason,
+        ),
+    };
+    if let Err(e) = tx
+        .send(Ok(grpc_api::SubmitOperationsResponse {
+            id,
+            operation_id,
+            status: status as i32,
+            block_id,
+            reason,
+            error: None,
+        }))
+        .await
+    {
+        error!("failed to send back operation status : {}", e);
+        return false;
+    }
+    true
+}
+
+/// Reports an error concerning a whole request to the client
+async fn report_error(
+    tx: &Sender<Result<grpc_api::SubmitOperationsResponse, tonic::Status>>,
+    id: String,
+    code: tonic::Code,
+    error: String,
+) {
+    error!("{}", error);
+    if let Err(e) = tx
+        .send(Ok(grpc_api::SubmitOperationsResponse {
+            id,
+            operation_id: String::new(),
+            status: grpc_api::OperationSubmissionStatus::Unspecified as i32,
+            block_id: String::new(),
+            reason: String::new(),
+            error: Some(massa_proto_rs::google::rpc::Status {
+                code: code.into(),
+                message: error,
+                details: Vec::new(),
+            }),
+        }))
+        .await
+    {
+        error!(
+            "failed to send back submit_operations error response: {}",
+            e
+        );
+    }
+}
@@ -34,7 +34,11 @@ pub(crate) async fn transactions_throughput(
     let execution_controller = grpc.execution_controller.clone();
 
     // Create a channel for sending responses to the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(
+        &grpc.grpc_config,
+        grpc.metrics.clone(),
+        "transactions_throughput",
+    );
     // Extract the incoming stream of operations messages
     let mut in_stream = request.into_inner();
 
@@ -93,6 +97,6 @@ pub(crate) async fn transactions_throughput(
         }
     });
 
-    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let out_stream = rx.into_stream();
     Ok(Box::pin(out_stream) as TransactionsThroughputStreamType)
 }
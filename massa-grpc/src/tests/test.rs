@@ -1,11 +1,23 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::config::GrpcConfig;
-use crate::server::MassaGrpc;
+use crate::access_log::AccessLogLayer;
+use crate::config::{GrpcConfig, StreamBackpressurePolicy};
+use crate::drain::DrainController;
+use crate::error::GrpcError;
+use crate::private::{
+    ban_nodes_by_ids, ban_nodes_by_ips, get_bans, unban_nodes_by_ids, unban_nodes_by_ips,
+};
+use crate::server::{MassaGrpc, MassaPrivateGrpc};
+use crate::size::split_by_size;
+use crate::stream::blocks_filter::{cursor, parse_cursor, BlocksFilter};
+use crate::stream::channel::channel;
+use futures_util::StreamExt;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::test_exports::MockConsensusControllerImpl;
 use massa_consensus_exports::ConsensusChannels;
 use massa_execution_exports::{test_exports::MockExecutionController, ExecutionChannels};
+use massa_metrics::MassaMetrics;
+use massa_models::address::Address;
 use massa_models::config::{
     ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
@@ -14,11 +26,17 @@ use massa_models::config::{
     MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX, PERIODS_PER_CYCLE, T0,
     THREAD_COUNT, VERSION,
 };
+use massa_models::node::NodeId;
+use massa_models::slot::Slot;
 use massa_pool_exports::test_exports::MockPoolController;
 use massa_pool_exports::PoolChannels;
 use massa_pos_exports::test_exports::MockSelectorController;
+use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::api::v1::massa_service_client::MassaServiceClient;
-use massa_protocol_exports::MockProtocolController;
+use massa_proto_rs::massa::model::v1 as grpc_model;
+use massa_protocol_exports::{BanTarget, MockProtocolController, PeerBan, PeerId};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -44,10 +62,15 @@ async fn test_start_grpc_server() {
         block_sender: tokio::sync::broadcast::channel(100).0,
         block_header_sender: tokio::sync::broadcast::channel(100).0,
         filled_block_sender: tokio::sync::broadcast::channel(100).0,
+        reorg_sender: tokio::sync::broadcast::channel(100).0,
+        discarded_block_sender: tokio::sync::broadcast::channel(100).0,
     };
 
     let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
     let operation_sender = tokio::sync::broadcast::channel(5000).0;
+    let operation_lifecycle_sender = tokio::sync::broadcast::channel(5000).0;
+    let denunciation_sender = tokio::sync::broadcast::channel(1000).0;
+    let peer_event_sender = tokio::sync::broadcast::channel(256).0;
     let slot_execution_output_sender = tokio::sync::broadcast::channel(5000).0;
 
     let grpc_config = GrpcConfig {
@@ -58,6 +81,7 @@ async fn test_start_grpc_server() {
         enable_reflection: true,
         enable_mtls: false,
         bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8888),
+        private_bind: None,
         accept_compressed: None,
         send_compressed: None,
         max_decoding_message_size: 4194304,
@@ -93,6 +117,13 @@ async fn test_start_grpc_server() {
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         max_block_ids_per_request: 50,
         max_operation_ids_per_request: 250,
+        max_datastore_entries_per_request: 500,
+        max_tracked_operations_per_stream: 10000,
+        stream_backpressure_policy: StreamBackpressurePolicy::Block,
+        enable_peer_events_stream: false,
+        enable_access_log: false,
+        access_log_file: None,
+        shutdown_drain_timeout: MassaTime::from_millis(1000),
         server_certificate_path: PathBuf::default(),
         server_private_key_path: PathBuf::default(),
         client_certificate_authority_root_path: PathBuf::default(),
@@ -115,16 +146,21 @@ async fn test_start_grpc_server() {
         pool_channels: PoolChannels {
             endorsement_sender,
             operation_sender,
+            operation_lifecycle_sender,
+            denunciation_sender,
             selector: selector_ctrl.0.clone(),
             execution_controller: execution_ctrl.0.clone(),
         },
         pool_command_sender: pool_ctrl.0,
         protocol_command_sender: Box::new(MockProtocolController::new()),
+        peer_event_sender,
         selector_controller: selector_ctrl.0,
         storage: shared_storage,
         grpc_config: grpc_config.clone(),
         version: *VERSION,
         mip_store,
+        drain_controller: DrainController::new(),
+        metrics: MassaMetrics::new(false, THREAD_COUNT),
     };
 
     let stop_handle = service.serve(&grpc_config).await.unwrap();
@@ -139,3 +175,310 @@ async fn test_start_grpc_server() {
     let _res = MassaServiceClient::new(channel);
     stop_handle.stop();
 }
+
+#[test]
+fn test_drain_rejects_new_requests() {
+    let drain_controller = DrainController::new();
+    let guard = drain_controller.enter().unwrap();
+    let status = drain_controller.status().unwrap();
+    assert!(!status.draining);
+    assert_eq!(status.active_requests, 1);
+
+    let status = drain_controller
+        .start_drain(MassaTime::from_millis(60_000))
+        .unwrap();
+    assert!(status.draining);
+    assert!(!status.drained);
+    assert!(drain_controller.enter().is_err());
+
+    drop(guard);
+    let status = drain_controller.status().unwrap();
+    assert_eq!(status.active_requests, 0);
+    assert!(status.drained);
+}
+
+#[tokio::test]
+async fn test_drain_closes_streams_with_final_status() {
+    let drain_controller = DrainController::new();
+    let guard = drain_controller.enter().unwrap();
+    // a subscription stream with two queued messages, never ending by itself
+    let stream = futures_util::stream::iter(vec![Ok(1), Ok(2)])
+        .chain(futures_util::stream::pending::<Result<u32, tonic::Status>>());
+    let stream = drain_controller.wrap_stream(guard, Box::pin(stream));
+
+    drain_controller
+        .start_drain(MassaTime::from_millis(100))
+        .unwrap();
+    let received: Vec<Result<u32, tonic::Status>> = stream.collect().await;
+    assert_eq!(received.len(), 3);
+    assert_eq!(*received[0].as_ref().unwrap(), 1);
+    assert_eq!(*received[1].as_ref().unwrap(), 2);
+    assert_eq!(
+        received[2].as_ref().unwrap_err().code(),
+        tonic::Code::Unavailable
+    );
+
+    let status = drain_controller.wait_drained().await.unwrap();
+    assert_eq!(status.active_requests, 0);
+}
+
+#[test]
+fn test_blocks_filter_and_cursor() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+    let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    let no_filter = BlocksFilter::new(None).unwrap();
+    assert!(no_filter.matches(Slot::new(1, 0), &other));
+
+    let filter = BlocksFilter::new(Some(grpc_api::BlocksFilter {
+        slot_range: Some(grpc_model::SlotRange {
+            start_slot: Some(grpc_model::Slot {
+                period: 2,
+                thread: 0,
+            }),
+            end_slot: Some(grpc_model::Slot {
+                period: 4,
+                thread: 0,
+            }),
+        }),
+        addresses: vec![creator.to_string()],
+    }))
+    .unwrap();
+    assert!(!filter.matches(Slot::new(1, 5), &creator));
+    assert!(filter.matches(Slot::new(2, 0), &creator));
+    assert!(filter.matches(Slot::new(3, 31), &creator));
+    assert!(!filter.matches(Slot::new(4, 0), &creator));
+    assert!(!filter.matches(Slot::new(3, 0), &other));
+
+    assert!(BlocksFilter::new(Some(grpc_api::BlocksFilter {
+        slot_range: None,
+        addresses: vec!["not an address".to_string()],
+    }))
+    .is_err());
+
+    let slot = Slot::new(12, 7);
+    assert_eq!(parse_cursor(&cursor(slot)).unwrap(), slot);
+    assert!(parse_cursor("12").is_err());
+    assert!(parse_cursor("a:7").is_err());
+}
+
+#[tokio::test]
+async fn test_stream_channel_backpressure_policies() {
+    let metrics = MassaMetrics::new(false, THREAD_COUNT);
+
+    // the oldest messages are dropped
+    let (tx, rx) = channel::<u32>(
+        2,
+        StreamBackpressurePolicy::DropOldest,
+        metrics.clone(),
+        "test",
+    );
+    for i in 0..4 {
+        tx.send(Ok(i)).await.unwrap();
+    }
+    drop(tx);
+    let received: Vec<u32> = rx.into_stream().map(|res| res.unwrap()).collect().await;
+    assert_eq!(received, vec![2, 3]);
+
+    // the new messages are dropped
+    let (tx, rx) = channel::<u32>(
+        2,
+        StreamBackpressurePolicy::DropNewest,
+        metrics.clone(),
+        "test",
+    );
+    for i in 0..4 {
+        tx.send(Ok(i)).await.unwrap();
+    }
+    drop(tx);
+    let received: Vec<u32> = rx.into_stream().map(|res| res.unwrap()).collect().await;
+    assert_eq!(received, vec![0, 1]);
+
+    // the stream is closed with an error
+    let (tx, rx) = channel::<u32>(
+        2,
+        StreamBackpressurePolicy::Disconnect,
+        metrics.clone(),
+        "test",
+    );
+    tx.send(Ok(0)).await.unwrap();
+    tx.send(Ok(1)).await.unwrap();
+    assert!(tx.send(Ok(2)).await.is_err());
+    assert!(tx.send(Ok(3)).await.is_err());
+    let received: Vec<Result<u32, tonic::Status>> = rx.into_stream().collect().await;
+    assert_eq!(received.len(), 1);
+    assert_eq!(
+        received[0].as_ref().unwrap_err().code(),
+        tonic::Code::ResourceExhausted
+    );
+
+    // the sender waits for the client
+    let (tx, rx) = channel::<u32>(2, StreamBackpressurePolicy::Block, metrics, "test");
+    let producer = tokio::spawn(async move {
+        for i in 0..4 {
+            tx.send(Ok(i)).await.unwrap();
+        }
+    });
+    let received: Vec<u32> = rx.into_stream().map(|res| res.unwrap()).collect().await;
+    producer.await.unwrap();
+    assert_eq!(received, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_split_by_size() {
+    let entry = |size: usize| grpc_model::BytesMapFieldEntry {
+        key: vec![0],
+        value: vec![1; size],
+    };
+    // each entry is about 110 bytes once encoded
+    let entries: Vec<_> = (0..5).map(|_| entry(100)).collect();
+    let chunks = split_by_size(entries, 250);
+    assert_eq!(
+        chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+
+    // an entry larger than a chunk is alone in its chunk
+    let chunks = split_by_size(vec![entry(10), entry(1000), entry(10)], 250);
+    assert_eq!(
+        chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+        vec![1, 1, 1]
+    );
+    assert_eq!(chunks[1][0].value.len(), 1000);
+
+    assert!(split_by_size(Vec::<grpc_model::BytesMapFieldEntry>::new(), 250).is_empty());
+}
+
+#[tokio::test]
+async fn test_access_log() {
+    use tower::{Layer, Service};
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let layer = AccessLogLayer::new(true, Some(file.path())).unwrap();
+    let mut service = layer.layer(tower::service_fn(
+        |_request: hyper::Request<hyper::Body>| async {
+            // a trailers-only response carrying a NOT_FOUND status
+            let mut response = hyper::Response::new(hyper::Body::empty());
+            response
+                .headers_mut()
+                .insert("grpc-status", "5".parse().unwrap());
+            Ok::<_, std::convert::Infallible>(response)
+        },
+    ));
+
+    let request = hyper::Request::builder()
+        .uri("/massa.api.v1.MassaService/GetBlocks")
+        .body(hyper::Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    // the record is written once the response is complete
+    assert!(std::fs::read_to_string(file.path()).unwrap().is_empty());
+    drop(response);
+
+    let content = std::fs::read_to_string(file.path()).unwrap();
+    let record: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+    assert_eq!(record["method"], "/massa.api.v1.MassaService/GetBlocks");
+    assert_eq!(record["http_status"], 200);
+    assert_eq!(record["grpc_status"], 5);
+    assert!(record["peer"].is_null());
+    assert!(record["identity"].is_null());
+}
+
+#[test]
+fn test_private_bans() {
+    let node_id = NodeId::new(KeyPair::generate(0).unwrap().get_public_key());
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+    let peer_id = PeerId::from_public_key(node_id.get_public_key());
+
+    let mut protocol_controller = MockProtocolController::new();
+    let banned_peer = peer_id.clone();
+    protocol_controller
+        .expect_add_bans()
+        .withf(move |targets, duration, reason| {
+            targets == &vec![BanTarget::PeerId(banned_peer.clone())]
+                && *duration == Some(MassaTime::from_millis(60_000))
+                && reason == "spam"
+        })
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    protocol_controller
+        .expect_add_bans()
+        .withf(move |targets, duration, reason| {
+            targets == &vec![BanTarget::Ip(ip)]
+                && duration.is_none()
+                && reason == "banned by the node operator"
+        })
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    protocol_controller
+        .expect_remove_bans()
+        .withf(move |targets| targets == &vec![BanTarget::Ip(ip)])
+        .times(1)
+        .returning(|_| Ok(()));
+    let listed_peer = peer_id.clone();
+    protocol_controller.expect_get_bans().returning(move || {
+        Ok(vec![PeerBan {
+            target: BanTarget::PeerId(listed_peer.clone()),
+            reason: "spam".to_string(),
+            banned_at: MassaTime::from_millis(1_000),
+            expires_at: Some(MassaTime::from_millis(61_000)),
+        }])
+    });
+    let grpc = MassaPrivateGrpc {
+        protocol_command_sender: Box::new(protocol_controller),
+        metrics: MassaMetrics::new(false, THREAD_COUNT),
+    };
+
+    ban_nodes_by_ids(
+        &grpc,
+        tonic::Request::new(grpc_api::BanNodesByIdsRequest {
+            node_ids: vec![node_id.to_string()],
+            duration: Some(60_000),
+            reason: Some("spam".to_string()),
+        }),
+    )
+    .unwrap();
+    ban_nodes_by_ips(
+        &grpc,
+        tonic::Request::new(grpc_api::BanNodesByIpsRequest {
+            ips: vec![ip.to_string()],
+            duration: None,
+            reason: None,
+        }),
+    )
+    .unwrap();
+    unban_nodes_by_ips(
+        &grpc,
+        tonic::Request::new(grpc_api::UnbanNodesByIpsRequest {
+            ips: vec![ip.to_string()],
+        }),
+    )
+    .unwrap();
+
+    let bans = get_bans(&grpc, tonic::Request::new(grpc_api::GetBansRequest {}))
+        .unwrap()
+        .bans;
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0].node_id, Some(node_id.to_string()));
+    assert_eq!(bans[0].ip, None);
+    assert_eq!(bans[0].expires_at, Some(61_000));
+
+    // malformed targets are rejected before reaching protocol
+    assert!(matches!(
+        ban_nodes_by_ips(
+            &grpc,
+            tonic::Request::new(grpc_api::BanNodesByIpsRequest {
+                ips: vec!["not an ip".to_string()],
+                duration: None,
+                reason: None,
+            }),
+        ),
+        Err(GrpcError::InvalidArgument(_))
+    ));
+    assert!(unban_nodes_by_ids(
+        &grpc,
+        tonic::Request::new(grpc_api::UnbanNodesByIdsRequest { node_ids: vec![] }),
+    )
+    .is_err());
+}
@@ -9,4 +9,6 @@ pub use settings::HASH_SIZE_BYTES;
 mod error;
 mod hash;
 pub use hash::*;
+mod merkle;
+pub use merkle::{compute_merkle_root, MerkleProof};
 mod settings;
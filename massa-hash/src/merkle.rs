@@ -0,0 +1,152 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Binary Merkle tree over a list of leaf hashes.
+//!
+//! Leaves and internal nodes are domain-separated to prevent second preimage attacks.
+//! When a level has an odd number of nodes, the last node is promoted unchanged to the next level.
+
+use crate::Hash;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of a leaf node
+const LEAF_PREFIX: u8 = 0;
+/// Prefix of an internal node
+const NODE_PREFIX: u8 = 1;
+
+fn hash_leaf(leaf: &Hash) -> Hash {
+    let mut bytes = Vec::with_capacity(1 + leaf.to_bytes().len());
+    bytes.push(LEAF_PREFIX);
+    bytes.extend_from_slice(leaf.to_bytes());
+    Hash::compute_from(&bytes)
+}
+
+fn hash_node(left: &Hash, right: &Hash) -> Hash {
+    let mut bytes = Vec::with_capacity(1 + 2 * left.to_bytes().len());
+    bytes.push(NODE_PREFIX);
+    bytes.extend_from_slice(left.to_bytes());
+    bytes.extend_from_slice(right.to_bytes());
+    Hash::compute_from(&bytes)
+}
+
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Compute the Merkle root of a list of leaves.
+/// The root of an empty list is the hash of an empty byte slice.
+///
+/// # Example
+/// ```
+/// # use massa_hash::{compute_merkle_root, Hash};
+/// let leaves = vec![Hash::compute_from(b"a"), Hash::compute_from(b"b")];
+/// let root = compute_merkle_root(&leaves);
+/// assert_ne!(root, compute_merkle_root(&leaves[..1]));
+/// ```
+pub fn compute_merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return Hash::compute_from(&[]);
+    }
+    let mut level: Vec<Hash> = leaves.iter().map(hash_leaf).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Proof that a leaf belongs to a Merkle tree of a given root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// index of the proven leaf
+    pub leaf_index: u64,
+    /// total number of leaves in the tree
+    pub leaf_count: u64,
+    /// sibling hashes from the leaf level up to the root (levels without sibling are skipped)
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleProof {
+    /// Build the inclusion proof of the leaf at `leaf_index`.
+    /// Returns `None` if the index is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// # use massa_hash::{compute_merkle_root, Hash, MerkleProof};
+    /// let leaves: Vec<Hash> = (0u8..5).map(|i| Hash::compute_from(&[i])).collect();
+    /// let proof = MerkleProof::compute(&leaves, 3).unwrap();
+    /// assert!(proof.verify(&leaves[3], &compute_merkle_root(&leaves)));
+    /// ```
+    pub fn compute(leaves: &[Hash], leaf_index: usize) -> Option<Self> {
+        if leaf_index >= leaves.len() {
+            return None;
+        }
+        let mut siblings = Vec::new();
+        let mut index = leaf_index;
+        let mut level: Vec<Hash> = leaves.iter().map(hash_leaf).collect();
+        while level.len() > 1 {
+            let sibling_index = index ^ 1;
+            if let Some(sibling) = level.get(sibling_index) {
+                siblings.push(*sibling);
+            }
+            level = next_level(&level);
+            index /= 2;
+        }
+        Some(MerkleProof {
+            leaf_index: leaf_index as u64,
+            leaf_count: leaves.len() as u64,
+            siblings,
+        })
+    }
+
+    /// Check that `leaf` is included at `self.leaf_index` in the tree of root `root`
+    pub fn verify(&self, leaf: &Hash, root: &Hash) -> bool {
+        if self.leaf_index >= self.leaf_count {
+            return false;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut current = hash_leaf(leaf);
+        let mut index = self.leaf_index;
+        let mut level_len = self.leaf_count;
+        while level_len > 1 {
+            let sibling_index = index ^ 1;
+            if sibling_index < level_len {
+                let Some(sibling) = siblings.next() else {
+                    return false;
+                };
+                current = if index % 2 == 0 {
+                    hash_node(&current, sibling)
+                } else {
+                    hash_node(sibling, &current)
+                };
+            }
+            index /= 2;
+            level_len = (level_len + 1) / 2;
+        }
+        siblings.next().is_none() && &current == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_proofs() {
+        for count in 1u8..=9 {
+            let leaves: Vec<Hash> = (0..count).map(|i| Hash::compute_from(&[i])).collect();
+            let root = compute_merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = MerkleProof::compute(&leaves, index).unwrap();
+                assert!(proof.verify(leaf, &root));
+                assert!(!proof.verify(&Hash::compute_from(b"other"), &root));
+            }
+            assert!(MerkleProof::compute(&leaves, leaves.len()).is_none());
+        }
+    }
+}
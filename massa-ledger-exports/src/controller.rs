@@ -2,7 +2,7 @@ use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 
-use crate::{LedgerChanges, LedgerError};
+use crate::{LedgerChanges, LedgerEntryProof, LedgerError};
 use ::massa_db::DBBatch;
 
 pub trait LedgerController: Send + Sync + Debug {
@@ -37,11 +37,26 @@ pub trait LedgerController: Send + Sync + Debug {
     /// A copy of the datastore value, or `None` if the ledger entry or datastore entry was not found
     fn get_data_entry(&self, addr: &Address, key: &[u8]) -> Option<Vec<u8>>;
 
-    /// Get every key of the datastore for a given address.
+    /// Get every key of the datastore for a given address, optionally restricted to keys starting with `prefix`.
     ///
     /// # Returns
     /// A `BTreeSet` of the datastore keys
-    fn get_datastore_keys(&self, addr: &Address) -> Option<BTreeSet<Vec<u8>>>;
+    fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>>;
+
+    /// Gets the balance, bytecode and given datastore entries of an address,
+    /// with the proof of each value against the final state hash.
+    ///
+    /// # Arguments
+    /// * `addr`: target address
+    /// * `datastore_keys`: datastore keys to prove
+    ///
+    /// # Returns
+    /// The proofs, or an error if the final state hash does not allow proofs
+    fn get_entry_proof(
+        &self,
+        addr: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, LedgerError>;
 
     /// Reset the ledger
     ///
@@ -11,6 +11,7 @@ mod key;
 mod ledger_changes;
 mod ledger_entry;
 mod mapping_grpc;
+mod proof;
 mod types;
 
 pub use config::LedgerConfig;
@@ -26,6 +27,7 @@ pub use ledger_changes::{
     LedgerEntryUpdateDeserializer, LedgerEntryUpdateSerializer,
 };
 pub use ledger_entry::{LedgerEntry, LedgerEntryDeserializer, LedgerEntrySerializer};
+pub use proof::{LedgerEntryProof, LedgerKeyProof};
 pub use types::{
     Applicable, SetOrDelete, SetOrKeep, SetOrKeepDeserializer, SetOrKeepSerializer,
     SetUpdateOrDelete, SetUpdateOrDeleteDeserializer, SetUpdateOrDeleteSerializer,
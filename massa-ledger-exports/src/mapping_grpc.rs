@@ -1,6 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::{LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep};
+use crate::{LedgerEntry, LedgerEntryUpdate, LedgerKeyProof, SetOrDelete, SetOrKeep};
 use massa_proto_rs::massa::model::v1 as grpc_model;
 
 impl From<LedgerEntry> for grpc_model::LedgerEntry {
@@ -60,3 +60,15 @@ impl From<LedgerEntryUpdate> for grpc_model::LedgerEntryUpdate {
         }
     }
 }
+
+impl From<LedgerKeyProof> for grpc_model::LedgerKeyProof {
+    fn from(value: LedgerKeyProof) -> Self {
+        grpc_model::LedgerKeyProof {
+            key: value.key,
+            value: value.value,
+            side_nodes: value.proof.side_nodes,
+            non_membership_leaf_data: value.proof.non_membership_leaf_data,
+            sibling_data: value.proof.sibling_data,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Proofs of ledger entries against the final state hash
+
+use massa_db::StateProof;
+use massa_hash::Hash;
+use massa_models::slot::Slot;
+
+/// Value of a ledger database key along with its proof against the final state hash
+#[derive(Debug, Clone)]
+pub struct LedgerKeyProof {
+    /// serialized database key
+    pub key: Vec<u8>,
+    /// serialized value, `None` if the key is absent from the ledger
+    pub value: Option<Vec<u8>>,
+    /// proof of the value, or of the absence of the key
+    pub proof: StateProof,
+}
+
+impl LedgerKeyProof {
+    /// Checks the proof against a state hash
+    pub fn verify(&self, state_hash: &Hash) -> bool {
+        self.proof
+            .verify(state_hash, &self.key, self.value.as_deref())
+    }
+}
+
+/// Final ledger entry of an address, with the proof of each of its values
+#[derive(Debug, Clone)]
+pub struct LedgerEntryProof {
+    /// final state hash against which the values are proven
+    pub state_hash: Hash,
+    /// slot at which the final state was when the proofs were made
+    pub slot: Slot,
+    /// balance of the entry
+    pub balance: LedgerKeyProof,
+    /// bytecode of the entry
+    pub bytecode: LedgerKeyProof,
+    /// requested datastore entries, in the order of the request
+    pub datastore: Vec<LedgerKeyProof>,
+}
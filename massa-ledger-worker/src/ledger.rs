@@ -5,7 +5,7 @@
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
 use massa_db::{DBBatch, MassaDB};
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerEntryProof, LedgerError,
 };
 use massa_models::{
     address::Address,
@@ -139,12 +139,22 @@ impl LedgerController for FinalLedger {
             .get_sub_entry(addr, LedgerSubEntry::Datastore(key.to_owned()))
     }
 
-    /// Get every key of the datastore for a given address.
+    /// Get every key of the datastore for a given address, optionally restricted to keys starting with `prefix`.
     ///
     /// # Returns
     /// A `BTreeSet` of the datastore keys
-    fn get_datastore_keys(&self, addr: &Address) -> Option<BTreeSet<Vec<u8>>> {
-        self.sorted_ledger.get_datastore_keys(addr)
+    fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
+        self.sorted_ledger.get_datastore_keys(addr, prefix)
+    }
+
+    /// Gets the balance, bytecode and given datastore entries of an address,
+    /// with the proof of each value against the final state hash.
+    fn get_entry_proof(
+        &self,
+        addr: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, LedgerError> {
+        self.sorted_ledger.get_entry_proof(addr, datastore_keys)
     }
 
     /// Reset the disk ledger.
@@ -157,16 +157,54 @@ impl LedgerDB {
         db.db.get_cf(handle, serialized_key).expect(CRUD_ERROR)
     }
 
-    /// Get every key of the datastore for a given address.
+    /// Get the balance, bytecode and given datastore entries of an address, with their proofs against the state hash.
+    ///
+    /// Everything is read under the same database lock so that the proofs match the returned state hash.
+    pub fn get_entry_proof(
+        &self,
+        addr: &Address,
+        datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, LedgerError> {
+        let db = self.db.read();
+        let handle = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let prove = |ty: LedgerSubEntry| -> Result<LedgerKeyProof, LedgerError> {
+            let mut key = Vec::new();
+            self.key_serializer_db
+                .serialize(&ty.derive_key(addr), &mut key)
+                .expect(KEY_SER_ERROR);
+            let value = db.db.get_cf(handle, &key).expect(CRUD_ERROR);
+            let proof = db
+                .get_state_proof(&key)
+                .map_err(|e| LedgerError::ProofError(e.to_string()))?;
+            Ok(LedgerKeyProof { key, value, proof })
+        };
+
+        Ok(LedgerEntryProof {
+            state_hash: db.get_db_hash(),
+            slot: db
+                .get_change_id()
+                .map_err(|e| LedgerError::ProofError(e.to_string()))?,
+            balance: prove(LedgerSubEntry::Balance)?,
+            bytecode: prove(LedgerSubEntry::Bytecode)?,
+            datastore: datastore_keys
+                .iter()
+                .map(|key| prove(LedgerSubEntry::Datastore(key.clone())))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Get every key of the datastore for a given address, optionally restricted to keys starting with `prefix`.
     ///
     /// # Returns
     /// A `BTreeSet` of the datastore keys
-    pub fn get_datastore_keys(&self, addr: &Address) -> Option<BTreeSet<Vec<u8>>> {
+    pub fn get_datastore_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
         let db = self.db.read();
         let handle = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
 
         let mut opt = ReadOptions::default();
-        let key_prefix = datastore_prefix_from_address(addr);
+        // datastore keys are appended raw to the address prefix in the DB keys
+        let mut key_prefix = datastore_prefix_from_address(addr);
+        key_prefix.extend_from_slice(prefix);
 
         opt.set_iterate_range(key_prefix.clone()..end_prefix(&key_prefix).unwrap());
 
@@ -602,6 +640,35 @@ mod tests {
         assert!(ledger_db.get_entire_datastore(&addr).is_empty());
     }
 
+    #[test]
+    fn test_ledger_db_entry_proof() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, _data) = init_test_ledger(addr);
+        ledger_db.db.read().set_initial_change_id(Slot::new(1, 0));
+
+        let proof = ledger_db
+            .get_entry_proof(&addr, &[b"2".to_vec(), b"4".to_vec()])
+            .unwrap();
+        assert_eq!(proof.state_hash, ledger_db.db.read().get_db_hash());
+
+        // existing values are proven
+        assert!(proof.balance.value.is_some());
+        assert!(proof.balance.verify(&proof.state_hash));
+        assert_eq!(proof.datastore[0].value, Some(b"b".to_vec()));
+        assert!(proof.datastore[0].verify(&proof.state_hash));
+
+        // absent keys are proven absent
+        assert!(proof.bytecode.value.is_none());
+        assert!(proof.bytecode.verify(&proof.state_hash));
+        assert!(proof.datastore[1].value.is_none());
+        assert!(proof.datastore[1].verify(&proof.state_hash));
+
+        // a tampered value is rejected
+        let mut tampered = proof.datastore[0].clone();
+        tampered.value = Some(b"z".to_vec());
+        assert!(!tampered.verify(&proof.state_hash));
+    }
+
     #[test]
     fn test_end_prefix() {
         assert_eq!(end_prefix(&[5, 6, 7]), Some(vec![5, 6, 8]));
@@ -1,5 +1,9 @@
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, IntCounter, IntGauge};
+use prometheus::{
+    register_int_gauge, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts,
+};
+use std::time::Duration;
 
 #[cfg(not(feature = "testing"))]
 mod server;
@@ -74,6 +78,12 @@ pub struct MassaMetrics {
     consensus_state_discarded_index: IntGauge,
     consensus_state_block_statuses: IntGauge,
 
+    // consensus health
+    consensus_stale_block_rate: Gauge,
+    consensus_clique_count: Histogram,
+    consensus_header_latency: Histogram,
+    consensus_discarded_blocks: IntCounterVec,
+
     // endorsement cache
     endorsement_cache_checked_endorsements: IntGauge,
     endorsement_cache_known_by_peer: IntGauge,
@@ -86,6 +96,19 @@ pub struct MassaMetrics {
 
     final_cursor_thread: IntGauge,
     final_cursor_period: IntGauge,
+
+    // gRPC API
+    grpc_requests: IntCounterVec,
+    grpc_request_duration: HistogramVec,
+    grpc_active_streams: IntGaugeVec,
+    grpc_stream_dropped_messages: IntCounterVec,
+    grpc_stream_slow_client_disconnections: IntCounterVec,
+
+    // bootstrap
+    bootstrap_phase: IntGauge,
+    bootstrap_received_bytes: IntGauge,
+    bootstrap_streamed_period: IntGauge,
+    bootstrap_eta_seconds: IntGauge,
 }
 
 impl MassaMetrics {
@@ -212,6 +235,40 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // consensus health
+        let consensus_stale_block_rate = Gauge::new(
+            "consensus_stale_block_rate",
+            "share of stale blocks among the final and stale blocks of the stats timespan",
+        )
+        .unwrap();
+
+        let consensus_clique_count = Histogram::with_opts(
+            HistogramOpts::new(
+                "consensus_clique_count",
+                "number of cliques of the block graph, observed at each slot",
+            )
+            .buckets(vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 12.0, 16.0]),
+        )
+        .unwrap();
+
+        let consensus_header_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "consensus_header_latency_seconds",
+                "delay between the timestamp of the slot of a header and its first reception",
+            )
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0]),
+        )
+        .unwrap();
+
+        let consensus_discarded_blocks = IntCounterVec::new(
+            Opts::new(
+                "consensus_discarded_blocks",
+                "blocks discarded by the consensus by reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+
         let endorsement_cache_checked_endorsements = IntGauge::new(
             "endorsement_cache_checked_endorsements",
             "endorsement cache checked endorsements size",
@@ -232,6 +289,68 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // gRPC API
+        let grpc_requests = IntCounterVec::new(
+            Opts::new("grpc_requests", "gRPC requests count by method and status"),
+            &["method", "status"],
+        )
+        .unwrap();
+
+        let grpc_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "grpc_request_duration_seconds",
+                "gRPC unary requests processing time by method",
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        let grpc_active_streams = IntGaugeVec::new(
+            Opts::new("grpc_active_streams", "gRPC active streams by method"),
+            &["method"],
+        )
+        .unwrap();
+
+        let grpc_stream_dropped_messages = IntCounterVec::new(
+            Opts::new(
+                "grpc_stream_dropped_messages",
+                "messages dropped by lagging gRPC streams or by their backpressure policy by method",
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        let grpc_stream_slow_client_disconnections = IntCounterVec::new(
+            Opts::new(
+                "grpc_stream_slow_client_disconnections",
+                "gRPC streams closed because their client was too slow by method",
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        // bootstrap progress
+        let bootstrap_phase = IntGauge::new(
+            "bootstrap_phase",
+            "current bootstrap phase, 7 once the bootstrap is finished",
+        )
+        .unwrap();
+        let bootstrap_received_bytes = IntGauge::new(
+            "bootstrap_received_bytes",
+            "bytes received from the bootstrap servers",
+        )
+        .unwrap();
+        let bootstrap_streamed_period = IntGauge::new(
+            "bootstrap_streamed_period",
+            "period of the last received part of the final state",
+        )
+        .unwrap();
+        let bootstrap_eta_seconds = IntGauge::new(
+            "bootstrap_eta_seconds",
+            "estimated time left in the current bootstrap phase, -1 if unknown",
+        )
+        .unwrap();
+
         if enabled {
             // TODO addr from config
             #[cfg(not(feature = "testing"))]
@@ -258,6 +377,10 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(consensus_state_incoming_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_discarded_index.clone()));
                 let _ = prometheus::register(Box::new(consensus_state_block_statuses.clone()));
+                let _ = prometheus::register(Box::new(consensus_stale_block_rate.clone()));
+                let _ = prometheus::register(Box::new(consensus_clique_count.clone()));
+                let _ = prometheus::register(Box::new(consensus_header_latency.clone()));
+                let _ = prometheus::register(Box::new(consensus_discarded_blocks.clone()));
                 let _ = prometheus::register(Box::new(
                     operation_cache_checked_operations_prefix.clone(),
                 ));
@@ -266,6 +389,16 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(endorsement_cache_known_by_peer.clone()));
                 let _ = prometheus::register(Box::new(block_graph_counter.clone()));
                 let _ = prometheus::register(Box::new(block_graph_ms.clone()));
+                let _ = prometheus::register(Box::new(grpc_requests.clone()));
+                let _ = prometheus::register(Box::new(grpc_request_duration.clone()));
+                let _ = prometheus::register(Box::new(grpc_active_streams.clone()));
+                let _ = prometheus::register(Box::new(grpc_stream_dropped_messages.clone()));
+                let _ =
+                    prometheus::register(Box::new(grpc_stream_slow_client_disconnections.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_phase.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_received_bytes.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_streamed_period.clone()));
+                let _ = prometheus::register(Box::new(bootstrap_eta_seconds.clone()));
             }
         }
 
@@ -286,6 +419,10 @@ impl MassaMetrics {
             consensus_state_incoming_index,
             consensus_state_discarded_index,
             consensus_state_block_statuses,
+            consensus_stale_block_rate,
+            consensus_clique_count,
+            consensus_header_latency,
+            consensus_discarded_blocks,
             endorsement_cache_checked_endorsements,
             endorsement_cache_known_by_peer,
             // blocks_counter,
@@ -295,7 +432,32 @@ impl MassaMetrics {
             active_cursor_period,
             final_cursor_thread,
             final_cursor_period,
+            grpc_requests,
+            grpc_request_duration,
+            grpc_active_streams,
+            grpc_stream_dropped_messages,
+            grpc_stream_slow_client_disconnections,
+            bootstrap_phase,
+            bootstrap_received_bytes,
+            bootstrap_streamed_period,
+            bootstrap_eta_seconds,
+        }
+    }
+
+    pub fn set_bootstrap_progress(
+        &self,
+        phase: u8,
+        received_bytes: u64,
+        streamed_period: Option<u64>,
+        eta: Option<Duration>,
+    ) {
+        self.bootstrap_phase.set(phase as i64);
+        self.bootstrap_received_bytes.set(received_bytes as i64);
+        if let Some(period) = streamed_period {
+            self.bootstrap_streamed_period.set(period as i64);
         }
+        self.bootstrap_eta_seconds
+            .set(eta.map_or(-1, |eta| eta.as_secs() as i64));
     }
 
     // pub fn inc_blocks_counter(&self) {
@@ -362,6 +524,21 @@ impl MassaMetrics {
             .set(active_index_without_ops as i64);
     }
 
+    pub fn set_consensus_health(&self, stale_block_rate: f64, clique_count: usize) {
+        self.consensus_stale_block_rate.set(stale_block_rate);
+        self.consensus_clique_count.observe(clique_count as f64);
+    }
+
+    pub fn observe_consensus_header_latency(&self, latency: Duration) {
+        self.consensus_header_latency.observe(latency.as_secs_f64());
+    }
+
+    pub fn inc_consensus_discarded_blocks(&self, reason: &str) {
+        self.consensus_discarded_blocks
+            .with_label_values(&[reason])
+            .inc();
+    }
+
     pub fn set_block_cache_metrics(&self, checked_header_size: usize, blocks_known_by_peer: usize) {
         self.block_cache_checked_headers_size
             .set(checked_header_size as i64);
@@ -405,6 +582,39 @@ impl MassaMetrics {
     pub fn inc_block_graph_counter(&self) {
         self.block_graph_counter.inc();
     }
+
+    pub fn inc_grpc_requests(&self, method: &str, success: bool) {
+        let status = if success { "ok" } else { "error" };
+        self.grpc_requests
+            .with_label_values(&[method, status])
+            .inc();
+    }
+
+    pub fn observe_grpc_request_duration(&self, method: &str, duration: Duration) {
+        self.grpc_request_duration
+            .with_label_values(&[method])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn inc_grpc_active_streams(&self, method: &str) {
+        self.grpc_active_streams.with_label_values(&[method]).inc();
+    }
+
+    pub fn dec_grpc_active_streams(&self, method: &str) {
+        self.grpc_active_streams.with_label_values(&[method]).dec();
+    }
+
+    pub fn inc_grpc_stream_dropped_messages(&self, method: &str, count: u64) {
+        self.grpc_stream_dropped_messages
+            .with_label_values(&[method])
+            .inc_by(count);
+    }
+
+    pub fn inc_grpc_stream_slow_client_disconnections(&self, method: &str) {
+        self.grpc_stream_slow_client_disconnections
+            .with_label_values(&[method])
+            .inc();
+    }
 }
 // mod test {
 //     use massa_channel::MassaChannel;
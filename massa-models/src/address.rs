@@ -732,4 +732,53 @@ mod test {
 
         assert_eq!(addr, addr2);
     }
+
+    #[test]
+    fn test_address_from_public_key_fixtures() {
+        // (public key, address) pairs serialized by older releases, they must never change
+        let fixtures = [
+            (
+                "P1VRHdT1GSm91SzwMNaPrzKvtjXcB7iVrfncZwTWqWWpTZDyhYi",
+                "AU123EPK9mTPZNSBNeWnsv9j3U4yEmduZgH2kBsV4pUnWcipHrWiU",
+                0,
+            ),
+            (
+                "P3SATru37sSN9GaeFxCPpJvhor9KppZNeJrip69QqAXCx9eRssi",
+                "AU32EC2x4DXAtNXJn5mFtPx3GnxgDzEoeDvze7J5cYvaDsexZz1S",
+                1,
+            ),
+            (
+                "P1Av6JwzQqbuDAic6WYzZozzNmy57h2RucjHiL8SgLsv2BETBK6",
+                "AU12B5orHLxPVx7yooy5qE6wHFX84kthsBBG4FN134RcBAbZLrDRD",
+                0,
+            ),
+            (
+                "P37fGYQ2GGH8LzJK16AzX8bmHtanmPsJhFoPxUpLfztJWmxwEcr",
+                "AU3dMAPvDRaR6ru4qBpVgZuZESmeak1gYp7zkU6Yfg71RagW8qvZ",
+                1,
+            ),
+        ];
+        for (public_key, address, version) in fixtures {
+            let public_key = PublicKey::from_str(public_key).unwrap();
+            let parsed = Address::from_str(address).unwrap();
+            assert_eq!(Address::from_public_key(&public_key), parsed);
+            assert_eq!(parsed.to_string(), address);
+            let parsed_version = match parsed {
+                Address::User(UserAddress::UserAddressV0(addr)) => addr.get_version(),
+                Address::User(UserAddress::UserAddressV1(addr)) => addr.get_version(),
+                Address::SC(_) => panic!("{} is not a user address", address),
+            };
+            assert_eq!(parsed_version, version);
+
+            let mut buffer: Vec<u8> = vec![];
+            AddressSerializer::new()
+                .serialize(&parsed, &mut buffer)
+                .unwrap();
+            let (rest, deserialized): (&[u8], Address) = AddressDeserializer::new()
+                .deserialize::<massa_serialization::DeserializeError>(&buffer)
+                .unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(deserialized, parsed);
+        }
+    }
 }
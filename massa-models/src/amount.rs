@@ -14,6 +14,25 @@ use std::str::FromStr;
 /// decimal factor for the internal representation
 pub const AMOUNT_DECIMAL_FACTOR: u64 = 1_000_000_000;
 
+/// Units in which an `Amount` can be expressed in its string form
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmountUnit {
+    /// MAS, the default unit (e.g. "1.5 MAS" or "1.5")
+    Mas,
+    /// nano-MAS, the smallest indivisible unit (e.g. "1500 nMAS")
+    NanoMas,
+}
+
+impl AmountUnit {
+    /// Suffix of the unit in the string form of an amount
+    pub const fn suffix(&self) -> &'static str {
+        match self {
+            AmountUnit::Mas => "MAS",
+            AmountUnit::NanoMas => "nMAS",
+        }
+    }
+}
+
 /// A structure representing a decimal Amount of coins with safe operations
 /// this allows ensuring that there is never an uncontrolled overflow or precision loss
 /// while providing a convenient decimal interface for users
@@ -113,6 +132,22 @@ impl Amount {
         self.0
     }
 
+    /// Format the amount with an explicit unit suffix
+    ///
+    /// ```
+    /// # use massa_models::amount::{Amount, AmountUnit};
+    /// # use std::str::FromStr;
+    /// let amount = Amount::from_str("1.5").unwrap();
+    /// assert_eq!(amount.to_string_with_unit(AmountUnit::Mas), "1.5 MAS");
+    /// assert_eq!(amount.to_string_with_unit(AmountUnit::NanoMas), "1500000000 nMAS");
+    /// ```
+    pub fn to_string_with_unit(&self, unit: AmountUnit) -> String {
+        match unit {
+            AmountUnit::Mas => format!("{} {}", self, unit.suffix()),
+            AmountUnit::NanoMas => format!("{} {}", self.0, unit.suffix()),
+        }
+    }
+
     /// constructs an `Amount` from the underlying raw `u64` representation
     /// Warning: do not use this unless you know what you are doing
     /// because the raw value does not take the `AMOUNT_DECIMAL_FACTOR` into account
@@ -224,7 +259,9 @@ impl fmt::Debug for Amount {
 }
 
 /// build an Amount from decimal string form (like "10.33")
-/// note that this will fail if the string format is invalid
+/// with an optional unit suffix ("10.33 MAS", "10330000000 nMAS"), MAS being the default unit.
+/// note that this will fail if the string format is invalid, if the unit is unknown,
+/// if a nMAS amount is not an integer
 /// or if the conversion would cause an overflow, underflow or precision loss
 ///
 /// ```
@@ -235,14 +272,50 @@ impl fmt::Debug for Amount {
 /// assert!(Amount::from_str("1111111111111111111111").is_err());
 /// assert!(Amount::from_str("-11.1").is_err());
 /// assert!(Amount::from_str("abc").is_err());
+/// assert_eq!(Amount::from_str("1.5 MAS").unwrap(), Amount::from_str("1.5").unwrap());
+/// assert_eq!(Amount::from_str("1500nMAS").unwrap(), Amount::from_raw(1500));
+/// assert!(Amount::from_str("1.5 nMAS").is_err());
+/// assert!(Amount::from_str("1.5 mas").is_err());
+/// assert!(Amount::from_str("MAS").is_err());
 /// ```
 impl FromStr for Amount {
     type Err = ModelsError;
 
     fn from_str(str_amount: &str) -> Result<Self, Self::Err> {
-        let res = Decimal::from_str_exact(str_amount)
-            .map_err(|err| ModelsError::AmountParseError(err.to_string()))?;
-        Amount::from_decimal(res)
+        // nMAS must be checked first as it ends with the MAS suffix
+        let (value, unit) =
+            if let Some(value) = str_amount.strip_suffix(AmountUnit::NanoMas.suffix()) {
+                (value, AmountUnit::NanoMas)
+            } else if let Some(value) = str_amount.strip_suffix(AmountUnit::Mas.suffix()) {
+                (value, AmountUnit::Mas)
+            } else {
+                (str_amount, AmountUnit::Mas)
+            };
+        // allow a single space between the value and its unit
+        let value = if value.len() != str_amount.len() {
+            value.strip_suffix(' ').unwrap_or(value)
+        } else {
+            value
+        };
+        match unit {
+            AmountUnit::Mas => {
+                let res = Decimal::from_str_exact(value)
+                    .map_err(|err| ModelsError::AmountParseError(err.to_string()))?;
+                Amount::from_decimal(res)
+            }
+            AmountUnit::NanoMas => {
+                if !value.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ModelsError::AmountParseError(format!(
+                        "{} amounts must be non-negative integers",
+                        AmountUnit::NanoMas.suffix()
+                    )));
+                }
+                value
+                    .parse::<u64>()
+                    .map(Amount::from_raw)
+                    .map_err(|err| ModelsError::AmountParseError(err.to_string()))
+            }
+        }
     }
 }
 
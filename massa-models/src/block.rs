@@ -43,6 +43,7 @@ use std::fmt::Formatter;
 // use std::str::FromStr;
 use crate::block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader};
 use crate::block_id::BlockId;
+use crate::{address::Address, slot::Slot};
 
 /// block
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +63,43 @@ pub struct FilledBlock {
     pub operations: Vec<(OperationId, Option<SecureShareOperation>)>,
 }
 
+/// Blockclique change reverting blocks that were previously in the blockclique,
+/// broadcast so that indexers can roll back the data they derived from these blocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgEvent {
+    /// latest block of the previous blockclique
+    pub old_tip: BlockId,
+    /// latest block of the new blockclique
+    pub new_tip: BlockId,
+    /// number of reverted blocks
+    pub depth: u64,
+    /// blocks removed from the blockclique without becoming final, latest slot first
+    pub reverted_blocks: Vec<BlockId>,
+}
+
+/// Reason why the consensus discarded a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockDiscardReason {
+    /// the block is invalid, with the description of the issue
+    Invalid(String),
+    /// the block is incompatible with the blockclique
+    Stale,
+}
+
+/// Block discarded by the consensus, broadcast so that the protocol can account for the peers that sent it,
+/// and so that block producers can know why their block was rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscardedBlockEvent {
+    /// id of the discarded block
+    pub block_id: BlockId,
+    /// slot of the discarded block
+    pub slot: Slot,
+    /// creator of the discarded block
+    pub creator: Address,
+    /// why the block was discarded
+    pub reason: BlockDiscardReason,
+}
+
 /// Block with assosciated meta-data and interfaces allowing trust of data in untrusted network
 pub type SecureShareBlock = SecureShare<Block, BlockId>;
 
@@ -394,7 +432,7 @@ impl std::fmt::Display for Block {
 // }
 
 /// Block status within the graph
-#[derive(Eq, PartialEq, Debug, Deserialize, Serialize)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Deserialize, Serialize)]
 pub enum BlockGraphStatus {
     /// received but not yet graph-processed
     Incoming,
@@ -414,6 +452,48 @@ pub enum BlockGraphStatus {
     NotFound,
 }
 
+/// Finality of a block, for clients deciding when to accept the operations it includes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFinality {
+    /// status of the block
+    pub status: BlockFinalityStatus,
+    /// number of final or blockclique blocks having the block as an ancestor
+    pub confirmations: u64,
+    /// fitness the blockclique descendants of the block still miss for it to become final, 0 once final
+    pub fitness_margin: u64,
+}
+
+/// Status of a block on its way to finality
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockFinalityStatus {
+    /// not yet processed, or active in an alternative clique only
+    Candidate,
+    /// active in the blockclique
+    InBlockclique,
+    /// final
+    Final,
+    /// discarded, and why
+    Discarded {
+        /// reason of the discard
+        reason: String,
+    },
+}
+
+/// Metadata of a block pruned from the consensus graph, kept by archival nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedBlock {
+    /// id of the block
+    pub id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// address of the creator of the block
+    pub creator: Address,
+    /// parents of the block, one per thread
+    pub parents: Vec<BlockId>,
+    /// status of the block when it was pruned: final or discarded
+    pub status: BlockFinalityStatus,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -14,7 +14,7 @@ use std::convert::TryInto;
 use std::str::FromStr;
 
 /// Size in bytes of a serialized block ID
-const BLOCK_ID_SIZE_BYTES: usize = massa_hash::HASH_SIZE_BYTES;
+pub const BLOCK_ID_SIZE_BYTES: usize = massa_hash::HASH_SIZE_BYTES;
 
 /// block id
 #[derive(
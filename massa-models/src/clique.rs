@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::block_id::BlockId;
 use crate::prehash::PreHashSet;
+use crate::slot::Slot;
 use std::ops::Bound::{Excluded, Included};
 
 /// Mutually compatible blocks in the graph
@@ -33,6 +34,37 @@ pub struct Clique {
     pub is_blockclique: bool,
 }
 
+/// Block of a clique, with its contribution to the fitness of the clique
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CliqueBlock {
+    /// block id
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// fitness of the block: its number of endorsements plus one
+    pub fitness: u64,
+    /// whether the block belongs to no other clique
+    pub exclusive: bool,
+}
+
+/// Maximal clique of the graph with the breakdown of its fitness, to inspect the choice of the blockclique
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CliqueInspection {
+    /// index of the clique in the list of maximal cliques
+    pub index: usize,
+    /// True if it is the clique of higher fitness
+    pub is_blockclique: bool,
+    /// sum of the fitnesses of the blocks of the clique
+    pub fitness: u64,
+    /// fitness missing to the clique to reach the blockclique.
+    /// The blocks exclusive to the clique become stale when it exceeds `delta_f0`
+    pub fitness_gap: u64,
+    /// tie-breaker between cliques of equal fitness: the sum of the block ids as big-endian integers, the lowest sum wins
+    pub hash_sum: String,
+    /// blocks of the clique, sorted by slot
+    pub blocks: Vec<CliqueBlock>,
+}
+
 impl Default for Clique {
     fn default() -> Self {
         Clique {
@@ -33,6 +33,13 @@ impl DatastoreSerializer {
             vec_u8_serializer: VecU8Serializer::new(),
         }
     }
+
+    /// Serializes a datastore into a new buffer, e.g. for the operation datastore of a read-only execution
+    pub fn serialize_to_vec(&self, datastore: &Datastore) -> Result<Vec<u8>, SerializeError> {
+        let mut buffer = Vec::new();
+        self.serialize(datastore, &mut buffer)?;
+        Ok(buffer)
+    }
 }
 
 impl Serializer<Datastore> for DatastoreSerializer {
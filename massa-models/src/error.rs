@@ -34,6 +34,8 @@ pub enum ModelsError {
     AddressParseError(String),
     /// node id parsing error
     NodeIdParseError,
+    /// IP range parsing error: {0}
+    IpRangeParseError(String),
     /// block id parsing error
     BlockIdParseError,
     /// operation id parsing error
@@ -0,0 +1,119 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::ModelsError;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Range of IP addresses in CIDR notation (`10.0.0.0/8`, `2001:db8::/32`), or a single IP address.
+///
+/// IPv4-mapped IPv6 addresses are handled as IPv4 addresses.
+#[derive(
+    Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd, SerializeDisplay, DeserializeFromStr,
+)]
+pub struct IpRange {
+    /// first address of the range
+    network: IpAddr,
+    /// number of leading bits shared by the addresses of the range
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Creates the range of the addresses sharing the `prefix_len` first bits of `ip`
+    pub fn new(ip: IpAddr, prefix_len: u8) -> Result<Self, ModelsError> {
+        let ip = ip.to_canonical();
+        let network = match ip {
+            IpAddr::V4(ip) if prefix_len <= 32 => {
+                IpAddr::V4(Ipv4Addr::from(u32::from(ip) & Self::mask_v4(prefix_len)))
+            }
+            IpAddr::V6(ip) if prefix_len <= 128 => {
+                IpAddr::V6(Ipv6Addr::from(u128::from(ip) & Self::mask_v6(prefix_len)))
+            }
+            _ => {
+                return Err(ModelsError::IpRangeParseError(format!(
+                    "prefix length {} too long for {}",
+                    prefix_len, ip
+                )))
+            }
+        };
+        Ok(IpRange {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Returns true if `ip` is in the range
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                u32::from(ip) & Self::mask_v4(self.prefix_len) == u32::from(network)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                u128::from(ip) & Self::mask_v6(self.prefix_len) == u128::from(network)
+            }
+            _ => false,
+        }
+    }
+
+    fn mask_v4(prefix_len: u8) -> u32 {
+        u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)
+    }
+
+    fn mask_v6(prefix_len: u8) -> u128 {
+        u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0)
+    }
+}
+
+impl From<IpAddr> for IpRange {
+    fn from(ip: IpAddr) -> Self {
+        let ip = ip.to_canonical();
+        IpRange {
+            network: ip,
+            prefix_len: if ip.is_ipv4() { 32 } else { 128 },
+        }
+    }
+}
+
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if *self == IpRange::from(self.network) {
+            write!(f, "{}", self.network)
+        } else {
+            write!(f, "{}/{}", self.network, self.prefix_len)
+        }
+    }
+}
+
+impl std::fmt::Debug for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl std::str::FromStr for IpRange {
+    type Err = ModelsError;
+    /// ## Example
+    /// ```rust
+    /// # use std::net::IpAddr;
+    /// # use std::str::FromStr;
+    /// # use massa_models::ip_range::IpRange;
+    /// let range = IpRange::from_str("192.168.1.7/16").unwrap();
+    /// assert_eq!(range.to_string(), "192.168.0.0/16");
+    /// assert!(range.contains(&IpAddr::from_str("192.168.255.1").unwrap()));
+    /// assert!(range.contains(&IpAddr::from_str("::ffff:192.168.0.1").unwrap()));
+    /// assert!(!range.contains(&IpAddr::from_str("192.169.0.1").unwrap()));
+    /// assert_eq!(IpRange::from_str("2001:db8::1").unwrap().to_string(), "2001:db8::1");
+    /// assert!(IpRange::from_str("10.0.0.0/33").is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            ModelsError::IpRangeParseError(format!("invalid IP range {}: {}", s, e))
+        };
+        match s.split_once('/') {
+            Some((ip, prefix_len)) => IpRange::new(
+                ip.parse().map_err(|e| invalid(&e))?,
+                prefix_len.parse().map_err(|e| invalid(&e))?,
+            ),
+            None => Ok(IpRange::from(s.parse::<IpAddr>().map_err(|e| invalid(&e))?)),
+        }
+    }
+}
@@ -5,6 +5,7 @@
 #![warn(unused_crate_dependencies)]
 #![feature(bound_map)]
 #![feature(int_roundings)]
+#![feature(ip)]
 #![feature(iter_intersperse)]
 #![feature(variant_count)]
 
@@ -40,6 +41,8 @@ pub mod endorsement;
 pub mod error;
 /// execution related structures
 pub mod execution;
+/// IP address ranges
+pub mod ip_range;
 /// ledger related structures
 pub mod ledger;
 /// mapping grpc
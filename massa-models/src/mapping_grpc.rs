@@ -174,6 +174,14 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::CallSc(call_sc));
             }
+            OperationType::StakeDelegation { operator } => {
+                let stake_delegation = grpc_model::StakeDelegation {
+                    operator: operator.map(|operator| operator.to_string()),
+                };
+                grpc_operation_type.r#type = Some(
+                    grpc_model::operation_type::Type::StakeDelegation(stake_delegation),
+                );
+            }
         }
 
         grpc_operation_type
@@ -198,6 +206,7 @@ impl From<OperationType> for grpc_api::OpType {
             OperationType::RollSell { .. } => grpc_api::OpType::RollSell,
             OperationType::ExecuteSC { .. } => grpc_api::OpType::ExecuteSc,
             OperationType::CallSC { .. } => grpc_api::OpType::CallSc,
+            OperationType::StakeDelegation { .. } => grpc_api::OpType::StakeDelegation,
         }
     }
 }
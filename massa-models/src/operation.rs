@@ -14,9 +14,9 @@ use crate::{
 };
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
-    DeserializeError, Deserializer, SerializeError, Serializer, U16VarIntDeserializer,
-    U16VarIntSerializer, U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer,
-    U64VarIntSerializer,
+    DeserializeError, Deserializer, OptionDeserializer, OptionSerializer, SerializeError,
+    Serializer, U16VarIntDeserializer, U16VarIntSerializer, U32VarIntDeserializer,
+    U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
 use nom::error::context;
 use nom::multi::length_count;
@@ -240,6 +240,7 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    StakeDelegation = 5,
 }
 
 /// the operation as sent in the network
@@ -467,6 +468,12 @@ pub enum OperationType {
         /// Extra coins that are spent from the caller's balance and transferred to the target
         coins: Amount,
     },
+    /// the sender delegates the block production and endorsement of its rolls to an operator.
+    /// The rewards of the delegated rolls are still credited to the sender.
+    StakeDelegation {
+        /// operator producing blocks and endorsements for the sender's rolls, `None` revokes the delegation
+        operator: Option<Address>,
+    },
 }
 
 impl std::fmt::Display for OperationType {
@@ -512,6 +519,13 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- max_gas:{}", max_gas)?;
                 writeln!(f, "\t- coins:{}", coins)?;
             }
+            OperationType::StakeDelegation { operator } => {
+                writeln!(f, "Stake delegation:")?;
+                match operator {
+                    Some(operator) => writeln!(f, "\t- operator:{}", operator)?,
+                    None => writeln!(f, "\t- operator: none (revoked)")?,
+                }
+            }
         }
         Ok(())
     }
@@ -524,6 +538,7 @@ pub struct OperationTypeSerializer {
     vec_u8_serializer: VecU8Serializer,
     amount_serializer: AmountSerializer,
     address_serializer: AddressSerializer,
+    operator_serializer: OptionSerializer<Address, AddressSerializer>,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     datastore_serializer: DatastoreSerializer,
 }
@@ -537,6 +552,7 @@ impl OperationTypeSerializer {
             vec_u8_serializer: VecU8Serializer::new(),
             amount_serializer: AmountSerializer::new(),
             address_serializer: AddressSerializer::new(),
+            operator_serializer: OptionSerializer::new(AddressSerializer::new()),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             datastore_serializer: DatastoreSerializer::new(),
         }
@@ -619,6 +635,11 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.vec_u8_serializer.serialize(param, buffer)?;
             }
+            OperationType::StakeDelegation { operator } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::StakeDelegation), buffer)?;
+                self.operator_serializer.serialize(operator, buffer)?;
+            }
         }
         Ok(())
     }
@@ -630,6 +651,7 @@ pub struct OperationTypeDeserializer {
     rolls_number_deserializer: U64VarIntDeserializer,
     max_gas_deserializer: U64VarIntDeserializer,
     address_deserializer: AddressDeserializer,
+    operator_deserializer: OptionDeserializer<Address, AddressDeserializer>,
     data_deserializer: VecU8Deserializer,
     amount_deserializer: AmountDeserializer,
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
@@ -652,6 +674,7 @@ impl OperationTypeDeserializer {
             rolls_number_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
             max_gas_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
             address_deserializer: AddressDeserializer::new(),
+            operator_deserializer: OptionDeserializer::new(AddressDeserializer::new()),
             data_deserializer: VecU8Deserializer::new(
                 Included(0),
                 Included(max_datastore_value_length),
@@ -807,6 +830,13 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::StakeDelegation => {
+                    context("Failed StakeDelegation deserialization", |input| {
+                        self.operator_deserializer.deserialize(input)
+                    })
+                    .map(|operator| OperationType::StakeDelegation { operator })
+                    .parse(input)
+                }
             }
         })
         .parse(buffer)
@@ -832,6 +862,7 @@ impl SecureShareOperation {
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
             OperationType::Transaction { .. } => 0,
+            OperationType::StakeDelegation { .. } => 0,
         }
     }
 
@@ -852,6 +883,7 @@ impl SecureShareOperation {
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
             }
+            OperationType::StakeDelegation { .. } => {}
         }
         res
     }
@@ -865,6 +897,7 @@ impl SecureShareOperation {
             OperationType::RollSell { .. } => Amount::zero(),
             OperationType::ExecuteSC { max_coins, .. } => *max_coins,
             OperationType::CallSC { coins, .. } => *coins,
+            OperationType::StakeDelegation { .. } => Amount::zero(),
         };
 
         // add all fees and return
@@ -884,6 +917,9 @@ impl SecureShareOperation {
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            OperationType::StakeDelegation { .. } => {
+                res.insert(Address::from_public_key(&self.content_creator_pub_key));
+            }
         }
         Ok(res)
     }
@@ -1528,4 +1564,37 @@ mod tests {
 
         assert_eq!(op.get_validity_range(10), 40..=50);
     }
+
+    #[test]
+    #[serial]
+    fn test_stake_delegation() {
+        let operator_keypair = KeyPair::generate(0).unwrap();
+        let operator = Address::from_public_key(&operator_keypair.get_public_key());
+
+        let deserializer = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        );
+        // delegation then revocation
+        for op in [
+            OperationType::StakeDelegation {
+                operator: Some(operator),
+            },
+            OperationType::StakeDelegation { operator: None },
+        ] {
+            let mut ser_type = Vec::new();
+            OperationTypeSerializer::new()
+                .serialize(&op, &mut ser_type)
+                .unwrap();
+            let (rest, res_type) = deserializer
+                .deserialize::<DeserializeError>(&ser_type)
+                .unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(res_type, op);
+        }
+    }
 }
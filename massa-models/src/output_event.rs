@@ -1,4 +1,5 @@
 use crate::{address::Address, block_id::BlockId, operation::OperationId, slot::Slot};
+use massa_hash::Hash;
 use serde::{Deserialize, Serialize};
 use std::{collections::VecDeque, fmt::Display};
 
@@ -11,6 +12,41 @@ pub struct SCOutputEvent {
     pub data: String,
 }
 
+impl SCOutputEvent {
+    /// Compute the hash committing to this event in the per-slot event Merkle tree.
+    ///
+    /// Only the fields that are deterministic across nodes are committed:
+    /// the finality and read-only flags are excluded.
+    pub fn compute_commitment_hash(&self) -> Hash {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.context.slot.to_bytes_key());
+        bytes.extend_from_slice(&self.context.index_in_slot.to_be_bytes());
+        match self.context.block {
+            Some(block_id) => {
+                bytes.push(1);
+                bytes.extend_from_slice(block_id.to_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&(self.context.call_stack.len() as u64).to_be_bytes());
+        for address in self.context.call_stack.iter() {
+            let address_bytes = address.to_prefixed_bytes();
+            bytes.extend_from_slice(&(address_bytes.len() as u64).to_be_bytes());
+            bytes.extend_from_slice(&address_bytes);
+        }
+        match self.context.origin_operation_id {
+            Some(operation_id) => {
+                bytes.push(1);
+                bytes.extend_from_slice(operation_id.to_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.push(self.context.is_error as u8);
+        bytes.extend_from_slice(self.data.as_bytes());
+        Hash::compute_from(&bytes)
+    }
+}
+
 impl Display for SCOutputEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Context: {}", self.context)?;
@@ -80,13 +80,33 @@ where
         content_serializer: Ser,
         keypair: &KeyPair,
     ) -> Result<SecureShare<Self, ID>, ModelsError> {
+        self.new_verifiable_with_signer(content_serializer, keypair.get_public_key(), |hash| {
+            Ok(keypair.sign(hash)?)
+        })
+    }
+
+    /// Same as `new_verifiable`, but the signature is produced by `sign` from the hash to sign,
+    /// so that the secret key of `public_key` does not have to be available (e.g. hardware wallets).
+    fn new_verifiable_with_signer<Ser, ID, F, E>(
+        self,
+        content_serializer: Ser,
+        public_key: PublicKey,
+        sign: F,
+    ) -> Result<SecureShare<Self, ID>, E>
+    where
+        Ser: Serializer<Self>,
+        ID: Id,
+        F: FnOnce(&Hash) -> Result<Signature, E>,
+        E: From<ModelsError>,
+    {
         let mut content_serialized = Vec::new();
-        content_serializer.serialize(&self, &mut content_serialized)?;
-        let public_key = keypair.get_public_key();
+        content_serializer
+            .serialize(&self, &mut content_serialized)
+            .map_err(ModelsError::from)?;
         let hash = Self::compute_hash(&self, &content_serialized, &public_key);
         let creator_address = Address::from_public_key(&public_key);
         Ok(SecureShare {
-            signature: self.sign(keypair, &hash)?,
+            signature: sign(&self.compute_signed_hash(&public_key, &hash))?,
             content_creator_pub_key: public_key,
             content_creator_address: creator_address,
             content: self,
@@ -4,6 +4,7 @@ use crate::slot::Slot;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::fmt::Formatter;
+use std::net::SocketAddr;
 
 /// execution statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,3 +128,67 @@ impl std::fmt::Display for PoolStats {
         Ok(())
     }
 }
+
+/// phase of a bootstrap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BootstrapPhase {
+    /// connecting to a bootstrap server and syncing versions and clocks
+    Connecting,
+    /// downloading ranges of the final state from several servers at once
+    DownloadingStateRanges,
+    /// streaming the final state
+    DownloadingState,
+    /// streaming the consensus graph, once the final state is complete
+    DownloadingConsensus,
+    /// computing the hash of the received final state
+    ComputingHash,
+    /// fetching the peers of the bootstrap server
+    FetchingPeers,
+    /// asking other bootstrap servers to attest the hash of the final state
+    AttestingState,
+    /// bootstrap complete
+    Finished,
+}
+
+impl BootstrapPhase {
+    /// Index of the phase, used as a metric value
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// progress of a bootstrap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapStats {
+    /// current phase
+    pub phase: BootstrapPhase,
+    /// bootstrap server the node is connected to, none during a download from several servers
+    pub server: Option<SocketAddr>,
+    /// bytes received from the bootstrap servers since the start of the bootstrap
+    pub received_bytes: u64,
+    /// slot of the last received part of the final state
+    pub streamed_slot: Option<Slot>,
+    /// time elapsed since the start of the bootstrap
+    pub elapsed: MassaTime,
+    /// estimated time left in the current phase, when the amount left to download is known
+    pub eta: Option<MassaTime>,
+}
+
+impl std::fmt::Display for BootstrapStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Bootstrap stats:")?;
+        writeln!(f, "\tPhase: {:?}", self.phase)?;
+        if let Some(server) = self.server {
+            writeln!(f, "\tServer: {}", server)?;
+        }
+        writeln!(f, "\tReceived: {} bytes", self.received_bytes)?;
+        if let Some(slot) = self.streamed_slot {
+            writeln!(f, "\tStreamed slot: {}", slot)?;
+        }
+        writeln!(f, "\tElapsed: {} s", self.elapsed.to_millis() / 1000)?;
+        if let Some(eta) = self.eta {
+            writeln!(f, "\tEstimated time left: {} s", eta.to_millis() / 1000)?;
+        }
+        Ok(())
+    }
+}
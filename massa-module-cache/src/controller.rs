@@ -5,8 +5,11 @@ use schnellru::{ByLength, LruMap};
 use tracing::{debug, warn};
 
 use crate::{
-    config::ModuleCacheConfig, error::CacheError, hd_cache::HDCache, lru_cache::LRUCache,
-    types::ModuleInfo,
+    config::ModuleCacheConfig,
+    error::CacheError,
+    hd_cache::HDCache,
+    lru_cache::LRUCache,
+    types::{GasCostsSchedule, ModuleInfo},
 };
 
 /// `LruMap` specialization for `PreHashed` keys
@@ -33,18 +36,36 @@ impl ModuleCache {
             hd_cache: HDCache::new(
                 cfg.hd_cache_path.clone(),
                 cfg.hd_cache_size,
+                cfg.hd_cache_max_byte_size,
                 cfg.snip_amount,
             ),
             cfg,
         }
     }
 
+    /// Key of the module compiled from `bytecode` with the gas costs of `schedule`.
+    /// The modules compiled with other gas costs are kept but not reused.
+    fn module_hash(bytecode: &[u8], schedule: &GasCostsSchedule) -> Hash {
+        Hash::compute_from(
+            &[
+                &Hash::compute_from(bytecode).to_bytes()[..],
+                &schedule.version.to_bytes()[..],
+            ]
+            .concat(),
+        )
+    }
+
     /// Internal function to compile and build `ModuleInfo`
-    fn compile_cached(&mut self, bytecode: &[u8], hash: Hash) -> ModuleInfo {
+    fn compile_cached(
+        &mut self,
+        bytecode: &[u8],
+        schedule: &GasCostsSchedule,
+        hash: Hash,
+    ) -> ModuleInfo {
         match RuntimeModule::new(
             bytecode,
             self.cfg.compilation_gas,
-            self.cfg.gas_costs.clone(),
+            schedule.gas_costs.clone(),
             Compiler::CL,
         ) {
             Ok(module) => {
@@ -59,11 +80,11 @@ impl ModuleCache {
     }
 
     /// Save a new or an already existing module in the cache
-    pub fn save_module(&mut self, bytecode: &[u8]) {
-        let hash = Hash::compute_from(bytecode);
+    pub fn save_module(&mut self, bytecode: &[u8], schedule: &GasCostsSchedule) {
+        let hash = Self::module_hash(bytecode, schedule);
         if let Some(hd_module_info) =
             self.hd_cache
-                .get(hash, self.cfg.compilation_gas, self.cfg.gas_costs.clone())
+                .get(hash, self.cfg.compilation_gas, schedule.gas_costs.clone())
         {
             debug!("save_module: {} present in hd", hash);
             self.lru_cache.insert(hash, hd_module_info);
@@ -72,42 +93,42 @@ impl ModuleCache {
             self.hd_cache.insert(hash, lru_module_info);
         } else {
             debug!("save_module: {} missing", hash);
-            let module_info = self.compile_cached(bytecode, hash);
+            let module_info = self.compile_cached(bytecode, schedule, hash);
             self.hd_cache.insert(hash, module_info.clone());
             self.lru_cache.insert(hash, module_info);
         }
     }
 
     /// Set the initialization cost of a cached module
-    pub fn set_init_cost(&mut self, bytecode: &[u8], init_cost: u64) {
-        let hash = Hash::compute_from(bytecode);
+    pub fn set_init_cost(&mut self, bytecode: &[u8], schedule: &GasCostsSchedule, init_cost: u64) {
+        let hash = Self::module_hash(bytecode, schedule);
         self.lru_cache.set_init_cost(hash, init_cost);
         self.hd_cache.set_init_cost(hash, init_cost);
     }
 
     /// Set a cached module as invalid
-    pub fn set_invalid(&mut self, bytecode: &[u8]) {
-        let hash = Hash::compute_from(bytecode);
+    pub fn set_invalid(&mut self, bytecode: &[u8], schedule: &GasCostsSchedule) {
+        let hash = Self::module_hash(bytecode, schedule);
         self.lru_cache.set_invalid(hash);
         self.hd_cache.set_invalid(hash);
     }
 
     /// Load a cached module for execution
-    fn load_module_info(&mut self, bytecode: &[u8]) -> ModuleInfo {
-        let hash = Hash::compute_from(bytecode);
+    fn load_module_info(&mut self, bytecode: &[u8], schedule: &GasCostsSchedule) -> ModuleInfo {
+        let hash = Self::module_hash(bytecode, schedule);
         if let Some(lru_module_info) = self.lru_cache.get(hash) {
             debug!("load_module: {} present in lru", hash);
             lru_module_info
         } else if let Some(hd_module_info) =
             self.hd_cache
-                .get(hash, self.cfg.compilation_gas, self.cfg.gas_costs.clone())
+                .get(hash, self.cfg.compilation_gas, schedule.gas_costs.clone())
         {
             debug!("load_module: {} missing in lru but present in hd", hash);
             self.lru_cache.insert(hash, hd_module_info.clone());
             hd_module_info
         } else {
             debug!("load_module: {} missing", hash);
-            let module_info = self.compile_cached(bytecode, hash);
+            let module_info = self.compile_cached(bytecode, schedule, hash);
             self.hd_cache.insert(hash, module_info.clone());
             self.lru_cache.insert(hash, module_info.clone());
             module_info
@@ -118,9 +139,10 @@ impl ModuleCache {
     pub fn load_module(
         &mut self,
         bytecode: &[u8],
+        schedule: &GasCostsSchedule,
         execution_gas: u64,
     ) -> Result<RuntimeModule, CacheError> {
-        let module_info = self.load_module_info(bytecode);
+        let module_info = self.load_module_info(bytecode, schedule);
         let module = match module_info {
             ModuleInfo::Invalid => {
                 return Err(CacheError::LoadError("Loading invalid module".to_string()));
@@ -143,14 +165,65 @@ impl ModuleCache {
     pub fn load_tmp_module(
         &self,
         bytecode: &[u8],
+        schedule: &GasCostsSchedule,
         limit: u64,
     ) -> Result<RuntimeModule, CacheError> {
         debug!("load_tmp_module");
         Ok(RuntimeModule::new(
             bytecode,
             limit,
-            self.cfg.gas_costs.clone(),
+            schedule.gas_costs.clone(),
             Compiler::SP,
         )?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_sc_runtime::GasCosts;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    const BYTECODE: [u8; 72] = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01,
+        0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0b, 0x01, 0x07, 0x61, 0x64, 0x64, 0x5f, 0x6f, 0x6e,
+        0x65, 0x00, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x41, 0x01, 0x6a, 0x0b, 0x00,
+        0x1a, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x0a, 0x01, 0x00, 0x07, 0x61, 0x64, 0x64, 0x5f,
+        0x6f, 0x6e, 0x65, 0x02, 0x07, 0x01, 0x00, 0x01, 0x00, 0x02, 0x70, 0x30,
+    ];
+
+    fn schedule(version: &[u8]) -> GasCostsSchedule {
+        GasCostsSchedule {
+            gas_costs: GasCosts::default(),
+            version: Hash::compute_from(version),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_modules_cached_by_gas_costs_schedule() {
+        let hd_cache_dir = TempDir::new().unwrap();
+        let mut cache = ModuleCache::new(ModuleCacheConfig {
+            hd_cache_path: hd_cache_dir.path().to_path_buf(),
+            compilation_gas: 1_000_000,
+            lru_cache_size: 10,
+            hd_cache_size: 10,
+            hd_cache_max_byte_size: u64::MAX,
+            snip_amount: 1,
+        });
+        let schedule_v0 = schedule(b"v0");
+        let schedule_v1 = schedule(b"v1");
+
+        cache.save_module(&BYTECODE, &schedule_v0);
+        cache.set_init_cost(&BYTECODE, &schedule_v0, 100);
+        assert!(cache.load_module(&BYTECODE, &schedule_v0, 10).is_err());
+        assert!(cache.load_module(&BYTECODE, &schedule_v0, 100).is_ok());
+
+        // the module compiled with another schedule is another entry
+        assert!(cache.load_module(&BYTECODE, &schedule_v1, 10).is_ok());
+        cache.set_invalid(&BYTECODE, &schedule_v1);
+        assert!(cache.load_module(&BYTECODE, &schedule_v1, 100).is_err());
+        assert!(cache.load_module(&BYTECODE, &schedule_v0, 100).is_ok());
+    }
+}
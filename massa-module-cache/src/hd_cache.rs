@@ -7,7 +7,7 @@ use massa_sc_runtime::{GasCosts, RuntimeModule};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use rand::RngCore;
 use rocksdb::{Direction, IteratorMode, WriteBatch, DB};
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 use tracing::debug;
 
 const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
@@ -38,12 +38,17 @@ macro_rules! metadata_key {
 pub(crate) struct HDCache {
     /// RocksDB database
     db: DB,
-    /// How many entries are in the db. Count is initialized at creation time by iterating
+    /// How many modules are in the db. Count is initialized at creation time by iterating
     /// over all the entries in the db then it is maintained in memory
     entry_count: usize,
+    /// Total size in bytes of the serialized modules in the db, maintained like `entry_count`
+    byte_size: u64,
     /// Maximum number of entries we want to keep in the db.
     /// When this maximum is reached `snip_amount` entries are removed
     max_entry_count: usize,
+    /// Maximum total size in bytes of the serialized modules we want to keep in the db.
+    /// Entries are removed by `snip_amount` until an insertion fits in it
+    max_byte_size: u64,
     /// How many entries are removed when `entry_count` reaches `max_entry_count`
     snip_amount: usize,
     /// Module metadata serializer
@@ -58,15 +63,36 @@ impl HDCache {
     /// # Arguments
     /// * path: where to store the db
     /// * max_entry_count: maximum number of entries we want to keep in the db
+    /// * max_byte_size: maximum total size of the serialized modules we want to keep in the db
     /// * amount_to_remove: how many entries are removed when `entry_count` reaches `max_entry_count`
-    pub fn new(path: PathBuf, max_entry_count: usize, snip_amount: usize) -> Self {
+    pub fn new(
+        path: PathBuf,
+        max_entry_count: usize,
+        max_byte_size: u64,
+        snip_amount: usize,
+    ) -> Self {
         let db = DB::open_default(path).expect(OPEN_ERROR);
-        let entry_count = db.iterator(IteratorMode::Start).count();
+
+        // each module is stored with its metadata, only count the module keys
+        let mut entry_count: usize = 0;
+        let mut byte_size: u64 = 0;
+        for (key, value) in db.iterator(IteratorMode::Start).flatten() {
+            if key.last() == Some(&MODULE_IDENT) {
+                entry_count += 1;
+                byte_size = byte_size.saturating_add(value.len() as u64);
+            }
+        }
+        debug!(
+            "(HD open) entry_count is: {}, byte_size is: {}",
+            entry_count, byte_size
+        );
 
         Self {
             db,
             entry_count,
+            byte_size,
             max_entry_count,
+            max_byte_size,
             snip_amount,
             meta_ser: ModuleMetadataSerializer::new(),
             meta_deser: ModuleMetadataDeserializer::new(),
@@ -75,10 +101,6 @@ impl HDCache {
 
     /// Insert a new module in the cache
     pub fn insert(&mut self, hash: Hash, module_info: ModuleInfo) {
-        if self.entry_count >= self.max_entry_count {
-            self.snip();
-        }
-
         let mut ser_metadata = Vec::new();
         let ser_module = match module_info {
             ModuleInfo::Invalid => {
@@ -100,6 +122,14 @@ impl HDCache {
                 module.serialize().expect(MOD_SER_ERROR)
             }
         };
+        let module_size = ser_module.len() as u64;
+
+        while self.entry_count > 0
+            && (self.entry_count >= self.max_entry_count
+                || self.byte_size.saturating_add(module_size) > self.max_byte_size)
+        {
+            self.snip();
+        }
 
         let mut batch = WriteBatch::default();
         batch.put(module_key!(hash), ser_module);
@@ -107,8 +137,12 @@ impl HDCache {
         self.db.write(batch).expect(CRUD_ERROR);
 
         self.entry_count = self.entry_count.saturating_add(1);
+        self.byte_size = self.byte_size.saturating_add(module_size);
 
-        debug!("(HD insert) entry_count is: {}", self.entry_count);
+        debug!(
+            "(HD insert) entry_count is: {}, byte_size is: {}",
+            self.entry_count, self.byte_size
+        );
     }
 
     /// Sets the initialization cost of a given module separately
@@ -173,9 +207,12 @@ impl HDCache {
     fn snip(&mut self) {
         let mut iter = self.db.raw_iterator();
         let mut batch = WriteBatch::default();
+        let mut snipped_keys = HashSet::new();
         let mut snipped_count: usize = 0;
+        let mut snipped_size: u64 = 0;
+        let snip_amount = self.snip_amount.min(self.entry_count);
 
-        while snipped_count < self.snip_amount {
+        while snipped_count < snip_amount {
             // generate a random key
             let mut rbytes = [0u8; 16];
             rand::thread_rng().fill_bytes(&mut rbytes);
@@ -192,11 +229,17 @@ impl HDCache {
             // unwrap justified by above conditional statement.
             // seeking the previous key of a randombly generated one
             // will always end up on a metadata key.
-            let metadata_key = iter.key().unwrap();
+            let metadata_key = iter.key().unwrap().to_vec();
+
+            // the batch is not applied yet, skip the entries picked twice
+            if !snipped_keys.insert(metadata_key.clone()) {
+                continue;
+            }
             batch.delete(metadata_key);
             iter.prev();
             let module_key = iter.key().unwrap();
             batch.delete(module_key);
+            snipped_size += iter.value().unwrap().len() as u64;
 
             // increase snipped_count
             snipped_count += 1;
@@ -207,9 +250,10 @@ impl HDCache {
             panic!("snipped_count incoherence");
         }
 
-        // delete the key and reduce entry_count
+        // delete the key and reduce entry_count and byte_size
         self.db.write(batch).expect(CRUD_ERROR);
         self.entry_count -= snipped_count;
+        self.byte_size = self.byte_size.saturating_sub(snipped_size);
     }
 }
 
@@ -238,7 +282,7 @@ mod tests {
 
     fn setup() -> HDCache {
         let tmp_path = TempDir::new().unwrap().path().to_path_buf();
-        HDCache::new(tmp_path, 1000, 10)
+        HDCache::new(tmp_path, 1000, u64::MAX, 10)
     }
 
     #[test]
@@ -310,4 +354,43 @@ mod tests {
             assert!(cached_module.is_none());
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_insert_more_than_max_byte_size() {
+        let tmp_path = TempDir::new().unwrap().path().to_path_buf();
+        let module = make_default_module_info();
+        let module_size = match &module {
+            ModuleInfo::Module(module) => module.serialize().unwrap().len() as u64,
+            _ => unreachable!(),
+        };
+        let mut cache = HDCache::new(tmp_path, 1000, 3 * module_size, 2);
+
+        for count in 0..10 {
+            let key = Hash::compute_from(count.to_string().as_bytes());
+            cache.insert(key, module.clone());
+            assert!(cache.byte_size <= cache.max_byte_size);
+            assert_eq!(cache.byte_size, cache.entry_count as u64 * module_size);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_reopen_keeps_modules() {
+        let tmp_dir = TempDir::new().unwrap();
+        let module = make_default_module_info();
+        let hash = Hash::compute_from(b"test_hash");
+
+        let mut cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, u64::MAX, 10);
+        cache.insert(hash, module);
+        cache.set_init_cost(hash, 100);
+        let byte_size = cache.byte_size;
+        drop(cache);
+
+        let cache = HDCache::new(tmp_dir.path().to_path_buf(), 1000, u64::MAX, 10);
+        assert_eq!(cache.entry_count, 1);
+        assert_eq!(cache.byte_size, byte_size);
+        let cached_module = cache.get(hash, 1, GasCosts::default()).unwrap();
+        assert!(matches!(cached_module, ModuleInfo::ModuleAndDelta(_)));
+    }
 }
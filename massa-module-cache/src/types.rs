@@ -1,4 +1,5 @@
-use massa_sc_runtime::RuntimeModule;
+use massa_hash::Hash;
+use massa_sc_runtime::{GasCosts, RuntimeModule};
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
@@ -9,6 +10,18 @@ use nom::{
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::ops::Bound::Included;
 
+/// Gas costs the modules are compiled with, given with each access to the cache
+#[derive(Clone)]
+pub struct GasCostsSchedule {
+    /// Gas costs used to:
+    /// * setup `massa-sc-runtime` metering on compilation
+    /// * debit compilation costs
+    pub gas_costs: GasCosts,
+    /// Version of the gas costs, part of the keys of the cached modules
+    /// so that the modules compiled with other gas costs are not reused
+    pub version: Hash,
+}
+
 /// Main type
 #[derive(Clone)]
 pub enum ModuleInfo {
@@ -0,0 +1,202 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Crash bundles written when the node panics.
+//!
+//! A crash bundle is a directory containing everything needed to investigate a crash report:
+//! the panic message and backtrace, the last log lines, the node configuration
+//! with its secrets redacted and the database statistics.
+
+use crate::settings::SETTINGS;
+use massa_db::MassaDB;
+use massa_models::config::constants::VERSION;
+use massa_time::MassaTime;
+use parking_lot::{Mutex, RwLock};
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::panic::PanicInfo;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Configuration fields whose name contains one of these words are redacted from the bundles
+const SECRET_MARKERS: [&str; 6] = [
+    "password",
+    "secret",
+    "token",
+    "private_key",
+    "keypair",
+    "wallet",
+];
+
+/// Keeps the last log lines in memory so that they can be written in a crash bundle
+#[derive(Clone)]
+pub struct LogTail {
+    /// last log lines, oldest first
+    lines: Arc<Mutex<VecDeque<String>>>,
+    /// maximum number of lines kept
+    capacity: usize,
+}
+
+impl LogTail {
+    /// Creates a log tail keeping at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        LogTail {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns the kept lines, oldest first.
+    /// Does not wait if the lines are locked, which happens if the panic occurred while logging.
+    fn snapshot(&self) -> Option<Vec<String>> {
+        self.lines
+            .try_lock()
+            .map(|lines| lines.iter().cloned().collect())
+    }
+}
+
+impl Write for LogTail {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.capacity == 0 {
+            return Ok(buf.len());
+        }
+        let mut lines = self.lines.lock();
+        for line in String::from_utf8_lossy(buf).lines() {
+            if line.is_empty() {
+                continue;
+            }
+            if lines.len() >= self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogTail {
+    type Writer = LogTail;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Writes a crash bundle when the node panics
+#[derive(Clone)]
+pub struct CrashReporter {
+    /// directory in which the crash bundles are created
+    directory: PathBuf,
+    /// last log lines
+    log_tail: LogTail,
+    /// database of the running node, set once it is opened
+    db: Arc<Mutex<Option<Arc<RwLock<MassaDB>>>>>,
+}
+
+impl CrashReporter {
+    /// Creates a crash reporter writing its bundles in `directory`
+    /// and keeping the last `log_lines` log lines
+    pub fn new(directory: PathBuf, log_lines: usize) -> Self {
+        CrashReporter {
+            directory,
+            log_tail: LogTail::new(log_lines),
+            db: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Log writer feeding the log lines written in the crash bundles
+    pub fn log_tail(&self) -> LogTail {
+        self.log_tail.clone()
+    }
+
+    /// Sets the database whose statistics are written in the crash bundles.
+    /// Called each time the node (re)opens its database.
+    pub fn set_db(&self, db: Arc<RwLock<MassaDB>>) {
+        *self.db.lock() = Some(db);
+    }
+
+    /// Writes a crash bundle for the given panic and returns its path
+    pub fn write_bundle(&self, info: &PanicInfo) -> std::io::Result<PathBuf> {
+        let now = MassaTime::now().map(|t| t.to_millis()).unwrap_or_default();
+        let path = self.directory.join(format!("crash_{}", now));
+        std::fs::create_dir_all(&path)?;
+
+        let thread = std::thread::current();
+        std::fs::write(
+            path.join("panic.txt"),
+            format!(
+                "version: {}\ntimestamp: {}\nthread: {}\n{}\n\nbacktrace:\n{}\n",
+                *VERSION,
+                now,
+                thread.name().unwrap_or("<unnamed>"),
+                info,
+                Backtrace::force_capture()
+            ),
+        )?;
+
+        let logs = match self.log_tail.snapshot() {
+            Some(lines) => lines.join("\n"),
+            None => {
+                "log lines unavailable: the logger was busy when the panic occurred".to_string()
+            }
+        };
+        std::fs::write(path.join("logs.txt"), logs)?;
+
+        std::fs::write(
+            path.join("config.txt"),
+            redact_secrets(&format!("{:#?}", *SETTINGS)),
+        )?;
+
+        // the database may be locked by the panicking thread, do not wait for it
+        let db_stats = match self.db.try_lock().as_deref() {
+            Some(Some(db)) => match db.try_read() {
+                Some(db) => db.get_db_stats(),
+                None => "database statistics unavailable: the database is locked".to_string(),
+            },
+            Some(None) => "database statistics unavailable: the database is not opened".to_string(),
+            None => "database statistics unavailable: the database handle is locked".to_string(),
+        };
+        std::fs::write(path.join("db_stats.txt"), db_stats)?;
+
+        Ok(path)
+    }
+
+    /// Installs a panic hook writing a crash bundle, then running the previous hook
+    pub fn install_panic_hook(&self) {
+        let reporter = self.clone();
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            match reporter.write_bundle(info) {
+                Ok(path) => eprintln!(
+                    "The node crashed. A crash report was written to {}, please attach it when reporting the issue.",
+                    path.display()
+                ),
+                Err(e) => eprintln!("The node crashed and the crash report could not be written: {}", e),
+            }
+            previous_hook(info);
+        }));
+    }
+}
+
+/// Redacts the values of the secret fields of a pretty printed configuration
+fn redact_secrets(config: &str) -> String {
+    config
+        .lines()
+        .map(|line| {
+            if let Some((field, value)) = line.split_once(": ") {
+                let name = field.trim().to_lowercase();
+                let nested = value.ends_with('{') || value.ends_with('[') || value.ends_with('(');
+                if !nested && SECRET_MARKERS.iter().any(|marker| name.contains(marker)) {
+                    return format!("{}: <redacted>,", field);
+                }
+            }
+            line.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Execution controller of the header-only relay nodes, whose execution is disabled.
+//!
+//! No block is executed: the blockclique updates of consensus are dropped, the final state stays at its bootstrapped slot
+//! and the queries answer as if nothing had been executed. The read-only executions and the proofs are refused.
+
+use massa_execution_exports::{
+    EventProof, ExecutionAddressInfo, ExecutionController, ExecutionError, ExecutionManager,
+    IndexedOperation, OperationTrace, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+};
+use massa_ledger_exports::LedgerEntryProof;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::block_id::BlockId;
+use massa_models::denunciation::DenunciationIndex;
+use massa_models::execution::EventFilter;
+use massa_models::operation::OperationId;
+use massa_models::output_event::SCOutputEvent;
+use massa_models::prehash::PreHashMap;
+use massa_models::slot::Slot;
+use massa_models::stats::ExecutionStats;
+use massa_storage::Storage;
+use massa_time::MassaTime;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Error returned by the requests that need the execution
+const EXECUTION_DISABLED: &str = "execution is disabled on this header-only relay node";
+
+/// Execution controller that executes nothing
+#[derive(Clone)]
+pub struct DisabledExecutionController {
+    /// slot of the final state, which is never executed further
+    final_cursor: Slot,
+}
+
+impl DisabledExecutionController {
+    /// Creates the controller of a node whose final state stays at `final_cursor`
+    pub fn new(final_cursor: Slot) -> Self {
+        DisabledExecutionController { final_cursor }
+    }
+}
+
+impl ExecutionController for DisabledExecutionController {
+    fn update_blockclique_status(
+        &self,
+        _finalized_blocks: HashMap<Slot, BlockId>,
+        _new_blockclique: Option<HashMap<Slot, BlockId>>,
+        _block_storage: PreHashMap<BlockId, Storage>,
+    ) {
+    }
+
+    fn get_filtered_sc_output_event(&self, _filter: EventFilter) -> Vec<SCOutputEvent> {
+        Vec::new()
+    }
+
+    fn get_event_proof(&self, _slot: Slot, _index_in_slot: u64) -> Option<EventProof> {
+        None
+    }
+
+    fn get_ledger_entry_proof(
+        &self,
+        _address: &Address,
+        _datastore_keys: &[Vec<u8>],
+    ) -> Result<LedgerEntryProof, ExecutionError> {
+        Err(ExecutionError::RuntimeError(EXECUTION_DISABLED.to_string()))
+    }
+
+    fn get_operations_by_address(
+        &self,
+        _address: &Address,
+        _cursor: Option<(Slot, OperationId)>,
+        _limit: usize,
+    ) -> Option<Vec<IndexedOperation>> {
+        None
+    }
+
+    fn get_operation_trace(&self, _operation_id: &OperationId) -> Option<Option<OperationTrace>> {
+        None
+    }
+
+    fn get_final_and_candidate_balance(
+        &self,
+        addresses: &[Address],
+    ) -> Vec<(Option<Amount>, Option<Amount>)> {
+        vec![(None, None); addresses.len()]
+    }
+
+    fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)> {
+        vec![(None, None); batch.len()]
+    }
+
+    fn get_final_and_active_data_entry(
+        &self,
+        input: Vec<(Address, Vec<u8>)>,
+    ) -> Vec<(Option<Vec<u8>>, Option<Vec<u8>>)> {
+        vec![(None, None); input.len()]
+    }
+
+    fn get_final_and_candidate_datastore_keys(
+        &self,
+        _addr: &Address,
+        _prefix: &[u8],
+    ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>) {
+        (BTreeSet::new(), BTreeSet::new())
+    }
+
+    fn get_cycle_active_rolls(&self, _cycle: u64) -> BTreeMap<Address, u64> {
+        BTreeMap::new()
+    }
+
+    fn execute_readonly_request(
+        &self,
+        _req: ReadOnlyExecutionRequest,
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        Err(ExecutionError::RuntimeError(EXECUTION_DISABLED.to_string()))
+    }
+
+    fn is_denunciation_executed(&self, _denunciation_index: &DenunciationIndex) -> bool {
+        false
+    }
+
+    fn get_addresses_infos(&self, addresses: &[Address]) -> Vec<ExecutionAddressInfo> {
+        addresses
+            .iter()
+            .map(|_| ExecutionAddressInfo {
+                candidate_balance: Amount::zero(),
+                final_balance: Amount::zero(),
+                final_roll_count: 0,
+                final_datastore_keys: BTreeSet::new(),
+                final_delegation: None,
+                candidate_roll_count: 0,
+                candidate_datastore_keys: BTreeSet::new(),
+                candidate_delegation: None,
+                future_deferred_credits: BTreeMap::new(),
+                cycle_infos: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn get_stats(&self) -> ExecutionStats {
+        let now = MassaTime::now().expect("could not get current time");
+        ExecutionStats {
+            time_window_start: now,
+            time_window_end: now,
+            final_block_count: 0,
+            final_executed_operations_count: 0,
+            active_cursor: self.final_cursor,
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ExecutionController> {
+        Box::new(self.clone())
+    }
+}
+
+/// Execution manager of the header-only relay nodes, with no execution thread to stop
+pub struct DisabledExecutionManager;
+
+impl ExecutionManager for DisabledExecutionManager {
+    fn stop(&mut self) {}
+}
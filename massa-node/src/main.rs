@@ -6,9 +6,13 @@
 #![feature(ip)]
 extern crate massa_logging;
 
+use crate::crash_dump::CrashReporter;
+use crate::disabled_execution::{DisabledExecutionController, DisabledExecutionManager};
 #[cfg(feature = "op_spammer")]
 use crate::operation_injector::start_operation_injector;
 use crate::settings::SETTINGS;
+use crate::snapshot_source::{download_snapshot, SnapshotSignature};
+use crate::webhooks::WebhookDispatcher;
 
 use crossbeam_channel::TryRecvError;
 use ctrlc as _;
@@ -17,31 +21,37 @@ use massa_api::{ApiServer, ApiV2, Private, Public, RpcServer, StopHandle, API};
 use massa_api_exports::config::APIConfig;
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
-use massa_bootstrap::{
-    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
-    DefaultConnector,
-};
+use massa_bootstrap::{get_state, BootstrapConfig, DefaultConnector};
+#[cfg(feature = "bootstrap_serving")]
+use massa_bootstrap::{start_bootstrap_server, BootstrapManager, BootstrapTcpListener};
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::events::ConsensusEvent;
-use massa_consensus_exports::{ConsensusChannels, ConsensusConfig, ConsensusManager};
+use massa_consensus_exports::{
+    ConsensusChannels, ConsensusConfig, ConsensusController, ConsensusManager,
+};
 use massa_consensus_worker::start_consensus_worker;
-use massa_db::{MassaDB, MassaDBConfig};
+use massa_db::{MassaArchive, MassaDB, MassaDBConfig};
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
-    ExecutionChannels, ExecutionConfig, ExecutionManager, GasCosts, StorageCostsConstants,
+    ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionManager, GasCosts,
+    StorageCostsConstants,
 };
-use massa_execution_worker::start_execution_worker;
+use massa_execution_worker::{replay_final_slots, start_execution_worker, ReplayReport};
 use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+#[cfg(feature = "factory")]
 use massa_factory_worker::start_factory;
 use massa_final_state::{FinalState, FinalStateConfig};
 use massa_grpc::config::GrpcConfig;
+use massa_grpc::drain::DrainController;
 use massa_grpc::server::MassaGrpc;
+use massa_hash::Hash;
 use massa_ledger_exports::LedgerConfig;
 use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::address::Address;
+use massa_models::block_id::BlockId;
 use massa_models::config::constants::{
     BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE, CONSENSUS_BOOTSTRAP_PART_SIZE,
     DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY,
@@ -76,17 +86,19 @@ use massa_models::config::{
     POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE, POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
 };
 use massa_models::slot::Slot;
+use massa_models::stats::BootstrapStats;
 use massa_pool_exports::{PoolChannels, PoolConfig, PoolManager};
 use massa_pool_worker::start_pool_controller;
 use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorManager};
 use massa_pos_worker::start_selector_worker;
-use massa_protocol_exports::{ProtocolConfig, ProtocolManager};
+use massa_protocol_exports::{ProtocolConfig, ProtocolController, ProtocolManager};
 use massa_protocol_worker::{create_protocol_controller, start_protocol_controller};
+use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{MipComponent, MipInfo, MipState};
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-use massa_wallet::Wallet;
+use massa_wallet::{load_password, store_password, PasswordBackend, Wallet};
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
 use std::collections::{BTreeMap, HashMap};
@@ -102,14 +114,50 @@ use tokio::sync::{broadcast, mpsc};
 use tracing::{error, info, warn};
 use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
+mod crash_dump;
+mod disabled_execution;
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
 mod settings;
+mod snapshot_source;
+mod webhooks;
+
+/// Size of the channel of the bootstrap progress reports
+const BOOTSTRAP_PROGRESS_CHANNEL_SIZE: usize = 100;
+
+/// Minimum delay between two logs of the bootstrap progress within the same phase
+const BOOTSTRAP_PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Gas costs schedule built into the node, applied from a version of the `GasCosts` MIP component
+struct BuiltinGasCostsSchedule {
+    /// version of the `GasCosts` MIP component
+    version: u32,
+    /// abi gas costs file, with its built-in content
+    abi_gas_costs: (&'static str, &'static [u8]),
+    /// wasm gas costs file, with its built-in content
+    wasm_gas_costs: (&'static str, &'static [u8]),
+}
+
+/// Gas costs schedules of the versions of the `GasCosts` MIP component (see `mip_store`).
+/// They are consensus-critical, so they are part of the node instead of its configuration:
+/// their files must match the built-in content.
+const GAS_COSTS_SCHEDULES: [BuiltinGasCostsSchedule; 1] = [BuiltinGasCostsSchedule {
+    version: 1,
+    abi_gas_costs: (
+        "base_config/gas_costs/v1/abi_gas_costs.json",
+        include_bytes!("../base_config/gas_costs/v1/abi_gas_costs.json"),
+    ),
+    wasm_gas_costs: (
+        "base_config/gas_costs/v1/wasm_gas_costs.json",
+        include_bytes!("../base_config/gas_costs/v1/wasm_gas_costs.json"),
+    ),
+}];
 
 async fn launch(
     args: &Args,
     node_wallet: Arc<RwLock<Wallet>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
+    crash_reporter: Option<&CrashReporter>,
 ) -> (
     MassaReceiver<ConsensusEvent>,
     Option<BootstrapManager>,
@@ -118,12 +166,13 @@ async fn launch(
     Box<dyn SelectorManager>,
     Box<dyn PoolManager>,
     Box<dyn ProtocolManager>,
-    Box<dyn FactoryManager>,
+    Option<Box<dyn FactoryManager>>,
     mpsc::Receiver<()>,
     StopHandle,
     StopHandle,
     StopHandle,
     Option<massa_grpc::server::StopHandle>,
+    Option<WebhookDispatcher>,
 ) {
     info!("Node version : {}", *VERSION);
     let now = MassaTime::now().expect("could not get now time");
@@ -183,71 +232,72 @@ async fn launch(
     let shared_storage: Storage = Storage::create_root();
 
     // init final state
-    let ledger_config = LedgerConfig {
-        thread_count: THREAD_COUNT,
-        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
-        disk_ledger_path: SETTINGS.ledger.disk_ledger_path.clone(),
-        max_key_length: MAX_DATASTORE_KEY_LENGTH,
-        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
-    };
-    let async_pool_config = AsyncPoolConfig {
-        max_length: MAX_ASYNC_POOL_LENGTH,
-        thread_count: THREAD_COUNT,
-        max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
-        max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
-    };
-    let pos_config = PoSConfig {
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        thread_count: THREAD_COUNT,
-        cycle_history_length: POS_SAVED_CYCLES,
-        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
-        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
-        max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-    };
-    let executed_ops_config = ExecutedOpsConfig {
-        thread_count: THREAD_COUNT,
-    };
-    let executed_denunciations_config = ExecutedDenunciationsConfig {
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-    };
-    let final_state_config = FinalStateConfig {
-        ledger_config: ledger_config.clone(),
-        async_pool_config,
-        pos_config,
-        executed_ops_config,
-        executed_denunciations_config,
-        final_history_length: SETTINGS.ledger.final_history_length,
-        thread_count: THREAD_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        initial_seed_string: INITIAL_DRAW_SEED.into(),
-        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
-        endorsement_count: ENDORSEMENT_COUNT,
-        max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
-        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-    };
+    let final_state_config = final_state_config();
+    let ledger_config = final_state_config.ledger_config.clone();
 
     // Start massa metrics
     let metrics = MassaMetrics::new(SETTINGS.metrics.enabled, THREAD_COUNT);
 
+    // Keep the current disk ledger if we can resume from it with a differential bootstrap
+    let differential_sync = SETTINGS.bootstrap.differential_sync
+        && args.restart_from_snapshot_at_period.is_none()
+        && SETTINGS.ledger.disk_ledger_path.exists();
+
+    // an interrupted bootstrap resumes on top of the partial final state it left on disk
+    let resume_bootstrap = args.restart_from_snapshot_at_period.is_none()
+        && args.bootstrap_from_file.is_none()
+        && SETTINGS.bootstrap.checkpoint_path.exists()
+        && SETTINGS.ledger.disk_ledger_path.exists();
+    if !resume_bootstrap && SETTINGS.bootstrap.checkpoint_path.exists() {
+        std::fs::remove_file(&SETTINGS.bootstrap.checkpoint_path)
+            .expect("bootstrap checkpoint delete failed");
+    }
+
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
     if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
         info!("Loading old ledger for next episode");
-    } else {
-        if SETTINGS.ledger.disk_ledger_path.exists() {
-            std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
-                .expect("disk ledger delete failed");
-        }
-        if SETTINGS.execution.hd_cache_path.exists() {
-            std::fs::remove_dir_all(SETTINGS.execution.hd_cache_path.clone())
-                .expect("disk hd cache delete failed");
-        }
+    } else if resume_bootstrap {
+        info!("Keeping the partial final state to resume the interrupted bootstrap");
+    } else if differential_sync && args.bootstrap_from_file.is_none() {
+        info!("Keeping the local final state for a differential bootstrap");
+    } else if SETTINGS.ledger.disk_ledger_path.exists() {
+        std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
+            .expect("disk ledger delete failed");
     }
 
+    // a signed snapshot published over HTTP(S) replaces the download of the whole state from the bootstrap servers
+    let full_bootstrap = !args.keep_ledger
+        && args.restart_from_snapshot_at_period.is_none()
+        && !resume_bootstrap
+        && !differential_sync
+        && args.bootstrap_from_file.is_none();
+    let downloaded_snapshot = if full_bootstrap && !SETTINGS.bootstrap.snapshot_url.is_empty() {
+        match download_snapshot(
+            &SETTINGS.bootstrap.snapshot_url,
+            &SETTINGS.bootstrap.snapshot_trusted_keys,
+            &SETTINGS.bootstrap.snapshot_download_path,
+        )
+        .await
+        {
+            Ok(signature) => Some(signature),
+            Err(e) => {
+                warn!(
+                    "Could not download the final state snapshot, the whole state will be bootstrapped: {}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let snapshot_path = args.bootstrap_from_file.clone().or_else(|| {
+        downloaded_snapshot
+            .as_ref()
+            .map(|_| SETTINGS.bootstrap.snapshot_download_path.clone())
+    });
+
     let db_config = MassaDBConfig {
         path: SETTINGS.ledger.disk_ledger_path.clone(),
         max_history_length: SETTINGS.ledger.final_history_length,
@@ -255,63 +305,47 @@ async fn launch(
         thread_count: THREAD_COUNT,
     };
     let db = Arc::new(RwLock::new(MassaDB::new(db_config)));
+    if let Some(snapshot_path) = &snapshot_path {
+        let slot = db
+            .write()
+            .import_snapshot(snapshot_path)
+            .expect("could not import the final state snapshot");
+        if let Some(signature) = &downloaded_snapshot {
+            // the snapshot hash was checked against the imported state, it must also be the signed one
+            let state_hash = db.read().get_db_hash();
+            if slot != signature.slot || state_hash != signature.state_hash {
+                panic!(
+                    "the downloaded snapshot at slot {} with state hash {} does not match its signature at slot {} with state hash {}",
+                    slot, state_hash, signature.slot, signature.state_hash
+                );
+            }
+            std::fs::remove_file(snapshot_path).expect("downloaded snapshot delete failed");
+        }
+        info!(
+            "Final state imported from {} at slot {}, only the changes since then will be bootstrapped",
+            snapshot_path.display(),
+            slot
+        );
+    }
+    if let Some(crash_reporter) = crash_reporter {
+        crash_reporter.set_db(db.clone());
+    }
 
     // Create final ledger
     let ledger = FinalLedger::new(ledger_config.clone(), db.clone());
 
     // launch selector worker
-    let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
-        max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
-        channel_size: CHANNEL_SIZE,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
-    })
-    .expect("could not start selector worker");
+    let (selector_manager, selector_controller) =
+        start_selector_worker(selector_config()).expect("could not start selector worker");
 
     // Creates an empty default store
-    let mip_stats_config = MipStatsConfig {
-        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
-        counters_max: MIP_STORE_STATS_COUNTERS_MAX,
-    };
-    let mip_0001_start = MassaTime::from_utc_ymd_hms(2023, 6, 14, 15, 0, 0).unwrap();
-    let mip_0001_timeout = MassaTime::from_utc_ymd_hms(2023, 6, 14, 16, 0, 0).unwrap();
-    let mip_0001_defined_start = MassaTime::from_utc_ymd_hms(2023, 2, 14, 14, 30, 0).unwrap();
-    let mip_0002_start = MassaTime::from_utc_ymd_hms(2023, 6, 16, 13, 0, 0).unwrap();
-    let mip_0002_timeout = MassaTime::from_utc_ymd_hms(2023, 6, 19, 14, 0, 0).unwrap();
-    let mip_0002_defined_start = MassaTime::from_utc_ymd_hms(2023, 6, 16, 10, 0, 0).unwrap();
-    let mip_list_1: [(MipInfo, MipState); 2] = [
-        (
-            MipInfo {
-                name: "MIP-0001".to_string(),
-                version: 1,
-                components: BTreeMap::from([
-                    (MipComponent::Address, 1),
-                    (MipComponent::KeyPair, 1),
-                ]),
-                start: mip_0001_start,
-                timeout: mip_0001_timeout,
-                activation_delay: MassaTime::from_millis(100),
-            },
-            MipState::new(mip_0001_defined_start),
-        ),
-        (
-            MipInfo {
-                name: "MIP-0002".to_string(),
-                version: 2,
-                components: BTreeMap::from([(MipComponent::FinalStateHashKind, 1)]),
-                start: mip_0002_start,
-                timeout: mip_0002_timeout,
-                activation_delay: T0
-                    .saturating_mul(PERIODS_PER_CYCLE.saturating_add(1))
-                    .saturating_mul(40),
-            },
-            MipState::new(mip_0002_defined_start),
-        ),
-    ];
-    let mip_store =
-        MipStore::try_from((mip_list_1, mip_stats_config)).expect("mip store creation failed");
+    let mip_store = mip_store();
+
+    // Open the archive of the pruned blocks and cycles, in archival mode
+    let archive = SETTINGS
+        .archive
+        .enabled
+        .then(|| Arc::new(MassaArchive::new(SETTINGS.archive.path.clone())));
 
     // Create final state, either from a snapshot, or from scratch
     let final_state = Arc::new(parking_lot::RwLock::new(
@@ -331,11 +365,12 @@ async fn launch(
                 Box::new(ledger),
                 selector_controller.clone(),
                 mip_store.clone(),
-                true,
+                !(differential_sync || resume_bootstrap || snapshot_path.is_some()),
             )
             .expect("could not init final state"),
         },
     ));
+    final_state.write().pos_state.archive = archive.clone();
 
     let bootstrap_config: BootstrapConfig = BootstrapConfig {
         bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
@@ -354,11 +389,25 @@ async fn launch(
         max_clock_delta: SETTINGS.bootstrap.max_clock_delta,
         cache_duration: SETTINGS.bootstrap.cache_duration,
         keep_ledger: args.keep_ledger,
+        // the imported snapshot is completed like a local final state kept for a differential bootstrap
+        differential_sync: differential_sync || snapshot_path.is_some(),
+        checkpoint_path: SETTINGS.bootstrap.checkpoint_path.clone(),
+        parallel_download_servers: SETTINGS.bootstrap.parallel_download_servers,
+        state_attestations: SETTINGS.bootstrap.state_attestations,
+        state_attestation_attempts: SETTINGS.bootstrap.state_attestation_attempts,
+        trusted_state_hash: args.trusted_state_slot.zip(args.trusted_state_hash),
         max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
         max_simultaneous_bootstraps: SETTINGS.bootstrap.max_simultaneous_bootstraps,
+        max_sessions_per_ip: SETTINGS.bootstrap.max_sessions_per_ip,
+        max_queued_sessions: SETTINGS.bootstrap.max_queued_sessions,
+        queue_timeout: SETTINGS.bootstrap.queue_timeout,
+        min_session_bytes_per_second: SETTINGS.bootstrap.min_session_bytes_per_second,
         per_ip_min_interval: SETTINGS.bootstrap.per_ip_min_interval,
         ip_list_max_size: SETTINGS.bootstrap.ip_list_max_size,
         max_bytes_read_write: SETTINGS.bootstrap.max_bytes_read_write,
+        max_total_upload_bytes_per_second: SETTINGS.bootstrap.max_total_upload_bytes_per_second,
+        max_total_download_bytes_per_second: SETTINGS.bootstrap.max_total_download_bytes_per_second,
+        stream_compression: SETTINGS.bootstrap.stream_compression,
         max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
         randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
         thread_count: THREAD_COUNT,
@@ -394,6 +443,10 @@ async fn launch(
         max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
     };
 
+    let (bootstrap_progress_tx, bootstrap_progress_rx) =
+        crossbeam_channel::bounded(BOOTSTRAP_PROGRESS_CHANNEL_SIZE);
+    let bootstrap_progress_monitor =
+        spawn_bootstrap_progress_monitor(bootstrap_progress_rx, metrics.clone());
     let bootstrap_state = match get_state(
         &bootstrap_config,
         final_state.clone(),
@@ -402,6 +455,7 @@ async fn launch(
         *GENESIS_TIMESTAMP,
         *END_TIMESTAMP,
         args.restart_from_snapshot_at_period,
+        Some(bootstrap_progress_tx),
         sig_int_toggled,
     ) {
         Ok(vals) => vals,
@@ -411,6 +465,9 @@ async fn launch(
         }
         Err(err) => panic!("critical error detected in the bootstrap process: {}", err),
     };
+    let bootstrap_stats = bootstrap_progress_monitor
+        .join()
+        .expect("bootstrap progress monitor thread panicked");
 
     if !final_state.read().is_db_valid() {
         // TODO: Bootstrap again instead of panicking
@@ -454,55 +511,8 @@ async fn launch(
         }
     }
 
-    // Storage costs constants
-    let storage_costs_constants = StorageCostsConstants {
-        ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
-        ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
-        ledger_entry_datastore_base_cost: LEDGER_COST_PER_BYTE
-            .checked_mul_u64(LEDGER_ENTRY_DATASTORE_BASE_SIZE as u64)
-            .expect("Overflow when creating constant ledger_entry_datastore_base_size"),
-    };
-
-    // launch execution module
-    let execution_config = ExecutionConfig {
-        max_final_events: SETTINGS.execution.max_final_events,
-        readonly_queue_length: SETTINGS.execution.readonly_queue_length,
-        cursor_delay: SETTINGS.execution.cursor_delay,
-        max_async_gas: MAX_ASYNC_GAS,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
-        roll_price: ROLL_PRICE,
-        thread_count: THREAD_COUNT,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        block_reward: BLOCK_REWARD,
-        endorsement_count: ENDORSEMENT_COUNT as u64,
-        operation_validity_period: OPERATION_VALIDITY_PERIODS,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
-        max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
-        max_bytecode_size: MAX_BYTECODE_LENGTH,
-        max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
-        storage_costs_constants,
-        max_read_only_gas: SETTINGS.execution.max_read_only_gas,
-        initial_vesting_path: SETTINGS.execution.initial_vesting_path.clone(),
-        gas_costs: GasCosts::new(
-            SETTINGS.execution.abi_gas_costs_file.clone(),
-            SETTINGS.execution.wasm_gas_costs_file.clone(),
-        )
-        .expect("Failed to load gas costs"),
-        last_start_period: final_state.read().last_start_period,
-        hd_cache_path: SETTINGS.execution.hd_cache_path.clone(),
-        lru_cache_size: SETTINGS.execution.lru_cache_size,
-        hd_cache_size: SETTINGS.execution.hd_cache_size,
-        snip_amount: SETTINGS.execution.snip_amount,
-        roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
-        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        broadcast_enabled: SETTINGS.api.enable_broadcast,
-        broadcast_slot_execution_output_channel_capacity: SETTINGS
-            .execution
-            .broadcast_slot_execution_output_channel_capacity,
-    };
+    // launch execution module, unless execution is disabled on this header-only relay node
+    let execution_config = execution_config(final_state.read().last_start_period);
 
     let execution_channels = ExecutionChannels {
         slot_execution_output_sender: broadcast::channel(
@@ -511,14 +521,37 @@ async fn launch(
         .0,
     };
 
-    let (execution_manager, execution_controller) = start_execution_worker(
-        execution_config,
-        final_state.clone(),
-        selector_controller.clone(),
-        mip_store.clone(),
-        execution_channels.clone(),
-        metrics.clone(),
-    );
+    // the gas estimations of the API account for the compilation of the executed bytecode
+    let sp_compilation_cost = execution_config.gas_costs.sp_compilation_cost;
+    let (execution_manager, execution_controller): (
+        Box<dyn ExecutionManager>,
+        Box<dyn ExecutionController>,
+    ) = if SETTINGS.modules.execution {
+        start_execution_worker(
+            execution_config,
+            final_state.clone(),
+            selector_controller.clone(),
+            mip_store.clone(),
+            execution_channels.clone(),
+            metrics.clone(),
+        )
+    } else {
+        // header-only relay node: nothing is produced nor served from the final state, which is no longer executed
+        if SETTINGS.modules.factory || SETTINGS.modules.bootstrap_server {
+            panic!("modules.factory and modules.bootstrap_server must be disabled when modules.execution is disabled");
+        }
+        info!("Execution disabled: this node relays the blocks without executing them nor checking their PoS draws");
+        let final_cursor = final_state
+            .read()
+            .db
+            .read()
+            .get_change_id()
+            .expect("could not get the slot of the final state");
+        (
+            Box::new(DisabledExecutionManager),
+            Box::new(DisabledExecutionController::new(final_cursor)),
+        )
+    };
 
     // launch pool controller
     let pool_config = PoolConfig {
@@ -530,17 +563,25 @@ async fn launch(
         operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
+        max_operation_pool_byte_size: SETTINGS.pool.max_operation_pool_byte_size,
+        max_operations_per_sender: SETTINGS.pool.max_operations_per_sender,
+        operation_eviction_policy: SETTINGS.pool.operation_eviction_policy,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
+        endorsement_retention_periods: SETTINGS.pool.endorsement_retention_periods,
+        endorsement_pool_cleanup_interval: SETTINGS.pool.endorsement_pool_cleanup_interval,
         operations_channel_size: POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
         endorsements_channel_size: POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE,
         denunciations_channel_size: POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
-        broadcast_enabled: SETTINGS.api.enable_broadcast,
+        broadcast_enabled: SETTINGS.api.enable_broadcast || SETTINGS.webhooks.enabled,
         broadcast_endorsements_channel_capacity: SETTINGS
             .pool
             .broadcast_endorsements_channel_capacity,
         broadcast_operations_channel_capacity: SETTINGS.pool.broadcast_operations_channel_capacity,
+        broadcast_denunciations_channel_capacity: SETTINGS
+            .pool
+            .broadcast_denunciations_channel_capacity,
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
@@ -553,6 +594,14 @@ async fn launch(
         endorsement_sender: broadcast::channel(pool_config.broadcast_endorsements_channel_capacity)
             .0,
         operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity).0,
+        operation_lifecycle_sender: broadcast::channel(
+            pool_config.broadcast_operations_channel_capacity,
+        )
+        .0,
+        denunciation_sender: broadcast::channel(
+            pool_config.broadcast_denunciations_channel_capacity,
+        )
+        .0,
         selector: selector_controller.clone(),
         execution_controller: execution_controller.clone(),
     };
@@ -562,8 +611,21 @@ async fn launch(
         &shared_storage,
         pool_channels.clone(),
         node_wallet.clone(),
+        None,
+        Some(SETTINGS.pool.persistence_path.clone()),
+        Some(SETTINGS.pool.denylist_path.clone()),
     );
 
+    // trusted checkpoint and the chain of its ancestors, trusted before the sync starts
+    let trusted_checkpoint = args.checkpoint_slot.zip(args.checkpoint_block_id);
+    let trusted_checkpoint_ancestors: Vec<BlockId> = match &args.checkpoint_chain {
+        Some(path) => serde_json::from_str(
+            &std::fs::read_to_string(path).expect("could not read the checkpoint chain file"),
+        )
+        .expect("could not parse the checkpoint chain file"),
+        None => Vec::new(),
+    };
+
     // launch protocol controller
     let mut listeners = HashMap::default();
     listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
@@ -588,6 +650,10 @@ async fn launch(
         operation_batch_proc_period: SETTINGS.protocol.operation_batch_proc_period,
         asked_operations_pruning_period: SETTINGS.protocol.asked_operations_pruning_period,
         operation_announcement_interval: SETTINGS.protocol.operation_announcement_interval,
+        local_operations_rebroadcast_interval: SETTINGS
+            .protocol
+            .local_operations_rebroadcast_interval,
+        max_local_operations: SETTINGS.protocol.max_local_operations,
         max_operations_per_message: SETTINGS.protocol.max_operations_per_message,
         max_serialized_operations_size_per_block: MAX_BLOCK_SIZE as usize,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
@@ -600,9 +666,16 @@ async fn launch(
         max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
         max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
         last_start_period: final_state.read().last_start_period,
+        trusted_block_ids: trusted_checkpoint
+            .map(|(_, block_id)| block_id)
+            .into_iter()
+            .chain(trusted_checkpoint_ancestors.iter().copied())
+            .collect(),
+        header_only: !SETTINGS.modules.execution,
         max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
         max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+        ban_list_file: SETTINGS.protocol.ban_list_file.clone(),
         listeners,
         keypair_file: SETTINGS.protocol.keypair_file.clone(),
         max_known_blocks_saved_size: SETTINGS.protocol.max_known_blocks_size,
@@ -650,6 +723,10 @@ async fn launch(
         peers_categories: SETTINGS.protocol.peers_categories.clone(),
         default_category_info: SETTINGS.protocol.default_category_info,
         version: *VERSION,
+        broadcast_enabled: SETTINGS.api.enable_broadcast || SETTINGS.webhooks.enabled,
+        broadcast_peer_events_channel_capacity: SETTINGS
+            .protocol
+            .broadcast_peer_events_channel_capacity,
     };
 
     let (protocol_controller, protocol_channels) =
@@ -664,6 +741,7 @@ async fn launch(
         max_discarded_blocks: SETTINGS.consensus.max_discarded_blocks,
         max_future_processing_blocks: SETTINGS.consensus.max_future_processing_blocks,
         max_dependency_blocks: SETTINGS.consensus.max_dependency_blocks,
+        max_orphan_headers: SETTINGS.consensus.max_orphan_headers,
         delta_f0: DELTA_F0,
         operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         periods_per_cycle: PERIODS_PER_CYCLE,
@@ -674,7 +752,7 @@ async fn launch(
         max_gas_per_block: MAX_GAS_PER_BLOCK,
         channel_size: CHANNEL_SIZE,
         bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
-        broadcast_enabled: SETTINGS.api.enable_broadcast,
+        broadcast_enabled: SETTINGS.api.enable_broadcast || SETTINGS.webhooks.enabled,
         broadcast_blocks_headers_channel_capacity: SETTINGS
             .consensus
             .broadcast_blocks_headers_channel_capacity,
@@ -682,11 +760,27 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_reorgs_channel_capacity: SETTINGS.consensus.broadcast_reorgs_channel_capacity,
+        broadcast_discarded_blocks_channel_capacity: SETTINGS
+            .consensus
+            .broadcast_discarded_blocks_channel_capacity,
+        header_verification_batch_size: SETTINGS.consensus.header_verification_batch_size,
         last_start_period: final_state.read().last_start_period,
+        trusted_checkpoint,
+        trusted_checkpoint_ancestors,
+        header_only: !SETTINGS.modules.execution,
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
             .force_keep_final_periods_without_ops,
     };
+    if let Some((slot, block_id)) = consensus_config.trusted_checkpoint {
+        info!(
+            "Trusted checkpoint {} at slot {}, with {} configured ancestors: the block and its ancestors are not validated",
+            block_id,
+            slot,
+            consensus_config.trusted_checkpoint_ancestors.len()
+        );
+    }
 
     let (consensus_event_sender, consensus_event_receiver) =
         MassaChannel::new("consensus_event".to_string(), Some(CHANNEL_SIZE));
@@ -705,6 +799,11 @@ async fn launch(
             consensus_config.broadcast_filled_blocks_channel_capacity,
         )
         .0,
+        reorg_sender: broadcast::channel(consensus_config.broadcast_reorgs_channel_capacity).0,
+        discarded_block_sender: broadcast::channel(
+            consensus_config.broadcast_discarded_blocks_channel_capacity,
+        )
+        .0,
     };
 
     let (consensus_controller, consensus_manager) = start_consensus_worker(
@@ -713,8 +812,10 @@ async fn launch(
         bootstrap_state.graph,
         shared_storage.clone(),
         metrics.clone(),
+        archive.clone(),
     );
 
+    let peer_event_sender = protocol_channels.peer_event_sender.clone();
     let (protocol_manager, keypair, node_id) = start_protocol_controller(
         protocol_config.clone(),
         selector_controller.clone(),
@@ -724,11 +825,11 @@ async fn launch(
         shared_storage.clone(),
         protocol_channels,
         mip_store.clone(),
-        metrics,
+        metrics.clone(),
     )
     .expect("could not start protocol controller");
 
-    // launch factory
+    // launch factory, unless block production is disabled on this node
     let factory_config = FactoryConfig {
         thread_count: THREAD_COUNT,
         genesis_timestamp: *GENESIS_TIMESTAMP,
@@ -748,33 +849,21 @@ async fn launch(
         protocol: protocol_controller.clone(),
         storage: shared_storage.clone(),
     };
-    let factory_manager = start_factory(
+    let factory_manager = launch_factory(
         factory_config,
         node_wallet.clone(),
         factory_channels,
         mip_store.clone(),
     );
 
-    let bootstrap_manager = bootstrap_config.listen_addr.map(|addr| {
-        let (waker, listener) = BootstrapTcpListener::new(&addr).unwrap_or_else(|_| {
-            panic!(
-                "{}",
-                format!("Could not bind to address: {}", addr).as_str()
-            )
-        });
-        let mut manager = start_bootstrap_server(
-            listener,
-            consensus_controller.clone(),
-            protocol_controller.clone(),
-            final_state.clone(),
-            bootstrap_config,
-            keypair.clone(),
-            *VERSION,
-        )
-        .expect("Could not start bootstrap server");
-        manager.set_listener_stopper(waker);
-        manager
-    });
+    // launch bootstrap server, unless bootstrap serving is disabled in this build or on this node
+    let bootstrap_manager = launch_bootstrap_server(
+        bootstrap_config,
+        consensus_controller.clone(),
+        protocol_controller.clone(),
+        final_state.clone(),
+        keypair.clone(),
+    );
 
     let api_config: APIConfig = APIConfig {
         bind_private: SETTINGS.api.bind_private,
@@ -782,7 +871,6 @@ async fn launch(
         bind_api: SETTINGS.api.bind_api,
         draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
         max_arguments: SETTINGS.api.max_arguments,
-        openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
         max_request_body_size: SETTINGS.api.max_request_body_size,
@@ -791,6 +879,9 @@ async fn launch(
         max_subscriptions_per_connection: SETTINGS.api.max_subscriptions_per_connection,
         max_log_length: SETTINGS.api.max_log_length,
         allow_hosts: SETTINGS.api.allow_hosts.clone(),
+        cors_allowed_origins: SETTINGS.api.cors_allowed_origins.clone(),
+        cors_allowed_headers: SETTINGS.api.cors_allowed_headers.clone(),
+        public_bearer_token: SETTINGS.api.public_bearer_token.clone(),
         batch_request_limit: SETTINGS.api.batch_request_limit,
         ping_interval: SETTINGS.api.ping_interval,
         enable_http: SETTINGS.api.enable_http,
@@ -806,6 +897,10 @@ async fn launch(
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
+        gas_estimation_max_gas: MAX_GAS_PER_BLOCK.min(SETTINGS.execution.max_read_only_gas),
+        max_gas_estimation_executions: SETTINGS.api.max_gas_estimation_executions,
+        gas_estimation_margin_percent: SETTINGS.api.gas_estimation_margin_percent,
+        sp_compilation_cost,
     };
 
     // spawn Massa API
@@ -831,7 +926,21 @@ async fn launch(
     let mut api_config = api_config.clone();
     api_config.enable_ws = false;
 
+    // Whether to dispatch events to webhooks
+    let webhook_dispatcher = SETTINGS.webhooks.enabled.then(|| {
+        WebhookDispatcher::start(
+            &SETTINGS.webhooks,
+            node_wallet.clone(),
+            selector_controller.clone(),
+            mip_store.clone(),
+            &consensus_channels,
+            &execution_channels,
+            &pool_channels,
+        )
+    });
+
     // Whether to spawn gRPC API
+    let grpc_drain_controller = DrainController::new();
     let grpc_handle = if SETTINGS.grpc.enabled {
         let grpc_config = GrpcConfig {
             enabled: SETTINGS.grpc.enabled,
@@ -841,6 +950,7 @@ async fn launch(
             enable_reflection: SETTINGS.grpc.enable_reflection,
             enable_mtls: SETTINGS.grpc.enable_mtls,
             bind: SETTINGS.grpc.bind,
+            private_bind: SETTINGS.grpc.private_bind,
             accept_compressed: SETTINGS.grpc.accept_compressed.clone(),
             send_compressed: SETTINGS.grpc.send_compressed.clone(),
             max_decoding_message_size: SETTINGS.grpc.max_decoding_message_size,
@@ -882,6 +992,13 @@ async fn launch(
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             max_block_ids_per_request: SETTINGS.grpc.max_block_ids_per_request,
             max_operation_ids_per_request: SETTINGS.grpc.max_operation_ids_per_request,
+            max_datastore_entries_per_request: SETTINGS.grpc.max_datastore_entries_per_request,
+            max_tracked_operations_per_stream: SETTINGS.grpc.max_tracked_operations_per_stream,
+            stream_backpressure_policy: SETTINGS.grpc.stream_backpressure_policy,
+            enable_peer_events_stream: SETTINGS.grpc.enable_peer_events_stream,
+            enable_access_log: SETTINGS.grpc.enable_access_log,
+            access_log_file: SETTINGS.grpc.access_log_file.clone(),
+            shutdown_drain_timeout: SETTINGS.grpc.shutdown_drain_timeout,
             server_certificate_path: SETTINGS.grpc.server_certificate_path.clone(),
             server_private_key_path: SETTINGS.grpc.server_private_key_path.clone(),
             client_certificate_authority_root_path: SETTINGS
@@ -898,11 +1015,14 @@ async fn launch(
             pool_channels,
             pool_command_sender: pool_controller.clone(),
             protocol_command_sender: protocol_controller.clone(),
+            peer_event_sender: peer_event_sender.clone(),
             selector_controller: selector_controller.clone(),
             storage: shared_storage.clone(),
             grpc_config: grpc_config.clone(),
             version: *VERSION,
             mip_store: mip_store.clone(),
+            drain_controller: grpc_drain_controller.clone(),
+            metrics,
         };
 
         // HACK maybe should remove timeout later
@@ -941,8 +1061,10 @@ async fn launch(
     let (api_private, api_private_stop_rx) = API::<Private>::new(
         protocol_controller.clone(),
         execution_controller.clone(),
+        pool_controller.clone(),
         api_config.clone(),
         node_wallet,
+        grpc_handle.as_ref().map(|_| grpc_drain_controller),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -966,6 +1088,8 @@ async fn launch(
         node_id,
         shared_storage.clone(),
         mip_store.clone(),
+        bootstrap_stats,
+        archive.clone(),
     );
     let api_public_handle = api_public
         .serve(&SETTINGS.api.bind_public, &api_config)
@@ -1019,6 +1143,7 @@ async fn launch(
         api_public_handle,
         api_handle,
         grpc_handle,
+        webhook_dispatcher,
     )
 }
 
@@ -1029,7 +1154,103 @@ struct Managers {
     selector_manager: Box<dyn SelectorManager>,
     pool_manager: Box<dyn PoolManager>,
     protocol_manager: Box<dyn ProtocolManager>,
-    factory_manager: Box<dyn FactoryManager>,
+    factory_manager: Option<Box<dyn FactoryManager>>,
+    webhook_dispatcher: Option<WebhookDispatcher>,
+}
+
+/// Launches the factory, unless block production is disabled on this node
+#[cfg(feature = "factory")]
+fn launch_factory(
+    factory_config: FactoryConfig,
+    node_wallet: Arc<RwLock<Wallet>>,
+    factory_channels: FactoryChannels,
+    mip_store: MipStore,
+) -> Option<Box<dyn FactoryManager>> {
+    if SETTINGS.modules.factory {
+        Some(start_factory(
+            factory_config,
+            node_wallet,
+            factory_channels,
+            mip_store,
+        ))
+    } else {
+        info!("Factory disabled: this node will not produce blocks nor endorsements");
+        None
+    }
+}
+
+/// Block production is left out of the builds without the `factory` feature
+#[cfg(not(feature = "factory"))]
+fn launch_factory(
+    _factory_config: FactoryConfig,
+    _node_wallet: Arc<RwLock<Wallet>>,
+    _factory_channels: FactoryChannels,
+    _mip_store: MipStore,
+) -> Option<Box<dyn FactoryManager>> {
+    if SETTINGS.modules.factory {
+        warn!("Factory disabled: this node was built without the `factory` feature");
+    }
+    None
+}
+
+/// Launches the bootstrap server, unless bootstrap serving is disabled on this node
+#[cfg(feature = "bootstrap_serving")]
+fn launch_bootstrap_server(
+    bootstrap_config: BootstrapConfig,
+    consensus_controller: Box<dyn ConsensusController>,
+    protocol_controller: Box<dyn ProtocolController>,
+    final_state: Arc<RwLock<FinalState>>,
+    keypair: KeyPair,
+) -> Option<BootstrapManager> {
+    let addr = bootstrap_config.listen_addr?;
+    if !SETTINGS.modules.bootstrap_server {
+        info!("Bootstrap server disabled: this node will not serve bootstrap to other nodes");
+        return None;
+    }
+    let (waker, listener) = BootstrapTcpListener::new(&addr).unwrap_or_else(|_| {
+        panic!(
+            "{}",
+            format!("Could not bind to address: {}", addr).as_str()
+        )
+    });
+    let mut manager = start_bootstrap_server(
+        listener,
+        consensus_controller,
+        protocol_controller,
+        final_state,
+        bootstrap_config,
+        keypair,
+        *VERSION,
+    )
+    .expect("Could not start bootstrap server");
+    manager.set_listener_stopper(waker);
+    Some(manager)
+}
+
+/// Bootstrap serving is left out of the builds without the `bootstrap_serving` feature
+#[cfg(not(feature = "bootstrap_serving"))]
+fn launch_bootstrap_server(
+    bootstrap_config: BootstrapConfig,
+    _consensus_controller: Box<dyn ConsensusController>,
+    _protocol_controller: Box<dyn ProtocolController>,
+    _final_state: Arc<RwLock<FinalState>>,
+    _keypair: KeyPair,
+) -> Option<BootstrapManager> {
+    if bootstrap_config.listen_addr.is_some() && SETTINGS.modules.bootstrap_server {
+        warn!("Bootstrap server disabled: this node was built without the `bootstrap_serving` feature");
+    }
+    None
+}
+
+/// Bootstrap manager of the builds without the `bootstrap_serving` feature, which never run a bootstrap server
+#[cfg(not(feature = "bootstrap_serving"))]
+enum BootstrapManager {}
+
+#[cfg(not(feature = "bootstrap_serving"))]
+impl BootstrapManager {
+    fn stop(self) -> Result<(), BootstrapError> {
+        match self {}
+    }
 }
 
 async fn stop(
@@ -1041,7 +1262,8 @@ async fn stop(
         mut selector_manager,
         mut pool_manager,
         mut protocol_manager,
-        mut factory_manager,
+        factory_manager,
+        webhook_dispatcher,
     }: Managers,
     api_private_handle: StopHandle,
     api_public_handle: StopHandle,
@@ -1055,11 +1277,16 @@ async fn stop(
             .expect("bootstrap server shutdown failed")
     }
 
+    // stop webhooks dispatcher
+    if let Some(webhook_dispatcher) = webhook_dispatcher {
+        webhook_dispatcher.stop();
+    }
+
     info!("Start stopping API's: gRPC, EXPERIMENTAL, PUBLIC, PRIVATE");
 
-    // stop Massa gRPC API
+    // drain then stop Massa gRPC API
     if let Some(handle) = grpc_handle {
-        handle.stop();
+        handle.shutdown().await;
     }
 
     // stop Massa API
@@ -1075,7 +1302,9 @@ async fn stop(
     info!("API | PRIVATE JsonRPC | stopped");
 
     // stop factory
-    factory_manager.stop();
+    if let Some(mut factory_manager) = factory_manager {
+        factory_manager.stop();
+    }
 
     // stop protocol controller
     protocol_manager.stop();
@@ -1111,6 +1340,34 @@ struct Args {
     #[structopt(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Start from a final state snapshot written by `snapshot export`, then bootstrap only the changes since it
+    #[structopt(long = "bootstrap-from-file", parse(from_os_str))]
+    bootstrap_from_file: Option<PathBuf>,
+
+    /// Trusted final block: the block and its ancestors are not validated, and no other block is accepted at its slot
+    #[structopt(long = "checkpoint-block-id", requires = "checkpoint_slot")]
+    checkpoint_block_id: Option<BlockId>,
+
+    /// Slot of the trusted final block, as `period,thread`
+    #[structopt(long = "checkpoint-slot", requires = "checkpoint_block_id")]
+    checkpoint_slot: Option<Slot>,
+
+    /// JSON file listing the ids of the ancestors of the trusted final block, trusted before they are received
+    #[structopt(long = "checkpoint-chain", requires = "checkpoint_block_id")]
+    checkpoint_chain: Option<PathBuf>,
+
+    /// Hash of the final state at `trusted-state-slot`, known out-of-band, that the bootstrapped final state must match,
+    /// at the bootstrapped slot or an earlier one still in its hash history
+    #[structopt(long = "trusted-state-hash", requires = "trusted_state_slot")]
+    trusted_state_hash: Option<Hash>,
+
+    /// Slot of the trusted final state hash, as `period,thread`
+    #[structopt(long = "trusted-state-slot", requires = "trusted_state_hash")]
+    trusted_state_slot: Option<Slot>,
+
+    #[structopt(subcommand)]
+    command: Option<NodeCommand>,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[structopt(
@@ -1133,9 +1390,468 @@ struct Args {
     dl_interval: u64,
 }
 
+#[derive(StructOpt)]
+enum NodeCommand {
+    /// Final state snapshots
+    Snapshot(SnapshotCommand),
+    /// Replay the final slots recorded by the stopped node on top of a snapshot, and report the first slot
+    /// leading to another final state than the recorded one. Requires the record of the final slots (`final_slots_record_path`)
+    Replay {
+        /// Snapshot of the final state to replay the slots from, exported by `snapshot export`
+        #[structopt(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// Last slot to replay, as `period,thread`
+        #[structopt(long = "to")]
+        to: Slot,
+    },
+}
+
+#[derive(StructOpt)]
+enum SnapshotCommand {
+    /// Export the final state of the stopped node to a file, to start other nodes from it with `--bootstrap-from-file`
+    Export {
+        /// Snapshot file to write
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+        /// Secret key signing the snapshot, to publish it for the nodes downloading it from their `snapshot_url`.
+        /// The signature is written next to the snapshot, with the `.sig` extension
+        #[structopt(long = "sign-with")]
+        sign_with: Option<KeyPair>,
+    },
+}
+
+/// Logs the progress reports of the bootstrap and exports them as metrics, until the end of the bootstrap.
+/// Returns the last report, none if the node did not bootstrap.
+fn spawn_bootstrap_progress_monitor(
+    receiver: crossbeam_channel::Receiver<BootstrapStats>,
+    metrics: MassaMetrics,
+) -> std::thread::JoinHandle<Option<BootstrapStats>> {
+    std::thread::Builder::new()
+        .name("bootstrap-progress".to_string())
+        .spawn(move || {
+            let mut last: Option<BootstrapStats> = None;
+            let mut last_log: Option<std::time::Instant> = None;
+            // the channel is disconnected when the bootstrap ends
+            for stats in receiver {
+                metrics.set_bootstrap_progress(
+                    stats.phase.index(),
+                    stats.received_bytes,
+                    stats.streamed_slot.map(|slot| slot.period),
+                    stats.eta.map(|eta| eta.to_duration()),
+                );
+                let phase_changed = last.as_ref().map(|last| last.phase) != Some(stats.phase);
+                if phase_changed
+                    || last_log.map_or(true, |log| log.elapsed() >= BOOTSTRAP_PROGRESS_LOG_INTERVAL)
+                {
+                    last_log = Some(std::time::Instant::now());
+                    info!(
+                        "Bootstrap progress: {:?}{}, {} bytes received in {} s{}{}",
+                        stats.phase,
+                        stats
+                            .server
+                            .map(|server| format!(" from {}", server))
+                            .unwrap_or_default(),
+                        stats.received_bytes,
+                        stats.elapsed.to_millis() / 1000,
+                        stats
+                            .streamed_slot
+                            .map(|slot| format!(", state at slot {}", slot))
+                            .unwrap_or_default(),
+                        stats
+                            .eta
+                            .map(|eta| format!(", about {} s left", eta.to_millis() / 1000))
+                            .unwrap_or_default(),
+                    );
+                }
+                last = Some(stats);
+            }
+            last
+        })
+        .expect("failed to spawn the bootstrap progress monitor thread")
+}
+
+/// Final state configuration, from the settings and constants
+fn final_state_config() -> FinalStateConfig {
+    let ledger_config = LedgerConfig {
+        thread_count: THREAD_COUNT,
+        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
+        disk_ledger_path: SETTINGS.ledger.disk_ledger_path.clone(),
+        max_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+    };
+    let async_pool_config = AsyncPoolConfig {
+        max_length: MAX_ASYNC_POOL_LENGTH,
+        thread_count: THREAD_COUNT,
+        max_async_message_data: MAX_ASYNC_MESSAGE_DATA,
+        max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
+    };
+    let pos_config = PoSConfig {
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        thread_count: THREAD_COUNT,
+        cycle_history_length: POS_SAVED_CYCLES,
+        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+        max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+    };
+    let executed_ops_config = ExecutedOpsConfig {
+        thread_count: THREAD_COUNT,
+    };
+    let executed_denunciations_config = ExecutedDenunciationsConfig {
+        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+    };
+    let final_state_config = FinalStateConfig {
+        ledger_config: ledger_config.clone(),
+        async_pool_config,
+        pos_config,
+        executed_ops_config,
+        executed_denunciations_config,
+        final_history_length: SETTINGS.ledger.final_history_length,
+        thread_count: THREAD_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        initial_seed_string: INITIAL_DRAW_SEED.into(),
+        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        t0: T0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+    };
+    final_state_config
+}
+
+/// Selector configuration, from the constants
+fn selector_config() -> SelectorConfig {
+    SelectorConfig {
+        max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
+        channel_size: CHANNEL_SIZE,
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+    }
+}
+
+/// Store of the MIPs known by this version of the node
+fn mip_store() -> MipStore {
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        counters_max: MIP_STORE_STATS_COUNTERS_MAX,
+    };
+    let mip_0001_start = MassaTime::from_utc_ymd_hms(2023, 6, 14, 15, 0, 0).unwrap();
+    let mip_0001_timeout = MassaTime::from_utc_ymd_hms(2023, 6, 14, 16, 0, 0).unwrap();
+    let mip_0001_defined_start = MassaTime::from_utc_ymd_hms(2023, 2, 14, 14, 30, 0).unwrap();
+    let mip_0002_start = MassaTime::from_utc_ymd_hms(2023, 6, 16, 13, 0, 0).unwrap();
+    let mip_0002_timeout = MassaTime::from_utc_ymd_hms(2023, 6, 19, 14, 0, 0).unwrap();
+    let mip_0002_defined_start = MassaTime::from_utc_ymd_hms(2023, 6, 16, 10, 0, 0).unwrap();
+    let mip_0003_start = MassaTime::from_utc_ymd_hms(2023, 9, 4, 13, 0, 0).unwrap();
+    let mip_0003_timeout = MassaTime::from_utc_ymd_hms(2023, 9, 11, 14, 0, 0).unwrap();
+    let mip_0003_defined_start = MassaTime::from_utc_ymd_hms(2023, 9, 4, 10, 0, 0).unwrap();
+    let mip_0004_start = MassaTime::from_utc_ymd_hms(2023, 10, 2, 13, 0, 0).unwrap();
+    let mip_0004_timeout = MassaTime::from_utc_ymd_hms(2023, 10, 9, 14, 0, 0).unwrap();
+    let mip_0004_defined_start = MassaTime::from_utc_ymd_hms(2023, 10, 2, 10, 0, 0).unwrap();
+    let mip_0005_start = MassaTime::from_utc_ymd_hms(2023, 11, 6, 13, 0, 0).unwrap();
+    let mip_0005_timeout = MassaTime::from_utc_ymd_hms(2023, 11, 13, 14, 0, 0).unwrap();
+    let mip_0005_defined_start = MassaTime::from_utc_ymd_hms(2023, 11, 6, 10, 0, 0).unwrap();
+    let mip_list_1: [(MipInfo, MipState); 5] = [
+        (
+            MipInfo {
+                name: "MIP-0001".to_string(),
+                version: 1,
+                components: BTreeMap::from([
+                    (MipComponent::Address, 1),
+                    (MipComponent::KeyPair, 1),
+                ]),
+                start: mip_0001_start,
+                timeout: mip_0001_timeout,
+                activation_delay: MassaTime::from_millis(100),
+            },
+            MipState::new(mip_0001_defined_start),
+        ),
+        (
+            MipInfo {
+                name: "MIP-0002".to_string(),
+                version: 2,
+                components: BTreeMap::from([(MipComponent::FinalStateHashKind, 1)]),
+                start: mip_0002_start,
+                timeout: mip_0002_timeout,
+                activation_delay: T0
+                    .saturating_mul(PERIODS_PER_CYCLE.saturating_add(1))
+                    .saturating_mul(40),
+            },
+            MipState::new(mip_0002_defined_start),
+        ),
+        (
+            MipInfo {
+                name: "MIP-0003".to_string(),
+                version: 3,
+                // call depth limit and reentrancy lock
+                components: BTreeMap::from([(MipComponent::VM, 1)]),
+                start: mip_0003_start,
+                timeout: mip_0003_timeout,
+                activation_delay: T0
+                    .saturating_mul(PERIODS_PER_CYCLE.saturating_add(1))
+                    .saturating_mul(40),
+            },
+            MipState::new(mip_0003_defined_start),
+        ),
+        (
+            MipInfo {
+                name: "MIP-0004".to_string(),
+                version: 4,
+                // stake delegation operation, delegations of the PoS state and draws of the operators
+                components: BTreeMap::from([(MipComponent::StakeDelegation, 1)]),
+                start: mip_0004_start,
+                timeout: mip_0004_timeout,
+                activation_delay: T0
+                    .saturating_mul(PERIODS_PER_CYCLE.saturating_add(1))
+                    .saturating_mul(40),
+            },
+            MipState::new(mip_0004_defined_start),
+        ),
+        (
+            MipInfo {
+                name: "MIP-0005".to_string(),
+                version: 5,
+                // gas costs schedule of `GAS_COSTS_SCHEDULES`
+                components: BTreeMap::from([(MipComponent::GasCosts, 1)]),
+                start: mip_0005_start,
+                timeout: mip_0005_timeout,
+                activation_delay: T0
+                    .saturating_mul(PERIODS_PER_CYCLE.saturating_add(1))
+                    .saturating_mul(40),
+            },
+            MipState::new(mip_0005_defined_start),
+        ),
+    ];
+    MipStore::try_from((mip_list_1, mip_stats_config)).expect("mip store creation failed")
+}
+
+/// Execution configuration, from the settings and constants
+fn execution_config(last_start_period: u64) -> ExecutionConfig {
+    // Storage costs constants
+    let storage_costs_constants = StorageCostsConstants {
+        ledger_cost_per_byte: LEDGER_COST_PER_BYTE,
+        ledger_entry_base_cost: LEDGER_ENTRY_BASE_COST,
+        ledger_entry_datastore_base_cost: LEDGER_COST_PER_BYTE
+            .checked_mul_u64(LEDGER_ENTRY_DATASTORE_BASE_SIZE as u64)
+            .expect("Overflow when creating constant ledger_entry_datastore_base_size"),
+    };
+
+    let (gas_costs, gas_costs_version) = load_gas_costs(
+        &SETTINGS.execution.abi_gas_costs_file,
+        &SETTINGS.execution.wasm_gas_costs_file,
+    );
+    ExecutionConfig {
+        max_final_events: SETTINGS.execution.max_final_events,
+        readonly_queue_length: SETTINGS.execution.readonly_queue_length,
+        cursor_delay: SETTINGS.execution.cursor_delay,
+        max_async_gas: MAX_ASYNC_GAS,
+        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        roll_price: ROLL_PRICE,
+        thread_count: THREAD_COUNT,
+        t0: T0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        block_reward: BLOCK_REWARD,
+        endorsement_count: ENDORSEMENT_COUNT as u64,
+        operation_validity_period: OPERATION_VALIDITY_PERIODS,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        stats_time_window_duration: SETTINGS.execution.stats_time_window_duration,
+        max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
+        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+        max_bytecode_size: MAX_BYTECODE_LENGTH,
+        max_call_depth: SETTINGS.execution.max_call_depth,
+        max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
+        storage_costs_constants,
+        max_read_only_gas: SETTINGS.execution.max_read_only_gas,
+        initial_vesting_path: SETTINGS.execution.initial_vesting_path.clone(),
+        gas_costs,
+        gas_costs_version,
+        gas_costs_schedules: GAS_COSTS_SCHEDULES
+            .iter()
+            .map(|schedule| (schedule.version, load_gas_costs_schedule(schedule)))
+            .collect(),
+        last_start_period,
+        hd_cache_path: SETTINGS.execution.hd_cache_path.clone(),
+        lru_cache_size: SETTINGS.execution.lru_cache_size,
+        hd_cache_size: SETTINGS.execution.hd_cache_size,
+        hd_cache_max_byte_size: SETTINGS.execution.hd_cache_max_byte_size,
+        snip_amount: SETTINGS.execution.snip_amount,
+        operation_index_path: SETTINGS.execution.operation_index_path.clone(),
+        event_store_path: SETTINGS.execution.event_store_path.clone(),
+        event_store_retention_periods: SETTINGS.execution.event_store_retention_periods,
+        final_slots_record_path: SETTINGS.execution.final_slots_record_path.clone(),
+        state_changes_export_path: SETTINGS.execution.state_changes_export_path.clone(),
+        roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
+        denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        broadcast_enabled: SETTINGS.api.enable_broadcast || SETTINGS.webhooks.enabled,
+        broadcast_slot_execution_output_channel_capacity: SETTINGS
+            .execution
+            .broadcast_slot_execution_output_channel_capacity,
+        max_operation_traces: SETTINGS.execution.max_operation_traces,
+        parallel_execution_threads: SETTINGS.execution.parallel_execution_threads,
+    }
+}
+
+/// Load the gas costs from their abi and wasm files, with the hash of the files identifying them
+fn load_gas_costs(abi_gas_costs_file: &Path, wasm_gas_costs_file: &Path) -> (GasCosts, Hash) {
+    let gas_costs = GasCosts::new(
+        abi_gas_costs_file.to_path_buf(),
+        wasm_gas_costs_file.to_path_buf(),
+    )
+    .expect("Failed to load gas costs");
+    let gas_costs_version = Hash::compute_from(
+        &[
+            std::fs::read(abi_gas_costs_file).expect("Failed to read abi gas costs"),
+            std::fs::read(wasm_gas_costs_file).expect("Failed to read wasm gas costs"),
+        ]
+        .concat(),
+    );
+    (gas_costs, gas_costs_version)
+}
+
+/// Load a gas costs schedule built into the node, refusing files differing from the built-in content
+fn load_gas_costs_schedule(schedule: &BuiltinGasCostsSchedule) -> (GasCosts, Hash) {
+    for (file, content) in [schedule.abi_gas_costs, schedule.wasm_gas_costs] {
+        if std::fs::read(file).expect("Failed to read gas costs schedule") != content {
+            panic!(
+                "gas costs schedule file {} differs from the one built into the node",
+                file
+            );
+        }
+    }
+    load_gas_costs(
+        Path::new(schedule.abi_gas_costs.0),
+        Path::new(schedule.wasm_gas_costs.0),
+    )
+}
+
+/// Export the final state kept on disk to a snapshot file.
+/// The node must be stopped, since the database cannot be opened twice.
+/// The snapshot is signed with `keypair`, if any, for the nodes downloading it.
+fn export_snapshot(path: &Path, keypair: Option<&KeyPair>) -> anyhow::Result<()> {
+    if !SETTINGS.ledger.disk_ledger_path.exists() {
+        anyhow::bail!(
+            "no final state found at {}",
+            SETTINGS.ledger.disk_ledger_path.display()
+        );
+    }
+    let db = MassaDB::new(MassaDBConfig {
+        path: SETTINGS.ledger.disk_ledger_path.clone(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        thread_count: THREAD_COUNT,
+    });
+    db.export_snapshot(path)?;
+    let slot = db.get_change_id()?;
+    println!(
+        "Final state at slot {} exported to {}",
+        slot,
+        path.display()
+    );
+    if let Some(keypair) = keypair {
+        SnapshotSignature::new(slot, db.get_db_hash(), keypair)?.write(path)?;
+        println!(
+            "Snapshot signed by {} in {}",
+            keypair.get_public_key(),
+            snapshot_source::signature_path(path).display()
+        );
+    }
+    Ok(())
+}
+
+/// Replay the recorded final slots on top of a snapshot, up to `end_slot`, and report the first divergence.
+/// The snapshot is imported next to the final state of the node, which is left untouched.
+fn replay(snapshot: &Path, end_slot: Slot) -> anyhow::Result<()> {
+    let Some(record_path) = SETTINGS.execution.final_slots_record_path.clone() else {
+        anyhow::bail!("the final slots are not recorded, set `final_slots_record_path` in the execution settings");
+    };
+    let replay_db_path = SETTINGS
+        .ledger
+        .disk_ledger_path
+        .with_file_name("replay_final_state");
+    if replay_db_path.exists() {
+        std::fs::remove_dir_all(&replay_db_path)?;
+    }
+    let db = Arc::new(RwLock::new(MassaDB::new(MassaDBConfig {
+        path: replay_db_path.clone(),
+        max_history_length: SETTINGS.ledger.final_history_length,
+        max_new_elements: MAX_BOOTSTRAPPED_NEW_ELEMENTS as usize,
+        thread_count: THREAD_COUNT,
+    })));
+    let snapshot_slot = db.write().import_snapshot(snapshot)?;
+    println!(
+        "Final state imported from {} at slot {}",
+        snapshot.display(),
+        snapshot_slot
+    );
+
+    let final_state_config = final_state_config();
+    let ledger = FinalLedger::new(final_state_config.ledger_config.clone(), db.clone());
+    let (mut selector_manager, selector_controller) = start_selector_worker(selector_config())?;
+    let mip_store = mip_store();
+    let final_state = Arc::new(RwLock::new(FinalState::new(
+        db,
+        final_state_config,
+        Box::new(ledger),
+        selector_controller.clone(),
+        mip_store.clone(),
+        false,
+    )?));
+    final_state.write().recompute_caches();
+    final_state.write().compute_initial_draws()?;
+
+    let last_start_period = final_state.read().last_start_period;
+    let report = replay_final_slots(
+        execution_config(last_start_period),
+        final_state,
+        selector_controller,
+        mip_store,
+        record_path,
+        end_slot,
+    );
+    selector_manager.stop();
+    std::fs::remove_dir_all(&replay_db_path)?;
+
+    println!("{}", replay_summary(&report?, end_slot));
+    Ok(())
+}
+
+/// Describes the first divergence found by a replay up to `end_slot`, if any
+fn replay_summary(report: &ReplayReport, end_slot: Slot) -> String {
+    match &report.divergence {
+        Some(divergence) => format!(
+            "Divergence at slot {} (block {}) after {} replayed slots: recorded state hash {}, replayed state hash {}",
+            divergence.slot,
+            divergence
+                .block_id
+                .map_or_else(|| "none, miss".to_string(), |block_id| block_id.to_string()),
+            report.replayed_slots,
+            divergence.recorded_state_hash,
+            divergence.replayed_state_hash
+        ),
+        None => format!(
+            "{} slots replayed up to slot {}, no divergence",
+            report.replayed_slots, end_slot
+        ),
+    }
+}
+
 /// Load wallet, asking for passwords if necessary
-fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLock<Wallet>>> {
-    let password = if path.is_file() {
+/// The password is read from `backend` if it holds one, and stored there otherwise.
+fn load_wallet(
+    password: Option<String>,
+    path: &Path,
+    backend: PasswordBackend,
+) -> anyhow::Result<Arc<RwLock<Wallet>>> {
+    let stored_password = match password {
+        Some(_) => None,
+        None => load_password(backend, path)?,
+    };
+    let password = if let Some(stored_password) = stored_password.clone() {
+        stored_password
+    } else if path.is_file() {
         password.unwrap_or_else(|| {
             Password::new()
                 .with_prompt("Enter staking keys file password")
@@ -1151,14 +1867,25 @@ fn load_wallet(password: Option<String>, path: &Path) -> anyhow::Result<Arc<RwLo
                 .expect("IO error: Password reading failed, staking keys file couldn't be created")
         })
     };
-    Ok(Arc::new(RwLock::new(Wallet::new(
-        PathBuf::from(path),
-        password,
-    )?)))
+    let wallet = Wallet::new(PathBuf::from(path), password.clone())?;
+    if stored_password.is_none() && backend != PasswordBackend::None {
+        if let Err(e) = store_password(backend, path, &password) {
+            warn!("failed to store the staking wallet password: {}", e);
+        }
+    }
+    Ok(Arc::new(RwLock::new(wallet)))
 }
 
 #[paw::main]
 fn main(args: Args) -> anyhow::Result<()> {
+    match &args.command {
+        Some(NodeCommand::Snapshot(SnapshotCommand::Export { file, sign_with })) => {
+            return export_snapshot(file, sign_with.as_ref());
+        }
+        Some(NodeCommand::Replay { snapshot, to }) => return replay(snapshot, *to),
+        None => {}
+    }
+
     let tokio_rt = tokio::runtime::Builder::new_multi_thread()
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
@@ -1175,24 +1902,47 @@ fn main(args: Args) -> anyhow::Result<()> {
 async fn run(args: Args) -> anyhow::Result<()> {
     let mut cur_args = args;
     use tracing_subscriber::prelude::*;
+    let level_filter = match SETTINGS.logging.level {
+        4 => LevelFilter::TRACE,
+        3 => LevelFilter::DEBUG,
+        2 => LevelFilter::INFO,
+        1 => LevelFilter::WARN,
+        _ => LevelFilter::ERROR,
+    };
     // spawn the console server in the background, returning a `Layer`:
     let tracing_layer = tracing_subscriber::fmt::layer()
-        .with_filter(match SETTINGS.logging.level {
-            4 => LevelFilter::TRACE,
-            3 => LevelFilter::DEBUG,
-            2 => LevelFilter::INFO,
-            1 => LevelFilter::WARN,
-            _ => LevelFilter::ERROR,
-        })
+        .with_filter(level_filter)
         .with_filter(filter_fn(|metadata| {
             metadata.target().starts_with("massa") // ignore non-massa logs
         }));
+    // keep the last log lines for the crash bundles
+    let crash_reporter = SETTINGS.crash_dump.enabled.then(|| {
+        CrashReporter::new(
+            SETTINGS.crash_dump.directory.clone(),
+            SETTINGS.crash_dump.log_lines,
+        )
+    });
+    let crash_dump_layer = crash_reporter.as_ref().map(|crash_reporter| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(crash_reporter.log_tail())
+            .with_filter(level_filter)
+            .with_filter(filter_fn(|metadata| {
+                metadata.target().starts_with("massa") // ignore non-massa logs
+            }))
+    });
     // build a `Subscriber` by combining layers with a `tracing_subscriber::Registry`:
     tracing_subscriber::registry()
         // add the console layer to the subscriber or default layers...
         .with(tracing_layer)
+        .with(crash_dump_layer)
         .init();
 
+    // Write a crash bundle when a panic occurs, before running the default handler
+    if let Some(crash_reporter) = &crash_reporter {
+        crash_reporter.install_panic_hook();
+    }
+
     // Setup panic handlers,
     // and when a panic occurs,
     // run default handler,
@@ -1207,6 +1957,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
     let node_wallet = load_wallet(
         cur_args.password.clone(),
         &SETTINGS.factory.staking_wallet_path,
+        SETTINGS.factory.staking_wallet_password_backend,
     )?;
 
     // interrupt signal listener
@@ -1239,7 +1990,14 @@ async fn run(args: Args) -> anyhow::Result<()> {
             api_public_handle,
             api_handle,
             grpc_handle,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+            webhook_dispatcher,
+        ) = launch(
+            &cur_args,
+            node_wallet.clone(),
+            Arc::clone(&sig_int_toggled),
+            crash_reporter.as_ref(),
+        )
+        .await;
 
         // interrupt signal listener
         let (tx, rx) = crossbeam_channel::bounded(1);
@@ -1302,6 +2060,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
                 pool_manager,
                 protocol_manager,
                 factory_manager,
+                webhook_dispatcher,
             },
             api_private_handle,
             api_public_handle,
@@ -1315,7 +2074,58 @@ async fn run(args: Args) -> anyhow::Result<()> {
         }
         // If we restart because of a desync, then we do not want to restart from a snapshot
         cur_args.restart_from_snapshot_at_period = None;
+        // nor import the snapshot again over the bootstrapped final state
+        cur_args.bootstrap_from_file = None;
+        // and the state is bootstrapped again at a later slot than the trusted hash
+        cur_args.trusted_state_hash = None;
+        cur_args.trusted_state_slot = None;
         interrupt_signal_listener.abort();
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_execution_worker::ReplayDivergence;
+
+    #[test]
+    fn test_replay_command() {
+        let args =
+            Args::from_iter_safe(["massa-node", "replay", "snapshot.bin", "--to", "12,3"]).unwrap();
+        match args.command {
+            Some(NodeCommand::Replay { snapshot, to }) => {
+                assert_eq!(snapshot, PathBuf::from("snapshot.bin"));
+                assert_eq!(to, Slot::new(12, 3));
+            }
+            _ => panic!("expected the replay command"),
+        }
+        assert!(Args::from_iter_safe(["massa-node", "replay", "snapshot.bin"]).is_err());
+
+        let end_slot = Slot::new(12, 3);
+        let report = ReplayReport {
+            replayed_slots: 20,
+            divergence: None,
+        };
+        assert_eq!(
+            replay_summary(&report, end_slot),
+            "20 slots replayed up to slot (period: 12, thread: 3), no divergence"
+        );
+
+        // the first divergence is reported with the tampered slot
+        let report = ReplayReport {
+            replayed_slots: 7,
+            divergence: Some(ReplayDivergence {
+                slot: Slot::new(11, 2),
+                block_id: None,
+                recorded_state_hash: Hash::compute_from(b"tampered final state"),
+                replayed_state_hash: Hash::compute_from(b"final state"),
+            }),
+        };
+        let summary = replay_summary(&report, end_slot);
+        assert!(summary.starts_with(
+            "Divergence at slot (period: 11, thread: 2) (block none, miss) after 7 replayed slots"
+        ));
+        assert!(summary.contains(&Hash::compute_from(b"tampered final state").to_string()));
+    }
+}
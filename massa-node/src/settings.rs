@@ -3,10 +3,15 @@
 //! Build here the default node settings from the configuration file toml
 use std::{collections::HashMap, path::PathBuf};
 
+use crate::webhooks::WebhookEventKind;
 use massa_bootstrap::IpType;
-use massa_models::{config::build_massa_settings, node::NodeId};
+use massa_grpc::config::StreamBackpressurePolicy;
+use massa_models::{address::Address, config::build_massa_settings, node::NodeId};
+use massa_pool_exports::OperationEvictionPolicy;
 use massa_protocol_exports::PeerCategoryInfo;
+use massa_signature::PublicKey;
 use massa_time::MassaTime;
+use massa_wallet::PasswordBackend;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
 
@@ -32,9 +37,26 @@ pub struct ExecutionSettings {
     pub hd_cache_path: PathBuf,
     pub lru_cache_size: u32,
     pub hd_cache_size: usize,
+    pub hd_cache_max_byte_size: u64,
     pub snip_amount: usize,
+    /// path to the index of the final operations by address, none to disable it
+    pub operation_index_path: Option<PathBuf>,
+    /// path to the persistent store of the final events, none to keep them in memory (see `max_final_events`)
+    pub event_store_path: Option<PathBuf>,
+    /// number of periods the final events are kept for in the persistent store, 0 to keep them forever
+    pub event_store_retention_periods: u64,
+    /// path of the record of the final slots replayed by the `replay` command, none to disable it
+    pub final_slots_record_path: Option<PathBuf>,
+    /// path of the file the state changes of the final slots are appended to, none to disable their export
+    pub state_changes_export_path: Option<PathBuf>,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// max number of operation traces kept, 0 to disable the tracing of the executed operations
+    pub max_operation_traces: usize,
+    /// max number of threads executing the independent operations of a block in parallel, 0 or 1 to execute them serially
+    pub parallel_execution_threads: usize,
+    /// max depth of nested smart contract calls
+    pub max_call_depth: u16,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -67,11 +89,41 @@ pub struct BootstrapSettings {
     pub max_clock_delta: MassaTime,
     pub cache_duration: MassaTime,
     pub max_simultaneous_bootstraps: u32,
+    /// Max simultaneous bootstraps from a given IP, queued ones included
+    pub max_sessions_per_ip: u32,
+    /// Max number of connections waiting for a bootstrap slot
+    pub max_queued_sessions: u32,
+    /// Max time a connection waits for a bootstrap slot
+    pub queue_timeout: MassaTime,
+    /// Minimum rate at which a client must read the bootstrap data, in bytes per second
+    pub min_session_bytes_per_second: f64,
     pub per_ip_min_interval: MassaTime,
     pub ip_list_max_size: usize,
     pub max_bytes_read_write: f64,
+    /// Upload limit of all the bootstrap connections together, in bytes per second
+    pub max_total_upload_bytes_per_second: f64,
+    /// Download limit of all the bootstrap connections together, in bytes per second
+    pub max_total_download_bytes_per_second: f64,
+    /// Compress the state and consensus parts with zstd when the other side supports it
+    pub stream_compression: bool,
     /// Allocated time with which to manage the bootstrap process
     pub bootstrap_timeout: MassaTime,
+    /// Only fetch the final state changes since the last local slot when restarting
+    pub differential_sync: bool,
+    /// File keeping the progress of an ongoing bootstrap, to resume it after an interruption
+    pub checkpoint_path: PathBuf,
+    /// Number of bootstrap servers the state is downloaded from at once
+    pub parallel_download_servers: u32,
+    /// Number of other bootstrap servers that must attest the hash of the bootstrapped final state
+    pub state_attestations: u32,
+    /// Number of times the bootstrap servers are asked to attest the bootstrapped final state before the bootstrap fails
+    pub state_attestation_attempts: u32,
+    /// URL of a signed final state snapshot to start from instead of downloading the whole state, empty to disable
+    pub snapshot_url: String,
+    /// Keys trusted to sign the snapshots downloaded from `snapshot_url`
+    pub snapshot_trusted_keys: Vec<PublicKey>,
+    /// Where the snapshot downloaded from `snapshot_url` is stored until it is imported
+    pub snapshot_download_path: PathBuf,
 }
 
 /// Factory settings
@@ -81,20 +133,38 @@ pub struct FactorySettings {
     pub initial_delay: MassaTime,
     /// Staking wallet file
     pub staking_wallet_path: PathBuf,
+    /// Where the password of the staking wallet is stored to unlock it without typing it
+    pub staking_wallet_password_backend: PasswordBackend,
 }
 
 /// Pool configuration, read from a file configuration
 #[derive(Debug, Deserialize, Clone)]
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
+    /// max total size of the operations in the pool (in bytes)
+    pub max_operation_pool_byte_size: usize,
+    /// max number of operations of a single sender in the pool
+    pub max_operations_per_sender: usize,
+    /// order in which the operations are evicted when the pool exceeds one of its quotas
+    pub operation_eviction_policy: OperationEvictionPolicy,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
+    /// number of periods after their slot during which the endorsements are kept, even if that slot is not final
+    pub endorsement_retention_periods: u64,
+    /// interval between the cleanups of the endorsement pool
+    pub endorsement_pool_cleanup_interval: MassaTime,
     pub max_item_return_count: usize,
     /// endorsements channel capacity
     pub broadcast_endorsements_channel_capacity: usize,
     /// operations channel capacity
     pub broadcast_operations_channel_capacity: usize,
+    /// denunciations channel capacity
+    pub broadcast_denunciations_channel_capacity: usize,
+    /// file where the pending operations and endorsements are saved on shutdown, to be reloaded on startup
+    pub persistence_path: PathBuf,
+    /// file listing the senders whose operations this node does not pool nor propagate (local policy)
+    pub denylist_path: PathBuf,
 }
 
 /// API and server configuration, read from a file configuration.
@@ -105,19 +175,25 @@ pub struct APISettings {
     pub bind_public: SocketAddr,
     pub bind_api: SocketAddr,
     pub max_arguments: u64,
-    pub openrpc_spec_path: PathBuf,
     pub max_request_body_size: u32,
     pub max_response_body_size: u32,
     pub max_connections: u32,
     pub max_subscriptions_per_connection: u32,
     pub max_log_length: u32,
     pub allow_hosts: Vec<String>,
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allowed_headers: Vec<String>,
+    pub public_bearer_token: Option<String>,
     pub batch_request_limit: u32,
     pub ping_interval: MassaTime,
     pub enable_http: bool,
     pub enable_ws: bool,
     // whether to broadcast for blocks, endorsement and operations
     pub enable_broadcast: bool,
+    /// max number of read-only executions run by a gas estimation
+    pub max_gas_estimation_executions: u32,
+    /// safety margin added to the estimated gas, in percent
+    pub gas_estimation_margin_percent: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -135,6 +211,10 @@ pub struct Settings {
     pub factory: FactorySettings,
     pub grpc: GrpcSettings,
     pub metrics: MetricsSettings,
+    pub crash_dump: CrashDumpSettings,
+    pub webhooks: WebhooksSettings,
+    pub modules: ModulesSettings,
+    pub archive: ArchiveSettings,
 }
 
 /// Consensus configuration
@@ -147,6 +227,7 @@ pub struct ConsensusSettings {
     pub max_future_processing_blocks: usize,
     /// Maximum number of blocks allowed in `DependencyWaitingBlocks`.
     pub max_dependency_blocks: usize,
+    pub max_orphan_headers: usize,
     /// stats time span
     pub stats_timespan: MassaTime,
     /// force keep at least this number of final periods in RAM for each thread
@@ -161,6 +242,12 @@ pub struct ConsensusSettings {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// reorgs channel capacity
+    pub broadcast_reorgs_channel_capacity: usize,
+    /// discarded blocks channel capacity
+    pub broadcast_discarded_blocks_channel_capacity: usize,
+    /// maximum number of pending incoming headers whose signatures are verified together
+    pub header_verification_batch_size: usize,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -175,6 +262,73 @@ pub struct MetricsSettings {
     pub enabled: bool,
 }
 
+/// Optional node modules, disabled to run lightweight relay or API-only nodes.
+/// The modules left out of the build by the `factory` and `bootstrap_serving` features stay disabled.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModulesSettings {
+    /// execute the final and candidate blocks. Disabled on header-only relay nodes, which do not check the PoS draws
+    /// since execution no longer feeds the selector, and whose final state stays at its bootstrapped slot.
+    /// Requires `factory` and `bootstrap_server` to be disabled
+    pub execution: bool,
+    /// produce blocks and endorsements with the staking keys
+    pub factory: bool,
+    /// serve bootstrap to other nodes, requires `bootstrap.bind` to be set
+    pub bootstrap_server: bool,
+}
+
+/// Archival mode settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct ArchiveSettings {
+    /// keep the metadata of the blocks pruned from the consensus graph and the cycles pruned from the final state
+    pub enabled: bool,
+    /// path of the archive database
+    pub path: PathBuf,
+}
+
+/// Crash bundles settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct CrashDumpSettings {
+    /// write a crash bundle when the node panics
+    pub enabled: bool,
+    /// directory in which the crash bundles are written
+    pub directory: PathBuf,
+    /// number of log lines kept in memory and written in the crash bundles
+    pub log_lines: usize,
+}
+
+/// Webhooks dispatcher settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhooksSettings {
+    /// POST the node events to the configured endpoints
+    pub enabled: bool,
+    /// addresses whose final balance changes and operations are notified, in addition to the node wallet addresses
+    pub watched_addresses: Vec<Address>,
+    /// maximum number of delivery attempts of a payload
+    pub max_attempts: u32,
+    /// delay before the first retry, doubled after each failed attempt
+    pub retry_delay: MassaTime,
+    /// timeout of a delivery attempt
+    pub request_timeout: MassaTime,
+    /// interval at which the MIP activations are checked
+    pub mip_check_interval: MassaTime,
+    /// maximum number of payloads waiting to be delivered to an endpoint
+    pub queue_size: usize,
+    /// endpoints receiving the events
+    pub endpoints: Vec<WebhookEndpointSettings>,
+}
+
+/// Webhook endpoint settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct WebhookEndpointSettings {
+    /// URL to which the payloads are POSTed
+    pub url: String,
+    /// events sent to this endpoint
+    pub events: Vec<WebhookEventKind>,
+    /// key used to sign the payloads with HMAC-SHA256, the payloads are not signed if empty
+    #[serde(default)]
+    pub secret: String,
+}
+
 /// Protocol Configuration, read from toml user configuration file
 #[derive(Debug, Deserialize, Clone)]
 pub struct ProtocolSettings {
@@ -210,6 +364,10 @@ pub struct ProtocolSettings {
     pub asked_operations_pruning_period: MassaTime,
     /// Interval at which operations are announced in batches.
     pub operation_announcement_interval: MassaTime,
+    /// Interval at which the operations submitted to this node are announced again until they are included or expire
+    pub local_operations_rebroadcast_interval: MassaTime,
+    /// Maximum number of operations submitted to this node that are rebroadcast
+    pub max_local_operations: usize,
     /// Maximum of operations sent in one message.
     pub max_operations_per_message: u64,
     /// Time threshold after which operation are not propagated
@@ -218,6 +376,8 @@ pub struct ProtocolSettings {
     pub max_endorsements_propagation_time: MassaTime,
     /// Path for initial peers
     pub initial_peers_file: PathBuf,
+    /// Path of the file where the bans set by the node operator are persisted
+    pub ban_list_file: PathBuf,
     /// Keypair
     pub keypair_file: PathBuf,
     /// Ip we are bind to listen to
@@ -240,6 +400,8 @@ pub struct ProtocolSettings {
     pub peers_categories: HashMap<String, PeerCategoryInfo>,
     /// Limits for default category
     pub default_category_info: PeerCategoryInfo,
+    /// peer events broadcast channel capacity
+    pub broadcast_peer_events_channel_capacity: usize,
 }
 
 /// gRPC settings
@@ -260,6 +422,8 @@ pub struct GrpcSettings {
     pub enable_mtls: bool,
     /// bind for the Massa gRPC API
     pub bind: SocketAddr,
+    /// bind for the private gRPC API managing the node, not served if none
+    pub private_bind: Option<SocketAddr>,
     /// which compression encodings does the server accept for requests
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
@@ -298,6 +462,20 @@ pub struct GrpcSettings {
     pub max_block_ids_per_request: u32,
     /// max number of operation ids that can be included in a single request
     pub max_operation_ids_per_request: u32,
+    /// max number of datastore entries returned for a single datastore entries stream request
+    pub max_datastore_entries_per_request: u32,
+    /// maximum number of operations followed at the same time by a SubmitOperations stream
+    pub max_tracked_operations_per_stream: usize,
+    /// what to do when a client does not read the messages of a subscription stream fast enough
+    pub stream_backpressure_policy: StreamBackpressurePolicy,
+    /// whether to serve the peer events stream, which exposes the ips of the peers
+    pub enable_peer_events_stream: bool,
+    /// whether to write an access log record for each call
+    pub enable_access_log: bool,
+    /// optional file to which the access log records are appended as JSON lines
+    pub access_log_file: Option<PathBuf>,
+    /// time given to the active streams to flush and end when the node shuts down
+    pub shutdown_drain_timeout: MassaTime,
     /// server certificate path
     pub server_certificate_path: PathBuf,
     /// server private key path
@@ -0,0 +1,161 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Final state snapshots fetched over HTTP(S), for example from an object storage or a CDN,
+//! so that new nodes do not download the whole state from the public bootstrap servers.
+//!
+//! A snapshot written by `snapshot export` is published along with a signature file, at the same URL followed by `.sig`.
+//! The signature file holds the slot and the hash of the exported final state, signed by the key of the publisher.
+//! The node only imports a snapshot whose signature comes from one of its trusted keys, and whose recomputed state
+//! hash is the signed one: the bootstrap servers then only stream the changes since the slot of the snapshot.
+
+use anyhow::{anyhow, bail, Result};
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use massa_hash::Hash;
+use massa_models::slot::Slot;
+use massa_signature::{KeyPair, PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+/// Extension of the signature file of a snapshot
+const SIGNATURE_EXTENSION: &str = "sig";
+
+type HttpsClient = Client<HttpsConnector<HttpConnector>>;
+
+/// Signature of the final state exported in a snapshot, stored next to it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotSignature {
+    /// slot of the exported final state
+    pub slot: Slot,
+    /// hash of the exported final state
+    pub state_hash: Hash,
+    /// key of the publisher of the snapshot
+    pub public_key: PublicKey,
+    /// signature of the slot and of the state hash
+    pub signature: Signature,
+}
+
+impl SnapshotSignature {
+    /// Signs the final state at `slot` with `keypair`
+    pub fn new(slot: Slot, state_hash: Hash, keypair: &KeyPair) -> Result<Self> {
+        Ok(SnapshotSignature {
+            slot,
+            state_hash,
+            public_key: keypair.get_public_key(),
+            signature: keypair.sign(&signed_hash(&slot, &state_hash))?,
+        })
+    }
+
+    /// Checks that the signature is valid and comes from one of the `trusted_keys`
+    pub fn verify(&self, trusted_keys: &[PublicKey]) -> Result<()> {
+        if !trusted_keys.contains(&self.public_key) {
+            bail!("snapshot signed by the untrusted key {}", self.public_key);
+        }
+        self.public_key
+            .verify_signature(&signed_hash(&self.slot, &self.state_hash), &self.signature)?;
+        Ok(())
+    }
+
+    /// Writes the signature next to the snapshot `path`
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(signature_path(path), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Hash of the signed data: the slot key followed by the state hash
+fn signed_hash(slot: &Slot, state_hash: &Hash) -> Hash {
+    Hash::compute_from(&[&slot.to_bytes_key()[..], state_hash.to_bytes()].concat())
+}
+
+/// Path of the signature file of the snapshot `path`
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(SIGNATURE_EXTENSION);
+    path.into()
+}
+
+/// Downloads the snapshot published at `url` to `path`, after checking that its signature comes from one of the `trusted_keys`.
+///
+/// The state hash of the snapshot must still be checked against the returned signature once imported.
+pub async fn download_snapshot(
+    url: &str,
+    trusted_keys: &[PublicKey],
+    path: &Path,
+) -> Result<SnapshotSignature> {
+    let client: HttpsClient = Client::builder().build(
+        HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build(),
+    );
+    let signature_url = format!("{}.{}", url, SIGNATURE_EXTENSION);
+    let signature_body = get(&client, &signature_url).await?;
+    let signature: SnapshotSignature =
+        serde_json::from_slice(&hyper::body::to_bytes(signature_body).await?)
+            .map_err(|e| anyhow!("invalid snapshot signature at {}: {}", signature_url, e))?;
+    signature.verify(trusted_keys)?;
+    info!(
+        "Downloading the final state snapshot at slot {} from {}",
+        signature.slot, url
+    );
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut body = get(&client, url).await?;
+    let mut size = 0;
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        size += chunk.len();
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    info!("Final state snapshot downloaded: {} bytes", size);
+    Ok(signature)
+}
+
+/// Sends a GET request to `url` and returns the body of the response, which must be successful
+async fn get(client: &HttpsClient, url: &str) -> Result<Body> {
+    let uri = url
+        .parse::<Uri>()
+        .map_err(|e| anyhow!("invalid URL {}: {}", url, e))?;
+    let response = client
+        .get(uri)
+        .await
+        .map_err(|e| anyhow!("cannot get {}: {}", url, e))?;
+    if !response.status().is_success() {
+        bail!("cannot get {}: status {}", url, response.status());
+    }
+    Ok(response.into_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_signature() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let slot = Slot::new(10, 3);
+        let signature =
+            SnapshotSignature::new(slot, Hash::compute_from(b"state"), &keypair).unwrap();
+        signature.verify(&[keypair.get_public_key()]).unwrap();
+
+        let other = KeyPair::generate(0).unwrap();
+        assert!(signature.verify(&[other.get_public_key()]).is_err());
+
+        let tampered = SnapshotSignature {
+            state_hash: Hash::compute_from(b"other state"),
+            ..signature
+        };
+        assert!(tampered.verify(&[keypair.get_public_key()]).is_err());
+    }
+}
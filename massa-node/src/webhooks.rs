@@ -0,0 +1,456 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Webhooks dispatcher.
+//!
+//! When enabled, the node POSTs a JSON payload to the configured endpoints each time one of the events
+//! they subscribed to happens: a final balance change of a watched address, a block produced by a staking key of the node,
+//! a slot missed by a staking key of the node, the activation of a MIP, or a change in the status of an operation
+//! sent by a watched address.
+//! Failed deliveries are retried with an exponential backoff, and the payloads are signed with HMAC-SHA256
+//! when the endpoint has a secret.
+
+use crate::settings::{WebhookEndpointSettings, WebhooksSettings};
+use hmac::{Hmac, Mac};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use massa_consensus_exports::ConsensusChannels;
+use massa_execution_exports::{ExecutionChannels, ExecutionOutput, SlotExecutionOutput};
+use massa_ledger_exports::{SetOrKeep, SetUpdateOrDelete};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::block_id::BlockId;
+use massa_models::operation::OperationId;
+use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
+use massa_pool_exports::{OperationLifecycleEvent, OperationLifecycleStatus, PoolChannels};
+use massa_pos_exports::SelectorController;
+use massa_time::MassaTime;
+use massa_versioning::versioning::{ComponentStateTypeId, MipStore};
+use massa_wallet::Wallet;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Header containing the hex encoded HMAC-SHA256 of the payload
+const SIGNATURE_HEADER: &str = "X-Massa-Signature";
+
+type HttpsClient = Client<HttpsConnector<HttpConnector>>;
+
+/// Kinds of events an endpoint can subscribe to
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    /// final balance change of a watched address
+    BalanceChange,
+    /// block produced by a staking key of the node
+    BlockProduced,
+    /// slot missed by a staking key of the node
+    MissedSlot,
+    /// activation of a MIP
+    MipActivation,
+    /// change in the status of an operation sent by a watched address
+    OperationStatus,
+}
+
+/// Event notified to the endpoints
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+enum WebhookEvent {
+    BalanceChange {
+        address: Address,
+        balance: Amount,
+        slot: Slot,
+    },
+    BlockProduced {
+        block_id: BlockId,
+        slot: Slot,
+        creator: Address,
+    },
+    MissedSlot {
+        slot: Slot,
+        producer: Address,
+    },
+    MipActivation {
+        name: String,
+        version: u32,
+    },
+    OperationStatus {
+        operation_id: OperationId,
+        creator: Address,
+        #[serde(flatten)]
+        status: OperationLifecycleStatus,
+    },
+}
+
+impl WebhookEvent {
+    fn kind(&self) -> WebhookEventKind {
+        match self {
+            WebhookEvent::BalanceChange { .. } => WebhookEventKind::BalanceChange,
+            WebhookEvent::BlockProduced { .. } => WebhookEventKind::BlockProduced,
+            WebhookEvent::MissedSlot { .. } => WebhookEventKind::MissedSlot,
+            WebhookEvent::MipActivation { .. } => WebhookEventKind::MipActivation,
+            WebhookEvent::OperationStatus { .. } => WebhookEventKind::OperationStatus,
+        }
+    }
+}
+
+/// Payload POSTed to the endpoints
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    /// time at which the event was noticed by the node, in milliseconds since the unix epoch
+    timestamp: u64,
+    #[serde(flatten)]
+    event: &'a WebhookEvent,
+}
+
+/// Queue of the payloads waiting to be delivered to an endpoint
+struct EndpointQueue {
+    url: String,
+    events: Vec<WebhookEventKind>,
+    sender: mpsc::Sender<Arc<Vec<u8>>>,
+}
+
+/// Collects the node events and delivers them to the webhook endpoints
+pub struct WebhookDispatcher {
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl WebhookDispatcher {
+    /// Starts the dispatcher.
+    /// The events are read from the broadcast channels, which must be enabled.
+    pub fn start(
+        settings: &WebhooksSettings,
+        node_wallet: Arc<RwLock<Wallet>>,
+        selector_controller: Box<dyn SelectorController>,
+        mip_store: MipStore,
+        consensus_channels: &ConsensusChannels,
+        execution_channels: &ExecutionChannels,
+        pool_channels: &PoolChannels,
+    ) -> Self {
+        let client: HttpsClient = Client::builder().build(
+            HttpsConnectorBuilder::new()
+                .with_native_roots()
+                .https_or_http()
+                .enable_http1()
+                .build(),
+        );
+
+        let mut tasks = Vec::new();
+        let mut queues = Vec::new();
+        for endpoint in &settings.endpoints {
+            let uri = match endpoint.url.parse::<Uri>() {
+                Ok(uri) => uri,
+                Err(e) => {
+                    warn!("webhooks | ignoring endpoint {}: {}", endpoint.url, e);
+                    continue;
+                }
+            };
+            let (sender, receiver) = mpsc::channel(settings.queue_size);
+            queues.push(EndpointQueue {
+                url: endpoint.url.clone(),
+                events: endpoint.events.clone(),
+                sender,
+            });
+            tasks.push(tokio::spawn(run_endpoint(
+                client.clone(),
+                uri,
+                endpoint.clone(),
+                settings.clone(),
+                receiver,
+            )));
+        }
+        info!(
+            "webhooks | dispatching events to {} endpoints",
+            queues.len()
+        );
+
+        tasks.push(tokio::spawn(watch_events(
+            settings.clone(),
+            queues,
+            node_wallet,
+            selector_controller,
+            mip_store,
+            consensus_channels.block_sender.subscribe(),
+            execution_channels.slot_execution_output_sender.subscribe(),
+            pool_channels.operation_lifecycle_sender.subscribe(),
+        )));
+
+        WebhookDispatcher { tasks }
+    }
+
+    /// Stops the dispatcher, the payloads not delivered yet are dropped
+    pub fn stop(self) {
+        for task in self.tasks {
+            task.abort();
+        }
+        info!("webhooks | stopped");
+    }
+}
+
+/// Reads the node events and queues the payloads for the endpoints subscribed to them
+async fn watch_events(
+    settings: WebhooksSettings,
+    queues: Vec<EndpointQueue>,
+    node_wallet: Arc<RwLock<Wallet>>,
+    selector_controller: Box<dyn SelectorController>,
+    mip_store: MipStore,
+    mut block_receiver: tokio::sync::broadcast::Receiver<massa_models::block::SecureShareBlock>,
+    mut slot_execution_output_receiver: tokio::sync::broadcast::Receiver<SlotExecutionOutput>,
+    mut operation_lifecycle_receiver: tokio::sync::broadcast::Receiver<OperationLifecycleEvent>,
+) {
+    let watched_addresses: PreHashSet<Address> =
+        settings.watched_addresses.iter().copied().collect();
+    // MIPs already active when the node starts are not notified
+    let mut known_active_mips = active_mips(&mip_store);
+    let mut mip_check_interval = tokio::time::interval(settings.mip_check_interval.to_duration());
+
+    loop {
+        let events = tokio::select! {
+            res = block_receiver.recv() => match res {
+                Ok(block) => {
+                    let creator = block.content_creator_address;
+                    if node_wallet.read().get_wallet_address_list().contains(&creator) {
+                        vec![WebhookEvent::BlockProduced {
+                            block_id: block.id,
+                            slot: block.content.header.content.slot,
+                            creator,
+                        }]
+                    } else {
+                        continue;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("webhooks | {} blocks were not processed, the dispatcher is lagging", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            res = slot_execution_output_receiver.recv() => match res {
+                Ok(SlotExecutionOutput::FinalizedSlot(output)) => {
+                    let mut local_addresses = node_wallet.read().get_wallet_address_list();
+                    let missed_slot = missed_slot_event(&output, &*selector_controller, &local_addresses);
+                    local_addresses.extend(watched_addresses.iter().copied());
+                    balance_change_events(&output, &local_addresses)
+                        .into_iter()
+                        .chain(missed_slot)
+                        .collect()
+                }
+                Ok(SlotExecutionOutput::ExecutedSlot(_)) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("webhooks | {} slot execution outputs were not processed, the dispatcher is lagging", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            res = operation_lifecycle_receiver.recv() => match res {
+                // the evictions reflecting only the production schedule of the node are not notified
+                Ok(OperationLifecycleEvent {
+                    status: OperationLifecycleStatus::Evicted { reason },
+                    ..
+                }) if reason.is_local() => continue,
+                Ok(event) => {
+                    if watched_addresses.contains(&event.creator)
+                        || node_wallet.read().get_wallet_address_list().contains(&event.creator)
+                    {
+                        vec![WebhookEvent::OperationStatus {
+                            operation_id: event.operation_id,
+                            creator: event.creator,
+                            status: event.status,
+                        }]
+                    } else {
+                        continue;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("webhooks | {} operation lifecycle events were not processed, the dispatcher is lagging", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = mip_check_interval.tick() => {
+                let now_active = active_mips(&mip_store);
+                let events = now_active
+                    .difference(&known_active_mips)
+                    .map(|(version, name)| WebhookEvent::MipActivation {
+                        name: name.clone(),
+                        version: *version,
+                    })
+                    .collect();
+                known_active_mips = now_active;
+                events
+            }
+        };
+
+        for event in events {
+            queue_event(&queues, &event);
+        }
+    }
+}
+
+/// Returns the balance changes of the given addresses in a final slot
+fn balance_change_events(
+    output: &ExecutionOutput,
+    addresses: &PreHashSet<Address>,
+) -> Vec<WebhookEvent> {
+    output
+        .state_changes
+        .ledger_changes
+        .0
+        .iter()
+        .filter(|(address, _)| addresses.contains(address))
+        .filter_map(|(address, change)| {
+            let balance = match change {
+                SetUpdateOrDelete::Set(entry) => entry.balance,
+                SetUpdateOrDelete::Update(update) => match update.balance {
+                    SetOrKeep::Set(balance) => balance,
+                    SetOrKeep::Keep => return None,
+                },
+                SetUpdateOrDelete::Delete => Amount::zero(),
+            };
+            Some(WebhookEvent::BalanceChange {
+                address: *address,
+                balance,
+                slot: output.slot,
+            })
+        })
+        .collect()
+}
+
+/// Returns an event if the final slot has no block and was assigned to one of the given addresses
+fn missed_slot_event(
+    output: &ExecutionOutput,
+    selector_controller: &dyn SelectorController,
+    addresses: &PreHashSet<Address>,
+) -> Option<WebhookEvent> {
+    if output.block_id.is_some() {
+        return None;
+    }
+    let producer = selector_controller.get_producer(output.slot).ok()?;
+    addresses
+        .contains(&producer)
+        .then_some(WebhookEvent::MissedSlot {
+            slot: output.slot,
+            producer,
+        })
+}
+
+/// Returns the version and name of the active MIPs
+fn active_mips(mip_store: &MipStore) -> BTreeSet<(u32, String)> {
+    mip_store
+        .get_mip_status()
+        .into_iter()
+        .filter(|(_, state)| *state == ComponentStateTypeId::Active)
+        .map(|(mip_info, _)| (mip_info.version, mip_info.name))
+        .collect()
+}
+
+/// Queues the payload of an event for the endpoints subscribed to it
+fn queue_event(queues: &[EndpointQueue], event: &WebhookEvent) {
+    let subscribers: Vec<&EndpointQueue> = queues
+        .iter()
+        .filter(|queue| queue.events.contains(&event.kind()))
+        .collect();
+    if subscribers.is_empty() {
+        return;
+    }
+    let payload = WebhookPayload {
+        timestamp: MassaTime::now()
+            .map(|now| now.to_millis())
+            .unwrap_or_default(),
+        event,
+    };
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => Arc::new(body),
+        Err(e) => {
+            warn!("webhooks | failed to serialize {:?}: {}", event, e);
+            return;
+        }
+    };
+    for queue in subscribers {
+        if queue.sender.try_send(body.clone()).is_err() {
+            warn!(
+                "webhooks | queue of {} is full, dropping {:?}",
+                queue.url, event
+            );
+        }
+    }
+}
+
+/// Delivers the queued payloads to an endpoint, in order
+async fn run_endpoint(
+    client: HttpsClient,
+    uri: Uri,
+    endpoint: WebhookEndpointSettings,
+    settings: WebhooksSettings,
+    mut receiver: mpsc::Receiver<Arc<Vec<u8>>>,
+) {
+    while let Some(body) = receiver.recv().await {
+        let mut retry_delay = settings.retry_delay.to_duration();
+        for attempt in 1..=settings.max_attempts {
+            match post(&client, &uri, &endpoint.secret, &body, &settings).await {
+                Ok(()) => break,
+                Err(e) if attempt < settings.max_attempts => {
+                    warn!(
+                        "webhooks | attempt {}/{} to {} failed, retrying in {:?}: {}",
+                        attempt, settings.max_attempts, endpoint.url, retry_delay, e
+                    );
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = retry_delay.saturating_mul(2);
+                }
+                Err(e) => {
+                    warn!(
+                        "webhooks | dropping payload after {} failed attempts to {}: {}",
+                        attempt, endpoint.url, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// POSTs a payload to an endpoint, signing it if the endpoint has a secret
+async fn post(
+    client: &HttpsClient,
+    uri: &Uri,
+    secret: &str,
+    body: &[u8],
+    settings: &WebhooksSettings,
+) -> Result<(), String> {
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(uri.clone())
+        .header(hyper::header::CONTENT_TYPE, "application/json");
+    if !secret.is_empty() {
+        request = request.header(SIGNATURE_HEADER, sign(secret, body));
+    }
+    let request = request
+        .body(Body::from(body.to_vec()))
+        .map_err(|e| e.to_string())?;
+
+    let response = tokio::time::timeout(
+        settings.request_timeout.to_duration(),
+        client.request(request),
+    )
+    .await
+    .map_err(|_| "request timed out".to_string())?
+    .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("endpoint answered {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Returns the hex encoded HMAC-SHA256 of the payload
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
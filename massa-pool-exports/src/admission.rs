@@ -0,0 +1,84 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Operator-defined policy applied to operations when they enter the pool
+
+use massa_models::operation::SecureShareOperation;
+use massa_time::MassaTime;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Decision of an admission hook about one operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationAdmissionVerdict {
+    /// the operation is admitted
+    Accept,
+    /// the operation is admitted with the given tags,
+    /// which can change its score through `OperationAdmissionConfig::tag_score_factors`
+    Tag(Vec<String>),
+    /// the operation is rejected for the given reason
+    Veto(String),
+}
+
+/// Operator-defined logic called on the operations entering the pool (e.g. MEV policy, spam heuristics)
+pub trait OperationAdmissionHook: Send + Sync {
+    /// Returns one verdict per operation, in the same order.
+    /// Missing verdicts are considered as `OperationAdmissionVerdict::Accept`.
+    fn check_operations(
+        &self,
+        operations: &[SecureShareOperation],
+    ) -> Vec<OperationAdmissionVerdict>;
+}
+
+/// Admission hook configuration, that can be changed while the node is running
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OperationAdmissionConfig {
+    /// maximum time given to the hook to check a batch of operations
+    pub timing_budget: MassaTime,
+    /// if true, the operations of a batch for which the hook did not answer in time are rejected,
+    /// otherwise they are admitted untagged
+    pub veto_on_timeout: bool,
+    /// score multiplier applied to the operations carrying a given tag
+    pub tag_score_factors: BTreeMap<String, f32>,
+}
+
+impl Default for OperationAdmissionConfig {
+    fn default() -> Self {
+        OperationAdmissionConfig {
+            timing_budget: MassaTime::from_millis(50),
+            veto_on_timeout: false,
+            tag_score_factors: BTreeMap::new(),
+        }
+    }
+}
+
+/// Admission hook along with its dynamic configuration.
+/// Clones share the same configuration.
+#[derive(Clone)]
+pub struct OperationAdmissionPolicy {
+    /// operator-defined hook
+    pub hook: Arc<dyn OperationAdmissionHook>,
+    /// configuration shared with the pool
+    config: Arc<RwLock<OperationAdmissionConfig>>,
+}
+
+impl OperationAdmissionPolicy {
+    /// Creates a new admission policy
+    pub fn new(hook: Arc<dyn OperationAdmissionHook>, config: OperationAdmissionConfig) -> Self {
+        OperationAdmissionPolicy {
+            hook,
+            config: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// Returns the current configuration
+    pub fn get_config(&self) -> OperationAdmissionConfig {
+        self.config.read().clone()
+    }
+
+    /// Replaces the configuration, applied from the next admitted batch and pool refresh
+    pub fn set_config(&self, config: OperationAdmissionConfig) {
+        *self.config.write() = config;
+    }
+}
@@ -1,7 +1,12 @@
 use massa_execution_exports::ExecutionController;
-use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
+use massa_models::{
+    denunciation::Denunciation, endorsement::SecureShareEndorsement,
+    operation::SecureShareOperation,
+};
 use massa_pos_exports::SelectorController;
 
+use crate::OperationLifecycleEvent;
+
 /// channels used by the pool worker
 #[derive(Clone)]
 pub struct PoolChannels {
@@ -11,6 +16,10 @@ pub struct PoolChannels {
     pub endorsement_sender: tokio::sync::broadcast::Sender<SecureShareEndorsement>,
     /// Broadcast channel for new operations
     pub operation_sender: tokio::sync::broadcast::Sender<SecureShareOperation>,
+    /// Broadcast channel for new denunciations
+    pub denunciation_sender: tokio::sync::broadcast::Sender<Denunciation>,
+    /// Broadcast channel for the lifecycle events of the operations
+    pub operation_lifecycle_sender: tokio::sync::broadcast::Sender<OperationLifecycleEvent>,
     /// Selector to get draws
     pub selector: Box<dyn SelectorController>,
 }
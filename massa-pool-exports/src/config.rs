@@ -1,5 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::OperationEvictionPolicy;
 use massa_models::amount::Amount;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
@@ -25,8 +26,19 @@ pub struct PoolConfig {
     pub max_operations_per_block: u32,
     /// max operation pool size per thread (in number of operations)
     pub max_operation_pool_size: usize,
+    /// max total size of the operations in the pool (in bytes)
+    pub max_operation_pool_byte_size: usize,
+    /// max number of operations of a single sender in the pool
+    pub max_operations_per_sender: usize,
+    /// order in which the operations are evicted when the operation pool exceeds one of its quotas
+    pub operation_eviction_policy: OperationEvictionPolicy,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
+    /// number of periods after their inclusion slot during which the endorsements are kept in the pool,
+    /// even if that slot is not final yet
+    pub endorsement_retention_periods: u64,
+    /// interval between the cleanups of the endorsements that outlived their retention
+    pub endorsement_pool_cleanup_interval: MassaTime,
     /// max number of endorsements per block
     pub max_block_endorsement_count: u32,
     /// operations channel capacity
@@ -41,6 +53,8 @@ pub struct PoolConfig {
     pub broadcast_endorsements_channel_capacity: usize,
     /// operations channel capacity
     pub broadcast_operations_channel_capacity: usize,
+    /// denunciations channel capacity
+    pub broadcast_denunciations_channel_capacity: usize,
     /// genesis timestamp
     pub genesis_timestamp: MassaTime,
     /// period duration
@@ -1,6 +1,8 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    address::Address,
+    amount::Amount,
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
@@ -9,6 +11,8 @@ use massa_models::{
 };
 use massa_storage::Storage;
 
+use crate::{OperationPoolUsage, PoolStatistics};
+
 /// Trait defining a pool controller
 pub trait PoolController: Send + Sync {
     /// Asynchronously add operations to pool. Simply print a warning on failure.
@@ -42,12 +46,39 @@ pub trait PoolController: Send + Sync {
     /// Get the number of operations in the pool
     fn get_operation_count(&self) -> usize;
 
+    /// Get the fees of the operations in the pool at the given percentiles (0 to 100).
+    /// Returns one fee per percentile, or an empty list if the pool has no operation.
+    fn get_operation_fee_percentiles(&self, percentiles: &[u8]) -> Vec<Amount>;
+
+    /// Get the fee an operation needs to rank among the best-paying operations of the pool
+    /// that fit in `target_slots` blocks. Returns `None` if all the operations of the pool fit.
+    fn get_operation_inclusion_fee(&self, target_slots: u64) -> Option<Amount>;
+
+    /// Get the usage of the operation pool quotas, along with the eviction policy
+    fn get_operation_pool_usage(&self) -> OperationPoolUsage;
+
+    /// Get the statistics of the operation and endorsement pools: size, fee percentiles,
+    /// operations by expire period and endorsement pool fill rate
+    fn get_pool_statistics(&self) -> PoolStatistics;
+
     /// Check if the pool contains a list of endorsements. Returns one boolean per item.
     fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool>;
 
     /// Check if the pool contains a list of operations. Returns one boolean per item.
     fn contains_operations(&self, operations: &[OperationId]) -> Vec<bool>;
 
+    /// Get the senders in the local denylist of the node, whose operations are neither pooled nor propagated
+    fn get_denied_senders(&self) -> Vec<Address>;
+
+    /// Check if a list of senders is in the local denylist. Returns one boolean per item.
+    fn contains_denied_senders(&self, addresses: &[Address]) -> Vec<bool>;
+
+    /// Add senders to the local denylist, and save it. Their pending operations are evicted at the next refresh.
+    fn add_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()>;
+
+    /// Remove senders from the local denylist, and save it
+    fn remove_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()>;
+
     /// Check if the pool contains a denunciation. Returns a boolean
     #[cfg(feature = "testing")]
     fn contains_denunciation(&self, denunciation: &Denunciation) -> bool;
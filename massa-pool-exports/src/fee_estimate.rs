@@ -0,0 +1,33 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::amount::Amount;
+
+/// Fee suggested for an operation to be included within a target number of slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// suggested fee
+    pub fee: Amount,
+    /// fee needed to rank among the operations of the pool fitting in the target slots,
+    /// `None` if all the operations of the pool fit
+    pub pool_fee: Option<Amount>,
+    /// median fee of the operations included in the recent blocks, `None` if there are none
+    pub recent_median_fee: Option<Amount>,
+}
+
+impl FeeEstimate {
+    /// Suggests a fee from the pool composition and the fees of the recently included operations.
+    ///
+    /// The suggested fee is the fee needed to outbid the pool backlog,
+    /// but not lower than the median fee recently paid.
+    pub fn new(pool_fee: Option<Amount>, mut included_fees: Vec<Amount>) -> Self {
+        included_fees.sort_unstable();
+        let recent_median_fee = included_fees.get(included_fees.len() / 2).copied();
+        FeeEstimate {
+            fee: pool_fee
+                .unwrap_or_default()
+                .max(recent_median_fee.unwrap_or_default()),
+            pool_fee,
+            recent_median_fee,
+        }
+    }
+}
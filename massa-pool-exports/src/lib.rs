@@ -5,13 +5,29 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod admission;
 mod channels;
 mod config;
 mod controller_traits;
+mod fee_estimate;
+mod lifecycle;
+mod quota;
+mod statistics;
 
+pub use admission::{
+    OperationAdmissionConfig, OperationAdmissionHook, OperationAdmissionPolicy,
+    OperationAdmissionVerdict,
+};
 pub use channels::PoolChannels;
 pub use config::PoolConfig;
 pub use controller_traits::{PoolController, PoolManager};
+pub use fee_estimate::FeeEstimate;
+pub use lifecycle::{OperationEvictionReason, OperationLifecycleEvent, OperationLifecycleStatus};
+pub use quota::{OperationEvictionPolicy, OperationPoolUsage};
+pub use statistics::{
+    DenunciationPoolMetrics, ExpiryBucket, FeePercentile, PoolStatistics,
+    POOL_STATISTICS_FEE_PERCENTILES,
+};
 
 /// Test utils
 #[cfg(feature = "testing")]
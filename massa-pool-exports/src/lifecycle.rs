@@ -0,0 +1,81 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, operation::OperationId};
+use serde::{Deserialize, Serialize};
+
+/// Reason why an operation was evicted from the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationEvictionReason {
+    /// the operation uses more space or gas than a block allows
+    TooLarge,
+    /// the node has no block to produce in which the operation could be included
+    NoProductionSlot,
+    /// the sender cannot pay for the operation along with its other pending operations
+    InsufficientBalance,
+    /// the sender or the pool exceeds one of the pool quotas
+    Quota,
+    /// the pool is full of better operations
+    PoolFull,
+    /// the sender is in the local denylist of the node
+    Denylisted,
+}
+
+impl OperationEvictionReason {
+    /// Whether the eviction only reflects the production schedule or the local policy of the node,
+    /// the operation remaining includable by the other nodes that produce blocks in its validity range
+    pub fn is_local(&self) -> bool {
+        matches!(
+            self,
+            OperationEvictionReason::NoProductionSlot | OperationEvictionReason::Denylisted
+        )
+    }
+}
+
+impl std::fmt::Display for OperationEvictionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationEvictionReason::TooLarge => write!(f, "too large for a block"),
+            OperationEvictionReason::NoProductionSlot => {
+                write!(f, "no block to produce in its validity range")
+            }
+            OperationEvictionReason::InsufficientBalance => write!(f, "insufficient balance"),
+            OperationEvictionReason::Quota => write!(f, "pool quota exceeded"),
+            OperationEvictionReason::PoolFull => write!(f, "pool full"),
+            OperationEvictionReason::Denylisted => write!(f, "sender denied by the node"),
+        }
+    }
+}
+
+/// Step of the lifecycle of an operation in the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OperationLifecycleStatus {
+    /// the operation entered the pool
+    EnteredPool,
+    /// the operation was included in a block and executed in a candidate slot
+    Included,
+    /// the operation was executed in a final slot, successfully or not
+    Final {
+        /// whether the execution succeeded
+        success: bool,
+    },
+    /// the validity range of the operation ended before it was included
+    Expired,
+    /// the operation was evicted from the pool
+    Evicted {
+        /// why the operation was evicted
+        reason: OperationEvictionReason,
+    },
+}
+
+/// Change in the lifecycle of an operation, broadcast by the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationLifecycleEvent {
+    /// operation id
+    pub operation_id: OperationId,
+    /// creator of the operation
+    pub creator: Address,
+    /// new status of the operation
+    pub status: OperationLifecycleStatus,
+}
@@ -0,0 +1,41 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+
+/// Order in which the operations are evicted when the operation pool exceeds one of its quotas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationEvictionPolicy {
+    /// evict the operations paying the lowest fee per byte first
+    LowestFeeDensity,
+    /// evict the operations that entered the pool first
+    Oldest,
+}
+
+impl std::fmt::Display for OperationEvictionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationEvictionPolicy::LowestFeeDensity => write!(f, "lowest fee density first"),
+            OperationEvictionPolicy::Oldest => write!(f, "oldest first"),
+        }
+    }
+}
+
+/// Usage of the operation pool quotas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationPoolUsage {
+    /// eviction policy applied when a quota is exceeded
+    pub eviction_policy: OperationEvictionPolicy,
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// max number of operations in the pool
+    pub max_operation_count: usize,
+    /// total size of the operations in the pool, in bytes
+    pub byte_size: usize,
+    /// max total size of the operations in the pool, in bytes
+    pub max_byte_size: usize,
+    /// highest number of operations of a single sender in the pool
+    pub max_sender_operation_count: usize,
+    /// max number of operations of a single sender in the pool
+    pub max_operations_per_sender: usize,
+}
@@ -0,0 +1,120 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::amount::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Percentiles at which the fees of the operation pool are reported in the pool statistics
+pub const POOL_STATISTICS_FEE_PERCENTILES: [u8; 5] = [10, 25, 50, 75, 90];
+
+/// Fee paid by the operations of the pool at a given percentile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FeePercentile {
+    /// percentile, from 0 to 100
+    pub percentile: u8,
+    /// fee at that percentile (nearest rank)
+    pub fee: Amount,
+}
+
+/// Number of operations of the pool whose validity range ends at a given period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExpiryBucket {
+    /// last period at which the operations can be included
+    pub expire_period: u64,
+    /// number of operations expiring after that period
+    pub operation_count: usize,
+}
+
+/// State of the denunciation precursor index of the pool, and its counters since the node started
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DenunciationPoolMetrics {
+    /// number of slots with at least one tracked precursor
+    pub tracked_slot_count: usize,
+    /// number of items waiting for a second precursor to be denounced
+    pub pending_precursor_count: usize,
+    /// number of denunciations in the pool
+    pub denunciation_count: usize,
+    /// number of precursors received
+    pub received_precursor_count: u64,
+    /// number of received precursors that were already known
+    pub duplicate_precursor_count: u64,
+    /// number of received precursors rejected because expired, too far in the future or not matching the selection
+    pub rejected_precursor_count: u64,
+}
+
+/// Statistics of the operation, endorsement and denunciation pools
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PoolStatistics {
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// total size of the operations in the pool, in bytes
+    pub operation_byte_size: usize,
+    /// fees of the operations at the percentiles of `POOL_STATISTICS_FEE_PERCENTILES`,
+    /// empty if the pool has no operation
+    pub fee_percentiles: Vec<FeePercentile>,
+    /// number of operations by expire period, sorted by increasing period
+    pub expiry_histogram: Vec<ExpiryBucket>,
+    /// number of endorsements in the pool
+    pub endorsement_count: usize,
+    /// max number of endorsements in the pool
+    pub max_endorsement_count: usize,
+    /// share of the endorsement pool in use, from 0 to 1
+    pub endorsement_fill_rate: f64,
+    /// number of endorsements in the pool for each thread
+    pub endorsement_thread_counts: Vec<usize>,
+    /// max number of endorsements in the pool for each thread
+    pub max_endorsement_count_per_thread: usize,
+    /// state of the denunciation pool
+    pub denunciations: DenunciationPoolMetrics,
+}
+
+impl std::fmt::Display for PoolStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Operations: {} ({} bytes)",
+            self.operation_count, self.operation_byte_size
+        )?;
+        if !self.fee_percentiles.is_empty() {
+            writeln!(f, "Operation fees:")?;
+            for fee_percentile in &self.fee_percentiles {
+                writeln!(
+                    f,
+                    "\tp{}: {}",
+                    fee_percentile.percentile, fee_percentile.fee
+                )?;
+            }
+        }
+        if !self.expiry_histogram.is_empty() {
+            writeln!(f, "Operations by expire period:")?;
+            for bucket in &self.expiry_histogram {
+                writeln!(f, "\t{}: {}", bucket.expire_period, bucket.operation_count)?;
+            }
+        }
+        writeln!(
+            f,
+            "Endorsements: {}/{} ({:.1}%)",
+            self.endorsement_count,
+            self.max_endorsement_count,
+            self.endorsement_fill_rate * 100.0
+        )?;
+        writeln!(
+            f,
+            "Endorsements per thread (max {}): {:?}",
+            self.max_endorsement_count_per_thread, self.endorsement_thread_counts
+        )?;
+        writeln!(
+            f,
+            "Denunciations: {} ({} pending precursors over {} slots)",
+            self.denunciations.denunciation_count,
+            self.denunciations.pending_precursor_count,
+            self.denunciations.tracked_slot_count
+        )?;
+        writeln!(
+            f,
+            "Denunciation precursors received: {} ({} duplicates, {} rejected)",
+            self.denunciations.received_precursor_count,
+            self.denunciations.duplicate_precursor_count,
+            self.denunciations.rejected_precursor_count
+        )
+    }
+}
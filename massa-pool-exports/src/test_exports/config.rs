@@ -7,7 +7,7 @@ use massa_models::config::{
 };
 use massa_time::MassaTime;
 
-use crate::PoolConfig;
+use crate::{OperationEvictionPolicy, PoolConfig};
 
 impl Default for PoolConfig {
     fn default() -> Self {
@@ -18,7 +18,12 @@ impl Default for PoolConfig {
             roll_price: ROLL_PRICE,
             max_block_size: MAX_BLOCK_SIZE,
             max_operation_pool_size: 32000,
+            max_operation_pool_byte_size: 32_000_000,
+            max_operations_per_sender: 32000,
+            operation_eviction_policy: OperationEvictionPolicy::LowestFeeDensity,
             max_endorsements_pool_size_per_thread: 1000,
+            endorsement_retention_periods: 2,
+            endorsement_pool_cleanup_interval: MassaTime::from_millis(1000),
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
             operations_channel_size: 1024,
@@ -27,6 +32,7 @@ impl Default for PoolConfig {
             broadcast_enabled: false,
             broadcast_endorsements_channel_capacity: 2000,
             broadcast_operations_channel_capacity: 5000,
+            broadcast_denunciations_channel_capacity: 1000,
             genesis_timestamp: MassaTime::now().unwrap(),
             t0: T0,
             periods_per_cycle: PERIODS_PER_CYCLE,
@@ -12,12 +12,13 @@ use std::sync::{
 use massa_models::config::THREAD_COUNT;
 use massa_models::denunciation::{Denunciation, DenunciationPrecursor};
 use massa_models::{
-    block_id::BlockId, endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    address::Address, amount::Amount, block_id::BlockId, endorsement::EndorsementId,
+    operation::OperationId, slot::Slot,
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
-use crate::PoolController;
+use crate::{OperationPoolUsage, PoolController, PoolStatistics};
 
 /// Test tool to mock pool controller responses
 pub struct PoolEventReceiver(pub Receiver<MockPoolControllerMessage>);
@@ -76,6 +77,30 @@ pub enum MockPoolControllerMessage {
         /// Response channel
         response_tx: mpsc::Sender<usize>,
     },
+    /// Get operation fee percentiles
+    GetOperationFeePercentiles {
+        /// percentiles to compute
+        percentiles: Vec<u8>,
+        /// Response channel
+        response_tx: mpsc::Sender<Vec<Amount>>,
+    },
+    /// Get operation inclusion fee
+    GetOperationInclusionFee {
+        /// number of slots the operation should be included within
+        target_slots: u64,
+        /// Response channel
+        response_tx: mpsc::Sender<Option<Amount>>,
+    },
+    /// Get operation pool usage
+    GetOperationPoolUsage {
+        /// Response channel
+        response_tx: mpsc::Sender<OperationPoolUsage>,
+    },
+    /// Get pool statistics
+    GetPoolStatistics {
+        /// Response channel
+        response_tx: mpsc::Sender<PoolStatistics>,
+    },
     /// Get denunciation count
     GetDenunciationCount {
         /// Response channel
@@ -118,6 +143,9 @@ pub enum MockPoolControllerMessage {
 pub struct MockPoolController {
     q: Arc<Mutex<Sender<MockPoolControllerMessage>>>,
     last_final_cs_periods: Vec<u64>,
+    /// local denylist, kept in the mock so that the components checking it on each operation
+    /// do not need to answer through the channel
+    denied_senders: Arc<Mutex<Vec<Address>>>,
 }
 
 impl MockPoolController {
@@ -129,6 +157,7 @@ impl MockPoolController {
             Box::new(MockPoolController {
                 q: Arc::new(Mutex::new(tx)),
                 last_final_cs_periods: vec![0u64; THREAD_COUNT as usize],
+                denied_senders: Default::default(),
             }),
             PoolEventReceiver(rx),
         )
@@ -221,6 +250,52 @@ impl PoolController for MockPoolController {
         response_rx.recv().unwrap()
     }
 
+    fn get_operation_fee_percentiles(&self, percentiles: &[u8]) -> Vec<Amount> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetOperationFeePercentiles {
+                percentiles: percentiles.to_vec(),
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
+    fn get_operation_inclusion_fee(&self, target_slots: u64) -> Option<Amount> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetOperationInclusionFee {
+                target_slots,
+                response_tx,
+            })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
+    fn get_operation_pool_usage(&self) -> OperationPoolUsage {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetOperationPoolUsage { response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
+    fn get_pool_statistics(&self) -> PoolStatistics {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.q
+            .lock()
+            .unwrap()
+            .send(MockPoolControllerMessage::GetPoolStatistics { response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool> {
         let (response_tx, response_rx) = mpsc::channel();
         self.q
@@ -247,6 +322,36 @@ impl PoolController for MockPoolController {
         response_rx.recv().unwrap()
     }
 
+    fn get_denied_senders(&self) -> Vec<Address> {
+        self.denied_senders.lock().unwrap().clone()
+    }
+
+    fn contains_denied_senders(&self, addresses: &[Address]) -> Vec<bool> {
+        let denied_senders = self.denied_senders.lock().unwrap();
+        addresses
+            .iter()
+            .map(|addr| denied_senders.contains(addr))
+            .collect()
+    }
+
+    fn add_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()> {
+        let mut denied_senders = self.denied_senders.lock().unwrap();
+        for addr in addresses {
+            if !denied_senders.contains(&addr) {
+                denied_senders.push(addr);
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()> {
+        self.denied_senders
+            .lock()
+            .unwrap()
+            .retain(|addr| !addresses.contains(addr));
+        Ok(())
+    }
+
     fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         self.last_final_cs_periods = final_cs_periods.to_vec();
         self.q
@@ -0,0 +1,87 @@
+#[cfg(feature = "benchmarking")]
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[cfg(feature = "benchmarking")]
+fn criterion_benchmark(c: &mut Criterion) {
+    use massa_hash::Hash;
+    use massa_models::{
+        address::Address,
+        amount::Amount,
+        config::{MAX_BLOCK_SIZE, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK, THREAD_COUNT},
+        operation::OperationId,
+        slot::Slot,
+    };
+    use massa_pool_worker::{OperationInfo, OperationPriority, OperationPriorityIndex};
+    use massa_signature::KeyPair;
+    use massa_time::MassaTime;
+
+    /// Number of pending operations in the pool
+    const PENDING_OPERATIONS: u64 = 100_000;
+
+    /// This function is used to prepare the data for the benchmarks
+    /// It creates the pending operations, spread over all threads with varied fees and sizes.
+    fn prepare_operations() -> Vec<OperationInfo> {
+        let creator_address =
+            Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        (0..PENDING_OPERATIONS)
+            .map(|i| OperationInfo {
+                id: OperationId::from_bytes(&Hash::compute_from(&i.to_be_bytes()).into_bytes()),
+                size: 100 + (i % 200) as usize,
+                max_gas: (i % 7) * 1_000_000,
+                creator_address,
+                thread: (i % THREAD_COUNT as u64) as u8,
+                fee: Amount::from_raw((i * 7_919) % 1_000_003),
+                max_spending: Amount::zero(),
+                validity_period_range: 0..=10,
+                tags: Vec::new(),
+                received_at: MassaTime::from_millis(i),
+            })
+            .collect()
+    }
+
+    /// Indexes the operations as the pool does after a refresh, scored by fee density
+    fn index_operations(ops: &[OperationInfo]) -> OperationPriorityIndex {
+        let mut index = OperationPriorityIndex::new(THREAD_COUNT);
+        for (position, op_info) in ops.iter().enumerate() {
+            index.insert(
+                op_info.thread,
+                OperationPriority::scored(op_info.fee_density() as f32, position),
+            );
+        }
+        index
+    }
+
+    c.bench_function("Index 100k pending operations", |b| {
+        let ops = prepare_operations();
+        b.iter(|| index_operations(black_box(&ops)))
+    });
+
+    c.bench_function(
+        "Select block operations among 100k pending operations",
+        |b| {
+            let ops = prepare_operations();
+            let index = index_operations(&ops);
+            let slot = Slot::new(5, 3);
+            b.iter(|| {
+                index.select_block_operations(
+                    black_box(&ops),
+                    black_box(&slot),
+                    MAX_BLOCK_SIZE as usize,
+                    MAX_GAS_PER_BLOCK,
+                    MAX_OPERATIONS_PER_BLOCK,
+                )
+            })
+        },
+    );
+}
+
+#[cfg(feature = "benchmarking")]
+criterion_group!(benches, criterion_benchmark);
+
+#[cfg(feature = "benchmarking")]
+criterion_main!(benches);
+
+#[cfg(not(feature = "benchmarking"))]
+fn main() {
+    println!("Please use the `--features benchmarking` flag to run this benchmark.");
+}
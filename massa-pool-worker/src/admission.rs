@@ -0,0 +1,95 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Runs the operator admission hook on a dedicated thread so that a slow hook cannot stall the pool
+
+use massa_models::operation::SecureShareOperation;
+use massa_pool_exports::{OperationAdmissionPolicy, OperationAdmissionVerdict};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError, SyncSender, TrySendError};
+use tracing::warn;
+
+/// A batch of operations to check, along with the channel on which to send the verdicts
+type AdmissionRequest = (
+    Vec<SecureShareOperation>,
+    SyncSender<Vec<OperationAdmissionVerdict>>,
+);
+
+/// Admission hook runner
+pub(crate) struct AdmissionHookRunner {
+    /// admission policy (hook and dynamic config)
+    pub(crate) policy: OperationAdmissionPolicy,
+    /// requests sender, the hook thread stops when it is dropped
+    request_sender: SyncSender<AdmissionRequest>,
+}
+
+impl AdmissionHookRunner {
+    /// Spawns the hook thread
+    pub(crate) fn spawn(policy: OperationAdmissionPolicy) -> Self {
+        // at most one batch waits while the hook is busy, the next ones get the fallback verdict
+        let (request_sender, request_receiver) = sync_channel::<AdmissionRequest>(1);
+        let hook = policy.hook.clone();
+        std::thread::Builder::new()
+            .name("pool-admission-hook".into())
+            .spawn(move || {
+                while let Ok((operations, verdict_sender)) = request_receiver.recv() {
+                    let mut verdicts = hook.check_operations(&operations);
+                    verdicts.resize(operations.len(), OperationAdmissionVerdict::Accept);
+                    // the pool may have stopped waiting for this batch
+                    let _ = verdict_sender.send(verdicts);
+                }
+            })
+            .expect("failed to spawn thread : pool-admission-hook");
+        AdmissionHookRunner {
+            policy,
+            request_sender,
+        }
+    }
+
+    /// Returns one verdict per operation.
+    /// If the hook is still busy with a previous batch or does not answer within the timing budget,
+    /// all the operations get the fallback verdict of the configuration.
+    pub(crate) fn check_operations(
+        &self,
+        operations: Vec<SecureShareOperation>,
+    ) -> Vec<OperationAdmissionVerdict> {
+        let config = self.policy.get_config();
+        let count = operations.len();
+        let fallback = || {
+            let verdict = if config.veto_on_timeout {
+                OperationAdmissionVerdict::Veto("admission hook timed out".to_string())
+            } else {
+                OperationAdmissionVerdict::Accept
+            };
+            vec![verdict; count]
+        };
+        let (verdict_sender, verdict_receiver) = sync_channel(1);
+        match self.request_sender.try_send((operations, verdict_sender)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!(
+                    "pool admission hook is still busy, skipping it for {} operations",
+                    count
+                );
+                return fallback();
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("pool admission hook thread is unreachable");
+                return fallback();
+            }
+        }
+        match verdict_receiver.recv_timeout(config.timing_budget.to_duration()) {
+            Ok(verdicts) => verdicts,
+            Err(RecvTimeoutError::Timeout) => {
+                warn!(
+                    "pool admission hook exceeded its timing budget of {} ms for {} operations",
+                    config.timing_budget.to_millis(),
+                    count
+                );
+                fallback()
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                warn!("pool admission hook thread stopped while checking operations");
+                fallback()
+            }
+        }
+    }
+}
@@ -3,16 +3,24 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    address::Address, amount::Amount, block_id::BlockId, denunciation::Denunciation,
+    denunciation::DenunciationPrecursor, endorsement::EndorsementId, operation::OperationId,
+    slot::Slot,
+};
+use massa_pool_exports::{
+    FeePercentile, OperationPoolUsage, PoolConfig, PoolController, PoolManager, PoolStatistics,
+    POOL_STATISTICS_FEE_PERCENTILES,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::sync::mpsc::TrySendError;
 use std::sync::{mpsc::SyncSender, Arc};
 use tracing::{info, warn};
 
+use crate::denylist::SenderDenylist;
+use crate::persistence::save_pool;
+
 use crate::{
     denunciation_pool::DenunciationPool, endorsement_pool::EndorsementPool,
     operation_pool::OperationPool,
@@ -35,13 +43,15 @@ pub enum Command {
 #[derive(Clone)]
 pub struct PoolControllerImpl {
     /// Config
-    pub(crate) _config: PoolConfig,
+    pub(crate) config: PoolConfig,
     /// Shared reference to the operation pool
     pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
     /// Shared reference to the endorsement pool
     pub(crate) endorsement_pool: Arc<RwLock<EndorsementPool>>,
     /// Shared reference to the denunciation pool
     pub(crate) denunciation_pool: Arc<RwLock<DenunciationPool>>,
+    /// Shared reference to the local denylist of senders
+    pub(crate) denylist: Arc<RwLock<SenderDenylist>>,
     /// Operation write worker command sender
     pub(crate) operations_input_sender: SyncSender<Command>,
     /// Endorsement write worker command sender
@@ -204,6 +214,64 @@ impl PoolController for PoolControllerImpl {
         self.operation_pool.read().len()
     }
 
+    /// Get the fees of the operations in the pool at the given percentiles
+    fn get_operation_fee_percentiles(&self, percentiles: &[u8]) -> Vec<Amount> {
+        self.operation_pool.read().fee_percentiles(percentiles)
+    }
+
+    /// Get the fee needed to rank among the operations of the pool fitting in `target_slots` blocks
+    fn get_operation_inclusion_fee(&self, target_slots: u64) -> Option<Amount> {
+        self.operation_pool.read().inclusion_fee(target_slots)
+    }
+
+    /// Get the usage of the operation pool quotas
+    fn get_operation_pool_usage(&self) -> OperationPoolUsage {
+        self.operation_pool.read().usage()
+    }
+
+    /// Get the statistics of the operation and endorsement pools
+    fn get_pool_statistics(&self) -> PoolStatistics {
+        let (usage, fees, expiry_histogram) = {
+            let operation_pool = self.operation_pool.read();
+            (
+                operation_pool.usage(),
+                operation_pool.fee_percentiles(&POOL_STATISTICS_FEE_PERCENTILES),
+                operation_pool.expiry_histogram(),
+            )
+        };
+        let (endorsement_count, max_endorsement_count, endorsement_thread_counts) = {
+            let endorsement_pool = self.endorsement_pool.read();
+            (
+                endorsement_pool.len(),
+                endorsement_pool.capacity(),
+                endorsement_pool.thread_counts(),
+            )
+        };
+        PoolStatistics {
+            operation_count: usage.operation_count,
+            operation_byte_size: usage.byte_size,
+            fee_percentiles: POOL_STATISTICS_FEE_PERCENTILES
+                .iter()
+                .zip(fees)
+                .map(|(percentile, fee)| FeePercentile {
+                    percentile: *percentile,
+                    fee,
+                })
+                .collect(),
+            expiry_histogram,
+            endorsement_count,
+            max_endorsement_count,
+            endorsement_fill_rate: if max_endorsement_count == 0 {
+                0.0
+            } else {
+                endorsement_count as f64 / max_endorsement_count as f64
+            },
+            endorsement_thread_counts,
+            max_endorsement_count_per_thread: self.config.max_endorsements_pool_size_per_thread,
+            denunciations: self.denunciation_pool.read().metrics(),
+        }
+    }
+
     /// Check if the pool contains a list of endorsements. Returns one boolean per item.
     fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool> {
         let lck = self.endorsement_pool.read();
@@ -216,6 +284,30 @@ impl PoolController for PoolControllerImpl {
         operations.iter().map(|id| lck.contains(id)).collect()
     }
 
+    /// Get the senders in the local denylist
+    fn get_denied_senders(&self) -> Vec<Address> {
+        self.denylist.read().addresses()
+    }
+
+    /// Check if a list of senders is in the local denylist. Returns one boolean per item.
+    fn contains_denied_senders(&self, addresses: &[Address]) -> Vec<bool> {
+        let lck = self.denylist.read();
+        if lck.is_empty() {
+            return vec![false; addresses.len()];
+        }
+        addresses.iter().map(|addr| lck.contains(addr)).collect()
+    }
+
+    /// Add senders to the local denylist
+    fn add_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()> {
+        self.denylist.write().add(addresses)
+    }
+
+    /// Remove senders from the local denylist
+    fn remove_denied_senders(&self, addresses: Vec<Address>) -> std::io::Result<()> {
+        self.denylist.write().remove(addresses)
+    }
+
     /// Check if the pool contains a denunciation. Returns a boolean
     #[cfg(feature = "testing")]
     fn contains_denunciation(&self, denunciation: &Denunciation) -> bool {
@@ -256,6 +348,12 @@ pub struct PoolManagerImpl {
     pub(crate) endorsements_input_sender: SyncSender<Command>,
     /// Denunciations input data mpsc (used to stop the pool thread)
     pub(crate) denunciations_input_sender: SyncSender<Command>,
+    /// File where the operations and endorsements are saved when stopping, if persistence is enabled
+    pub(crate) persistence_path: Option<PathBuf>,
+    /// Shared reference to the operation pool, to save it when stopping
+    pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
+    /// Shared reference to the endorsement pool, to save it when stopping
+    pub(crate) endorsement_pool: Arc<RwLock<EndorsementPool>>,
 }
 
 impl PoolManager for PoolManagerImpl {
@@ -280,6 +378,18 @@ impl PoolManager for PoolManagerImpl {
                 .join()
                 .expect("denunciations pool thread panicked on try to join");
         }
+        if let Some(path) = &self.persistence_path {
+            let operations = self.operation_pool.read().get_operations();
+            let endorsements = self.endorsement_pool.read().get_endorsements();
+            match save_pool(path, &operations, &endorsements) {
+                Ok(()) => info!(
+                    "saved {} operations and {} endorsements of the pool",
+                    operations.len(),
+                    endorsements.len()
+                ),
+                Err(err) => warn!("could not save the pool to {}: {}", path.display(), err),
+            }
+        }
         info!("pool workers stopped");
     }
 }
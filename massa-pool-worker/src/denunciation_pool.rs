@@ -1,7 +1,14 @@
 //! Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use std::collections::{btree_map::Entry, BTreeMap};
-use tracing::{debug, info};
+//! Denunciation pool.
+//!
+//! The precursors are indexed by slot, each slot holding one entry for its block header and one per
+//! endorsement index, so that a known precursor is detected in constant time, before the costly checks.
+//! The slots are kept sorted so that the expired ones are pruned from the front. As the precursors
+//! outside of the denunciation expiry window are ignored, the memory used by the index stays bounded.
+
+use std::collections::BTreeMap;
+use tracing::{debug, info, trace};
 
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::slot::Slot;
@@ -10,7 +17,7 @@ use massa_models::{
     denunciation::{Denunciation, DenunciationPrecursor},
     timeslots::get_closest_slot_to_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{DenunciationPoolMetrics, PoolChannels, PoolConfig};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 
@@ -21,8 +28,12 @@ pub struct DenunciationPool {
     channels: PoolChannels,
     /// last consensus final periods, per thread
     last_cs_final_periods: Vec<u64>,
-    /// Internal cache for denunciations
-    denunciations_cache: BTreeMap<DenunciationIndex, DenunciationStatus>,
+    /// precursors and denunciations, indexed by slot
+    denunciations_cache: BTreeMap<Slot, SlotDenunciations>,
+    /// number of denunciations in `denunciations_cache`
+    denunciation_count: usize,
+    /// precursor counters, since the node started
+    metrics: DenunciationPoolMetrics,
 }
 
 impl DenunciationPool {
@@ -32,39 +43,55 @@ impl DenunciationPool {
             channels,
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             denunciations_cache: Default::default(),
+            denunciation_count: 0,
+            metrics: Default::default(),
         }
     }
 
     /// Get the number of stored elements
     pub fn len(&self) -> usize {
-        self.denunciations_cache
-            .iter()
-            .filter(|(_, de_st)| matches!(*de_st, DenunciationStatus::DenunciationEmitted(..)))
-            .count()
+        self.denunciation_count
+    }
+
+    /// Get the state of the precursor index and its counters
+    pub fn metrics(&self) -> DenunciationPoolMetrics {
+        DenunciationPoolMetrics {
+            tracked_slot_count: self.denunciations_cache.len(),
+            pending_precursor_count: self
+                .denunciations_cache
+                .values()
+                .map(SlotDenunciations::pending_count)
+                .sum(),
+            denunciation_count: self.denunciation_count,
+            ..self.metrics
+        }
     }
 
     /// Checks whether an element is stored in the pool - only used in unit tests for now
     #[cfg(feature = "testing")]
     pub fn contains(&self, denunciation: &Denunciation) -> bool {
         self.denunciations_cache
-            .iter()
-            .find(|(_, de_st)| match *de_st {
-                DenunciationStatus::Accumulating(_) => false,
-                DenunciationStatus::DenunciationEmitted(de) => de == denunciation,
+            .get(denunciation.get_slot())
+            .map_or(false, |slot_denunciations| {
+                slot_denunciations.iter().any(|status| match status {
+                    DenunciationStatus::Accumulating(_) => false,
+                    DenunciationStatus::DenunciationEmitted(de) => de == denunciation,
+                })
             })
-            .is_some()
     }
 
     /// Add a denunciation precursor to the pool - can lead to a Denunciation creation
     /// Note that the Denunciation is stored in the denunciation pool internal cache
     pub fn add_denunciation_precursor(&mut self, denunciation_precursor: DenunciationPrecursor) {
-        let slot = denunciation_precursor.get_slot();
+        self.metrics.received_precursor_count += 1;
+        let slot = *denunciation_precursor.get_slot();
 
         // Do some checkups before adding the denunciation precursor
 
         if slot.period <= self.config.last_start_period {
             // denunciation created before last restart (can be 0 or >= 0 after a network restart) - ignored
             // Note: as we use '<=', also ignore denunciation created for genesis block
+            self.metrics.rejected_precursor_count += 1;
             return;
         }
 
@@ -87,11 +114,37 @@ impl DenunciationPool {
             &self.config.denunciation_expire_periods,
         ) {
             // too old - cannot be denounced anymore
+            self.metrics.rejected_precursor_count += 1;
             return;
         }
 
         if slot.period.saturating_sub(slot_now.period) > self.config.denunciation_expire_periods {
             // too much in the future - ignored
+            self.metrics.rejected_precursor_count += 1;
+            return;
+        }
+
+        let key = DenunciationIndex::from(&denunciation_precursor);
+        if matches!(key, DenunciationIndex::Endorsement { index, .. } if index >= self.config.max_block_endorsement_count)
+        {
+            // endorsement index out of range
+            self.metrics.rejected_precursor_count += 1;
+            return;
+        }
+
+        // Ignore the precursors already known before any costly check
+        let known = match self
+            .denunciations_cache
+            .get(&slot)
+            .and_then(|slot_denunciations| slot_denunciations.get(&key))
+        {
+            Some(DenunciationStatus::Accumulating(de_p)) => *de_p == denunciation_precursor,
+            // Already 2 entries - so a Denunciation has already been created
+            Some(DenunciationStatus::DenunciationEmitted(..)) => true,
+            None => false,
+        };
+        if known {
+            self.metrics.duplicate_precursor_count += 1;
             return;
         }
 
@@ -99,7 +152,55 @@ impl DenunciationPool {
         // Note: If the public key of the header creator is not checked to match the PoS,
         //       someone can spam with headers coming from various non-PoS-drawn pubkeys
         //       and cause a problem
-        match &denunciation_precursor {
+        if !self.is_selected(&denunciation_precursor) {
+            self.metrics.rejected_precursor_count += 1;
+            return;
+        }
+
+        let max_block_endorsement_count = self.config.max_block_endorsement_count;
+        let entry = self
+            .denunciations_cache
+            .entry(slot)
+            .or_insert_with(|| SlotDenunciations::new(max_block_endorsement_count))
+            .get_mut(&key);
+        let denunciation_: Option<Denunciation> = match entry {
+            Some(DenunciationStatus::Accumulating(de_p)) => {
+                match Denunciation::try_from((&*de_p, &denunciation_precursor)) {
+                    Ok(de) => {
+                        *entry = Some(DenunciationStatus::DenunciationEmitted(de.clone()));
+                        Some(de)
+                    }
+                    Err(e) => {
+                        debug!(
+                            "Denunciation pool cannot create denunciation from endorsements: {}",
+                            e
+                        );
+                        None
+                    }
+                }
+            }
+            Some(DenunciationStatus::DenunciationEmitted(..)) => None,
+            None => {
+                *entry = Some(DenunciationStatus::Accumulating(denunciation_precursor));
+                None
+            }
+        };
+
+        if let Some(denunciation) = denunciation_ {
+            self.denunciation_count += 1;
+            info!("Created a new denunciation : {:?}", denunciation);
+            // Broadcast the denunciation to active channel subscribers.
+            if self.config.broadcast_enabled {
+                if let Err(err) = self.channels.denunciation_sender.send(denunciation) {
+                    trace!("error, failed to broadcast denunciation: {}", err);
+                }
+            }
+        }
+    }
+
+    /// Check that the creator of the denounced item was selected to produce it
+    fn is_selected(&self, denunciation_precursor: &DenunciationPrecursor) -> bool {
+        match denunciation_precursor {
             DenunciationPrecursor::Endorsement(de_p) => {
                 // Get selected address from selector and check
                 let selected = self.channels.selector.get_selection(de_p.slot);
@@ -109,16 +210,17 @@ impl DenunciationPool {
                             let a = Address::from_public_key(&de_p.public_key);
                             if *address != a {
                                 debug!("Denunciation pool received a secure share endorsement but address was not selected: received {} but expected {} ({})", address, a, de_p.public_key);
-                                return;
+                                return false;
                             }
+                            true
                         } else {
                             debug!("Denunciation pool could not get selected address for endorsements at index");
-                            return;
+                            false
                         }
                     }
                     Err(e) => {
                         debug!("Cannot get producer from selector: {}", e);
-                        return;
+                        false
                     }
                 }
             }
@@ -130,96 +232,64 @@ impl DenunciationPool {
                             != Address::from_public_key(denunciation_precursor.get_public_key())
                         {
                             debug!("Denunciation pool received a secured header but address was not selected");
-                            return;
+                            return false;
                         }
+                        true
                     }
                     Err(e) => {
                         debug!("Cannot get producer from selector: {}", e);
-                        return;
+                        false
                     }
                 }
             }
         }
-
-        let key = DenunciationIndex::from(&denunciation_precursor);
-
-        let denunciation_: Option<Denunciation> = match self.denunciations_cache.entry(key) {
-            Entry::Occupied(mut eo) => match eo.get_mut() {
-                DenunciationStatus::Accumulating(de_p_) => {
-                    let de_p: &DenunciationPrecursor = de_p_;
-                    if *de_p != denunciation_precursor {
-                        match Denunciation::try_from((de_p, &denunciation_precursor)) {
-                            Ok(de) => {
-                                eo.insert(DenunciationStatus::DenunciationEmitted(de.clone()));
-                                Some(de)
-                            }
-                            Err(e) => {
-                                debug!("Denunciation pool cannot create denunciation from endorsements: {}", e);
-                                None
-                            }
-                        }
-                    } else {
-                        // same denunciation precursor - do nothing
-                        None
-                    }
-                }
-                DenunciationStatus::DenunciationEmitted(..) => {
-                    // Already 2 entries - so a Denunciation has already been created
-                    None
-                }
-            },
-            Entry::Vacant(ev) => {
-                ev.insert(DenunciationStatus::Accumulating(denunciation_precursor));
-                None
-            }
-        };
-
-        if let Some(denunciation) = denunciation_ {
-            info!("Created a new denunciation : {:?}", denunciation);
-        }
-        self.cleanup_caches();
     }
 
-    /// cleanup internal cache, removing too old denunciation
+    /// cleanup internal cache, removing the slots that are too old to be denounced
     fn cleanup_caches(&mut self) {
-        self.denunciations_cache.retain(|de_idx, _| {
-            let slot = de_idx.get_slot();
-            // Check add_denunciation_precursor notes about last_cs_final_periods.iter().min()
-            !Denunciation::is_expired(
-                &slot.period,
-                self.last_cs_final_periods.iter().min().unwrap_or(&0),
+        // Check add_denunciation_precursor notes about last_cs_final_periods.iter().min()
+        let min_final_period = *self.last_cs_final_periods.iter().min().unwrap_or(&0);
+        while let Some(entry) = self.denunciations_cache.first_entry() {
+            if !Denunciation::is_expired(
+                &entry.key().period,
+                &min_final_period,
                 &self.config.denunciation_expire_periods,
-            )
-        });
+            ) {
+                break;
+            }
+            self.denunciation_count -= entry.remove().denunciation_count();
+        }
     }
 
     /// get denunciations for block creation
     pub fn get_block_denunciations(&self, target_slot: &Slot) -> Vec<Denunciation> {
         let mut res = Vec::with_capacity(self.config.max_denunciations_per_block_header as usize);
-        for (de_idx, de_status) in &self.denunciations_cache {
-            if let DenunciationStatus::DenunciationEmitted(de) = de_status {
-                // Checks
-                // 1. the denunciation has not been executed already
-                // 2. Denounced item slot is equal or before target slot of block header
-                // 3. Denounced item slot is not too old
-                let de_slot = de.get_slot();
-                if !self
-                    .channels
-                    .execution_controller
-                    .is_denunciation_executed(de_idx)
-                    && de_slot <= target_slot
-                    && !Denunciation::is_expired(
-                        &de_slot.period,
-                        &target_slot.period,
-                        &self.config.denunciation_expire_periods,
-                    )
-                {
-                    res.push(de.clone());
-                }
+        // Checks
+        // 1. Denounced item slot is equal or before target slot of block header
+        // 2. Denounced item slot is not too old
+        // 3. the denunciation has not been executed already
+        for (de_slot, slot_denunciations) in self.denunciations_cache.range(..=*target_slot) {
+            if Denunciation::is_expired(
+                &de_slot.period,
+                &target_slot.period,
+                &self.config.denunciation_expire_periods,
+            ) {
+                continue;
             }
+            for de_status in slot_denunciations.iter() {
+                if let DenunciationStatus::DenunciationEmitted(de) = de_status {
+                    if !self
+                        .channels
+                        .execution_controller
+                        .is_denunciation_executed(&DenunciationIndex::from(de))
+                    {
+                        res.push(de.clone());
+                    }
+                }
 
-            if res.len() >= self.config.max_denunciations_per_block_header as usize {
-                break;
+                if res.len() >= self.config.max_denunciations_per_block_header as usize {
+                    return res;
+                }
             }
         }
         res
@@ -263,3 +333,63 @@ enum DenunciationStatus {
     /// 2 DenunciationPrecursor received, a Denunciation was then created
     DenunciationEmitted(Denunciation),
 }
+
+/// Denunciation statuses of the items produced at a slot
+struct SlotDenunciations {
+    /// status of the block header
+    block_header: Option<DenunciationStatus>,
+    /// status of the endorsements, by endorsement index
+    endorsements: Vec<Option<DenunciationStatus>>,
+}
+
+impl SlotDenunciations {
+    fn new(max_block_endorsement_count: u32) -> Self {
+        SlotDenunciations {
+            block_header: None,
+            endorsements: std::iter::repeat_with(|| None)
+                .take(max_block_endorsement_count as usize)
+                .collect(),
+        }
+    }
+
+    /// Status of the denounced item, if any precursor was received for it
+    fn get(&self, key: &DenunciationIndex) -> Option<&DenunciationStatus> {
+        match key {
+            DenunciationIndex::BlockHeader { .. } => self.block_header.as_ref(),
+            DenunciationIndex::Endorsement { index, .. } => self
+                .endorsements
+                .get(*index as usize)
+                .and_then(Option::as_ref),
+        }
+    }
+
+    /// Mutable status of the denounced item.
+    /// Panics if the endorsement index is out of range, which must be checked beforehand
+    fn get_mut(&mut self, key: &DenunciationIndex) -> &mut Option<DenunciationStatus> {
+        match key {
+            DenunciationIndex::BlockHeader { .. } => &mut self.block_header,
+            DenunciationIndex::Endorsement { index, .. } => &mut self.endorsements[*index as usize],
+        }
+    }
+
+    /// Statuses of the denounced items, in the order of their `DenunciationIndex`
+    fn iter(&self) -> impl Iterator<Item = &DenunciationStatus> {
+        self.block_header
+            .iter()
+            .chain(self.endorsements.iter().flatten())
+    }
+
+    /// Number of items with a single precursor
+    fn pending_count(&self) -> usize {
+        self.iter()
+            .filter(|status| matches!(status, DenunciationStatus::Accumulating(_)))
+            .count()
+    }
+
+    /// Number of items denounced
+    fn denunciation_count(&self) -> usize {
+        self.iter()
+            .filter(|status| matches!(status, DenunciationStatus::DenunciationEmitted(_)))
+            .count()
+    }
+}
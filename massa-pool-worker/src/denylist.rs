@@ -0,0 +1,80 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Local denylist of operation senders.
+//!
+//! The operations created by a denied address are rejected when they enter the pool,
+//! the pending ones are evicted at the next refresh, and the node does not propagate them.
+//! This is a local policy of the node: the operations remain valid for the rest of the network,
+//! and can still be included in the blocks produced by other nodes.
+//!
+//! The list is stored as a JSON array of addresses, rewritten every time it is updated.
+
+use massa_models::{address::Address, prehash::PreHashSet};
+use std::{io, path::PathBuf};
+
+/// Addresses whose operations the node refuses to pool and propagate
+#[derive(Default)]
+pub(crate) struct SenderDenylist {
+    /// file the list is saved to, if any
+    path: Option<PathBuf>,
+    /// denied addresses
+    addresses: PreHashSet<Address>,
+}
+
+impl SenderDenylist {
+    /// Loads the list saved at `path`, if the file exists
+    pub(crate) fn load(path: Option<PathBuf>) -> io::Result<Self> {
+        let mut addresses = PreHashSet::default();
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            let content = std::fs::read_to_string(path)?;
+            let list: Vec<Address> = serde_json::from_str(&content)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            addresses.extend(list);
+        }
+        Ok(SenderDenylist { path, addresses })
+    }
+
+    /// Whether `address` is denied
+    pub(crate) fn contains(&self, address: &Address) -> bool {
+        self.addresses.contains(address)
+    }
+
+    /// Whether the list is empty
+    pub(crate) fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Get the denied addresses, sorted
+    pub(crate) fn addresses(&self) -> Vec<Address> {
+        let mut addresses: Vec<Address> = self.addresses.iter().copied().collect();
+        addresses.sort_unstable();
+        addresses
+    }
+
+    /// Adds `addresses` to the list and saves it
+    pub(crate) fn add(&mut self, addresses: Vec<Address>) -> io::Result<()> {
+        self.addresses.extend(addresses);
+        self.save()
+    }
+
+    /// Removes `addresses` from the list and saves it
+    pub(crate) fn remove(&mut self, addresses: Vec<Address>) -> io::Result<()> {
+        for address in &addresses {
+            self.addresses.remove(address);
+        }
+        self.save()
+    }
+
+    /// Writes the list to its file, if any
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&self.addresses())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        std::fs::write(path, content)
+    }
+}
@@ -2,12 +2,14 @@
 
 use massa_models::{
     block_id::BlockId,
-    endorsement::EndorsementId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashSet},
     slot::Slot,
+    timeslots::get_latest_block_slot_at_timestamp,
 };
 use massa_pool_exports::{PoolChannels, PoolConfig};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{
@@ -63,23 +65,69 @@ impl EndorsementPool {
         self.storage.get_endorsement_refs().len()
     }
 
+    /// Get the max number of stored elements, across all threads
+    pub fn capacity(&self) -> usize {
+        self.config.max_endorsements_pool_size_per_thread * self.config.thread_count as usize
+    }
+
+    /// Get the number of stored elements of each thread
+    pub fn thread_counts(&self) -> Vec<usize> {
+        self.endorsements_sorted.iter().map(BTreeMap::len).collect()
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &EndorsementId) -> bool {
         self.storage.get_endorsement_refs().contains(id)
     }
 
+    /// Get all the endorsements of the pool
+    pub(crate) fn get_endorsements(&self) -> Vec<SecureShareEndorsement> {
+        let endorsements = self.storage.read_endorsements();
+        self.storage
+            .get_endorsement_refs()
+            .iter()
+            .filter_map(|id| endorsements.get(id).cloned())
+            .collect()
+    }
+
     /// notify of new final CS periods
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final CS period counter
         self.last_cs_final_periods = final_cs_periods.to_vec();
 
         // remove all endorsements whose periods <= last_cs_final_periods[endorsement.thread]
+        self.drop_endorsements_until(final_cs_periods);
+    }
+
+    /// Drop the endorsements whose inclusion slot is more than `endorsement_retention_periods` periods old:
+    /// this node can no longer produce a block including them, even if their slot is not final yet
+    pub(crate) fn cleanup(&mut self) {
+        let now = MassaTime::now().expect("could not get current time");
+        let Ok(Some(current_slot)) = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            now,
+        ) else {
+            return;
+        };
+        let Some(max_period) = current_slot
+            .period
+            .checked_sub(self.config.endorsement_retention_periods.saturating_add(1))
+        else {
+            return;
+        };
+        self.drop_endorsements_until(&vec![max_period; self.config.thread_count as usize]);
+    }
+
+    /// Drop the endorsements whose inclusion period is at most `max_periods[thread]`
+    fn drop_endorsements_until(&mut self, max_periods: &[u64]) {
         let mut removed: PreHashSet<EndorsementId> = Default::default();
         for thread in 0..self.config.thread_count {
             while let Some((&(inclusion_slot, index, block_id), &endo_id)) =
                 self.endorsements_sorted[thread as usize].first_key_value()
             {
-                if inclusion_slot.period <= self.last_cs_final_periods[thread as usize] {
+                if inclusion_slot.period <= max_periods[thread as usize] {
                     self.endorsements_sorted[thread as usize].pop_first();
                     self.endorsements_indexed
                         .remove(&(inclusion_slot, index, block_id))
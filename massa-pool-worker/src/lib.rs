@@ -10,15 +10,28 @@
 #![feature(hash_drain_filter)]
 #![feature(deadline_api)]
 
+mod admission;
 mod controller_impl;
 mod denunciation_pool;
+mod denylist;
 mod endorsement_pool;
 mod operation_pool;
+mod persistence;
+mod priority;
+mod quota;
 mod types;
 mod worker;
 
 pub use worker::start_pool_controller;
 
+#[cfg(feature = "benchmarking")]
+pub use priority::{OperationPriority, OperationPriorityIndex};
+#[cfg(feature = "benchmarking")]
+pub use types::OperationInfo;
+
+#[cfg(feature = "benchmarking")]
+use criterion as _;
+
 #[cfg(test)]
 use mockall as _;
 
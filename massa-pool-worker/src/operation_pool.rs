@@ -3,19 +3,33 @@
 use massa_models::{
     address::Address,
     amount::Amount,
-    operation::OperationId,
+    operation::{OperationId, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{
+    ExpiryBucket, OperationAdmissionPolicy, OperationAdmissionVerdict, OperationEvictionReason,
+    OperationLifecycleEvent, OperationLifecycleStatus, OperationPoolUsage, PoolChannels,
+    PoolConfig,
+};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
-use tracing::debug;
+use std::{
+    cmp::max,
+    cmp::Ordering,
+    cmp::PartialOrd,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+use tracing::{debug, trace};
 
+use crate::admission::AdmissionHookRunner;
+use crate::denylist::SenderDenylist;
+use crate::priority::{OperationPriority, OperationPriorityIndex};
+use crate::quota::{pool_usage, quota_evictions};
 use crate::types::OperationInfo;
 
 pub struct OperationPool {
@@ -25,6 +39,12 @@ pub struct OperationPool {
     /// operations map
     sorted_ops: Vec<OperationInfo>,
 
+    /// positions of the operations in `sorted_ops`, by thread and priority, for block production
+    priority_index: OperationPriorityIndex,
+
+    /// ops removed after being executed in a candidate slot, waiting to be executed in a final slot, with their sender
+    included_ops: PreHashMap<OperationId, Address>,
+
     /// storage instance
     pub(crate) storage: Storage,
 
@@ -36,6 +56,12 @@ pub struct OperationPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// optional operator-defined admission hook
+    admission_hook: Option<AdmissionHookRunner>,
+
+    /// local denylist of senders, shared with the controller
+    denylist: Arc<RwLock<SenderDenylist>>,
 }
 
 impl OperationPool {
@@ -44,14 +70,20 @@ impl OperationPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        admission_policy: Option<OperationAdmissionPolicy>,
+        denylist: Arc<RwLock<SenderDenylist>>,
     ) -> Self {
         OperationPool {
             sorted_ops: Default::default(),
+            priority_index: OperationPriorityIndex::new(config.thread_count),
+            included_ops: Default::default(),
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
             config,
             storage: storage.clone_without_refs(),
             channels,
             wallet,
+            admission_hook: admission_policy.map(AdmissionHookRunner::spawn),
+            denylist,
         }
     }
 
@@ -95,8 +127,9 @@ impl OperationPool {
         pos_draws
     }
 
-    /// Returns the list of executed ops with a boolean indicating whether they are executed as final.
-    fn get_execution_statuses(&self) -> PreHashMap<OperationId, bool> {
+    /// Returns the list of executed ops with whether their final execution succeeded,
+    /// `None` if they are only executed in candidate slots.
+    fn get_execution_statuses(&self) -> PreHashMap<OperationId, Option<bool>> {
         let op_ids: Vec<OperationId> = self.sorted_ops.iter().map(|op_info| op_info.id).collect();
         self.channels
             .execution_controller
@@ -105,8 +138,8 @@ impl OperationPool {
             .zip(op_ids.into_iter())
             .filter_map(
                 |((spec_status, final_status), op_id)| match (spec_status, final_status) {
-                    (Some(_), Some(_)) => Some((op_id, true)),
-                    (Some(_), None) => Some((op_id, false)),
+                    (Some(_), Some(success)) => Some((op_id, Some(success))),
+                    (Some(_), None) => Some((op_id, None)),
                     _ => None,
                 },
             )
@@ -133,57 +166,176 @@ impl OperationPool {
             .collect()
     }
 
+    /// Broadcasts a change in the lifecycle of an operation
+    fn notify_lifecycle(
+        &self,
+        operation_id: OperationId,
+        creator: Address,
+        status: OperationLifecycleStatus,
+    ) {
+        if !self.config.broadcast_enabled {
+            return;
+        }
+        if let Err(err) = self
+            .channels
+            .operation_lifecycle_sender
+            .send(OperationLifecycleEvent {
+                operation_id,
+                creator,
+                status,
+            })
+        {
+            trace!(
+                "error, failed to broadcast the lifecycle of operation {}: {}",
+                operation_id,
+                err
+            );
+        }
+    }
+
+    /// Removes the given ops from the pool and the storage, and notifies their new status
+    fn remove_ops(&mut self, removed: PreHashMap<OperationId, OperationLifecycleStatus>) {
+        if removed.is_empty() {
+            return;
+        }
+        let mut removed_info = Vec::with_capacity(removed.len());
+        self.sorted_ops
+            .retain(|op_info| match removed.get(&op_info.id) {
+                Some(status) => {
+                    removed_info.push((op_info.id, op_info.creator_address, *status));
+                    false
+                }
+                None => true,
+            });
+        // drop from storage
+        self.storage
+            .drop_operation_refs(&removed.keys().copied().collect());
+        for (op_id, creator, status) in removed_info {
+            if status == OperationLifecycleStatus::Included && self.config.broadcast_enabled {
+                self.included_ops.insert(op_id, creator);
+            }
+            self.notify_lifecycle(op_id, creator, status);
+        }
+    }
+
+    /// Notifies the included ops executed in a final slot since the last refresh,
+    /// and forgets the ones whose candidate execution was cancelled
+    fn refresh_included_ops(&mut self) {
+        if self.included_ops.is_empty() {
+            return;
+        }
+        let op_ids: Vec<OperationId> = self.included_ops.keys().copied().collect();
+        let statuses = self
+            .channels
+            .execution_controller
+            .get_ops_exec_status(&op_ids);
+        for (op_id, (spec_status, final_status)) in op_ids.into_iter().zip(statuses) {
+            match (spec_status, final_status) {
+                (_, Some(success)) => {
+                    if let Some(creator) = self.included_ops.remove(&op_id) {
+                        self.notify_lifecycle(
+                            op_id,
+                            creator,
+                            OperationLifecycleStatus::Final { success },
+                        );
+                    }
+                }
+                (None, None) => {
+                    self.included_ops.remove(&op_id);
+                }
+                (Some(_), None) => {}
+            }
+        }
+    }
+
     /// Filter out ops that are not of interest.
     fn prefilter_ops(
         &mut self,
-        exec_statuses: &PreHashMap<OperationId, bool>,
+        exec_statuses: &PreHashMap<OperationId, Option<bool>>,
         pos_draws: &BTreeSet<Slot>,
         sender_balances: &PreHashMap<Address, Amount>,
     ) {
-        let mut removed = PreHashSet::default();
-        self.sorted_ops.retain(|op_info| {
+        let mut removed = PreHashMap::default();
+        let denylist = self.denylist.read();
+        for op_info in &self.sorted_ops {
+            // filter out ops created by a sender denied by the local policy
+            if denylist.contains(&op_info.creator_address) {
+                removed.insert(
+                    op_info.id,
+                    OperationLifecycleStatus::Evicted {
+                        reason: OperationEvictionReason::Denylisted,
+                    },
+                );
+                continue;
+            }
+
             // filter out ops that use too much resources
-            let mut retain = (op_info.max_gas <= self.config.max_block_gas)
-                && (op_info.size <= self.config.max_block_size as usize);
+            if op_info.max_gas > self.config.max_block_gas
+                || op_info.size > self.config.max_block_size as usize
+            {
+                removed.insert(
+                    op_info.id,
+                    OperationLifecycleStatus::Evicted {
+                        reason: OperationEvictionReason::TooLarge,
+                    },
+                );
+                continue;
+            }
 
             // filter out ops that are not valid during our PoS draws
-            if retain {
-                retain = pos_draws.iter().any(|slot| {
-                    op_info.thread == slot.thread
-                        && op_info.validity_period_range.contains(&slot.period)
-                });
+            if !pos_draws.iter().any(|slot| {
+                op_info.thread == slot.thread
+                    && op_info.validity_period_range.contains(&slot.period)
+            }) {
+                // the ops that can no longer be included in a non-final block are expired
+                let status = if *op_info.validity_period_range.end()
+                    <= self.last_cs_final_periods[op_info.thread as usize]
+                {
+                    OperationLifecycleStatus::Expired
+                } else {
+                    OperationLifecycleStatus::Evicted {
+                        reason: OperationEvictionReason::NoProductionSlot,
+                    }
+                };
+                removed.insert(op_info.id, status);
+                continue;
             }
 
             // filter out ops that have been executed in final or candidate slots
             // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
-            if retain {
-                retain = !exec_statuses.contains_key(&op_info.id);
-            }
-
-            // filter out ops that spend more than the sender's balance
-            if retain {
-                retain = match sender_balances.get(&op_info.creator_address) {
-                    Some(v) => &op_info.max_spending <= v,
-                    None => false, // filter out ops for which the sender does not exist
+            if let Some(final_status) = exec_statuses.get(&op_info.id) {
+                let status = match final_status {
+                    Some(success) => OperationLifecycleStatus::Final { success: *success },
+                    None => OperationLifecycleStatus::Included,
                 };
+                removed.insert(op_info.id, status);
+                continue;
             }
 
-            if !retain {
-                removed.insert(op_info.id);
-                return false;
+            // filter out ops that spend more than the sender's balance
+            // (or for which the sender does not exist)
+            let affordable = sender_balances
+                .get(&op_info.creator_address)
+                .map_or(false, |balance| &op_info.max_spending <= balance);
+            if !affordable {
+                removed.insert(
+                    op_info.id,
+                    OperationLifecycleStatus::Evicted {
+                        reason: OperationEvictionReason::InsufficientBalance,
+                    },
+                );
             }
-            true
-        });
-        // drop from storage
-        self.storage.drop_operation_refs(&removed);
+        }
+        drop(denylist);
+        self.remove_ops(removed);
     }
 
     /// Eliminate all operations that would cause a sender balance overflow.
     /// Assumes that the ops are sorted by ascending score.
     fn eliminate_balance_overflows(&mut self, sender_balances: &PreHashMap<Address, Amount>) {
         let mut balance_cache = PreHashMap::default();
-        let mut removed = PreHashSet::default();
-        self.sorted_ops.retain(|op_info| {
+        let mut removed = PreHashMap::default();
+        for op_info in &self.sorted_ops {
             let balance = balance_cache
                 .entry(op_info.creator_address)
                 .or_insert_with(|| {
@@ -193,42 +345,69 @@ impl OperationPool {
                         .unwrap_or_default()
                 });
             match balance.checked_sub(op_info.max_spending) {
-                Some(v) => {
-                    *balance = v;
-                    true
-                }
+                Some(v) => *balance = v,
                 None => {
-                    removed.insert(op_info.id);
-                    false
+                    removed.insert(
+                        op_info.id,
+                        OperationLifecycleStatus::Evicted {
+                            reason: OperationEvictionReason::InsufficientBalance,
+                        },
+                    );
                 }
             }
-        });
-        // drop from storage
-        self.storage.drop_operation_refs(&removed);
+        }
+        self.remove_ops(removed);
+    }
+
+    /// Evicts operations, following the eviction policy, until the per-sender and total size quotas are respected
+    fn enforce_quotas(&mut self) {
+        let evicted = quota_evictions(&self.sorted_ops, &self.config);
+        if evicted.is_empty() {
+            return;
+        }
+        debug!(
+            "{} operations evicted from the pool to respect its quotas",
+            evicted.len()
+        );
+        self.remove_ops(
+            evicted
+                .into_iter()
+                .map(|op_id| {
+                    (
+                        op_id,
+                        OperationLifecycleStatus::Evicted {
+                            reason: OperationEvictionReason::Quota,
+                        },
+                    )
+                })
+                .collect(),
+        );
     }
 
     /// Truncates the container to the max allowed size
     fn truncate_container(&mut self) {
         if self.sorted_ops.len() > self.config.max_operation_pool_size {
-            let mut removed = PreHashSet::default();
-            for op_info in self
+            let removed = self
                 .sorted_ops
                 .iter()
                 .skip(self.config.max_operation_pool_size)
-            {
-                removed.insert(op_info.id);
-            }
-            self.sorted_ops
-                .truncate(self.config.max_operation_pool_size);
-            // drop from storage
-            self.storage.drop_operation_refs(&removed);
+                .map(|op_info| {
+                    (
+                        op_info.id,
+                        OperationLifecycleStatus::Evicted {
+                            reason: OperationEvictionReason::PoolFull,
+                        },
+                    )
+                })
+                .collect();
+            self.remove_ops(removed);
         }
     }
 
     /// Score the operations
     fn score_operations(
         &self,
-        _exec_statuses: &PreHashMap<OperationId, bool>,
+        _exec_statuses: &PreHashMap<OperationId, Option<bool>>,
         pos_draws: &BTreeSet<Slot>,
     ) -> PreHashMap<OperationId, f32> {
         let now = MassaTime::now().expect("could not get current time");
@@ -241,6 +420,13 @@ impl OperationPool {
         .expect("could not get current slot")
         .map_or(0, |s| s.period);
 
+        // score multipliers of the tags given by the admission hook
+        let tag_score_factors = self
+            .admission_hook
+            .as_ref()
+            .map(|hook| hook.policy.get_config().tag_score_factors)
+            .unwrap_or_default();
+
         let mut scores = PreHashMap::with_capacity(self.sorted_ops.len());
         for op_info in &self.sorted_ops {
             // fee factor
@@ -303,8 +489,15 @@ impl OperationPool {
             };
             */
 
+            // operator-defined tag factor
+            let tag_factor: f32 = op_info
+                .tags
+                .iter()
+                .filter_map(|tag| tag_score_factors.get(tag))
+                .product();
+
             // compute the score as being the product of all the factors and the fee
-            let score = fee_factor * resource_factor * inclusion_factor;
+            let score = fee_factor * resource_factor * inclusion_factor * tag_factor;
             //  * reexecution_factor; // TODO: re-execution followup
 
             // store the score
@@ -315,6 +508,9 @@ impl OperationPool {
 
     /// Refresh the pool
     pub(crate) fn refresh(&mut self) {
+        // follow the ops removed after their inclusion until their final execution
+        self.refresh_included_ops();
+
         // get PoS draws
         let pos_draws = self.get_pos_draws();
 
@@ -343,8 +539,19 @@ impl OperationPool {
         // eliminate balance overflows in sorted ops
         self.eliminate_balance_overflows(&sender_balances);
 
+        // eliminate quota overflows
+        self.enforce_quotas();
+
         // eliminate container size overflows
         self.truncate_container();
+
+        // index the remaining ops for block production
+        self.priority_index.clear();
+        for (index, op_info) in self.sorted_ops.iter().enumerate() {
+            let score = scores.get(&op_info.id).copied().unwrap_or_default();
+            self.priority_index
+                .insert(op_info.thread, OperationPriority::scored(score, index));
+        }
     }
 
     /// Get the number of stored elements
@@ -352,11 +559,74 @@ impl OperationPool {
         self.sorted_ops.len()
     }
 
+    /// Get the fees of the stored operations at the given percentiles (nearest rank).
+    /// Returns an empty list if no operation is stored.
+    pub fn fee_percentiles(&self, percentiles: &[u8]) -> Vec<Amount> {
+        if self.sorted_ops.is_empty() {
+            return Vec::new();
+        }
+        let mut fees: Vec<Amount> = self.sorted_ops.iter().map(|op_info| op_info.fee).collect();
+        fees.sort_unstable();
+        percentiles
+            .iter()
+            .map(|percentile| {
+                let rank = (fees.len() * (*percentile).min(100) as usize + 99) / 100;
+                fees[rank.saturating_sub(1)]
+            })
+            .collect()
+    }
+
+    /// Get the fee an operation needs to rank among the best-paying stored operations
+    /// that fit in `target_slots` blocks, that is slightly more than the best fee left out.
+    /// Returns `None` if all the stored operations fit.
+    pub fn inclusion_fee(&self, target_slots: u64) -> Option<Amount> {
+        let capacity =
+            target_slots.saturating_mul(self.config.max_operations_per_block as u64) as usize;
+        if self.sorted_ops.len() <= capacity {
+            return None;
+        }
+        let mut fees: Vec<Amount> = self.sorted_ops.iter().map(|op_info| op_info.fee).collect();
+        fees.sort_unstable_by(|a, b| b.cmp(a));
+        Some(fees[capacity].saturating_add(Amount::from_raw(1)))
+    }
+
+    /// Get the usage of the quotas of the pool
+    pub fn usage(&self) -> OperationPoolUsage {
+        pool_usage(&self.sorted_ops, &self.config)
+    }
+
+    /// Get the number of stored operations by expire period, sorted by increasing period
+    pub fn expiry_histogram(&self) -> Vec<ExpiryBucket> {
+        let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+        for op_info in &self.sorted_ops {
+            *counts
+                .entry(*op_info.validity_period_range.end())
+                .or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .map(|(expire_period, operation_count)| ExpiryBucket {
+                expire_period,
+                operation_count,
+            })
+            .collect()
+    }
+
     /// Checks whether an element is stored in the pool.
     pub fn contains(&self, id: &OperationId) -> bool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Get all the operations of the pool
+    pub(crate) fn get_operations(&self) -> Vec<SecureShareOperation> {
+        let ops = self.storage.read_operations();
+        self.storage
+            .get_op_refs()
+            .iter()
+            .filter_map(|id| ops.get(id).cloned())
+            .collect()
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -370,19 +640,69 @@ impl OperationPool {
     /// Add a list of operations to the end of the pool.
     /// They will be cleaned up at the next refresh.
     pub(crate) fn add_operations(&mut self, mut ops_storage: Storage) {
-        let new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
+        let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
         {
             let ops = ops_storage.read_operations();
-            for new_op_id in &new_op_ids {
-                let op = ops
-                    .get(new_op_id)
-                    .expect("operation not found in storage but listed as owned");
-                self.sorted_ops.push(OperationInfo::from_op(
+            let new_ops: Vec<_> = new_op_ids
+                .iter()
+                .map(|new_op_id| {
+                    ops.get(new_op_id)
+                        .expect("operation not found in storage but listed as owned")
+                })
+                .collect();
+
+            // reject the ops created by a sender denied by the local policy
+            let new_ops: Vec<_> = {
+                let denylist = self.denylist.read();
+                new_ops
+                    .into_iter()
+                    .filter(|op| {
+                        let denied = denylist.contains(&op.content_creator_address);
+                        if denied {
+                            debug!("operation {} rejected: sender is denylisted", op.id);
+                            new_op_ids.remove(&op.id);
+                        }
+                        !denied
+                    })
+                    .collect()
+            };
+
+            // let the admission hook veto or tag the new operations
+            let verdicts = match &self.admission_hook {
+                Some(admission_hook) => admission_hook
+                    .check_operations(new_ops.iter().map(|op| (*op).clone()).collect()),
+                None => vec![OperationAdmissionVerdict::Accept; new_ops.len()],
+            };
+
+            for (op, verdict) in new_ops.into_iter().zip(verdicts) {
+                let mut op_info = OperationInfo::from_op(
                     op,
                     self.config.operation_validity_periods,
                     self.config.roll_price,
                     self.config.thread_count,
-                ));
+                );
+                match verdict {
+                    OperationAdmissionVerdict::Accept => {}
+                    OperationAdmissionVerdict::Tag(tags) => op_info.tags = tags,
+                    OperationAdmissionVerdict::Veto(reason) => {
+                        debug!(
+                            "operation {} vetoed by the admission hook: {}",
+                            op.id, reason
+                        );
+                        new_op_ids.remove(&op.id);
+                        continue;
+                    }
+                }
+                self.notify_lifecycle(
+                    op_info.id,
+                    op_info.creator_address,
+                    OperationLifecycleStatus::EnteredPool,
+                );
+                self.priority_index.insert(
+                    op_info.thread,
+                    OperationPriority::unscored(op_info.fee_density(), self.sorted_ops.len()),
+                );
+                self.sorted_ops.push(op_info);
             }
         }
 
@@ -401,59 +721,17 @@ impl OperationPool {
 
     /// get operations for block creation
     ///
-    /// Searches the available operations, and selects the sub-set of operations that:
+    /// Walks the operations of the slot's thread by priority, and selects the sub-set of operations that:
     /// - fit inside the block
     /// - is the most profitable for block producer
     pub fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
-        // init list of selected operation IDs
-        let mut op_ids = Vec::new();
-
-        // init remaining space
-        let mut remaining_space = self.config.max_block_size as usize;
-        // init remaining gas
-        let mut remaining_gas = self.config.max_block_gas;
-        // init remaining number of operations
-        let mut remaining_ops = self.config.max_operations_per_block;
-
-        // iterate over pool operations in the right thread, from best to worst
-        for op_info in &self.sorted_ops {
-            // if we have reached the maximum number of operations, stop
-            if remaining_ops == 0 {
-                break;
-            }
-
-            // check thread
-            if op_info.thread != slot.thread {
-                continue;
-            }
-
-            // exclude ops for which the block slot is outside of their validity range
-            if !op_info.validity_period_range.contains(&slot.period) {
-                continue;
-            }
-
-            // exclude ops that are too large
-            if op_info.size > remaining_space {
-                continue;
-            }
-
-            // exclude ops that require too much gas
-            if op_info.max_gas > remaining_gas {
-                continue;
-            }
-
-            // here we consider the operation as accepted
-            op_ids.push(op_info.id);
-
-            // update remaining block space
-            remaining_space -= op_info.size;
-
-            // update remaining block gas
-            remaining_gas -= op_info.max_gas;
-
-            // update remaining number of operations
-            remaining_ops -= 1;
-        }
+        let op_ids = self.priority_index.select_block_operations(
+            &self.sorted_ops,
+            slot,
+            self.config.max_block_size as usize,
+            self.config.max_block_gas,
+            self.config.max_operations_per_block,
+        );
 
         // generate storage
         let mut res_storage = self.storage.clone_without_refs();
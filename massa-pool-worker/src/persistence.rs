@@ -0,0 +1,98 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Persistence of the pending operations and endorsements across restarts.
+//!
+//! The content of the operation and endorsement pools is written to a file when the pool stops,
+//! in the network serialization format. When the pool starts, the file is read back, the signatures
+//! are verified again, and the operations and endorsements go through the usual checks of the pools,
+//! so that the ones that expired or were executed in the meantime are dropped.
+
+use massa_models::{
+    config::{
+        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE,
+    },
+    endorsement::{EndorsementDeserializer, SecureShareEndorsement},
+    operation::{OperationsDeserializer, OperationsSerializer, SecureShareOperation},
+    secure_share::{SecureShareDeserializer, SecureShareSerializer},
+};
+use massa_pool_exports::PoolConfig;
+use massa_serialization::{
+    DeserializeError, Deserializer, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
+};
+use std::{io, ops::Bound::Included, path::Path};
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Writes the pending operations and endorsements to `path`
+pub(crate) fn save_pool(
+    path: &Path,
+    operations: &Vec<SecureShareOperation>,
+    endorsements: &[SecureShareEndorsement],
+) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    OperationsSerializer::new()
+        .serialize(operations, &mut buffer)
+        .map_err(invalid_data)?;
+    let count: u32 = endorsements.len().try_into().map_err(invalid_data)?;
+    U32VarIntSerializer::new()
+        .serialize(&count, &mut buffer)
+        .map_err(invalid_data)?;
+    let endorsement_serializer = SecureShareSerializer::new();
+    for endorsement in endorsements {
+        endorsement_serializer
+            .serialize(endorsement, &mut buffer)
+            .map_err(invalid_data)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, buffer)
+}
+
+/// Reads the operations and endorsements saved at `path`, keeping only the ones with a valid signature
+pub(crate) fn load_pool(
+    path: &Path,
+    config: &PoolConfig,
+) -> io::Result<(Vec<SecureShareOperation>, Vec<SecureShareEndorsement>)> {
+    let buffer = std::fs::read(path)?;
+    let (rest, operations) = OperationsDeserializer::new(
+        u32::MAX,
+        MAX_DATASTORE_VALUE_LENGTH,
+        MAX_FUNCTION_NAME_LENGTH,
+        MAX_PARAMETERS_SIZE,
+        MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+    )
+    .deserialize::<DeserializeError>(&buffer)
+    .map_err(invalid_data)?;
+    let (mut rest, count) = U32VarIntDeserializer::new(Included(0), Included(u32::MAX))
+        .deserialize::<DeserializeError>(rest)
+        .map_err(invalid_data)?;
+    let endorsement_deserializer = SecureShareDeserializer::new(EndorsementDeserializer::new(
+        config.thread_count,
+        config.max_block_endorsement_count,
+    ));
+    let mut endorsements = Vec::new();
+    for _ in 0..count {
+        let (next, endorsement): (&[u8], SecureShareEndorsement) = endorsement_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .map_err(invalid_data)?;
+        endorsements.push(endorsement);
+        rest = next;
+    }
+    Ok((
+        operations
+            .into_iter()
+            .filter(|op| op.verify_signature().is_ok())
+            .collect(),
+        endorsements
+            .into_iter()
+            .filter(|endorsement| endorsement.verify_signature().is_ok())
+            .collect(),
+    ))
+}
@@ -0,0 +1,134 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Block production index of the operation pool.
+//!
+//! The operations of each thread are kept sorted by priority, so that producing a block only walks
+//! the best operations of its thread instead of scanning the whole pool.
+//! The operations scored at the last refresh come first, by decreasing score, that is their fee
+//! weighted by the space and gas they use and by their chances of inclusion.
+//! They are followed by the operations received since the last refresh, by decreasing fee per byte.
+
+use massa_models::{operation::OperationId, slot::Slot};
+use std::{cmp::Ordering, collections::BTreeSet};
+
+use crate::types::OperationInfo;
+
+/// Key of an operation in the block production index
+#[derive(Debug, Clone, Copy)]
+pub struct OperationPriority {
+    /// whether the operation was scored at the last refresh
+    scored: bool,
+    /// score of the operation, or its fee density if it was not scored yet
+    value: f64,
+    /// position of the operation in the pool
+    index: usize,
+}
+
+impl OperationPriority {
+    /// Priority of an operation scored at the last refresh
+    pub fn scored(score: f32, index: usize) -> Self {
+        OperationPriority {
+            scored: true,
+            value: score as f64,
+            index,
+        }
+    }
+
+    /// Priority of an operation received since the last refresh
+    pub fn unscored(fee_density: f64, index: usize) -> Self {
+        OperationPriority {
+            scored: false,
+            value: fee_density,
+            index,
+        }
+    }
+}
+
+impl Ord for OperationPriority {
+    /// Highest priorities first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .scored
+            .cmp(&self.scored)
+            .then_with(|| other.value.total_cmp(&self.value))
+            .then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for OperationPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OperationPriority {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OperationPriority {}
+
+/// Operations of the pool sorted by priority, per thread
+pub struct OperationPriorityIndex {
+    threads: Vec<BTreeSet<OperationPriority>>,
+}
+
+impl OperationPriorityIndex {
+    /// Creates an empty index
+    pub fn new(thread_count: u8) -> Self {
+        OperationPriorityIndex {
+            threads: vec![BTreeSet::new(); thread_count as usize],
+        }
+    }
+
+    /// Removes all the operations from the index
+    pub fn clear(&mut self) {
+        self.threads.iter_mut().for_each(BTreeSet::clear);
+    }
+
+    /// Adds an operation of `thread` to the index
+    pub fn insert(&mut self, thread: u8, priority: OperationPriority) {
+        self.threads[thread as usize].insert(priority);
+    }
+
+    /// Selects the operations to include in a block at `slot`, from the highest priority to the lowest,
+    /// within the size, gas and operation count limits of the block.
+    /// `ops` are the operations of the pool, at the positions they were indexed with.
+    pub fn select_block_operations(
+        &self,
+        ops: &[OperationInfo],
+        slot: &Slot,
+        max_size: usize,
+        max_gas: u64,
+        max_count: u32,
+    ) -> Vec<OperationId> {
+        let mut op_ids = Vec::new();
+        let mut remaining_space = max_size;
+        let mut remaining_gas = max_gas;
+        let mut remaining_ops = max_count;
+        for priority in &self.threads[slot.thread as usize] {
+            // if we have reached the maximum number of operations, or the block has no space left, stop
+            if remaining_ops == 0 || remaining_space == 0 {
+                break;
+            }
+            let op_info = &ops[priority.index];
+
+            // exclude ops for which the block slot is outside of their validity range,
+            // that are too large, or that require too much gas
+            if !op_info.validity_period_range.contains(&slot.period)
+                || op_info.size > remaining_space
+                || op_info.max_gas > remaining_gas
+            {
+                continue;
+            }
+
+            // here we consider the operation as accepted
+            op_ids.push(op_info.id);
+            remaining_space -= op_info.size;
+            remaining_gas -= op_info.max_gas;
+            remaining_ops -= 1;
+        }
+        op_ids
+    }
+}
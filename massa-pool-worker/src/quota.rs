@@ -0,0 +1,101 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Quotas of the operation pool.
+//!
+//! On top of the max number of operations, the pool limits the number of operations of each sender,
+//! so that a single address cannot fill it, and the total size of the operations it stores.
+//! When a quota is exceeded, the operations are evicted in the order given by the eviction policy.
+
+use massa_models::{
+    address::Address,
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+};
+use massa_pool_exports::{OperationEvictionPolicy, OperationPoolUsage, PoolConfig};
+use std::cmp::Ordering;
+
+use crate::types::OperationInfo;
+
+/// Indexes of `ops` in eviction order: the first ones are evicted first
+fn eviction_order(ops: &[OperationInfo], policy: OperationEvictionPolicy) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..ops.len()).collect();
+    match policy {
+        OperationEvictionPolicy::LowestFeeDensity => order.sort_by(|a, b| {
+            ops[*a]
+                .fee_density()
+                .partial_cmp(&ops[*b].fee_density())
+                .unwrap_or(Ordering::Equal)
+        }),
+        OperationEvictionPolicy::Oldest => order.sort_by_key(|index| ops[*index].received_at),
+    }
+    order
+}
+
+/// Number of operations of each sender
+fn sender_operation_counts(ops: &[OperationInfo]) -> PreHashMap<Address, usize> {
+    let mut counts = PreHashMap::default();
+    for op_info in ops {
+        *counts.entry(op_info.creator_address).or_default() += 1;
+    }
+    counts
+}
+
+/// Returns the operations to evict so that the per-sender and total size quotas are respected
+pub(crate) fn quota_evictions(
+    ops: &[OperationInfo],
+    config: &PoolConfig,
+) -> PreHashSet<OperationId> {
+    let mut evicted = PreHashSet::default();
+    let mut sender_counts = sender_operation_counts(ops);
+    let mut byte_size: usize = ops.iter().map(|op_info| op_info.size).sum();
+    let within_sender_quota = sender_counts
+        .values()
+        .all(|count| *count <= config.max_operations_per_sender);
+    if within_sender_quota && byte_size <= config.max_operation_pool_byte_size {
+        return evicted;
+    }
+    let order = eviction_order(ops, config.operation_eviction_policy);
+
+    // per-sender quota
+    if !within_sender_quota {
+        for index in &order {
+            let op_info = &ops[*index];
+            let count = sender_counts
+                .get_mut(&op_info.creator_address)
+                .expect("sender missing from the operation counts");
+            if *count > config.max_operations_per_sender {
+                *count -= 1;
+                byte_size -= op_info.size;
+                evicted.insert(op_info.id);
+            }
+        }
+    }
+
+    // total size quota
+    for index in &order {
+        if byte_size <= config.max_operation_pool_byte_size {
+            break;
+        }
+        let op_info = &ops[*index];
+        if evicted.insert(op_info.id) {
+            byte_size -= op_info.size;
+        }
+    }
+    evicted
+}
+
+/// Usage of the quotas by `ops`
+pub(crate) fn pool_usage(ops: &[OperationInfo], config: &PoolConfig) -> OperationPoolUsage {
+    OperationPoolUsage {
+        eviction_policy: config.operation_eviction_policy,
+        operation_count: ops.len(),
+        max_operation_count: config.max_operation_pool_size,
+        byte_size: ops.iter().map(|op_info| op_info.size).sum(),
+        max_byte_size: config.max_operation_pool_byte_size,
+        max_sender_operation_count: sender_operation_counts(ops)
+            .into_values()
+            .max()
+            .unwrap_or_default(),
+        max_operations_per_sender: config.max_operations_per_sender,
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::{create_some_operations, OpGenerator};
+use crate::admission::AdmissionHookRunner;
+use massa_models::operation::SecureShareOperation;
+use massa_pool_exports::{
+    OperationAdmissionConfig, OperationAdmissionHook, OperationAdmissionPolicy,
+    OperationAdmissionVerdict,
+};
+use massa_time::MassaTime;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Vetoes the first operation, tags the second one and sleeps for the given duration
+struct TestHook(Duration);
+
+impl OperationAdmissionHook for TestHook {
+    fn check_operations(
+        &self,
+        operations: &[SecureShareOperation],
+    ) -> Vec<OperationAdmissionVerdict> {
+        std::thread::sleep(self.0);
+        operations
+            .iter()
+            .enumerate()
+            .map(|(i, _)| match i {
+                0 => OperationAdmissionVerdict::Veto("test".to_string()),
+                1 => OperationAdmissionVerdict::Tag(vec!["test".to_string()]),
+                _ => OperationAdmissionVerdict::Accept,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_admission_hook_verdicts() {
+    let runner = AdmissionHookRunner::spawn(OperationAdmissionPolicy::new(
+        Arc::new(TestHook(Duration::ZERO)),
+        OperationAdmissionConfig {
+            timing_budget: MassaTime::from_millis(1000),
+            ..Default::default()
+        },
+    ));
+    let verdicts = runner.check_operations(create_some_operations(3, &OpGenerator::default()));
+    assert_eq!(
+        verdicts,
+        vec![
+            OperationAdmissionVerdict::Veto("test".to_string()),
+            OperationAdmissionVerdict::Tag(vec!["test".to_string()]),
+            OperationAdmissionVerdict::Accept,
+        ]
+    );
+}
+
+#[test]
+fn test_admission_hook_timing_budget() {
+    let policy = OperationAdmissionPolicy::new(
+        Arc::new(TestHook(Duration::from_millis(500))),
+        OperationAdmissionConfig {
+            timing_budget: MassaTime::from_millis(10),
+            veto_on_timeout: false,
+            ..Default::default()
+        },
+    );
+    let runner = AdmissionHookRunner::spawn(policy.clone());
+
+    // the slow hook is skipped and the operations are admitted
+    let verdicts = runner.check_operations(create_some_operations(2, &OpGenerator::default()));
+    assert_eq!(verdicts, vec![OperationAdmissionVerdict::Accept; 2]);
+
+    // the configuration change is taken into account right away
+    policy.set_config(OperationAdmissionConfig {
+        timing_budget: MassaTime::from_millis(10),
+        veto_on_timeout: true,
+        ..Default::default()
+    });
+    let verdicts = runner.check_operations(create_some_operations(2, &OpGenerator::default()));
+    assert!(verdicts
+        .iter()
+        .all(|verdict| matches!(verdict, OperationAdmissionVerdict::Veto(_))));
+}
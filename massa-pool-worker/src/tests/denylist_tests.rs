@@ -0,0 +1,33 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::denylist::SenderDenylist;
+use massa_models::address::Address;
+use massa_signature::KeyPair;
+use tempfile::TempDir;
+
+fn random_address() -> Address {
+    Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+}
+
+#[test]
+fn test_sender_denylist_persistence() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("config").join("pool_denylist.json");
+    let (denied, other) = (random_address(), random_address());
+
+    // a missing file is an empty list
+    let mut denylist = SenderDenylist::load(Some(path.clone())).unwrap();
+    assert!(denylist.is_empty());
+    denylist.add(vec![denied, other]).unwrap();
+    denylist.remove(vec![other]).unwrap();
+
+    // the updates are saved right away
+    let denylist = SenderDenylist::load(Some(path.clone())).unwrap();
+    assert!(denylist.contains(&denied));
+    assert!(!denylist.contains(&other));
+    assert_eq!(denylist.addresses(), vec![denied]);
+
+    // an unreadable file is reported instead of being ignored
+    std::fs::write(&path, "not a list").unwrap();
+    assert!(SenderDenylist::load(Some(path)).is_err());
+}
@@ -1,5 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+mod admission_tests;
+mod denylist_tests;
 mod operation_pool_tests;
+mod persistence_tests;
+mod priority_tests;
+mod quota_tests;
 mod scenario;
 pub(crate) mod tools;
@@ -0,0 +1,24 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::{_create_endorsement, create_some_operations, OpGenerator};
+use crate::persistence::{load_pool, save_pool};
+use massa_models::slot::Slot;
+use massa_pool_exports::PoolConfig;
+use tempfile::TempDir;
+
+#[test]
+fn test_pool_persistence() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("pool").join("pool.bin");
+    let operations = create_some_operations(5, &OpGenerator::default().expirery(10));
+    let endorsements = vec![
+        _create_endorsement(Slot::new(3, 0)),
+        _create_endorsement(Slot::new(4, 1)),
+    ];
+    save_pool(&path, &operations, &endorsements).unwrap();
+
+    let (loaded_operations, loaded_endorsements) =
+        load_pool(&path, &PoolConfig::default()).unwrap();
+    assert_eq!(loaded_operations, operations);
+    assert_eq!(loaded_endorsements, endorsements);
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::OpGenerator;
+use crate::priority::{OperationPriority, OperationPriorityIndex};
+use crate::types::OperationInfo;
+use massa_models::{amount::Amount, slot::Slot};
+use massa_pool_exports::PoolConfig;
+use massa_signature::KeyPair;
+
+#[test]
+fn test_priority_index_selection() {
+    let config = PoolConfig::default();
+    let creator = KeyPair::generate(0).unwrap();
+    let ops: Vec<OperationInfo> = [1, 2, 3, 4]
+        .into_iter()
+        .map(|fee| {
+            let op = OpGenerator::default()
+                .creator(creator.clone())
+                .fee(Amount::from_raw(fee))
+                .expirery(10)
+                .generate();
+            OperationInfo::from_op(
+                &op,
+                config.operation_validity_periods,
+                config.roll_price,
+                config.thread_count,
+            )
+        })
+        .collect();
+    let thread = ops[0].thread;
+
+    // ops scored at the last refresh come first, the ones received since by fee density
+    let mut index = OperationPriorityIndex::new(config.thread_count);
+    index.insert(thread, OperationPriority::scored(1.0, 0));
+    index.insert(thread, OperationPriority::scored(2.0, 1));
+    index.insert(thread, OperationPriority::unscored(ops[2].fee_density(), 2));
+    index.insert(thread, OperationPriority::unscored(ops[3].fee_density(), 3));
+    let slot = Slot::new(10, thread);
+    let max_size = config.max_block_size as usize;
+    assert_eq!(
+        index.select_block_operations(&ops, &slot, max_size, config.max_block_gas, 3),
+        vec![ops[1].id, ops[0].id, ops[3].id]
+    );
+
+    // only the ops fitting in the block, valid at the slot and of its thread are selected
+    assert_eq!(
+        index.select_block_operations(&ops, &slot, ops[1].size, config.max_block_gas, 3),
+        vec![ops[1].id]
+    );
+    assert!(index
+        .select_block_operations(
+            &ops,
+            &Slot::new(11, thread),
+            max_size,
+            config.max_block_gas,
+            3
+        )
+        .is_empty());
+    assert!(index
+        .select_block_operations(
+            &ops,
+            &Slot::new(10, (thread + 1) % config.thread_count),
+            max_size,
+            config.max_block_gas,
+            3
+        )
+        .is_empty());
+
+    index.clear();
+    assert!(index
+        .select_block_operations(&ops, &slot, max_size, config.max_block_gas, 3)
+        .is_empty());
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::OpGenerator;
+use crate::quota::{pool_usage, quota_evictions};
+use crate::types::OperationInfo;
+use massa_models::{amount::Amount, prehash::PreHashSet};
+use massa_pool_exports::{OperationEvictionPolicy, PoolConfig};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+
+#[test]
+fn test_pool_quotas() {
+    let config = PoolConfig::default();
+    let sender = KeyPair::generate(0).unwrap();
+    // three ops of the same sender, the oldest one paying the highest fee, and an op of another sender
+    let mut ops: Vec<OperationInfo> = [(3, 10), (2, 20), (1, 30)]
+        .into_iter()
+        .map(|(fee, received_at)| {
+            let op = OpGenerator::default()
+                .creator(sender.clone())
+                .fee(Amount::from_raw(fee))
+                .expirery(10)
+                .generate();
+            let mut op_info = OperationInfo::from_op(
+                &op,
+                config.operation_validity_periods,
+                config.roll_price,
+                config.thread_count,
+            );
+            op_info.received_at = MassaTime::from_millis(received_at);
+            op_info
+        })
+        .collect();
+    let other = OpGenerator::default()
+        .fee(Amount::from_raw(2))
+        .expirery(10)
+        .generate();
+    let mut other_info = OperationInfo::from_op(
+        &other,
+        config.operation_validity_periods,
+        config.roll_price,
+        config.thread_count,
+    );
+    other_info.received_at = MassaTime::from_millis(40);
+    ops.push(other_info);
+    let byte_size: usize = ops.iter().map(|op_info| op_info.size).sum();
+
+    // within the quotas
+    assert!(quota_evictions(&ops, &config).is_empty());
+    let usage = pool_usage(&ops, &config);
+    assert_eq!(usage.operation_count, 4);
+    assert_eq!(usage.byte_size, byte_size);
+    assert_eq!(usage.max_sender_operation_count, 3);
+
+    // per-sender quota: the sender keeps its two best paying ops, or its two most recent ones
+    let sender_quota = PoolConfig {
+        max_operations_per_sender: 2,
+        ..config
+    };
+    assert_eq!(
+        quota_evictions(&ops, &sender_quota),
+        PreHashSet::from_iter([ops[2].id])
+    );
+    assert_eq!(
+        quota_evictions(
+            &ops,
+            &PoolConfig {
+                operation_eviction_policy: OperationEvictionPolicy::Oldest,
+                ..sender_quota
+            }
+        ),
+        PreHashSet::from_iter([ops[0].id])
+    );
+
+    // size quota: the lowest fee densities are evicted until the pool fits
+    let size_quota = PoolConfig {
+        max_operation_pool_byte_size: byte_size - 1,
+        ..config
+    };
+    assert_eq!(
+        quota_evictions(&ops, &size_quota),
+        PreHashSet::from_iter([ops[2].id])
+    );
+}
@@ -112,6 +112,8 @@ impl PoolTestBoilerPlate {
         let wallet = Arc::new(RwLock::new(create_test_wallet(Some(addresses))));
         let endorsement_sender = broadcast::channel(2000).0;
         let operation_sender = broadcast::channel(5000).0;
+        let operation_lifecycle_sender = broadcast::channel(5000).0;
+        let denunciation_sender = broadcast::channel(1000).0;
         let (pool_manager, pool_controller) = start_pool_controller(
             cfg,
             &storage,
@@ -119,9 +121,14 @@ impl PoolTestBoilerPlate {
                 execution_controller: execution_story,
                 endorsement_sender,
                 operation_sender,
+                operation_lifecycle_sender,
+                denunciation_sender,
                 selector: selector_story,
             },
             wallet,
+            None,
+            None,
+            None,
         );
 
         Self {
@@ -142,6 +149,8 @@ pub fn operation_pool_test<F>(
 {
     let endorsement_sender = broadcast::channel(2000).0;
     let operation_sender = broadcast::channel(5000).0;
+    let operation_lifecycle_sender = broadcast::channel(5000).0;
+    let denunciation_sender = broadcast::channel(1000).0;
     let storage = Storage::create_root();
     let keypair = KeyPair::generate(0).unwrap();
     let address = Address::from_public_key(&keypair.get_public_key());
@@ -155,9 +164,14 @@ pub fn operation_pool_test<F>(
             execution_controller,
             endorsement_sender,
             operation_sender,
+            operation_lifecycle_sender,
+            denunciation_sender,
             selector,
         },
         wallet,
+        None,
+        None,
+        None,
     );
     test(pool_controller, storage);
     pool_manager.stop();
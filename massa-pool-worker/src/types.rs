@@ -3,22 +3,36 @@ use massa_models::{
     amount::Amount,
     operation::{OperationId, SecureShareOperation},
 };
+use massa_time::MassaTime;
 use std::ops::RangeInclusive;
 
+/// Data of a pool operation used to sort and select it
 #[derive(Debug, Clone)]
 pub struct OperationInfo {
+    /// operation id
     pub id: OperationId,
+    /// serialized size of the op
     pub size: usize,
+    /// max gas the op can use
     pub max_gas: u64,
+    /// sender of the op
     pub creator_address: Address,
+    /// thread in which the op can be included
     pub thread: u8,
+    /// fee of the op
     pub fee: Amount,
     /// max amount that the op might spend from the sender's balance
     pub max_spending: Amount,
+    /// periods during which the op can be included
     pub validity_period_range: RangeInclusive<u64>,
+    /// tags given by the admission hook
+    pub tags: Vec<String>,
+    /// time at which the op entered the pool
+    pub received_at: MassaTime,
 }
 
 impl OperationInfo {
+    /// Extracts the data of a pool operation
     pub fn from_op(
         op: &SecureShareOperation,
         operation_validity_periods: u64,
@@ -34,6 +48,13 @@ impl OperationInfo {
             thread: op.content_creator_address.get_thread(thread_count),
             validity_period_range: op.get_validity_range(operation_validity_periods),
             max_spending: op.get_max_spending(roll_price),
+            tags: Vec::new(),
+            received_at: MassaTime::now().expect("could not get current time"),
         }
     }
+
+    /// Fee paid per byte of the operation
+    pub fn fee_density(&self) -> f64 {
+        self.fee.to_raw() as f64 / self.size.max(1) as f64
+    }
 }
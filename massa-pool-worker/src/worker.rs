@@ -4,13 +4,16 @@
 
 use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
+use crate::denylist::SenderDenylist;
 use crate::operation_pool::OperationPool;
+use crate::persistence::load_pool;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
 use massa_pool_exports::PoolConfig;
-use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
+use massa_pool_exports::{OperationAdmissionPolicy, PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::time::Instant;
 use std::{
     sync::mpsc::{sync_channel, Receiver, RecvError, RecvTimeoutError},
@@ -18,7 +21,7 @@ use std::{
     thread,
     thread::JoinHandle,
 };
-use tracing::warn;
+use tracing::{error, info, warn};
 
 /// Endorsement pool write thread instance
 pub(crate) struct EndorsementPoolThread {
@@ -33,27 +36,26 @@ impl EndorsementPoolThread {
     pub(crate) fn spawn(
         receiver: Receiver<Command>,
         endorsement_pool: Arc<RwLock<EndorsementPool>>,
+        config: PoolConfig,
     ) -> JoinHandle<()> {
         let thread_builder = thread::Builder::new().name("endorsement-pool".into());
         thread_builder
-            .spawn(|| {
+            .spawn(move || {
                 let this = Self {
                     receiver,
                     endorsement_pool,
                 };
-                this.run()
+                this.run(config)
             })
             .expect("failed to spawn thread : endorsement-pool")
     }
 
     /// Runs the thread
-    fn run(self) {
+    fn run(self, config: PoolConfig) {
+        let mut next_cleanup = Instant::now();
         loop {
-            match self.receiver.recv() {
-                Err(RecvError) => break,
-                Ok(Command::Stop) => {
-                    break;
-                }
+            match self.receiver.recv_deadline(next_cleanup) {
+                Err(RecvTimeoutError::Disconnected) | Ok(Command::Stop) => break,
                 Ok(Command::AddItems(endorsements)) => {
                     self.endorsement_pool.write().add_endorsements(endorsements)
                 }
@@ -61,10 +63,17 @@ impl EndorsementPoolThread {
                     .endorsement_pool
                     .write()
                     .notify_final_cs_periods(&final_cs_periods),
-                _ => {
+                Ok(_) => {
                     warn!("EndorsementPoolThread received an unexpected command");
                     continue;
                 }
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+            if next_cleanup <= Instant::now() {
+                self.endorsement_pool.write().cleanup();
+                next_cleanup = Instant::now()
+                    .checked_add(config.endorsement_pool_cleanup_interval.to_duration())
+                    .expect("could not compute time of next endorsement pool cleanup")
             }
         }
     }
@@ -180,12 +189,22 @@ impl DenunciationPoolThread {
 }
 
 /// Start pool manager and controller
+///
+/// `admission_policy` is an optional operator-defined hook called on the operations entering the pool
+///
+/// If `persistence_path` is set, the operations and endorsements saved there when the pool last stopped are loaded back,
+/// and the pool is saved there again when it stops
+///
+/// If `denylist_path` is set, the local denylist of senders is loaded from there, and saved there when it is updated
 #[allow(clippy::type_complexity)]
 pub fn start_pool_controller(
     config: PoolConfig,
     storage: &Storage,
     channels: PoolChannels,
     wallet: Arc<RwLock<Wallet>>,
+    admission_policy: Option<OperationAdmissionPolicy>,
+    persistence_path: Option<PathBuf>,
+    denylist_path: Option<PathBuf>,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
         sync_channel(config.operations_channel_size);
@@ -193,11 +212,25 @@ pub fn start_pool_controller(
         sync_channel(config.endorsements_channel_size);
     let (denunciations_input_sender, denunciations_input_receiver) =
         sync_channel(config.denunciations_channel_size);
+    let denylist = match SenderDenylist::load(denylist_path.clone()) {
+        Ok(denylist) => denylist,
+        Err(err) => {
+            // the file is left untouched so that it can be fixed, the updates are kept in memory only
+            error!(
+                "could not load the pool denylist, no sender is denied: {}",
+                err
+            );
+            SenderDenylist::default()
+        }
+    };
+    let denylist = Arc::new(RwLock::new(denylist));
     let operation_pool = Arc::new(RwLock::new(OperationPool::init(
         config,
         storage,
         channels.clone(),
         wallet.clone(),
+        admission_policy,
+        denylist.clone(),
     )));
     let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
         config,
@@ -206,11 +239,32 @@ pub fn start_pool_controller(
         wallet,
     )));
     let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
+    if let Some(path) = persistence_path.as_ref().filter(|path| path.exists()) {
+        match load_pool(path, &config) {
+            Ok((operations, endorsements)) => {
+                info!(
+                    "loaded {} operations and {} endorsements saved in the pool",
+                    operations.len(),
+                    endorsements.len()
+                );
+                let mut operations_storage = storage.clone_without_refs();
+                operations_storage.store_operations(operations);
+                operation_pool.write().add_operations(operations_storage);
+                let mut endorsements_storage = storage.clone_without_refs();
+                endorsements_storage.store_endorsements(endorsements);
+                endorsement_pool
+                    .write()
+                    .add_endorsements(endorsements_storage);
+            }
+            Err(err) => warn!("could not load the pool from {}: {}", path.display(), err),
+        }
+    }
     let controller = PoolControllerImpl {
-        _config: config,
+        config,
         operation_pool: operation_pool.clone(),
         endorsement_pool: endorsement_pool.clone(),
         denunciation_pool: denunciation_pool.clone(),
+        denylist,
         operations_input_sender: operations_input_sender.clone(),
         endorsements_input_sender: endorsements_input_sender.clone(),
         denunciations_input_sender: denunciations_input_sender.clone(),
@@ -218,9 +272,12 @@ pub fn start_pool_controller(
     };
 
     let operations_thread_handle =
-        OperationPoolThread::spawn(operations_input_receiver, operation_pool, config);
-    let endorsements_thread_handle =
-        EndorsementPoolThread::spawn(endorsements_input_receiver, endorsement_pool);
+        OperationPoolThread::spawn(operations_input_receiver, operation_pool.clone(), config);
+    let endorsements_thread_handle = EndorsementPoolThread::spawn(
+        endorsements_input_receiver,
+        endorsement_pool.clone(),
+        config,
+    );
     let denunciations_thread_handle =
         DenunciationPoolThread::spawn(denunciations_input_receiver, denunciation_pool);
 
@@ -231,6 +288,9 @@ pub fn start_pool_controller(
         operations_input_sender,
         endorsements_input_sender,
         denunciations_input_sender,
+        persistence_path,
+        operation_pool,
+        endorsement_pool,
     };
     (Box::new(manager), Box::new(controller))
 }
@@ -16,12 +16,43 @@ use massa_models::{
 use std::collections::{HashMap, VecDeque};
 
 /// Selections of endorsements and producer
+///
+/// The drawn addresses are the roll owners. When an owner has delegated its stake,
+/// the block or endorsement has to be created by its operator, the owner still receives the rewards.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Selection {
-    /// Chosen endorsements
+    /// Chosen endorsements creators
     pub endorsements: Vec<Address>,
     /// Chosen block producer
     pub producer: Address,
+    /// Owners of the rolls drawn for the endorsements
+    pub endorsement_owners: Vec<Address>,
+    /// Owner of the rolls drawn for the block
+    pub producer_owner: Address,
+}
+
+impl Selection {
+    /// Owner of the rolls for which `creator` produced the block.
+    /// Returns `creator` if it was not selected as the block producer.
+    pub fn get_producer_owner(&self, creator: &Address) -> Address {
+        if self.producer == *creator {
+            self.producer_owner
+        } else {
+            *creator
+        }
+    }
+
+    /// Owner of the rolls for which `creator` created the endorsement at `index`.
+    /// Returns `creator` if it was not selected for this endorsement.
+    pub fn get_endorsement_owner(&self, index: usize, creator: &Address) -> Address {
+        match (
+            self.endorsements.get(index),
+            self.endorsement_owners.get(index),
+        ) {
+            (Some(selected), Some(owner)) if selected == creator => *owner,
+            _ => *creator,
+        }
+    }
 }
 
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
@@ -37,12 +68,17 @@ pub trait SelectorController: Send + Sync {
     /// # Arguments
     /// * `cycle`: cycle number to be drawn
     /// * `lookback_rolls`: look back rolls used for the draw (cycle - 3)
+    /// * `lookback_delegations`: look back stake delegations, owner to operator (cycle - 3)
     /// * `lookback_seed`: look back seed hash for the draw (cycle - 2)
+    /// * `stake_delegation_version`: version of the `StakeDelegation` MIP component at the end of cycle - 2,
+    ///   the look back stake delegations are only applied to the draws from version 1
     fn feed_cycle(
         &self,
         cycle: u64,
         lookback_rolls: BTreeMap<Address, u64>,
+        lookback_delegations: BTreeMap<Address, Address>,
         lookback_seed: Hash,
+        stake_delegation_version: u32,
     ) -> PosResult<()>;
 
     /// Get [Selection] computed for a slot:
@@ -12,7 +12,7 @@ use massa_serialization::{
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::value,
+    combinator::{cond, value},
     error::{context, ContextError, ParseError},
     multi::length_count,
     sequence::tuple,
@@ -83,10 +83,17 @@ impl CycleInfoHashComputer {
             .unwrap();
         Hash::compute_from(&buffer)
     }
+
+    fn compute_delegation_entry_hash(&self, owner: &Address, operator: &Address) -> Hash {
+        let mut buffer = Vec::new();
+        self.address_ser.serialize(owner, &mut buffer).unwrap();
+        self.address_ser.serialize(operator, &mut buffer).unwrap();
+        Hash::compute_from(&buffer)
+    }
 }
 
 /// State of a cycle for all threads
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CycleInfo {
     /// cycle number
     pub cycle: u64,
@@ -107,6 +114,8 @@ pub struct CycleInfo {
     /// Snapshot of the final state hash
     /// Used for PoS selections
     pub final_state_hash_snapshot: Option<Hash>,
+    /// Stake delegations, from the roll owner to its operator
+    pub delegations: BTreeMap<Address, Address>,
 }
 
 impl CycleInfo {
@@ -117,6 +126,7 @@ impl CycleInfo {
         roll_counts: BTreeMap<Address, u64>,
         rng_seed: BitVec<u8>,
         production_stats: PreHashMap<Address, ProductionStats>,
+        delegations: BTreeMap<Address, Address>,
     ) -> Self {
         let hash_computer = CycleInfoHashComputer::new();
         let mut roll_counts_hash = Hash::from_bytes(CYCLE_INFO_HASH_INITIAL_BYTES);
@@ -135,6 +145,15 @@ impl CycleInfo {
             production_stats_hash ^= hash_computer.compute_prod_stats_entry_hash(addr, prod_stats);
         }
         hash_concat.extend(production_stats_hash.to_bytes());
+        // the delegations are only hashed when there are some,
+        // so that the hash of a cycle without delegation is the one it had before stake delegation
+        if !delegations.is_empty() {
+            let mut delegations_hash = Hash::from_bytes(CYCLE_INFO_HASH_INITIAL_BYTES);
+            for (owner, operator) in &delegations {
+                delegations_hash ^= hash_computer.compute_delegation_entry_hash(owner, operator);
+            }
+            hash_concat.extend(delegations_hash.to_bytes());
+        }
 
         // compute the global hash
         let cycle_global_hash = Hash::compute_from(&hash_concat);
@@ -150,6 +169,7 @@ impl CycleInfo {
             production_stats_hash,
             cycle_global_hash,
             final_state_hash_snapshot: None,
+            delegations,
         }
     }
 }
@@ -163,23 +183,21 @@ pub struct CycleInfoSerializer {
     pub production_stats_ser: ProductionStatsSerializer,
     pub address_ser: AddressSerializer,
     pub opt_hash_ser: OptionSerializer<Hash, HashSerializer>,
-}
-
-impl Default for CycleInfoSerializer {
-    fn default() -> Self {
-        Self::new()
-    }
+    pub stake_delegation_version: u32,
 }
 
 impl CycleInfoSerializer {
     /// Creates a new `CycleInfo` serializer
-    pub fn new() -> Self {
+    ///
+    /// The delegations are only serialized from version 1 of the `StakeDelegation` MIP component.
+    pub fn new(stake_delegation_version: u32) -> Self {
         Self {
             u64_ser: U64VarIntSerializer::new(),
             bitvec_ser: BitVecSerializer::new(),
             production_stats_ser: ProductionStatsSerializer::new(),
             address_ser: AddressSerializer::new(),
             opt_hash_ser: OptionSerializer::new(HashSerializer::new()),
+            stake_delegation_version,
         }
     }
 }
@@ -211,6 +229,20 @@ impl Serializer<CycleInfo> for CycleInfoSerializer {
         self.opt_hash_ser
             .serialize(&value.final_state_hash_snapshot, buffer)?;
 
+        // cycle_info.delegations
+        if self.stake_delegation_version >= 1 {
+            self.u64_ser
+                .serialize(&(value.delegations.len() as u64), buffer)?;
+            for (owner, operator) in &value.delegations {
+                self.address_ser.serialize(owner, buffer)?;
+                self.address_ser.serialize(operator, buffer)?;
+            }
+        } else if !value.delegations.is_empty() {
+            return Err(SerializeError::GeneralError(
+                "delegations before the activation of stake delegation".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -224,17 +256,28 @@ pub struct CycleInfoDeserializer {
     pub bitvec_deser: BitVecDeserializer,
     pub production_stats_deser: ProductionStatsDeserializer,
     pub opt_hash_deser: OptionDeserializer<Hash, HashDeserializer>,
+    pub delegations_deser: DelegationsDeserializer,
+    pub stake_delegation_version: u32,
 }
 
 impl CycleInfoDeserializer {
     /// Creates a new `CycleInfo` deserializer
-    pub fn new(max_rolls_length: u64, max_production_stats_length: u64) -> CycleInfoDeserializer {
+    ///
+    /// The delegations are only deserialized from version 1 of the `StakeDelegation` MIP component.
+    pub fn new(
+        max_rolls_length: u64,
+        max_production_stats_length: u64,
+        stake_delegation_version: u32,
+    ) -> CycleInfoDeserializer {
         CycleInfoDeserializer {
             u64_deser: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
             rolls_deser: RollsDeserializer::new(max_rolls_length),
             bitvec_deser: BitVecDeserializer::new(),
             production_stats_deser: ProductionStatsDeserializer::new(max_production_stats_length),
             opt_hash_deser: OptionDeserializer::new(HashDeserializer::new()),
+            // there is at most one delegation per roll owner
+            delegations_deser: DelegationsDeserializer::new(max_rolls_length),
+            stake_delegation_version,
         }
     }
 }
@@ -260,17 +303,24 @@ impl Deserializer<CycleInfo> for CycleInfoDeserializer {
                 context("final_state_hash_snapshot", |input| {
                     self.opt_hash_deser.deserialize(input)
                 }),
+                context(
+                    "delegations",
+                    cond(self.stake_delegation_version >= 1, |input| {
+                        self.delegations_deser.deserialize(input)
+                    }),
+                ),
             )),
         )
         .map(
             #[allow(clippy::type_complexity)]
-            |(cycle, complete, roll_counts, rng_seed, production_stats, opt_hash): (
+            |(cycle, complete, roll_counts, rng_seed, production_stats, opt_hash, delegations): (
                 u64,                                  // cycle
                 bool,                                 // complete
                 Vec<(Address, u64)>,                  // roll_counts
                 BitVec<u8>,                           // rng_seed
                 PreHashMap<Address, ProductionStats>, // production_stats (address, n_success, n_fail)
                 Option<Hash>,                         // final_state_hash_snapshot
+                Option<Vec<(Address, Address)>>,      // delegations (owner, operator)
             )| {
                 let mut cycle = CycleInfo::new_with_hash(
                     cycle,
@@ -278,6 +328,7 @@ impl Deserializer<CycleInfo> for CycleInfoDeserializer {
                     roll_counts.into_iter().collect(),
                     rng_seed,
                     production_stats,
+                    delegations.unwrap_or_default().into_iter().collect(),
                 );
                 cycle.final_state_hash_snapshot = opt_hash;
                 cycle
@@ -477,6 +528,52 @@ impl Deserializer<Vec<(Address, u64)>> for RollsDeserializer {
     }
 }
 
+#[derive(Clone)]
+#[allow(missing_docs)]
+/// Deserializer for stake delegations
+pub struct DelegationsDeserializer {
+    length_deserializer: U64VarIntDeserializer,
+    pub address_deserializer: AddressDeserializer,
+}
+
+impl DelegationsDeserializer {
+    /// Creates a new stake delegations deserializer
+    pub fn new(max_delegations_length: u64) -> DelegationsDeserializer {
+        DelegationsDeserializer {
+            length_deserializer: U64VarIntDeserializer::new(
+                Included(u64::MIN),
+                Included(max_delegations_length),
+            ),
+            address_deserializer: AddressDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<Vec<(Address, Address)>> for DelegationsDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], Vec<(Address, Address)>, E> {
+        context(
+            "Failed delegations deserialization",
+            length_count(
+                context("Failed length deserialization", |input| {
+                    self.length_deserializer.deserialize(input)
+                }),
+                tuple((
+                    context("Failed owner deserialization", |input| {
+                        self.address_deserializer.deserialize(input)
+                    }),
+                    context("Failed operator deserialization", |input| {
+                        self.address_deserializer.deserialize(input)
+                    }),
+                )),
+            ),
+        )
+        .parse(buffer)
+    }
+}
+
 #[derive(Clone)]
 #[allow(missing_docs)]
 /// Serializer for cycle history
@@ -487,20 +584,14 @@ pub struct CycleHistorySerializer {
 
 impl CycleHistorySerializer {
     /// Creates a new `CycleHistory` serializer
-    pub fn new() -> Self {
+    pub fn new(stake_delegation_version: u32) -> Self {
         Self {
             u64_serializer: U64VarIntSerializer::new(),
-            cycle_info_serializer: CycleInfoSerializer::new(),
+            cycle_info_serializer: CycleInfoSerializer::new(stake_delegation_version),
         }
     }
 }
 
-impl Default for CycleHistorySerializer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl Serializer<VecDeque<CycleInfo>> for CycleHistorySerializer {
     fn serialize(
         &self,
@@ -530,6 +621,7 @@ impl CycleHistoryDeserializer {
         max_cycle_history_length: u64,
         max_rolls_length: u64,
         max_production_stats_length: u64,
+        stake_delegation_version: u32,
     ) -> Self {
         Self {
             u64_deserializer: U64VarIntDeserializer::new(
@@ -539,6 +631,7 @@ impl CycleHistoryDeserializer {
             cycle_info_deserializer: CycleInfoDeserializer::new(
                 max_rolls_length,
                 max_production_stats_length,
+                stake_delegation_version,
             ),
         }
     }
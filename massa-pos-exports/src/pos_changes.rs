@@ -4,17 +4,23 @@ use crate::{
 };
 use bitvec::prelude::*;
 use massa_models::{
-    address::{Address, AddressSerializer},
+    address::{Address, AddressDeserializer, AddressSerializer},
     prehash::PreHashMap,
     serialization::{BitVecDeserializer, BitVecSerializer},
 };
-use massa_serialization::{Deserializer, SerializeError, Serializer, U64VarIntSerializer};
+use massa_serialization::{
+    Deserializer, OptionDeserializer, OptionSerializer, SerializeError, Serializer,
+    U64VarIntDeserializer, U64VarIntSerializer,
+};
 use nom::{
+    combinator::cond,
     error::{context, ContextError, ParseError},
+    multi::length_count,
     sequence::tuple,
     IResult, Parser,
 };
 use serde::{Deserialize, Serialize};
+use std::ops::Bound::Included;
 
 /// Recap of all PoS changes
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -31,6 +37,9 @@ pub struct PoSChanges {
     /// set deferred credits indexed by target slot (can be set to 0 to cancel some, in case of slash)
     /// ordered structure to ensure slot iteration order is deterministic
     pub deferred_credits: DeferredCredits,
+
+    /// new stake delegations, from the roll owner to its operator (`None` revokes the delegation)
+    pub delegation_changes: PreHashMap<Address, Option<Address>>,
 }
 
 impl Default for PoSChanges {
@@ -40,6 +49,7 @@ impl Default for PoSChanges {
             roll_changes: Default::default(),
             production_stats: Default::default(),
             deferred_credits: DeferredCredits::new_with_hash(),
+            delegation_changes: Default::default(),
         }
     }
 }
@@ -51,6 +61,7 @@ impl PoSChanges {
             && self.roll_changes.is_empty()
             && self.production_stats.is_empty()
             && self.deferred_credits.credits.is_empty()
+            && self.delegation_changes.is_empty()
     }
 
     /// Extends the current `PosChanges` with another one
@@ -71,6 +82,9 @@ impl PoSChanges {
 
         // extend deferred credits
         self.deferred_credits.extend(other.deferred_credits);
+
+        // extend delegation changes
+        self.delegation_changes.extend(other.delegation_changes);
     }
 }
 
@@ -81,23 +95,24 @@ pub struct PoSChangesSerializer {
     production_stats_serializer: ProductionStatsSerializer,
     address_serializer: AddressSerializer,
     deferred_credits_serializer: DeferredCreditsSerializer,
-}
-
-impl Default for PoSChangesSerializer {
-    fn default() -> Self {
-        Self::new()
-    }
+    operator_serializer: OptionSerializer<Address, AddressSerializer>,
+    stake_delegation_version: u32,
 }
 
 impl PoSChangesSerializer {
     /// Create a new `PoSChanges` Serializer
-    pub fn new() -> PoSChangesSerializer {
+    ///
+    /// # Arguments
+    /// * `stake_delegation_version`: version of the `StakeDelegation` MIP component, the delegation changes are only serialized from version 1
+    pub fn new(stake_delegation_version: u32) -> PoSChangesSerializer {
         PoSChangesSerializer {
             bit_vec_serializer: BitVecSerializer::new(),
             u64_serializer: U64VarIntSerializer::new(),
             production_stats_serializer: ProductionStatsSerializer::new(),
             address_serializer: AddressSerializer::new(),
             deferred_credits_serializer: DeferredCreditsSerializer::new(),
+            operator_serializer: OptionSerializer::new(AddressSerializer::new()),
+            stake_delegation_version,
         }
     }
 }
@@ -124,6 +139,20 @@ impl Serializer<PoSChanges> for PoSChangesSerializer {
         self.deferred_credits_serializer
             .serialize(&value.deferred_credits, buffer)?;
 
+        // delegation_changes
+        if self.stake_delegation_version >= 1 {
+            self.u64_serializer
+                .serialize(&(value.delegation_changes.len() as u64), buffer)?;
+            for (owner, operator) in value.delegation_changes.iter() {
+                self.address_serializer.serialize(owner, buffer)?;
+                self.operator_serializer.serialize(operator, buffer)?;
+            }
+        } else if !value.delegation_changes.is_empty() {
+            return Err(SerializeError::GeneralError(
+                "delegation changes before the activation of stake delegation".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -134,15 +163,22 @@ pub struct PoSChangesDeserializer {
     rolls_deserializer: RollsDeserializer,
     production_stats_deserializer: ProductionStatsDeserializer,
     deferred_credits_deserializer: DeferredCreditsDeserializer,
+    delegations_length_deserializer: U64VarIntDeserializer,
+    address_deserializer: AddressDeserializer,
+    operator_deserializer: OptionDeserializer<Address, AddressDeserializer>,
+    stake_delegation_version: u32,
 }
 
 impl PoSChangesDeserializer {
     /// Create a new `PoSChanges` Deserializer
+    ///
+    /// The delegation changes are only deserialized from version 1 of the `StakeDelegation` MIP component.
     pub fn new(
         thread_count: u8,
         max_rolls_length: u64,
         max_production_stats_length: u64,
         max_credits_length: u64,
+        stake_delegation_version: u32,
     ) -> PoSChangesDeserializer {
         PoSChangesDeserializer {
             bit_vec_deserializer: BitVecDeserializer::new(),
@@ -155,6 +191,13 @@ impl PoSChangesDeserializer {
                 max_credits_length,
                 true,
             ),
+            delegations_length_deserializer: U64VarIntDeserializer::new(
+                Included(u64::MIN),
+                Included(max_rolls_length),
+            ),
+            address_deserializer: AddressDeserializer::new(),
+            operator_deserializer: OptionDeserializer::new(AddressDeserializer::new()),
+            stake_delegation_version,
         }
     }
 }
@@ -179,14 +222,39 @@ impl Deserializer<PoSChanges> for PoSChangesDeserializer {
                 context("Failed deferred_credits deserialization", |input| {
                     self.deferred_credits_deserializer.deserialize(input)
                 }),
+                context(
+                    "Failed delegation_changes deserialization",
+                    cond(
+                        self.stake_delegation_version >= 1,
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.delegations_length_deserializer.deserialize(input)
+                            }),
+                            tuple((
+                                context("Failed owner deserialization", |input| {
+                                    self.address_deserializer.deserialize(input)
+                                }),
+                                context("Failed operator deserialization", |input| {
+                                    self.operator_deserializer.deserialize(input)
+                                }),
+                            )),
+                        ),
+                    ),
+                ),
             )),
         )
         .map(
-            |(seed_bits, roll_changes, production_stats, deferred_credits)| PoSChanges {
-                seed_bits,
-                roll_changes: roll_changes.into_iter().collect(),
-                production_stats,
-                deferred_credits,
+            |(seed_bits, roll_changes, production_stats, deferred_credits, delegation_changes)| {
+                PoSChanges {
+                    seed_bits,
+                    roll_changes: roll_changes.into_iter().collect(),
+                    production_stats,
+                    deferred_credits,
+                    delegation_changes: delegation_changes
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect(),
+                }
             },
         )
         .parse(buffer)
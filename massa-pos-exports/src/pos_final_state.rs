@@ -6,7 +6,7 @@ use crate::{
 use crate::{DeferredCredits, PoSConfig};
 use bitvec::vec::BitVec;
 use massa_db::{
-    DBBatch, MassaDB, CF_ERROR, CYCLE_HISTORY_DESER_ERROR, CYCLE_HISTORY_PREFIX,
+    DBBatch, MassaArchive, MassaDB, CF_ERROR, CYCLE_HISTORY_DESER_ERROR, CYCLE_HISTORY_PREFIX,
     CYCLE_HISTORY_SER_ERROR, DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX,
     DEFERRED_CREDITS_SER_ERROR, STATE_CF,
 };
@@ -30,6 +30,7 @@ const RNG_SEED_IDENT: u8 = 1u8;
 const FINAL_STATE_HASH_SNAPSHOT_IDENT: u8 = 2u8;
 const ROLL_COUNT_IDENT: u8 = 3u8;
 const PROD_STATS_IDENT: u8 = 4u8;
+const DELEGATION_IDENT: u8 = 5u8;
 
 // Production stats idents
 const PROD_STATS_FAIL_IDENT: u8 = 0u8;
@@ -116,6 +117,27 @@ macro_rules! prod_stats_success_key {
     };
 }
 
+/// Delegation key prefix macro
+#[macro_export]
+macro_rules! delegation_prefix {
+    ($cycle_prefix:expr) => {
+        [&$cycle_prefix[..], &[DELEGATION_IDENT]].concat()
+    };
+}
+
+/// Delegation key formatting macro
+#[macro_export]
+macro_rules! delegation_key {
+    ($cycle_prefix:expr, $addr:expr) => {
+        [
+            &$cycle_prefix[..],
+            &[DELEGATION_IDENT],
+            &$addr.to_prefixed_bytes()[..],
+        ]
+        .concat()
+    };
+}
+
 /// Deferred credits key formatting macro
 #[macro_export]
 macro_rules! deferred_credits_key {
@@ -149,6 +171,8 @@ pub struct PoSFinalState {
     pub cycle_info_serializer: CycleHistorySerializer,
     /// cycle info deserializer
     pub cycle_info_deserializer: CycleHistoryDeserializer,
+    /// archive of the pruned cycles, in archival mode
+    pub archive: Option<Arc<MassaArchive>>,
 }
 
 impl PoSFinalState {
@@ -174,10 +198,13 @@ impl PoSFinalState {
 
         let deferred_credits_deserializer =
             DeferredCreditsDeserializer::new(config.thread_count, config.max_credit_length, true);
+        // the delegations of the cycles are stored under their own keys,
+        // the cycle info serializers only handle the other fields of the cycle history
         let cycle_info_deserializer = CycleHistoryDeserializer::new(
             config.cycle_history_length as u64,
             config.max_rolls_length,
             config.max_production_stats_length,
+            0,
         );
 
         let pos_state = Self {
@@ -190,8 +217,9 @@ impl PoSFinalState {
             initial_seeds,
             deferred_credits_serializer: DeferredCreditsSerializer::new(),
             deferred_credits_deserializer,
-            cycle_info_serializer: CycleHistorySerializer::new(),
+            cycle_info_serializer: CycleHistorySerializer::new(0),
             cycle_info_deserializer,
+            archive: None,
         };
 
         Ok(pos_state)
@@ -239,6 +267,7 @@ impl PoSFinalState {
                 self.initial_rolls.clone(),
                 rng_seed,
                 PreHashMap::default(),
+                BTreeMap::new(),
             ),
             batch,
         );
@@ -285,6 +314,7 @@ impl PoSFinalState {
                 last_cycle_info.roll_counts.clone(),
                 rng_seed,
                 last_cycle_info.production_stats.clone(),
+                last_cycle_info.delegations.clone(),
             ),
             batch,
         );
@@ -292,8 +322,12 @@ impl PoSFinalState {
         Ok(())
     }
 
-    /// Deletes a given cycle from RocksDB
+    /// Deletes a given cycle from RocksDB, after archiving it in archival mode
     pub fn delete_cycle_info(&mut self, cycle: u64, batch: &mut DBBatch) {
+        if let Some(archive) = &self.archive {
+            archive.archive_cycle(cycle, &self.get_cycle_info(cycle));
+        }
+
         let db = self.db.read();
         let handle = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
 
@@ -309,7 +343,12 @@ impl PoSFinalState {
 
     /// Sends the current draw inputs (initial or bootstrapped) to the selector.
     /// Waits for the initial draws to be performed.
-    pub fn compute_initial_draws(&mut self) -> PosResult<()> {
+    ///
+    /// `stake_delegation_version` gives the version of the `StakeDelegation` MIP component at the end of a cycle.
+    pub fn compute_initial_draws(
+        &mut self,
+        stake_delegation_version: impl Fn(u64) -> u32,
+    ) -> PosResult<()> {
         // if cycle_history starts at a cycle that is strictly higher than 0, do not feed cycles 0, 1 to selector
         let history_starts_late = self
             .cycle_history_cache
@@ -322,7 +361,8 @@ impl PoSFinalState {
         // feed cycles 0, 1 to selector if necessary
         if !history_starts_late {
             for draw_cycle in 0u64..=1 {
-                self.feed_selector(draw_cycle)?;
+                // the draws of cycles 0 and 1 look back to the initial rolls, without delegation
+                self.feed_selector(draw_cycle, 0)?;
                 max_cycle = Some(draw_cycle);
             }
         }
@@ -340,7 +380,7 @@ impl PoSFinalState {
             let draw_cycle = hist_item.0.checked_add(2).ok_or_else(|| {
                 PosError::OverflowError("cycle overflow in give_selector_controller".into())
             })?;
-            self.feed_selector(draw_cycle)?;
+            self.feed_selector(draw_cycle, stake_delegation_version(hist_item.0))?;
             max_cycle = Some(draw_cycle);
         }
 
@@ -363,18 +403,21 @@ impl PoSFinalState {
     ///     extend `roll_counts` with `changes.roll_changes`
     ///         delete all entries from `roll_counts` for which the roll count is zero
     ///     add each element of `changes.production_stats` to the cycle's `production_stats`
+    ///     set or remove the cycle's delegations with `changes.delegation_changes`
     /// for each `changes.deferred_credits` targeting cycle Ct:
     ///     overwrite `self.deferred_credits` entries of cycle Ct in `cycle_history` with the ones from change
     ///         remove entries for which Amount = 0
     /// if slot S was the last of cycle C:
     ///     set complete=true for cycle C in the history
     ///     compute the seed hash and notifies the `PoSDrawer` for cycle `C+3`
+    ///     with `stake_delegation_version`, the version of the `StakeDelegation` MIP component at slot S
     ///
     pub fn apply_changes_to_batch(
         &mut self,
         changes: PoSChanges,
         slot: Slot,
         feed_selector: bool,
+        stake_delegation_version: u32,
         batch: &mut DBBatch,
     ) -> PosResult<()> {
         let slots_per_cycle: usize = self
@@ -405,6 +448,8 @@ impl PoSFinalState {
                         roll_counts,
                         BitVec::with_capacity(slots_per_cycle),
                         PreHashMap::default(),
+                        // delegations stay in effect until they are revoked
+                        self.get_all_delegations(info.0),
                     ),
                     batch,
                 );
@@ -456,6 +501,11 @@ impl PoSFinalState {
             }
         }
 
+        // set delegations
+        for (owner, operator) in changes.delegation_changes {
+            self.put_cycle_history_delegation(cycle, &owner, operator.as_ref(), batch);
+        }
+
         // if the cycle just completed, check that it has the right number of seed bits
         if complete && rng_seed.len() != slots_per_cycle {
             panic!(
@@ -480,42 +530,50 @@ impl PoSFinalState {
             slot, self.cycle_history_cache
         );
         if complete && feed_selector {
-            self.feed_selector(cycle.checked_add(2).ok_or_else(|| {
-                PosError::OverflowError("cycle overflow when feeding selector".into())
-            })?)
+            self.feed_selector(
+                cycle.checked_add(2).ok_or_else(|| {
+                    PosError::OverflowError("cycle overflow when feeding selector".into())
+                })?,
+                stake_delegation_version,
+            )
         } else {
             Ok(())
         }
     }
 
     /// Feeds the selector targeting a given draw cycle
-    pub fn feed_selector(&self, draw_cycle: u64) -> PosResult<()> {
+    ///
+    /// `stake_delegation_version` is the version of the `StakeDelegation` MIP component
+    /// at the end of `draw_cycle - 2`, the look back delegations are only drawn from version 1
+    pub fn feed_selector(&self, draw_cycle: u64, stake_delegation_version: u32) -> PosResult<()> {
         // get roll lookback
 
-        let (lookback_rolls, lookback_state_hash) = match draw_cycle.checked_sub(3) {
-            // looking back in history
-            Some(c) => {
-                let index = self
-                    .get_cycle_index(c)
-                    .ok_or(PosError::CycleUnavailable(c))?;
-                let cycle_info = &self.cycle_history_cache[index];
-                if !cycle_info.1 {
-                    return Err(PosError::CycleUnfinished(c));
+        let (lookback_rolls, lookback_delegations, lookback_state_hash) =
+            match draw_cycle.checked_sub(3) {
+                // looking back in history
+                Some(c) => {
+                    let index = self
+                        .get_cycle_index(c)
+                        .ok_or(PosError::CycleUnavailable(c))?;
+                    let cycle_info = &self.cycle_history_cache[index];
+                    if !cycle_info.1 {
+                        return Err(PosError::CycleUnfinished(c));
+                    }
+                    // take the final_state_hash_snapshot at cycle - 3
+                    // it will later be combined with rng_seed from cycle - 2 to determine the selection seed
+                    // do this here to avoid a potential attacker manipulating the selections
+                    let state_hash = self.get_cycle_history_final_state_hash_snapshot(cycle_info.0);
+                    (
+                        self.get_all_roll_counts(cycle_info.0),
+                        self.get_all_delegations(cycle_info.0),
+                        Some(state_hash.expect(
+                            "critical: a complete cycle must contain a final state hash snapshot",
+                        )),
+                    )
                 }
-                // take the final_state_hash_snapshot at cycle - 3
-                // it will later be combined with rng_seed from cycle - 2 to determine the selection seed
-                // do this here to avoid a potential attacker manipulating the selections
-                let state_hash = self.get_cycle_history_final_state_hash_snapshot(cycle_info.0);
-                (
-                    self.get_all_roll_counts(cycle_info.0),
-                    Some(state_hash.expect(
-                        "critical: a complete cycle must contain a final state hash snapshot",
-                    )),
-                )
-            }
-            // looking back to negative cycles
-            None => (self.initial_rolls.clone(), None),
-        };
+                // looking back to negative cycles
+                None => (self.initial_rolls.clone(), BTreeMap::new(), None),
+            };
 
         // get seed lookback
         let lookback_seed = match draw_cycle.checked_sub(2) {
@@ -542,9 +600,13 @@ impl PoSFinalState {
         };
 
         // feed selector
-        self.selector
-            .as_ref()
-            .feed_cycle(draw_cycle, lookback_rolls, lookback_seed)
+        self.selector.as_ref().feed_cycle(
+            draw_cycle,
+            lookback_rolls,
+            lookback_delegations,
+            lookback_seed,
+            stake_delegation_version,
+        )
     }
 
     /// Feeds the selector targeting a given draw cycle
@@ -764,6 +826,67 @@ impl PoSFinalState {
         roll_counts
     }
 
+    /// Get all the stake delegations, from the roll owner to its operator, for a given cycle
+    pub fn get_all_delegations(&self, cycle: u64) -> BTreeMap<Address, Address> {
+        let db = self.db.read();
+        let handle = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+
+        let mut delegations: BTreeMap<Address, Address> = BTreeMap::new();
+
+        let prefix = delegation_prefix!(self.cycle_history_cycle_prefix(cycle));
+        for (serialized_key, serialized_value) in
+            db.db.prefix_iterator_cf(handle, &prefix).flatten()
+        {
+            if !serialized_key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+
+            let (_, owner) = self
+                .cycle_info_deserializer
+                .cycle_info_deserializer
+                .rolls_deser
+                .address_deserializer
+                .deserialize::<DeserializeError>(&serialized_key[prefix.len()..])
+                .expect(CYCLE_HISTORY_DESER_ERROR);
+
+            let (_, operator) = self
+                .cycle_info_deserializer
+                .cycle_info_deserializer
+                .rolls_deser
+                .address_deserializer
+                .deserialize::<DeserializeError>(&serialized_value)
+                .expect(CYCLE_HISTORY_DESER_ERROR);
+
+            delegations.insert(owner, operator);
+        }
+
+        delegations
+    }
+
+    /// Retrieves the operator a given address delegated its stake to at the latest cycle
+    pub fn get_delegation_for(&self, addr: &Address) -> Option<Address> {
+        self.cycle_history_cache.back().and_then(|info| {
+            let db = self.db.read();
+            let handle = db.db.cf_handle(STATE_CF).expect(CF_ERROR);
+
+            let key = delegation_key!(self.cycle_history_cycle_prefix(info.0), addr);
+
+            db.db
+                .get_cf(handle, key)
+                .expect(CYCLE_HISTORY_DESER_ERROR)
+                .map(|serialized_value| {
+                    let (_, operator) = self
+                        .cycle_info_deserializer
+                        .cycle_info_deserializer
+                        .rolls_deser
+                        .address_deserializer
+                        .deserialize::<DeserializeError>(&serialized_value)
+                        .expect(CYCLE_HISTORY_DESER_ERROR);
+                    operator
+                })
+        })
+    }
+
     /// Retrieves the productions statistics for all addresses on a given cycle
     pub fn get_all_production_stats(
         &self,
@@ -943,8 +1066,14 @@ impl PoSFinalState {
             .get_all_production_stats(cycle)
             .unwrap_or(PreHashMap::default());
 
-        let mut cycle_info =
-            CycleInfo::new_with_hash(cycle, complete, roll_counts, rng_seed, production_stats);
+        let mut cycle_info = CycleInfo::new_with_hash(
+            cycle,
+            complete,
+            roll_counts,
+            rng_seed,
+            production_stats,
+            self.get_all_delegations(cycle),
+        );
         cycle_info.final_state_hash_snapshot = final_state_hash_snapshot;
         cycle_info
     }
@@ -1066,6 +1195,9 @@ impl PoSFinalState {
                 batch,
             );
         }
+        for (owner, operator) in cycle_info.delegations.iter() {
+            self.put_cycle_history_delegation(cycle_info.cycle, owner, Some(operator), batch);
+        }
         self.cycle_history_cache
             .push_back((cycle_info.cycle, cycle_info.complete));
     }
@@ -1193,6 +1325,35 @@ impl PoSFinalState {
         }
     }
 
+    /// Internal function to set or remove (`None` operator) the stake delegation of an address in the cycle history
+    fn put_cycle_history_delegation(
+        &self,
+        cycle: u64,
+        owner: &Address,
+        operator: Option<&Address>,
+        batch: &mut DBBatch,
+    ) {
+        let db = self.db.read();
+
+        let prefix = self.cycle_history_cycle_prefix(cycle);
+
+        if let Some(operator) = operator {
+            let mut serialized_operator = Vec::new();
+            self.cycle_info_serializer
+                .cycle_info_serializer
+                .address_ser
+                .serialize(operator, &mut serialized_operator)
+                .expect(CYCLE_HISTORY_SER_ERROR);
+            db.put_or_update_entry_value(
+                batch,
+                delegation_key!(prefix, owner),
+                &serialized_operator,
+            );
+        } else {
+            db.delete_key(batch, delegation_key!(prefix, owner));
+        }
+    }
+
     /// Internal function to put an entry
     pub fn put_deferred_credits_entry(
         &self,
@@ -1380,6 +1541,30 @@ impl PoSFinalState {
                     }
                 }
             }
+            DELEGATION_IDENT => {
+                let Ok((rest, _owner)): std::result::Result<(&[u8], Address), nom::Err<massa_serialization::DeserializeError<'_>>> = self
+                    .cycle_info_deserializer
+                    .cycle_info_deserializer
+                    .rolls_deser
+                    .address_deserializer
+                    .deserialize::<DeserializeError>(&rest[1..]) else {
+                    return false;
+                };
+                if !rest.is_empty() {
+                    return false;
+                }
+                let Ok((rest, _operator)): std::result::Result<(&[u8], Address), nom::Err<massa_serialization::DeserializeError<'_>>> = self
+                    .cycle_info_deserializer
+                    .cycle_info_deserializer
+                    .rolls_deser
+                    .address_deserializer
+                    .deserialize::<DeserializeError>(serialized_value) else {
+                    return false;
+                };
+                if !rest.is_empty() {
+                    return false;
+                }
+            }
             _ => {
                 return false;
             }
@@ -1467,6 +1652,7 @@ fn test_pos_final_state_hash_computation() {
     use crate::test_exports::MockSelectorController;
     use crate::DeferredCredits;
     use crate::PoSFinalState;
+    use crate::{CycleInfoDeserializer, CycleInfoSerializer};
     use bitvec::prelude::*;
     use massa_db::{MassaDB, MassaDBConfig};
     use massa_models::config::constants::{
@@ -1508,6 +1694,7 @@ fn test_pos_final_state_hash_computation() {
         pos_config.cycle_history_length as u64,
         pos_config.max_rolls_length,
         pos_config.max_production_stats_length,
+        0,
     );
 
     let mut pos_state = PoSFinalState {
@@ -1520,8 +1707,9 @@ fn test_pos_final_state_hash_computation() {
         initial_seeds,
         deferred_credits_serializer: DeferredCreditsSerializer::new(),
         deferred_credits_deserializer,
-        cycle_info_serializer: CycleHistorySerializer::new(),
+        cycle_info_serializer: CycleHistorySerializer::new(0),
         cycle_info_deserializer,
+        archive: None,
     };
 
     pos_state.recompute_pos_state_caches();
@@ -1549,11 +1737,12 @@ fn test_pos_final_state_hash_computation() {
         roll_changes: roll_changes.clone(),
         production_stats: production_stats.clone(),
         deferred_credits: DeferredCredits::new_with_hash(),
+        delegation_changes: Default::default(),
     };
 
     let mut batch = DBBatch::new();
     pos_state
-        .apply_changes_to_batch(changes, Slot::new(0, 0), false, &mut batch)
+        .apply_changes_to_batch(changes, Slot::new(0, 0), false, 0, &mut batch)
         .unwrap();
     db.write()
         .write_batch(batch, Default::default(), Some(Slot::new(0, 0)), false);
@@ -1574,16 +1763,18 @@ fn test_pos_final_state_hash_computation() {
         roll_changes: roll_changes.clone(),
         production_stats: production_stats.clone(),
         deferred_credits: DeferredCredits::new_with_hash(),
+        delegation_changes: Default::default(),
     };
 
     let mut batch = DBBatch::new();
     pos_state
-        .apply_changes_to_batch(changes, Slot::new(0, 1), false, &mut batch)
+        .apply_changes_to_batch(changes, Slot::new(0, 1), false, 0, &mut batch)
         .unwrap();
     db.write()
         .write_batch(batch, Default::default(), Some(Slot::new(0, 1)), false);
 
-    // update changes twice
+    // update changes twice, delegating the stake of addr
+    let operator = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
     roll_changes.clear();
     roll_changes.insert(addr, 0);
     production_stats.clear();
@@ -1600,11 +1791,12 @@ fn test_pos_final_state_hash_computation() {
         roll_changes,
         production_stats,
         deferred_credits: DeferredCredits::new_with_hash(),
+        delegation_changes: PreHashMap::from_iter([(addr, Some(operator))]),
     };
 
     let mut batch = DBBatch::new();
     pos_state
-        .apply_changes_to_batch(changes, Slot::new(1, 0), false, &mut batch)
+        .apply_changes_to_batch(changes, Slot::new(1, 0), false, 1, &mut batch)
         .unwrap();
     db.write()
         .write_batch(batch, Default::default(), Some(Slot::new(1, 0)), false);
@@ -1631,8 +1823,13 @@ fn test_pos_final_state_hash_computation() {
         BTreeMap::default(),
         bitvec![u8, Lsb0; 0, 0, 0, 1, 1, 0, 0, 1],
         prod_stats,
+        BTreeMap::from([(addr, operator)]),
     );
 
+    assert_eq!(
+        cycle_info_a.delegations, cycle_info_b.delegations,
+        "delegations mismatch"
+    );
     assert_eq!(
         cycle_info_a.roll_counts_hash, cycle_info_b.roll_counts_hash,
         "roll_counts_hash mismatch"
@@ -1645,4 +1842,61 @@ fn test_pos_final_state_hash_computation() {
         cycle_info_a.cycle_global_hash, cycle_info_b.cycle_global_hash,
         "global_hash mismatch"
     );
+
+    // the delegations are covered by the hash
+    let cycle_info_c = CycleInfo::new_with_hash(
+        0,
+        false,
+        BTreeMap::default(),
+        cycle_info_b.rng_seed.clone(),
+        cycle_info_b.production_stats.clone(),
+        BTreeMap::default(),
+    );
+    assert_ne!(
+        cycle_info_b.cycle_global_hash, cycle_info_c.cycle_global_hash,
+        "delegations not hashed"
+    );
+
+    // the delegations are serialized once stake delegation is active
+    let mut serialized = Vec::new();
+    CycleInfoSerializer::new(1)
+        .serialize(&cycle_info_a, &mut serialized)
+        .unwrap();
+    let (rest, deserialized) = CycleInfoDeserializer::new(
+        pos_state.config.max_rolls_length,
+        pos_state.config.max_production_stats_length,
+        1,
+    )
+    .deserialize::<DeserializeError>(&serialized)
+    .unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        deserialized, cycle_info_a,
+        "cycle info serialization mismatch"
+    );
+
+    // before its activation, the serialization of a cycle without delegation is left unchanged
+    let mut serialized_v0 = Vec::new();
+    pos_state
+        .cycle_info_serializer
+        .cycle_info_serializer
+        .serialize(&cycle_info_c, &mut serialized_v0)
+        .unwrap();
+    let mut serialized_v1 = Vec::new();
+    CycleInfoSerializer::new(1)
+        .serialize(&cycle_info_c, &mut serialized_v1)
+        .unwrap();
+    assert_eq!(serialized_v0.len() + 1, serialized_v1.len());
+    let (rest, deserialized) = pos_state
+        .cycle_info_deserializer
+        .cycle_info_deserializer
+        .deserialize::<DeserializeError>(&serialized_v0)
+        .unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(deserialized, cycle_info_c);
+    assert!(pos_state
+        .cycle_info_serializer
+        .cycle_info_serializer
+        .serialize(&cycle_info_a, &mut Vec::new())
+        .is_err());
 }
@@ -24,8 +24,12 @@ pub enum MockSelectorControllerMessage {
         cycle: u64,
         /// look back rolls
         lookback_rolls: BTreeMap<Address, u64>,
+        /// look back stake delegations
+        lookback_delegations: BTreeMap<Address, Address>,
         /// look back seed
         lookback_seed: Hash,
+        /// version of the stake delegation MIP component
+        stake_delegation_version: u32,
     },
     /// Get a list of slots where address has been chosen to produce a block and a list where he is chosen for the endorsements.
     /// Look from the start slot to the end slot.
@@ -93,14 +97,18 @@ impl SelectorController for MockSelectorController {
         &self,
         cycle: u64,
         lookback_rolls: BTreeMap<Address, u64>,
+        lookback_delegations: BTreeMap<Address, Address>,
         lookback_seed: Hash,
+        stake_delegation_version: u32,
     ) -> PosResult<()> {
         self.0
             .lock()
             .send(MockSelectorControllerMessage::FeedCycle {
                 cycle,
                 lookback_rolls,
+                lookback_delegations,
                 lookback_seed,
+                stake_delegation_version,
             })
             .unwrap();
         Ok(())
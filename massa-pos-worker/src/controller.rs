@@ -57,7 +57,9 @@ impl SelectorController for SelectorControllerImpl {
     /// # Arguments
     /// * `cycle`: cycle number to be drawn
     /// * `lookback_rolls`: look back rolls used for the draw (cycle - 3)
+    /// * `lookback_delegations`: look back stake delegations, owner to operator (cycle - 3)
     /// * `lookback_seed`: look back seed hash for the draw (cycle - 2)
+    /// * `stake_delegation_version`: version of the `StakeDelegation` MIP component at the end of cycle - 2
 
     /// * This a non-blocking function where the worker is separate,
     /// * so the feed is queued and not applied immediately and that's
@@ -69,7 +71,9 @@ impl SelectorController for SelectorControllerImpl {
         &self,
         cycle: u64,
         lookback_rolls: BTreeMap<Address, u64>,
+        lookback_delegations: BTreeMap<Address, Address>,
         lookback_seed: Hash,
+        stake_delegation_version: u32,
     ) -> PosResult<()> {
         // check status
         {
@@ -82,7 +86,9 @@ impl SelectorController for SelectorControllerImpl {
             .send(Command::DrawInput {
                 cycle,
                 lookback_rolls,
+                lookback_delegations,
                 lookback_seed,
+                stake_delegation_version,
             })
             .map_err(|_err| {
                 PosError::ChannelDown(
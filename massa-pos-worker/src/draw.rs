@@ -17,7 +17,9 @@ use tracing::debug;
 /// # Parameters
 /// * `cycle`: Cycle to draw
 /// * `lookback_rolls`: Roll counts at look back (`cycle-3`)
+/// * `lookback_delegations`: Stake delegations at look back (`cycle-3`), the operators create the blocks and endorsements of the drawn owners
 /// * `lookback_seed`: RNG seed at look back (`cycle-2`)
+/// * `stake_delegation_version`: version of the `StakeDelegation` MIP component, the delegations are ignored below version 1
 ///
 /// # Result
 /// - The draws can throw the errors of the function `get_params` and from the
@@ -29,7 +31,9 @@ pub(crate) fn perform_draws(
     cfg: &SelectorConfig,
     cycle: u64,
     lookback_rolls: BTreeMap<Address, u64>,
+    lookback_delegations: BTreeMap<Address, Address>,
     lookback_seed: Hash,
+    stake_delegation_version: u32,
 ) -> PosResult<CycleDraws> {
     // get seeded RNG
     let mut rng = Xoshiro256PlusPlus::from_seed(*lookback_seed.to_bytes());
@@ -63,7 +67,7 @@ pub(crate) fn perform_draws(
     let mut count = 0;
     loop {
         // draw block creator
-        let producer = if cur_slot.period > 0 {
+        let producer_owner = if cur_slot.period > 0 {
             addresses[dist.sample(&mut rng)]
         } else {
             // force draws for genesis blocks
@@ -71,13 +75,24 @@ pub(crate) fn perform_draws(
         };
 
         // draw endorsement creators
-        let endorsements: Vec<_> = (0..cfg.endorsement_count)
+        let endorsement_owners: Vec<_> = (0..cfg.endorsement_count)
             .map(|_index| addresses[dist.sample(&mut rng)])
             .collect();
 
+        // once stake delegation is active, the operators create the blocks and endorsements
+        // of the owners who delegated their stake
+        let operator_of = |owner: &Address| {
+            if stake_delegation_version >= 1 {
+                *lookback_delegations.get(owner).unwrap_or(owner)
+            } else {
+                *owner
+            }
+        };
         let selection = Selection {
-            producer,
-            endorsements,
+            producer: operator_of(&producer_owner),
+            endorsements: endorsement_owners.iter().map(operator_of).collect(),
+            producer_owner,
+            endorsement_owners,
         };
         if count < 5 {
             five_first_slots.push((cur_slot, selection.clone()));
@@ -103,3 +118,106 @@ pub(crate) fn perform_draws(
 
     Ok(cycle_draws)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+
+    fn new_address() -> Address {
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+    }
+
+    #[test]
+    fn test_draws_with_delegation() {
+        let cfg = SelectorConfig::default();
+        let owner = new_address();
+        let other_owner = new_address();
+        let operator = new_address();
+        let rolls = BTreeMap::from([(owner, 10), (other_owner, 10)]);
+        let seed = Hash::compute_from(b"seed");
+
+        let draws = perform_draws(&cfg, 1, rolls.clone(), BTreeMap::new(), seed, 1).unwrap();
+        let delegated_draws = perform_draws(
+            &cfg,
+            1,
+            rolls.clone(),
+            BTreeMap::from([(owner, operator)]),
+            seed,
+            1,
+        )
+        .unwrap();
+        assert_eq!(draws.draws.len(), delegated_draws.draws.len());
+
+        // the delegations are ignored until stake delegation is active
+        let inactive_draws =
+            perform_draws(&cfg, 1, rolls, BTreeMap::from([(owner, operator)]), seed, 0).unwrap();
+        assert_eq!(inactive_draws.draws, draws.draws);
+
+        let mut operator_productions = 0;
+        let mut operator_endorsements = 0;
+        for (slot, selection) in delegated_draws.draws.iter() {
+            // the delegation does not change the drawn owners
+            let undelegated = draws.draws.get(slot).unwrap();
+            assert_eq!(selection.producer_owner, undelegated.producer_owner);
+            assert_eq!(selection.endorsement_owners, undelegated.endorsement_owners);
+            assert_eq!(undelegated.producer, undelegated.producer_owner);
+            assert_eq!(undelegated.endorsements, undelegated.endorsement_owners);
+
+            // the operator creates the blocks and endorsements of the owner who delegated
+            let expected_producer = if selection.producer_owner == owner {
+                operator_productions += 1;
+                operator
+            } else {
+                selection.producer_owner
+            };
+            assert_eq!(selection.producer, expected_producer);
+            for (index, endorsement_owner) in selection.endorsement_owners.iter().enumerate() {
+                let expected_endorser = if *endorsement_owner == owner {
+                    operator_endorsements += 1;
+                    operator
+                } else {
+                    *endorsement_owner
+                };
+                assert_eq!(selection.endorsements[index], expected_endorser);
+            }
+        }
+        assert!(operator_productions > 0);
+        assert!(operator_endorsements > 0);
+    }
+
+    #[test]
+    fn test_delegated_selection_owners() {
+        let cfg = SelectorConfig::default();
+        let owner = new_address();
+        let operator = new_address();
+        let outsider = new_address();
+        let draws = perform_draws(
+            &cfg,
+            1,
+            BTreeMap::from([(owner, 10)]),
+            BTreeMap::from([(owner, operator)]),
+            Hash::compute_from(b"seed"),
+            1,
+        )
+        .unwrap();
+
+        let slot = Slot::new_first_of_cycle(1, cfg.periods_per_cycle).unwrap();
+        let selection = draws.draws.get(&slot).unwrap();
+        assert_eq!(selection.producer, operator);
+
+        // the rewards of the operator go to the owner
+        assert_eq!(selection.get_producer_owner(&operator), owner);
+        for index in 0..selection.endorsements.len() {
+            assert_eq!(selection.get_endorsement_owner(index, &operator), owner);
+        }
+
+        // an address which was not selected keeps its rewards
+        assert_eq!(selection.get_producer_owner(&outsider), outsider);
+        assert_eq!(selection.get_endorsement_owner(0, &outsider), outsider);
+        assert_eq!(
+            selection.get_endorsement_owner(selection.endorsements.len(), &operator),
+            operator
+        );
+    }
+}
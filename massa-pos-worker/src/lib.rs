@@ -23,7 +23,9 @@ pub(crate) enum Command {
     DrawInput {
         cycle: u64,
         lookback_rolls: BTreeMap<Address, u64>,
+        lookback_delegations: BTreeMap<Address, Address>,
         lookback_seed: Hash,
+        stake_delegation_version: u32,
     },
     /// Stop the thread (usually sent by the manager and pushed at the top
     /// of the command queue)
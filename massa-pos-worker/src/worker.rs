@@ -112,13 +112,22 @@ impl SelectorThread {
             let Ok(Command::DrawInput {
                 cycle,
                 lookback_rolls,
+                lookback_delegations,
                 lookback_seed,
+                stake_delegation_version,
             }) = self.input_mpsc.recv() else {
                 break;
             };
 
             // perform draws
-            let draws_result = perform_draws(&self.cfg, cycle, lookback_rolls, lookback_seed);
+            let draws_result = perform_draws(
+                &self.cfg,
+                cycle,
+                lookback_rolls,
+                lookback_delegations,
+                lookback_seed,
+                stake_delegation_version,
+            );
 
             // add result to cache and notify waiters
             self.process_draws_result(cycle, draws_result)?;
@@ -6,11 +6,12 @@ use std::net::SocketAddr;
 use crate::error::ProtocolError;
 use crate::BootstrapPeers;
 
-use crate::PeerId;
+use crate::{BanTarget, PeerBan, PeerId};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use peernet::peer::PeerConnectionType;
 
 #[cfg_attr(any(test, feature = "testing"), mockall::automock)]
@@ -78,6 +79,21 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Ban peers or ips for `duration`, or until they are unbanned if there is no duration.
+    /// The bans are persisted and survive restarts.
+    fn add_bans(
+        &self,
+        targets: Vec<BanTarget>,
+        duration: Option<MassaTime>,
+        reason: String,
+    ) -> Result<(), ProtocolError>;
+
+    /// Lift the bans set on peers or ips by `add_bans`
+    fn remove_bans(&self, targets: Vec<BanTarget>) -> Result<(), ProtocolError>;
+
+    /// Get the bans set by `add_bans` that are still in force
+    fn get_bans(&self) -> Result<Vec<PeerBan>, ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
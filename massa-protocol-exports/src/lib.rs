@@ -1,6 +1,8 @@
 mod bootstrap_peers;
 mod controller_trait;
 mod error;
+mod peer_ban;
+mod peer_event;
 mod peer_id;
 mod settings;
 
@@ -9,6 +11,8 @@ pub use bootstrap_peers::{
 };
 pub use controller_trait::{ProtocolController, ProtocolManager};
 pub use error::ProtocolError;
+pub use peer_ban::{BanTarget, PeerBan};
+pub use peer_event::{PeerEvent, PeerEventKind};
 pub use peer_id::{PeerId, PeerIdDeserializer, PeerIdSerializer};
 pub use peernet::peer::PeerConnectionType;
 pub use peernet::transports::TransportType;
@@ -0,0 +1,34 @@
+use crate::PeerId;
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// What a ban applies to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BanTarget {
+    /// a peer, whatever the ip it connects from
+    PeerId(PeerId),
+    /// all the peers connecting from an ip
+    Ip(IpAddr),
+}
+
+/// Ban set by the node operator, persisted across restarts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBan {
+    /// what is banned
+    pub target: BanTarget,
+    /// human readable cause of the ban
+    pub reason: String,
+    /// when the ban was set
+    pub banned_at: MassaTime,
+    /// when the ban is lifted, never if none
+    pub expires_at: Option<MassaTime>,
+}
+
+impl PeerBan {
+    /// Whether the ban is lifted at `now`
+    pub fn is_expired(&self, now: MassaTime) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::PeerId;
+use std::net::IpAddr;
+
+/// What happened to a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerEventKind {
+    /// a connection with the peer was established
+    Connected,
+    /// the connection with the peer was closed
+    Disconnected,
+    /// the peer was banned
+    Banned,
+}
+
+/// Connectivity event broadcast by the protocol worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerEvent {
+    /// id of the peer
+    pub peer_id: PeerId,
+    /// ip of the peer, if known
+    pub ip: Option<IpAddr>,
+    /// what happened
+    pub kind: PeerEventKind,
+    /// human readable cause of the event
+    pub reason: String,
+}
@@ -6,7 +6,7 @@ use std::{
     path::PathBuf,
 };
 
-use massa_models::version::Version;
+use massa_models::{block_id::BlockId, prehash::PreHashSet, version::Version};
 use massa_time::MassaTime;
 use peernet::transports::TransportType;
 use serde::Deserialize;
@@ -28,6 +28,8 @@ pub struct ProtocolConfig {
     pub listeners: HashMap<SocketAddr, TransportType>,
     /// initial peers path
     pub initial_peers: PathBuf,
+    /// path of the file where the bans set by the node operator are persisted
+    pub ban_list_file: PathBuf,
     /// after `ask_block_timeout` milliseconds we try to ask a block to another node
     pub ask_block_timeout: MassaTime,
     /// Max known blocks we keep in block_handler
@@ -64,6 +66,11 @@ pub struct ProtocolConfig {
     pub asked_operations_pruning_period: MassaTime,
     /// Interval at which operations are announced in batches.
     pub operation_announcement_interval: MassaTime,
+    /// Interval at which the operations submitted to this node are announced again
+    /// to the peers that do not know them, until they are included in a block or expire
+    pub local_operations_rebroadcast_interval: MassaTime,
+    /// Maximum number of operations submitted to this node that are rebroadcast
+    pub max_local_operations: usize,
     /// Maximum time we keep an operation in the storage
     pub max_operation_storage_time: MassaTime,
     /// Maximum of operations sent in one message.
@@ -142,6 +149,11 @@ pub struct ProtocolConfig {
     pub max_size_listeners_per_peer: u64,
     /// Last start period
     pub last_start_period: u64,
+    /// trusted checkpoint and its configured ancestors, whose header and endorsement signatures are not verified
+    pub trusted_block_ids: PreHashSet<BlockId>,
+    /// header-only relay mode: execution is disabled and does not feed the selector,
+    /// so the PoS draws of the endorsements are not checked
+    pub header_only: bool,
     /// try connection timer
     pub try_connection_timer: MassaTime,
     /// Max in connections
@@ -160,4 +172,8 @@ pub struct ProtocolConfig {
     pub default_category_info: PeerCategoryInfo,
     /// Version
     pub version: Version,
+    /// whether peer events broadcast is enabled
+    pub broadcast_enabled: bool,
+    /// peer events broadcast channel capacity
+    pub broadcast_peer_events_channel_capacity: usize,
 }
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use crate::{settings::PeerCategoryInfo, ProtocolConfig};
 use massa_models::config::{ENDORSEMENT_COUNT, MAX_MESSAGE_SIZE};
+use massa_models::prehash::PreHashSet;
 use massa_time::MassaTime;
 use tempfile::NamedTempFile;
 
@@ -30,6 +31,8 @@ impl Default for ProtocolConfig {
             asked_operations_buffer_capacity: 10000,
             asked_operations_pruning_period: MassaTime::from_millis(500),
             operation_announcement_interval: MassaTime::from_millis(150),
+            local_operations_rebroadcast_interval: MassaTime::from_millis(2000),
+            max_local_operations: 10000,
             max_operations_per_message: 1024,
             max_operations_per_block: 5000,
             thread_count: 32,
@@ -44,6 +47,10 @@ impl Default for ProtocolConfig {
                 .expect("cannot create temp file")
                 .path()
                 .to_path_buf(),
+            ban_list_file: NamedTempFile::new()
+                .expect("cannot create temp file")
+                .path()
+                .to_path_buf(),
             listeners: HashMap::default(),
             thread_tester_count: 2,
             max_size_channel_commands_connectivity: 1000,
@@ -73,6 +80,8 @@ impl Default for ProtocolConfig {
             max_size_listeners_per_peer: 100,
             max_size_peers_announcement: 100,
             last_start_period: 0,
+            trusted_block_ids: PreHashSet::default(),
+            header_only: false,
             read_write_limit_bytes_per_second: 1024 * 1000,
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
@@ -87,6 +96,8 @@ impl Default for ProtocolConfig {
                 max_in_connections_per_ip: 0,
             },
             version: "TEST.23.2".parse().unwrap(),
+            broadcast_enabled: false,
+            broadcast_peer_events_channel_capacity: 128,
         }
     }
 }
@@ -5,7 +5,9 @@ use massa_metrics::MassaMetrics;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
+use massa_protocol_exports::{
+    PeerCategoryInfo, PeerEvent, PeerEventKind, PeerId, ProtocolConfig, ProtocolError,
+};
 use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
@@ -14,7 +16,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{collections::HashMap, net::IpAddr};
 use std::{thread::JoinHandle, time::Duration};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 use crate::{
     handlers::peer_handler::models::{InitialPeers, PeerState, SharedPeerDB},
@@ -127,6 +129,7 @@ pub(crate) fn start_connectivity_thread(
                 network_controller.get_active_connections(),
                 peer_categories.iter().map(|(key, value)|(key.clone(), (value.0.clone(), value.1.target_out_connections))).collect(),
                 config.default_category_info.target_out_connections,
+                protocol_channels.peer_event_sender.clone(),
                 &config,
             );
 
@@ -142,6 +145,7 @@ pub(crate) fn start_connectivity_thread(
                 sender_operations_propagation_ext.clone(),
                 protocol_channels.operation_handler_propagation.1.clone(),
                 peer_management_handler.sender.command_sender.clone(),
+                mip_store.clone(),
                 massa_metrics.clone(),
             );
             let mut endorsement_handler = EndorsementHandler::new(
@@ -181,6 +185,9 @@ pub(crate) fn start_connectivity_thread(
                 massa_metrics.clone(),
             );
 
+            // Peers connected at the previous tick, diffed with the current ones to broadcast the peer events
+            let mut last_peers_connected: HashMap<PeerId, SocketAddr> = HashMap::new();
+
             //Try to connect to peers
             loop {
                 select! {
@@ -231,6 +238,10 @@ pub(crate) fn start_connectivity_thread(
                         let peers_connected = active_conn.get_peers_connected();
                         // update massa metrics
                         massa_metrics.set_active_connections(active_conn.get_nb_in_connections(), active_conn.get_nb_out_connections());
+                        if config.broadcast_enabled {
+                            broadcast_peer_events(&protocol_channels.peer_event_sender, &last_peers_connected, &peers_connected);
+                            last_peers_connected = peers_connected.iter().map(|(peer_id, peer)| (peer_id.clone(), peer.0)).collect();
+                        }
 
                         let mut slots_per_category: Vec<(String, usize)> = peer_categories.iter().map(|(category, category_infos)| {
                             (category.clone(), category_infos.1.target_out_connections.saturating_sub(peers_connected.iter().filter(|(_, peer)| {
@@ -267,6 +278,9 @@ pub(crate) fn start_connectivity_thread(
                                     if !canonical_ip.is_global()  {
                                         continue;
                                     }
+                                    if peer_db_read.is_banned_by_operator(peer_id, Some(canonical_ip)) {
+                                        continue;
+                                    }
                                     // Check if the peer is in a category and we didn't reached out target yet
                                     let mut category_found = None;
                                     for (name, (ips, _)) in &peer_categories {
@@ -311,3 +325,39 @@ pub(crate) fn start_connectivity_thread(
     // Start controller
     Ok((protocol_channels.connectivity_thread.0, handle))
 }
+
+/// Broadcasts a connection event for the peers connected since the previous tick
+/// and a disconnection event for the peers no longer connected
+pub(crate) fn broadcast_peer_events(
+    peer_event_sender: &tokio::sync::broadcast::Sender<PeerEvent>,
+    last_peers_connected: &HashMap<PeerId, SocketAddr>,
+    peers_connected: &HashMap<PeerId, (SocketAddr, PeerConnectionType, Option<String>)>,
+) {
+    let connected = peers_connected
+        .iter()
+        .filter(|(peer_id, _)| !last_peers_connected.contains_key(peer_id))
+        .map(|(peer_id, (addr, connection_type, _))| PeerEvent {
+            peer_id: peer_id.clone(),
+            ip: Some(addr.ip()),
+            kind: PeerEventKind::Connected,
+            reason: if *connection_type == PeerConnectionType::OUT {
+                "outgoing connection".to_string()
+            } else {
+                "incoming connection".to_string()
+            },
+        });
+    let disconnected = last_peers_connected
+        .iter()
+        .filter(|(peer_id, _)| !peers_connected.contains_key(peer_id))
+        .map(|(peer_id, addr)| PeerEvent {
+            peer_id: peer_id.clone(),
+            ip: Some(addr.ip()),
+            kind: PeerEventKind::Disconnected,
+            reason: "connection closed".to_string(),
+        });
+    for event in connected.chain(disconnected) {
+        if let Err(err) = peer_event_sender.send(event) {
+            debug!("failed to broadcast peer event: {}", err);
+        }
+    }
+}
@@ -7,8 +7,11 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     stats::NetworkStats,
 };
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
+use massa_protocol_exports::{
+    BanTarget, BootstrapPeers, PeerBan, PeerId, ProtocolController, ProtocolError,
+};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use peernet::peer::PeerConnectionType;
 
 use crate::{
@@ -112,14 +115,10 @@ impl ProtocolController for ProtocolControllerImpl {
     ///
     /// note: Full `OperationId` is replaced by a `OperationPrefixId` later by the worker.
     fn propagate_operations(&self, operations: Storage) -> Result<(), ProtocolError> {
-        //TODO: Change when send will be in propagation
-        let operations = operations.get_op_refs().clone();
         self.sender_operation_handler
             .as_ref()
             .unwrap()
-            .try_send(OperationHandlerPropagationCommand::AnnounceOperations(
-                operations,
-            ))
+            .try_send(OperationHandlerPropagationCommand::PropagateLocalOperations(operations))
             .map_err(|_| {
                 ProtocolError::ChannelError("propagate_operations command send error".into())
             })
@@ -162,7 +161,10 @@ impl ProtocolController for ProtocolControllerImpl {
         self.sender_peer_management_thread
             .as_ref()
             .unwrap()
-            .try_send(PeerManagementCmd::Ban(peer_ids))
+            .try_send(PeerManagementCmd::Ban(
+                peer_ids,
+                "banned by the node operator".to_string(),
+            ))
             .map_err(|_| ProtocolError::ChannelError("ban_peers command send error".into()))
     }
 
@@ -174,6 +176,49 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn add_bans(
+        &self,
+        targets: Vec<BanTarget>,
+        duration: Option<MassaTime>,
+        reason: String,
+    ) -> Result<(), ProtocolError> {
+        let now = MassaTime::now()?;
+        let bans = targets
+            .into_iter()
+            .map(|target| PeerBan {
+                target,
+                reason: reason.clone(),
+                banned_at: now,
+                expires_at: duration.map(|duration| now.saturating_add(duration)),
+            })
+            .collect();
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::AddBans(bans))
+            .map_err(|_| ProtocolError::ChannelError("add_bans command send error".into()))
+    }
+
+    fn remove_bans(&self, targets: Vec<BanTarget>) -> Result<(), ProtocolError> {
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::RemoveBans(targets))
+            .map_err(|_| ProtocolError::ChannelError("remove_bans command send error".into()))
+    }
+
+    fn get_bans(&self) -> Result<Vec<PeerBan>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_bans".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetBans { responder: sender })
+            .map_err(|_| ProtocolError::ChannelError("get_bans command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_bans command receive error".into()))
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
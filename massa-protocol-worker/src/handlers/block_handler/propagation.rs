@@ -137,7 +137,10 @@ impl PropagationThread {
     fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                "invalid block propagation".to_string(),
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
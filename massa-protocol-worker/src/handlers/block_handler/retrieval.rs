@@ -535,21 +535,25 @@ impl RetrievalThread {
             }
         }
 
-        if let Err(err) =
-            self.note_endorsements_from_peer(header.content.endorsements.clone(), from_peer_id)
-        {
-            warn!(
-                "node {} sent us a header containing critically incorrect endorsements: {}",
-                from_peer_id, err
-            );
-            return Ok(None);
-        };
+        // the trusted checkpoint and its ancestors are committed to by their id:
+        // their signatures and the draws of their endorsements are not checked
+        if !self.config.trusted_block_ids.contains(&block_id) {
+            if let Err(err) =
+                self.note_endorsements_from_peer(header.content.endorsements.clone(), from_peer_id)
+            {
+                warn!(
+                    "node {} sent us a header containing critically incorrect endorsements: {}",
+                    from_peer_id, err
+                );
+                return Ok(None);
+            };
 
-        // check header signature
-        if let Err(err) = header.verify_signature() {
-            massa_trace!("protocol.protocol_worker.check_header.err_signature", { "header": header, "err": format!("{}", err)});
-            return Ok(None);
-        };
+            // check header signature
+            if let Err(err) = header.verify_signature() {
+                massa_trace!("protocol.protocol_worker.check_header.err_signature", { "header": header, "err": format!("{}", err)});
+                return Ok(None);
+            };
+        }
 
         // check endorsement in header integrity
         let mut used_endorsement_indices: HashSet<u32> =
@@ -612,7 +616,10 @@ impl RetrievalThread {
     fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                "invalid block data received".to_string(),
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 
@@ -675,24 +682,26 @@ impl RetrievalThread {
                 .collect::<Vec<_>>(),
         )?;
 
-        // Check PoS draws
-        for endorsement in new_endorsements.values() {
-            let selection = self
-                .selector_controller
-                .get_selection(endorsement.content.slot)?;
-            let Some(address) = selection.endorsements.get(endorsement.content.index as usize) else {
-                return Err(ProtocolError::GeneralProtocolError(
-                    format!(
-                        "No selection on slot {} for index {}",
-                        endorsement.content.slot, endorsement.content.index
-                    )
-                ))
-            };
-            if address != &endorsement.content_creator_address {
-                return Err(ProtocolError::GeneralProtocolError(format!(
-                    "Invalid endorsement: expected address {}, got {}",
-                    address, endorsement.content_creator_address
-                )));
+        // Check PoS draws, unless the selector is not fed on this header-only relay node
+        if !self.config.header_only {
+            for endorsement in new_endorsements.values() {
+                let selection = self
+                    .selector_controller
+                    .get_selection(endorsement.content.slot)?;
+                let Some(address) = selection.endorsements.get(endorsement.content.index as usize) else {
+                    return Err(ProtocolError::GeneralProtocolError(
+                        format!(
+                            "No selection on slot {} for index {}",
+                            endorsement.content.slot, endorsement.content.index
+                        )
+                    ))
+                };
+                if address != &endorsement.content_creator_address {
+                    return Err(ProtocolError::GeneralProtocolError(format!(
+                        "Invalid endorsement: expected address {}, got {}",
+                        address, endorsement.content_creator_address
+                    )));
+                }
             }
         }
 
@@ -952,6 +961,7 @@ impl RetrievalThread {
     /// - wanted operations doesn't match
     /// - duplicated operation
     /// - full operations serialized size overflow
+    /// - operation type not active at the block slot
     ///
     /// We received these operation because we asked for the missing operation
     fn on_block_full_operations_received(
@@ -1008,14 +1018,38 @@ impl RetrievalThread {
                 // Ban the node if:
                 // - mismatch with asked operations (asked operations are the one that are not in storage) + operations already in storage and block operations
                 // - full operations serialized size overflow
-                let full_op_size: usize = {
+                // - operation type not active at the block slot
+                let slot_timestamp = get_block_slot_timestamp(
+                    self.config.thread_count,
+                    self.config.t0,
+                    self.config.genesis_timestamp,
+                    header.content.slot,
+                )?;
+                let (full_op_size, inactive_op_type): (usize, bool) = {
                     let stored_operations = info.storage.read_operations();
                     known_operations
                         .iter()
-                        .map(|id| stored_operations.get(id).unwrap().serialized_size())
-                        .sum()
+                        .fold((0, false), |(size, inactive), id| {
+                            let op = stored_operations.get(id).unwrap();
+                            (
+                                size + op.serialized_size(),
+                                inactive
+                                    || !self.mip_store.is_operation_type_active_at(
+                                        &op.content.op,
+                                        slot_timestamp,
+                                    ),
+                            )
+                        })
                 };
-                if full_op_size > self.config.max_serialized_operations_size_per_block {
+                if inactive_op_type {
+                    warn!("Peer id {} sent us full operations for block id {} but some of their types are not active at the block slot.", from_peer_id, block_id);
+                    if let Err(err) = self.ban_node(&from_peer_id) {
+                        warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
+                    }
+                    self.block_wishlist.remove(&block_id);
+                    self.consensus_controller
+                        .mark_invalid_block(block_id, header);
+                } else if full_op_size > self.config.max_serialized_operations_size_per_block {
                     warn!("Peer id {} sent us full operations for block id {} but they exceed max size.", from_peer_id, block_id);
                     if let Err(err) = self.ban_node(&from_peer_id) {
                         warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
@@ -181,24 +181,26 @@ impl RetrievalThread {
                 .collect::<Vec<_>>(),
         )?;
 
-        // Check PoS draws
-        for endorsement in new_endorsements.values() {
-            let selection = self
-                .selector_controller
-                .get_selection(endorsement.content.slot)?;
-            let Some(address) = selection.endorsements.get(endorsement.content.index as usize) else {
-                        return Err(ProtocolError::GeneralProtocolError(
-                            format!(
-                                "No selection on slot {} for index {}",
-                                endorsement.content.slot, endorsement.content.index
-                            )
-                        ))
-                    };
-            if address != &endorsement.content_creator_address {
-                return Err(ProtocolError::GeneralProtocolError(format!(
-                    "Invalid endorsement: expected address {}, got {}",
-                    address, endorsement.content_creator_address
-                )));
+        // Check PoS draws, unless the selector is not fed on this header-only relay node
+        if !self.config.header_only {
+            for endorsement in new_endorsements.values() {
+                let selection = self
+                    .selector_controller
+                    .get_selection(endorsement.content.slot)?;
+                let Some(address) = selection.endorsements.get(endorsement.content.index as usize) else {
+                            return Err(ProtocolError::GeneralProtocolError(
+                                format!(
+                                    "No selection on slot {} for index {}",
+                                    endorsement.content.slot, endorsement.content.index
+                                )
+                            ))
+                        };
+                if address != &endorsement.content_creator_address {
+                    return Err(ProtocolError::GeneralProtocolError(format!(
+                        "Invalid endorsement: expected address {}, got {}",
+                        address, endorsement.content_creator_address
+                    )));
+                }
             }
         }
 
@@ -285,7 +287,10 @@ impl RetrievalThread {
     fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                "invalid endorsements received".to_string(),
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
@@ -1,8 +1,11 @@
 use massa_models::{operation::OperationId, prehash::PreHashSet};
+use massa_storage::Storage;
 
 #[derive(Clone)]
 pub enum OperationHandlerPropagationCommand {
     Stop,
     /// operations ids
     AnnounceOperations(PreHashSet<OperationId>),
+    /// operations submitted to this node, rebroadcast until they are included or expire
+    PropagateLocalOperations(Storage),
 }
@@ -1,11 +1,21 @@
-use std::{mem, thread::JoinHandle};
+use std::{
+    mem,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 use crossbeam::channel::RecvTimeoutError;
 use massa_channel::receiver::MassaReceiver;
 use massa_logging::massa_trace;
-use massa_models::operation::OperationId;
+use massa_models::{
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+    timeslots::get_latest_block_slot_at_timestamp,
+};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
+use massa_storage::Storage;
+use massa_time::MassaTime;
 use tracing::{debug, info, log::warn};
 
 use crate::{
@@ -22,6 +32,11 @@ struct PropagationThread {
     internal_receiver: MassaReceiver<OperationHandlerPropagationCommand>,
     active_connections: Box<dyn ActiveConnectionsTrait>,
     operations_to_announce: Vec<OperationId>,
+    /// operations submitted to this node and not included in a block yet, with their expiration period
+    local_operations: PreHashMap<OperationId, u64>,
+    /// keeps the local operations available to the peers asking for them
+    local_operations_storage: Storage,
+    next_local_rebroadcast: Instant,
     config: ProtocolConfig,
     cache: SharedOperationCache,
     operation_message_serializer: MessagesSerializer,
@@ -34,34 +49,34 @@ impl PropagationThread {
             .expect("Can't init interval op propagation");
         loop {
             match self.internal_receiver.recv_deadline(next_announce) {
-                Ok(internal_message) => {
-                    match internal_message {
-                        OperationHandlerPropagationCommand::AnnounceOperations(operations_ids) => {
-                            // Note operations as checked.
-                            {
-                                let mut cache_write = self.cache.write();
-                                for op_id in operations_ids.iter().copied() {
-                                    cache_write.insert_checked_operation(op_id);
-                                }
-                            }
-                            self.operations_to_announce.extend(operations_ids);
-                            if self.operations_to_announce.len()
-                                > self.config.operation_announcement_buffer_capacity
-                            {
-                                self.announce_ops();
-                                next_announce = std::time::Instant::now()
-                                    .checked_add(
-                                        self.config.operation_announcement_interval.to_duration(),
-                                    )
-                                    .expect("Can't init interval op propagation");
-                            }
+                Ok(internal_message) => match internal_message {
+                    OperationHandlerPropagationCommand::AnnounceOperations(operations_ids) => {
+                        if self.add_operations_to_announce(operations_ids) {
+                            self.announce_ops();
+                            next_announce = std::time::Instant::now()
+                                .checked_add(
+                                    self.config.operation_announcement_interval.to_duration(),
+                                )
+                                .expect("Can't init interval op propagation");
                         }
-                        OperationHandlerPropagationCommand::Stop => {
-                            info!("Stop operation propagation thread");
-                            return;
+                    }
+                    OperationHandlerPropagationCommand::PropagateLocalOperations(storage) => {
+                        let operations_ids = storage.get_op_refs().clone();
+                        self.track_local_operations(storage);
+                        if self.add_operations_to_announce(operations_ids) {
+                            self.announce_ops();
+                            next_announce = std::time::Instant::now()
+                                .checked_add(
+                                    self.config.operation_announcement_interval.to_duration(),
+                                )
+                                .expect("Can't init interval op propagation");
                         }
                     }
-                }
+                    OperationHandlerPropagationCommand::Stop => {
+                        info!("Stop operation propagation thread");
+                        return;
+                    }
+                },
                 Err(RecvTimeoutError::Timeout) => {
                     self.announce_ops();
                     next_announce = std::time::Instant::now()
@@ -72,7 +87,102 @@ impl PropagationThread {
                     return;
                 }
             }
+            if Instant::now() >= self.next_local_rebroadcast {
+                self.rebroadcast_local_operations();
+                self.next_local_rebroadcast = Instant::now()
+                    .checked_add(
+                        self.config
+                            .local_operations_rebroadcast_interval
+                            .to_duration(),
+                    )
+                    .unwrap_or_else(|| Instant::now() + Duration::from_secs(1));
+            }
+        }
+    }
+
+    /// Notes operations as checked and buffers them for the next announcement.
+    /// Returns true if the buffer is full and the operations must be announced right away.
+    fn add_operations_to_announce(&mut self, operations_ids: PreHashSet<OperationId>) -> bool {
+        {
+            let mut cache_write = self.cache.write();
+            for op_id in operations_ids.iter().copied() {
+                cache_write.insert_checked_operation(op_id);
+            }
+        }
+        self.operations_to_announce.extend(operations_ids);
+        self.operations_to_announce.len() > self.config.operation_announcement_buffer_capacity
+    }
+
+    /// Starts tracking operations submitted to this node, up to the max number of local operations
+    fn track_local_operations(&mut self, mut storage: Storage) {
+        let mut tracked = PreHashSet::default();
+        {
+            let operations = storage.read_operations();
+            for op_id in storage.get_op_refs() {
+                if self.local_operations.len() >= self.config.max_local_operations {
+                    break;
+                }
+                if let Some(operation) = operations.get(op_id) {
+                    if self
+                        .local_operations
+                        .insert(*op_id, operation.content.expire_period)
+                        .is_none()
+                    {
+                        tracked.insert(*op_id);
+                    }
+                }
+            }
+        }
+        self.local_operations_storage.extend(storage.split_off(
+            &Default::default(),
+            &tracked,
+            &Default::default(),
+        ));
+    }
+
+    /// Forgets the local operations included in a block or expired,
+    /// and announces the other ones again to the peers that do not know them,
+    /// as a single announcement can be lost when the peers change
+    fn rebroadcast_local_operations(&mut self) {
+        if self.local_operations.is_empty() {
+            return;
+        }
+        let now = MassaTime::now().expect("could not get current time");
+        let current_period = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            now,
+        )
+        .ok()
+        .flatten()
+        .map_or(0, |slot| slot.period);
+        let forgotten: PreHashSet<OperationId> = {
+            let blocks = self.local_operations_storage.read_blocks();
+            self.local_operations
+                .iter()
+                .filter(|(op_id, expire_period)| {
+                    **expire_period < current_period
+                        || blocks.get_blocks_by_operation(op_id).is_some()
+                })
+                .map(|(op_id, _)| *op_id)
+                .collect()
+        };
+        for op_id in &forgotten {
+            self.local_operations.remove(op_id);
+        }
+        self.local_operations_storage
+            .drop_operation_refs(&forgotten);
+        if self.local_operations.is_empty() {
+            return;
         }
+        debug!(
+            "Rebroadcast {} local operations",
+            self.local_operations.len()
+        );
+        self.operations_to_announce
+            .extend(self.local_operations.keys().copied());
+        self.announce_ops();
     }
 
     fn announce_ops(&mut self) {
@@ -144,6 +254,7 @@ impl PropagationThread {
 pub fn start_propagation_thread(
     internal_receiver: MassaReceiver<OperationHandlerPropagationCommand>,
     active_connections: Box<dyn ActiveConnectionsTrait>,
+    storage: Storage,
     config: ProtocolConfig,
     cache: SharedOperationCache,
 ) -> JoinHandle<()> {
@@ -154,6 +265,11 @@ pub fn start_propagation_thread(
                 internal_receiver,
                 active_connections,
                 operations_to_announce: Vec::new(),
+                local_operations: PreHashMap::default(),
+                local_operations_storage: storage,
+                next_local_rebroadcast: Instant::now()
+                    .checked_add(config.local_operations_rebroadcast_interval.to_duration())
+                    .expect("Can't init interval local op rebroadcast"),
                 config,
                 cache,
                 operation_message_serializer: MessagesSerializer::new()
@@ -21,6 +21,7 @@ use massa_protocol_exports::{ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
 use massa_time::{MassaTime, TimeError};
+use massa_versioning::versioning::MipStore;
 use schnellru::{ByLength, LruMap};
 
 use crate::{
@@ -65,6 +66,7 @@ pub struct RetrievalThread {
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     operation_message_serializer: MessagesSerializer,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    mip_store: MipStore,
     massa_metrics: MassaMetrics,
 }
 
@@ -190,6 +192,7 @@ impl RetrievalThread {
         let length = operations.len();
         let mut new_operations = PreHashMap::with_capacity(length);
         let mut received_ids = PreHashSet::with_capacity(length);
+        let now = MassaTime::now()?;
         for operation in operations {
             let operation_id = operation.id;
             if operation.serialized_size() > self.config.max_serialized_operations_size_per_block {
@@ -202,6 +205,14 @@ impl RetrievalThread {
             };
             received_ids.insert(operation_id);
 
+            // Drop the operations whose type is not active yet, they can't be included in blocks.
+            if !self
+                .mip_store
+                .is_operation_type_active_at(&operation.content.op, now)
+            {
+                continue;
+            }
+
             // Check operation signature only if not already checked.
             if self
                 .cache
@@ -255,15 +266,26 @@ impl RetrievalThread {
             let mut ops = self.storage.clone_without_refs();
             ops.store_operations(new_operations.into_values().collect());
 
-            // Propagate operations when their expire period isn't `max_operations_propagation_time` old.
+            // Propagate operations when their expire period isn't `max_operations_propagation_time` old,
+            // and their sender is not in the local denylist of the pool.
             let mut ops_to_propagate = ops.clone();
             let operations_to_not_propagate = {
                 let now = MassaTime::now()?;
                 let read_operations = ops_to_propagate.read_operations();
-                ops_to_propagate
-                    .get_op_refs()
+                let op_ids: Vec<OperationId> =
+                    ops_to_propagate.get_op_refs().iter().copied().collect();
+                let senders: Vec<_> = op_ids
                     .iter()
-                    .filter(|op_id| {
+                    .map(|op_id| read_operations.get(op_id).unwrap().content_creator_address)
+                    .collect();
+                let denied_senders = self.pool_controller.contains_denied_senders(&senders);
+                op_ids
+                    .iter()
+                    .zip(denied_senders)
+                    .filter(|(op_id, denied)| {
+                        if *denied {
+                            return true;
+                        }
                         let expire_period =
                             read_operations.get(op_id).unwrap().content.expire_period;
                         let expire_period_timestamp = get_block_slot_timestamp(
@@ -281,7 +303,7 @@ impl RetrievalThread {
                             Err(_) => true,
                         }
                     })
-                    .copied()
+                    .map(|(op_id, _)| *op_id)
                     .collect()
             };
             ops_to_propagate.drop_operation_refs(&operations_to_not_propagate);
@@ -505,7 +527,10 @@ impl RetrievalThread {
     fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![peer_id.clone()]))
+            .try_send(PeerManagementCmd::Ban(
+                vec![peer_id.clone()],
+                "invalid operations received".to_string(),
+            ))
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
@@ -521,6 +546,7 @@ pub fn start_retrieval_thread(
     receiver_ext: MassaReceiver<OperationHandlerRetrievalCommand>,
     internal_sender: MassaSender<OperationHandlerPropagationCommand>,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
+    mip_store: MipStore,
     massa_metrics: MassaMetrics,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
@@ -546,6 +572,7 @@ pub fn start_retrieval_thread(
                     .with_operation_message_serializer(OperationMessageSerializer::new()),
                 op_batch_buffer: VecDeque::new(),
                 peer_cmd_sender,
+                mip_store,
                 massa_metrics,
             };
             retrieval_thread.run();
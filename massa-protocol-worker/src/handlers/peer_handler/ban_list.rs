@@ -0,0 +1,177 @@
+use massa_protocol_exports::{BanTarget, PeerBan, PeerId};
+use massa_time::MassaTime;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use tracing::log::{info, warn};
+
+/// Bans set by the node operator, by peer id or by ip.
+///
+/// The list is written to `path` on every change and reloaded on startup,
+/// so that the bans survive restarts. Expired bans are pruned periodically.
+#[derive(Default)]
+pub struct BanList {
+    /// file where the bans are persisted, none to keep them in memory only
+    path: Option<PathBuf>,
+    bans: HashMap<BanTarget, PeerBan>,
+}
+
+impl BanList {
+    /// Load the bans persisted at `path`. A missing or empty file means no ban.
+    pub fn load(path: PathBuf) -> Self {
+        let bans = match std::fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => {
+                serde_json::from_str::<Vec<PeerBan>>(&content).unwrap_or_else(|err| {
+                    warn!("ignoring invalid ban list file {:?}: {}", path, err);
+                    Vec::new()
+                })
+            }
+            _ => Vec::new(),
+        };
+        BanList {
+            path: Some(path),
+            bans: bans
+                .into_iter()
+                .map(|ban| (ban.target.clone(), ban))
+                .collect(),
+        }
+    }
+
+    /// Add or replace bans
+    pub fn add(&mut self, bans: Vec<PeerBan>) {
+        for mut ban in bans {
+            ban.target = canonical(ban.target);
+            info!("Banned {:?}: {}", ban.target, ban.reason);
+            self.bans.insert(ban.target.clone(), ban);
+        }
+        self.save();
+    }
+
+    /// Lift bans, returns the targets that were actually banned
+    pub fn remove(&mut self, targets: &[BanTarget]) -> Vec<BanTarget> {
+        let removed: Vec<BanTarget> = targets
+            .iter()
+            .map(|target| canonical(target.clone()))
+            .filter(|target| self.bans.remove(target).is_some())
+            .collect();
+        for target in &removed {
+            info!("Unbanned {:?}", target);
+        }
+        if !removed.is_empty() {
+            self.save();
+        }
+        removed
+    }
+
+    /// Remove the bans expired at `now`
+    pub fn prune(&mut self, now: MassaTime) {
+        let count = self.bans.len();
+        self.bans.retain(|_, ban| !ban.is_expired(now));
+        if self.bans.len() != count {
+            self.save();
+        }
+    }
+
+    /// Whether a peer, optionally connecting from `ip`, is banned at `now`
+    pub fn is_banned(&self, peer_id: &PeerId, ip: Option<IpAddr>, now: MassaTime) -> bool {
+        self.is_in_force(&BanTarget::PeerId(peer_id.clone()), now)
+            || ip.map_or(false, |ip| self.is_ip_banned(ip, now))
+    }
+
+    /// Whether an ip is banned at `now`
+    pub fn is_ip_banned(&self, ip: IpAddr, now: MassaTime) -> bool {
+        self.is_in_force(&BanTarget::Ip(ip.to_canonical()), now)
+    }
+
+    fn is_in_force(&self, target: &BanTarget, now: MassaTime) -> bool {
+        self.bans
+            .get(target)
+            .map_or(false, |ban| !ban.is_expired(now))
+    }
+
+    /// Bans still in force at `now`
+    pub fn get_bans(&self, now: MassaTime) -> Vec<PeerBan> {
+        self.bans
+            .values()
+            .filter(|ban| !ban.is_expired(now))
+            .cloned()
+            .collect()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let bans: Vec<&PeerBan> = self.bans.values().collect();
+        let result = serde_json::to_string_pretty(&bans)
+            .map_err(|err| err.to_string())
+            .and_then(|content| std::fs::write(path, content).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("failed to persist the ban list to {:?}: {}", path, err);
+        }
+    }
+}
+
+/// IPv4-mapped IPv6 addresses are banned as their IPv4 counterpart
+fn canonical(target: BanTarget) -> BanTarget {
+    match target {
+        BanTarget::Ip(ip) => BanTarget::Ip(ip.to_canonical()),
+        target => target,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BanList;
+    use massa_protocol_exports::{BanTarget, PeerBan, PeerId};
+    use massa_signature::KeyPair;
+    use massa_time::MassaTime;
+    use std::net::IpAddr;
+    use tempfile::TempDir;
+
+    fn ban(target: BanTarget, expires_at: Option<u64>) -> PeerBan {
+        PeerBan {
+            target,
+            reason: "test".to_string(),
+            banned_at: MassaTime::from_millis(0),
+            expires_at: expires_at.map(MassaTime::from_millis),
+        }
+    }
+
+    #[test]
+    fn test_ban_list_persistence_and_expiry() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("bans.json");
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other_peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let ip: IpAddr = "1.2.3.4".parse().unwrap();
+        let mapped_ip: IpAddr = "::ffff:1.2.3.4".parse().unwrap();
+
+        let mut ban_list = BanList::load(path.clone());
+        ban_list.add(vec![
+            ban(BanTarget::PeerId(peer_id.clone()), None),
+            ban(BanTarget::Ip(mapped_ip), Some(1000)),
+        ]);
+
+        // the bans survive a reload, ip bans apply to the ipv4-mapped addresses
+        let mut ban_list = BanList::load(path.clone());
+        let now = MassaTime::from_millis(500);
+        assert!(ban_list.is_banned(&peer_id, None, now));
+        assert!(ban_list.is_banned(&other_peer_id, Some(ip), now));
+        assert!(!ban_list.is_banned(&other_peer_id, None, now));
+        assert_eq!(ban_list.get_bans(now).len(), 2);
+
+        // the ip ban expires
+        let later = MassaTime::from_millis(1000);
+        assert!(!ban_list.is_ip_banned(ip, later));
+        ban_list.prune(later);
+        assert_eq!(BanList::load(path.clone()).get_bans(now).len(), 1);
+
+        // the peer ban is lifted
+        assert_eq!(
+            ban_list.remove(&[BanTarget::PeerId(peer_id.clone()), BanTarget::Ip(ip)]),
+            vec![BanTarget::PeerId(peer_id.clone())]
+        );
+        assert!(!BanList::load(path).is_banned(&peer_id, None, later));
+    }
+}
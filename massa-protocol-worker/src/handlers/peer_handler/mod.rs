@@ -9,10 +9,12 @@ use massa_hash::Hash;
 use massa_models::config::SIGNATURE_DESER_SIZE;
 use massa_models::version::{VersionDeserializer, VersionSerializer};
 use massa_protocol_exports::{
-    BootstrapPeers, PeerId, PeerIdDeserializer, PeerIdSerializer, ProtocolConfig,
+    BanTarget, BootstrapPeers, PeerEvent, PeerEventKind, PeerId, PeerIdDeserializer,
+    PeerIdSerializer, ProtocolConfig,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
 use massa_signature::Signature;
+use massa_time::MassaTime;
 use peernet::context::Context as _;
 use peernet::messages::MessagesSerializer as _;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
@@ -50,6 +52,7 @@ use self::{
 /// This handler is here to check that announcements we receive are valid and
 /// that all the endpoints we received are active.
 mod announcement;
+pub mod ban_list;
 mod messages;
 pub mod models;
 mod tester;
@@ -81,6 +84,7 @@ impl PeerManagementHandler {
         mut active_connections: Box<dyn ActiveConnectionsTrait>,
         target_out_connections: HashMap<String, (Vec<IpAddr>, usize)>,
         default_target_out_connections: usize,
+        peer_event_sender: tokio::sync::broadcast::Sender<PeerEvent>,
         config: &ProtocolConfig,
     ) -> Self {
         let message_serializer = PeerManagementMessageSerializer::new();
@@ -111,6 +115,8 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
+                            peer_db.write().ban_list.prune(MassaTime::now().expect("Unable to get MassaTime::now"));
+
                             let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
                             if peers_to_send.is_empty() {
                                 continue;
@@ -129,9 +135,21 @@ impl PeerManagementHandler {
                             receiver_cmd.inc_metrics();
                             // internal command
                            match cmd {
-                             Ok(PeerManagementCmd::Ban(peer_ids)) => {
+                             Ok(PeerManagementCmd::Ban(peer_ids, reason)) => {
                                 // remove running handshake ?
                                 for peer_id in peer_ids {
+                                    if config.broadcast_enabled {
+                                        let ip = active_connections
+                                            .get_peers_connected()
+                                            .get(&peer_id)
+                                            .map(|(addr, _, _)| addr.ip())
+                                            .or_else(|| {
+                                                peer_db.read().peers.get(&peer_id).and_then(|peer| {
+                                                    peer.last_announce.listeners.keys().next().map(|addr| addr.ip())
+                                                })
+                                            });
+                                        broadcast_peer_ban(&peer_event_sender, &peer_id, ip, &reason);
+                                    }
                                     active_connections.shutdown_connection(&peer_id);
 
                                     // update peer_db
@@ -143,6 +161,35 @@ impl PeerManagementHandler {
                                     peer_db.write().unban_peer(&peer_id);
                                 }
                             },
+                             Ok(PeerManagementCmd::AddBans(bans)) => {
+                                // disconnect the banned peers, and all the peers connected from a banned ip
+                                let peers_connected = active_connections.get_peers_connected();
+                                for ban in &bans {
+                                    for (peer_id, (addr, _, _)) in &peers_connected {
+                                        let banned = match &ban.target {
+                                            BanTarget::PeerId(banned_id) => banned_id == peer_id,
+                                            BanTarget::Ip(ip) => addr.ip().to_canonical() == ip.to_canonical(),
+                                        };
+                                        if !banned {
+                                            continue;
+                                        }
+                                        if config.broadcast_enabled {
+                                            broadcast_peer_ban(&peer_event_sender, peer_id, Some(addr.ip()), &ban.reason);
+                                        }
+                                        active_connections.shutdown_connection(peer_id);
+                                    }
+                                }
+                                peer_db.write().ban_list.add(bans);
+                            },
+                             Ok(PeerManagementCmd::RemoveBans(targets)) => {
+                                peer_db.write().ban_list.remove(&targets);
+                            },
+                             Ok(PeerManagementCmd::GetBans { responder }) => {
+                                let bans = peer_db.read().ban_list.get_bans(MassaTime::now().expect("Unable to get MassaTime::now"));
+                                if let Err(err) = responder.try_send(bans) {
+                                    warn!("error sending bans: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::GetBootstrapPeers { responder }) => {
                                 let mut peers = peer_db.read().get_rand_peers_to_send(100);
                                 // Add myself
@@ -176,8 +223,11 @@ impl PeerManagementHandler {
                                 }
                             };
                             // check if peer is banned
-                            if let Some(peer) = peer_db.read().peers.get(&peer_id) {
-                                if peer.state == PeerState::Banned {
+                            {
+                                let peer_db_read = peer_db.read();
+                                let banned = peer_db_read.peers.get(&peer_id).map_or(false, |peer| peer.state == PeerState::Banned)
+                                    || peer_db_read.is_banned_by_operator(&peer_id, None);
+                                if banned {
                                     warn!("Banned peer sent us a message: {:?}", peer_id);
                                     continue;
                                 }
@@ -253,6 +303,24 @@ impl PeerManagementHandler {
     }
 }
 
+/// Broadcasts the ban of a peer to the peer events subscribers
+fn broadcast_peer_ban(
+    peer_event_sender: &tokio::sync::broadcast::Sender<PeerEvent>,
+    peer_id: &PeerId,
+    ip: Option<IpAddr>,
+    reason: &str,
+) {
+    let event = PeerEvent {
+        peer_id: peer_id.clone(),
+        ip,
+        kind: PeerEventKind::Banned,
+        reason: reason.to_string(),
+    };
+    if let Err(err) = peer_event_sender.send(event) {
+        debug!("failed to broadcast peer ban event: {}", err);
+    }
+}
+
 #[derive(Clone)]
 pub struct MassaHandshake {
     pub announcement_serializer: AnnouncementSerializer,
@@ -357,6 +425,10 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     debug!("Banned peer tried to connect: {:?}", peer_id);
                 }
             }
+            if peer_db_read.is_banned_by_operator(&peer_id, Some(endpoint.get_target_addr().ip())) {
+                return Err(PeerNetError::HandshakeError
+                    .error("Massa Handshake", Some(String::from("Peer is banned"))));
+            }
         }
 
         let res = {
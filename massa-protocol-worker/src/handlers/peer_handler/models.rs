@@ -1,5 +1,5 @@
 use massa_channel::sender::MassaSender;
-use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolError};
+use massa_protocol_exports::{BanTarget, BootstrapPeers, PeerBan, PeerId, ProtocolError};
 use massa_time::MassaTime;
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
@@ -7,10 +7,11 @@ use rand::seq::SliceRandom;
 use std::cmp::Reverse;
 use std::collections::BTreeSet;
 use std::time::Duration;
-use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, net::IpAddr, net::SocketAddr, sync::Arc};
 use tracing::log::info;
 
 use super::announcement::Announcement;
+use super::ban_list::BanList;
 
 const THREE_DAYS_MS: u64 = 3 * 24 * 60 * 60 * 1_000_000;
 
@@ -23,6 +24,8 @@ pub struct PeerDB {
     pub index_by_newest: BTreeSet<(Reverse<u64>, PeerId)>,
     /// Tested addresses used to avoid testing the same address too often. //TODO: Need to be pruned
     pub tested_addresses: HashMap<SocketAddr, MassaTime>,
+    /// bans set by the node operator
+    pub ban_list: BanList,
 }
 
 pub type SharedPeerDB = Arc<RwLock<PeerDB>>;
@@ -46,8 +49,16 @@ pub enum PeerState {
 
 #[derive(Clone)]
 pub enum PeerManagementCmd {
-    Ban(Vec<PeerId>),
+    /// ban the peers, with the reason of the ban
+    Ban(Vec<PeerId>, String),
     Unban(Vec<PeerId>),
+    /// ban peers or ips set by the node operator
+    AddBans(Vec<PeerBan>),
+    /// lift bans set by the node operator
+    RemoveBans(Vec<BanTarget>),
+    GetBans {
+        responder: MassaSender<Vec<PeerBan>>,
+    },
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
@@ -142,6 +153,12 @@ impl PeerDB {
         result
     }
 
+    /// Whether a peer, optionally connecting from `ip`, is banned by the node operator
+    pub fn is_banned_by_operator(&self, peer_id: &PeerId, ip: Option<IpAddr>) -> bool {
+        let now = MassaTime::now().expect("Unable to get MassaTime::now");
+        self.ban_list.is_banned(peer_id, ip, now)
+    }
+
     pub fn get_banned_peer_count(&self) -> u64 {
         self.peers
             .values()
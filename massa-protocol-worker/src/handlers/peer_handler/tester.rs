@@ -126,6 +126,10 @@ impl Tester {
                                 .error("Tester Handshake", Some(String::from("Peer is banned"))));
                         }
                     }
+                    if peer_db_read.is_banned_by_operator(&peer_id, Some(addr.ip())) {
+                        return Err(PeerNetError::HandshakeError
+                            .error("Tester Handshake", Some(String::from("Peer is banned"))));
+                    }
                 }
 
                 let (data, version) = version_deserializer
@@ -72,6 +72,11 @@ fn test_protocol_sends_valid_endorsements_it_receives_to_pool() {
                             ENDORSEMENT_COUNT as usize
                         ],
                         producer: endorsement.content_creator_address,
+                        endorsement_owners: vec![
+                            endorsement.content_creator_address;
+                            ENDORSEMENT_COUNT as usize
+                        ],
+                        producer_owner: endorsement.content_creator_address,
                     }))
                     .unwrap();
             } else {
@@ -216,6 +221,11 @@ fn test_protocol_propagates_endorsements_to_active_nodes() {
                             ENDORSEMENT_COUNT as usize
                         ],
                         producer: endorsement.content_creator_address,
+                        endorsement_owners: vec![
+                            endorsement.content_creator_address;
+                            ENDORSEMENT_COUNT as usize
+                        ],
+                        producer_owner: endorsement.content_creator_address,
                     }))
                     .unwrap();
             } else {
@@ -318,6 +328,11 @@ fn test_protocol_propagates_endorsements_only_to_nodes_that_dont_know_about_it_b
                             ENDORSEMENT_COUNT as usize
                         ],
                         producer: endorsement.content_creator_address,
+                        endorsement_owners: vec![
+                            endorsement.content_creator_address;
+                            ENDORSEMENT_COUNT as usize
+                        ],
+                        producer_owner: endorsement.content_creator_address,
                     }))
                     .unwrap();
             } else {
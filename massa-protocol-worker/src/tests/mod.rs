@@ -5,13 +5,15 @@ use massa_metrics::MassaMetrics;
 use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX};
 use massa_pool_exports::test_exports::MockPoolController;
 use massa_pos_exports::test_exports::MockSelectorController;
-use massa_protocol_exports::{PeerCategoryInfo, PeerData, PeerId, ProtocolConfig};
+use massa_protocol_exports::{PeerCategoryInfo, PeerData, PeerEventKind, PeerId, ProtocolConfig};
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use peernet::peer::PeerConnectionType;
 use peernet::transports::TransportType;
 use tempfile::NamedTempFile;
 
+use crate::connectivity::broadcast_peer_events;
 use crate::{create_protocol_controller, start_protocol_controller};
 
 mod ban_nodes_scenarios;
@@ -315,3 +317,36 @@ fn stop_with_controller_still_exists() {
     manager1.stop();
     manager2.stop();
 }
+
+#[test]
+fn peer_events_from_connections_diff() {
+    let peer_a = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let peer_b = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    let addr_a = "1.2.3.4:31244".parse().unwrap();
+    let addr_b = "5.6.7.8:31244".parse().unwrap();
+    let (sender, mut receiver) = tokio::sync::broadcast::channel(10);
+
+    // peer A is already connected, peer B connects
+    let last_peers_connected = HashMap::from([(peer_a.clone(), addr_a)]);
+    let peers_connected = HashMap::from([
+        (peer_a.clone(), (addr_a, PeerConnectionType::OUT, None)),
+        (peer_b.clone(), (addr_b, PeerConnectionType::IN, None)),
+    ]);
+    broadcast_peer_events(&sender, &last_peers_connected, &peers_connected);
+    let event = receiver.try_recv().unwrap();
+    assert_eq!(event.peer_id, peer_b);
+    assert_eq!(event.ip, Some(addr_b.ip()));
+    assert_eq!(event.kind, PeerEventKind::Connected);
+    assert_eq!(event.reason, "incoming connection");
+    assert!(receiver.try_recv().is_err());
+
+    // peer A disconnects
+    let last_peers_connected = HashMap::from([(peer_a.clone(), addr_a), (peer_b.clone(), addr_b)]);
+    let peers_connected = HashMap::from([(peer_b, (addr_b, PeerConnectionType::IN, None))]);
+    broadcast_peer_events(&sender, &last_peers_connected, &peers_connected);
+    let event = receiver.try_recv().unwrap();
+    assert_eq!(event.peer_id, peer_a);
+    assert_eq!(event.ip, Some(addr_a.ip()));
+    assert_eq!(event.kind, PeerEventKind::Disconnected);
+    assert!(receiver.try_recv().is_err());
+}
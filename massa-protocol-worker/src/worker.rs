@@ -5,7 +5,7 @@ use massa_models::node::NodeId;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{
-    BootstrapPeers, PeerData, PeerId, ProtocolConfig, ProtocolController, ProtocolError,
+    BootstrapPeers, PeerData, PeerEvent, PeerId, ProtocolConfig, ProtocolController, ProtocolError,
     ProtocolManager,
 };
 use massa_serialization::U64VarIntDeserializer;
@@ -43,6 +43,7 @@ use crate::{
             commands_retrieval::OperationHandlerRetrievalCommand,
         },
         peer_handler::{
+            ban_list::BanList,
             models::{PeerDB, PeerManagementCmd},
             MassaHandshake,
         },
@@ -85,6 +86,8 @@ pub struct ProtocolChannels {
         MassaSender<PeerManagementCmd>,
         MassaReceiver<PeerManagementCmd>,
     ),
+    /// Broadcast channel for peer connections, disconnections and bans
+    pub peer_event_sender: tokio::sync::broadcast::Sender<PeerEvent>,
 }
 
 /// This function exists because consensus need the protocol controller and we need consensus controller.
@@ -163,6 +166,10 @@ pub fn create_protocol_controller(
             ),
             connectivity_thread: (sender_connectivity_ext, receiver_connectivity_ext),
             peer_management_handler: (sender_peer_management_ext, receiver_peer_management_ext),
+            peer_event_sender: tokio::sync::broadcast::channel(
+                config.broadcast_peer_events_channel_capacity,
+            )
+            .0,
         },
     )
 }
@@ -187,7 +194,10 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = Arc::new(RwLock::new(PeerDB {
+        ban_list: BanList::load(config.ban_list_file.clone()),
+        ..Default::default()
+    }));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -18,21 +18,30 @@ use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
     address::AddressInfo,
-    block::{BlockInfo, BlockSummary},
-    datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    block::{BlockFinalityInfo, BlockInfo, BlockInfoV2, BlockSummary, SlotBlock, SlotBlockOptions},
+    datastore::{
+        DatastoreEntryInput, DatastoreEntryOutput, DatastoreKeysInput, DatastoreKeysOutput,
+    },
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, GasEstimate, GasEstimationRequest, OperationTrace,
+        ReadOnlyBytecodeExecution, ReadOnlyCall, SCOutputEventPage, SCOutputEventQuery,
+    },
+    graph::GraphExportFormat,
+    node::{BanInfo, BanInput, BanTarget, GrpcDrainStatus, NodeStatus},
+    operation::{
+        AddressOperations, FeeEstimate, OperationInfo, OperationInput, PoolStatistics, PoolStatus,
+    },
     TimeInterval,
 };
+use massa_models::ip_range::IpRange;
 use massa_models::secure_share::SecureShare;
 use massa_models::{
     address::Address,
-    block::FilledBlock,
+    block::{ArchivedBlock, DiscardedBlockEvent, FilledBlock, ReorgEvent},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueInspection},
     composite::PubkeySig,
     endorsement::EndorsementId,
     execution::EventFilter,
@@ -40,17 +49,21 @@ use massa_models::{
     operation::{Operation, OperationId},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
     version::Version,
 };
 use massa_proto_rs::massa::api::v1::massa_service_client::MassaServiceClient;
+use massa_time::MassaTime;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use thiserror::Error;
 
 mod config;
+mod subscription;
 pub use config::ClientConfig;
 pub use config::HttpConfig;
 pub use config::WsConfig;
+pub use subscription::SubscriptionRequest;
 
 /// Error when creating a new client
 #[derive(Error, Debug)]
@@ -183,6 +196,25 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Put the gRPC API in drain mode with the given grace period
+    pub async fn node_start_grpc_drain(
+        &self,
+        grace_period: MassaTime,
+    ) -> RpcResult<GrpcDrainStatus> {
+        self.http_client
+            .request("node_start_grpc_drain", rpc_params![grace_period])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the gRPC API drain progress
+    pub async fn node_grpc_drain_status(&self) -> RpcResult<GrpcDrainStatus> {
+        self.http_client
+            .request("node_grpc_drain_status", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Unban given ip address(es)
     /// No confirmation to expect.
     pub async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
@@ -201,6 +233,30 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Bans node id(s) and IP address(es) for the given duration, or until they are unbanned
+    pub async fn node_add_bans(&self, bans: BanInput) -> RpcResult<()> {
+        self.http_client
+            .request("node_add_bans", rpc_params![bans])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the bans still in force
+    pub async fn node_bans(&self) -> RpcResult<Vec<BanInfo>> {
+        self.http_client
+            .request("node_bans", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Lifts the bans set on node id(s) and IP address(es)
+    pub async fn node_remove_bans(&self, targets: Vec<BanTarget>) -> RpcResult<()> {
+        self.http_client
+            .request("node_remove_bans", rpc_params![targets])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns node peers whitelist IP address(es).
     pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         self.http_client
@@ -226,7 +282,7 @@ impl RpcClient {
     }
 
     /// Returns node bootstrap whitelist IP address(es).
-    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpRange>> {
         self.http_client
             .request("node_bootstrap_whitelist", rpc_params![])
             .await
@@ -243,7 +299,7 @@ impl RpcClient {
     }
 
     /// Add IP address(es) to node bootstrap whitelist.
-    pub async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    pub async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         self.http_client
             .request("node_add_to_bootstrap_whitelist", rpc_params![ips])
             .await
@@ -251,7 +307,7 @@ impl RpcClient {
     }
 
     /// Remove IP address(es) to bootstrap whitelist.
-    pub async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    pub async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         self.http_client
             .request("node_remove_from_bootstrap_whitelist", rpc_params![ips])
             .await
@@ -259,7 +315,7 @@ impl RpcClient {
     }
 
     /// Returns node bootstrap blacklist IP address(es).
-    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
+    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpRange>> {
         self.http_client
             .request("node_bootstrap_blacklist", rpc_params![])
             .await
@@ -267,7 +323,7 @@ impl RpcClient {
     }
 
     /// Add IP address(es) to node bootstrap blacklist.
-    pub async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    pub async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         self.http_client
             .request("node_add_to_bootstrap_blacklist", rpc_params![ips])
             .await
@@ -275,13 +331,37 @@ impl RpcClient {
     }
 
     /// Remove IP address(es) to bootstrap blacklist.
-    pub async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+    pub async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpRange>) -> RpcResult<()> {
         self.http_client
             .request("node_remove_from_bootstrap_blacklist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the sender address(es) whose operations the node rejects from its pool and does not propagate.
+    pub async fn node_pool_denylist(&self) -> RpcResult<Vec<Address>> {
+        self.http_client
+            .request("node_pool_denylist", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Add sender address(es) to the pool denylist.
+    pub async fn node_add_to_pool_denylist(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.http_client
+            .request("node_add_to_pool_denylist", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Remove sender address(es) from the pool denylist.
+    pub async fn node_remove_from_pool_denylist(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.http_client
+            .request("node_remove_from_pool_denylist", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     ////////////////
     // public-api //
     ////////////////
@@ -303,6 +383,14 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Inspect the cliques of the graph, with the breakdown of their fitness
+    pub async fn get_cliques_inspection(&self) -> RpcResult<Vec<CliqueInspection>> {
+        self.http_client
+            .request("get_cliques_inspection", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     // Debug (specific information)
 
     /// Returns the active stakers and their roll counts for the current cycle.
@@ -324,6 +412,33 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the final executed operations involving an address, most recent first.
+    pub async fn get_operations_by_address(
+        &self,
+        address: Address,
+        cursor: Option<String>,
+        limit: Option<u64>,
+    ) -> RpcResult<AddressOperations> {
+        self.http_client
+            .request(
+                "get_operations_by_address",
+                rpc_params![address, cursor, limit],
+            )
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the trace of the last execution of an operation, if it was traced
+    pub async fn trace_operation(
+        &self,
+        operation_id: OperationId,
+    ) -> RpcResult<Option<OperationTrace>> {
+        self.http_client
+            .request("trace_operation", rpc_params![operation_id])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
     pub async fn get_endorsements(
         &self,
@@ -343,6 +458,75 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// get the status in the graph of some blocks, with the blocks themselves if they are known
+    pub async fn get_blocks_v2(&self, block_ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfoV2>> {
+        self.http_client
+            .request("v2_get_blocks", rpc_params![block_ids])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// get the finality of blocks
+    pub async fn get_blocks_finality(
+        &self,
+        block_ids: Vec<BlockId>,
+    ) -> RpcResult<Vec<BlockFinalityInfo>> {
+        self.http_client
+            .request("get_blocks_finality", rpc_params![block_ids])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// export the block graph of a slot range in DOT or GraphML
+    pub async fn get_block_graph_export(
+        &self,
+        start: Slot,
+        end: Slot,
+        format: GraphExportFormat,
+    ) -> RpcResult<String> {
+        self.http_client
+            .request("get_block_graph_export", rpc_params![start, end, format])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// get the metadata of the archived blocks with a slot in `[start, end)`, on archival nodes
+    pub async fn get_archived_blocks(
+        &self,
+        start: Slot,
+        end: Slot,
+    ) -> RpcResult<Vec<ArchivedBlock>> {
+        self.http_client
+            .request("get_archived_blocks", rpc_params![start, end])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// get the block of the blockclique, or the final block, at a slot
+    pub async fn get_block_by_slot(
+        &self,
+        slot: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Option<SlotBlock>> {
+        self.http_client
+            .request("get_block_by_slot", rpc_params![slot, options])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// get the blocks of the blockclique, or final blocks, from slot `start` (included) to slot `end` (excluded)
+    pub async fn get_blocks_by_slot_range(
+        &self,
+        start: Slot,
+        end: Slot,
+        options: Option<SlotBlockOptions>,
+    ) -> RpcResult<Vec<SlotBlock>> {
+        self.http_client
+            .request("get_blocks_by_slot_range", rpc_params![start, end, options])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get events emitted by smart contracts with various filters
     pub async fn get_filtered_sc_output_event(
         &self,
@@ -354,6 +538,17 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get a page of the events emitted by smart contracts, sorted by slot
+    pub async fn get_sc_output_events(
+        &self,
+        query: SCOutputEventQuery,
+    ) -> RpcResult<SCOutputEventPage> {
+        self.http_client
+            .request("get_sc_output_events", rpc_params![query])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     pub(crate) async fn _get_graph_interval(
@@ -385,8 +580,44 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// List the datastore keys of an address starting with a prefix, sorted by key
+    pub async fn get_datastore_keys(
+        &self,
+        input: DatastoreKeysInput,
+    ) -> RpcResult<DatastoreKeysOutput> {
+        self.http_client
+            .request("get_datastore_keys", rpc_params![input])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     // User (interaction with the node)
 
+    /// Suggests a fee for an operation to be included within `target_inclusion_slots` slots
+    pub async fn get_fee_estimate(&self, target_inclusion_slots: u64) -> RpcResult<FeeEstimate> {
+        self.http_client
+            .request("get_fee_estimate", rpc_params![target_inclusion_slots])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the size, fee percentiles and expiry histogram of the operation pool,
+    /// and the fill rate of the endorsement pool
+    pub async fn get_pool_statistics(&self) -> RpcResult<PoolStatistics> {
+        self.http_client
+            .request("get_pool_statistics", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the pool statistics and the operations created by `addresses` waiting in the pool
+    pub async fn get_pool_status(&self, addresses: Option<Vec<Address>>) -> RpcResult<PoolStatus> {
+        self.http_client
+            .request("get_pool_status", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     pub async fn send_operations(
         &self,
@@ -433,6 +664,14 @@ impl RpcClient {
                 to_error_obj("missing return value on execute_read_only_call".to_owned())
             })
     }
+
+    /// estimate the max gas of an unsigned SC operation
+    pub async fn estimate_gas(&self, request: GasEstimationRequest) -> RpcResult<GasEstimate> {
+        self.http_client
+            .request("estimate_gas", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
 }
 
 /// Client V2
@@ -590,6 +829,40 @@ impl RpcClientV2 {
         }
     }
 
+    /// Blockclique changes reverting blocks.
+    pub async fn subscribe_new_reorgs(
+        &self,
+    ) -> Result<Subscription<ReorgEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_new_reorgs",
+                    rpc_params![],
+                    "unsubscribe_new_reorgs",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
+    /// Blocks discarded by the consensus, with the reason why.
+    pub async fn subscribe_new_discarded_blocks(
+        &self,
+    ) -> Result<Subscription<DiscardedBlockEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_new_discarded_blocks",
+                    rpc_params![],
+                    "unsubscribe_new_discarded_blocks",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
     /// New produced operations.
     pub async fn subscribe_new_operations(
         &self,
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Request side of the gRPC streaming subscriptions.
+//!
+//! The streaming methods of the gRPC API (`new_blocks`, `new_slot_execution_outputs`...) end a
+//! subscription as soon as the client closes its request stream, so a subscription needs a
+//! request stream that stays open after its first message.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tonic::codegen::futures_core::Stream;
+
+/// Request stream sending a single subscription request, then staying open
+pub struct SubscriptionRequest<T> {
+    request: Option<T>,
+}
+
+impl<T> SubscriptionRequest<T> {
+    /// Subscription request stream sending `request`
+    pub fn new(request: T) -> Self {
+        SubscriptionRequest {
+            request: Some(request),
+        }
+    }
+}
+
+impl<T: Unpin> Stream for SubscriptionRequest<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.request.take() {
+            Some(request) => Poll::Ready(Some(request)),
+            // never ends: the subscription lasts until the response stream is dropped
+            None => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Verification compatibility between `PublicKey` and `Signature` versions.
+//!
+//! Each version of `PublicKey` and `Signature` is bound to an underlying signature scheme.
+//! A signature can be checked against a public key as soon as both rely on the same scheme,
+//! even if their version numbers differ (e.g. a V0 public key and a V1 signature are both ed25519).
+
+use crate::error::MassaSignatureError;
+
+/// Signature scheme backing a `PublicKey` or `Signature` version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureScheme {
+    /// ed25519 (through `ed25519_dalek`)
+    Ed25519,
+}
+
+/// Compatibility matrix: version number -> signature scheme.
+///
+/// Adding a new `PublicKey`/`Signature` version requires declaring its scheme here.
+/// Versions sharing a scheme are considered verification-compatible.
+const VERSION_SCHEMES: [(u64, SignatureScheme); 2] =
+    [(0, SignatureScheme::Ed25519), (1, SignatureScheme::Ed25519)];
+
+impl SignatureScheme {
+    /// Get the signature scheme associated to a `PublicKey` or `Signature` version
+    pub fn from_version(version: u64) -> Result<Self, MassaSignatureError> {
+        VERSION_SCHEMES
+            .iter()
+            .find(|(v, _)| *v == version)
+            .map(|(_, scheme)| *scheme)
+            .ok_or_else(|| {
+                MassaSignatureError::InvalidVersionError(format!(
+                    "No signature scheme declared for version {}",
+                    version
+                ))
+            })
+    }
+}
+
+/// Returns the common signature scheme if a signature of version `signature_version`
+/// can be verified with a public key of version `public_key_version`.
+pub fn verification_scheme(
+    public_key_version: u64,
+    signature_version: u64,
+) -> Result<SignatureScheme, MassaSignatureError> {
+    let public_key_scheme = SignatureScheme::from_version(public_key_version)?;
+    let signature_scheme = SignatureScheme::from_version(signature_version)?;
+    if public_key_scheme != signature_scheme {
+        return Err(MassaSignatureError::InvalidVersionError(format!(
+            "PublicKey version {} ({:?}) cannot verify Signature version {} ({:?})",
+            public_key_version, public_key_scheme, signature_version, signature_scheme
+        )));
+    }
+    Ok(public_key_scheme)
+}
+
+/// Checks whether a signature of version `signature_version`
+/// can be verified with a public key of version `public_key_version`
+pub fn is_verification_compatible(public_key_version: u64, signature_version: u64) -> bool {
+    verification_scheme(public_key_version, signature_version).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatibility_matrix() {
+        assert!(is_verification_compatible(0, 0));
+        assert!(is_verification_compatible(0, 1));
+        assert!(is_verification_compatible(1, 0));
+        assert!(is_verification_compatible(1, 1));
+        assert!(!is_verification_compatible(0, 42));
+        assert!(!is_verification_compatible(42, 1));
+    }
+}
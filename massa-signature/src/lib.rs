@@ -3,9 +3,13 @@
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
+mod compatibility;
 mod error;
 mod signature_impl;
+#[cfg(test)]
+mod tests;
 
+pub use compatibility::{is_verification_compatible, verification_scheme, SignatureScheme};
 pub use error::MassaSignatureError;
 pub use signature_impl::{
     verify_signature_batch, KeyPair, PublicKey, PublicKeyDeserializer, PublicKeyV0, PublicKeyV1,
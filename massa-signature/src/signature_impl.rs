@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::compatibility::{verification_scheme, SignatureScheme};
 use crate::error::MassaSignatureError;
 
 use ed25519_dalek::{Signer, Verifier};
@@ -580,16 +581,32 @@ impl PublicKey {
         hash: &Hash,
         signature: &Signature,
     ) -> Result<(), MassaSignatureError> {
-        match (self, signature) {
-            (PublicKey::PublicKeyV0(pubkey), Signature::SignatureV0(signature)) => {
-                pubkey.verify_signature(hash, signature)
-            }
-            (PublicKey::PublicKeyV1(pubkey), Signature::SignatureV1(signature)) => {
-                pubkey.verify_signature(hash, signature)
-            }
-            _ => Err(MassaSignatureError::InvalidVersionError(String::from(
-                "The PublicKey and Signature versions do not match",
-            ))),
+        match verification_scheme(self.get_version(), signature.get_version())? {
+            SignatureScheme::Ed25519 => self
+                .ed25519_public_key()
+                .verify(hash.to_bytes(), signature.ed25519_signature())
+                .map_err(|err| {
+                    MassaSignatureError::SignatureError(format!(
+                        "Signature verification failed: {}",
+                        err
+                    ))
+                }),
+        }
+    }
+
+    /// Get the version of the given `PublicKey`
+    pub fn get_version(&self) -> u64 {
+        match self {
+            PublicKey::PublicKeyV0(pubkey) => pubkey.get_version(),
+            PublicKey::PublicKeyV1(pubkey) => pubkey.get_version(),
+        }
+    }
+
+    /// Underlying ed25519 public key, shared by all ed25519-based versions
+    fn ed25519_public_key(&self) -> &ed25519_dalek::PublicKey {
+        match self {
+            PublicKey::PublicKeyV0(pubkey) => &pubkey.0,
+            PublicKey::PublicKeyV1(pubkey) => &pubkey.0,
         }
     }
 
@@ -695,6 +712,11 @@ impl PublicKey {
     /// Size of a public key
     pub const PUBLIC_KEY_SIZE_BYTES: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
 
+    /// Return the current version public key
+    pub fn get_version(&self) -> u64 {
+        Self::VERSION
+    }
+
     /// Return the total length after serialization
     pub fn get_ser_len(&self) -> usize {
         Self::VERSION_VARINT_SIZE_BYTES + Self::PUBLIC_KEY_SIZE_BYTES
@@ -876,6 +898,11 @@ pub struct Signature(ed25519_dalek::Signature);
 impl Signature {
     /// Size of a signature
     pub const SIGNATURE_SIZE_BYTES: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+    /// Return the current version signature
+    pub fn get_version(&self) -> u64 {
+        Self::VERSION
+    }
 }
 
 impl std::fmt::Display for Signature {
@@ -913,6 +940,22 @@ impl FromStr for Signature {
 }
 
 impl Signature {
+    /// Get the version of the given `Signature`
+    pub fn get_version(&self) -> u64 {
+        match self {
+            Signature::SignatureV0(signature) => signature.get_version(),
+            Signature::SignatureV1(signature) => signature.get_version(),
+        }
+    }
+
+    /// Underlying ed25519 signature, shared by all ed25519-based versions
+    fn ed25519_signature(&self) -> &ed25519_dalek::Signature {
+        match self {
+            Signature::SignatureV0(signature) => &signature.0,
+            Signature::SignatureV1(signature) => &signature.0,
+        }
+    }
+
     /// Serialize a `Signature` using `bs58` encoding with checksum.
     ///
     /// # Example
@@ -1278,6 +1321,9 @@ impl Deserializer<Signature> for SignatureDeserializer {
 }
 
 /// Verifies a batch of signatures
+///
+/// The batch may mix `PublicKey` and `Signature` versions as long as
+/// each pair is verification-compatible (see `is_verification_compatible`).
 pub fn verify_signature_batch(
     batch: &[(Hash, Signature, PublicKey)],
 ) -> Result<(), MassaSignatureError> {
@@ -1297,20 +1343,14 @@ pub fn verify_signature_batch(
     let mut signatures = Vec::with_capacity(batch.len());
     let mut public_keys = Vec::with_capacity(batch.len());
 
-    for (hash, signature_, public_key_) in batch.iter() {
-        let (signature, public_key) = match (signature_, public_key_) {
-            (Signature::SignatureV0(s), PublicKey::PublicKeyV0(pk)) => (s.0, pk.0),
-            (Signature::SignatureV1(s), PublicKey::PublicKeyV1(pk)) => (s.0, pk.0),
-            _ => {
-                return Err(MassaSignatureError::InvalidVersionError(String::from(
-                    "Batch contains unsupported or incompatible versions",
-                )))
+    for (hash, signature, public_key) in batch.iter() {
+        match verification_scheme(public_key.get_version(), signature.get_version())? {
+            SignatureScheme::Ed25519 => {
+                hashes.push(hash.to_bytes().as_slice());
+                signatures.push(*signature.ed25519_signature());
+                public_keys.push(*public_key.ed25519_public_key());
             }
-        };
-
-        hashes.push(hash.to_bytes().as_slice());
-        signatures.push(signature);
-        public_keys.push(public_key);
+        }
     }
 
     ed25519_dalek::verify_batch(&hashes, signatures.as_slice(), public_keys.as_slice()).map_err(
@@ -1342,6 +1382,36 @@ mod tests {
             .is_ok())
     }
 
+    #[test]
+    #[serial]
+    fn test_verify_cross_version() {
+        let hash = Hash::compute_from("Hello World!".as_bytes());
+        let keypair_v0 = KeyPair::generate(0).unwrap();
+        // same secret key, re-tagged as version 1
+        let mut keypair_bytes = keypair_v0.to_bytes();
+        keypair_bytes[0] = 1;
+        let keypair_v1 = KeyPair::from_bytes(&keypair_bytes).unwrap();
+        let signature_v1 = keypair_v1.sign(&hash).unwrap();
+        assert_eq!(signature_v1.get_version(), 1);
+        keypair_v0
+            .get_public_key()
+            .verify_signature(&hash, &signature_v1)
+            .unwrap();
+
+        let other_keypair = KeyPair::generate(1).unwrap();
+        let other_signature = other_keypair.sign(&hash).unwrap();
+        verify_signature_batch(&[
+            (hash, signature_v1, keypair_v0.get_public_key()),
+            (hash, other_signature, other_keypair.get_public_key()),
+            (
+                hash,
+                keypair_v0.sign(&hash).unwrap(),
+                keypair_v1.get_public_key(),
+            ),
+        ])
+        .unwrap();
+    }
+
     #[test]
     #[serial]
     fn test_serde_keypair() {
@@ -0,0 +1,160 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Cross-version compatibility of the serialized signature artifacts.
+//!
+//! `fixtures/v0_v1_artifacts.json` holds keys and signatures serialized with the V0 and V1 formats.
+//! These fixtures must never be regenerated: they stand for artifacts produced by older releases
+//! (wallet files, signed operations stored on disk or on chain) that every new release must still accept.
+
+use crate::{is_verification_compatible, verify_signature_batch, KeyPair, PublicKey, Signature};
+use massa_hash::Hash;
+use serde::Deserialize;
+use std::str::FromStr;
+
+const FIXTURES: &str = include_str!("fixtures/v0_v1_artifacts.json");
+
+#[derive(Deserialize)]
+struct Fixtures {
+    /// message whose hash was signed
+    message: String,
+    /// hash of the message
+    hash: String,
+    artifacts: Vec<Artifact>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    /// artifacts sharing an owner were produced from the same secret key
+    owner: String,
+    version: u64,
+    secret_key: String,
+    public_key: String,
+    signature: String,
+}
+
+fn load_fixtures() -> (Hash, Vec<Artifact>) {
+    let fixtures: Fixtures = serde_json::from_str(FIXTURES).expect("invalid fixtures file");
+    let hash = Hash::compute_from(fixtures.message.as_bytes());
+    assert_eq!(hash.to_string(), fixtures.hash, "message hash changed");
+    (hash, fixtures.artifacts)
+}
+
+#[test]
+fn test_fixtures_keys_parse() {
+    let (_, artifacts) = load_fixtures();
+    for artifact in &artifacts {
+        let keypair = KeyPair::from_str(&artifact.secret_key).unwrap();
+        assert_eq!(keypair.get_version(), artifact.version);
+        assert_eq!(keypair.to_string(), artifact.secret_key);
+        assert_eq!(
+            KeyPair::from_bytes(&keypair.to_bytes())
+                .unwrap()
+                .to_string(),
+            artifact.secret_key
+        );
+
+        let public_key = PublicKey::from_str(&artifact.public_key).unwrap();
+        assert_eq!(public_key.get_version(), artifact.version);
+        assert_eq!(public_key.to_string(), artifact.public_key);
+        assert_eq!(keypair.get_public_key(), public_key);
+        assert_eq!(
+            PublicKey::from_bytes(&public_key.to_bytes()).unwrap(),
+            public_key
+        );
+
+        // wallet files store the keypairs with the serde format
+        let wallet_keypair: KeyPair = serde_json::from_value(serde_json::json!({
+            "secret_key": artifact.secret_key,
+            "public_key": artifact.public_key,
+        }))
+        .unwrap();
+        assert_eq!(wallet_keypair.to_string(), artifact.secret_key);
+        let serde_public_key: PublicKey =
+            serde_json::from_value(serde_json::json!(artifact.public_key)).unwrap();
+        assert_eq!(serde_public_key, public_key);
+    }
+}
+
+#[test]
+fn test_fixtures_signatures_parse() {
+    let (hash, artifacts) = load_fixtures();
+    for artifact in &artifacts {
+        let signature = Signature::from_str(&artifact.signature).unwrap();
+        assert_eq!(signature.get_version(), artifact.version);
+        assert_eq!(signature.to_string(), artifact.signature);
+        assert_eq!(
+            Signature::from_bs58_check(&artifact.signature).unwrap(),
+            signature
+        );
+        assert_eq!(
+            Signature::from_bytes(&signature.to_bytes()).unwrap(),
+            signature
+        );
+        let serde_signature: Signature =
+            serde_json::from_value(serde_json::json!(artifact.signature)).unwrap();
+        assert_eq!(serde_signature, signature);
+
+        // ed25519 signatures are deterministic: signing again must give the stored signature
+        let keypair = KeyPair::from_str(&artifact.secret_key).unwrap();
+        assert_eq!(keypair.sign(&hash).unwrap(), signature);
+    }
+}
+
+#[test]
+fn test_fixtures_verification_matrix() {
+    let (hash, artifacts) = load_fixtures();
+    let other_hash = Hash::compute_from(b"another message");
+    for key_artifact in &artifacts {
+        let public_key = PublicKey::from_str(&key_artifact.public_key).unwrap();
+        for signature_artifact in &artifacts {
+            let signature = Signature::from_str(&signature_artifact.signature).unwrap();
+            let expected = key_artifact.owner == signature_artifact.owner
+                && is_verification_compatible(key_artifact.version, signature_artifact.version);
+            assert_eq!(
+                public_key.verify_signature(&hash, &signature).is_ok(),
+                expected,
+                "public key V{} of {} against signature V{} of {}",
+                key_artifact.version,
+                key_artifact.owner,
+                signature_artifact.version,
+                signature_artifact.owner
+            );
+            assert!(public_key
+                .verify_signature(&other_hash, &signature)
+                .is_err());
+        }
+    }
+}
+
+#[test]
+fn test_fixtures_batch_verification() {
+    let (hash, artifacts) = load_fixtures();
+    // pair every signature with the public key of the same owner in the other version
+    let batch: Vec<(Hash, Signature, PublicKey)> = artifacts
+        .iter()
+        .map(|signature_artifact| {
+            let key_artifact = artifacts
+                .iter()
+                .find(|artifact| {
+                    artifact.owner == signature_artifact.owner
+                        && artifact.version != signature_artifact.version
+                })
+                .unwrap();
+            (
+                hash,
+                Signature::from_str(&signature_artifact.signature).unwrap(),
+                PublicKey::from_str(&key_artifact.public_key).unwrap(),
+            )
+        })
+        .collect();
+    verify_signature_batch(&batch).unwrap();
+
+    // swapping the public keys of two owners must make the batch fail
+    let mut tampered = batch.clone();
+    let first_key = tampered[0].2;
+    let last_key = tampered[tampered.len() - 1].2;
+    tampered[0].2 = last_key;
+    let last = tampered.len() - 1;
+    tampered[last].2 = first_key;
+    assert!(verify_signature_batch(&tampered).is_err());
+}
@@ -61,3 +61,17 @@ pub fn advance_state_until(at_state: ComponentState, versioning_info: &MipInfo)
     // Active
     state
 }
+
+/// Advances the state of a MIP until it is active, `activation_delay` + 3 ms after its start.
+/// Usable by the tests of the other crates, which cannot name a `ComponentState`.
+pub fn advance_state_until_active(versioning_info: &MipInfo) -> MipState {
+    advance_state_until(
+        ComponentState::active(
+            versioning_info
+                .start
+                .saturating_add(versioning_info.activation_delay)
+                .saturating_add(MassaTime::from_millis(3)),
+        ),
+        versioning_info,
+    )
+}
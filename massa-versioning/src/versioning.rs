@@ -14,6 +14,7 @@ use massa_db::{
 };
 use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, MIP_STORE_STATS_COUNTERS_MAX};
 use massa_models::error::ModelsError;
+use massa_models::operation::OperationType;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{amount::Amount, config::VERSIONING_THRESHOLD_TRANSITION_ACCEPTED};
@@ -36,6 +37,10 @@ pub enum MipComponent {
     Block,
     VM,
     FinalStateHashKind,
+    // gas costs schedule applied to the executions
+    GasCosts,
+    // stake delegation operation, delegations of the PoS state and draws of the operators
+    StakeDelegation,
     #[doc(hidden)]
     #[num_enum(default)]
     __Nonexhaustive,
@@ -566,6 +571,17 @@ impl MipStore {
         lock.get_latest_component_version_at(component, ts)
     }
 
+    /// Whether an operation of type `op` is valid at `ts`:
+    /// the operation types introduced by a MIP are only valid once the MIP is active
+    pub fn is_operation_type_active_at(&self, op: &OperationType, ts: MassaTime) -> bool {
+        match op {
+            OperationType::StakeDelegation { .. } => {
+                self.get_latest_component_version_at(&MipComponent::StakeDelegation, ts) >= 1
+            }
+            _ => true,
+        }
+    }
+
     // GRPC
 
     /// Retrieve a list of MIP info with their corresponding state (as id) - used for grpc API
@@ -2249,4 +2265,49 @@ mod test {
         assert_eq!(mip_store.stats.network_version_counters.len(), 1);
         assert_eq!(mip_store.stats.network_version_counters.get(&2), Some(&1));
     }
+
+    #[test]
+    fn test_operation_type_active_at() {
+        // Test MipStore::is_operation_type_active_at with the stake delegation MIP
+
+        let mi = MipInfo {
+            name: "MIP-0004".to_string(),
+            version: 4,
+            components: BTreeMap::from([(MipComponent::StakeDelegation, 1)]),
+            start: MassaTime::from_millis(12),
+            timeout: MassaTime::from_millis(40),
+            activation_delay: MassaTime::from_millis(2),
+        };
+        let mip_stats_cfg = MipStatsConfig {
+            block_count_considered: 10,
+            counters_max: 5,
+        };
+        let mip_store = MipStore::try_from((
+            [(mi.clone(), MipState::new(MassaTime::from_millis(10)))],
+            mip_stats_cfg,
+        ))
+        .unwrap();
+
+        let stake_delegation = OperationType::StakeDelegation { operator: None };
+        let roll_buy = OperationType::RollBuy { roll_count: 1 };
+
+        // the MIP is not active yet
+        assert!(
+            !mip_store.is_operation_type_active_at(&stake_delegation, MassaTime::from_millis(100))
+        );
+        assert!(mip_store.is_operation_type_active_at(&roll_buy, MassaTime::from_millis(100)));
+
+        // the MIP is active from start + 3 ms + activation delay
+        mip_store.0.write().store = BTreeMap::from([(
+            mi.clone(),
+            advance_state_until(ComponentState::active(MassaTime::from_millis(17)), &mi),
+        )]);
+        assert!(
+            !mip_store.is_operation_type_active_at(&stake_delegation, MassaTime::from_millis(5))
+        );
+        assert!(
+            mip_store.is_operation_type_active_at(&stake_delegation, MassaTime::from_millis(100))
+        );
+        assert!(mip_store.is_operation_type_active_at(&roll_buy, MassaTime::from_millis(5)));
+    }
 }
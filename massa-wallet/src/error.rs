@@ -22,4 +22,31 @@ pub enum WalletError {
     MissingKeyError(Address),
     /// `MassaCipher` error: {0}
     MassaCipherError(#[from] massa_cipher::CipherError),
+    /// `MassaSignature` error: {0}
+    MassaSignatureError(#[from] massa_signature::MassaSignatureError),
+    /// Signer error: {0}
+    SignerError(String),
+    /// Keystore error: {0}
+    KeystoreError(String),
+    /// HD wallet error: {0}
+    HdError(String),
+    /// Wallet file error: {0}
+    WalletFileError(String),
+    /// Wallet lock error: {0}
+    WalletLockError(String),
+    /// Keychain error: {0}
+    KeychainError(String),
+    /// TPM unavailable: {0}
+    TpmUnavailable(String),
+    /// `systemd-creds` {action} failed with {status}: {stderr}
+    TpmCommandFailed {
+        /// action of `systemd-creds` that failed
+        action: &'static str,
+        /// exit status of `systemd-creds`
+        status: std::process::ExitStatus,
+        /// error output of `systemd-creds`
+        stderr: String,
+    },
+    /// Signing refused: {0}
+    SigningRefused(String),
 }
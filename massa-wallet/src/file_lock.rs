@@ -0,0 +1,156 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Safe concurrent access to the wallet file, e.g. by the node and the client.
+//!
+//! Accesses to the wallet file are serialized by an advisory lock file next to it
+//! (`<wallet file>.lock`), created exclusively and holding the process id of its owner and its
+//! creation time. A lock whose owner is no longer running, or older than [`STALE_LOCK_AGE`],
+//! is considered left over by a crashed process and removed.
+//!
+//! The wallet file is replaced atomically: the new content is written and synced to a temporary
+//! file which is then renamed over the wallet file, so that a crash never leaves it truncated.
+
+use crate::WalletError;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Maximum time waited for the lock of the wallet file
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between two attempts to take the lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Age after which a lock is considered stale, whatever its owner
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Lock of the wallet file, released when dropped
+pub(crate) struct WalletFileLock {
+    /// path of the lock file
+    path: PathBuf,
+}
+
+impl WalletFileLock {
+    /// Takes the lock of a wallet file, waiting for its release if it is held
+    pub(crate) fn acquire(wallet_path: &Path) -> Result<Self, WalletError> {
+        Self::acquire_with_timeout(wallet_path, LOCK_TIMEOUT)
+    }
+
+    /// Takes the lock of a wallet file, waiting at most `timeout` for its release
+    fn acquire_with_timeout(wallet_path: &Path, timeout: Duration) -> Result<Self, WalletError> {
+        let path = lock_path(wallet_path);
+        let start = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}\n{}", std::process::id(), now_millis())?;
+                    return Ok(WalletFileLock { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        // another process may remove it first
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+            if start.elapsed().unwrap_or_default() >= timeout {
+                return Err(WalletError::WalletLockError(format!(
+                    "{} is locked by another process, remove {} if no other process uses the wallet",
+                    wallet_path.display(),
+                    path.display()
+                )));
+            }
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+}
+
+impl Drop for WalletFileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Path of the lock file of a wallet file
+fn lock_path(wallet_path: &Path) -> PathBuf {
+    let mut path = wallet_path.as_os_str().to_owned();
+    path.push(".lock");
+    PathBuf::from(path)
+}
+
+/// Milliseconds since the UNIX epoch
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+/// True if a lock was left over by a process that is no longer running or is too old.
+/// A lock that cannot be read yet is being written, so it is not stale.
+fn is_stale(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let mut lines = content.lines();
+    let (Some(Ok(pid)), Some(Ok(created_at))) = (
+        lines.next().map(str::parse::<u32>),
+        lines.next().map(str::parse::<u128>),
+    ) else {
+        return false;
+    };
+    if now_millis().saturating_sub(created_at) > STALE_LOCK_AGE.as_millis() {
+        return true;
+    }
+    // the running processes are only known on linux
+    let proc = Path::new("/proc");
+    proc.is_dir() && !proc.join(pid.to_string()).exists()
+}
+
+/// Path of the backup of a wallet file rewritten in a new format
+pub(crate) fn backup_path(wallet_path: &Path) -> PathBuf {
+    let mut path = wallet_path.as_os_str().to_owned();
+    path.push(".bak");
+    PathBuf::from(path)
+}
+
+/// Replaces the content of a file atomically
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> Result<(), WalletError> {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&tmp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_file_lock() {
+        let wallet_path =
+            std::env::temp_dir().join(format!("massa_wallet_lock_test_{}", std::process::id()));
+        let lock = WalletFileLock::acquire(&wallet_path).unwrap();
+        assert!(
+            WalletFileLock::acquire_with_timeout(&wallet_path, Duration::from_millis(100)).is_err()
+        );
+        drop(lock);
+        assert!(!lock_path(&wallet_path).exists());
+
+        // stale lock of a process that ended long ago
+        std::fs::write(lock_path(&wallet_path), format!("{}\n{}\n", u32::MAX, 0)).unwrap();
+        drop(WalletFileLock::acquire(&wallet_path).unwrap());
+        assert!(!lock_path(&wallet_path).exists());
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Hierarchical-deterministic accounts.
+//!
+//! An HD wallet holds a single 32 bytes seed from which any number of accounts are derived:
+//! the secret key of the account `i` is `blake3(seed ++ "massa-hd-account" ++ u32_le(i))`,
+//! as a version 0 keypair. Backing up the seed is enough to recover all the accounts.
+//!
+//! Accounts are derived lazily. The derivation index of each derived account is kept in the
+//! wallet file, and restoring a seed scans the addresses in order until `gap_limit`
+//! consecutive ones were never used.
+
+use crate::keystore::{from_hex, to_hex};
+use crate::WalletError;
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_signature::KeyPair;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Size of an HD seed, in bytes
+pub const HD_SEED_SIZE: usize = 32;
+
+/// Default number of consecutive unused addresses ending an account scan
+pub const HD_DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Domain separation of the account derivation
+const HD_ACCOUNT_DOMAIN: &[u8] = b"massa-hd-account";
+
+/// Seed and derived accounts of an HD wallet
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HdWallet {
+    /// seed of the accounts
+    seed: [u8; HD_SEED_SIZE],
+    /// addresses of the derived accounts, by derivation index
+    pub accounts: BTreeMap<u32, Address>,
+}
+
+impl HdWallet {
+    /// Creates an HD wallet from a seed, without any derived account
+    pub fn new(seed: [u8; HD_SEED_SIZE]) -> Self {
+        HdWallet {
+            seed,
+            accounts: BTreeMap::new(),
+        }
+    }
+
+    /// Creates an HD wallet from a random seed
+    pub fn generate() -> Result<Self, WalletError> {
+        // the secret key of a fresh keypair is a fine source of randomness
+        let entropy = KeyPair::generate(0)?.to_bytes();
+        Ok(Self::new(Hash::compute_from(&entropy).into_bytes()))
+    }
+
+    /// Seed of the accounts
+    pub fn seed(&self) -> &[u8; HD_SEED_SIZE] {
+        &self.seed
+    }
+
+    /// Seed of the accounts, in hexadecimal
+    pub fn seed_hex(&self) -> String {
+        to_hex(&self.seed)
+    }
+
+    /// Parses a seed given in hexadecimal
+    pub fn parse_seed(value: &str) -> Result<[u8; HD_SEED_SIZE], WalletError> {
+        from_hex(value)?.try_into().map_err(|_| {
+            WalletError::HdError(format!("the seed must be {} bytes long", HD_SEED_SIZE))
+        })
+    }
+
+    /// Derives the keypair of the account at `index`, without recording it
+    pub fn derive(&self, index: u32) -> Result<KeyPair, WalletError> {
+        let secret =
+            Hash::compute_from(&[&self.seed[..], HD_ACCOUNT_DOMAIN, &index.to_le_bytes()].concat());
+        // version 0 keypair: the varint version prefix is a single zero byte
+        Ok(KeyPair::from_bytes(
+            &[&[0u8][..], secret.to_bytes()].concat(),
+        )?)
+    }
+
+    /// Index of the next account to derive
+    pub fn next_index(&self) -> u32 {
+        self.accounts
+            .keys()
+            .next_back()
+            .map_or(0, |index| index.saturating_add(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hd_derivation_is_deterministic() {
+        let hd = HdWallet::new([7; HD_SEED_SIZE]);
+        let first = hd.derive(0).unwrap();
+        assert_eq!(first.to_string(), hd.derive(0).unwrap().to_string());
+        assert_ne!(first.to_string(), hd.derive(1).unwrap().to_string());
+        assert_ne!(
+            first.to_string(),
+            HdWallet::new([8; HD_SEED_SIZE])
+                .derive(0)
+                .unwrap()
+                .to_string()
+        );
+    }
+}
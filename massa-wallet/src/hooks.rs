@@ -0,0 +1,278 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Hooks called on each signature made with the keys of the wallet.
+//!
+//! Every signature request goes through the `check` of all the hooks of the wallet, any of them
+//! being able to refuse it, and is then `record`ed by all of them with the refusal reason if any.
+//! [`AuditLog`] records the requests in an append-only file and [`TransferLimit`] asks for a
+//! confirmation of the transfers above a threshold.
+
+use crate::{Signer, WalletError};
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::operation::{Operation, OperationType, SecureShareOperation};
+use massa_signature::{PublicKey, Signature};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What is about to be signed
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SigningSummary {
+    /// an operation
+    Operation {
+        /// type of the operation, e.g. `Transaction`
+        operation_type: String,
+        /// fee of the operation
+        fee: Amount,
+        /// last period at which the operation can be included
+        expire_period: u64,
+        /// coins sent or made available by the operation
+        amount: Amount,
+        /// recipient or target of the operation, if any
+        recipient: Option<Address>,
+    },
+    /// an arbitrary hash, e.g. of a message
+    Hash {
+        /// signed hash
+        hash: Hash,
+    },
+}
+
+impl SigningSummary {
+    /// Summarizes an operation
+    pub fn operation(operation: &Operation) -> Self {
+        let (operation_type, amount, recipient) = match &operation.op {
+            OperationType::Transaction {
+                recipient_address,
+                amount,
+            } => ("Transaction", *amount, Some(*recipient_address)),
+            OperationType::RollBuy { .. } => ("RollBuy", Amount::zero(), None),
+            OperationType::RollSell { .. } => ("RollSell", Amount::zero(), None),
+            OperationType::ExecuteSC { max_coins, .. } => ("ExecuteSC", *max_coins, None),
+            OperationType::CallSC {
+                target_addr, coins, ..
+            } => ("CallSC", *coins, Some(*target_addr)),
+            OperationType::StakeDelegation { operator } => {
+                ("StakeDelegation", Amount::zero(), *operator)
+            }
+        };
+        SigningSummary::Operation {
+            operation_type: operation_type.to_string(),
+            fee: operation.fee,
+            expire_period: operation.expire_period,
+            amount,
+            recipient,
+        }
+    }
+}
+
+/// A signature request
+#[derive(Clone, Debug, Serialize)]
+pub struct SigningRequest {
+    /// signing address
+    pub address: Address,
+    /// what is signed
+    pub summary: SigningSummary,
+}
+
+/// Hook called on each signature
+pub trait SigningHook: Send + Sync {
+    /// Checks a signature request, returning an error refuses it
+    fn check(&self, _request: &SigningRequest) -> Result<(), WalletError> {
+        Ok(())
+    }
+
+    /// Records a signature request once checked by all the hooks, with the reason of its refusal
+    fn record(
+        &self,
+        _request: &SigningRequest,
+        _refusal: Option<&WalletError>,
+    ) -> Result<(), WalletError> {
+        Ok(())
+    }
+}
+
+/// Hooks of a wallet
+#[derive(Clone, Default)]
+pub(crate) struct SigningHooks(pub Vec<Arc<dyn SigningHook>>);
+
+impl std::fmt::Debug for SigningHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} signing hooks", self.0.len())
+    }
+}
+
+impl SigningHooks {
+    /// Runs the hooks on a request, failing if it is refused
+    fn run(&self, request: &SigningRequest) -> Result<(), WalletError> {
+        let refusal = self.0.iter().try_for_each(|hook| hook.check(request)).err();
+        for hook in &self.0 {
+            hook.record(request, refusal.as_ref())?;
+        }
+        match refusal {
+            Some(refusal) => Err(refusal),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Signer of a wallet key, running the hooks of the wallet before each signature
+pub struct WalletSigner<'a> {
+    /// signer of the key
+    pub(crate) inner: &'a dyn Signer,
+    /// hooks of the wallet
+    pub(crate) hooks: &'a SigningHooks,
+}
+
+impl Signer for WalletSigner<'_> {
+    fn public_key(&self) -> PublicKey {
+        self.inner.public_key()
+    }
+
+    fn sign_hash(&self, hash: &Hash) -> Result<Signature, WalletError> {
+        self.hooks.run(&SigningRequest {
+            address: self.address(),
+            summary: SigningSummary::Hash { hash: *hash },
+        })?;
+        self.inner.sign_hash(hash)
+    }
+
+    fn requires_confirmation(&self) -> bool {
+        self.inner.requires_confirmation()
+    }
+
+    fn can_export_secret_key(&self) -> bool {
+        self.inner.can_export_secret_key()
+    }
+
+    fn sign_operation(&self, content: Operation) -> Result<SecureShareOperation, WalletError> {
+        self.hooks.run(&SigningRequest {
+            address: self.address(),
+            summary: SigningSummary::operation(&content),
+        })?;
+        // the inner signer signs the hash of the operation without running the hooks again
+        self.inner.sign_operation(content)
+    }
+}
+
+/// Entry of an audit log
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    /// milliseconds since the UNIX epoch
+    timestamp: u128,
+    #[serde(flatten)]
+    request: &'a SigningRequest,
+    /// reason of the refusal of the request, if refused
+    refusal: Option<String>,
+}
+
+/// Appends each signature request to a file, as one JSON object per line
+pub struct AuditLog {
+    /// log file
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Creates an audit log appending to `path`
+    pub fn new(path: PathBuf) -> Self {
+        AuditLog { path }
+    }
+}
+
+impl SigningHook for AuditLog {
+    fn record(
+        &self,
+        request: &SigningRequest,
+        refusal: Option<&WalletError>,
+    ) -> Result<(), WalletError> {
+        let entry = AuditEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            request,
+            refusal: refusal.map(|refusal| refusal.to_string()),
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
+
+/// Refuses the operations moving more than a threshold unless `confirm` accepts them
+pub struct TransferLimit {
+    /// largest amount signed without confirmation
+    threshold: Amount,
+    /// second confirmation of the operations above the threshold
+    confirm: Box<dyn Fn(&SigningRequest) -> bool + Send + Sync>,
+}
+
+impl TransferLimit {
+    /// Creates a transfer limit confirming the operations above `threshold` with `confirm`
+    pub fn new(
+        threshold: Amount,
+        confirm: impl Fn(&SigningRequest) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        TransferLimit {
+            threshold,
+            confirm: Box::new(confirm),
+        }
+    }
+}
+
+impl SigningHook for TransferLimit {
+    fn check(&self, request: &SigningRequest) -> Result<(), WalletError> {
+        let SigningSummary::Operation { amount, .. } = &request.summary else {
+            return Ok(());
+        };
+        if *amount > self.threshold && !(self.confirm)(request) {
+            return Err(WalletError::SigningRefused(format!(
+                "moving {} is above the limit of {} and was not confirmed",
+                amount, self.threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_signature::KeyPair;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_transfer_limit() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let hooks = SigningHooks(vec![Arc::new(TransferLimit::new(
+            Amount::from_str("10").unwrap(),
+            |_| false,
+        ))]);
+        let signer = WalletSigner {
+            inner: &keypair,
+            hooks: &hooks,
+        };
+        let transfer = |amount: &str| Operation {
+            fee: Amount::zero(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address: signer.address(),
+                amount: Amount::from_str(amount).unwrap(),
+            },
+        };
+        assert!(signer.sign_operation(transfer("10")).is_ok());
+        assert!(matches!(
+            signer.sign_operation(transfer("11")),
+            Err(WalletError::SigningRefused(_))
+        ));
+    }
+}
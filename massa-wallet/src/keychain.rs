@@ -0,0 +1,298 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Storage of the wallet password outside of the wallet file, to unlock it without typing it.
+//!
+//! The password is kept by the operating system:
+//! * [`PasswordBackend::Keychain`]: the credential store of the OS, through the `keyring` crate:
+//!   the macOS Keychain, the Linux secret service or the Windows Credential Manager.
+//!   Only available with the `keyring` feature;
+//! * [`PasswordBackend::Tpm`]: a file next to the wallet sealed by the TPM of the machine
+//!   (`systemd-creds --with-key=tpm2`, Linux only), the password being written to its standard input.
+//!   The presence of `systemd-creds` and of a TPM2 device is checked before sealing.
+//!
+//! Entries are identified by the absolute path of the wallet file.
+
+use crate::WalletError;
+#[cfg(feature = "keyring")]
+use keyring::Entry;
+use serde::Deserialize;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Service name of the keychain entries, and name of the TPM sealed credentials
+const KEYCHAIN_SERVICE: &str = "massa-wallet";
+
+/// Tool sealing the credentials with the TPM
+const SYSTEMD_CREDS: &str = "systemd-creds";
+
+/// Where the wallet password is stored
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordBackend {
+    /// the password is typed at each unlock
+    #[default]
+    None,
+    /// OS keychain: macOS Keychain, Linux secret service or Windows Credential Manager
+    Keychain,
+    /// file sealed by the TPM
+    Tpm,
+}
+
+/// Stores the password of a wallet file in a backend, replacing the previous one
+pub fn store_password(
+    backend: PasswordBackend,
+    wallet_path: &Path,
+    password: &str,
+) -> Result<(), WalletError> {
+    match backend {
+        PasswordBackend::None => Ok(()),
+        #[cfg(feature = "keyring")]
+        PasswordBackend::Keychain => set_entry_password(&keychain_entry(wallet_path)?, password),
+        #[cfg(not(feature = "keyring"))]
+        PasswordBackend::Keychain => Err(keychain_unavailable()),
+        PasswordBackend::Tpm => {
+            // `has-tpm2` fails without a usable TPM2 device
+            match run_systemd_creds(SYSTEMD_CREDS, "has-tpm2", &["has-tpm2"], None) {
+                Ok(_) => {}
+                Err(WalletError::TpmCommandFailed { .. }) => {
+                    return Err(WalletError::TpmUnavailable(
+                        "no TPM2 device is available to seal the password".to_string(),
+                    ))
+                }
+                Err(e) => return Err(e),
+            }
+            run_systemd_creds(
+                SYSTEMD_CREDS,
+                "encrypt",
+                &[
+                    "encrypt",
+                    "--with-key=tpm2",
+                    &format!("--name={}", KEYCHAIN_SERVICE),
+                    "-",
+                    &sealed_path(wallet_path, "tpm").to_string_lossy(),
+                ],
+                Some(password),
+            )
+            .map(drop)
+        }
+    }
+}
+
+/// Reads the password of a wallet file from a backend, `None` if it holds none
+pub fn load_password(
+    backend: PasswordBackend,
+    wallet_path: &Path,
+) -> Result<Option<String>, WalletError> {
+    match backend {
+        PasswordBackend::None => Ok(None),
+        #[cfg(feature = "keyring")]
+        PasswordBackend::Keychain => get_entry_password(&keychain_entry(wallet_path)?),
+        #[cfg(not(feature = "keyring"))]
+        PasswordBackend::Keychain => Err(keychain_unavailable()),
+        PasswordBackend::Tpm => {
+            let path = sealed_path(wallet_path, "tpm");
+            if !path.is_file() {
+                return Ok(None);
+            }
+            let password = run_systemd_creds(
+                SYSTEMD_CREDS,
+                "decrypt",
+                &[
+                    "decrypt",
+                    &format!("--name={}", KEYCHAIN_SERVICE),
+                    &path.to_string_lossy(),
+                    "-",
+                ],
+                None,
+            )?;
+            // systemd-creds ends its output with a new line
+            Ok(Some(password.trim_end_matches(['\r', '\n']).to_string()))
+        }
+    }
+}
+
+/// Error of the keychain backend in the builds without the `keyring` feature
+#[cfg(not(feature = "keyring"))]
+fn keychain_unavailable() -> WalletError {
+    WalletError::KeychainError("this build does not include the `keyring` feature".to_string())
+}
+
+/// Keychain entry of a wallet file
+#[cfg(feature = "keyring")]
+fn keychain_entry(wallet_path: &Path) -> Result<Entry, WalletError> {
+    Entry::new(KEYCHAIN_SERVICE, &account(wallet_path)?).map_err(keychain_error)
+}
+
+/// Stores a password in a keychain entry
+#[cfg(feature = "keyring")]
+fn set_entry_password(entry: &Entry, password: &str) -> Result<(), WalletError> {
+    entry.set_password(password).map_err(keychain_error)
+}
+
+/// Reads the password of a keychain entry, `None` if there is no such entry
+#[cfg(feature = "keyring")]
+fn get_entry_password(entry: &Entry) -> Result<Option<String>, WalletError> {
+    match entry.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(keychain_error(e)),
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keychain_error(error: keyring::Error) -> WalletError {
+    WalletError::KeychainError(format!("keychain error: {}", error))
+}
+
+/// Identifier of the entry of a wallet file
+#[cfg_attr(not(feature = "keyring"), allow(dead_code))]
+fn account(wallet_path: &Path) -> Result<String, WalletError> {
+    let path = if wallet_path.is_absolute() {
+        wallet_path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(wallet_path)
+    };
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Path of a sealed password file next to a wallet file
+fn sealed_path(wallet_path: &Path, extension: &str) -> PathBuf {
+    let mut path = wallet_path.as_os_str().to_owned();
+    path.push(format!(".{}", extension));
+    PathBuf::from(path)
+}
+
+/// Runs the `action` of `systemd-creds` found at `program`, writing `input` to its standard input,
+/// and returns its standard output.
+/// A missing `systemd-creds` and a failed action are reported by their own errors.
+fn run_systemd_creds(
+    program: &str,
+    action: &'static str,
+    args: &[&str],
+    input: Option<&str>,
+) -> Result<String, WalletError> {
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Err(WalletError::TpmUnavailable(format!(
+                "{} was not found, it is only available on Linux with systemd",
+                program
+            )))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if let Some(input) = input {
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(WalletError::TpmCommandFailed {
+            action,
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    String::from_utf8(output.stdout).map_err(|_| WalletError::TpmCommandFailed {
+        action,
+        status: output.status,
+        stderr: "invalid UTF-8 output".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "keyring")]
+    use keyring::mock::MockCredential;
+
+    /// Keychain entry kept in memory
+    #[cfg(feature = "keyring")]
+    fn mock_entry(wallet_path: &Path) -> Entry {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        keychain_entry(wallet_path).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "keyring")]
+    fn test_keychain_password_roundtrip() {
+        let entry = mock_entry(Path::new("wallet.yaml"));
+        assert_eq!(get_entry_password(&entry).unwrap(), None);
+
+        set_entry_password(&entry, "first password").unwrap();
+        assert_eq!(
+            get_entry_password(&entry).unwrap(),
+            Some("first password".to_string())
+        );
+        // storing again replaces the password
+        set_entry_password(&entry, "second password").unwrap();
+        assert_eq!(
+            get_entry_password(&entry).unwrap(),
+            Some("second password".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "keyring")]
+    fn test_keychain_errors_reported() {
+        let entry = mock_entry(Path::new("wallet.yaml"));
+        set_entry_password(&entry, "password").unwrap();
+        let mock: &MockCredential = entry.get_credential().downcast_ref().unwrap();
+
+        // a locked or unavailable keychain is an error, not a missing password
+        mock.set_error(keyring::Error::NoStorageAccess("locked".into()));
+        assert!(matches!(
+            get_entry_password(&entry),
+            Err(WalletError::KeychainError(_))
+        ));
+        mock.set_error(keyring::Error::NoStorageAccess("locked".into()));
+        assert!(matches!(
+            set_entry_password(&entry, "other password"),
+            Err(WalletError::KeychainError(_))
+        ));
+        assert_eq!(
+            get_entry_password(&entry).unwrap(),
+            Some("password".to_string())
+        );
+    }
+
+    #[test]
+    fn test_entries_identified_by_absolute_path() {
+        let relative = Path::new("config").join("wallet.yaml");
+        let absolute = std::env::current_dir().unwrap().join(&relative);
+        assert_eq!(account(&relative).unwrap(), account(&absolute).unwrap());
+        assert_eq!(
+            sealed_path(&absolute, "tpm"),
+            std::env::current_dir()
+                .unwrap()
+                .join("config")
+                .join("wallet.yaml.tpm")
+        );
+        assert_eq!(
+            load_password(PasswordBackend::None, &absolute).unwrap(),
+            None
+        );
+        assert_eq!(
+            load_password(PasswordBackend::Tpm, &absolute).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_missing_systemd_creds_reported() {
+        assert!(matches!(
+            run_systemd_creds("massa-missing-systemd-creds", "decrypt", &["decrypt"], None),
+            Err(WalletError::TpmUnavailable(_))
+        ));
+    }
+}
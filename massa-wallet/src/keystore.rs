@@ -0,0 +1,203 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Encrypted keystore files, to move a key between machines and wallets.
+//!
+//! A keystore is a JSON document holding a single encrypted secret key:
+//! ```json
+//! {
+//!   "version": 1,
+//!   "address": "AU12...",
+//!   "public_key": "P1...",
+//!   "crypto": {
+//!     "cipher": "aes-256-gcm",
+//!     "cipher_params": { "nonce": "<hex>" },
+//!     "ciphertext": "<hex>",
+//!     "kdf": "pbkdf2-hmac-sha256",
+//!     "kdf_params": { "rounds": 600000, "salt": "<base64>", "key_length": 32 }
+//!   }
+//! }
+//! ```
+//! The plaintext is the secret key in its usual text form (`S1...`). The AES-256 key is derived
+//! from the password with `kdf_params`, the salt being encoded in unpadded base64 as in the PHC
+//! string format. The ciphertext is followed by the 16 bytes AES-GCM authentication tag.
+//! `address` and `public_key` are not secret, they are checked against the decrypted key.
+
+use crate::WalletError;
+use massa_cipher::{decrypt_detached, encrypt_detached, DetachedParams, Kdf, KEY_LENGTH};
+use massa_models::address::Address;
+use massa_signature::{KeyPair, PublicKey};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Version of the keystore format
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Default number of PBKDF2 rounds of a keystore
+pub const KEYSTORE_KDF_ROUNDS: u32 = 600_000;
+
+/// Cipher of the keystore format
+const KEYSTORE_CIPHER: &str = "aes-256-gcm";
+
+/// Key derivation function of the keystore format
+const KEYSTORE_KDF: &str = "pbkdf2-hmac-sha256";
+
+/// An encrypted secret key
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Keystore {
+    /// version of the keystore format
+    pub version: u32,
+    /// address of the key
+    pub address: Address,
+    /// public key
+    pub public_key: PublicKey,
+    /// encrypted secret key
+    pub crypto: KeystoreCrypto,
+}
+
+/// Encryption of a keystore secret key
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeystoreCrypto {
+    /// cipher, `aes-256-gcm`
+    pub cipher: String,
+    /// cipher parameters
+    pub cipher_params: CipherParams,
+    /// encrypted secret key, in hexadecimal
+    pub ciphertext: String,
+    /// key derivation function, `pbkdf2-hmac-sha256`
+    pub kdf: String,
+    /// key derivation parameters
+    pub kdf_params: KdfParams,
+}
+
+/// Parameters of the keystore cipher
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CipherParams {
+    /// AES-GCM nonce, in hexadecimal
+    pub nonce: String,
+}
+
+/// Parameters of the keystore key derivation function
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KdfParams {
+    /// number of rounds
+    pub rounds: u32,
+    /// salt, in unpadded base64
+    pub salt: String,
+    /// length of the derived key, in bytes
+    pub key_length: usize,
+}
+
+impl Keystore {
+    /// Encrypts a keypair with a password, deriving the encryption key with `rounds` PBKDF2 rounds
+    pub fn encrypt(keypair: &KeyPair, password: &str, rounds: u32) -> Result<Self, WalletError> {
+        let public_key = keypair.get_public_key();
+        let params = DetachedParams::generate(Kdf::Pbkdf2HmacSha256 { rounds })?;
+        let ciphertext = encrypt_detached(password, &params, keypair.to_string().as_bytes(), &[])?;
+        Ok(Keystore {
+            version: KEYSTORE_VERSION,
+            address: Address::from_public_key(&public_key),
+            public_key,
+            crypto: KeystoreCrypto {
+                cipher: KEYSTORE_CIPHER.to_string(),
+                cipher_params: CipherParams {
+                    nonce: to_hex(&params.nonce),
+                },
+                ciphertext: to_hex(&ciphertext),
+                kdf: KEYSTORE_KDF.to_string(),
+                kdf_params: KdfParams {
+                    rounds,
+                    salt: params.salt,
+                    key_length: KEY_LENGTH,
+                },
+            },
+        })
+    }
+
+    /// Decrypts the keypair with a password, checking that it matches the keystore address
+    pub fn decrypt(&self, password: &str) -> Result<KeyPair, WalletError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported keystore version {}",
+                self.version
+            )));
+        }
+        let crypto = &self.crypto;
+        if crypto.cipher != KEYSTORE_CIPHER
+            || crypto.kdf != KEYSTORE_KDF
+            || crypto.kdf_params.key_length != KEY_LENGTH
+        {
+            return Err(WalletError::KeystoreError(format!(
+                "unsupported encryption {} with {} ({} bytes key)",
+                crypto.cipher, crypto.kdf, crypto.kdf_params.key_length
+            )));
+        }
+        let secret_key = decrypt_detached(
+            password,
+            &DetachedParams {
+                kdf: Kdf::Pbkdf2HmacSha256 {
+                    rounds: crypto.kdf_params.rounds,
+                },
+                salt: crypto.kdf_params.salt.clone(),
+                nonce: from_hex(&crypto.cipher_params.nonce)?,
+            },
+            &from_hex(&crypto.ciphertext)?,
+            &[],
+        )?;
+        let secret_key = String::from_utf8(secret_key)
+            .map_err(|_| WalletError::KeystoreError("invalid secret key".to_string()))?;
+        let keypair = KeyPair::from_str(&secret_key)?;
+        if keypair.get_public_key() != self.public_key
+            || Address::from_public_key(&self.public_key) != self.address
+        {
+            return Err(WalletError::KeystoreError(format!(
+                "the secret key does not match the address {}",
+                self.address
+            )));
+        }
+        Ok(keypair)
+    }
+}
+
+/// Encodes bytes in lowercase hexadecimal
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes hexadecimal bytes
+pub(crate) fn from_hex(value: &str) -> Result<Vec<u8>, WalletError> {
+    let invalid = || WalletError::KeystoreError(format!("invalid hexadecimal {}", value));
+    if value.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let keystore = Keystore::encrypt(&keypair, "password", 1_000).unwrap();
+        let json = serde_json::to_string(&keystore).unwrap();
+        let keystore: Keystore = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            keystore.address,
+            Address::from_public_key(&keypair.get_public_key())
+        );
+        assert_eq!(
+            keystore.decrypt("password").unwrap().to_string(),
+            keypair.to_string()
+        );
+        assert!(keystore.decrypt("wrong password").is_err());
+    }
+}
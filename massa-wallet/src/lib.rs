@@ -6,19 +6,40 @@
 #![feature(map_try_insert)]
 
 pub use error::WalletError;
+pub use file_lock::STALE_LOCK_AGE;
+pub use hd::{HdWallet, HD_DEFAULT_GAP_LIMIT, HD_SEED_SIZE};
+pub use hooks::{
+    AuditLog, SigningHook, SigningRequest, SigningSummary, TransferLimit, WalletSigner,
+};
+pub use keychain::{load_password, store_password, PasswordBackend};
+pub use keystore::{Keystore, KEYSTORE_KDF_ROUNDS, KEYSTORE_VERSION};
+pub use massa_cipher::Kdf;
+pub use metadata::AddressMetadata;
+pub use signer::Signer;
+pub use wallet_file::{WALLET_FILE_VERSION, WALLET_KDF};
 
-use massa_cipher::{decrypt, encrypt};
+use file_lock::{backup_path, write_atomic, WalletFileLock};
+use hooks::SigningHooks;
 use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::composite::PubkeySig;
-use massa_models::operation::{Operation, OperationSerializer, SecureShareOperation};
+use massa_models::operation::{Operation, SecureShareOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
-use massa_models::secure_share::SecureShareContent;
 use massa_signature::{KeyPair, PublicKey};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 mod error;
+mod file_lock;
+mod hd;
+mod hooks;
+mod keychain;
+mod keystore;
+mod metadata;
+mod signer;
+mod wallet_file;
 
 /// Contains the keypairs created in the wallet.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -29,26 +50,82 @@ pub struct Wallet {
     wallet_path: PathBuf,
     /// Password
     password: String,
+    /// HD seed and derived accounts, if the wallet is hierarchical-deterministic
+    hd: Option<HdWallet>,
+    /// Labels and metadata of the addresses
+    #[serde(default)]
+    metadata: PreHashMap<Address, AddressMetadata>,
+    /// Key derivation of the password
+    #[serde(skip, default = "default_kdf")]
+    kdf: Kdf,
+    /// Hooks called on each signature
+    #[serde(skip)]
+    hooks: SigningHooks,
+}
+
+/// Default key derivation of the password
+fn default_kdf() -> Kdf {
+    WALLET_KDF
+}
+
+/// Decrypted content of a wallet file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WalletKeys {
+    /// keys with the HD seed and derivation indexes of an HD wallet, and the address metadata
+    Structured {
+        keys: PreHashMap<Address, KeyPair>,
+        #[serde(default)]
+        hd: Option<HdWallet>,
+        #[serde(default)]
+        metadata: PreHashMap<Address, AddressMetadata>,
+    },
+    /// keys of a wallet without HD seed nor metadata
+    Flat(PreHashMap<Address, KeyPair>),
 }
 
 impl Wallet {
-    /// Generates a new wallet initialized with the provided file content
+    /// Generates a new wallet initialized with the provided file content.
+    /// A wallet file in the format of the previous versions, or whose password is derived with PBKDF2,
+    /// is rewritten in the current one with Argon2id. The previous file is kept next to it,
+    /// with the `.bak` extension appended.
     pub fn new(path: PathBuf, password: String) -> Result<Wallet, WalletError> {
         if path.is_file() {
-            let content = &std::fs::read(&path)?[..];
-            let (_version, decrypted_content) = decrypt(&password, content)?;
-            let keys =
-                serde_json::from_slice::<PreHashMap<Address, KeyPair>>(&decrypted_content[..])?;
-            Ok(Wallet {
+            let _lock = WalletFileLock::acquire(&path)?;
+            let content = std::fs::read(&path)?;
+            let decoded = wallet_file::decode(&password, &content)?;
+            let (keys, hd, metadata) =
+                match serde_json::from_slice::<WalletKeys>(&decoded.content[..])? {
+                    WalletKeys::Structured { keys, hd, metadata } => (keys, hd, metadata),
+                    WalletKeys::Flat(keys) => (keys, None, PreHashMap::default()),
+                };
+            let wallet = Wallet {
                 keys,
                 wallet_path: path,
                 password,
-            })
+                hd,
+                metadata,
+                kdf: match decoded.kdf {
+                    Some(kdf @ Kdf::Argon2id { .. }) => kdf,
+                    _ => WALLET_KDF,
+                },
+                hooks: SigningHooks::default(),
+            };
+            if !matches!(decoded.kdf, Some(Kdf::Argon2id { .. })) {
+                // the lock is held from the read, so that the file is not changed in between
+                write_atomic(&backup_path(&wallet.wallet_path), &content)?;
+                wallet.write_file()?;
+            }
+            Ok(wallet)
         } else {
             let wallet = Wallet {
                 keys: PreHashMap::default(),
                 wallet_path: path,
                 password,
+                hd: None,
+                metadata: PreHashMap::default(),
+                kdf: WALLET_KDF,
+                hooks: SigningHooks::default(),
             };
             wallet.save()?;
             Ok(wallet)
@@ -59,10 +136,10 @@ impl Wallet {
     /// returns none if the address isn't in the wallet or if an error occurred during the signature
     /// else returns the public key that signed the message and the signature
     pub fn sign_message(&self, address: &Address, msg: Vec<u8>) -> Option<PubkeySig> {
-        if let Some(key) = self.keys.get(address) {
-            if let Ok(signature) = key.sign(&Hash::compute_from(&msg)) {
+        if let Some(signer) = self.get_signer(address) {
+            if let Ok(signature) = signer.sign_hash(&Hash::compute_from(&msg)) {
                 Some(PubkeySig {
-                    public_key: key.get_public_key(),
+                    public_key: signer.public_key(),
                     signature,
                 })
             } else {
@@ -81,6 +158,9 @@ impl Wallet {
         for key in keys {
             let addr = Address::from_public_key(&key.get_public_key());
             if self.keys.try_insert(addr, key).is_ok() {
+                self.metadata
+                    .entry(addr)
+                    .or_insert_with(AddressMetadata::created_now);
                 changed = true;
             }
             addrs.push(addr);
@@ -99,6 +179,10 @@ impl Wallet {
             if self.keys.remove(address).is_some() {
                 changed = true;
             }
+            self.metadata.remove(address);
+        }
+        if let Some(hd) = self.hd.as_mut() {
+            hd.accounts.retain(|_, addr| !addresses.contains(addr));
         }
         if changed {
             self.save()?;
@@ -106,11 +190,47 @@ impl Wallet {
         Ok(())
     }
 
+    /// Exports the key of an address as a keystore encrypted with `password`
+    pub fn export_keystore(
+        &self,
+        address: &Address,
+        password: &str,
+    ) -> Result<Keystore, WalletError> {
+        let keypair = self
+            .find_associated_keypair(address)
+            .ok_or(WalletError::MissingKeyError(*address))?;
+        Keystore::encrypt(keypair, password, KEYSTORE_KDF_ROUNDS)
+    }
+
+    /// Decrypts the key of a keystore and adds it to the wallet, returns its address.
+    /// The wallet file is updated.
+    pub fn import_keystore(
+        &mut self,
+        keystore: &Keystore,
+        password: &str,
+    ) -> Result<Address, WalletError> {
+        let keypair = keystore.decrypt(password)?;
+        Ok(self.add_keypairs(vec![keypair])?[0])
+    }
+
     /// Finds the keypair associated with given address
     pub fn find_associated_keypair(&self, address: &Address) -> Option<&KeyPair> {
         self.keys.get(address)
     }
 
+    /// Finds the signer of the given address, which runs the signing hooks of the wallet
+    pub fn get_signer(&self, address: &Address) -> Option<WalletSigner<'_>> {
+        self.keys.get(address).map(|keypair| WalletSigner {
+            inner: keypair,
+            hooks: &self.hooks,
+        })
+    }
+
+    /// Adds a hook called on each signature made through the wallet
+    pub fn add_signing_hook(&mut self, hook: Arc<dyn SigningHook>) {
+        self.hooks.0.push(hook);
+    }
+
     /// Finds the public key associated with given address
     pub fn find_associated_public_key(&self, address: &Address) -> Option<PublicKey> {
         self.keys
@@ -123,13 +243,154 @@ impl Wallet {
         self.keys.keys().copied().collect()
     }
 
+    /// Sets the key derivation of the password, e.g. to make brute-forcing
+    /// the password harder or unlocking the wallet faster.
+    /// The parameters are capped like the ones read from a wallet file. The wallet file is updated.
+    pub fn set_kdf(&mut self, kdf: Kdf) -> Result<(), WalletError> {
+        wallet_file::check_kdf(&kdf)?;
+        self.kdf = kdf;
+        self.save()
+    }
+
+    /// HD seed and derived accounts, if the wallet is hierarchical-deterministic
+    pub fn hd_wallet(&self) -> Option<&HdWallet> {
+        self.hd.as_ref()
+    }
+
+    /// Turns the wallet into an HD wallet, with the given seed or a random one.
+    /// The existing keys are kept. The wallet file is updated.
+    pub fn enable_hd(
+        &mut self,
+        seed: Option<[u8; HD_SEED_SIZE]>,
+    ) -> Result<&HdWallet, WalletError> {
+        if self.hd.is_some() {
+            return Err(WalletError::HdError(
+                "the wallet already has an HD seed".to_string(),
+            ));
+        }
+        let hd = match seed {
+            Some(seed) => HdWallet::new(seed),
+            None => HdWallet::generate()?,
+        };
+        self.hd = Some(hd);
+        self.save()?;
+        Ok(self.hd.as_ref().expect("HD seed set above"))
+    }
+
+    /// Derives the `count` accounts following the last derived one and adds them to the wallet,
+    /// returns their derivation indexes and addresses.
+    /// The wallet file is updated.
+    pub fn derive_hd_accounts(&mut self, count: u32) -> Result<Vec<(u32, Address)>, WalletError> {
+        let next_index = self.hd_wallet_or_err()?.next_index();
+        let indexes: Vec<u32> = (next_index..next_index.saturating_add(count)).collect();
+        let addresses = self.add_hd_accounts(&indexes)?;
+        Ok(indexes.into_iter().zip(addresses).collect())
+    }
+
+    /// Addresses of the `count` accounts from derivation index `from`, without adding them
+    pub fn hd_lookahead(&self, from: u32, count: u32) -> Result<Vec<(u32, Address)>, WalletError> {
+        let hd = self.hd_wallet_or_err()?;
+        (from..from.saturating_add(count))
+            .map(|index| {
+                let keypair = hd.derive(index)?;
+                Ok((index, Address::from_public_key(&keypair.get_public_key())))
+            })
+            .collect()
+    }
+
+    /// Adds the accounts at the given derivation indexes to the wallet, returns their addresses.
+    /// The wallet file is updated.
+    pub fn add_hd_accounts(&mut self, indexes: &[u32]) -> Result<Vec<Address>, WalletError> {
+        let hd = self.hd_wallet_or_err()?;
+        let keypairs = indexes
+            .iter()
+            .map(|index| hd.derive(*index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let hd = self.hd.as_mut().expect("HD seed checked above");
+        for (index, keypair) in indexes.iter().zip(&keypairs) {
+            hd.accounts
+                .insert(*index, Address::from_public_key(&keypair.get_public_key()));
+        }
+        // add_keypairs does not save the wallet if all the keys were already in it
+        let addresses = self.add_keypairs(keypairs)?;
+        self.save()?;
+        Ok(addresses)
+    }
+
+    /// HD seed and derived accounts, failing if the wallet is not hierarchical-deterministic
+    fn hd_wallet_or_err(&self) -> Result<&HdWallet, WalletError> {
+        self.hd
+            .as_ref()
+            .ok_or_else(|| WalletError::HdError("the wallet has no HD seed".to_string()))
+    }
+
+    /// Label and metadata of an address of the wallet
+    pub fn get_metadata(&self, address: &Address) -> Option<&AddressMetadata> {
+        self.metadata.get(address)
+    }
+
+    /// Sets or removes the label of an address of the wallet.
+    /// The wallet file is updated.
+    pub fn set_label(
+        &mut self,
+        address: &Address,
+        label: Option<String>,
+    ) -> Result<(), WalletError> {
+        self.metadata_mut(address)?.label = label;
+        self.save()
+    }
+
+    /// Sets or removes a metadata field of an address of the wallet.
+    /// The wallet file is updated.
+    pub fn set_metadata_field(
+        &mut self,
+        address: &Address,
+        key: String,
+        value: Option<String>,
+    ) -> Result<(), WalletError> {
+        let metadata = self.metadata_mut(address)?;
+        match value {
+            Some(value) => metadata.fields.insert(key, value),
+            None => metadata.fields.remove(&key),
+        };
+        self.save()
+    }
+
+    /// Metadata of an address of the wallet, created if missing
+    fn metadata_mut(&mut self, address: &Address) -> Result<&mut AddressMetadata, WalletError> {
+        if !self.keys.contains_key(address) {
+            return Err(WalletError::MissingKeyError(*address));
+        }
+        Ok(self.metadata.entry(*address).or_default())
+    }
+
     /// Save the wallet in json format in a file
-    /// Only the keypair is dumped
+    /// Only the keypair is dumped, with the HD seed and derivation indexes of an HD wallet and
+    /// the address metadata. Wallets without HD seed nor metadata keep the flat format of the
+    /// previous versions.
     fn save(&self) -> Result<(), WalletError> {
-        let ser_keys = serde_json::to_string(&self.keys)?;
-        let encrypted_content = encrypt(&self.password, ser_keys.as_bytes())?;
-        std::fs::write(&self.wallet_path, encrypted_content)?;
-        Ok(())
+        let _lock = WalletFileLock::acquire(&self.wallet_path)?;
+        self.write_file()
+    }
+
+    /// Writes the wallet file, the caller holding its lock
+    fn write_file(&self) -> Result<(), WalletError> {
+        let metadata: HashMap<&Address, &AddressMetadata> = self
+            .metadata
+            .iter()
+            .filter(|(_, metadata)| **metadata != AddressMetadata::default())
+            .collect();
+        let ser_keys = if self.hd.is_none() && metadata.is_empty() {
+            serde_json::to_string(&self.keys)?
+        } else {
+            serde_json::to_string(&serde_json::json!({
+                "keys": self.keys,
+                "hd": self.hd,
+                "metadata": metadata,
+            }))?
+        };
+        let encrypted_content = wallet_file::encode(&self.password, ser_keys.as_bytes(), self.kdf)?;
+        write_atomic(&self.wallet_path, &encrypted_content)
     }
 
     /// Export keys and addresses
@@ -143,10 +404,9 @@ impl Wallet {
         content: Operation,
         address: Address,
     ) -> Result<SecureShareOperation, WalletError> {
-        let sender_keypair = self
-            .find_associated_keypair(&address)
-            .ok_or_else(|| WalletError::MissingKeyError(address))?;
-        Ok(Operation::new_verifiable(content, OperationSerializer::new(), sender_keypair).unwrap())
+        self.get_signer(&address)
+            .ok_or(WalletError::MissingKeyError(address))?
+            .sign_operation(content)
     }
 }
 
@@ -165,3 +425,56 @@ impl std::fmt::Display for Wallet {
 /// Test utils
 #[cfg(feature = "testing")]
 pub mod test_exports;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_rewritten_with_argon2id() {
+        let wallet_path = std::env::temp_dir().join(format!(
+            "massa_wallet_migration_test_{}",
+            std::process::id()
+        ));
+        let keypair = KeyPair::generate(0).unwrap();
+        let keys: PreHashMap<Address, KeyPair> =
+            [(Address::from_public_key(&keypair.get_public_key()), keypair)]
+                .into_iter()
+                .collect();
+        let content = serde_json::to_vec(&keys).unwrap();
+
+        // wallets in the format of the previous versions, or with PBKDF2, are rewritten and backed up
+        let legacy = massa_cipher::encrypt("password", &content).unwrap();
+        let pbkdf2 = wallet_file::encode(
+            "password",
+            &content,
+            Kdf::Pbkdf2HmacSha256 { rounds: 1_000 },
+        )
+        .unwrap();
+        for previous in [legacy, pbkdf2] {
+            std::fs::write(&wallet_path, &previous).unwrap();
+            let wallet = Wallet::new(wallet_path.clone(), "password".to_string()).unwrap();
+            assert_eq!(
+                wallet.get_wallet_address_list(),
+                keys.keys().copied().collect()
+            );
+            assert_eq!(std::fs::read(backup_path(&wallet_path)).unwrap(), previous);
+            let rewritten =
+                wallet_file::decode("password", &std::fs::read(&wallet_path).unwrap()).unwrap();
+            assert_eq!(rewritten.kdf, Some(WALLET_KDF));
+            assert_eq!(rewritten.content, content);
+        }
+
+        // a wallet in the current format is left untouched
+        std::fs::remove_file(backup_path(&wallet_path)).unwrap();
+        let current = std::fs::read(&wallet_path).unwrap();
+        Wallet::new(wallet_path.clone(), "password".to_string()).unwrap();
+        assert_eq!(std::fs::read(&wallet_path).unwrap(), current);
+        assert!(!backup_path(&wallet_path).exists());
+
+        // a wrong password does not rewrite the wallet
+        assert!(Wallet::new(wallet_path.clone(), "wrong".to_string()).is_err());
+        assert_eq!(std::fs::read(&wallet_path).unwrap(), current);
+        std::fs::remove_file(&wallet_path).unwrap();
+    }
+}
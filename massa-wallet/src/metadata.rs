@@ -0,0 +1,37 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Labels and metadata of the addresses of the wallet, stored in the wallet file with the keys.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Metadata of an address of the wallet
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AddressMetadata {
+    /// human-readable label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// time at which the key was added to the wallet, in milliseconds since the UNIX epoch.
+    /// Unknown for the keys added by the previous versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// arbitrary key/value metadata
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl AddressMetadata {
+    /// Metadata of a key added now
+    pub(crate) fn created_now() -> Self {
+        AddressMetadata {
+            created_at: Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+            ),
+            ..Default::default()
+        }
+    }
+}
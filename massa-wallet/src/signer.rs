@@ -0,0 +1,60 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Signing backends.
+//!
+//! Wallet commands only need a public key and a way to sign a hash, so they go through the
+//! `Signer` trait instead of using a `KeyPair` directly. The keys stored in the wallet file are
+//! software signers; hardware wallets (e.g. Ledger) implement the same trait and keep their secret
+//! key on the device, the user confirming each signature on it.
+
+use crate::WalletError;
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_models::operation::{Operation, OperationSerializer, SecureShareOperation};
+use massa_models::secure_share::SecureShareContent;
+use massa_signature::{KeyPair, PublicKey, Signature};
+
+/// Something able to sign on behalf of an address
+pub trait Signer {
+    /// Public key whose signatures are produced
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs a hash
+    fn sign_hash(&self, hash: &Hash) -> Result<Signature, WalletError>;
+
+    /// True if the user must confirm each signature on an external device
+    fn requires_confirmation(&self) -> bool {
+        false
+    }
+
+    /// True if the secret key can be exported, which is required to register it for staking
+    fn can_export_secret_key(&self) -> bool {
+        false
+    }
+
+    /// Address of the signer
+    fn address(&self) -> Address {
+        Address::from_public_key(&self.public_key())
+    }
+
+    /// Signs an operation
+    fn sign_operation(&self, content: Operation) -> Result<SecureShareOperation, WalletError> {
+        content.new_verifiable_with_signer(OperationSerializer::new(), self.public_key(), |hash| {
+            self.sign_hash(hash)
+        })
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        self.get_public_key()
+    }
+
+    fn sign_hash(&self, hash: &Hash) -> Result<Signature, WalletError> {
+        Ok(self.sign(hash)?)
+    }
+
+    fn can_export_secret_key(&self) -> bool {
+        true
+    }
+}
@@ -1,11 +1,17 @@
 use std::io::Write;
 
-use massa_cipher::encrypt;
 use massa_models::{address::Address, prehash::PreHashMap};
 use massa_signature::KeyPair;
 use tempfile::NamedTempFile;
 
-use crate::Wallet;
+use crate::{wallet_file, Kdf, Wallet};
+
+/// Key derivation of the test wallets, cheap to keep the tests fast
+const TEST_WALLET_KDF: Kdf = Kdf::Argon2id {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
 
 /// Creates a temporary file and a temporary wallet.
 pub fn create_test_wallet(default_accounts: Option<PreHashMap<Address, KeyPair>>) -> Wallet {
@@ -14,11 +20,12 @@ pub fn create_test_wallet(default_accounts: Option<PreHashMap<Address, KeyPair>>
     wallet_file
         .as_file()
         .write_all(
-            &encrypt(
+            &wallet_file::encode(
                 "test",
                 serde_json::to_string::<PreHashMap<Address, KeyPair>>(&accounts)
                     .unwrap()
                     .as_bytes(),
+                TEST_WALLET_KDF,
             )
             .unwrap(),
         )
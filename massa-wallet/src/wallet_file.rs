@@ -0,0 +1,272 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Encrypted wallet file format.
+//!
+//! Version 2 files start with an authenticated header:
+//!
+//! | field      | size           | content                                           |
+//! |------------|----------------|---------------------------------------------------|
+//! | magic      | 8              | `MASSAWLT`                                        |
+//! | version    | 4              | format version, `2`, little endian                |
+//! | kdf        | 1              | key derivation function, `2` for Argon2id, `1` for PBKDF2-HMAC-SHA256 |
+//! | kdf params | 12 or 4        | Argon2id memory in KiB, iterations and parallelism, or PBKDF2 rounds, little endian |
+//! | salt size  | 1              | size of the salt                                  |
+//! | salt       | salt size      | KDF salt, in unpadded base64                      |
+//! | nonce      | 12             | AES-GCM nonce                                     |
+//! | checksum   | 8              | first 8 bytes of the blake3 hash of the fields above |
+//!
+//! followed by the wallet content encrypted with AES-256-GCM, the whole header being
+//! authenticated as associated data. The checksum tells a corrupted header from a wrong password.
+//! The KDF parameters are capped before deriving the key, so that a crafted header cannot make
+//! the unlock of the wallet run for hours or exhaust the memory.
+//!
+//! The files are written with Argon2id. Files with PBKDF2, or without the magic, in the format
+//! of the previous versions (see `massa_cipher::encrypt`), are still read and rewritten by the wallet.
+
+use crate::WalletError;
+use massa_cipher::{decrypt, decrypt_detached, encrypt_detached, DetachedParams, Kdf, NONCE_SIZE};
+use massa_hash::Hash;
+
+/// Magic bytes starting a wallet file
+const WALLET_FILE_MAGIC: &[u8; 8] = b"MASSAWLT";
+
+/// Current version of the wallet file format
+pub const WALLET_FILE_VERSION: u32 = 2;
+
+/// Identifier of PBKDF2-HMAC-SHA256 in the header
+const KDF_PBKDF2_HMAC_SHA256: u8 = 1;
+
+/// Identifier of Argon2id in the header
+const KDF_ARGON2ID: u8 = 2;
+
+/// Default key derivation of a wallet file, the second recommended Argon2id option of RFC 9106
+pub const WALLET_KDF: Kdf = Kdf::Argon2id {
+    memory_kib: 64 * 1024,
+    iterations: 3,
+    parallelism: 4,
+};
+
+/// Maximum PBKDF2 rounds accepted in a header
+const MAX_PBKDF2_ROUNDS: u32 = 10_000_000;
+
+/// Maximum Argon2id memory accepted in a header, in KiB
+const MAX_ARGON2_MEMORY_KIB: u32 = 1024 * 1024;
+
+/// Maximum Argon2id iterations accepted in a header
+const MAX_ARGON2_ITERATIONS: u32 = 64;
+
+/// Maximum Argon2id parallelism accepted in a header
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// Size of the header checksum
+const CHECKSUM_SIZE: usize = 8;
+
+/// Decrypted wallet file
+pub(crate) struct WalletFileContent {
+    /// decrypted content
+    pub content: Vec<u8>,
+    /// key derivation of the file, `None` for the format of the previous versions
+    pub kdf: Option<Kdf>,
+}
+
+/// Checks that the KDF parameters are within the caps of the wallet files
+pub(crate) fn check_kdf(kdf: &Kdf) -> Result<(), WalletError> {
+    let valid = match *kdf {
+        Kdf::Pbkdf2HmacSha256 { rounds } => (1..=MAX_PBKDF2_ROUNDS).contains(&rounds),
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            (1..=MAX_ARGON2_PARALLELISM).contains(&parallelism)
+                && (8 * parallelism..=MAX_ARGON2_MEMORY_KIB).contains(&memory_kib)
+                && (1..=MAX_ARGON2_ITERATIONS).contains(&iterations)
+        }
+    };
+    if !valid {
+        return Err(invalid(&format!(
+            "key derivation parameters out of bounds: {:?}",
+            kdf
+        )));
+    }
+    Ok(())
+}
+
+/// Encrypts a wallet content in the current format
+pub(crate) fn encode(password: &str, content: &[u8], kdf: Kdf) -> Result<Vec<u8>, WalletError> {
+    check_kdf(&kdf)?;
+    let params = DetachedParams::generate(kdf)?;
+    let salt = params.salt.as_bytes();
+    let salt_size =
+        u8::try_from(salt.len()).map_err(|_| invalid("salt too long for the header"))?;
+    let mut data = Vec::new();
+    data.extend(WALLET_FILE_MAGIC);
+    data.extend(WALLET_FILE_VERSION.to_le_bytes());
+    match kdf {
+        Kdf::Pbkdf2HmacSha256 { rounds } => {
+            data.push(KDF_PBKDF2_HMAC_SHA256);
+            data.extend(rounds.to_le_bytes());
+        }
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+            parallelism,
+        } => {
+            data.push(KDF_ARGON2ID);
+            data.extend(memory_kib.to_le_bytes());
+            data.extend(iterations.to_le_bytes());
+            data.extend(parallelism.to_le_bytes());
+        }
+    }
+    data.push(salt_size);
+    data.extend(salt);
+    data.extend(&params.nonce);
+    let checksum = Hash::compute_from(&data);
+    data.extend(&checksum.to_bytes()[..CHECKSUM_SIZE]);
+    let ciphertext = encrypt_detached(password, &params, content, &data)?;
+    data.extend(ciphertext);
+    Ok(data)
+}
+
+/// Decrypts a wallet file, in the current format or the one of the previous versions
+pub(crate) fn decode(password: &str, data: &[u8]) -> Result<WalletFileContent, WalletError> {
+    let Some(rest) = data.strip_prefix(WALLET_FILE_MAGIC) else {
+        let (_version, content) = decrypt(password, data)?;
+        return Ok(WalletFileContent {
+            content,
+            kdf: None,
+        });
+    };
+    let mut reader = Reader(rest);
+    let version = u32::from_le_bytes(reader.array()?);
+    if version != WALLET_FILE_VERSION {
+        return Err(invalid(&format!("unsupported version {}", version)));
+    }
+    let kdf = match reader.take(1)?[0] {
+        KDF_PBKDF2_HMAC_SHA256 => Kdf::Pbkdf2HmacSha256 {
+            rounds: u32::from_le_bytes(reader.array()?),
+        },
+        KDF_ARGON2ID => Kdf::Argon2id {
+            memory_kib: u32::from_le_bytes(reader.array()?),
+            iterations: u32::from_le_bytes(reader.array()?),
+            parallelism: u32::from_le_bytes(reader.array()?),
+        },
+        kdf => {
+            return Err(invalid(&format!(
+                "unsupported key derivation function {}",
+                kdf
+            )))
+        }
+    };
+    let salt_size = reader.take(1)?[0] as usize;
+    let salt = std::str::from_utf8(reader.take(salt_size)?)
+        .map_err(|_| invalid("invalid salt"))?
+        .to_string();
+    let nonce = reader.take(NONCE_SIZE)?.to_vec();
+    let header_size = data.len() - reader.0.len();
+    let checksum = reader.take(CHECKSUM_SIZE)?;
+    if checksum != &Hash::compute_from(&data[..header_size]).to_bytes()[..CHECKSUM_SIZE] {
+        return Err(invalid("header checksum mismatch"));
+    }
+    check_kdf(&kdf)?;
+    let params = DetachedParams { kdf, salt, nonce };
+    let content = decrypt_detached(
+        password,
+        &params,
+        reader.0,
+        &data[..header_size + CHECKSUM_SIZE],
+    )?;
+    Ok(WalletFileContent {
+        content,
+        kdf: Some(kdf),
+    })
+}
+
+/// Error of a corrupted or unsupported wallet file
+fn invalid(reason: &str) -> WalletError {
+    WalletError::WalletFileError(reason.to_string())
+}
+
+/// Reads the fields of a header
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Reads the next `size` bytes
+    fn take(&mut self, size: usize) -> Result<&'a [u8], WalletError> {
+        if self.0.len() < size {
+            return Err(invalid("truncated header"));
+        }
+        let (field, rest) = self.0.split_at(size);
+        self.0 = rest;
+        Ok(field)
+    }
+
+    /// Reads the next `N` bytes
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], WalletError> {
+        Ok(self.take(N)?.try_into().expect("N bytes taken"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap Argon2id parameters, to keep the tests fast
+    const TEST_KDF: Kdf = Kdf::Argon2id {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_wallet_file_roundtrip() {
+        let data = encode("password", b"content", TEST_KDF).unwrap();
+        let decoded = decode("password", &data).unwrap();
+        assert_eq!(decoded.content, b"content");
+        assert_eq!(decoded.kdf, Some(TEST_KDF));
+        assert!(decode("wrong password", &data).is_err());
+
+        // the header is authenticated
+        let mut tampered = data.clone();
+        tampered[13] ^= 1;
+        assert!(decode("password", &tampered).is_err());
+
+        // files with PBKDF2 are still read
+        let pbkdf2 = Kdf::Pbkdf2HmacSha256 { rounds: 1_000 };
+        let data = encode("password", b"content", pbkdf2).unwrap();
+        let decoded = decode("password", &data).unwrap();
+        assert_eq!(decoded.content, b"content");
+        assert_eq!(decoded.kdf, Some(pbkdf2));
+
+        // files of the previous versions are still read
+        let legacy = massa_cipher::encrypt("password", b"content").unwrap();
+        let decoded = decode("password", &legacy).unwrap();
+        assert_eq!(decoded.content, b"content");
+        assert_eq!(decoded.kdf, None);
+    }
+
+    #[test]
+    fn test_wallet_file_kdf_capped() {
+        // a header asking for too many rounds is rejected before deriving the key
+        let mut data = encode("password", b"content", Kdf::Pbkdf2HmacSha256 { rounds: 1 }).unwrap();
+        let rounds_offset = WALLET_FILE_MAGIC.len() + 4 + 1;
+        data[rounds_offset..rounds_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let header_size = data.len() - b"content".len() - 16 - CHECKSUM_SIZE;
+        let checksum = Hash::compute_from(&data[..header_size]);
+        data[header_size..header_size + CHECKSUM_SIZE]
+            .copy_from_slice(&checksum.to_bytes()[..CHECKSUM_SIZE]);
+        assert!(matches!(
+            decode("password", &data),
+            Err(WalletError::WalletFileError(_))
+        ));
+
+        assert!(check_kdf(&WALLET_KDF).is_ok());
+        assert!(check_kdf(&Kdf::Argon2id {
+            memory_kib: 4 * 1024 * 1024,
+            iterations: 3,
+            parallelism: 4,
+        })
+        .is_err());
+        assert!(encode("password", b"content", Kdf::Pbkdf2HmacSha256 { rounds: 0 }).is_err());
+    }
+}